@@ -165,6 +165,18 @@ test_rewrite "npm test" \
   "npm test" \
   "rtk npm test"
 
+test_rewrite "npm ci" \
+  "npm ci" \
+  "rtk npm ci"
+
+test_rewrite "npm run ci (script, not npm ci)" \
+  "npm run ci" \
+  "rtk npm run ci"
+
+test_rewrite "pnpm install --frozen-lockfile" \
+  "pnpm install --frozen-lockfile" \
+  "rtk pnpm install --frozen-lockfile"
+
 test_rewrite "vue-tsc -b" \
   "vue-tsc -b" \
   "rtk tsc -b"
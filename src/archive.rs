@@ -0,0 +1,220 @@
+//! Archives and compressed files read in process, for `rtk grep
+//! --search-zip`: `.gz`, `.tar`, `.tar.gz`/`.tgz` and `.zip`. A member is
+//! named `archive!member`, so results point inside the bundle.
+
+use anyhow::{Context, Result};
+use flate2::read::GzDecoder;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+/// Between the archive path and the member path in a result
+pub const MEMBER_SEP: char = '!';
+
+/// Members larger than this once decompressed are skipped
+const MAX_MEMBER_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Bytes looked at for a NUL to tell binary members, as grep -I does
+const BINARY_SNIFF_BYTES: usize = 8192;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Gzip,
+    Tar,
+    TarGz,
+    Zip,
+}
+
+fn format(path: &Path) -> Option<Format> {
+    let name = path.file_name()?.to_str()?.to_lowercase();
+    if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+        Some(Format::TarGz)
+    } else if name.ends_with(".gz") {
+        Some(Format::Gzip)
+    } else if name.ends_with(".tar") {
+        Some(Format::Tar)
+    } else if name.ends_with(".zip") {
+        Some(Format::Zip)
+    } else {
+        None
+    }
+}
+
+/// Whether `path` is an archive or compressed file rtk can open
+pub fn is_archive(path: &Path) -> bool {
+    format(path).is_some()
+}
+
+/// Read one member, `None` when it is binary or too large (then noted in
+/// `skipped`)
+fn read_member(
+    reader: impl Read,
+    label: &str,
+    skipped: &mut Vec<String>,
+) -> Result<Option<String>> {
+    let mut bytes = Vec::new();
+    reader
+        .take(MAX_MEMBER_BYTES + 1)
+        .read_to_end(&mut bytes)
+        .with_context(|| format!("Failed to read {}", label))?;
+    if bytes.len() as u64 > MAX_MEMBER_BYTES {
+        skipped.push(format!(
+            "warning: {}: over {} decompressed, skipped",
+            label,
+            crate::utils::format_bytes(MAX_MEMBER_BYTES)
+        ));
+        return Ok(None);
+    }
+    if bytes[..bytes.len().min(BINARY_SNIFF_BYTES)].contains(&0) {
+        return Ok(None);
+    }
+    Ok(Some(String::from_utf8_lossy(&bytes).into_owned()))
+}
+
+fn tar_members(
+    reader: impl Read,
+    archive: &str,
+    visit: &mut impl FnMut(&str, &str),
+    skipped: &mut Vec<String>,
+) -> Result<()> {
+    let mut tar = tar::Archive::new(reader);
+    for entry in tar
+        .entries()
+        .with_context(|| format!("Failed to read {}", archive))?
+    {
+        let entry = entry.with_context(|| format!("Failed to read {}", archive))?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let name = format!(
+            "{}{}{}",
+            archive,
+            MEMBER_SEP,
+            entry.path()?.to_string_lossy()
+        );
+        if let Some(text) = read_member(entry, &name, skipped)? {
+            visit(&name, &text);
+        }
+    }
+    Ok(())
+}
+
+/// Call `visit` with the name and text of every text member of the archive
+/// at `path`. Binary members are left out; the returned warnings name the
+/// ones skipped for their size.
+pub fn for_each_member(path: &Path, mut visit: impl FnMut(&str, &str)) -> Result<Vec<String>> {
+    let format = format(path).with_context(|| format!("{} is not an archive", path.display()))?;
+    let archive = path.to_string_lossy();
+    let open = || {
+        File::open(path)
+            .map(BufReader::new)
+            .with_context(|| format!("Failed to open {}", archive))
+    };
+    let mut skipped = Vec::new();
+
+    match format {
+        Format::Gzip => {
+            let inner = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let name = format!("{}{}{}", archive, MEMBER_SEP, inner);
+            if let Some(text) = read_member(GzDecoder::new(open()?), &name, &mut skipped)? {
+                visit(&name, &text);
+            }
+        }
+        Format::Tar => tar_members(open()?, &archive, &mut visit, &mut skipped)?,
+        Format::TarGz => tar_members(GzDecoder::new(open()?), &archive, &mut visit, &mut skipped)?,
+        Format::Zip => {
+            let mut zip = zip::ZipArchive::new(open()?)
+                .with_context(|| format!("Failed to read {}", archive))?;
+            for i in 0..zip.len() {
+                let member = zip
+                    .by_index(i)
+                    .with_context(|| format!("Failed to read {}", archive))?;
+                if !member.is_file() {
+                    continue;
+                }
+                let name = format!("{}{}{}", archive, MEMBER_SEP, member.name());
+                if let Some(text) = read_member(member, &name, &mut skipped)? {
+                    visit(&name, &text);
+                }
+            }
+        }
+    }
+    Ok(skipped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    fn members(path: &Path) -> Vec<(String, String)> {
+        let mut found = Vec::new();
+        let skipped = for_each_member(path, |name, text| {
+            found.push((name.to_string(), text.to_string()))
+        })
+        .unwrap();
+        assert!(skipped.is_empty());
+        found
+    }
+
+    #[test]
+    fn test_formats() {
+        assert!(is_archive(Path::new("logs/app.log.gz")));
+        assert!(is_archive(Path::new("bundle.TGZ")));
+        assert!(is_archive(Path::new("bundle.zip")));
+        assert!(!is_archive(Path::new("src/main.rs")));
+        assert_eq!(format(Path::new("a.tar.gz")), Some(Format::TarGz));
+    }
+
+    #[test]
+    fn test_gzip_and_tar_gz_members() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("app.log.gz");
+        let mut gz = GzEncoder::new(File::create(&log).unwrap(), Compression::default());
+        gz.write_all(b"ERROR boom\n").unwrap();
+        gz.finish().unwrap();
+        let name = format!("{}!app.log", log.display());
+        assert_eq!(members(&log), vec![(name, "ERROR boom\n".to_string())]);
+
+        let bundle = dir.path().join("bundle.tar.gz");
+        let gz = GzEncoder::new(File::create(&bundle).unwrap(), Compression::default());
+        let mut tar = tar::Builder::new(gz);
+        for (name, data) in [("logs/a.log", &b"ok\n"[..]), ("bin/tool", b"\0\x01")] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            tar.append_data(&mut header, name, data).unwrap();
+        }
+        tar.into_inner().unwrap().finish().unwrap();
+        // The binary member is left out
+        assert_eq!(
+            members(&bundle),
+            vec![(format!("{}!logs/a.log", bundle.display()), "ok\n".into())]
+        );
+    }
+
+    #[test]
+    fn test_zip_members() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bundle.zip");
+        let mut zip = zip::ZipWriter::new(File::create(&path).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        zip.add_directory("logs/", options).unwrap();
+        zip.start_file("logs/b.log", options).unwrap();
+        zip.write_all(b"WARN slow\n").unwrap();
+        zip.finish().unwrap();
+        assert_eq!(
+            members(&path),
+            vec![(
+                format!("{}!logs/b.log", path.display()),
+                "WARN slow\n".into()
+            )]
+        );
+    }
+}
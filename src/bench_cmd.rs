@@ -0,0 +1,363 @@
+//! Benchmark summary: runs `cargo bench` (criterion or libtest benches) or
+//! hyperfine, keeps one line per benchmark, and compares each with the
+//! result stored by the previous run of the same command in the same
+//! directory. Changes wider than both runs' noise are marked.
+
+use crate::tracking::{self, BenchRecord};
+use crate::utils::{self, truncate};
+use anyhow::{Context, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Deserialize;
+use std::process::Command;
+
+/// Benchmarks listed before the rest are counted
+const MAX_BENCHES: usize = 30;
+
+/// Changes smaller than this are noise even when the intervals separate
+const MIN_CHANGE_PCT: f64 = 2.0;
+
+lazy_static! {
+    /// Criterion: `fib 20   time:   [26.029 µs 26.251 µs 26.505 µs]`
+    static ref CRITERION_TIME: Regex = Regex::new(
+        r"^(.*?)\s*time:\s+\[([\d.]+)\s*(\S+)\s+([\d.]+)\s*(\S+)\s+([\d.]+)\s*(\S+)\]"
+    )
+    .unwrap();
+    /// libtest: `test bench_add ... bench:       1,234 ns/iter (+/- 56)`
+    static ref LIBTEST_BENCH: Regex =
+        Regex::new(r"^test (\S+)\s+\.\.\. bench:\s+([\d,.]+) ns/iter \(\+/- ([\d,.]+)\)").unwrap();
+}
+
+#[derive(Debug, Deserialize)]
+struct HyperfineExport {
+    results: Vec<HyperfineResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HyperfineResult {
+    command: String,
+    /// Seconds
+    mean: f64,
+    stddev: Option<f64>,
+}
+
+/// Run benchmarks and compare them with the previous run
+pub fn run(args: &[String], verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+    let hyperfine = args.first().is_some_and(|a| a == "hyperfine");
+
+    // hyperfine's own summary is verbose; its JSON export is what we read
+    let export = tempfile::NamedTempFile::new()?;
+    let (mut cmd, command) = if hyperfine {
+        let mut cmd = Command::new("hyperfine");
+        cmd.args(&args[1..])
+            .arg("--style")
+            .arg("basic")
+            .arg("--export-json")
+            .arg(export.path());
+        (cmd, args.join(" "))
+    } else {
+        // `rtk bench <args>` and `rtk bench cargo bench <args>`
+        let rest = match args {
+            [cargo, bench, rest @ ..] if cargo == "cargo" && bench == "bench" => rest,
+            rest => rest,
+        };
+        let mut cmd = Command::new("cargo");
+        cmd.arg("bench").args(rest);
+        (cmd, format!("cargo bench {}", rest.join(" ")))
+    };
+
+    if verbose > 0 {
+        eprintln!("Running: {}", command.trim());
+    }
+
+    let output = cmd
+        .output()
+        .with_context(|| format!("Failed to run {}", command.trim()))?;
+    let (stdout, stderr) = crate::output::captured(&output);
+    let raw = format!("{}\n{}", stdout, stderr);
+    let exit_code = utils::exit_code(output.status);
+
+    let results = if hyperfine {
+        let json = std::fs::read_to_string(export.path()).unwrap_or_default();
+        parse_hyperfine_json(&json)
+    } else {
+        parse_cargo_bench(&stdout)
+    };
+
+    let tool = if hyperfine {
+        "hyperfine"
+    } else {
+        "cargo bench"
+    };
+    let filtered = if results.is_empty() {
+        // Build errors or an unknown harness: show the end of the output
+        let tail: Vec<&str> = raw.lines().rev().take(20).collect();
+        let tail: Vec<&str> = tail.into_iter().rev().collect();
+        format!("{}: no benchmark results\n{}", tool, tail.join("\n"))
+    } else {
+        let cwd = std::env::current_dir().unwrap_or_default();
+        let scope = format!("{}\t{}", cwd.display(), command.trim());
+        let previous = tracking::swap_benchmarks(&scope, &results);
+        format_comparison(tool, &results, &previous)
+    };
+
+    if let Some(hint) = crate::tee::tee_and_hint(&raw, "bench", exit_code) {
+        println!("{}\n{}", filtered, hint);
+    } else {
+        println!("{}", filtered);
+    }
+    timer.track(command.trim(), "rtk bench", &raw, &filtered);
+
+    utils::exit_with(tool, exit_code, verbose);
+    Ok(())
+}
+
+/// Criterion's `time:` lines (name on the same or the previous line) and
+/// libtest's `bench:` lines
+fn parse_cargo_bench(output: &str) -> Vec<BenchRecord> {
+    let mut results = Vec::new();
+    let mut last_line = "";
+    for line in output.lines() {
+        if let Some(caps) = CRITERION_TIME.captures(line) {
+            // Long names get a line of their own
+            let name = match caps[1].trim() {
+                "" => last_line.trim(),
+                name => name,
+            };
+            let low = to_ns(&caps[2], &caps[3]);
+            let estimate = to_ns(&caps[4], &caps[5]);
+            let high = to_ns(&caps[6], &caps[7]);
+            if let (Some(low), Some(estimate), Some(high)) = (low, estimate, high) {
+                results.push(BenchRecord {
+                    name: name.to_string(),
+                    mean_ns: estimate,
+                    spread_ns: (high - low) / 2.0,
+                });
+            }
+        } else if let Some(caps) = LIBTEST_BENCH.captures(line) {
+            let number = |s: &str| s.replace(',', "").parse::<f64>().ok();
+            if let (Some(mean), Some(spread)) = (number(&caps[2]), number(&caps[3])) {
+                results.push(BenchRecord {
+                    name: caps[1].to_string(),
+                    mean_ns: mean,
+                    spread_ns: spread,
+                });
+            }
+        }
+        if !line.trim().is_empty() {
+            last_line = line;
+        }
+    }
+    results
+}
+
+/// hyperfine `--export-json` output: one result per command
+fn parse_hyperfine_json(json: &str) -> Vec<BenchRecord> {
+    let Ok(export) = serde_json::from_str::<HyperfineExport>(json) else {
+        return Vec::new();
+    };
+    export
+        .results
+        .into_iter()
+        .map(|r| BenchRecord {
+            name: r.command,
+            mean_ns: r.mean * 1e9,
+            spread_ns: r.stddev.unwrap_or(0.0) * 1e9,
+        })
+        .collect()
+}
+
+/// `value` in `unit` (criterion's ps/ns/µs/us/ms/s) as nanoseconds
+fn to_ns(value: &str, unit: &str) -> Option<f64> {
+    let value: f64 = value.parse().ok()?;
+    let factor = match unit {
+        "ps" => 1e-3,
+        "ns" => 1.0,
+        "µs" | "us" => 1e3,
+        "ms" => 1e6,
+        "s" => 1e9,
+        _ => return None,
+    };
+    Some(value * factor)
+}
+
+/// Nanoseconds with the unit that keeps 1-3 integer digits
+fn format_duration(ns: f64) -> String {
+    if ns >= 1e9 {
+        format!("{:.2} s", ns / 1e9)
+    } else if ns >= 1e6 {
+        format!("{:.2} ms", ns / 1e6)
+    } else if ns >= 1e3 {
+        format!("{:.2} µs", ns / 1e3)
+    } else {
+        format!("{:.1} ns", ns)
+    }
+}
+
+/// Change of `after` from `before` in percent, and whether it stands out
+/// of both runs' noise
+fn change(before: &BenchRecord, after: &BenchRecord) -> (f64, bool) {
+    let diff = after.mean_ns - before.mean_ns;
+    let pct = if before.mean_ns > 0.0 {
+        diff / before.mean_ns * 100.0
+    } else {
+        0.0
+    };
+    let significant =
+        diff.abs() > before.spread_ns + after.spread_ns && pct.abs() >= MIN_CHANGE_PCT;
+    (pct, significant)
+}
+
+/// One line per benchmark: time, then the change from the previous run
+/// (`✗` slower, `✓` faster, `~` within noise) or `new`
+fn format_comparison(tool: &str, results: &[BenchRecord], previous: &[BenchRecord]) -> String {
+    let mut slower = 0;
+    let mut faster = 0;
+    let mut lines = Vec::new();
+    let name_width = results
+        .iter()
+        .map(|r| r.name.chars().count())
+        .max()
+        .unwrap_or(0)
+        .min(40);
+
+    for result in results {
+        let name = truncate(&result.name, 40);
+        let time = format_duration(result.mean_ns);
+        let comparison = match previous.iter().find(|p| p.name == result.name) {
+            None if previous.is_empty() => String::new(),
+            None => "  new".to_string(),
+            Some(before) => {
+                let (pct, significant) = change(before, result);
+                let marker = match (significant, pct > 0.0) {
+                    (false, _) => "~",
+                    (true, true) => {
+                        slower += 1;
+                        "✗"
+                    }
+                    (true, false) => {
+                        faster += 1;
+                        "✓"
+                    }
+                };
+                format!(
+                    "  {:+.1}% {} (was {})",
+                    pct,
+                    marker,
+                    format_duration(before.mean_ns)
+                )
+            }
+        };
+        lines.push(format!(
+            "  {:<width$}  {:>10}{}",
+            name,
+            time,
+            comparison,
+            width = name_width
+        ));
+    }
+
+    let mut out = format!("{}: {} benchmarks", tool, results.len());
+    if !previous.is_empty() {
+        out.push_str(&format!(
+            " ({} slower, {} faster than last run)",
+            slower, faster
+        ));
+    }
+    out.push('\n');
+    for line in lines.iter().take(MAX_BENCHES) {
+        out.push_str(line);
+        out.push('\n');
+    }
+    if lines.len() > MAX_BENCHES {
+        out.push_str(&format!(
+            "  ... +{} more benchmarks\n",
+            lines.len() - MAX_BENCHES
+        ));
+    }
+    out.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bench(name: &str, mean_ns: f64, spread_ns: f64) -> BenchRecord {
+        BenchRecord {
+            name: name.into(),
+            mean_ns,
+            spread_ns,
+        }
+    }
+
+    #[test]
+    fn test_parse_criterion_and_libtest() {
+        let output = "\
+Benchmarking fib 20: Analyzing
+fib 20                  time:   [26.029 µs 26.251 µs 26.505 µs]
+                        change: [-2.1% +0.3% +2.5%] (p = 0.80 > 0.05)
+                        No change in performance detected.
+a/very/long/benchmark/name/that/wraps
+                        time:   [1.0000 ms 1.2000 ms 1.4000 ms]
+test bench_add ... bench:       1,234 ns/iter (+/- 56)
+";
+        let results = parse_cargo_bench(output);
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].name, "fib 20");
+        assert!((results[0].mean_ns - 26_251.0).abs() < 0.01);
+        assert!((results[0].spread_ns - 238.0).abs() < 0.01);
+        assert_eq!(results[1].name, "a/very/long/benchmark/name/that/wraps");
+        assert_eq!(results[1].mean_ns, 1.2e6);
+        assert_eq!(results[2], bench("bench_add", 1234.0, 56.0));
+    }
+
+    #[test]
+    fn test_parse_hyperfine_json() {
+        let json =
+            r#"{"results":[{"command":"sleep 0.1","mean":0.1012,"stddev":0.0004,"times":[]}]}"#;
+        let results = parse_hyperfine_json(json);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].name, "sleep 0.1");
+        assert!((results[0].mean_ns - 101_200_000.0).abs() < 1.0);
+        assert!(parse_hyperfine_json("not json").is_empty());
+    }
+
+    #[test]
+    fn test_change_significance() {
+        let before = bench("a", 100.0, 2.0);
+        assert_eq!(change(&before, &bench("a", 120.0, 2.0)), (20.0, true));
+        // Inside the combined noise
+        assert!(!change(&before, &bench("a", 103.0, 2.0)).1);
+        // Separate intervals but a negligible change
+        assert!(!change(&bench("a", 1000.0, 1.0), &bench("a", 1010.0, 1.0)).1);
+    }
+
+    #[test]
+    fn test_format_comparison() {
+        let results = vec![
+            bench("parse", 120_000.0, 1_000.0),
+            bench("render", 2_000_000.0, 10_000.0),
+            bench("fresh", 500.0, 5.0),
+        ];
+        let previous = vec![
+            bench("parse", 100_000.0, 1_000.0),
+            bench("render", 2_010_000.0, 20_000.0),
+        ];
+        let out = format_comparison("cargo bench", &results, &previous);
+        assert!(out.starts_with("cargo bench: 3 benchmarks (1 slower, 0 faster than last run)"));
+        assert!(out.contains("parse    120.00 µs  +20.0% ✗ (was 100.00 µs)"));
+        assert!(out.contains("-0.5% ~"));
+        assert!(out.contains("500.0 ns  new"));
+
+        let first = format_comparison("hyperfine", &results[..1], &[]);
+        assert_eq!(first, "hyperfine: 1 benchmarks\n  parse   120.00 µs");
+    }
+
+    #[test]
+    fn test_to_ns_units() {
+        assert_eq!(to_ns("2", "ms"), Some(2e6));
+        assert_eq!(to_ns("3", "us"), Some(3e3));
+        assert_eq!(to_ns("1", "fortnights"), None);
+    }
+}
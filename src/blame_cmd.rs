@@ -0,0 +1,354 @@
+//! `rtk git blame`: who owns a file (or a line range) and when it was last
+//! touched, aggregated per author, per commit and per region instead of one
+//! annotation per line.
+
+use crate::tracking;
+use crate::utils::truncate;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::process::Command;
+
+/// Commits listed, largest first
+const MAX_COMMITS: usize = 5;
+
+/// Regions listed, in file order
+const MAX_REGIONS: usize = 20;
+
+/// All-zero hash git uses for lines not committed yet
+pub(crate) const UNCOMMITTED: &str = "0000000000000000000000000000000000000000";
+
+/// One line of `git blame --line-porcelain`
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct BlameLine {
+    pub(crate) line: usize,
+    pub(crate) sha: String,
+    pub(crate) author: String,
+    pub(crate) time: i64,
+    pub(crate) summary: String,
+    pub(crate) filename: String,
+    pub(crate) content: String,
+}
+
+/// Lines of one author
+#[derive(Debug, PartialEq)]
+struct AuthorShare {
+    author: String,
+    lines: usize,
+    last: i64,
+}
+
+/// Run of consecutive lines from one author
+#[derive(Debug, PartialEq)]
+struct Region {
+    start: usize,
+    end: usize,
+    author: String,
+    /// Newest commit of the region
+    sha: String,
+    time: i64,
+    summary: String,
+}
+
+/// `args` are passed to `git blame`: a file, `-L` ranges, a revision
+pub fn run(args: &[String], verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    if verbose > 0 {
+        eprintln!("git blame --line-porcelain {}", args.join(" "));
+    }
+
+    let output = Command::new("git")
+        .args(["blame", "--line-porcelain"])
+        .args(args)
+        .output()
+        .context("Failed to run git blame")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        eprint!("{}", stderr);
+        std::process::exit(output.status.code().unwrap_or(1));
+    }
+
+    let lines = parse_line_porcelain(&String::from_utf8_lossy(&output.stdout));
+    let target = lines
+        .first()
+        .map(|l| l.filename.clone())
+        .unwrap_or_else(|| args.join(" "));
+    let summary = format_blame(&target, &lines);
+    println!("{}", summary);
+
+    // What plain `git blame` prints for the same lines
+    let raw: String = lines
+        .iter()
+        .map(|l| {
+            format!(
+                "{} ({} {} {}) {}\n",
+                short_sha(&l.sha),
+                l.author,
+                date(l.time),
+                l.line,
+                l.content
+            )
+        })
+        .collect();
+    timer.track(
+        &format!("git blame {}", args.join(" ")),
+        &format!("rtk git blame {}", args.join(" ")),
+        &raw,
+        &summary,
+    );
+    Ok(())
+}
+
+pub(crate) fn parse_line_porcelain(text: &str) -> Vec<BlameLine> {
+    let mut lines = Vec::new();
+    let mut current: Option<BlameLine> = None;
+
+    for line in text.lines() {
+        if let Some(content) = line.strip_prefix('\t') {
+            if let Some(mut entry) = current.take() {
+                entry.content = content.to_string();
+                lines.push(entry);
+            }
+            continue;
+        }
+        if let Some(entry) = current.as_mut() {
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            match key {
+                "author" => entry.author = value.to_string(),
+                "author-time" => entry.time = value.parse().unwrap_or(0),
+                "summary" => entry.summary = value.to_string(),
+                "filename" => entry.filename = value.to_string(),
+                _ => {}
+            }
+            continue;
+        }
+        // Header: <sha> <orig line> <final line> [<group size>]
+        let mut parts = line.split(' ');
+        let (Some(sha), Some(_), Some(final_line)) = (parts.next(), parts.next(), parts.next())
+        else {
+            continue;
+        };
+        current = Some(BlameLine {
+            line: final_line.parse().unwrap_or(0),
+            sha: sha.to_string(),
+            author: String::new(),
+            time: 0,
+            summary: String::new(),
+            filename: String::new(),
+            content: String::new(),
+        });
+    }
+    lines
+}
+
+fn short_sha(sha: &str) -> &str {
+    &sha[..sha.len().min(7)]
+}
+
+fn date(time: i64) -> String {
+    DateTime::<Utc>::from_timestamp(time, 0)
+        .map(|d| d.format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
+
+/// Summary of a commit, cut to `max` chars
+fn commit_summary(sha: &str, summary: &str, max: usize) -> String {
+    if sha == UNCOMMITTED {
+        "working tree changes".to_string()
+    } else {
+        truncate(summary, max)
+    }
+}
+
+fn author_name(line: &BlameLine) -> &str {
+    if line.sha == UNCOMMITTED {
+        "(uncommitted)"
+    } else {
+        &line.author
+    }
+}
+
+/// Authors by number of lines, most first
+fn by_author(lines: &[BlameLine]) -> Vec<AuthorShare> {
+    let mut shares: HashMap<&str, AuthorShare> = HashMap::new();
+    for line in lines {
+        let name = author_name(line);
+        let share = shares.entry(name).or_insert_with(|| AuthorShare {
+            author: name.to_string(),
+            lines: 0,
+            last: 0,
+        });
+        share.lines += 1;
+        share.last = share.last.max(line.time);
+    }
+    let mut shares: Vec<AuthorShare> = shares.into_values().collect();
+    shares.sort_by(|a, b| b.lines.cmp(&a.lines).then_with(|| a.author.cmp(&b.author)));
+    shares
+}
+
+/// Consecutive lines of the same author, with the newest commit among them
+fn regions(lines: &[BlameLine]) -> Vec<Region> {
+    let mut regions: Vec<Region> = Vec::new();
+    for line in lines {
+        let author = author_name(line);
+        match regions.last_mut() {
+            Some(region) if region.author == author && region.end + 1 == line.line => {
+                region.end = line.line;
+                if line.time > region.time {
+                    region.sha = line.sha.clone();
+                    region.time = line.time;
+                    region.summary = line.summary.clone();
+                }
+            }
+            _ => regions.push(Region {
+                start: line.line,
+                end: line.line,
+                author: author.to_string(),
+                sha: line.sha.clone(),
+                time: line.time,
+                summary: line.summary.clone(),
+            }),
+        }
+    }
+    regions
+}
+
+fn format_blame(target: &str, lines: &[BlameLine]) -> String {
+    if lines.is_empty() {
+        return format!("📝 {}: no lines blamed", target);
+    }
+
+    let authors = by_author(lines);
+    let mut commits: HashMap<&str, (usize, &BlameLine)> = HashMap::new();
+    for line in lines {
+        commits.entry(&line.sha).or_insert((0, line)).0 += 1;
+    }
+    let mut commits: Vec<(usize, &BlameLine)> = commits.into_values().collect();
+    commits.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| b.1.time.cmp(&a.1.time)));
+
+    let mut out = format!(
+        "📝 {} ({} lines, {} authors, {} commits)\n",
+        target,
+        lines.len(),
+        authors.len(),
+        commits.len()
+    );
+
+    out.push_str("Authors:\n");
+    let width = authors.iter().map(|a| a.author.len()).max().unwrap_or(0);
+    for share in &authors {
+        out.push_str(&format!(
+            "  {:<width$} {:>5} ({:>3}%)  last {}\n",
+            share.author,
+            share.lines,
+            share.lines * 100 / lines.len(),
+            date(share.last),
+            width = width
+        ));
+    }
+
+    out.push_str("Commits:\n");
+    for (count, line) in commits.iter().take(MAX_COMMITS) {
+        out.push_str(&format!(
+            "  {} {} {} {:>4} lines  {}\n",
+            short_sha(&line.sha),
+            date(line.time),
+            author_name(line),
+            count,
+            commit_summary(&line.sha, &line.summary, 60)
+        ));
+    }
+    if commits.len() > MAX_COMMITS {
+        out.push_str(&format!("  ... +{} commits\n", commits.len() - MAX_COMMITS));
+    }
+
+    let regions = regions(lines);
+    out.push_str("Regions:\n");
+    for region in regions.iter().take(MAX_REGIONS) {
+        out.push_str(&format!(
+            "  {:>9}  {} {} {} {}\n",
+            format!("{}-{}", region.start, region.end),
+            region.author,
+            date(region.time),
+            short_sha(&region.sha),
+            commit_summary(&region.sha, &region.summary, 50)
+        ));
+    }
+    if regions.len() > MAX_REGIONS {
+        out.push_str(&format!("  ... +{} regions\n", regions.len() - MAX_REGIONS));
+    }
+    out.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PORCELAIN: &str = "aaaaaaaa11111111111111111111111111111111 1 1 2
+author Alice
+author-time 1709251200
+summary Add parser
+filename src/a.rs
+\tfn parse() {
+aaaaaaaa11111111111111111111111111111111 2 2
+author Alice
+author-time 1709251200
+summary Add parser
+filename src/a.rs
+\t    todo!()
+bbbbbbbb22222222222222222222222222222222 3 3 1
+author Bob
+author-time 1714521600
+summary Fix parser
+filename src/a.rs
+\t}
+0000000000000000000000000000000000000000 4 4 1
+author Not Committed Yet
+author-time 1717200000
+summary Version of src/a.rs from src/a.rs
+filename src/a.rs
+\t// wip
+";
+
+    #[test]
+    fn test_parse_line_porcelain() {
+        let lines = parse_line_porcelain(PORCELAIN);
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[1].line, 2);
+        assert_eq!(lines[1].author, "Alice");
+        assert_eq!(lines[1].content, "    todo!()");
+        assert_eq!(lines[2].summary, "Fix parser");
+        assert_eq!(lines[3].filename, "src/a.rs");
+    }
+
+    #[test]
+    fn test_regions_merge_consecutive_author_lines() {
+        let lines = parse_line_porcelain(PORCELAIN);
+        let regions = regions(&lines);
+        assert_eq!(regions.len(), 3);
+        assert_eq!((regions[0].start, regions[0].end), (1, 2));
+        assert_eq!(regions[2].author, "(uncommitted)");
+    }
+
+    #[test]
+    fn test_format_blame() {
+        let lines = parse_line_porcelain(PORCELAIN);
+        let out = format_blame("src/a.rs", &lines);
+        let expected = "📝 src/a.rs (4 lines, 3 authors, 3 commits)
+Authors:
+  Alice             2 ( 50%)  last 2024-03-01
+  (uncommitted)     1 ( 25%)  last 2024-06-01
+  Bob               1 ( 25%)  last 2024-05-01
+Commits:
+  aaaaaaa 2024-03-01 Alice    2 lines  Add parser
+  0000000 2024-06-01 (uncommitted)    1 lines  working tree changes
+  bbbbbbb 2024-05-01 Bob    1 lines  Fix parser
+Regions:
+        1-2  Alice 2024-03-01 aaaaaaa Add parser
+        3-3  Bob 2024-05-01 bbbbbbb Fix parser
+        4-4  (uncommitted) 2024-06-01 0000000 working tree changes";
+        assert_eq!(out, expected);
+        assert!(format_blame("x.rs", &[]).ends_with("no lines blamed"));
+    }
+}
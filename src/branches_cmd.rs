@@ -0,0 +1,395 @@
+//! `rtk git branches`: local branches with upstream drift, last-commit age
+//! and whether they are merged into the base branch, plus the stash with
+//! short stats. One overview instead of `git branch -vv`, `git branch
+//! --merged`, `git log` and `git stash list`.
+
+use crate::todo_cmd::age;
+use crate::tracking;
+use crate::utils::truncate;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use std::process::Command;
+
+/// Branches listed, most recently committed first
+const MAX_BRANCHES: usize = 30;
+
+/// Stash entries listed, newest first
+const MAX_STASHES: usize = 10;
+
+/// Width of the commit subject column
+const SUBJECT_WIDTH: usize = 50;
+
+/// Separates the records of `git stash list --shortstat`
+const RECORD: char = '\x01';
+
+/// A local branch
+#[derive(Debug, Clone, PartialEq)]
+struct Branch {
+    name: String,
+    current: bool,
+    /// `origin/main`, empty without one
+    upstream: String,
+    /// Commits ahead of and behind the upstream
+    ahead: usize,
+    behind: usize,
+    /// The upstream branch was deleted
+    gone: bool,
+    merged: bool,
+    /// Last commit, Unix seconds
+    time: i64,
+    subject: String,
+}
+
+/// A stash entry with its diff stats
+#[derive(Debug, Clone, PartialEq)]
+struct Stash {
+    name: String,
+    time: i64,
+    message: String,
+    files: usize,
+    insertions: usize,
+    deletions: usize,
+}
+
+pub fn run(verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    let refs = git(&[
+        "for-each-ref",
+        "refs/heads",
+        "--format=%(HEAD)%00%(refname:short)%00%(upstream:short)%00%(upstream:track,nobracket)%00%(committerdate:unix)%00%(subject)",
+    ])?;
+    let base = base_branch();
+    if verbose > 0 {
+        eprintln!("Merged status against {}", base.as_deref().unwrap_or("-"));
+    }
+    let merged = match &base {
+        Some(base) => git(&["branch", "--merged", base, "--format=%(refname:short)"])?,
+        None => String::new(),
+    };
+    let merged: Vec<&str> = merged.lines().collect();
+    let branches = parse_branches(&refs, &merged);
+
+    let stash = git(&[
+        "stash",
+        "list",
+        "--shortstat",
+        &format!("--format={}%gd%x00%ct%x00%gs", RECORD),
+    ])?;
+    let stashes = parse_stashes(&stash);
+
+    let output = format_overview(&branches, &stashes, base.as_deref(), Utc::now().timestamp());
+    println!("{}", output);
+    timer.track(
+        "git branch -vv && git stash list",
+        "rtk git branches",
+        &format!("{}\n{}", refs, stash),
+        &output,
+    );
+    Ok(())
+}
+
+fn git(args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git {} failed: {}",
+            args[0],
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// The branch others are merged into: the remote's default branch, else
+/// a local main or master
+fn base_branch() -> Option<String> {
+    if let Ok(head) = git(&["symbolic-ref", "--short", "refs/remotes/origin/HEAD"]) {
+        let head = head.trim();
+        let local = head.strip_prefix("origin/").unwrap_or(head);
+        if git(&["rev-parse", "--verify", "--quiet", local]).is_ok() {
+            return Some(local.to_string());
+        }
+    }
+    ["main", "master"]
+        .into_iter()
+        .find(|b| git(&["rev-parse", "--verify", "--quiet", b]).is_ok())
+        .map(str::to_string)
+}
+
+/// `git for-each-ref` records (HEAD mark, name, upstream, track, time,
+/// subject), most recently committed first, the current branch on top
+fn parse_branches(refs: &str, merged: &[&str]) -> Vec<Branch> {
+    let mut branches: Vec<Branch> = refs
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.splitn(6, '\0').collect();
+            let [head, name, upstream, track, time, subject] = fields[..] else {
+                return None;
+            };
+            let (ahead, behind) = parse_track(track);
+            Some(Branch {
+                name: name.to_string(),
+                current: head == "*",
+                upstream: upstream.to_string(),
+                ahead,
+                behind,
+                gone: track == "gone",
+                merged: merged.contains(&name),
+                time: time.parse().unwrap_or(0),
+                subject: subject.to_string(),
+            })
+        })
+        .collect();
+    branches.sort_by(|a, b| {
+        b.current
+            .cmp(&a.current)
+            .then(b.time.cmp(&a.time))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    branches
+}
+
+/// `ahead 2, behind 3` → (2, 3)
+fn parse_track(track: &str) -> (usize, usize) {
+    let mut counts = (0, 0);
+    for part in track.split(", ") {
+        if let Some(n) = part.strip_prefix("ahead ") {
+            counts.0 = n.parse().unwrap_or(0);
+        } else if let Some(n) = part.strip_prefix("behind ") {
+            counts.1 = n.parse().unwrap_or(0);
+        }
+    }
+    counts
+}
+
+/// `git stash list --shortstat` records: name, time, message, then the
+/// shortstat line
+fn parse_stashes(list: &str) -> Vec<Stash> {
+    list.split(RECORD)
+        .filter_map(|record| {
+            let mut lines = record.lines().filter(|l| !l.trim().is_empty());
+            let fields: Vec<&str> = lines.next()?.splitn(3, '\0').collect();
+            let [name, time, message] = fields[..] else {
+                return None;
+            };
+            let mut stash = Stash {
+                name: name.to_string(),
+                time: time.parse().unwrap_or(0),
+                message: message.to_string(),
+                files: 0,
+                insertions: 0,
+                deletions: 0,
+            };
+            for part in lines.next().unwrap_or_default().split(", ") {
+                let Some((n, what)) = part.trim().split_once(' ') else {
+                    continue;
+                };
+                let n = n.parse().unwrap_or(0);
+                if what.starts_with("file") {
+                    stash.files = n;
+                } else if what.starts_with("insertion") {
+                    stash.insertions = n;
+                } else if what.starts_with("deletion") {
+                    stash.deletions = n;
+                }
+            }
+            Some(stash)
+        })
+        .collect()
+}
+
+/// `↑2 ↓1 origin/x`, `= origin/main`, `gone origin/x`, `local`
+fn drift(branch: &Branch) -> String {
+    if branch.upstream.is_empty() {
+        return "local".to_string();
+    }
+    if branch.gone {
+        return format!("gone {}", branch.upstream);
+    }
+    let mut parts = Vec::new();
+    if branch.ahead > 0 {
+        parts.push(format!("↑{}", branch.ahead));
+    }
+    if branch.behind > 0 {
+        parts.push(format!("↓{}", branch.behind));
+    }
+    if parts.is_empty() {
+        parts.push("=".to_string());
+    }
+    format!("{} {}", parts.join(" "), branch.upstream)
+}
+
+fn format_overview(branches: &[Branch], stashes: &[Stash], base: Option<&str>, now: i64) -> String {
+    let mut out = format!("🌿 {} branches", branches.len());
+    if let Some(base) = base {
+        out.push_str(&format!(" (base {})", base));
+    }
+    let merged: Vec<&str> = branches
+        .iter()
+        .filter(|b| b.merged && !b.current && Some(b.name.as_str()) != base)
+        .map(|b| b.name.as_str())
+        .collect();
+    let gone = branches.iter().filter(|b| b.gone).count();
+    let mut counts = Vec::new();
+    if !merged.is_empty() {
+        counts.push(format!("{} merged", merged.len()));
+    }
+    if gone > 0 {
+        counts.push(format!("{} gone upstream", gone));
+    }
+    if !counts.is_empty() {
+        out.push_str(&format!(", {}", counts.join(", ")));
+    }
+    out.push_str(":\n");
+
+    let name_width = branches
+        .iter()
+        .take(MAX_BRANCHES)
+        .map(|b| b.name.chars().count() + 2)
+        .max()
+        .unwrap_or(0);
+    let drifts: Vec<String> = branches.iter().take(MAX_BRANCHES).map(drift).collect();
+    let drift_width = drifts.iter().map(|d| d.chars().count()).max().unwrap_or(0);
+    for (branch, drift) in branches.iter().zip(&drifts) {
+        let mark = if branch.current { '*' } else { ' ' };
+        let name = if merged.contains(&branch.name.as_str()) {
+            format!("{} ✓", branch.name)
+        } else {
+            branch.name.clone()
+        };
+        out.push_str(&format!(
+            "{} {:<nw$} {:<dw$}  {:>4}  {}\n",
+            mark,
+            name,
+            drift,
+            age(now, branch.time),
+            truncate(&branch.subject, SUBJECT_WIDTH),
+            nw = name_width,
+            dw = drift_width
+        ));
+    }
+    if branches.len() > MAX_BRANCHES {
+        out.push_str(&format!(
+            "  ... +{} older branches\n",
+            branches.len() - MAX_BRANCHES
+        ));
+    }
+    if !merged.is_empty() {
+        out.push_str(&format!(
+            "✓ merged into {}: git branch -d {}\n",
+            base.unwrap_or("base"),
+            merged.join(" ")
+        ));
+    }
+
+    if !stashes.is_empty() {
+        out.push_str(&format!("\n📦 {} stashes:\n", stashes.len()));
+        for stash in stashes.iter().take(MAX_STASHES) {
+            out.push_str(&format!(
+                "  {} {:>4}  {} ({}F +{} -{})\n",
+                stash.name,
+                age(now, stash.time),
+                truncate(&stash.message, SUBJECT_WIDTH),
+                stash.files,
+                stash.insertions,
+                stash.deletions
+            ));
+        }
+        if stashes.len() > MAX_STASHES {
+            out.push_str(&format!(
+                "  ... +{} older stashes\n",
+                stashes.len() - MAX_STASHES
+            ));
+        }
+    }
+    out.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 86_400;
+    const NOW: i64 = 1_700_000_000;
+
+    fn refs() -> String {
+        [
+            format!("\0old-fix\0\0\0{}\0Fix the parser", NOW - 100 * DAY),
+            format!(
+                "*\0feature/x\0origin/feature/x\0ahead 2, behind 1\0{}\0Add x",
+                NOW - 3 * DAY
+            ),
+            format!("\0main\0origin/main\0\0{}\0Release 1.2", NOW - DAY),
+            format!("\0spike\0origin/spike\0gone\0{}\0Try y", NOW - 20 * DAY),
+        ]
+        .join("\n")
+    }
+
+    #[test]
+    fn test_parse_branches() {
+        let branches = parse_branches(&refs(), &["main", "old-fix"]);
+        let names: Vec<&str> = branches.iter().map(|b| b.name.as_str()).collect();
+        assert_eq!(names, vec!["feature/x", "main", "spike", "old-fix"]);
+        assert!(branches[0].current);
+        assert_eq!((branches[0].ahead, branches[0].behind), (2, 1));
+        assert!(branches[2].gone);
+        assert!(branches[3].merged && branches[3].upstream.is_empty());
+    }
+
+    #[test]
+    fn test_parse_stashes() {
+        let list = "\x01stash@{0}\x001699990000\x00On main: wip parser\n\n 3 files changed, 20 insertions(+), 4 deletions(-)\n\
+                    \x01stash@{1}\x001699000000\x00WIP on x: abc123 Add x\n\n 1 file changed, 1 deletion(-)\n";
+        let stashes = parse_stashes(list);
+        assert_eq!(stashes.len(), 2);
+        assert_eq!(
+            stashes[0],
+            Stash {
+                name: "stash@{0}".into(),
+                time: 1_699_990_000,
+                message: "On main: wip parser".into(),
+                files: 3,
+                insertions: 20,
+                deletions: 4,
+            }
+        );
+        assert_eq!(
+            (
+                stashes[1].files,
+                stashes[1].insertions,
+                stashes[1].deletions
+            ),
+            (1, 0, 1)
+        );
+    }
+
+    #[test]
+    fn test_format_overview() {
+        let branches = parse_branches(&refs(), &["main", "old-fix"]);
+        let stashes = vec![Stash {
+            name: "stash@{0}".into(),
+            time: NOW - 2 * DAY,
+            message: "On main: wip parser".into(),
+            files: 3,
+            insertions: 20,
+            deletions: 4,
+        }];
+        let out = format_overview(&branches, &stashes, Some("main"), NOW);
+        assert_eq!(
+            out,
+            "🌿 4 branches (base main), 1 merged, 1 gone upstream:\n\
+             * feature/x   ↑2 ↓1 origin/feature/x    3d  Add x\n\
+             \x20 main        = origin/main             1d  Release 1.2\n\
+             \x20 spike       gone origin/spike         2w  Try y\n\
+             \x20 old-fix ✓   local                    3mo  Fix the parser\n\
+             ✓ merged into main: git branch -d old-fix\n\
+             \n\
+             📦 1 stashes:\n\
+             \x20 stash@{0}   2d  On main: wip parser (3F +20 -4)"
+        );
+    }
+}
@@ -0,0 +1,361 @@
+//! `--budget`: a hard cap on the size of any command's output. The command
+//! runs again without the flag, its output is cut down to the budget and a
+//! last line says what was dropped.
+//!
+//! Output is read as sections: a line at column 0 (a file, a suite) and the
+//! indented lines below it (matches, with `  12- ` context lines). Least
+//! relevant goes first: later sections, then later lines within the
+//! sections left (each keeps its first match), then context lines, then
+//! whole lines from the end.
+
+use crate::tokenizer::TokenModel;
+use anyhow::{Context, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::ffi::OsString;
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+
+lazy_static! {
+    /// grep context lines: `  12- text`
+    static ref CONTEXT_RE: Regex = Regex::new(r"^\s+\d+-\s").unwrap();
+}
+
+/// Size of the note, reserved whenever something is dropped
+const NOTE_RESERVE: &str =
+    "✂️  budget 999999 tokens: dropped 99999 sections, 99999 lines, 99999 context lines, 99999 trailing lines";
+
+/// An output size limit: `2000` (tokens), `8kb` or `8000b` (bytes)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Budget {
+    Tokens(usize),
+    Bytes(usize),
+}
+
+impl FromStr for Budget {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.trim().to_ascii_lowercase();
+        let (number, unit) = lower.split_at(
+            lower
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(lower.len()),
+        );
+        let n: usize = number
+            .parse()
+            .map_err(|_| format!("invalid budget '{}': expected e.g. 2000, 8kb", s))?;
+        let bytes = |multiplier: usize| {
+            n.checked_mul(multiplier)
+                .map(Budget::Bytes)
+                .ok_or_else(|| format!("invalid budget '{}': too large", s))
+        };
+        match unit {
+            "" | "t" | "tok" | "tokens" => Ok(Budget::Tokens(n)),
+            "b" => Ok(Budget::Bytes(n)),
+            "kb" | "k" => bytes(1024),
+            "mb" | "m" => bytes(1024 * 1024),
+            _ => Err(format!(
+                "invalid budget unit '{}': use tokens (2000) or bytes (8000b, 8kb)",
+                unit
+            )),
+        }
+    }
+}
+
+impl Budget {
+    fn limit(self) -> usize {
+        match self {
+            Budget::Tokens(n) | Budget::Bytes(n) => n,
+        }
+    }
+
+    fn describe(self) -> String {
+        match self {
+            Budget::Tokens(n) => format!("{} tokens", n),
+            Budget::Bytes(n) => crate::utils::format_bytes(n as u64),
+        }
+    }
+
+    /// Cost of one output line, newline included
+    fn cost(self, model: TokenModel, line: &str) -> usize {
+        match self {
+            Budget::Tokens(_) => model.count(line),
+            Budget::Bytes(_) => line.len() + 1,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Line<'a> {
+    text: &'a str,
+    cost: usize,
+}
+
+#[derive(Debug)]
+struct Section<'a> {
+    header: Line<'a>,
+    body: Vec<Line<'a>>,
+}
+
+/// What [`fit`] took out
+#[derive(Debug, Default, PartialEq)]
+struct Dropped {
+    sections: usize,
+    lines: usize,
+    context: usize,
+    trailing: usize,
+}
+
+impl Dropped {
+    fn is_empty(&self) -> bool {
+        *self == Dropped::default()
+    }
+
+    fn note(&self, budget: Budget) -> String {
+        let parts: Vec<String> = [
+            (self.sections, "section"),
+            (self.lines, "line"),
+            (self.context, "context line"),
+            (self.trailing, "trailing line"),
+        ]
+        .iter()
+        .filter(|(n, _)| *n > 0)
+        .map(|(n, what)| format!("{} {}{}", n, what, if *n == 1 { "" } else { "s" }))
+        .collect();
+        format!(
+            "✂️  budget {}: dropped {}",
+            budget.describe(),
+            parts.join(", ")
+        )
+    }
+}
+
+fn is_context(line: &Line) -> bool {
+    CONTEXT_RE.is_match(line.text)
+}
+
+fn is_blank(line: &Line) -> bool {
+    line.text.trim().is_empty()
+}
+
+/// `output` cut down to `budget`, with a note on what was dropped
+fn fit(output: &str, budget: Budget, model: TokenModel) -> String {
+    let line = |text| Line {
+        text,
+        cost: budget.cost(model, text),
+    };
+    let mut sections: Vec<Section> = Vec::new();
+    for text in output.lines() {
+        match sections.last_mut() {
+            // Blank lines stay with the section they close
+            Some(section) if text.trim().is_empty() || text.starts_with(char::is_whitespace) => {
+                section.body.push(line(text))
+            }
+            _ => sections.push(Section {
+                header: line(text),
+                body: Vec::new(),
+            }),
+        }
+    }
+
+    let mut total: usize = sections
+        .iter()
+        .map(|s| s.header.cost + s.body.iter().map(|l| l.cost).sum::<usize>())
+        .sum();
+    if total <= budget.limit() {
+        return output.to_string();
+    }
+    let limit = budget
+        .limit()
+        .saturating_sub(budget.cost(model, NOTE_RESERVE));
+    let mut dropped = Dropped::default();
+
+    // Later sections with a body, keeping the first
+    while total > limit {
+        let with_body: Vec<usize> = (0..sections.len())
+            .filter(|&i| sections[i].body.iter().any(|l| !is_blank(l)))
+            .collect();
+        let [_, .., last] = with_body[..] else { break };
+        let section = sections.remove(last);
+        total -= section.header.cost + section.body.iter().map(|l| l.cost).sum::<usize>();
+        dropped.sections += 1;
+    }
+
+    // Later lines of each section, up to its first match
+    for section in sections.iter_mut().rev() {
+        let first_match = section
+            .body
+            .iter()
+            .position(|l| !is_context(l) && !is_blank(l));
+        let keep = first_match.map_or(1, |i| i + 1);
+        while total > limit && section.body.len() > keep {
+            let line = section.body.pop().expect("body has lines");
+            total -= line.cost;
+            if is_context(&line) {
+                dropped.context += 1;
+            } else if !is_blank(&line) {
+                dropped.lines += 1;
+            }
+        }
+    }
+
+    // Context around what is left
+    for section in sections.iter_mut() {
+        if total <= limit {
+            break;
+        }
+        section.body.retain(|line| {
+            let keep = total <= limit || !is_context(line);
+            if !keep {
+                total -= line.cost;
+                dropped.context += 1;
+            }
+            keep
+        });
+    }
+
+    let mut lines: Vec<&Line> = sections
+        .iter()
+        .flat_map(|s| std::iter::once(&s.header).chain(&s.body))
+        .collect();
+    while total > limit && lines.len() > 1 {
+        total -= lines.pop().expect("more than one line").cost;
+        dropped.trailing += 1;
+    }
+
+    let mut out: Vec<&str> = lines.iter().map(|l| l.text).collect();
+    let note = dropped.note(budget);
+    if !dropped.is_empty() {
+        out.push(&note);
+    }
+    out.join("\n")
+}
+
+/// The command line again, minus `--budget` and its value
+fn child_args(args: impl Iterator<Item = OsString>) -> Vec<OsString> {
+    let mut out = Vec::new();
+    let mut skip_value = false;
+    for arg in args {
+        if skip_value {
+            skip_value = false;
+        } else if arg == "--budget" {
+            skip_value = true;
+        } else if !arg.to_string_lossy().starts_with("--budget=") {
+            out.push(arg);
+        }
+    }
+    out
+}
+
+/// Run the current rtk command without `--budget` and print its output
+/// cut down to `budget`. Keeps the command's exit code.
+pub fn run(budget: Budget, verbose: u8) -> Result<()> {
+    let args = child_args(std::env::args_os().skip(1));
+    let exe = std::env::current_exe().context("Cannot locate the rtk binary")?;
+    let output = Command::new(exe)
+        .args(&args)
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .output()
+        .context("Failed to rerun rtk")?;
+    let raw = String::from_utf8_lossy(&output.stdout);
+
+    let model = crate::tracking::get_tokenizer();
+    let fitted = fit(raw.trim_end(), budget, model);
+    if verbose > 0 {
+        eprintln!(
+            "Budget {}: {} → {} bytes",
+            budget.describe(),
+            raw.len(),
+            fitted.len()
+        );
+    }
+    println!("{}", fitted);
+
+    let code = output.status.code().unwrap_or(1);
+    if code != 0 {
+        std::process::exit(code);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GREP: &str = "\
+🔍 9 matches in 3F:
+📄 src/a.rs (3):
+     2- use x;
+     3: fn a()
+     4- }
+     9: fn a2()
+📄 src/b.rs (2):
+     1: fn b()
+     5: fn b2()
+📄 src/c.rs (4):
+     7: fn c()
+     8: fn c2()
+     9: fn c3()
+    10: fn c4()";
+
+    #[test]
+    fn test_parse_budget() {
+        assert_eq!("2000".parse(), Ok(Budget::Tokens(2000)));
+        assert_eq!("500tokens".parse(), Ok(Budget::Tokens(500)));
+        assert_eq!("8000b".parse(), Ok(Budget::Bytes(8000)));
+        assert_eq!("8KB".parse(), Ok(Budget::Bytes(8192)));
+        assert!("lots".parse::<Budget>().is_err());
+        assert!("20gb".parse::<Budget>().is_err());
+        assert_eq!(
+            "99999999999999M".parse::<Budget>(),
+            Err("invalid budget '99999999999999M': too large".to_string())
+        );
+    }
+
+    #[test]
+    fn test_fit_keeps_output_within_budget() {
+        assert_eq!(fit(GREP, Budget::Bytes(10_000), TokenModel::Chars), GREP);
+
+        // Room for the first file only
+        let budget = Budget::Bytes(210);
+        let out = fit(GREP, budget, TokenModel::Chars);
+        assert!(out.len() <= 210, "{}", out);
+        assert_eq!(
+            out,
+            "\
+🔍 9 matches in 3F:
+📄 src/a.rs (3):
+     2- use x;
+     3: fn a()
+     4- }
+     9: fn a2()
+✂️  budget 210B: dropped 2 sections"
+        );
+    }
+
+    #[test]
+    fn test_fit_drops_lines_then_context() {
+        let budget = Budget::Bytes(165);
+        let out = fit(GREP, budget, TokenModel::Chars);
+        assert!(out.len() <= 165, "{}", out);
+        assert_eq!(
+            out,
+            "\
+🔍 9 matches in 3F:
+📄 src/a.rs (3):
+     3: fn a()
+✂️  budget 165B: dropped 2 sections, 1 line, 2 context lines"
+        );
+    }
+
+    #[test]
+    fn test_child_args_drop_budget() {
+        let args = ["--budget", "2000", "grep", "fn", "--budget=5kb", "src"].map(OsString::from);
+        assert_eq!(
+            child_args(args.into_iter()),
+            ["grep", "fn", "src"].map(OsString::from)
+        );
+    }
+}
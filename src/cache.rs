@@ -0,0 +1,209 @@
+//! Result cache: output of `rtk json` and `rtk outline` keyed by a hash of
+//! the input content and the options, so reruns on an unchanged file skip
+//! the parsing. Entries live in `~/.local/share/rtk/cache/` (or
+//! `RTK_CACHE_DIR`); the oldest go first past [`MAX_ENTRIES`].
+//!
+//! `--no-cache` (or `RTK_NO_CACHE=1`) bypasses it, `rtk cache clear`
+//! empties it. The rtk version is part of every key, so an upgrade never
+//! serves output from an older release.
+
+use anyhow::{Context, Result};
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::Hasher;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// Entries kept; storing one more removes the least recently written
+pub const MAX_ENTRIES: usize = 500;
+
+const EXTENSION: &str = "out";
+
+/// Cache key of `content` rendered by `command` with `options`
+pub fn key(command: &str, options: &str, content: &[u8]) -> String {
+    let mut hasher = header(command, options);
+    hasher.write(content);
+    finish(hasher, content.len() as u64)
+}
+
+/// Cache key of the content read from `reader`, hashed as it streams
+pub fn key_reader<R: Read>(command: &str, options: &str, mut reader: R) -> io::Result<String> {
+    let mut hasher = header(command, options);
+    let mut buf = vec![0; 64 * 1024];
+    let mut len = 0u64;
+    loop {
+        let n = reader.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.write(&buf[..n]);
+        len += n as u64;
+    }
+    Ok(finish(hasher, len))
+}
+
+fn header(command: &str, options: &str) -> DefaultHasher {
+    let mut hasher = DefaultHasher::new();
+    for part in [env!("CARGO_PKG_VERSION"), command, options] {
+        hasher.write(part.as_bytes());
+        hasher.write_u8(0);
+    }
+    hasher
+}
+
+/// Hash and content length: two inputs must agree on both to share a key
+fn finish(hasher: DefaultHasher, len: u64) -> String {
+    format!("{:016x}-{:x}", hasher.finish(), len)
+}
+
+/// `RTK_NO_CACHE` (also set by `--no-cache`) turns the cache off
+pub fn is_enabled() -> bool {
+    !matches!(std::env::var("RTK_NO_CACHE").ok().as_deref(), Some(v) if !v.is_empty() && v != "0")
+}
+
+fn cache_dir() -> Option<PathBuf> {
+    if let Ok(dir) = std::env::var("RTK_CACHE_DIR") {
+        return Some(PathBuf::from(dir));
+    }
+    dirs::data_local_dir().map(|d| d.join("rtk").join("cache"))
+}
+
+/// Cached output for `key`, if any
+pub fn get(key: &str) -> Option<String> {
+    if !is_enabled() {
+        return None;
+    }
+    fs::read_to_string(entry_path(&cache_dir()?, key)).ok()
+}
+
+/// Store `output` under `key`. Failures are ignored: the cache is only a
+/// shortcut.
+pub fn put(key: &str, output: &str) {
+    if !is_enabled() {
+        return;
+    }
+    if let Some(dir) = cache_dir() {
+        let _ = store(&dir, key, output, MAX_ENTRIES);
+    }
+}
+
+/// Cached output for `key`, or `render`'s, stored for next time
+pub fn get_or_render(key: &str, render: impl FnOnce() -> Result<String>) -> Result<String> {
+    if let Some(output) = get(key) {
+        return Ok(output);
+    }
+    let output = render()?;
+    put(key, &output);
+    Ok(output)
+}
+
+fn entry_path(dir: &Path, key: &str) -> PathBuf {
+    dir.join(format!("{}.{}", key, EXTENSION))
+}
+
+fn store(dir: &Path, key: &str, output: &str, max_entries: usize) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    // Written aside then renamed, so a concurrent reader never sees half
+    let tmp = dir.join(format!("{}.{}.tmp", key, std::process::id()));
+    fs::write(&tmp, output)?;
+    fs::rename(&tmp, entry_path(dir, key))?;
+    evict(dir, max_entries);
+    Ok(())
+}
+
+fn entries(dir: &Path) -> Vec<fs::DirEntry> {
+    fs::read_dir(dir)
+        .ok()
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == EXTENSION))
+        .collect()
+}
+
+/// Remove the oldest entries beyond `max_entries`
+fn evict(dir: &Path, max_entries: usize) {
+    let mut entries: Vec<_> = entries(dir)
+        .into_iter()
+        .map(|e| {
+            let modified = e.metadata().and_then(|m| m.modified()).ok();
+            (modified, e.path())
+        })
+        .collect();
+    if entries.len() <= max_entries {
+        return;
+    }
+    entries.sort();
+    let excess = entries.len() - max_entries;
+    for (_, path) in entries.into_iter().take(excess) {
+        let _ = fs::remove_file(path);
+    }
+}
+
+/// Remove every entry; returns how many there were and their total size
+fn clear(dir: &Path) -> Result<(usize, u64)> {
+    let mut removed = 0;
+    let mut bytes = 0;
+    for entry in entries(dir) {
+        bytes += entry.metadata().map(|m| m.len()).unwrap_or(0);
+        fs::remove_file(entry.path())
+            .with_context(|| format!("Failed to remove {}", entry.path().display()))?;
+        removed += 1;
+    }
+    Ok((removed, bytes))
+}
+
+/// `rtk cache clear`
+pub fn run_clear(verbose: u8) -> Result<()> {
+    let dir = cache_dir().context("No data directory for the cache")?;
+    if verbose > 0 {
+        eprintln!("Clearing cache: {}", dir.display());
+    }
+    let (removed, bytes) = clear(&dir)?;
+    println!(
+        "🗑️  Cleared {} cached result{} ({})",
+        removed,
+        if removed == 1 { "" } else { "s" },
+        crate::utils::format_bytes(bytes)
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_depends_on_everything() {
+        let base = key("json", "d=5", b"{\"a\":1}");
+        assert_eq!(base, key("json", "d=5", b"{\"a\":1}"));
+        assert_ne!(base, key("json", "d=4", b"{\"a\":1}"));
+        assert_ne!(base, key("outline", "d=5", b"{\"a\":1}"));
+        assert_ne!(base, key("json", "d=5", b"{\"a\":2}"));
+        // Separators keep command and options apart
+        assert_ne!(key("ab", "c", b""), key("a", "bc", b""));
+        // Streaming the content gives the same key as hashing it whole
+        let streamed = key_reader("json", "d=5", &b"{\"a\":1}"[..]).unwrap();
+        assert_eq!(base, streamed);
+    }
+
+    #[test]
+    fn test_store_evicts_and_clears() {
+        let dir = tempfile::tempdir().unwrap();
+        for i in 0..4 {
+            store(dir.path(), &format!("k{}", i), &format!("out {}", i), 3).unwrap();
+            // Distinct mtimes, so the eviction order is the write order
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+        assert_eq!(entries(dir.path()).len(), 3);
+        assert!(!entry_path(dir.path(), "k0").exists());
+        assert_eq!(
+            fs::read_to_string(entry_path(dir.path(), "k3")).unwrap(),
+            "out 3"
+        );
+
+        let (removed, bytes) = clear(dir.path()).unwrap();
+        assert_eq!((removed, bytes), (3, 15));
+        assert!(entries(dir.path()).is_empty());
+    }
+}
@@ -1,7 +1,9 @@
+use crate::delta;
 use crate::tracking;
 use crate::utils::truncate;
 use anyhow::{Context, Result};
-use std::collections::HashMap;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
 use std::ffi::OsString;
 use std::process::Command;
 use std::sync::OnceLock;
@@ -27,21 +29,39 @@ pub fn run(cmd: CargoCommand, args: &[String], verbose: u8) -> Result<()> {
     }
 }
 
-/// Generic cargo command runner with filtering
-fn run_cargo_filtered<F>(subcommand: &str, args: &[String], verbose: u8, filter_fn: F) -> Result<()>
+/// Generic cargo command runner with filtering.
+///
+/// With `json_messages`, compiler diagnostics are requested as JSON
+/// (`--message-format json`, unless the caller chose a format) and the
+/// filter sees the JSON lines; tracking and tee get the equivalent
+/// human-readable output.
+fn run_cargo_filtered<F>(
+    subcommand: &str,
+    args: &[String],
+    json_messages: bool,
+    verbose: u8,
+    filter_fn: F,
+) -> Result<()>
 where
     F: Fn(&str) -> String,
 {
     let timer = tracking::TimedExecution::start();
 
+    let json = json_messages && !args.iter().any(|a| a.starts_with("--message-format"));
+    let cargo_args = if json {
+        with_json_message_format(args)
+    } else {
+        args.to_vec()
+    };
+
     let mut cmd = Command::new("cargo");
     cmd.arg(subcommand);
-    for arg in args {
+    for arg in &cargo_args {
         cmd.arg(arg);
     }
 
     if verbose > 0 {
-        eprintln!("Running: cargo {} {}", subcommand, args.join(" "));
+        eprintln!("Running: cargo {} {}", subcommand, cargo_args.join(" "));
     }
 
     let output = cmd
@@ -49,13 +69,24 @@ where
         .with_context(|| format!("Failed to run cargo {}", subcommand))?;
     let stdout = String::from_utf8_lossy(&output.stdout);
     let stderr = String::from_utf8_lossy(&output.stderr);
-    let raw = format!("{}\n{}", stdout, stderr);
+    let combined = format!("{}\n{}", stdout, stderr);
 
     let exit_code = output
         .status
         .code()
         .unwrap_or(if output.status.success() { 0 } else { 1 });
-    let filtered = filter_fn(&raw);
+    let filtered = match delta_keys(subcommand, &combined) {
+        Some(keys) => {
+            let command = format!("cargo {} {}", subcommand, args.join(" "));
+            delta::annotate(filter_fn(&combined), &command, &keys)
+        }
+        None => filter_fn(&combined),
+    };
+    let raw = if json {
+        human_output(&combined)
+    } else {
+        combined
+    };
 
     if let Some(hint) = crate::tee::tee_and_hint(&raw, &format!("cargo_{}", subcommand), exit_code)
     {
@@ -79,27 +110,379 @@ where
 }
 
 fn run_build(args: &[String], verbose: u8) -> Result<()> {
-    run_cargo_filtered("build", args, verbose, filter_cargo_build)
+    run_cargo_filtered("build", args, true, verbose, |raw| {
+        filter_build_messages("build", raw)
+    })
 }
 
 fn run_test(args: &[String], verbose: u8) -> Result<()> {
-    run_cargo_filtered("test", args, verbose, filter_cargo_test)
+    run_cargo_filtered("test", args, true, verbose, filter_test_messages)
 }
 
 fn run_clippy(args: &[String], verbose: u8) -> Result<()> {
-    run_cargo_filtered("clippy", args, verbose, filter_cargo_clippy)
+    run_cargo_filtered("clippy", args, true, verbose, filter_clippy_messages)
 }
 
 fn run_check(args: &[String], verbose: u8) -> Result<()> {
-    run_cargo_filtered("check", args, verbose, filter_cargo_build)
+    run_cargo_filtered("check", args, true, verbose, |raw| {
+        filter_build_messages("check", raw)
+    })
 }
 
 fn run_install(args: &[String], verbose: u8) -> Result<()> {
-    run_cargo_filtered("install", args, verbose, filter_cargo_install)
+    run_cargo_filtered("install", args, false, verbose, filter_cargo_install)
 }
 
 fn run_nextest(args: &[String], verbose: u8) -> Result<()> {
-    run_cargo_filtered("nextest", args, verbose, filter_cargo_nextest)
+    run_cargo_filtered("nextest", args, false, verbose, filter_cargo_nextest)
+}
+
+/// Findings compared with the previous run (see [`delta`]): diagnostics
+/// for build/check/clippy, failing tests for test/nextest. `None` when the
+/// output can't be compared, e.g. tests that didn't compile.
+fn delta_keys(subcommand: &str, output: &str) -> Option<Vec<String>> {
+    match subcommand {
+        "build" | "check" | "clippy" => {
+            let messages = parse_json_messages(output)?;
+            Some(messages.diagnostics.iter().map(diagnostic_key).collect())
+        }
+        "test" => {
+            if parse_json_messages(output).is_some_and(|m| m.count("error") > 0)
+                || !output.contains("test result:")
+            {
+                return None;
+            }
+            Some(
+                output
+                    .lines()
+                    .filter_map(|line| line.strip_prefix("test ")?.strip_suffix(" ... FAILED"))
+                    .map(String::from)
+                    .collect(),
+            )
+        }
+        "nextest" => Some(
+            output
+                .lines()
+                .filter_map(|line| {
+                    let rest = line.trim_start().strip_prefix("FAIL [")?;
+                    Some(rest.split_once("] ")?.1.trim().to_string())
+                })
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+/// File, code and message of a diagnostic; the line is left out since it
+/// moves with unrelated edits
+fn diagnostic_key(diag: &Diagnostic) -> String {
+    let file = diag
+        .location
+        .as_deref()
+        .and_then(|l| l.split(':').next())
+        .unwrap_or("?");
+    let code = diag.code.as_deref().unwrap_or(&diag.level);
+    format!("{}: {}: {}", file, code, diag.message)
+}
+
+/// Add `--message-format json` before any `--` (test binary arguments)
+fn with_json_message_format(args: &[String]) -> Vec<String> {
+    let mut out = args.to_vec();
+    let at = args.iter().position(|a| a == "--").unwrap_or(args.len());
+    out.insert(at, "--message-format=json".to_string());
+    out
+}
+
+/// Compiler diagnostic from a `compiler-message` JSON record
+#[derive(Debug, Clone, PartialEq)]
+struct Diagnostic {
+    level: String,
+    /// Lint or error code: `E0308`, `unused_variables`, `clippy::needless_return`
+    code: Option<String>,
+    message: String,
+    /// Primary span as `file:line:col`, with its label if any
+    location: Option<String>,
+    /// `note:` / `help:` children, one line each
+    notes: Vec<String>,
+}
+
+/// What `--message-format json` output boils down to
+#[derive(Debug, Default)]
+struct JsonMessages {
+    diagnostics: Vec<Diagnostic>,
+    /// `compiler-artifact` records (crates built)
+    artifacts: usize,
+    /// From the `build-finished` record
+    success: Option<bool>,
+    /// Everything that isn't a JSON record (cargo status, test harness output)
+    text: String,
+}
+
+impl JsonMessages {
+    fn count(&self, level: &str) -> usize {
+        self.diagnostics.iter().filter(|d| d.level == level).count()
+    }
+}
+
+/// Split cargo output into JSON records and plain text. Returns `None` when
+/// there are no JSON records (older cargo, or a custom message format).
+fn parse_json_messages(output: &str) -> Option<JsonMessages> {
+    let mut messages = JsonMessages::default();
+    let mut seen = HashSet::new();
+    let mut found = false;
+
+    for line in output.lines() {
+        let record = line
+            .starts_with('{')
+            .then(|| serde_json::from_str::<Value>(line).ok())
+            .flatten();
+        let Some(record) = record else {
+            messages.text.push_str(line);
+            messages.text.push('\n');
+            continue;
+        };
+        found = true;
+
+        match record["reason"].as_str() {
+            Some("compiler-artifact") => messages.artifacts += 1,
+            Some("build-finished") => messages.success = record["success"].as_bool(),
+            Some("compiler-message") => {
+                if let Some(diag) = parse_diagnostic(&record["message"]) {
+                    // The same diagnostic is reported once per target (lib, bin, test)
+                    if seen.insert((
+                        diag.level.clone(),
+                        diag.message.clone(),
+                        diag.location.clone(),
+                    )) {
+                        messages.diagnostics.push(diag);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    found.then_some(messages)
+}
+
+fn parse_diagnostic(message: &Value) -> Option<Diagnostic> {
+    let level = message["level"].as_str()?.to_string();
+    let text = message["message"].as_str()?.to_string();
+    let spans = message["spans"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or(&[]);
+
+    // Summaries like "aborting due to 2 previous errors" carry no span
+    if spans.is_empty()
+        && (text.starts_with("aborting due to")
+            || text.ends_with("warning emitted")
+            || text.ends_with("warnings emitted"))
+    {
+        return None;
+    }
+    if !matches!(level.as_str(), "error" | "warning") {
+        return None;
+    }
+
+    let location = spans
+        .iter()
+        .find(|s| s["is_primary"].as_bool() == Some(true))
+        .or(spans.first())
+        .map(|s| {
+            let location = format!(
+                "{}:{}:{}",
+                s["file_name"].as_str().unwrap_or("?"),
+                s["line_start"],
+                s["column_start"]
+            );
+            match s["label"].as_str() {
+                Some(label) => format!("{} ({})", location, label),
+                None => location,
+            }
+        });
+
+    let notes = message["children"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|child| {
+            let level = child["level"].as_str()?;
+            let text = child["message"].as_str()?;
+            let suggestion = child["spans"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .find_map(|s| s["suggested_replacement"].as_str())
+                .filter(|s| !s.is_empty());
+            Some(match suggestion {
+                Some(replacement) => format!("{}: {}: `{}`", level, text, replacement),
+                None => format!("{}: {}", level, text),
+            })
+        })
+        .collect();
+
+    Some(Diagnostic {
+        level,
+        code: message["code"]["code"].as_str().map(String::from),
+        message: text,
+        location,
+        notes,
+    })
+}
+
+/// Human-readable equivalent of JSON-format output: rendered diagnostics
+/// in place of their records, other records dropped
+fn human_output(output: &str) -> String {
+    let mut out = String::new();
+    for line in output.lines() {
+        if line.starts_with('{') {
+            if let Ok(record) = serde_json::from_str::<Value>(line) {
+                if let Some(rendered) = record["message"]["rendered"].as_str() {
+                    out.push_str(rendered);
+                }
+                continue;
+            }
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    out
+}
+
+/// Errors and warnings with their location and notes. Notes repeated across
+/// diagnostics (`#[warn(...)] on by default`) are printed once.
+fn format_diagnostics(diagnostics: &[Diagnostic], limit: usize) -> String {
+    let mut out = String::new();
+    let mut seen_notes = HashSet::new();
+
+    // Errors first: they're what blocks the build
+    let mut sorted: Vec<&Diagnostic> = diagnostics.iter().collect();
+    sorted.sort_by_key(|d| d.level != "error");
+
+    for diag in sorted.iter().take(limit) {
+        match &diag.code {
+            Some(code) => out.push_str(&format!("{}[{}]: {}\n", diag.level, code, diag.message)),
+            None => out.push_str(&format!("{}: {}\n", diag.level, diag.message)),
+        }
+        if let Some(location) = &diag.location {
+            out.push_str(&format!("  --> {}\n", location));
+        }
+        for note in &diag.notes {
+            if seen_notes.insert(note.as_str()) {
+                out.push_str(&format!("  {}\n", note));
+            }
+        }
+        out.push('\n');
+    }
+
+    if diagnostics.len() > limit {
+        out.push_str(&format!("... +{} more issues\n", diagnostics.len() - limit));
+    }
+    out
+}
+
+/// `cargo build`/`check`: diagnostics from JSON messages, falling back to
+/// the text filter
+fn filter_build_messages(subcommand: &str, output: &str) -> String {
+    let Some(messages) = parse_json_messages(output) else {
+        return filter_cargo_build(output);
+    };
+    format_build_messages(subcommand, &messages)
+}
+
+fn format_build_messages(subcommand: &str, messages: &JsonMessages) -> String {
+    let errors = messages.count("error");
+    let warnings = messages.count("warning");
+    let failed = messages.success == Some(false) || errors > 0;
+
+    if !failed && warnings == 0 {
+        return format!(
+            "✓ cargo {} ({} crates compiled)",
+            subcommand, messages.artifacts
+        );
+    }
+
+    let mut result = format!(
+        "cargo {}: {} errors, {} warnings ({} crates)\n",
+        subcommand, errors, warnings, messages.artifacts
+    );
+    result.push_str("═══════════════════════════════════════\n");
+    result.push_str(&format_diagnostics(&messages.diagnostics, 15));
+    if failed {
+        result.push_str(&format!("✗ cargo {} failed", subcommand));
+    } else {
+        result.push_str(&format!(
+            "✓ cargo {} succeeded with {} warnings",
+            subcommand, warnings
+        ));
+    }
+    result
+}
+
+/// `cargo test`: compile errors from JSON messages, otherwise the test
+/// summary from the harness output
+fn filter_test_messages(output: &str) -> String {
+    match parse_json_messages(output) {
+        Some(messages) if messages.count("error") > 0 => format_build_messages("test", &messages),
+        Some(messages) => filter_cargo_test(&messages.text),
+        None => filter_cargo_test(output),
+    }
+}
+
+/// `cargo clippy`: JSON diagnostics grouped by lint, falling back to the
+/// text filter
+fn filter_clippy_messages(output: &str) -> String {
+    let Some(messages) = parse_json_messages(output) else {
+        return filter_cargo_clippy(output);
+    };
+
+    let errors = messages.count("error");
+    let warnings = messages.count("warning");
+    if errors == 0 && warnings == 0 {
+        return "✓ cargo clippy: No issues found".to_string();
+    }
+
+    let mut result = format!("cargo clippy: {} errors, {} warnings\n", errors, warnings);
+    result.push_str("═══════════════════════════════════════\n");
+
+    // Errors in full, warnings grouped by lint
+    let error_diags: Vec<Diagnostic> = messages
+        .diagnostics
+        .iter()
+        .filter(|d| d.level == "error")
+        .cloned()
+        .collect();
+    result.push_str(&format_diagnostics(&error_diags, 10));
+
+    let mut by_rule: Vec<(String, Vec<&str>)> = Vec::new();
+    for diag in messages.diagnostics.iter().filter(|d| d.level == "warning") {
+        let rule = diag.code.clone().unwrap_or_else(|| diag.message.clone());
+        let location = diag.location.as_deref().unwrap_or("?");
+        match by_rule.iter_mut().find(|(r, _)| *r == rule) {
+            Some((_, locations)) => locations.push(location),
+            None => by_rule.push((rule, vec![location])),
+        }
+    }
+    by_rule.sort_by_key(|(_, locations)| std::cmp::Reverse(locations.len()));
+
+    for (rule, locations) in by_rule.iter().take(15) {
+        result.push_str(&format!("  {} ({}x)\n", rule, locations.len()));
+        for loc in locations.iter().take(3) {
+            result.push_str(&format!("    {}\n", loc));
+        }
+        if locations.len() > 3 {
+            result.push_str(&format!("    ... +{} more\n", locations.len() - 3));
+        }
+    }
+    if by_rule.len() > 15 {
+        result.push_str(&format!("\n... +{} more rules\n", by_rule.len() - 15));
+    }
+
+    if messages.success == Some(false) || errors > 0 {
+        result.push_str("\n✗ cargo clippy failed");
+    }
+    result.trim().to_string()
 }
 
 /// Format crate name + version into a display string
@@ -903,7 +1286,7 @@ fn filter_cargo_clippy(output: &str) -> String {
 
     // Sort rules by frequency
     let mut rule_counts: Vec<_> = by_rule.iter().collect();
-    rule_counts.sort_by(|a, b| b.1.len().cmp(&a.1.len()));
+    rule_counts.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then_with(|| a.0.cmp(b.0)));
 
     for (rule, locations) in rule_counts.iter().take(15) {
         result.push_str(&format!("  {} ({}x)\n", rule, locations.len()));
@@ -949,6 +1332,7 @@ pub fn run_passthrough(args: &[OsString], verbose: u8) -> Result<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::stable::assert_format;
 
     #[test]
     fn test_filter_cargo_build_success() {
@@ -1002,6 +1386,32 @@ test result: ok. 15 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; fin
         assert!(!result.contains("test utils"));
     }
 
+    #[test]
+    fn test_delta_keys() {
+        let test_output = "test foo::test_a ... ok\ntest foo::test_b ... FAILED\n\ntest result: FAILED. 1 passed; 1 failed; 0 ignored\n";
+        assert_eq!(
+            delta_keys("test", test_output),
+            Some(vec!["foo::test_b".to_string()])
+        );
+        // Nothing ran: not comparable
+        assert_eq!(delta_keys("test", "error: could not compile `foo`"), None);
+
+        let nextest = "        FAIL [   0.003s] my-crate tests::test_bad\n";
+        assert_eq!(
+            delta_keys("nextest", nextest),
+            Some(vec!["my-crate tests::test_bad".to_string()])
+        );
+
+        let clippy = r#"{"reason":"compiler-message","message":{"level":"warning","message":"unused variable: `x`","code":{"code":"unused_variables"},"spans":[{"file_name":"src/lib.rs","line_start":3,"column_start":9,"is_primary":true,"label":null}],"children":[]}}"#;
+        assert_eq!(
+            delta_keys("clippy", clippy),
+            Some(vec![
+                "src/lib.rs: unused_variables: unused variable: `x`".to_string()
+            ])
+        );
+        assert_eq!(delta_keys("install", clippy), None);
+    }
+
     #[test]
     fn test_filter_cargo_test_failures() {
         let output = r#"running 5 tests
@@ -1023,6 +1433,7 @@ test result: FAILED. 4 passed; 1 failed; 0 ignored; 0 measured; 0 filtered out
         assert!(result.contains("FAILURES"));
         assert!(result.contains("test_b"));
         assert!(result.contains("test result:"));
+        assert_format!("cargo_test_failures", result);
     }
 
     #[test]
@@ -1203,6 +1614,114 @@ warning: `rtk` (bin) generated 2 warnings
         assert!(result.contains("0 errors, 2 warnings"));
         assert!(result.contains("unused_variables"));
         assert!(result.contains("clippy::too_many_arguments"));
+        assert_format!("cargo_clippy_warnings", result);
+    }
+
+    const JSON_WARNING: &str = r#"{"reason":"compiler-message","message":{"rendered":"warning: unused variable: `x`\n","level":"warning","message":"unused variable: `x`","code":{"code":"unused_variables","explanation":null},"spans":[{"file_name":"src/main.rs","line_start":10,"column_start":9,"is_primary":true,"suggested_replacement":null}],"children":[{"level":"note","message":"`#[warn(unused_variables)]` on by default","spans":[],"children":[]},{"level":"help","message":"if this is intentional, prefix it with an underscore","spans":[{"file_name":"src/main.rs","line_start":10,"column_start":9,"is_primary":true,"suggested_replacement":"_x"}],"children":[]}]}}"#;
+
+    const JSON_ERROR: &str = r#"{"reason":"compiler-message","message":{"rendered":"error[E0308]: mismatched types\n","level":"error","message":"mismatched types","code":{"code":"E0308","explanation":"..."},"spans":[{"file_name":"src/lib.rs","line_start":3,"column_start":5,"is_primary":true,"label":"expected `u32`, found `&str`"}],"children":[]}}"#;
+
+    const JSON_ABORT: &str = r#"{"reason":"compiler-message","message":{"rendered":"error: aborting due to 1 previous error\n","level":"error","message":"aborting due to 1 previous error","code":null,"spans":[],"children":[]}}"#;
+
+    fn json_output(records: &[&str]) -> String {
+        let mut out = String::from("   Compiling rtk v0.5.0\n");
+        for record in records {
+            out.push_str(record);
+            out.push('\n');
+        }
+        out
+    }
+
+    #[test]
+    fn test_with_json_message_format_before_test_args() {
+        let args = vec![
+            "--release".to_string(),
+            "--".to_string(),
+            "--nocapture".to_string(),
+        ];
+        assert_eq!(
+            with_json_message_format(&args),
+            vec!["--release", "--message-format=json", "--", "--nocapture"]
+        );
+    }
+
+    #[test]
+    fn test_filter_build_messages_json() {
+        let artifact = r#"{"reason":"compiler-artifact","target":{"name":"libc"}}"#;
+        let finished = r#"{"reason":"build-finished","success":false}"#;
+        let output = json_output(&[
+            artifact,
+            JSON_WARNING,
+            JSON_WARNING,
+            JSON_ERROR,
+            JSON_ABORT,
+            finished,
+        ]);
+        let result = filter_build_messages("build", &output);
+        assert!(result.starts_with("cargo build: 1 errors, 1 warnings (1 crates)"));
+        // Errors first, duplicates (lib + bin targets) reported once
+        let error_at = result
+            .find("error[E0308]: mismatched types\n  --> src/lib.rs:3:5 (expected `u32`, found `&str`)\n")
+            .unwrap();
+        assert!(error_at < result.find("warning[unused_variables]").unwrap());
+        assert!(result.contains("  --> src/main.rs:10:9\n"));
+        assert!(
+            result.contains("  help: if this is intentional, prefix it with an underscore: `_x`")
+        );
+        assert_eq!(result.matches("on by default").count(), 1);
+        assert!(!result.contains("aborting"));
+        assert!(!result.contains("Compiling"));
+        assert!(result.ends_with("✗ cargo build failed"));
+    }
+
+    #[test]
+    fn test_filter_build_messages_clean_and_fallback() {
+        let output = json_output(&[
+            r#"{"reason":"compiler-artifact","target":{"name":"a"}}"#,
+            r#"{"reason":"compiler-artifact","target":{"name":"b"}}"#,
+            r#"{"reason":"build-finished","success":true}"#,
+        ]);
+        assert_eq!(
+            filter_build_messages("check", &output),
+            "✓ cargo check (2 crates compiled)"
+        );
+        // No JSON records: text filter
+        let text = "   Compiling rtk v0.5.0\n    Finished dev target(s) in 1.0s\n";
+        assert!(filter_build_messages("build", text).contains("1 crates compiled"));
+    }
+
+    #[test]
+    fn test_filter_test_messages_json() {
+        let output = json_output(&[
+            JSON_WARNING,
+            r#"{"reason":"build-finished","success":true}"#,
+            "\nrunning 2 tests\ntest a ... ok\ntest b ... ok\n\ntest result: ok. 2 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.01s",
+        ]);
+        let result = filter_test_messages(&output);
+        assert!(result.contains("✓ cargo test: 2 passed"), "{}", result);
+
+        let output = json_output(&[JSON_ERROR]);
+        assert!(filter_test_messages(&output).contains("error[E0308]"));
+    }
+
+    #[test]
+    fn test_filter_clippy_messages_json() {
+        let output = json_output(&[JSON_WARNING]);
+        let result = filter_clippy_messages(&output);
+        assert!(result.starts_with("cargo clippy: 0 errors, 1 warnings"));
+        assert!(result.contains("  unused_variables (1x)\n    src/main.rs:10:9"));
+    }
+
+    #[test]
+    fn test_human_output_uses_rendered() {
+        let output = json_output(&[
+            JSON_WARNING,
+            r#"{"reason":"build-finished","success":true}"#,
+        ]);
+        assert_eq!(
+            human_output(&output),
+            "   Compiling rtk v0.5.0\nwarning: unused variable: `x`\n"
+        );
     }
 
     #[test]
@@ -0,0 +1,162 @@
+use crate::filter::{self, FilterLevel, Language};
+use crate::redact;
+use crate::tracking;
+use crate::utils::format_bytes;
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::Path;
+
+/// Bytes inspected (NUL bytes, invalid UTF-8) when detecting binary files
+const BINARY_SNIFF_LEN: usize = 8192;
+
+/// Preview a file: first and last `lines` lines with the middle elided,
+/// trailing whitespace stripped. Binary files get a one-line description.
+pub fn run(file: &Path, lines: usize, outline: bool, verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    if verbose > 0 {
+        eprintln!("Previewing: {} ({} head/tail lines)", file.display(), lines);
+    }
+
+    let bytes =
+        fs::read(file).with_context(|| format!("Failed to read file: {}", file.display()))?;
+
+    if is_binary(&bytes) {
+        let msg = format!(
+            "{}: binary {} ({})",
+            file.display(),
+            binary_kind(&bytes),
+            format_bytes(bytes.len() as u64)
+        );
+        println!("{}", msg);
+        timer.track(
+            &format!("cat {}", file.display()),
+            "rtk cat",
+            &String::from_utf8_lossy(&bytes),
+            &msg,
+        );
+        return Ok(());
+    }
+
+    let content = String::from_utf8_lossy(&bytes);
+    let lang = file
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(Language::from_extension)
+        .unwrap_or(Language::Unknown);
+
+    let output = preview(&content, &lang, lines, outline);
+    let output = redact::scrub(&output);
+    println!("{}", output);
+
+    timer.track(
+        &format!("cat {}", file.display()),
+        "rtk cat",
+        &content,
+        &output,
+    );
+    Ok(())
+}
+
+fn preview(content: &str, lang: &Language, lines: usize, outline: bool) -> String {
+    // Outline only makes sense for code we know how to parse
+    let body = if outline && *lang != Language::Unknown {
+        filter::get_filter(FilterLevel::Aggressive).filter(content, lang)
+    } else {
+        content.to_string()
+    };
+
+    let stripped: Vec<&str> = body.lines().map(str::trim_end).collect();
+    elide_middle(&stripped, lines)
+}
+
+/// Keep the first and last `keep` lines, replacing the rest with a marker
+fn elide_middle(lines: &[&str], keep: usize) -> String {
+    if lines.len() <= keep * 2 + 1 {
+        return lines.join("\n");
+    }
+
+    let elided = lines.len() - keep * 2;
+    let mut out: Vec<String> = lines[..keep].iter().map(|l| l.to_string()).collect();
+    out.push(format!("[... {} lines elided ...]", elided));
+    out.extend(lines[lines.len() - keep..].iter().map(|l| l.to_string()));
+    out.join("\n")
+}
+
+fn is_binary(bytes: &[u8]) -> bool {
+    let sniff = &bytes[..bytes.len().min(BINARY_SNIFF_LEN)];
+    // error_len() is None when the window just cuts a multi-byte char in half
+    sniff.contains(&0) || matches!(std::str::from_utf8(sniff), Err(e) if e.error_len().is_some())
+}
+
+fn binary_kind(bytes: &[u8]) -> &'static str {
+    match bytes {
+        [0x89, b'P', b'N', b'G', ..] => "PNG image",
+        [0xFF, 0xD8, 0xFF, ..] => "JPEG image",
+        [b'G', b'I', b'F', b'8', ..] => "GIF image",
+        [b'%', b'P', b'D', b'F', ..] => "PDF document",
+        [b'P', b'K', 0x03, 0x04, ..] => "zip archive",
+        [0x1F, 0x8B, ..] => "gzip archive",
+        [0x7F, b'E', b'L', b'F', ..] => "ELF executable",
+        [0xCF, 0xFA, 0xED, 0xFE, ..] | [0xFE, 0xED, 0xFA, 0xCF, ..] => "Mach-O executable",
+        [b'M', b'Z', ..] => "PE executable",
+        [0x00, b'a', b's', b'm', ..] => "WebAssembly module",
+        [b'S', b'Q', b'L', b'i', b't', b'e', ..] => "SQLite database",
+        _ => "file",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_elide_middle() {
+        let lines: Vec<String> = (1..=100).map(|i| format!("line {}", i)).collect();
+        let refs: Vec<&str> = lines.iter().map(String::as_str).collect();
+        let out = elide_middle(&refs, 3);
+        assert_eq!(
+            out,
+            "line 1\nline 2\nline 3\n[... 94 lines elided ...]\nline 98\nline 99\nline 100"
+        );
+    }
+
+    #[test]
+    fn test_short_file_untouched() {
+        // Eliding a single line would cost more than showing it
+        let out = elide_middle(&["a", "b", "c"], 1);
+        assert_eq!(out, "a\nb\nc");
+    }
+
+    #[test]
+    fn test_preview_strips_trailing_whitespace() {
+        let out = preview(
+            "fn main() {   \n    x();\t\n}\n",
+            &Language::Unknown,
+            10,
+            false,
+        );
+        assert_eq!(out, "fn main() {\n    x();\n}");
+    }
+
+    #[test]
+    fn test_preview_outline() {
+        let code = "use std::fs;\n\nfn main() {\n    let x = 1;\n    println!(\"{}\", x);\n}\n";
+        let out = preview(code, &Language::Rust, 10, true);
+        assert!(out.contains("use std::fs;"));
+        assert!(out.contains("fn main()"));
+        assert!(!out.contains("println!"));
+    }
+
+    #[test]
+    fn test_binary_detection() {
+        assert!(is_binary(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"));
+        assert!(!is_binary("héllo wörld\n".as_bytes()));
+        // Multi-byte char cut at the sniff boundary is still text
+        let mut text = "a".repeat(BINARY_SNIFF_LEN - 1).into_bytes();
+        text.extend_from_slice("é".as_bytes());
+        assert!(!is_binary(&text));
+        assert_eq!(binary_kind(b"\x89PNG\r\n"), "PNG image");
+        assert_eq!(binary_kind(b"\x7fELF\x02"), "ELF executable");
+    }
+}
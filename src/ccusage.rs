@@ -4,6 +4,7 @@
 //! Claude Code API usage metrics. Handles subprocess execution, JSON parsing,
 //! and graceful degradation when ccusage is unavailable.
 
+use crate::utils;
 use anyhow::{Context, Result};
 use serde::Deserialize;
 use std::process::Command;
@@ -84,21 +85,17 @@ struct MonthlyEntry {
 
 /// Check if ccusage binary exists in PATH
 fn binary_exists() -> bool {
-    Command::new("which")
-        .arg("ccusage")
-        .output()
-        .map(|o| o.status.success())
-        .unwrap_or(false)
+    utils::tool_exists("ccusage")
 }
 
 /// Build the ccusage command, falling back to npx if binary not in PATH
 fn build_command() -> Option<Command> {
     if binary_exists() {
-        return Some(Command::new("ccusage"));
+        return Some(utils::tool_command("ccusage"));
     }
 
     // Fallback: try npx
-    let npx_check = Command::new("npx")
+    let npx_check = utils::tool_command("npx")
         .arg("ccusage")
         .arg("--help")
         .stdout(std::process::Stdio::null())
@@ -106,7 +103,7 @@ fn build_command() -> Option<Command> {
         .status();
 
     if npx_check.map(|s| s.success()).unwrap_or(false) {
-        let mut cmd = Command::new("npx");
+        let mut cmd = utils::tool_command("npx");
         cmd.arg("ccusage");
         return Some(cmd);
     }
@@ -0,0 +1,429 @@
+//! `rtk ci`: the latest failing CI run (GitHub Actions, through `gh`),
+//! reduced to the error region of each failing step. Timestamps, log
+//! groups (the step's script and environment) and setup noise (downloads,
+//! installs, compiling dependencies) go; what remains around the error
+//! lines is kept, within [`MAX_REGION_LINES`] per step.
+
+use crate::todo_cmd::age;
+use crate::tracking;
+use crate::utils::{strip_ansi, truncate};
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_json::Value;
+use std::process::Command;
+
+/// Lines kept per failing step
+const MAX_REGION_LINES: usize = 60;
+
+/// Lines kept before and after each error line
+const CONTEXT_BEFORE: usize = 3;
+const CONTEXT_AFTER: usize = 8;
+
+/// Lines kept from the end of a step without a recognizable error
+const TAIL_LINES: usize = 30;
+
+/// Width of one log line
+const LINE_WIDTH: usize = 200;
+
+lazy_static! {
+    static ref TIMESTAMP_RE: Regex =
+        Regex::new(r"^\d{4}-\d\d-\d\dT\d\d:\d\d:\d\d(\.\d+)?Z ?").unwrap();
+    static ref ERROR_RE: Regex = Regex::new(
+        r"(?i)(^##\[error\]|\berror\b|\bfailed\b|\bfailure\b|panicked|exception|traceback|^FAIL\b|✗|exit (code|status) [1-9])"
+    )
+    .unwrap();
+    static ref NOISE_RE: Regex = Regex::new(
+        r"^\s*(Downloading|Downloaded|Compiling|Checking|Fresh|Updating|Installing|Collecting|Requirement already satisfied|Unpacking|Selecting previously|Preparing to unpack|Setting up|Processing triggers|Get:\d|Hit:\d|Fetched|Reading package lists|Building dependency tree|Resolving deltas|Receiving objects|remote: )"
+    )
+    .unwrap();
+}
+
+/// The log lines of one step of one job
+#[derive(Debug, Clone, PartialEq)]
+struct StepLog {
+    job: String,
+    step: String,
+    lines: Vec<String>,
+}
+
+/// A failed job and its first failed step
+#[derive(Debug, Clone, PartialEq)]
+struct FailedJob {
+    name: String,
+    step: Option<String>,
+}
+
+/// Summarize `run` (a run id), else the latest failed run of `branch`:
+/// the current branch by default, any with `all_branches`
+pub fn run(run: Option<&str>, branch: Option<&str>, all_branches: bool, verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    let branch = match branch {
+        Some(branch) => Some(branch.to_string()),
+        None if !all_branches => current_branch(),
+        None => None,
+    };
+    let branch = branch.as_deref();
+    let run_id = match run {
+        Some(id) => id.to_string(),
+        None => match latest_failed_run(branch, verbose)? {
+            Some(id) => id,
+            None => {
+                println!(
+                    "✅ No failed CI run{}",
+                    branch.map(|b| format!(" on {}", b)).unwrap_or_default()
+                );
+                return Ok(());
+            }
+        },
+    };
+
+    let view_raw = gh(
+        &[
+            "run",
+            "view",
+            &run_id,
+            "--json",
+            "databaseId,workflowName,displayTitle,headBranch,event,createdAt,url,conclusion,jobs",
+        ],
+        verbose,
+    )?;
+    let view: Value = serde_json::from_str(&view_raw).context("Failed to parse gh run view")?;
+    let log = gh(&["run", "view", &run_id, "--log-failed"], verbose)?;
+
+    let jobs = failed_jobs(&view);
+    let steps = parse_log(&log);
+    let output = format_run(&view, &jobs, &steps, Utc::now().timestamp());
+    println!("{}", output);
+    timer.track(
+        &format!("gh run view {} --log-failed", run_id),
+        "rtk ci",
+        &log,
+        &output,
+    );
+    Ok(())
+}
+
+fn gh(args: &[&str], verbose: u8) -> Result<String> {
+    if verbose > 0 {
+        eprintln!("Running: gh {}", args.join(" "));
+    }
+    let output = Command::new("gh")
+        .args(args)
+        .output()
+        .context("Failed to run gh (is the GitHub CLI installed?)")?;
+    if !output.status.success() {
+        bail!(
+            "gh {} failed: {}",
+            args[..2].join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn current_branch() -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--abbrev-ref", "HEAD"])
+        .output()
+        .ok()?;
+    let branch = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && branch != "HEAD").then_some(branch)
+}
+
+fn latest_failed_run(branch: Option<&str>, verbose: u8) -> Result<Option<String>> {
+    let mut args = vec![
+        "run",
+        "list",
+        "--status",
+        "failure",
+        "--limit",
+        "1",
+        "--json",
+        "databaseId",
+    ];
+    if let Some(branch) = branch {
+        args.extend(["--branch", branch]);
+    }
+    let list: Value =
+        serde_json::from_str(&gh(&args, verbose)?).context("Failed to parse gh run list")?;
+    Ok(list[0]["databaseId"].as_u64().map(|id| id.to_string()))
+}
+
+/// Failed jobs of `gh run view --json jobs`, with their first failed step
+fn failed_jobs(view: &Value) -> Vec<FailedJob> {
+    view["jobs"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|job| job["conclusion"].as_str() == Some("failure"))
+        .map(|job| FailedJob {
+            name: job["name"].as_str().unwrap_or("?").to_string(),
+            step: job["steps"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .find(|s| s["conclusion"].as_str() == Some("failure"))
+                .and_then(|s| s["name"].as_str())
+                .map(str::to_string),
+        })
+        .collect()
+}
+
+/// `gh run view --log-failed` lines (`job<TAB>step<TAB>timestamp text`)
+/// grouped per step, in order, timestamps and colors removed
+fn parse_log(log: &str) -> Vec<StepLog> {
+    let mut steps: Vec<StepLog> = Vec::new();
+    for line in log.lines() {
+        let mut parts = line.splitn(3, '\t');
+        let (Some(job), Some(step), Some(text)) = (parts.next(), parts.next(), parts.next()) else {
+            continue;
+        };
+        let text = strip_ansi(TIMESTAMP_RE.replace(text, "").trim_end());
+        match steps.last_mut() {
+            Some(last) if last.job == job && last.step == step => last.lines.push(text),
+            _ => steps.push(StepLog {
+                job: job.to_string(),
+                step: step.to_string(),
+                lines: vec![text],
+            }),
+        }
+    }
+    steps
+}
+
+/// Log lines without groups (a step's script and environment), debug
+/// output, setup noise and runs of blank lines
+fn clean(lines: &[String]) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    let mut in_group = false;
+    for line in lines {
+        if line.starts_with("##[group]") {
+            in_group = true;
+            continue;
+        }
+        if line.starts_with("##[endgroup]") {
+            in_group = false;
+            continue;
+        }
+        if in_group || line.starts_with("##[debug]") || NOISE_RE.is_match(line) {
+            continue;
+        }
+        if line.trim().is_empty() && out.last().is_none_or(|l| l.trim().is_empty()) {
+            continue;
+        }
+        let line = line
+            .strip_prefix("##[error]")
+            .map(|l| format!("error: {}", l))
+            .unwrap_or_else(|| line.clone());
+        out.push(line);
+    }
+    out
+}
+
+/// Ranges of `lines` around error lines, merged, the last ones first when
+/// they exceed the budget; the tail without any error line
+fn error_regions(lines: &[String]) -> Vec<(usize, usize)> {
+    let anchors: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| ERROR_RE.is_match(l))
+        .map(|(i, _)| i)
+        .collect();
+    if anchors.is_empty() {
+        return vec![(lines.len().saturating_sub(TAIL_LINES), lines.len())];
+    }
+    let mut windows: Vec<(usize, usize)> = Vec::new();
+    for &anchor in &anchors {
+        let start = anchor.saturating_sub(CONTEXT_BEFORE);
+        let end = (anchor + CONTEXT_AFTER + 1).min(lines.len());
+        match windows.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => windows.push((start, end)),
+        }
+    }
+    // The final error lines tell why the step failed: keep those first
+    let mut kept = Vec::new();
+    let mut budget = MAX_REGION_LINES;
+    for &(start, end) in windows.iter().rev() {
+        if budget == 0 {
+            break;
+        }
+        let start = start.max(end.saturating_sub(budget));
+        budget -= end - start;
+        kept.push((start, end));
+    }
+    kept.reverse();
+    kept
+}
+
+fn format_run(view: &Value, jobs: &[FailedJob], steps: &[StepLog], now: i64) -> String {
+    let created = view["createdAt"]
+        .as_str()
+        .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+        .map(|t| format!(", {} ago", age(now, t.timestamp())))
+        .unwrap_or_default();
+    let total = view["jobs"].as_array().map_or(0, Vec::len);
+    let mut out = vec![
+        format!(
+            "❌ {} #{}: {} on {} ({}{}): {} of {} jobs failed",
+            view["workflowName"].as_str().unwrap_or("CI"),
+            view["databaseId"],
+            truncate(view["displayTitle"].as_str().unwrap_or_default(), 60),
+            view["headBranch"].as_str().unwrap_or("?"),
+            view["event"].as_str().unwrap_or("?"),
+            created,
+            jobs.len(),
+            total
+        ),
+        format!("  {}", view["url"].as_str().unwrap_or_default()),
+    ];
+
+    for job in jobs {
+        let job_steps: Vec<&StepLog> = steps.iter().filter(|s| s.job == job.name).collect();
+        // The failed step's log, else the whole job's
+        let failed: Vec<&StepLog> = match &job.step {
+            Some(step) if job_steps.iter().any(|s| &s.step == step) => job_steps
+                .iter()
+                .copied()
+                .filter(|s| &s.step == step)
+                .collect(),
+            _ => job_steps,
+        };
+        let raw: Vec<String> = failed.iter().flat_map(|s| s.lines.clone()).collect();
+        let lines = clean(&raw);
+        let regions = error_regions(&lines);
+        out.push(String::new());
+        out.push(format!(
+            "📄 {}: step '{}' failed ({} log lines → {})",
+            job.name,
+            job.step.as_deref().unwrap_or("?"),
+            raw.len(),
+            regions
+                .iter()
+                .map(|(start, end)| end - start)
+                .sum::<usize>()
+        ));
+        if lines.is_empty() {
+            out.push("  (no log: gh run view --log-failed)".to_string());
+            continue;
+        }
+        let mut shown_to = 0;
+        for (start, end) in regions {
+            if start > shown_to {
+                out.push(format!("  ⋮ {} lines", start - shown_to));
+            }
+            for line in &lines[start..end] {
+                out.push(format!("  {}", truncate(line, LINE_WIDTH)));
+            }
+            shown_to = end;
+        }
+    }
+    out.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_parse_log() {
+        let log = "test\tSet up job\t2024-05-01T10:00:00.1234567Z Current runner version\n\
+                   test\tRun cargo test\t2024-05-01T10:00:05.0000000Z \x1b[31merror\x1b[0m: oops\n\
+                   test\tRun cargo test\t2024-05-01T10:00:06.0000000Z done\n\
+                   no tabs here\n";
+        assert_eq!(
+            parse_log(log),
+            vec![
+                StepLog {
+                    job: "test".into(),
+                    step: "Set up job".into(),
+                    lines: vec!["Current runner version".into()],
+                },
+                StepLog {
+                    job: "test".into(),
+                    step: "Run cargo test".into(),
+                    lines: vec!["error: oops".into(), "done".into()],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_clean() {
+        let raw = lines(
+            "##[group]Run cargo test\ncargo test --all\nshell: /usr/bin/bash -e {0}\n##[endgroup]\n\
+             \x20  Compiling serde v1.0.0\n\n\n##[debug]internal\nrunning 3 tests\n\
+             ##[error]Process completed with exit code 101.",
+        );
+        assert_eq!(
+            clean(&raw),
+            vec![
+                "running 3 tests",
+                "error: Process completed with exit code 101."
+            ]
+        );
+    }
+
+    #[test]
+    fn test_error_regions() {
+        let mut log: Vec<String> = (0..200).map(|i| format!("line {}", i)).collect();
+        log[50] = "thread 'x' panicked at src/a.rs:3".into();
+        log[199] = "error: exit code 101".into();
+        assert_eq!(error_regions(&log), vec![(47, 59), (196, 200)]);
+        // No error line: the tail
+        let quiet: Vec<String> = (0..100).map(|i| format!("line {}", i)).collect();
+        assert_eq!(error_regions(&quiet), vec![(70, 100)]);
+        // Over budget: the last errors win
+        let noisy: Vec<String> = (0..300).map(|i| format!("error {}", i)).collect();
+        assert_eq!(error_regions(&noisy), vec![(240, 300)]);
+    }
+
+    #[test]
+    fn test_format_run() {
+        let view = json!({
+            "databaseId": 42, "workflowName": "CI", "displayTitle": "Add x",
+            "headBranch": "feat/x", "event": "push", "createdAt": "2024-05-01T10:00:00Z",
+            "url": "https://github.com/o/r/actions/runs/42",
+            "jobs": [
+                { "name": "test", "conclusion": "failure", "steps": [
+                    { "name": "Set up job", "conclusion": "success" },
+                    { "name": "Run cargo test", "conclusion": "failure" }
+                ]},
+                { "name": "lint", "conclusion": "success", "steps": [] }
+            ]
+        });
+        let log = "test\tSet up job\t2024-05-01T10:00:00Z Runner 2.3\n\
+                   test\tRun cargo test\t2024-05-01T10:00:01Z running 1 test\n\
+                   test\tRun cargo test\t2024-05-01T10:00:02Z test a ... FAILED\n\
+                   test\tRun cargo test\t2024-05-01T10:00:03Z ##[error]Process completed with exit code 101.\n";
+        let jobs = failed_jobs(&view);
+        assert_eq!(
+            jobs,
+            vec![FailedJob {
+                name: "test".into(),
+                step: Some("Run cargo test".into())
+            }]
+        );
+        let now = DateTime::parse_from_rfc3339("2024-05-03T10:00:00Z")
+            .unwrap()
+            .timestamp();
+        assert_eq!(
+            format_run(&view, &jobs, &parse_log(log), now),
+            "❌ CI #42: Add x on feat/x (push, 2d ago): 1 of 2 jobs failed\n\
+             \x20 https://github.com/o/r/actions/runs/42\n\
+             \n\
+             📄 test: step 'Run cargo test' failed (3 log lines → 3)\n\
+             \x20 running 1 test\n\
+             \x20 test a ... FAILED\n\
+             \x20 error: Process completed with exit code 101."
+        );
+    }
+}
@@ -0,0 +1,3029 @@
+use crate::{
+    bench_cmd, blame_cmd, branches_cmd, budget, cache, cargo_cmd, cat_cmd, cc_economics, ci_cmd,
+    completions, config, container, count_cmd, csv_cmd, curl_cmd, deps, diff_cmd, discover, du_cmd,
+    env_cmd, explain_cmd, filter, find_cmd, format_cmd, gain, gh_cmd, git, go_cmd, golangci_cmd,
+    grep_cmd, grep_tui, help_cmd, history_cmd, hook_audit_cmd, http_cmd, index, init, json_cmd,
+    json_diff, json_query, json_types, layout, learn, lint_cmd, local_llm, log_cmd, logsum_cmd, ls,
+    mcp, next_cmd, npm_cmd, outline_cmd, output, paths, pip_cmd, pipe_cmd, playwright_cmd, plugin,
+    pnpm_cmd, pr_cmd, prettier_cmd, prisma_cmd, ps_cmd, pytest_cmd, read, recall_cmd, redact,
+    replace_cmd, replay_cmd, ruff_cmd, runner, sample_cmd, slots, stable, stats_cmd, summary,
+    symbols_cmd, tee, test_cmd, todo_cmd, toml_cmd, trace_cmd, tracking, tree_cmd, tsc_cmd, utils,
+    vitest_cmd, watch, wc_cmd, wget_cmd, xml_cmd, yaml_cmd,
+};
+
+use anyhow::{Context, Result};
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use clap_complete::CompleteEnv;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+#[derive(Parser)]
+#[command(
+    name = "rtk",
+    version,
+    about = "Rust Token Killer - Minimize LLM token consumption",
+    long_about = "A high-performance CLI proxy designed to filter and summarize system outputs before they reach your LLM context.",
+    // `rtk help <tool>` condenses other tools' help; `rtk --help` is rtk's own
+    disable_help_subcommand = true
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Commands,
+
+    /// Verbosity level (-v, -vv, -vvv)
+    #[arg(short, long, action = clap::ArgAction::Count, global = true)]
+    verbose: u8,
+
+    /// Ultra-compact mode: ASCII icons, inline format (Level 2 optimizations)
+    #[arg(short = 'u', long, global = true)]
+    ultra_compact: bool,
+
+    /// Set SKIP_ENV_VALIDATION=1 for child processes (Next.js, tsc, lint, prisma)
+    #[arg(long = "skip-env", global = true)]
+    skip_env: bool,
+
+    /// Don't record this command in the savings history (same as RTK_NO_TRACK=1)
+    #[arg(long = "no-track", global = true)]
+    no_track: bool,
+
+    /// Recompute instead of reusing cached results (same as RTK_NO_CACHE=1)
+    #[arg(long = "no-cache", global = true)]
+    no_cache: bool,
+
+    /// Show secrets (API keys, tokens, private keys) instead of masking them
+    /// in the output (same as RTK_NO_REDACT=1); the history stays scrubbed
+    #[arg(long = "no-redact", global = true)]
+    no_redact: bool,
+
+    /// Output format, given before the command: text, json (machine-readable
+    /// output for grep, json), sarif (grep and lint findings, for code
+    /// scanning); defaults to `defaults.format` from the config
+    // Not global: passthroughs (`git log --format`, `docker ps --format`)
+    // need the flag for their tool. Commands with formats of their own take
+    // it after their name too, see `Commands::format`. Validated as an
+    // `OutputFormat` but kept a string, like those of gain and stats export
+    #[arg(long, value_parser = output::OutputFormat::parser())]
+    format: Option<String>,
+
+    /// Color rtk's own output, given before the command: auto (when stdout
+    /// is a terminal), always, never
+    // Not global, like --format: `git diff --color=always` is git's
+    #[arg(long, value_enum, default_value_t = output::ColorChoice::Auto)]
+    color: output::ColorChoice,
+
+    /// Output width in columns (default: the terminal's; fixed defaults when piped)
+    #[arg(long, global = true)]
+    width: Option<usize>,
+
+    /// Hard cap on the output: tokens (2000) or bytes (8kb); later files,
+    /// matches and context are dropped first
+    #[arg(long, global = true, value_name = "TOKENS|BYTES")]
+    budget: Option<budget::Budget>,
+
+    /// Scale every output cap: aggressive (half), balanced, verbose (3x);
+    /// defaults to `defaults.profile` from the config
+    #[arg(long, global = true, value_enum)]
+    profile: Option<config::Profile>,
+
+    /// Path display: repo (root-relative, ~ for home, middle elided),
+    /// full (as printed), short (parent and filename)
+    #[arg(long, global = true, value_enum, default_value_t = paths::PathStyle::Repo)]
+    path_style: paths::PathStyle,
+
+    /// Versioned output for scripts: no colors, fixed widths; with a
+    /// VERSION, fail unless this rtk produces that output format
+    #[arg(long, global = true, value_name = "VERSION", num_args = 0..=1, require_equals = true)]
+    stable_output: Option<Option<u32>>,
+
+    /// Write the full raw output to FILE, print only the summary and where it went
+    #[arg(long, global = true, value_name = "FILE")]
+    out: Option<PathBuf>,
+
+    /// Like --out, into a new file of the tee directory (whatever the exit code and size)
+    #[arg(long, global = true)]
+    tee: bool,
+
+    /// Keep the full raw output under NAME, for slices with `rtk recall NAME`
+    #[arg(long, global = true, value_name = "NAME")]
+    save: Option<String>,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// List directory contents with token-optimized output (proxy to native ls)
+    Ls {
+        /// Arguments passed to ls (supports all native ls flags like -l, -a, -h, -R)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+
+    /// Directory tree with sizes, respecting .gitignore (native tree for other flags)
+    Tree {
+        /// Path and flags: -L <depth>, -d/--dirs-only, -a/--all, --max-entries <n>; other flags go to native tree
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+
+    /// Read file with intelligent filtering
+    Read {
+        /// File to read
+        file: PathBuf,
+        /// Filter: none, minimal, aggressive
+        #[arg(short, long, default_value = "minimal")]
+        level: filter::FilterLevel,
+        /// Max lines
+        #[arg(short, long)]
+        max_lines: Option<usize>,
+        /// Show line numbers
+        #[arg(short = 'n', long)]
+        line_numbers: bool,
+    },
+
+    /// Preview a file: first and last lines with the middle elided
+    Cat {
+        /// File to preview
+        file: PathBuf,
+        /// Lines kept at the start and at the end
+        #[arg(short = 'n', long, default_value = "20")]
+        lines: usize,
+        /// Signatures only for code files (imports, fn/struct/class headers)
+        #[arg(long)]
+        outline: bool,
+    },
+
+    /// Signatures of a source file with line numbers (Rust, TS/JS, Python, Go)
+    Outline {
+        /// Source file to outline
+        file: PathBuf,
+    },
+
+    /// Where a name is defined in the project: kind and file:line, no usages
+    Symbols {
+        /// Name to look up (exact; falls back to case-insensitive partial matches)
+        name: String,
+        /// Directory to index
+        #[arg(default_value = ".")]
+        path: String,
+        /// Only this kind: fn, method, struct, enum, trait, impl, class, interface, type, ...
+        #[arg(short, long)]
+        kind: Option<String>,
+        /// Maximum definitions shown
+        #[arg(short, long, default_value = "50")]
+        max: usize,
+    },
+
+    /// TODO/FIXME/HACK/XXX comments with owner and age (git blame), grouped by file or owner
+    Todo {
+        /// Directory to scan
+        #[arg(default_value = ".")]
+        path: String,
+        /// Group by file (line order) or owner (oldest first)
+        #[arg(long, value_enum, default_value_t = todo_cmd::GroupBy::File)]
+        by: todo_cmd::GroupBy,
+        /// Skip git blame: no owners (unless named in the marker) and no ages
+        #[arg(long)]
+        no_blame: bool,
+        /// Maximum markers shown
+        #[arg(short, long, default_value = "50")]
+        max: usize,
+    },
+
+    /// Commit overview: metadata and changed files with +/- counts, hunks only for matching files
+    Show {
+        /// Commit, tag or any revision
+        #[arg(default_value = "HEAD")]
+        rev: String,
+        /// Show hunks of changed files matching these paths or globs (e.g. src/parser '*.rs')
+        filter: Vec<String>,
+        /// Maximum diff lines
+        #[arg(short = 'n', long, default_value = "100")]
+        max_lines: usize,
+    },
+
+    /// Generate 2-line technical summary (heuristic-based)
+    Smart {
+        /// File to analyze
+        file: PathBuf,
+        /// Model: heuristic
+        #[arg(short, long, default_value = "heuristic")]
+        model: String,
+        /// Force model download
+        #[arg(long)]
+        force_download: bool,
+    },
+
+    /// Git commands with compact output
+    Git {
+        #[command(subcommand)]
+        command: GitCommands,
+    },
+
+    /// GitHub CLI (gh) commands with token-optimized output
+    Gh {
+        /// Subcommand: pr, issue, run, repo
+        subcommand: String,
+        /// Additional arguments
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+
+    /// Latest failed CI run (GitHub Actions): the error region of each failing step
+    Ci {
+        /// Run id (default: the latest failed run)
+        run: Option<String>,
+        /// Branch whose runs to look at (default: the current branch)
+        #[arg(short, long, conflicts_with = "all")]
+        branch: Option<String>,
+        /// Latest failed run of any branch
+        #[arg(long)]
+        all: bool,
+    },
+
+    /// One bounded summary of a pull/merge request: description, CI, reviews, comments, files
+    Pr {
+        /// PR number, URL or branch (default: the current branch's)
+        pr: Option<String>,
+        /// github (gh) or gitlab (glab); detected from the URL or the origin remote
+        #[arg(long, value_enum)]
+        provider: Option<pr_cmd::Provider>,
+    },
+
+    /// pnpm commands with ultra-compact output
+    Pnpm {
+        #[command(subcommand)]
+        command: PnpmCommands,
+    },
+
+    /// Run command and show only errors/warnings
+    Err {
+        /// Command to run
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        command: Vec<String>,
+    },
+
+    /// Run tests and show only failures
+    Test {
+        /// Print the full output of one failed test (name or substring)
+        #[arg(long)]
+        full: Option<String>,
+        /// Rerun on file changes, printing only newly failing tests
+        #[arg(long)]
+        watch: bool,
+        /// Test command (e.g. cargo test)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        command: Vec<String>,
+    },
+
+    /// Synopsis and matching options of a tool, from its --help or man page
+    Help {
+        /// Tool to describe (rtk's own help when omitted)
+        tool: Option<String>,
+        /// Words an option's text must contain, e.g. `rtk help tar extract`
+        query: Vec<String>,
+        /// Read the man page before --help
+        #[arg(long)]
+        man: bool,
+    },
+
+    /// Benchmarks (cargo bench, criterion, hyperfine) compared with the previous run
+    Bench {
+        /// `hyperfine <commands>`, or cargo bench arguments
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+
+    /// Show JSON structure without values
+    #[command(subcommand_negates_reqs = true)]
+    Json {
+        /// JSON file, - for stdin, or an http(s) URL
+        #[arg(required = true)]
+        file: Option<PathBuf>,
+        /// Max depth (default: defaults.max_depth)
+        #[arg(short, long)]
+        depth: Option<usize>,
+        /// Only this subtree: JSON Pointer (/items/3/name) or dotted path (.items[3].name)
+        #[arg(short, long)]
+        path: Option<String>,
+        /// Print the values of the selected subtree instead of its schema
+        #[arg(long)]
+        values: bool,
+        /// Add per-field statistics: distinct strings, number ranges, null rates
+        #[arg(long, conflicts_with = "values")]
+        stats: bool,
+        /// Build the schema while parsing, with bounded memory (automatic for
+        /// files over 64MB and .ndjson/.jsonl)
+        #[arg(long)]
+        stream: bool,
+        /// Output format: text, json, sarif (default: defaults.format)
+        #[arg(long, global = true, value_parser = output::OutputFormat::parser())]
+        format: Option<String>,
+        /// Color rtk's output: auto, always, never (default: as given before the command)
+        #[arg(long, global = true, value_enum)]
+        color: Option<output::ColorChoice>,
+        #[command(subcommand)]
+        command: Option<JsonCommands>,
+    },
+
+    /// Show YAML structure without values (multi-document, aliases and merge keys resolved)
+    #[command(subcommand_negates_reqs = true)]
+    Yaml {
+        /// YAML file (- for stdin)
+        #[arg(required = true)]
+        file: Option<PathBuf>,
+        /// Max depth (default: defaults.max_depth)
+        #[arg(short, long)]
+        depth: Option<usize>,
+        /// Output format: text, json, sarif (default: defaults.format)
+        #[arg(long, value_parser = output::OutputFormat::parser())]
+        format: Option<String>,
+        #[command(subcommand)]
+        command: Option<YamlCommands>,
+    },
+
+    /// Show XML/HTML element tree (tags, attribute names, repeat counts) without text
+    Xml {
+        /// XML or HTML file (- for stdin)
+        file: PathBuf,
+        /// Max depth (default: defaults.max_depth)
+        #[arg(short, long)]
+        depth: Option<usize>,
+        /// Output format: text, json, sarif (default: defaults.format)
+        #[arg(long, value_parser = output::OutputFormat::parser())]
+        format: Option<String>,
+    },
+
+    /// Show TOML structure without values (dependency tables collapsed to counts)
+    Toml {
+        /// TOML file (e.g. Cargo.toml, pyproject.toml)
+        #[arg(default_value = "Cargo.toml")]
+        file: PathBuf,
+        /// Max depth (default: defaults.max_depth)
+        #[arg(short, long)]
+        depth: Option<usize>,
+        /// List dependency names and version requirements
+        #[arg(long)]
+        deps: bool,
+        /// Output format: text, json, sarif (default: defaults.format)
+        #[arg(long, value_parser = output::OutputFormat::parser())]
+        format: Option<String>,
+    },
+
+    /// Summarize CSV/TSV columns: types, null rates, distinct counts, samples
+    Csv {
+        /// CSV or TSV file (- for stdin)
+        file: PathBuf,
+        /// Also preview the first N rows
+        #[arg(short, long, default_value = "0")]
+        rows: usize,
+        /// Field delimiter (auto-detected by default; 'tab' for TSV)
+        #[arg(short, long)]
+        delimiter: Option<String>,
+        /// Output format: text, json, sarif (default: defaults.format)
+        #[arg(long, value_parser = output::OutputFormat::parser())]
+        format: Option<String>,
+    },
+
+    /// First and last records of a big JSON, JSON Lines, CSV or text file (JSON/CSV stay parseable)
+    Sample {
+        /// File to sample (- for stdin)
+        file: PathBuf,
+        /// Records kept at each end
+        #[arg(short = 'n', long, default_value = "5")]
+        records: usize,
+    },
+
+    /// Summarize project dependencies
+    Deps {
+        /// Project path
+        #[arg(default_value = ".")]
+        path: PathBuf,
+    },
+
+    /// Show environment variables grouped by prefix (secrets masked, PATH-like values counted)
+    Env {
+        /// Filter by name (e.g. PATH, AWS)
+        #[arg(short, long)]
+        filter: Option<String>,
+        /// Show all (include sensitive)
+        #[arg(long)]
+        show_all: bool,
+    },
+
+    /// Find files with compact tree output
+    Find {
+        /// Pattern to search (glob)
+        pattern: String,
+        /// Path to search in
+        #[arg(default_value = ".")]
+        path: String,
+        /// Maximum results to show (default: defaults.max_results)
+        #[arg(short, long)]
+        max: Option<usize>,
+        /// Filter by type: f (file), d (directory)
+        #[arg(short = 't', long, default_value = "f")]
+        file_type: String,
+    },
+
+    /// Ultra-condensed diff (only changed lines, or git diff summary when no file given)
+    Diff {
+        /// Two files to compare, - for a unified diff on stdin, or what
+        /// `git diff` takes: revisions, ranges, `-- paths` (flags go first)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+        /// Show changed lines for these files (git diff summary only)
+        #[arg(short, long)]
+        expand: Vec<String>,
+        /// Summarize staged changes (git diff --cached)
+        #[arg(long)]
+        staged: bool,
+        /// Files changed and churn per directory, as a small tree, for the
+        /// same revisions, ranges and paths as git diff
+        #[arg(long, conflicts_with = "expand")]
+        heatmap: bool,
+        /// Output format: text, json, sarif (default: defaults.format)
+        #[arg(long, value_parser = output::OutputFormat::parser())]
+        format: Option<String>,
+    },
+
+    /// Filter and deduplicate log output
+    Log {
+        /// Log file (omit for stdin)
+        file: Option<PathBuf>,
+    },
+
+    /// Summarize a log: line templates with counts, first/last seen, error-rate timeline
+    Logsum {
+        /// Log file (omit for stdin)
+        file: Option<PathBuf>,
+        /// Templates to list
+        #[arg(short = 'n', long, default_value = "15")]
+        top: usize,
+        /// Most buckets in the error-rate timeline
+        #[arg(long, default_value = "12")]
+        buckets: usize,
+    },
+
+    /// Compact stack traces (Java, Python, Rust, Node) to exception and project frames
+    Trace {
+        /// File holding the traces (omit for stdin)
+        file: Option<PathBuf>,
+    },
+
+    /// Docker commands with compact output
+    Docker {
+        #[command(subcommand)]
+        command: DockerCommands,
+    },
+
+    /// Kubectl commands with compact output
+    Kubectl {
+        #[command(subcommand)]
+        command: KubectlCommands,
+    },
+
+    /// Compact any command's output (or stdin): strip ANSI, collapse progress bars,
+    /// count repeated lines, elide the middle
+    #[command(visible_alias = "x")]
+    Pipe {
+        /// Max lines kept (head and tail; errors in between are still listed)
+        #[arg(short = 'n', long, default_value = "60")]
+        max_lines: usize,
+        /// Command to run (omit to read stdin)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        command: Vec<String>,
+    },
+
+    /// Run command and show heuristic summary
+    Summary {
+        /// Command to run and summarize
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        command: Vec<String>,
+    },
+
+    /// Compact grep - strips whitespace, truncates, groups by file
+    Grep {
+        /// Pattern to search (a path when patterns come from -e or --any-of)
+        #[arg(required_unless_present_any = ["patterns", "any_of"])]
+        pattern: Option<String>,
+        /// Path to search in, default . (more paths and globs like 'src/**/*.rs' may follow)
+        path: Option<String>,
+        /// Search several patterns in one pass, repeatable; lines are tagged with the ones they match
+        #[arg(short = 'e', long = "pattern")]
+        patterns: Vec<String>,
+        /// Read patterns from a file, one per line (like -e for each)
+        #[arg(long, value_name = "FILE")]
+        any_of: Option<PathBuf>,
+        /// Max line length (default: defaults.max_line_len)
+        #[arg(short = 'l', long)]
+        max_len: Option<usize>,
+        /// Max results to show (default: defaults.max_results)
+        #[arg(short, long)]
+        max: Option<usize>,
+        /// Show only match context (not full line)
+        #[arg(short, long)]
+        context_only: bool,
+        /// Filter by file type, repeatable (e.g., -t rust -t ts)
+        #[arg(short = 't', long = "type", alias = "file-type")]
+        file_type: Vec<String>,
+        /// Show line numbers (always on, accepted for grep/rg compatibility)
+        #[arg(short = 'n', long)]
+        line_numbers: bool,
+        /// Case-insensitive search (the default for patterns without uppercase letters)
+        #[arg(short = 'i', long)]
+        ignore_case: bool,
+        /// Case-sensitive search even for all-lowercase patterns (no smart case)
+        #[arg(short = 's', long, conflicts_with = "ignore_case")]
+        case_sensitive: bool,
+        /// Match whole words only
+        #[arg(short = 'w', long = "word")]
+        word: bool,
+        /// Treat the pattern as a literal string, not a regex
+        #[arg(short = 'F', long = "fixed-string")]
+        fixed_string: bool,
+        /// Let matches span lines (rg -U); each match is shown as one joined entry
+        #[arg(short = 'U', long)]
+        multiline: bool,
+        /// Use PCRE2 regex syntax: lookaround, backreferences (rg -P)
+        #[arg(short = 'P', long)]
+        pcre2: bool,
+        /// Search hidden files and directories too
+        #[arg(long)]
+        hidden: bool,
+        /// Don't respect .gitignore, .ignore and .rgignore files (node_modules, build output, ...)
+        #[arg(long)]
+        no_ignore: bool,
+        /// Search inside .gz, .tar, .tar.gz and .zip files; results name the member (`logs.zip!app/error.log`)
+        #[arg(short = 'z', long)]
+        search_zip: bool,
+        /// Lines of context after each match
+        #[arg(short = 'A', long, default_value = "0")]
+        after_context: usize,
+        /// Lines of context before each match
+        #[arg(short = 'B', long, default_value = "0")]
+        before_context: usize,
+        /// Lines of context around each match (sets -A and -B)
+        #[arg(short = 'C', long)]
+        context: Option<usize>,
+        /// Group results: file (matching lines per file), dir (counts per directory)
+        /// or symbol (counts per enclosing function/class)
+        #[arg(long, default_value = "file", value_parser = ["file", "dir", "symbol"])]
+        group_by: String,
+        /// File order: relevance (match density, src/ before tests/ and vendor/), count, path, mtime
+        #[arg(long, value_enum, default_value_t = grep_cmd::SortOrder::Path)]
+        sort: grep_cmd::SortOrder,
+        /// Most lines shown per file; the --max budget is shared fairly between files (default: defaults.per_file)
+        #[arg(long)]
+        per_file: Option<usize>,
+        /// Wrap matched text in «» markers (colored when color is on)
+        #[arg(long)]
+        mark: bool,
+        /// Show the column of each match (line:col)
+        #[arg(long)]
+        column: bool,
+        /// Show matches in minified files, lockfiles and sourcemaps instead of a count
+        #[arg(long)]
+        no_collapse: bool,
+        /// List files with the same content (symlinks, vendored copies) separately instead of once
+        #[arg(long)]
+        no_dedup: bool,
+        /// Name the function or class around each match (Rust, TypeScript/JavaScript, Python, Go)
+        #[arg(long)]
+        scope: bool,
+        /// Rerun on file changes, printing only new matches
+        #[arg(long)]
+        watch: bool,
+        /// Browse the matches in a terminal UI: preview with context, copy file:line (y)
+        #[arg(long, conflicts_with = "watch")]
+        tui: bool,
+        /// Output format: text, json, sarif (default: defaults.format)
+        #[arg(long, value_parser = output::OutputFormat::parser())]
+        format: Option<String>,
+        /// Color rtk's output: auto, always, never (default: as given before the command)
+        #[arg(long, value_enum)]
+        color: Option<output::ColorChoice>,
+        /// Extra paths, then ripgrep arguments (e.g., -i, -A 3, -w, -g '!vendor/**')
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        extra_args: Vec<String>,
+    },
+
+    /// Search and replace across files: previews the changes per file, applies them with --write
+    Replace {
+        /// Pattern to replace (regex unless -F)
+        pattern: String,
+        /// Replacement text ($1, ${name} refer to capture groups)
+        replacement: String,
+        /// Files or directories to edit
+        #[arg(default_value = ".")]
+        paths: Vec<String>,
+        /// Write the changes (default: preview only)
+        #[arg(long)]
+        write: bool,
+        /// Only files of this type (rust, py, ts, ... or an extension); repeatable
+        #[arg(short = 't', long = "type")]
+        file_type: Vec<String>,
+        /// Case-insensitive match
+        #[arg(short = 'i', long)]
+        ignore_case: bool,
+        /// Match whole words only
+        #[arg(short = 'w', long = "word")]
+        word: bool,
+        /// Treat pattern and replacement as literal strings
+        #[arg(short = 'F', long = "fixed-string")]
+        fixed_string: bool,
+    },
+
+    /// Initialize rtk instructions in CLAUDE.md
+    Init {
+        /// Add to global ~/.claude/CLAUDE.md instead of local
+        #[arg(short, long)]
+        global: bool,
+
+        /// Show current configuration
+        #[arg(long)]
+        show: bool,
+
+        /// Inject full instructions into CLAUDE.md (legacy mode)
+        #[arg(long = "claude-md", group = "mode")]
+        claude_md: bool,
+
+        /// Hook only, no RTK.md
+        #[arg(long = "hook-only", group = "mode")]
+        hook_only: bool,
+
+        /// Auto-patch settings.json without prompting
+        #[arg(long = "auto-patch", group = "patch")]
+        auto_patch: bool,
+
+        /// Skip settings.json patching (print manual instructions)
+        #[arg(long = "no-patch", group = "patch")]
+        no_patch: bool,
+
+        /// Remove all RTK artifacts (hook, RTK.md, CLAUDE.md reference, settings.json entry)
+        #[arg(long)]
+        uninstall: bool,
+    },
+
+    /// Download with compact output (strips progress bars)
+    Wget {
+        /// URL to download
+        url: String,
+        /// Output to stdout instead of file
+        #[arg(short = 'O', long)]
+        stdout: bool,
+        /// Additional wget arguments
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+
+    /// Top processes by CPU/memory in a compact table (same-executable children folded)
+    Ps {
+        /// Only processes whose command line contains this (case-insensitive)
+        pattern: Option<String>,
+        /// Sort by memory instead of CPU
+        #[arg(short, long)]
+        mem: bool,
+        /// Rows to show
+        #[arg(short = 'n', long, default_value = "15")]
+        limit: usize,
+    },
+
+    /// Disk usage: source vs build artifacts vs .git, largest entries
+    Du {
+        /// Directory to measure
+        #[arg(default_value = ".")]
+        path: PathBuf,
+        /// Largest entries to list
+        #[arg(short = 'n', long, default_value = "10")]
+        limit: usize,
+    },
+
+    /// Lines, words and bytes, code/comment/blank lines per language and files per extension
+    Count {
+        /// Files or directories to count (default: .)
+        paths: Vec<PathBuf>,
+        /// Include gitignored and hidden files
+        #[arg(short, long)]
+        all: bool,
+    },
+
+    /// Word/line/byte count with compact output (strips paths and padding)
+    Wc {
+        /// Arguments passed to wc (files, flags like -l, -w, -c)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+
+    /// Show token savings summary and history
+    Gain {
+        /// Show ASCII graph of daily savings
+        #[arg(short, long)]
+        graph: bool,
+        /// Show recent command history
+        #[arg(short = 'H', long)]
+        history: bool,
+        /// Show monthly quota savings estimate
+        #[arg(short, long)]
+        quota: bool,
+        /// Subscription tier for quota calculation: pro, 5x, 20x
+        #[arg(short, long, default_value = "20x", requires = "quota")]
+        tier: String,
+        /// Show detailed daily breakdown (all days)
+        #[arg(short, long)]
+        daily: bool,
+        /// Show weekly breakdown
+        #[arg(short, long)]
+        weekly: bool,
+        /// Show monthly breakdown
+        #[arg(short, long)]
+        monthly: bool,
+        /// Show all time breakdowns (daily + weekly + monthly)
+        #[arg(short, long)]
+        all: bool,
+        /// Output format: text, json, csv
+        #[arg(short, long, default_value = "text")]
+        format: String,
+        /// Color rtk's output: auto, always, never (default: as given before the command)
+        #[arg(long, value_enum)]
+        color: Option<output::ColorChoice>,
+    },
+
+    /// Per-command raw vs filtered output volume and top offenders
+    Stats {
+        /// Max commands shown in the per-command table
+        #[arg(short, long, default_value = "10")]
+        top: usize,
+        /// Output format: text, json, sarif (default: defaults.format)
+        #[arg(long, value_parser = output::OutputFormat::parser())]
+        format: Option<String>,
+        #[command(subcommand)]
+        command: Option<StatsCommands>,
+    },
+
+    /// Print a shell completion script (e.g. `source <(rtk completions bash)`)
+    Completions {
+        /// Shell to complete in
+        #[arg(value_enum)]
+        shell: completions::Shell,
+    },
+
+    /// Serve rtk tools to agents (Model Context Protocol over stdio)
+    Serve {
+        /// Speak MCP (JSON-RPC on stdin/stdout); the only mode for now
+        #[arg(long)]
+        mcp: bool,
+    },
+
+    /// Re-print the output of a recent tracked command (list them without N)
+    Replay {
+        /// Entry number from the list (1 = most recent)
+        entry: Option<usize>,
+        /// Print the raw command output instead of rtk's filtered output
+        #[arg(long)]
+        raw: bool,
+    },
+
+    /// Print an output saved with --save NAME, or slices of it (list the slots without NAME)
+    Recall {
+        /// Slot name
+        name: Option<String>,
+        /// Only lines mentioning this path, repeatable
+        #[arg(long = "file", value_name = "PATH")]
+        files: Vec<String>,
+        /// Only lines matching this regex
+        #[arg(long, value_name = "REGEX")]
+        grep: Option<String>,
+        /// Only these lines of the slot: 10:20, 10:, :20 or 10
+        #[arg(long, value_name = "RANGE")]
+        lines: Option<recall_cmd::LineRange>,
+        /// Remove the slot
+        #[arg(long, requires = "name")]
+        remove: bool,
+    },
+
+    /// Searchable history of tracked commands with their savings; --rerun N runs one again
+    History {
+        /// Only commands containing this text (in the rtk or the original command line)
+        query: Option<String>,
+        /// Only this subcommand, e.g. git, "git log", grep
+        #[arg(short, long)]
+        command: Option<String>,
+        /// Entries to list
+        #[arg(short = 'n', long, default_value = "20")]
+        limit: usize,
+        /// Run entry N again (numbered as in the list, 1 = most recent)
+        #[arg(long, value_name = "N")]
+        rerun: Option<usize>,
+    },
+
+    /// Show what rtk omitted from a tracked command's output (progress, frames, files)
+    Explain {
+        /// Entry number from `rtk replay` (1 = most recent)
+        #[arg(default_value = "1")]
+        entry: usize,
+    },
+
+    /// Compact old tracking history and cap the database size
+    Gc {
+        /// Keep per-command history for this many days (default: tracking.history_days)
+        #[arg(long)]
+        days: Option<u32>,
+        /// Maximum database size in MB (default: tracking.max_db_mb)
+        #[arg(long)]
+        max_mb: Option<u64>,
+    },
+
+    /// Manage the result cache (json, outline)
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommands,
+    },
+
+    /// Trigram index making repeated rtk grep searches fast on large trees
+    Index {
+        #[command(subcommand)]
+        command: IndexCommands,
+    },
+
+    /// Claude Code economics: spending (ccusage) vs savings (rtk) analysis
+    CcEconomics {
+        /// Show detailed daily breakdown
+        #[arg(short, long)]
+        daily: bool,
+        /// Show weekly breakdown
+        #[arg(short, long)]
+        weekly: bool,
+        /// Show monthly breakdown
+        #[arg(short, long)]
+        monthly: bool,
+        /// Show all time breakdowns (daily + weekly + monthly)
+        #[arg(short, long)]
+        all: bool,
+        /// Output format: text, json, csv
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+
+    /// Show or create configuration file
+    Config {
+        /// Create default config file
+        #[arg(long)]
+        create: bool,
+        #[command(subcommand)]
+        action: Option<ConfigCommands>,
+    },
+
+    /// Vitest commands with compact output
+    Vitest {
+        #[command(subcommand)]
+        command: VitestCommands,
+    },
+
+    /// Prisma commands with compact output (no ASCII art)
+    Prisma {
+        #[command(subcommand)]
+        command: PrismaCommands,
+    },
+
+    /// TypeScript compiler with grouped error output
+    Tsc {
+        /// TypeScript compiler arguments
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+
+    /// Next.js build with compact output
+    Next {
+        /// Next.js build arguments
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+
+    /// Linter diagnostics grouped by rule (eslint, clippy, ruff, pylint, mypy, golangci-lint)
+    Lint {
+        /// Linter name (detected from the project when omitted) and its arguments
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+
+    /// Prettier format checker with compact output
+    Prettier {
+        /// Prettier arguments (e.g., --check, --write)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+
+    /// Universal format checker (prettier, black, ruff format)
+    Format {
+        /// Formatter arguments (auto-detects formatter from project files)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+
+    /// Playwright E2E tests with compact output
+    Playwright {
+        /// Playwright arguments
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+
+    /// Cargo commands with compact output
+    Cargo {
+        #[command(subcommand)]
+        command: CargoCommands,
+    },
+
+    /// npm run with filtered output (strip boilerplate); `ci`/`install` keep only the install summary
+    Npm {
+        /// npm run arguments (script name + options), or ci/install
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+
+    /// npx with intelligent routing (tsc, eslint, prisma -> specialized filters)
+    Npx {
+        /// npx arguments (command + options)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+
+    /// Curl with auto-JSON detection and schema output
+    Curl {
+        /// Curl arguments (URL + options)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+
+    /// HTTP request summary: status, timing, key headers and body schema
+    Http {
+        /// Method (GET, POST, PUT, PATCH, DELETE, HEAD, OPTIONS)
+        method: String,
+        /// Request URL
+        url: String,
+        /// Request body (`@file` reads a file); JSON bodies get a JSON content type
+        #[arg(short, long)]
+        data: Option<String>,
+        /// Extra request header, e.g. -H 'Authorization: Bearer ...' (repeatable)
+        #[arg(short = 'H', long = "header")]
+        headers: Vec<String>,
+        /// Max depth of the body schema (default: defaults.max_depth)
+        #[arg(long)]
+        depth: Option<usize>,
+    },
+
+    /// Discover missed RTK savings from Claude Code history
+    Discover {
+        /// Filter by project path (substring match)
+        #[arg(short, long)]
+        project: Option<String>,
+        /// Max commands per section
+        #[arg(short, long, default_value = "15")]
+        limit: usize,
+        /// Scan all projects (default: current project only)
+        #[arg(short, long)]
+        all: bool,
+        /// Limit to sessions from last N days
+        #[arg(short, long, default_value = "30")]
+        since: u64,
+        /// Output format: text, json
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+
+    /// Learn CLI corrections from Claude Code error history
+    Learn {
+        /// Filter by project path (substring match)
+        #[arg(short, long)]
+        project: Option<String>,
+        /// Scan all projects (default: current project only)
+        #[arg(short, long)]
+        all: bool,
+        /// Limit to sessions from last N days
+        #[arg(short, long, default_value = "30")]
+        since: u64,
+        /// Output format: text, json
+        #[arg(short, long, default_value = "text")]
+        format: String,
+        /// Generate .claude/rules/cli-corrections.md file
+        #[arg(short, long)]
+        write_rules: bool,
+        /// Minimum confidence threshold (0.0-1.0)
+        #[arg(long, default_value = "0.6")]
+        min_confidence: f64,
+        /// Minimum occurrences to include in report
+        #[arg(long, default_value = "1")]
+        min_occurrences: usize,
+    },
+
+    /// Execute command without filtering but track usage
+    Proxy {
+        /// Command and arguments to execute
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<OsString>,
+    },
+
+    /// Ruff linter/formatter with compact output
+    Ruff {
+        /// Ruff arguments (e.g., check, format --check)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+
+    /// Pytest test runner with compact output
+    Pytest {
+        /// Pytest arguments
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+
+    /// Pip package manager with compact output (auto-detects uv)
+    Pip {
+        /// Pip arguments (e.g., list, outdated, install)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+
+    /// Go commands with compact output
+    Go {
+        #[command(subcommand)]
+        command: GoCommands,
+    },
+
+    /// golangci-lint with compact output
+    #[command(name = "golangci-lint")]
+    GolangciLint {
+        /// golangci-lint arguments
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+
+    /// Show hook rewrite audit metrics (requires RTK_HOOK_AUDIT=1)
+    #[command(name = "hook-audit")]
+    HookAudit {
+        /// Show entries from last N days (0 = all time)
+        #[arg(short, long, default_value = "7")]
+        since: u64,
+    },
+}
+
+#[derive(Subcommand)]
+enum GitCommands {
+    /// Condensed diff output
+    Diff {
+        /// Git arguments (supports all git diff flags like --stat, --cached, etc)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// One-line commit history (hash, author initials, age, subject)
+    Log {
+        /// Git arguments (supports all git log flags like --oneline, --graph, --all, plus --graph-lite)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Compact status (supports all git status flags)
+    Status {
+        /// Git arguments (supports all git status flags like --porcelain, --short, -s)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Compact show (commit summary + stat + compacted diff)
+    Show {
+        /// Git arguments (supports all git show flags)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Add files → "ok ✓"
+    Add {
+        /// Files and flags to add (supports all git add flags like -A, -p, --all, etc)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Commit → "ok ✓ \<hash\>"
+    Commit {
+        /// Commit message (can be repeated for multi-paragraph)
+        #[arg(short, long)]
+        message: Vec<String>,
+    },
+    /// Push → "ok ✓ \<branch\>"
+    Push {
+        /// Git push arguments (supports -u, remote, branch, etc.)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Pull → "ok ✓ \<stats\>"
+    Pull {
+        /// Git pull arguments (supports --rebase, remote, branch, etc.)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Compact branch listing (current/local/remote)
+    Branch {
+        /// Git branch arguments (supports -d, -D, -m, etc.)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Local branches with upstream drift, age and merged status, plus the stash
+    Branches,
+    /// Fetch → "ok fetched (N new refs)"
+    Fetch {
+        /// Git fetch arguments
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Stash management (list, show, pop, apply, drop)
+    Stash {
+        /// Subcommand: list, show, pop, apply, drop, push
+        subcommand: Option<String>,
+        /// Additional arguments
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Ownership of a file or line range: per author, per commit and per region
+    Blame {
+        /// File, then git blame arguments (-L 10,40, a revision)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Compact worktree listing
+    Worktree {
+        /// Git worktree arguments (add, remove, prune, or empty for list)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Passthrough: runs any unsupported git subcommand directly
+    #[command(external_subcommand)]
+    Other(Vec<OsString>),
+}
+
+#[derive(Subcommand)]
+enum StatsCommands {
+    /// Export the tracking history for dashboards or an OpenTelemetry collector
+    Export {
+        /// Output format: csv, json (one row per record), otlp (OTLP/JSON metrics)
+        #[arg(short, long, default_value = "json")]
+        format: String,
+        /// Only records of the last N days
+        #[arg(long)]
+        since: Option<i64>,
+        /// POST the OTLP metrics to this collector (e.g. http://localhost:4318)
+        #[arg(long)]
+        endpoint: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum CacheCommands {
+    /// Remove every cached result
+    Clear,
+}
+
+#[derive(Subcommand)]
+enum IndexCommands {
+    /// Index the files under PATH (default: the repository root)
+    Build {
+        /// Directory to index
+        path: Option<String>,
+    },
+    /// Show the index covering PATH and how much changed since it was built
+    Status {
+        /// Directory to check
+        path: Option<String>,
+    },
+    /// Remove the index built for PATH
+    Clear {
+        /// Indexed directory
+        path: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum JsonCommands {
+    /// Structural diff of two documents: keys added, removed, changed
+    Diff {
+        /// Old document: file, - for stdin, or an http(s) URL
+        old: String,
+        /// New document: file, - for stdin, or an http(s) URL
+        new: String,
+        /// Compare inferred schemas only: new/missing keys and type changes
+        #[arg(long)]
+        schema_only: bool,
+    },
+    /// Extract values with a jq subset: paths, .[], |, select(...), {a, b: .c}
+    Query {
+        /// Filter, e.g. '.users[] | select(.age > 30) | .email'
+        query: String,
+        /// JSON or NDJSON file, - for stdin, or an http(s) URL
+        #[arg(default_value = "-")]
+        file: String,
+        /// Max results to show (default: defaults.max_results)
+        #[arg(short, long)]
+        max: Option<usize>,
+        /// Max line length (default: defaults.max_line_len)
+        #[arg(long)]
+        max_len: Option<usize>,
+    },
+    /// Inferred schema of a document, as JSON Schema (draft 2020-12) by default
+    Schema {
+        /// JSON or NDJSON file, - for stdin, or an http(s) URL
+        file: String,
+        /// Output: json-schema, json (rtk's schema), text
+        #[arg(short, long, default_value = "json-schema")]
+        output: String,
+    },
+    /// Rust structs (serde) or TypeScript interfaces for a document
+    Types {
+        /// JSON file, - for stdin, or an http(s) URL
+        file: String,
+        /// Target language: rust, ts
+        #[arg(short, long, value_enum, default_value_t = json_types::Lang::Rust)]
+        lang: json_types::Lang,
+        /// Name of the root type
+        #[arg(short, long, default_value = "Root")]
+        name: String,
+    },
+    /// Convert to YAML
+    ToYaml {
+        /// JSON file, - for stdin, or an http(s) URL
+        file: String,
+        /// One flow-style line instead of blocks
+        #[arg(long)]
+        minify: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum YamlCommands {
+    /// Convert to JSON (several documents become an array)
+    ToJson {
+        /// YAML file, - for stdin, or an http(s) URL
+        file: String,
+        /// Compact JSON on one line
+        #[arg(long)]
+        minify: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum PnpmCommands {
+    /// List installed packages (ultra-dense)
+    List {
+        /// Depth level (default: 0)
+        #[arg(short, long, default_value = "0")]
+        depth: usize,
+        /// Additional pnpm arguments
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Show outdated packages (condensed: "pkg: old → new")
+    Outdated {
+        /// Additional pnpm arguments
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Install packages (counts, peer warnings, audit and errors only)
+    Install {
+        /// Packages to install
+        packages: Vec<String>,
+        /// Additional pnpm arguments, after `--`
+        #[arg(last = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Build (delegates to next build filter)
+    Build {
+        /// Additional build arguments
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Typecheck (delegates to tsc filter)
+    Typecheck {
+        /// Additional typecheck arguments
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Passthrough: runs any unsupported pnpm subcommand directly
+    #[command(external_subcommand)]
+    Other(Vec<OsString>),
+}
+
+#[derive(Subcommand)]
+enum DockerCommands {
+    /// List containers (aligned table, state and size rollup)
+    Ps {
+        /// docker ps arguments (e.g. -a, -s)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// List images (one row per repository, size rollup)
+    Images {
+        /// docker images arguments (e.g. -a)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Build an image (per-stage timing and cache hits, errors verbatim)
+    Build {
+        /// docker build arguments (e.g. -t app .)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Show container logs (deduplicated)
+    Logs { container: String },
+    /// Docker Compose commands with compact output
+    Compose {
+        #[command(subcommand)]
+        command: ComposeCommands,
+    },
+    /// Passthrough: runs any unsupported docker subcommand directly
+    #[command(external_subcommand)]
+    Other(Vec<OsString>),
+}
+
+#[derive(Subcommand)]
+enum ConfigCommands {
+    /// Print an effective value (global config + .rtk.toml), e.g. defaults.max_depth
+    Get { key: String },
+    /// Set a value in the global config, or in ./.rtk.toml with --project
+    Set {
+        key: String,
+        value: String,
+        /// Write to ./.rtk.toml instead of the global config
+        #[arg(long)]
+        project: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ComposeCommands {
+    /// List compose services (compact)
+    Ps,
+    /// Show compose logs (deduplicated)
+    Logs {
+        /// Optional service name
+        service: Option<String>,
+    },
+    /// Build compose services (summary)
+    Build {
+        /// Optional service name
+        service: Option<String>,
+    },
+    /// Passthrough: runs any unsupported compose subcommand directly
+    #[command(external_subcommand)]
+    Other(Vec<OsString>),
+}
+
+#[derive(Subcommand)]
+enum KubectlCommands {
+    /// List pods grouped by status (Running 14, CrashLoopBackOff 2 with names)
+    Pods {
+        #[arg(short, long)]
+        namespace: Option<String>,
+        /// All namespaces
+        #[arg(short = 'A', long)]
+        all: bool,
+    },
+    /// List services
+    Services {
+        #[arg(short, long)]
+        namespace: Option<String>,
+        /// All namespaces
+        #[arg(short = 'A', long)]
+        all: bool,
+    },
+    /// Show pod logs (deduplicated)
+    Logs {
+        pod: String,
+        #[arg(short, long)]
+        container: Option<String>,
+    },
+    /// kubectl get; pods are grouped by status, other resources pass through
+    Get {
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// kubectl describe without volume/toleration noise, last distinct events only
+    Describe {
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Passthrough: runs any unsupported kubectl subcommand directly
+    #[command(external_subcommand)]
+    Other(Vec<OsString>),
+}
+
+#[derive(Subcommand)]
+enum VitestCommands {
+    /// Run tests with filtered output (90% token reduction)
+    Run {
+        /// Additional vitest arguments
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum PrismaCommands {
+    /// Generate Prisma Client (strip ASCII art)
+    Generate {
+        /// Additional prisma arguments
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Manage migrations
+    Migrate {
+        #[command(subcommand)]
+        command: PrismaMigrateCommands,
+    },
+    /// Push schema to database
+    DbPush {
+        /// Additional prisma arguments
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum PrismaMigrateCommands {
+    /// Create and apply migration
+    Dev {
+        /// Migration name
+        #[arg(short, long)]
+        name: Option<String>,
+        /// Additional arguments
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Check migration status
+    Status {
+        /// Additional arguments
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Deploy migrations to production
+    Deploy {
+        /// Additional arguments
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum CargoCommands {
+    /// Build with compact output (strip Compiling lines, keep errors)
+    Build {
+        /// Additional cargo build arguments
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Test with failures-only output
+    Test {
+        /// Additional cargo test arguments
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Clippy with warnings grouped by lint rule
+    Clippy {
+        /// Additional cargo clippy arguments
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Check with compact output (strip Checking lines, keep errors)
+    Check {
+        /// Additional cargo check arguments
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Install with compact output (strip dep compilation, keep installed/errors)
+    Install {
+        /// Additional cargo install arguments
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Nextest with failures-only output
+    Nextest {
+        /// Additional cargo nextest arguments (e.g., run, list, --lib)
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Passthrough: runs any unsupported cargo subcommand directly
+    #[command(external_subcommand)]
+    Other(Vec<OsString>),
+}
+
+#[derive(Subcommand)]
+enum GoCommands {
+    /// Run tests with compact output (90% token reduction via JSON streaming)
+    Test {
+        /// Additional go test arguments
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Build with compact output (errors only)
+    Build {
+        /// Additional go build arguments
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Vet with compact output
+    Vet {
+        /// Additional go vet arguments
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+    /// Passthrough: runs any unsupported go subcommand directly
+    #[command(external_subcommand)]
+    Other(Vec<OsString>),
+}
+
+impl Commands {
+    /// Commands whose output is the data itself (converted, sampled or read
+    /// back): read, cat and sample scrub the text they build, conversions
+    /// print it as is, instead of having their stdout rewritten by
+    /// `redact::install`
+    fn outputs_data(&self) -> bool {
+        matches!(
+            self,
+            Commands::Read { .. }
+                | Commands::Cat { .. }
+                | Commands::Sample { .. }
+                | Commands::Json {
+                    command: Some(JsonCommands::ToYaml { .. }),
+                    ..
+                }
+                | Commands::Yaml {
+                    command: Some(YamlCommands::ToJson { .. }),
+                    ..
+                }
+        )
+    }
+}
+
+impl Commands {
+    /// `--format` given after the command name, for commands with
+    /// machine-readable output
+    fn format(&self) -> Option<&str> {
+        match self {
+            Commands::Grep { format, .. }
+            | Commands::Json { format, .. }
+            | Commands::Yaml { format, .. }
+            | Commands::Xml { format, .. }
+            | Commands::Toml { format, .. }
+            | Commands::Csv { format, .. }
+            | Commands::Diff { format, .. }
+            | Commands::Stats { format, .. } => format.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// `--color` given after the command name, for commands that color
+    /// their output
+    fn color(&self) -> Option<output::ColorChoice> {
+        match self {
+            Commands::Grep { color, .. }
+            | Commands::Json { color, .. }
+            | Commands::Gain { color, .. } => *color,
+            _ => None,
+        }
+    }
+}
+
+/// The `rtk` command line, run by the binary
+pub fn run() -> Result<()> {
+    // Answers `COMPLETE=<shell> rtk -- ...` from the completion scripts
+    CompleteEnv::with_factory(|| completions::with_candidates(Cli::command()))
+        .var(completions::VAR)
+        .complete();
+
+    let cli = match Cli::try_parse() {
+        Ok(cli) => cli,
+        Err(e) if e.kind() == clap::error::ErrorKind::InvalidSubcommand => {
+            let args: Vec<OsString> = std::env::args_os().skip(1).collect();
+            redact::install();
+            match plugin::dispatch(&args) {
+                Some(result) => return result,
+                None => e.exit(),
+            }
+        }
+        Err(e) => e.exit(),
+    };
+    stable::set(cli.stable_output)?;
+    output::set_color(cli.command.color().unwrap_or(cli.color));
+    layout::set_width(cli.width);
+    paths::set_style(cli.path_style);
+    if cli.no_track {
+        // Read by tracking, and inherited by nested rtk invocations
+        std::env::set_var("RTK_NO_TRACK", "1");
+    }
+    if cli.no_cache {
+        std::env::set_var("RTK_NO_CACHE", "1");
+    }
+    if cli.no_redact {
+        std::env::set_var("RTK_NO_REDACT", "1");
+    }
+    // After colors and width are resolved: they look at the real stdout
+    if !cli.command.outputs_data() {
+        redact::install();
+    }
+    if matches!(
+        cli.command,
+        Commands::Grep { watch: true, .. } | Commands::Test { watch: true, .. }
+    ) {
+        return watch::run(cli.verbose);
+    }
+    if let Some(limit) = cli.budget {
+        return budget::run(limit, cli.verbose);
+    }
+    let defaults = config::Config::get().defaults.clone();
+    let profile = cli.profile.unwrap_or(defaults.profile);
+    let defaults = defaults.with_profile(profile);
+    let output_format = cli
+        .format
+        .as_deref()
+        .or(cli.command.format())
+        .and_then(|f| output::OutputFormat::from_str(f, true).ok())
+        .unwrap_or(defaults.format);
+    let format = output_format.as_str();
+    // Hints must not break a machine-readable document on stdout
+    let machine_readable = output_format.machine_readable();
+    tee::request(cli.out.clone(), cli.tee, machine_readable);
+    slots::request(cli.save.clone(), machine_readable)?;
+
+    match cli.command {
+        Commands::Ls { args } => {
+            ls::run(&args, cli.verbose)?;
+        }
+
+        Commands::Tree { args } => {
+            tree_cmd::run(&args, cli.verbose)?;
+        }
+
+        Commands::Read {
+            file,
+            level,
+            max_lines,
+            line_numbers,
+        } => {
+            if file == Path::new("-") {
+                read::run_stdin(level, max_lines, line_numbers, cli.verbose)?;
+            } else {
+                read::run(&file, level, max_lines, line_numbers, cli.verbose)?;
+            }
+        }
+
+        Commands::Cat {
+            file,
+            lines,
+            outline,
+        } => {
+            cat_cmd::run(&file, lines, outline, cli.verbose)?;
+        }
+
+        Commands::Todo {
+            path,
+            by,
+            no_blame,
+            max,
+        } => {
+            todo_cmd::run(&path, by, !no_blame, max, cli.verbose)?;
+        }
+
+        Commands::Show {
+            rev,
+            filter,
+            max_lines,
+        } => {
+            git::run_show_commit(&rev, &filter, max_lines, cli.verbose)?;
+        }
+
+        Commands::Outline { file } => {
+            outline_cmd::run(&file, cli.verbose)?;
+        }
+
+        Commands::Symbols {
+            name,
+            path,
+            kind,
+            max,
+        } => {
+            symbols_cmd::run(&name, &path, kind.as_deref(), max, cli.verbose)?;
+        }
+
+        Commands::Smart {
+            file,
+            model,
+            force_download,
+        } => {
+            local_llm::run(&file, &model, force_download, cli.verbose)?;
+        }
+
+        Commands::Git { command } => match command {
+            GitCommands::Diff { args } => {
+                git::run(git::GitCommand::Diff, &args, None, cli.verbose)?;
+            }
+            GitCommands::Log { args } => {
+                git::run(git::GitCommand::Log, &args, None, cli.verbose)?;
+            }
+            GitCommands::Status { args } => {
+                git::run(git::GitCommand::Status, &args, None, cli.verbose)?;
+            }
+            GitCommands::Show { args } => {
+                git::run(git::GitCommand::Show, &args, None, cli.verbose)?;
+            }
+            GitCommands::Add { args } => {
+                git::run(git::GitCommand::Add, &args, None, cli.verbose)?;
+            }
+            GitCommands::Commit { message } => {
+                git::run(
+                    git::GitCommand::Commit { messages: message },
+                    &[],
+                    None,
+                    cli.verbose,
+                )?;
+            }
+            GitCommands::Push { args } => {
+                git::run(git::GitCommand::Push, &args, None, cli.verbose)?;
+            }
+            GitCommands::Pull { args } => {
+                git::run(git::GitCommand::Pull, &args, None, cli.verbose)?;
+            }
+            GitCommands::Branch { args } => {
+                git::run(git::GitCommand::Branch, &args, None, cli.verbose)?;
+            }
+            GitCommands::Fetch { args } => {
+                git::run(git::GitCommand::Fetch, &args, None, cli.verbose)?;
+            }
+            GitCommands::Stash { subcommand, args } => {
+                git::run(
+                    git::GitCommand::Stash { subcommand },
+                    &args,
+                    None,
+                    cli.verbose,
+                )?;
+            }
+            GitCommands::Branches => {
+                branches_cmd::run(cli.verbose)?;
+            }
+            GitCommands::Blame { args } => {
+                blame_cmd::run(&args, cli.verbose)?;
+            }
+            GitCommands::Worktree { args } => {
+                git::run(git::GitCommand::Worktree, &args, None, cli.verbose)?;
+            }
+            GitCommands::Other(args) => {
+                git::run_passthrough(&args, cli.verbose)?;
+            }
+        },
+
+        Commands::Gh { subcommand, args } => {
+            gh_cmd::run(&subcommand, &args, cli.verbose, cli.ultra_compact)?;
+        }
+
+        Commands::Ci { run, branch, all } => {
+            ci_cmd::run(run.as_deref(), branch.as_deref(), all, cli.verbose)?;
+        }
+
+        Commands::Pr { pr, provider } => {
+            pr_cmd::run(pr.as_deref(), provider, cli.verbose)?;
+        }
+
+        Commands::Pnpm { command } => match command {
+            PnpmCommands::List { depth, args } => {
+                pnpm_cmd::run(pnpm_cmd::PnpmCommand::List { depth }, &args, cli.verbose)?;
+            }
+            PnpmCommands::Outdated { args } => {
+                pnpm_cmd::run(pnpm_cmd::PnpmCommand::Outdated, &args, cli.verbose)?;
+            }
+            PnpmCommands::Install { packages, args } => {
+                pnpm_cmd::run(
+                    pnpm_cmd::PnpmCommand::Install { packages },
+                    &args,
+                    cli.verbose,
+                )?;
+            }
+            PnpmCommands::Build { args } => {
+                next_cmd::run(&args, cli.verbose)?;
+            }
+            PnpmCommands::Typecheck { args } => {
+                tsc_cmd::run(&args, cli.verbose)?;
+            }
+            PnpmCommands::Other(args) => {
+                pnpm_cmd::run_passthrough(&args, cli.verbose)?;
+            }
+        },
+
+        Commands::Err { command } => {
+            let cmd = command.join(" ");
+            runner::run_err(&cmd, cli.verbose)?;
+        }
+
+        Commands::Test { full, command, .. } => {
+            let cmd = command.join(" ");
+            test_cmd::run(&cmd, full.as_deref(), cli.verbose)?;
+        }
+
+        Commands::Help { tool, query, man } => match tool {
+            Some(tool) => help_cmd::run(&tool, &query, man, cli.verbose)?,
+            None => Cli::command().print_help()?,
+        },
+
+        Commands::Bench { args } => {
+            bench_cmd::run(&args, cli.verbose)?;
+        }
+
+        Commands::Json {
+            command:
+                Some(JsonCommands::Diff {
+                    old,
+                    new,
+                    schema_only,
+                }),
+            depth,
+            ..
+        } => {
+            let depth = depth.unwrap_or(defaults.max_depth);
+            json_diff::run(&old, &new, schema_only, depth, format, cli.verbose)?;
+        }
+
+        Commands::Json {
+            command: Some(JsonCommands::ToYaml { file, minify }),
+            ..
+        } => {
+            yaml_cmd::run_from_json(&file, minify, cli.verbose)?;
+        }
+
+        Commands::Json {
+            command:
+                Some(JsonCommands::Query {
+                    query,
+                    file,
+                    max,
+                    max_len,
+                }),
+            ..
+        } => {
+            let max = max.unwrap_or(defaults.max_results);
+            let max_len = max_len.unwrap_or(layout::line_width(defaults.max_line_len, 0));
+            json_query::run(&query, &file, max, max_len, format, cli.verbose)?;
+        }
+
+        Commands::Json {
+            command: Some(JsonCommands::Schema { file, output }),
+            depth,
+            ..
+        } => {
+            let depth = depth.unwrap_or(defaults.max_depth);
+            json_cmd::run_schema(&file, depth, &output, cli.verbose)?;
+        }
+
+        Commands::Json {
+            command: Some(JsonCommands::Types { file, lang, name }),
+            depth,
+            ..
+        } => {
+            let depth = depth.unwrap_or(defaults.max_depth);
+            json_types::run(&file, lang, &name, depth, cli.verbose)?;
+        }
+
+        Commands::Json {
+            file,
+            depth,
+            path,
+            values,
+            stats,
+            stream,
+            format: _,
+            color: _,
+            command: None,
+        } => {
+            // Required unless a subcommand is given
+            let file = file.unwrap_or_default();
+            let depth = depth.unwrap_or(defaults.max_depth);
+            if file == Path::new("-") {
+                json_cmd::run_stdin(
+                    depth,
+                    path.as_deref(),
+                    values,
+                    stats,
+                    stream,
+                    format,
+                    cli.verbose,
+                )?;
+            } else if json_cmd::is_url(&file.to_string_lossy()) {
+                json_cmd::run_url(
+                    &file.to_string_lossy(),
+                    depth,
+                    path.as_deref(),
+                    values,
+                    stats,
+                    format,
+                    cli.verbose,
+                )?;
+            } else {
+                json_cmd::run(
+                    &file,
+                    depth,
+                    path.as_deref(),
+                    values,
+                    stats,
+                    stream,
+                    format,
+                    cli.verbose,
+                )?;
+            }
+        }
+
+        Commands::Yaml {
+            command: Some(YamlCommands::ToJson { file, minify }),
+            ..
+        } => {
+            yaml_cmd::run_to_json(&file, minify, cli.verbose)?;
+        }
+
+        Commands::Yaml {
+            file,
+            depth,
+            command: None,
+            ..
+        } => {
+            // Required unless a subcommand is given
+            let file = file.unwrap_or_default();
+            let depth = depth.unwrap_or(defaults.max_depth);
+            if file == Path::new("-") {
+                yaml_cmd::run_stdin(depth, format, cli.verbose)?;
+            } else {
+                yaml_cmd::run(&file, depth, format, cli.verbose)?;
+            }
+        }
+
+        Commands::Xml { file, depth, .. } => {
+            let depth = depth.unwrap_or(defaults.max_depth);
+            if file == Path::new("-") {
+                xml_cmd::run_stdin(depth, format, cli.verbose)?;
+            } else {
+                xml_cmd::run(&file, depth, format, cli.verbose)?;
+            }
+        }
+
+        Commands::Toml {
+            file, depth, deps, ..
+        } => {
+            let depth = depth.unwrap_or(defaults.max_depth);
+            toml_cmd::run(&file, depth, deps, format, cli.verbose)?;
+        }
+
+        Commands::Csv {
+            file,
+            rows,
+            delimiter,
+            ..
+        } => {
+            csv_cmd::run(&file, rows, delimiter.as_deref(), format, cli.verbose)?;
+        }
+
+        Commands::Sample { file, records } => {
+            sample_cmd::run(&file, records, cli.verbose)?;
+        }
+
+        Commands::Deps { path } => {
+            deps::run(&path, cli.verbose)?;
+        }
+
+        Commands::Env { filter, show_all } => {
+            env_cmd::run(filter.as_deref(), show_all, cli.verbose)?;
+        }
+
+        Commands::Find {
+            pattern,
+            path,
+            max,
+            file_type,
+        } => {
+            let max = max.unwrap_or(defaults.max_results);
+            find_cmd::run(&pattern, &path, max, &file_type, cli.verbose)?;
+        }
+
+        Commands::Diff {
+            args,
+            expand,
+            staged,
+            heatmap,
+            ..
+        } => {
+            // The heatmap always reads git: two paths are a range to it
+            let input = if heatmap {
+                diff_cmd::DiffInput::Git
+            } else {
+                diff_cmd::DiffInput::from_args(&args)
+            };
+            match input {
+                diff_cmd::DiffInput::Files(f1, f2) => {
+                    diff_cmd::run(&f1, &f2, cli.verbose)?;
+                }
+                diff_cmd::DiffInput::Stdin => {
+                    diff_cmd::run_stdin(cli.verbose)?;
+                }
+                diff_cmd::DiffInput::Git => {
+                    let mut git_args = Vec::new();
+                    if staged {
+                        git_args.push("--cached".to_string());
+                    }
+                    git_args.extend(args);
+                    if heatmap {
+                        diff_cmd::run_heatmap(&git_args, format, cli.verbose)?;
+                    } else {
+                        diff_cmd::run_git(&git_args, &expand, format, cli.verbose)?;
+                    }
+                }
+            }
+        }
+
+        Commands::Pipe { max_lines, command } => {
+            pipe_cmd::run(&command, max_lines, cli.verbose)?;
+        }
+
+        Commands::Log { file } => {
+            if let Some(f) = file {
+                log_cmd::run_file(&f, cli.verbose)?;
+            } else {
+                log_cmd::run_stdin(cli.verbose)?;
+            }
+        }
+
+        Commands::Logsum { file, top, buckets } => {
+            logsum_cmd::run(file.as_deref(), top, buckets, cli.verbose)?;
+        }
+
+        Commands::Trace { file } => {
+            trace_cmd::run(file.as_deref(), cli.verbose)?;
+        }
+
+        Commands::Docker { command } => match command {
+            DockerCommands::Ps { args } => {
+                container::run(container::ContainerCmd::DockerPs, &args, cli.verbose)?;
+            }
+            DockerCommands::Images { args } => {
+                container::run(container::ContainerCmd::DockerImages, &args, cli.verbose)?;
+            }
+            DockerCommands::Build { args } => {
+                container::run_docker_build(&args, cli.verbose)?;
+            }
+            DockerCommands::Logs { container: c } => {
+                container::run(container::ContainerCmd::DockerLogs, &[c], cli.verbose)?;
+            }
+            DockerCommands::Compose { command: compose } => match compose {
+                ComposeCommands::Ps => {
+                    container::run_compose_ps(cli.verbose)?;
+                }
+                ComposeCommands::Logs { service } => {
+                    container::run_compose_logs(service.as_deref(), cli.verbose)?;
+                }
+                ComposeCommands::Build { service } => {
+                    container::run_compose_build(service.as_deref(), cli.verbose)?;
+                }
+                ComposeCommands::Other(args) => {
+                    container::run_compose_passthrough(&args, cli.verbose)?;
+                }
+            },
+            DockerCommands::Other(args) => {
+                container::run_docker_passthrough(&args, cli.verbose)?;
+            }
+        },
+
+        Commands::Kubectl { command } => match command {
+            KubectlCommands::Pods { namespace, all } => {
+                let mut args: Vec<String> = Vec::new();
+                if all {
+                    args.push("-A".to_string());
+                } else if let Some(n) = namespace {
+                    args.push("-n".to_string());
+                    args.push(n);
+                }
+                container::run(container::ContainerCmd::KubectlPods, &args, cli.verbose)?;
+            }
+            KubectlCommands::Services { namespace, all } => {
+                let mut args: Vec<String> = Vec::new();
+                if all {
+                    args.push("-A".to_string());
+                } else if let Some(n) = namespace {
+                    args.push("-n".to_string());
+                    args.push(n);
+                }
+                container::run(container::ContainerCmd::KubectlServices, &args, cli.verbose)?;
+            }
+            KubectlCommands::Logs { pod, container: c } => {
+                let mut args = vec![pod];
+                if let Some(cont) = c {
+                    args.push("-c".to_string());
+                    args.push(cont);
+                }
+                container::run(container::ContainerCmd::KubectlLogs, &args, cli.verbose)?;
+            }
+            KubectlCommands::Get { args } => {
+                container::run_kubectl_get(&args, cli.verbose)?;
+            }
+            KubectlCommands::Describe { args } => {
+                container::run_kubectl_describe(&args, cli.verbose)?;
+            }
+            KubectlCommands::Other(args) => {
+                container::run_kubectl_passthrough(&args, cli.verbose)?;
+            }
+        },
+
+        Commands::Summary { command } => {
+            let cmd = command.join(" ");
+            summary::run(&cmd, cli.verbose)?;
+        }
+
+        Commands::Grep {
+            pattern,
+            path,
+            patterns,
+            any_of,
+            max_len,
+            max,
+            context_only,
+            file_type,
+            line_numbers: _, // no-op: line numbers always enabled in grep_cmd::run
+            ignore_case,
+            case_sensitive,
+            word,
+            fixed_string,
+            multiline,
+            pcre2,
+            hidden,
+            no_ignore,
+            search_zip,
+            after_context,
+            before_context,
+            context,
+            group_by,
+            sort,
+            per_file,
+            mark,
+            column,
+            no_collapse,
+            no_dedup,
+            scope,
+            watch: _,
+            tui,
+            format: _,
+            color: _,
+            extra_args,
+        } => {
+            // 8 columns go to the line number gutter
+            let max_len = max_len.unwrap_or(layout::line_width(defaults.max_line_len, 8));
+            let max = max.unwrap_or(defaults.max_results);
+            let per_file = per_file.unwrap_or(defaults.per_file);
+            let (patterns, positional) = grep_cmd::resolve_patterns(
+                patterns,
+                any_of.as_deref(),
+                pattern.into_iter().chain(path).collect(),
+            )?;
+            let path = positional.first().map_or(".", String::as_str);
+            let extra_args: Vec<String> = positional
+                .iter()
+                .skip(1)
+                .chain(&extra_args)
+                .cloned()
+                .collect();
+            let (paths, extra_args) = grep_cmd::split_paths(path, &extra_args);
+            let context_lines = grep_cmd::ContextLines {
+                before: context.unwrap_or(before_context),
+                after: context.unwrap_or(after_context),
+            };
+            let (context_lines, extra_args) =
+                grep_cmd::take_context_args(context_lines, &extra_args);
+            let match_options = grep_cmd::MatchOptions {
+                ignore_case,
+                case_sensitive,
+                word,
+                fixed: fixed_string,
+                multiline,
+                pcre2,
+                hidden,
+                no_ignore,
+                search_zip,
+            };
+            let (match_options, extra_args) = grep_cmd::take_match_args(match_options, &extra_args);
+            let match_options = match_options.smart_case(&patterns);
+            if tui {
+                let options = grep_cmd::SearchOptions {
+                    file_types: file_type,
+                    matching: match_options,
+                    extra_args,
+                };
+                return grep_tui::run(&patterns, &paths, &options);
+            }
+            grep_cmd::run(
+                &patterns,
+                &paths,
+                max_len,
+                max,
+                context_only,
+                &file_type,
+                match_options,
+                context_lines,
+                &group_by,
+                grep_cmd::ShowOptions {
+                    sort,
+                    per_file,
+                    mark,
+                    column,
+                    collapse: !no_collapse,
+                    dedup: !no_dedup,
+                    scope,
+                },
+                &extra_args,
+                format,
+                cli.verbose,
+            )?;
+        }
+
+        Commands::Replace {
+            pattern,
+            replacement,
+            paths,
+            write,
+            file_type,
+            ignore_case,
+            word,
+            fixed_string,
+        } => {
+            let matching = grep_cmd::MatchOptions {
+                ignore_case,
+                word,
+                fixed: fixed_string,
+                ..Default::default()
+            };
+            replace_cmd::run(
+                &pattern,
+                &replacement,
+                &paths,
+                &file_type,
+                matching,
+                write,
+                cli.verbose,
+            )?;
+        }
+
+        Commands::Init {
+            global,
+            show,
+            claude_md,
+            hook_only,
+            auto_patch,
+            no_patch,
+            uninstall,
+        } => {
+            if show {
+                init::show_config()?;
+            } else if uninstall {
+                init::uninstall(global, cli.verbose)?;
+            } else {
+                let patch_mode = if auto_patch {
+                    init::PatchMode::Auto
+                } else if no_patch {
+                    init::PatchMode::Skip
+                } else {
+                    init::PatchMode::Ask
+                };
+                init::run(global, claude_md, hook_only, patch_mode, cli.verbose)?;
+            }
+        }
+
+        Commands::Wget { url, stdout, args } => {
+            if stdout {
+                wget_cmd::run_stdout(&url, &args, cli.verbose)?;
+            } else {
+                wget_cmd::run(&url, &args, cli.verbose)?;
+            }
+        }
+
+        Commands::Ps {
+            pattern,
+            mem,
+            limit,
+        } => {
+            ps_cmd::run(pattern.as_deref(), mem, limit, cli.verbose)?;
+        }
+
+        Commands::Du { path, limit } => {
+            du_cmd::run(&path, limit, cli.verbose)?;
+        }
+
+        Commands::Count { paths, all } => {
+            count_cmd::run(&paths, all, cli.verbose)?;
+        }
+
+        Commands::Wc { args } => {
+            wc_cmd::run(&args, cli.verbose)?;
+        }
+
+        Commands::Gain {
+            graph,
+            history,
+            quota,
+            tier,
+            daily,
+            weekly,
+            monthly,
+            all,
+            format,
+            color: _,
+        } => {
+            gain::run(
+                graph,
+                history,
+                quota,
+                &tier,
+                daily,
+                weekly,
+                monthly,
+                all,
+                &format,
+                cli.verbose,
+            )?;
+        }
+
+        Commands::Stats { top, command, .. } => match command {
+            Some(StatsCommands::Export {
+                format,
+                since,
+                endpoint,
+            }) => {
+                stats_cmd::export(&format, since, endpoint.as_deref(), cli.verbose)?;
+            }
+            None => {
+                stats_cmd::run(top, format, cli.verbose)?;
+            }
+        },
+
+        Commands::Serve { mcp } => {
+            if !mcp {
+                anyhow::bail!("Only `rtk serve --mcp` is supported");
+            }
+            mcp::run(cli.verbose)?;
+        }
+
+        Commands::Explain { entry } => {
+            explain_cmd::run(entry, cli.verbose)?;
+        }
+
+        Commands::Completions { shell } => {
+            completions::run(shell)?;
+        }
+
+        Commands::Replay { entry, raw } => {
+            replay_cmd::run(entry, raw, cli.verbose)?;
+        }
+
+        Commands::Recall {
+            name,
+            files,
+            grep,
+            lines,
+            remove,
+        } => {
+            let slice = recall_cmd::Slice { files, grep, lines };
+            recall_cmd::run(name.as_deref(), &slice, remove, cli.verbose)?;
+        }
+
+        Commands::History {
+            query,
+            command,
+            limit,
+            rerun,
+        } => {
+            let filter = history_cmd::HistoryFilter { query, command };
+            history_cmd::run(&filter, limit, rerun, cli.verbose)?;
+        }
+
+        Commands::Gc { days, max_mb } => {
+            gain::run_gc(days, max_mb, cli.verbose)?;
+        }
+
+        Commands::Cache {
+            command: CacheCommands::Clear,
+        } => {
+            cache::run_clear(cli.verbose)?;
+        }
+
+        Commands::Index { command } => match command {
+            IndexCommands::Build { path } => index::run_build(path.as_deref(), cli.verbose)?,
+            IndexCommands::Status { path } => index::run_status(path.as_deref(), cli.verbose)?,
+            IndexCommands::Clear { path } => index::run_clear(path.as_deref(), cli.verbose)?,
+        },
+
+        Commands::CcEconomics {
+            daily,
+            weekly,
+            monthly,
+            all,
+            format,
+        } => {
+            cc_economics::run(daily, weekly, monthly, all, &format, cli.verbose)?;
+        }
+
+        Commands::Config { create, action } => match action {
+            Some(ConfigCommands::Get { key }) => config::get_value(&key)?,
+            Some(ConfigCommands::Set {
+                key,
+                value,
+                project,
+            }) => config::set_value(&key, &value, project)?,
+            None if create => {
+                let path = config::Config::create_default()?;
+                println!("Created: {}", path.display());
+            }
+            None => config::show_config()?,
+        },
+
+        Commands::Vitest { command } => match command {
+            VitestCommands::Run { args } => {
+                vitest_cmd::run(vitest_cmd::VitestCommand::Run, &args, cli.verbose)?;
+            }
+        },
+
+        Commands::Prisma { command } => match command {
+            PrismaCommands::Generate { args } => {
+                prisma_cmd::run(prisma_cmd::PrismaCommand::Generate, &args, cli.verbose)?;
+            }
+            PrismaCommands::Migrate { command } => match command {
+                PrismaMigrateCommands::Dev { name, args } => {
+                    prisma_cmd::run(
+                        prisma_cmd::PrismaCommand::Migrate {
+                            subcommand: prisma_cmd::MigrateSubcommand::Dev { name },
+                        },
+                        &args,
+                        cli.verbose,
+                    )?;
+                }
+                PrismaMigrateCommands::Status { args } => {
+                    prisma_cmd::run(
+                        prisma_cmd::PrismaCommand::Migrate {
+                            subcommand: prisma_cmd::MigrateSubcommand::Status,
+                        },
+                        &args,
+                        cli.verbose,
+                    )?;
+                }
+                PrismaMigrateCommands::Deploy { args } => {
+                    prisma_cmd::run(
+                        prisma_cmd::PrismaCommand::Migrate {
+                            subcommand: prisma_cmd::MigrateSubcommand::Deploy,
+                        },
+                        &args,
+                        cli.verbose,
+                    )?;
+                }
+            },
+            PrismaCommands::DbPush { args } => {
+                prisma_cmd::run(prisma_cmd::PrismaCommand::DbPush, &args, cli.verbose)?;
+            }
+        },
+
+        Commands::Tsc { args } => {
+            tsc_cmd::run(&args, cli.verbose)?;
+        }
+
+        Commands::Next { args } => {
+            next_cmd::run(&args, cli.verbose)?;
+        }
+
+        Commands::Lint { args } => {
+            lint_cmd::run(&args, format, cli.verbose)?;
+        }
+
+        Commands::Prettier { args } => {
+            prettier_cmd::run(&args, cli.verbose)?;
+        }
+
+        Commands::Format { args } => {
+            format_cmd::run(&args, cli.verbose)?;
+        }
+
+        Commands::Playwright { args } => {
+            playwright_cmd::run(&args, cli.verbose)?;
+        }
+
+        Commands::Cargo { command } => match command {
+            CargoCommands::Build { args } => {
+                cargo_cmd::run(cargo_cmd::CargoCommand::Build, &args, cli.verbose)?;
+            }
+            CargoCommands::Test { args } => {
+                cargo_cmd::run(cargo_cmd::CargoCommand::Test, &args, cli.verbose)?;
+            }
+            CargoCommands::Clippy { args } => {
+                cargo_cmd::run(cargo_cmd::CargoCommand::Clippy, &args, cli.verbose)?;
+            }
+            CargoCommands::Check { args } => {
+                cargo_cmd::run(cargo_cmd::CargoCommand::Check, &args, cli.verbose)?;
+            }
+            CargoCommands::Install { args } => {
+                cargo_cmd::run(cargo_cmd::CargoCommand::Install, &args, cli.verbose)?;
+            }
+            CargoCommands::Nextest { args } => {
+                cargo_cmd::run(cargo_cmd::CargoCommand::Nextest, &args, cli.verbose)?;
+            }
+            CargoCommands::Other(args) => {
+                cargo_cmd::run_passthrough(&args, cli.verbose)?;
+            }
+        },
+
+        Commands::Npm { args } => {
+            npm_cmd::run(&args, cli.verbose, cli.skip_env)?;
+        }
+
+        Commands::Curl { args } => {
+            curl_cmd::run(&args, cli.verbose)?;
+        }
+
+        Commands::Http {
+            method,
+            url,
+            data,
+            headers,
+            depth,
+        } => {
+            let depth = depth.unwrap_or(defaults.max_depth);
+            http_cmd::run(&method, &url, data.as_deref(), &headers, depth, cli.verbose)?;
+        }
+
+        Commands::Discover {
+            project,
+            limit,
+            all,
+            since,
+            format,
+        } => {
+            discover::run(project.as_deref(), all, since, limit, &format, cli.verbose)?;
+        }
+
+        Commands::Learn {
+            project,
+            all,
+            since,
+            format,
+            write_rules,
+            min_confidence,
+            min_occurrences,
+        } => {
+            learn::run(
+                project,
+                all,
+                since,
+                format,
+                write_rules,
+                min_confidence,
+                min_occurrences,
+            )?;
+        }
+
+        Commands::Npx { args } => {
+            if args.is_empty() {
+                anyhow::bail!("npx requires a command argument");
+            }
+
+            // Intelligent routing: delegate to specialized filters
+            match args[0].as_str() {
+                "tsc" | "typescript" => {
+                    tsc_cmd::run(&args[1..], cli.verbose)?;
+                }
+                "eslint" => {
+                    lint_cmd::run(&args, format, cli.verbose)?;
+                }
+                "prisma" => {
+                    // Route to prisma_cmd based on subcommand
+                    if args.len() > 1 {
+                        let prisma_args: Vec<String> = args[2..].to_vec();
+                        match args[1].as_str() {
+                            "generate" => {
+                                prisma_cmd::run(
+                                    prisma_cmd::PrismaCommand::Generate,
+                                    &prisma_args,
+                                    cli.verbose,
+                                )?;
+                            }
+                            "db" if args.len() > 2 && args[2] == "push" => {
+                                prisma_cmd::run(
+                                    prisma_cmd::PrismaCommand::DbPush,
+                                    &args[3..],
+                                    cli.verbose,
+                                )?;
+                            }
+                            _ => {
+                                // Passthrough other prisma subcommands
+                                let timer = tracking::TimedExecution::start();
+                                let mut cmd = utils::tool_command("npx");
+                                for arg in &args {
+                                    cmd.arg(arg);
+                                }
+                                let status = cmd.status().context("Failed to run npx prisma")?;
+                                let args_str = args.join(" ");
+                                timer.track_passthrough(
+                                    &format!("npx {}", args_str),
+                                    &format!("rtk npx {} (passthrough)", args_str),
+                                );
+                                if !status.success() {
+                                    std::process::exit(status.code().unwrap_or(1));
+                                }
+                            }
+                        }
+                    } else {
+                        let timer = tracking::TimedExecution::start();
+                        let status = utils::tool_command("npx")
+                            .arg("prisma")
+                            .status()
+                            .context("Failed to run npx prisma")?;
+                        timer.track_passthrough("npx prisma", "rtk npx prisma (passthrough)");
+                        if !status.success() {
+                            std::process::exit(status.code().unwrap_or(1));
+                        }
+                    }
+                }
+                "next" => {
+                    next_cmd::run(&args[1..], cli.verbose)?;
+                }
+                "prettier" => {
+                    prettier_cmd::run(&args[1..], cli.verbose)?;
+                }
+                "playwright" => {
+                    playwright_cmd::run(&args[1..], cli.verbose)?;
+                }
+                _ => {
+                    // Generic passthrough with npm boilerplate filter
+                    npm_cmd::run(&args, cli.verbose, cli.skip_env)?;
+                }
+            }
+        }
+
+        Commands::Ruff { args } => {
+            ruff_cmd::run(&args, cli.verbose)?;
+        }
+
+        Commands::Pytest { args } => {
+            pytest_cmd::run(&args, cli.verbose)?;
+        }
+
+        Commands::Pip { args } => {
+            pip_cmd::run(&args, cli.verbose)?;
+        }
+
+        Commands::Go { command } => match command {
+            GoCommands::Test { args } => {
+                go_cmd::run_test(&args, cli.verbose)?;
+            }
+            GoCommands::Build { args } => {
+                go_cmd::run_build(&args, cli.verbose)?;
+            }
+            GoCommands::Vet { args } => {
+                go_cmd::run_vet(&args, cli.verbose)?;
+            }
+            GoCommands::Other(args) => {
+                go_cmd::run_other(&args, cli.verbose)?;
+            }
+        },
+
+        Commands::GolangciLint { args } => {
+            golangci_cmd::run(&args, cli.verbose)?;
+        }
+
+        Commands::HookAudit { since } => {
+            hook_audit_cmd::run(since, cli.verbose)?;
+        }
+
+        Commands::Proxy { args } => {
+            use std::process::Command;
+
+            if args.is_empty() {
+                anyhow::bail!(
+                    "proxy requires a command to execute\nUsage: rtk proxy <command> [args...]"
+                );
+            }
+
+            let timer = tracking::TimedExecution::start();
+
+            let cmd_name = args[0].to_string_lossy();
+            let cmd_args: Vec<String> = args[1..]
+                .iter()
+                .map(|s| s.to_string_lossy().into_owned())
+                .collect();
+
+            if cli.verbose > 0 {
+                eprintln!("Proxy mode: {} {}", cmd_name, cmd_args.join(" "));
+            }
+
+            let output = Command::new(cmd_name.as_ref())
+                .args(&cmd_args)
+                .output()
+                .context(format!("Failed to execute command: {}", cmd_name))?;
+
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let full_output = format!("{}{}", stdout, stderr);
+
+            // Print output
+            print!("{}", stdout);
+            eprint!("{}", stderr);
+
+            // Track usage (input = output since no filtering)
+            timer.track(
+                &format!("{} {}", cmd_name, cmd_args.join(" ")),
+                &format!("rtk proxy {} {}", cmd_name, cmd_args.join(" ")),
+                &full_output,
+                &full_output,
+            );
+
+            // Exit with same code as child process
+            if !output.status.success() {
+                std::process::exit(output.status.code().unwrap_or(1));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    /// Run `f` on a thread with a main-thread-sized stack: building the
+    /// whole command tree in a debug build outgrows a test thread's 2 MiB
+    fn with_main_stack<T: Send + 'static>(f: impl FnOnce() -> T + Send + 'static) -> T {
+        std::thread::Builder::new()
+            .stack_size(8 * 1024 * 1024)
+            .spawn(f)
+            .unwrap()
+            .join()
+            .unwrap()
+    }
+
+    fn parse<const N: usize>(args: [&'static str; N]) -> Result<Cli, clap::Error> {
+        with_main_stack(move || Cli::try_parse_from(args))
+    }
+
+    #[test]
+    fn test_git_commit_single_message() {
+        let cli = parse(["rtk", "git", "commit", "-m", "fix: typo"]).unwrap();
+        match cli.command {
+            Commands::Git {
+                command: GitCommands::Commit { message },
+            } => {
+                assert_eq!(message, vec!["fix: typo"]);
+            }
+            _ => panic!("Expected Git Commit command"),
+        }
+    }
+
+    #[test]
+    fn test_git_commit_multiple_messages() {
+        let cli = parse([
+            "rtk",
+            "git",
+            "commit",
+            "-m",
+            "feat: add support",
+            "-m",
+            "Body paragraph here.",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Git {
+                command: GitCommands::Commit { message },
+            } => {
+                assert_eq!(message, vec!["feat: add support", "Body paragraph here."]);
+            }
+            _ => panic!("Expected Git Commit command"),
+        }
+    }
+
+    #[test]
+    fn test_git_commit_long_flag_multiple() {
+        let cli = parse([
+            "rtk",
+            "git",
+            "commit",
+            "--message",
+            "title",
+            "--message",
+            "body",
+            "--message",
+            "footer",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Git {
+                command: GitCommands::Commit { message },
+            } => {
+                assert_eq!(message, vec!["title", "body", "footer"]);
+            }
+            _ => panic!("Expected Git Commit command"),
+        }
+    }
+
+    #[test]
+    fn test_format_flag() {
+        let cli = parse(["rtk", "--format", "sarif", "lint"]).unwrap();
+        assert_eq!(cli.format.as_deref(), Some("sarif"));
+        let cli = parse(["rtk", "grep", "--format", "json", "fn main"]).unwrap();
+        assert_eq!(cli.command.format(), Some("json"));
+        assert!(parse(["rtk", "grep", "--format", "jsno", "fn main"]).is_err());
+        let cli = parse([
+            "rtk", "json", "diff", "a.json", "b.json", "--format", "json",
+        ])
+        .unwrap();
+        assert_eq!(cli.command.format(), Some("json"));
+
+        // Unset: falls back to defaults.format from the config
+        let cli = parse(["rtk", "json", "data.json"]).unwrap();
+        assert_eq!((cli.format, cli.command.format()), (None, None));
+    }
+
+    #[test]
+    fn test_passthrough_keeps_its_format_flag() {
+        let cli = parse(["rtk", "git", "log", "--format=%h %s", "-n", "2"]).unwrap();
+        match cli.command {
+            Commands::Git {
+                command: GitCommands::Log { args },
+            } => assert_eq!(args, ["--format=%h %s", "-n", "2"]),
+            _ => panic!("Expected git log command"),
+        }
+        assert_eq!(cli.format, None);
+
+        let cli = parse(["rtk", "docker", "ps", "--format", "{{.Names}}"]).unwrap();
+        match cli.command {
+            Commands::Docker {
+                command: DockerCommands::Ps { args },
+            } => assert_eq!(args, ["--format", "{{.Names}}"]),
+            _ => panic!("Expected docker ps command"),
+        }
+    }
+
+    #[test]
+    fn test_color_flag() {
+        let cli = parse(["rtk", "--color", "never", "git", "status"]).unwrap();
+        assert_eq!(cli.color, output::ColorChoice::Never);
+        let cli = parse(["rtk", "grep", "fn", "--color", "always"]).unwrap();
+        assert_eq!(cli.command.color(), Some(output::ColorChoice::Always));
+        let cli = parse(["rtk", "json", "query", ".a", "--color", "never"]).unwrap();
+        assert_eq!(cli.command.color(), Some(output::ColorChoice::Never));
+
+        // Passthroughs keep theirs
+        let cli = parse(["rtk", "git", "log", "--color", "-n1"]).unwrap();
+        match cli.command {
+            Commands::Git {
+                command: GitCommands::Log { args },
+            } => assert_eq!(args, ["--color", "-n1"]),
+            _ => panic!("Expected git log command"),
+        }
+        let cli = parse(["rtk", "git", "diff", "--color=always"]).unwrap();
+        match cli.command {
+            Commands::Git {
+                command: GitCommands::Diff { args },
+            } => assert_eq!(args, ["--color=always"]),
+            _ => panic!("Expected git diff command"),
+        }
+        assert_eq!(cli.color, output::ColorChoice::Auto);
+    }
+
+    #[test]
+    fn test_cli_definition_is_valid() {
+        // Completion builds every subcommand, not just the one parsed
+        with_main_stack(|| Cli::command().debug_assert());
+    }
+
+    #[test]
+    fn test_completes_file_types_and_shells() {
+        let complete = |args: &[&str]| {
+            let args: Vec<OsString> = args.iter().map(OsString::from).collect();
+            let index = args.len() - 1;
+            with_main_stack(move || {
+                let mut cmd = completions::with_candidates(Cli::command());
+                clap_complete::engine::complete(&mut cmd, args, index, None)
+                    .unwrap()
+                    .iter()
+                    .map(|c| c.get_value().to_string_lossy().to_string())
+                    .collect::<Vec<_>>()
+            })
+        };
+        assert!(complete(&["rtk", "grep", "fn", "-t", "ru"]).contains(&"rust".to_string()));
+        assert_eq!(
+            complete(&["rtk", "completions", "f"]),
+            vec!["fish".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_diff_forwards_revisions_and_paths() {
+        let cli = parse(["rtk", "diff", "--staged", "main..feature", "--", "src"]).unwrap();
+        match cli.command {
+            Commands::Diff { args, staged, .. } => {
+                assert!(staged);
+                assert_eq!(args, ["main..feature", "--", "src"]);
+            }
+            _ => panic!("Expected diff command"),
+        }
+    }
+
+    #[test]
+    fn test_diff_heatmap_takes_revisions() {
+        let cli = parse(["rtk", "diff", "--heatmap", "main..feature", "--", "src"]).unwrap();
+        match cli.command {
+            Commands::Diff { args, heatmap, .. } => {
+                assert!(heatmap);
+                assert_eq!(args, ["main..feature", "--", "src"]);
+            }
+            _ => panic!("Expected diff command"),
+        }
+        assert!(parse(["rtk", "diff", "--heatmap", "-e", "a.rs"]).is_err());
+    }
+
+    #[test]
+    fn test_global_profile_flag() {
+        let cli = parse(["rtk", "grep", "fn", "--profile", "aggressive"]).unwrap();
+        assert_eq!(cli.profile, Some(config::Profile::Aggressive));
+        assert!(parse(["rtk", "grep", "fn", "--profile", "tiny"]).is_err());
+    }
+
+    #[test]
+    fn test_global_format_does_not_shadow_subcommand_format() {
+        let cli = parse(["rtk", "gain", "--format", "csv"]).unwrap();
+        match cli.command {
+            Commands::Gain { format, .. } => assert_eq!(format, "csv"),
+            _ => panic!("Expected Gain command"),
+        }
+
+        let cli = parse(["rtk", "stats", "export", "--format", "otlp"]).unwrap();
+        match cli.command {
+            Commands::Stats {
+                command: Some(StatsCommands::Export { format, .. }),
+                ..
+            } => assert_eq!(format, "otlp"),
+            _ => panic!("Expected stats export command"),
+        }
+    }
+
+    #[test]
+    fn test_json_diff_subcommand_and_file() {
+        let cli = parse(["rtk", "json", "diff", "a.json", "b.json", "--schema-only"]).unwrap();
+        match cli.command {
+            Commands::Json {
+                command:
+                    Some(JsonCommands::Diff {
+                        old, schema_only, ..
+                    }),
+                ..
+            } => {
+                assert_eq!(old, "a.json");
+                assert!(schema_only);
+            }
+            _ => panic!("Expected json diff command"),
+        }
+
+        let cli = parse(["rtk", "json", "data.json", "-d", "2"]).unwrap();
+        match cli.command {
+            Commands::Json {
+                file,
+                command: None,
+                ..
+            } => assert_eq!(file, Some(PathBuf::from("data.json"))),
+            _ => panic!("Expected json command"),
+        }
+        assert!(parse(["rtk", "json"]).is_err());
+
+        let cli = parse(["rtk", "json", "query", ".users[] | .email"]).unwrap();
+        match cli.command {
+            Commands::Json {
+                command: Some(JsonCommands::Query { query, file, .. }),
+                ..
+            } => assert_eq!((query.as_str(), file.as_str()), (".users[] | .email", "-")),
+            _ => panic!("Expected json query command"),
+        }
+
+        let cli = parse(["rtk", "json", "schema", "api.json"]).unwrap();
+        match cli.command {
+            Commands::Json {
+                command: Some(JsonCommands::Schema { output, .. }),
+                ..
+            } => assert_eq!(output, "json-schema"),
+            _ => panic!("Expected json schema command"),
+        }
+
+        let cli = parse(["rtk", "json", "types", "api.json", "--lang", "typescript"]).unwrap();
+        match cli.command {
+            Commands::Json {
+                command: Some(JsonCommands::Types { lang, name, .. }),
+                ..
+            } => assert_eq!((lang, name.as_str()), (json_types::Lang::Ts, "Root")),
+            _ => panic!("Expected json types command"),
+        }
+
+        let cli = parse(["rtk", "yaml", "to-json", "ci.yml", "--minify"]).unwrap();
+        match cli.command {
+            Commands::Yaml {
+                command: Some(YamlCommands::ToJson { file, minify }),
+                ..
+            } => assert_eq!((file.as_str(), minify), ("ci.yml", true)),
+            _ => panic!("Expected yaml to-json command"),
+        }
+    }
+}
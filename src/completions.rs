@@ -0,0 +1,124 @@
+//! Shell completions. `rtk completions <shell>` prints a script that
+//! registers rtk with the shell; completing then calls back into rtk
+//! (`COMPLETE=<shell> rtk -- <words>`), so candidates can come from the
+//! current state: the file types `grep -t` knows, the entries `rtk replay`
+//! can show.
+
+use crate::tracking::Tracker;
+use anyhow::{Context, Result};
+use clap_complete::env::Shells;
+use clap_complete::{ArgValueCandidates, CompletionCandidate};
+use ignore::types::TypesBuilder;
+use std::io::Write;
+
+/// Environment variable that turns an rtk run into a completion request
+pub const VAR: &str = "COMPLETE";
+
+/// Shells `rtk completions` writes a script for
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    Powershell,
+}
+
+impl Shell {
+    fn name(self) -> &'static str {
+        match self {
+            Shell::Bash => "bash",
+            Shell::Zsh => "zsh",
+            Shell::Fish => "fish",
+            Shell::Powershell => "powershell",
+        }
+    }
+}
+
+fn registration(shell: Shell) -> Result<Vec<u8>> {
+    let shells = Shells::builtins();
+    let completer = shells
+        .completer(shell.name())
+        .with_context(|| format!("No completion support for {}", shell.name()))?;
+    let mut script = Vec::new();
+    completer
+        .write_registration(VAR, "rtk", "rtk", "rtk", &mut script)
+        .context("Failed to write the completion script")?;
+    Ok(script)
+}
+
+/// `rtk completions <shell>`
+pub fn run(shell: Shell) -> Result<()> {
+    let script = registration(shell)?;
+    std::io::stdout()
+        .write_all(&script)
+        .context("Failed to write the completion script")?;
+    Ok(())
+}
+
+/// `cmd` (rtk's CLI) with the dynamic candidates attached. Added here
+/// rather than in the derive attributes, which would grow every parse.
+pub fn with_candidates(cmd: clap::Command) -> clap::Command {
+    let file_types = |arg: clap::Arg| arg.add(ArgValueCandidates::new(file_types));
+    cmd.mut_subcommand("grep", |c| c.mut_arg("file_type", file_types))
+        .mut_subcommand("replace", |c| c.mut_arg("file_type", file_types))
+        .mut_subcommand("replay", |c| {
+            c.mut_arg("entry", |arg| {
+                arg.add(ArgValueCandidates::new(replay_entries))
+            })
+        })
+}
+
+/// File type names of `grep -t` (ripgrep's list), with their globs
+pub fn file_types() -> Vec<CompletionCandidate> {
+    let mut builder = TypesBuilder::new();
+    builder.add_defaults();
+    builder
+        .definitions()
+        .into_iter()
+        .map(|def| {
+            let globs = def.globs().join(" ");
+            CompletionCandidate::new(def.name()).help(Some(globs.into()))
+        })
+        .collect()
+}
+
+/// Entry numbers of `rtk replay`, each with its command
+pub fn replay_entries() -> Vec<CompletionCandidate> {
+    let Ok(entries) = Tracker::new().and_then(|t| t.get_replays()) else {
+        return Vec::new();
+    };
+    entries
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            CompletionCandidate::new((i + 1).to_string())
+                .help(Some(entry.rtk_cmd.clone().into()))
+                .display_order(Some(i))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registration_calls_back_into_rtk() {
+        for shell in [Shell::Bash, Shell::Zsh, Shell::Fish, Shell::Powershell] {
+            let script = String::from_utf8(registration(shell).unwrap()).unwrap();
+            assert!(script.contains(VAR), "{:?}: {}", shell, script);
+            assert!(script.contains("rtk"));
+        }
+    }
+
+    #[test]
+    fn test_file_types_include_common_languages() {
+        let names: Vec<String> = file_types()
+            .iter()
+            .map(|c| c.get_value().to_string_lossy().to_string())
+            .collect();
+        for name in ["rust", "py", "ts", "go"] {
+            assert!(names.iter().any(|n| n == name), "missing {}", name);
+        }
+    }
+}
@@ -1,6 +1,8 @@
-use anyhow::Result;
+use crate::tokenizer::TokenModel;
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct Config {
@@ -12,14 +14,65 @@ pub struct Config {
     pub filters: FilterConfig,
     #[serde(default)]
     pub tee: crate::tee::TeeConfig,
+    #[serde(default)]
+    pub defaults: DefaultsConfig,
+    #[serde(default)]
+    pub search: crate::grep_cmd::SearchConfig,
+    #[serde(default)]
+    pub secrets: crate::redact::SecretsConfig,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TrackingConfig {
     pub enabled: bool,
+    /// Days of per-command history; older entries are compacted into daily
+    /// per-command aggregates
     pub history_days: u32,
+    /// Size cap of the history database; the oldest entries go first
+    #[serde(default = "default_max_db_mb")]
+    pub max_db_mb: u64,
+    /// Recent invocations whose raw and filtered output is kept for
+    /// `rtk replay`; 0 disables
+    #[serde(default = "default_replay_entries")]
+    pub replay_entries: usize,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub database_path: Option<PathBuf>,
+    /// Tokenizer for token estimates: chars, cl100k, o200k or claude
+    #[serde(default)]
+    pub tokenizer: TokenModel,
+    /// Sanitization of the command lines stored in the history
+    #[serde(default)]
+    pub redact: RedactConfig,
+    /// Unix socket, FIFO or named pipe that receives each recorded command
+    /// as a JSON line (overridden by `RTK_EVENTS_SOCKET`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub events_socket: Option<PathBuf>,
+}
+
+/// Scrubbing of stored command lines, on top of the `[secrets]` masking.
+/// `patterns` also mask the outputs kept for `rtk replay`; with `args` set,
+/// no output is kept at all
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RedactConfig {
+    /// Keep only the program and subcommands, with arguments reduced to a count
+    pub args: bool,
+    /// Regexes masked with `****`: capture groups when the pattern has any,
+    /// otherwise the whole match
+    pub patterns: Vec<String>,
+}
+
+impl Default for RedactConfig {
+    fn default() -> Self {
+        Self {
+            args: false,
+            patterns: vec![
+                r"(?i)bearer\s+(\S+)".into(),
+                r"(?i)(?:password|passwd|token|secret|api[_-]?key)[=:]\s*(\S+)".into(),
+                r"://[^/\s:@]+:([^/\s@]+)@".into(),
+            ],
+        }
+    }
 }
 
 impl Default for TrackingConfig {
@@ -27,11 +80,24 @@ impl Default for TrackingConfig {
         Self {
             enabled: true,
             history_days: 90,
+            max_db_mb: default_max_db_mb(),
+            replay_entries: default_replay_entries(),
             database_path: None,
+            tokenizer: TokenModel::default(),
+            redact: RedactConfig::default(),
+            events_socket: None,
         }
     }
 }
 
+fn default_max_db_mb() -> u64 {
+    100
+}
+
+fn default_replay_entries() -> usize {
+    20
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct DisplayConfig {
     pub colors: bool,
@@ -49,6 +115,79 @@ impl Default for DisplayConfig {
     }
 }
 
+/// Compaction profile: scales every cap of [`DefaultsConfig`] at once
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Profile {
+    /// Half of every cap, for small context windows
+    Aggressive,
+    /// The configured caps as they are
+    #[default]
+    Balanced,
+    /// Three times every cap: nearly full output
+    Verbose,
+}
+
+impl Profile {
+    fn percent(self) -> usize {
+        match self {
+            Profile::Aggressive => 50,
+            Profile::Balanced => 100,
+            Profile::Verbose => 300,
+        }
+    }
+
+    /// `value` scaled by the profile, never below 1
+    pub fn scale(self, value: usize) -> usize {
+        (value * self.percent() / 100).max(1)
+    }
+}
+
+/// Defaults for command options, used when the flag is not given
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DefaultsConfig {
+    /// Line length cap (grep --max-len)
+    pub max_line_len: usize,
+    /// Result count cap (grep/find --max)
+    pub max_results: usize,
+    /// Schema depth (json/yaml/xml/toml/http --depth)
+    pub max_depth: usize,
+    /// Lines per file (grep --per-file)
+    pub per_file: usize,
+    /// Output format: text, json or sarif (global --format)
+    pub format: crate::output::OutputFormat,
+    /// Compaction profile (global --profile)
+    pub profile: Profile,
+}
+
+impl Default for DefaultsConfig {
+    fn default() -> Self {
+        Self {
+            max_line_len: 80,
+            max_results: 50,
+            max_depth: 5,
+            per_file: 10,
+            format: crate::output::OutputFormat::Text,
+            profile: Profile::Balanced,
+        }
+    }
+}
+
+impl DefaultsConfig {
+    /// The caps scaled by `profile`
+    pub fn with_profile(self, profile: Profile) -> Self {
+        Self {
+            max_line_len: profile.scale(self.max_line_len),
+            max_results: profile.scale(self.max_results),
+            max_depth: profile.scale(self.max_depth),
+            per_file: profile.scale(self.per_file),
+            profile,
+            ..self
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct FilterConfig {
     pub ignore_dirs: Vec<String>,
@@ -71,17 +210,50 @@ impl Default for FilterConfig {
     }
 }
 
+/// Per-project overrides, looked up from the current directory upwards
+pub const PROJECT_CONFIG_FILE: &str = ".rtk.toml";
+
+/// Tables a `.rtk.toml` may set: how output looks. The others (tracking,
+/// tee, search, secrets) come from the global config only, or a cloned
+/// repo could redirect files, run programs or turn redaction off.
+pub const PROJECT_TABLES: [&str; 3] = ["defaults", "display", "filters"];
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+static GLOBAL_CONFIG: OnceLock<Config> = OnceLock::new();
+
 impl Config {
+    /// Global config with the presentation tables of the nearest
+    /// `.rtk.toml` merged on top
     pub fn load() -> Result<Self> {
-        let path = get_config_path()?;
-
-        if path.exists() {
-            let content = std::fs::read_to_string(&path)?;
-            let config: Config = toml::from_str(&content)?;
-            Ok(config)
-        } else {
-            Ok(Config::default())
+        // Layered over the defaults so partial tables like `[tracking]
+        // enabled = false` stay valid
+        let mut merged = default_table()?;
+        merge_tables(&mut merged, read_table(&get_config_path()?)?);
+        if let Some(project) = find_project_config() {
+            merge_tables(&mut merged, project_overrides(read_table(&project)?).0);
         }
+        toml::Value::Table(merged)
+            .try_into()
+            .context("Invalid rtk config")
+    }
+
+    /// The effective config, read once per process; an invalid file falls
+    /// back to the defaults (`rtk config` reports the error)
+    pub fn get() -> &'static Config {
+        CONFIG.get_or_init(|| Self::load().unwrap_or_default())
+    }
+
+    /// The global config alone, for settings a checkout must not choose
+    /// even in a table it may set
+    pub fn global() -> &'static Config {
+        GLOBAL_CONFIG.get_or_init(|| {
+            let load = || -> Result<Self> {
+                let mut merged = default_table()?;
+                merge_tables(&mut merged, read_table(&get_config_path()?)?);
+                Ok(toml::Value::Table(merged).try_into()?)
+            };
+            load().unwrap_or_default()
+        })
     }
 
     pub fn save(&self) -> Result<()> {
@@ -103,17 +275,157 @@ impl Config {
     }
 }
 
+fn default_table() -> Result<toml::Table> {
+    match toml::Value::try_from(Config::default())? {
+        toml::Value::Table(table) => Ok(table),
+        _ => Ok(toml::Table::new()),
+    }
+}
+
+/// Parse a config file into a TOML table (empty if the file is missing)
+fn read_table(path: &Path) -> Result<toml::Table> {
+    if !path.exists() {
+        return Ok(toml::Table::new());
+    }
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    toml::from_str(&content).with_context(|| format!("Invalid TOML in {}", path.display()))
+}
+
+/// Deep merge: tables are merged key by key, other values are replaced
+fn merge_tables(base: &mut toml::Table, overrides: toml::Table) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base_table)), toml::Value::Table(table)) => {
+                merge_tables(base_table, table)
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// The tables of a `.rtk.toml` that apply ([`PROJECT_TABLES`]), and the
+/// names of those ignored
+fn project_overrides(table: toml::Table) -> (toml::Table, Vec<String>) {
+    let (allowed, ignored): (toml::Table, toml::Table) = table
+        .into_iter()
+        .partition(|(key, _)| PROJECT_TABLES.contains(&key.as_str()));
+    (allowed, ignored.into_iter().map(|(key, _)| key).collect())
+}
+
+fn find_project_config() -> Option<PathBuf> {
+    let cwd = std::env::current_dir().ok()?;
+    cwd.ancestors()
+        .map(|dir| dir.join(PROJECT_CONFIG_FILE))
+        .find(|path| path.is_file())
+}
+
 fn get_config_path() -> Result<PathBuf> {
     let config_dir = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
     Ok(config_dir.join("rtk").join("config.toml"))
 }
 
+/// Print one effective value, e.g. `defaults.max_depth`
+pub fn get_value(key: &str) -> Result<()> {
+    let config = toml::Value::try_from(Config::load()?)?;
+    let value = lookup(&config, key).with_context(|| format!("Unknown config key '{}'", key))?;
+    match value {
+        toml::Value::String(s) => println!("{}", s),
+        toml::Value::Table(_) => print!("{}", toml::to_string_pretty(value)?),
+        other => println!("{}", other),
+    }
+    Ok(())
+}
+
+/// Set a value in the global config, or in `./.rtk.toml` with `project`
+pub fn set_value(key: &str, raw: &str, project: bool) -> Result<()> {
+    let table_name = key.split('.').next().unwrap_or(key);
+    if project && !PROJECT_TABLES.contains(&table_name) {
+        anyhow::bail!(
+            "{} can only be set in the global config ({} takes {})",
+            key,
+            PROJECT_CONFIG_FILE,
+            PROJECT_TABLES.join(", ")
+        );
+    }
+    let path = if project {
+        std::env::current_dir()?.join(PROJECT_CONFIG_FILE)
+    } else {
+        get_config_path()?
+    };
+
+    // Known keys only: the default config has every key
+    let defaults = toml::Value::try_from(Config::default())?;
+    if !matches!(lookup(&defaults, key), Some(v) if !v.is_table())
+        && key != "tracking.database_path"
+    {
+        anyhow::bail!("Unknown config key '{}'", key);
+    }
+
+    let mut table = read_table(&path)?;
+    set_dotted(&mut table, key, parse_value(raw));
+
+    // Reject values of the wrong type before writing anything
+    let mut merged = default_table()?;
+    merge_tables(&mut merged, table.clone());
+    toml::Value::Table(merged)
+        .try_into::<Config>()
+        .with_context(|| format!("Invalid value '{}' for {}", raw, key))?;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, toml::to_string_pretty(&table)?)?;
+    println!("{} = {} ({})", key, raw, path.display());
+    Ok(())
+}
+
+fn lookup<'a>(value: &'a toml::Value, key: &str) -> Option<&'a toml::Value> {
+    key.split('.').try_fold(value, |v, part| v.get(part))
+}
+
+fn set_dotted(table: &mut toml::Table, key: &str, value: toml::Value) {
+    match key.split_once('.') {
+        Some((head, rest)) => {
+            let entry = table
+                .entry(head)
+                .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+            if !entry.is_table() {
+                *entry = toml::Value::Table(toml::Table::new());
+            }
+            if let toml::Value::Table(inner) = entry {
+                set_dotted(inner, rest, value);
+            }
+        }
+        None => {
+            table.insert(key.to_string(), value);
+        }
+    }
+}
+
+/// `true`, `42`, `["a", "b"]` parse as TOML; anything else is a string
+fn parse_value(raw: &str) -> toml::Value {
+    toml::from_str::<toml::Table>(&format!("v = {}", raw))
+        .ok()
+        .and_then(|mut t| t.remove("v"))
+        .unwrap_or_else(|| toml::Value::String(raw.to_string()))
+}
+
 pub fn show_config() -> Result<()> {
     let path = get_config_path()?;
     println!("Config: {}", path.display());
+    if let Some(project) = find_project_config() {
+        println!("Project overrides: {}", project.display());
+        let (_, ignored) = project_overrides(read_table(&project)?);
+        if !ignored.is_empty() {
+            println!("  ignored (global config only): [{}]", ignored.join("], ["));
+        }
+    }
     println!();
 
-    if path.exists() {
+    if path.exists() || find_project_config().is_some() {
         let config = Config::load()?;
         println!("{}", toml::to_string_pretty(&config)?);
     } else {
@@ -125,3 +437,109 @@ pub fn show_config() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_tables_overrides_nested_keys() {
+        let mut merged = default_table().unwrap();
+        let global: toml::Table =
+            toml::from_str("[defaults]\nmax_depth = 3\nformat = \"json\"").unwrap();
+        let project: toml::Table = toml::from_str("[defaults]\nmax_depth = 8").unwrap();
+        merge_tables(&mut merged, global);
+        merge_tables(&mut merged, project);
+        let config: Config = toml::Value::Table(merged).try_into().unwrap();
+        assert_eq!(config.defaults.max_depth, 8);
+        assert_eq!(config.defaults.format, crate::output::OutputFormat::Json);
+        assert_eq!(config.defaults.max_results, 50);
+        assert!(config.tracking.enabled);
+    }
+
+    #[test]
+    fn test_profile_scales_every_cap() {
+        let config: Config = toml::from_str("[defaults]\nprofile = \"aggressive\"").unwrap();
+        assert_eq!(config.defaults.profile, Profile::Aggressive);
+
+        let aggressive = DefaultsConfig::default().with_profile(Profile::Aggressive);
+        assert_eq!(
+            (
+                aggressive.max_line_len,
+                aggressive.max_results,
+                aggressive.max_depth,
+                aggressive.per_file
+            ),
+            (40, 25, 2, 5)
+        );
+        let verbose = DefaultsConfig::default().with_profile(Profile::Verbose);
+        assert_eq!((verbose.max_results, verbose.max_depth), (150, 15));
+        let balanced = DefaultsConfig::default().with_profile(Profile::Balanced);
+        assert_eq!(balanced.max_results, 50);
+        assert_eq!(Profile::Aggressive.scale(1), 1);
+    }
+
+    #[test]
+    fn test_set_dotted_and_parse_value() {
+        let mut table = toml::Table::new();
+        set_dotted(&mut table, "defaults.max_results", parse_value("20"));
+        set_dotted(&mut table, "defaults.format", parse_value("json"));
+        set_dotted(&mut table, "tracking.enabled", parse_value("false"));
+        let value = toml::Value::Table(table);
+        assert_eq!(
+            lookup(&value, "defaults.max_results"),
+            Some(&toml::Value::Integer(20))
+        );
+        assert_eq!(
+            lookup(&value, "defaults.format"),
+            Some(&toml::Value::String("json".into()))
+        );
+        assert_eq!(
+            lookup(&value, "tracking.enabled"),
+            Some(&toml::Value::Boolean(false))
+        );
+        assert!(lookup(&value, "defaults.nope").is_none());
+    }
+
+    #[test]
+    fn test_partial_tracking_table_over_defaults() {
+        let mut merged = default_table().unwrap();
+        merge_tables(
+            &mut merged,
+            toml::from_str("[tracking]\nenabled = false").unwrap(),
+        );
+        let config: Config = toml::Value::Table(merged).try_into().unwrap();
+        assert!(!config.tracking.enabled);
+        assert_eq!(config.tracking.history_days, 90);
+    }
+
+    #[test]
+    fn test_project_overrides_only_presentation_tables() {
+        let project: toml::Table = toml::from_str(
+            "[defaults]\nmax_depth = 8\n[tee]\ndirectory = \"/etc\"\n\
+             [secrets]\nenabled = false\n[search.args]\nrg = [\"--pre=x\"]\n\
+             [tracking]\ndatabase_path = \"/tmp/x.db\"",
+        )
+        .unwrap();
+        let (allowed, mut ignored) = project_overrides(project);
+        ignored.sort();
+        assert_eq!(ignored, ["search", "secrets", "tee", "tracking"]);
+
+        let mut merged = default_table().unwrap();
+        merge_tables(&mut merged, allowed);
+        let config: Config = toml::Value::Table(merged).try_into().unwrap();
+        assert_eq!(config.defaults.max_depth, 8);
+        assert!(config.secrets.enabled);
+        assert!(config.tee.directory.is_none());
+        assert!(config.search.args.is_empty());
+        assert!(config.tracking.database_path.is_none());
+    }
+
+    #[test]
+    fn test_partial_config_keeps_defaults() {
+        let config: Config = toml::from_str("[defaults]\nmax_line_len = 120").unwrap();
+        assert_eq!(config.defaults.max_line_len, 120);
+        assert_eq!(config.defaults.max_depth, 5);
+        assert_eq!(config.filters.ignore_dirs.len(), 6);
+    }
+}
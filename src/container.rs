@@ -1,4 +1,5 @@
 use crate::tracking;
+use crate::utils::format_bytes;
 use anyhow::{Context, Result};
 use std::ffi::OsString;
 use std::process::Command;
@@ -13,10 +14,13 @@ pub enum ContainerCmd {
     KubectlLogs,
 }
 
+/// Rows shown in docker tables before "... +N more"
+const MAX_DOCKER_ROWS: usize = 15;
+
 pub fn run(cmd: ContainerCmd, args: &[String], verbose: u8) -> Result<()> {
     match cmd {
-        ContainerCmd::DockerPs => docker_ps(verbose),
-        ContainerCmd::DockerImages => docker_images(verbose),
+        ContainerCmd::DockerPs => docker_ps(args, verbose),
+        ContainerCmd::DockerImages => docker_images(args, verbose),
         ContainerCmd::DockerLogs => docker_logs(args, verbose),
         ContainerCmd::KubectlPods => kubectl_pods(args, verbose),
         ContainerCmd::KubectlServices => kubectl_services(args, verbose),
@@ -24,133 +28,466 @@ pub fn run(cmd: ContainerCmd, args: &[String], verbose: u8) -> Result<()> {
     }
 }
 
-fn docker_ps(_verbose: u8) -> Result<()> {
+fn docker_ps(args: &[String], _verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
     let raw = Command::new("docker")
-        .args(["ps"])
+        .arg("ps")
+        .args(args)
         .output()
         .map(|o| String::from_utf8_lossy(&o.stdout).to_string())
         .unwrap_or_default();
 
+    // Referencing .Size makes docker compute sizes, which is slow: only on -s
+    let with_size = args.iter().any(|a| a == "-s" || a == "--size");
+    let mut format = "{{.Names}}\t{{.Image}}\t{{.Status}}\t{{.Ports}}".to_string();
+    if with_size {
+        format.push_str("\t{{.Size}}");
+    }
+
     let output = Command::new("docker")
-        .args([
-            "ps",
-            "--format",
-            "{{.ID}}\t{{.Names}}\t{{.Status}}\t{{.Image}}\t{{.Ports}}",
-        ])
+        .arg("ps")
+        .args(args.iter().filter(|a| *a != "-s" && *a != "--size"))
+        .args(["--format", &format])
         .output()
         .context("Failed to run docker ps")?;
+    if !output.status.success() {
+        eprint!("{}", String::from_utf8_lossy(&output.stderr));
+        std::process::exit(output.status.code().unwrap_or(1));
+    }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let mut rtk = String::new();
+    let rtk = format_docker_ps(&String::from_utf8_lossy(&output.stdout));
+    print!("{}", rtk);
+    timer.track("docker ps", "rtk docker ps", &raw, &rtk);
+    Ok(())
+}
 
-    if stdout.trim().is_empty() {
-        rtk.push_str("🐳 0 containers");
-        println!("{}", rtk);
-        timer.track("docker ps", "rtk docker ps", &raw, &rtk);
-        return Ok(());
+/// Format `docker ps --format "{{.Names}}\t{{.Image}}\t{{.Status}}\t{{.Ports}}[\t{{.Size}}]"`
+/// into an aligned table with a state and size rollup
+pub fn format_docker_ps(raw: &str) -> String {
+    let rows: Vec<Vec<&str>> = raw
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| l.split('\t').collect())
+        .collect();
+    if rows.is_empty() {
+        return "🐳 0 containers\n".to_string();
     }
 
-    let count = stdout.lines().count();
-    rtk.push_str(&format!("🐳 {} containers:\n", count));
-
-    for line in stdout.lines().take(15) {
-        let parts: Vec<&str> = line.split('\t').collect();
-        if parts.len() >= 4 {
-            let id = &parts[0][..12.min(parts[0].len())];
-            let name = parts[1];
-            let short_image = parts.get(3).unwrap_or(&"").split('/').last().unwrap_or("");
-            let ports = compact_ports(parts.get(4).unwrap_or(&""));
-            if ports == "-" {
-                rtk.push_str(&format!("  {} {} ({})\n", id, name, short_image));
-            } else {
-                rtk.push_str(&format!(
-                    "  {} {} ({}) [{}]\n",
-                    id, name, short_image, ports
-                ));
-            }
+    let mut table: Vec<[String; 4]> = Vec::new();
+    let mut up = 0;
+    let mut writable_bytes = 0u64;
+    let mut has_size = false;
+    for parts in &rows {
+        let status = parts.get(2).copied().unwrap_or("");
+        if status.starts_with("Up") {
+            up += 1;
+        }
+        if let Some(size) = parts.get(4) {
+            // "12.3kB (virtual 150MB)": the first figure is the writable layer
+            has_size = true;
+            writable_bytes += parse_docker_size(size.split(" (").next().unwrap_or(""));
         }
+        let image = parts.get(1).copied().unwrap_or("");
+        table.push([
+            parts[0].to_string(),
+            image.rsplit('/').next().unwrap_or(image).to_string(),
+            compact_status(status),
+            compact_ports(parts.get(3).copied().unwrap_or("")),
+        ]);
     }
-    if count > 15 {
-        rtk.push_str(&format!("  ... +{} more", count - 15));
+
+    let mut rtk = format!("🐳 {} containers ({} up", rows.len(), up);
+    if rows.len() > up {
+        rtk.push_str(&format!(", {} stopped", rows.len() - up));
+    }
+    rtk.push(')');
+    if has_size {
+        rtk.push_str(&format!(", {} writable", format_bytes(writable_bytes)));
     }
+    rtk.push('\n');
+    rtk.push_str(&format_table(
+        &["NAME", "IMAGE", "STATUS", "PORTS"],
+        &table,
+        MAX_DOCKER_ROWS,
+    ));
+    rtk
+}
 
-    print!("{}", rtk);
-    timer.track("docker ps", "rtk docker ps", &raw, &rtk);
-    Ok(())
+/// `Up 3 hours (healthy)` → `up 3h (healthy)`, `Exited (1) 2 days ago` → `exited(1) 2d`
+fn compact_status(status: &str) -> String {
+    let mut s = status
+        .replace("About an hour", "1 hours")
+        .replace("About a minute", "1 minutes")
+        .replace("Less than a second", "0 seconds")
+        .replace(" ago", "")
+        .replace("Exited (", "exited(")
+        .replace("Up ", "up ");
+    for (unit, short) in [
+        (" seconds", "s"),
+        (" second", "s"),
+        (" minutes", "m"),
+        (" minute", "m"),
+        (" hours", "h"),
+        (" hour", "h"),
+        (" days", "d"),
+        (" day", "d"),
+        (" weeks", "w"),
+        (" week", "w"),
+        (" months", "mo"),
+        (" month", "mo"),
+    ] {
+        s = s.replace(unit, short);
+    }
+    s
 }
 
-fn docker_images(_verbose: u8) -> Result<()> {
+fn docker_images(args: &[String], _verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
     let raw = Command::new("docker")
-        .args(["images"])
+        .arg("images")
+        .args(args)
         .output()
         .map(|o| String::from_utf8_lossy(&o.stdout).to_string())
         .unwrap_or_default();
 
     let output = Command::new("docker")
-        .args(["images", "--format", "{{.Repository}}:{{.Tag}}\t{{.Size}}"])
+        .arg("images")
+        .args(args)
+        .args([
+            "--format",
+            "{{.Repository}}\t{{.Tag}}\t{{.Size}}\t{{.CreatedSince}}",
+        ])
         .output()
         .context("Failed to run docker images")?;
+    if !output.status.success() {
+        eprint!("{}", String::from_utf8_lossy(&output.stderr));
+        std::process::exit(output.status.code().unwrap_or(1));
+    }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let lines: Vec<&str> = stdout.lines().collect();
-    let mut rtk = String::new();
+    let rtk = format_docker_images(&String::from_utf8_lossy(&output.stdout));
+    print!("{}", rtk);
+    timer.track("docker images", "rtk docker images", &raw, &rtk);
+    Ok(())
+}
 
-    if lines.is_empty() {
-        rtk.push_str("🐳 0 images");
-        println!("{}", rtk);
-        timer.track("docker images", "rtk docker images", &raw, &rtk);
-        return Ok(());
+/// Format `docker images --format "{{.Repository}}\t{{.Tag}}\t{{.Size}}\t{{.CreatedSince}}"`
+/// into one row per repository with its tags and summed size
+pub fn format_docker_images(raw: &str) -> String {
+    struct Repo<'a> {
+        name: &'a str,
+        tags: Vec<&'a str>,
+        bytes: u64,
+        newest: &'a str,
     }
 
-    let mut total_size_mb: f64 = 0.0;
-    for line in &lines {
+    let mut repos: Vec<Repo> = Vec::new();
+    let mut total = 0usize;
+    let mut total_bytes = 0u64;
+    let mut dangling = 0usize;
+    let mut dangling_bytes = 0u64;
+    for line in raw.lines().filter(|l| !l.trim().is_empty()) {
         let parts: Vec<&str> = line.split('\t').collect();
-        if let Some(size_str) = parts.get(1) {
-            if size_str.contains("GB") {
-                if let Ok(n) = size_str.replace("GB", "").trim().parse::<f64>() {
-                    total_size_mb += n * 1024.0;
-                }
-            } else if size_str.contains("MB") {
-                if let Ok(n) = size_str.replace("MB", "").trim().parse::<f64>() {
-                    total_size_mb += n;
-                }
+        let name = parts[0];
+        let tag = parts.get(1).copied().unwrap_or("");
+        let bytes = parse_docker_size(parts.get(2).copied().unwrap_or(""));
+        total += 1;
+        total_bytes += bytes;
+        if name == "<none>" {
+            dangling += 1;
+            dangling_bytes += bytes;
+            continue;
+        }
+        // docker lists newest first, so the first row of a repo is its newest
+        match repos.iter_mut().find(|r| r.name == name) {
+            Some(repo) => {
+                repo.tags.push(tag);
+                repo.bytes += bytes;
             }
+            None => repos.push(Repo {
+                name,
+                tags: vec![tag],
+                bytes,
+                newest: parts.get(3).copied().unwrap_or(""),
+            }),
         }
     }
 
-    let total_display = if total_size_mb > 1024.0 {
-        format!("{:.1}GB", total_size_mb / 1024.0)
-    } else {
-        format!("{:.0}MB", total_size_mb)
-    };
-    rtk.push_str(&format!("🐳 {} images ({})\n", lines.len(), total_display));
+    if total == 0 {
+        return "🐳 0 images\n".to_string();
+    }
 
-    for line in lines.iter().take(15) {
-        let parts: Vec<&str> = line.split('\t').collect();
-        if !parts.is_empty() {
-            let image = parts[0];
-            let size = parts.get(1).unwrap_or(&"");
-            let short = if image.len() > 40 {
-                format!("...{}", &image[image.len() - 37..])
+    let mut rtk = format!(
+        "🐳 {} images in {} repos ({})",
+        total,
+        repos.len(),
+        format_bytes(total_bytes)
+    );
+    if dangling > 0 {
+        rtk.push_str(&format!(
+            ", {} dangling ({})",
+            dangling,
+            format_bytes(dangling_bytes)
+        ));
+    }
+    rtk.push('\n');
+
+    let table: Vec<[String; 4]> = repos
+        .iter()
+        .map(|r| {
+            let mut tags = r
+                .tags
+                .iter()
+                .take(3)
+                .copied()
+                .collect::<Vec<_>>()
+                .join(", ");
+            if r.tags.len() > 3 {
+                tags.push_str(&format!(" +{}", r.tags.len() - 3));
+            }
+            let name = if r.name.len() > 40 {
+                format!("...{}", &r.name[r.name.len() - 37..])
             } else {
-                image.to_string()
+                r.name.to_string()
             };
-            rtk.push_str(&format!("  {} [{}]\n", short, size));
+            [name, tags, format_bytes(r.bytes), compact_status(r.newest)]
+        })
+        .collect();
+    rtk.push_str(&format_table(
+        &["REPOSITORY", "TAGS", "SIZE", "NEWEST"],
+        &table,
+        MAX_DOCKER_ROWS,
+    ));
+    rtk
+}
+
+/// Left-aligned columns sized to their content, last column unpadded
+fn format_table(header: &[&str; 4], rows: &[[String; 4]], limit: usize) -> String {
+    let shown = &rows[..rows.len().min(limit)];
+    let mut widths = header.map(|h| h.chars().count());
+    for row in shown {
+        for (w, cell) in widths.iter_mut().zip(row) {
+            *w = (*w).max(cell.chars().count());
         }
     }
-    if lines.len() > 15 {
-        rtk.push_str(&format!("  ... +{} more", lines.len() - 15));
+
+    let mut out = String::new();
+    let header = header.map(str::to_string);
+    for row in std::iter::once(&header).chain(shown) {
+        let line = format!(
+            "  {:w0$}  {:w1$}  {:w2$}  {}",
+            row[0],
+            row[1],
+            row[2],
+            row[3],
+            w0 = widths[0],
+            w1 = widths[1],
+            w2 = widths[2]
+        );
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    if rows.len() > limit {
+        out.push_str(&format!("  ... +{} more\n", rows.len() - limit));
     }
+    out
+}
 
-    print!("{}", rtk);
-    timer.track("docker images", "rtk docker images", &raw, &rtk);
+/// Docker sizes are decimal: `1.2GB`, `150MB`, `12.3kB`, `0B`
+fn parse_docker_size(size: &str) -> u64 {
+    let size = size.trim();
+    let split = size
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(size.len());
+    let Ok(n) = size[..split].parse::<f64>() else {
+        return 0;
+    };
+    let factor = match size[split..].trim().to_ascii_uppercase().as_str() {
+        "KB" => 1e3,
+        "MB" => 1e6,
+        "GB" => 1e9,
+        "TB" => 1e12,
+        _ => 1.0,
+    };
+    (n * factor) as u64
+}
+
+/// Run `docker build` with BuildKit plain progress, collapsed to per-stage
+/// timing and cache hits
+pub fn run_docker_build(args: &[String], verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    let mut cmd = Command::new("docker");
+    cmd.arg("build");
+    if !args.iter().any(|a| a.starts_with("--progress")) {
+        cmd.arg("--progress=plain");
+    }
+    cmd.args(args);
+
+    if verbose > 0 {
+        eprintln!("Running: docker build --progress=plain {}", args.join(" "));
+    }
+
+    let output = cmd.output().context("Failed to run docker build")?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let raw = format!("{}\n{}", stdout, stderr);
+
+    let rtk = format_docker_build(&raw, output.status.success());
+    println!("{}", rtk);
+    timer.track(
+        &format!("docker build {}", args.join(" ")),
+        &format!("rtk docker build {}", args.join(" ")),
+        &raw,
+        &rtk,
+    );
+
+    if !output.status.success() {
+        std::process::exit(output.status.code().unwrap_or(1));
+    }
     Ok(())
 }
 
+/// One Dockerfile stage's rollup in a BuildKit build
+#[derive(Debug, Default)]
+struct BuildStage {
+    name: String,
+    steps: usize,
+    cached: usize,
+    secs: f64,
+}
+
+/// Collapse `docker build --progress=plain` output. Each `#N [stage i/n] ...`
+/// vertex is attributed to its stage; `#N CACHED` and `#N DONE 1.2s` give the
+/// cache hits and timings. On failure the `------` error block is kept verbatim.
+pub fn format_docker_build(raw: &str, success: bool) -> String {
+    let mut stages: Vec<BuildStage> = Vec::new();
+    // vertex id → index into `stages` (None for internal/export vertices)
+    let mut vertices: std::collections::HashMap<&str, Option<usize>> =
+        std::collections::HashMap::new();
+    let mut image: Option<&str> = None;
+    let mut legacy_steps = 0;
+    let mut legacy_cached = 0;
+
+    for line in raw.lines() {
+        let line = line.trim_end();
+        if line.starts_with("Step ") && line.contains(" : ") {
+            legacy_steps += 1;
+            continue;
+        }
+        if line.trim_start().starts_with("---> Using cache") {
+            legacy_cached += 1;
+            continue;
+        }
+        let Some(rest) = line.strip_prefix('#') else {
+            continue;
+        };
+        let Some((id, body)) = rest.split_once(' ') else {
+            continue;
+        };
+        if !id.chars().all(|c| c.is_ascii_digit()) {
+            continue;
+        }
+
+        if let Some(label) = body.strip_prefix('[') {
+            if vertices.contains_key(id) {
+                continue;
+            }
+            let bracket = label.split(']').next().unwrap_or("");
+            let mut words = bracket.split_whitespace();
+            let first = words.next().unwrap_or("");
+            let stage_name = match words.next() {
+                Some(_) => first,
+                None if first.contains('/') => "build",
+                None => "",
+            };
+            if stage_name.is_empty() || stage_name == "internal" {
+                vertices.insert(id, None);
+                continue;
+            }
+            let idx = match stages.iter().position(|s| s.name == stage_name) {
+                Some(idx) => idx,
+                None => {
+                    stages.push(BuildStage {
+                        name: stage_name.to_string(),
+                        ..Default::default()
+                    });
+                    stages.len() - 1
+                }
+            };
+            stages[idx].steps += 1;
+            vertices.insert(id, Some(idx));
+        } else if let Some(name) = body.strip_prefix("naming to ") {
+            image = name.split_whitespace().next();
+        } else if body == "CACHED" {
+            if let Some(Some(idx)) = vertices.get(id) {
+                stages[*idx].cached += 1;
+            }
+        } else if let Some(secs) = body.strip_prefix("DONE ") {
+            if let Some(Some(idx)) = vertices.get(id) {
+                stages[*idx].secs += secs.trim_end_matches('s').parse::<f64>().unwrap_or(0.0);
+            }
+        }
+    }
+
+    let mut rtk = String::new();
+    let image = image
+        .map(|i| i.trim_start_matches("docker.io/library/"))
+        .map(|i| format!(": {}", i))
+        .unwrap_or_default();
+    if success {
+        rtk.push_str(&format!("🐳 Build ok{}\n", image));
+    } else {
+        rtk.push_str("🐳 Build failed\n");
+    }
+
+    if !stages.is_empty() {
+        let width = stages.iter().map(|s| s.name.len()).max().unwrap_or(0);
+        for s in &stages {
+            rtk.push_str(&format!(
+                "  {:width$}  {} steps, {} cached, {:.1}s\n",
+                s.name,
+                s.steps,
+                s.cached,
+                s.secs,
+                width = width
+            ));
+        }
+        let steps: usize = stages.iter().map(|s| s.steps).sum();
+        let cached: usize = stages.iter().map(|s| s.cached).sum();
+        let secs: f64 = stages.iter().map(|s| s.secs).sum();
+        rtk.push_str(&format!(
+            "  📊 {} steps, {} cached ({}%), {:.1}s\n",
+            steps,
+            cached,
+            cached * 100 / steps.max(1),
+            secs
+        ));
+    } else if legacy_steps > 0 {
+        rtk.push_str(&format!(
+            "  📊 {} steps, {} cached\n",
+            legacy_steps, legacy_cached
+        ));
+    }
+
+    if !success {
+        let lines: Vec<&str> = raw.lines().collect();
+        let error_block = match lines.iter().position(|l| l.trim() == "------") {
+            Some(start) => lines[start..].to_vec(),
+            None => lines
+                .iter()
+                .filter(|l| l.contains("ERROR") || l.starts_with("error"))
+                .copied()
+                .collect(),
+        };
+        rtk.push('\n');
+        rtk.push_str(error_block.join("\n").trim());
+    }
+
+    rtk.trim_end().to_string()
+}
+
 fn docker_logs(args: &[String], _verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
@@ -192,96 +529,298 @@ fn kubectl_pods(args: &[String], _verbose: u8) -> Result<()> {
 
     let output = cmd.output().context("Failed to run kubectl get pods")?;
     let raw = String::from_utf8_lossy(&output.stdout).to_string();
-    let mut rtk = String::new();
 
-    let json: serde_json::Value = match serde_json::from_str(&raw) {
-        Ok(v) => v,
-        Err(_) => {
-            rtk.push_str("☸️  No pods found");
-            println!("{}", rtk);
-            timer.track("kubectl get pods", "rtk kubectl pods", &raw, &rtk);
-            return Ok(());
-        }
+    let rtk = match serde_json::from_str::<serde_json::Value>(&raw) {
+        Ok(json) => format_kubectl_pods(&json),
+        Err(_) => "☸️  No pods found\n".to_string(),
     };
 
-    let items = json["items"].as_array();
-    if items.is_none() || items.unwrap().is_empty() {
-        rtk.push_str("☸️  No pods found");
-        println!("{}", rtk);
-        timer.track("kubectl get pods", "rtk kubectl pods", &raw, &rtk);
-        return Ok(());
-    }
+    print!("{}", rtk);
+    timer.track("kubectl get pods", "rtk kubectl pods", &raw, &rtk);
+    Ok(())
+}
 
-    let pods = items.unwrap();
-    let (mut running, mut pending, mut failed, mut restarts_total) = (0, 0, 0, 0i64);
-    let mut issues: Vec<String> = Vec::new();
+/// Pod names listed per status before "+N more"
+const MAX_PODS_PER_STATUS: usize = 5;
 
+/// Group `kubectl get pods -o json` by displayed status: healthy statuses are
+/// counted, the others list their pods with restarts and node
+pub fn format_kubectl_pods(json: &serde_json::Value) -> String {
+    let pods = match json["items"].as_array() {
+        Some(items) if !items.is_empty() => items,
+        _ => return "☸️  No pods found\n".to_string(),
+    };
+
+    let mut groups: Vec<(String, Vec<String>)> = Vec::new();
+    let mut restarts_total = 0i64;
+    let mut not_ready = 0;
     for pod in pods {
         let ns = pod["metadata"]["namespace"].as_str().unwrap_or("-");
         let name = pod["metadata"]["name"].as_str().unwrap_or("-");
-        let phase = pod["status"]["phase"].as_str().unwrap_or("Unknown");
-
-        if let Some(containers) = pod["status"]["containerStatuses"].as_array() {
-            for c in containers {
-                restarts_total += c["restartCount"].as_i64().unwrap_or(0);
-            }
+        let containers = pod["status"]["containerStatuses"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let restarts: i64 = containers
+            .iter()
+            .map(|c| c["restartCount"].as_i64().unwrap_or(0))
+            .sum();
+        restarts_total += restarts;
+
+        let status = pod_status(pod);
+        if status == "Running" && containers.iter().any(|c| c["ready"] == false) {
+            not_ready += 1;
         }
 
-        match phase {
-            "Running" => running += 1,
-            "Pending" => {
-                pending += 1;
-                issues.push(format!("{}/{} Pending", ns, name));
-            }
-            "Failed" | "Error" => {
-                failed += 1;
-                issues.push(format!("{}/{} {}", ns, name, phase));
-            }
-            _ => {
-                if let Some(containers) = pod["status"]["containerStatuses"].as_array() {
-                    for c in containers {
-                        if let Some(w) = c["state"]["waiting"]["reason"].as_str() {
-                            if w.contains("CrashLoop") || w.contains("Error") {
-                                failed += 1;
-                                issues.push(format!("{}/{} {}", ns, name, w));
-                            }
-                        }
-                    }
-                }
-            }
+        let mut label = format!("{}/{}", ns, name);
+        if restarts > 0 {
+            label.push_str(&format!(" ({} restarts)", restarts));
+        }
+        if let Some(node) = pod["spec"]["nodeName"].as_str() {
+            label.push_str(&format!(" @{}", node));
+        }
+        match groups.iter_mut().find(|(s, _)| *s == status) {
+            Some((_, names)) => names.push(label),
+            None => groups.push((status, vec![label])),
         }
     }
 
-    let mut parts = Vec::new();
-    if running > 0 {
-        parts.push(format!("{} ✓", running));
+    // Healthy statuses first, then problems by size
+    groups.sort_by_key(|(status, names)| {
+        (!is_healthy_status(status), std::cmp::Reverse(names.len()))
+    });
+
+    let mut rtk = format!("☸️  {} pods", pods.len());
+    let mut extras = Vec::new();
+    if not_ready > 0 {
+        extras.push(format!("{} running but not ready", not_ready));
     }
-    if pending > 0 {
-        parts.push(format!("{} pending", pending));
+    if restarts_total > 0 {
+        extras.push(format!("{} restarts", restarts_total));
     }
-    if failed > 0 {
-        parts.push(format!("{} ✗", failed));
+    if !extras.is_empty() {
+        rtk.push_str(&format!(" ({})", extras.join(", ")));
     }
-    if restarts_total > 0 {
-        parts.push(format!("{} restarts", restarts_total));
+    rtk.push('\n');
+
+    for (status, names) in &groups {
+        if is_healthy_status(status) {
+            rtk.push_str(&format!("  {} {}\n", status, names.len()));
+            continue;
+        }
+        rtk.push_str(&format!("  ⚠️  {} {}:\n", status, names.len()));
+        for name in names.iter().take(MAX_PODS_PER_STATUS) {
+            rtk.push_str(&format!("    {}\n", name));
+        }
+        if names.len() > MAX_PODS_PER_STATUS {
+            rtk.push_str(&format!(
+                "    ... +{} more\n",
+                names.len() - MAX_PODS_PER_STATUS
+            ));
+        }
     }
+    rtk
+}
 
-    rtk.push_str(&format!("☸️  {} pods: {}\n", pods.len(), parts.join(", ")));
-    if !issues.is_empty() {
-        rtk.push_str("⚠️  Issues:\n");
-        for issue in issues.iter().take(10) {
-            rtk.push_str(&format!("  {}\n", issue));
+/// The STATUS column of `kubectl get pods`: a container's waiting or
+/// terminated reason wins over the pod phase
+fn pod_status(pod: &serde_json::Value) -> String {
+    if pod["metadata"]["deletionTimestamp"].is_string() {
+        return "Terminating".to_string();
+    }
+    let phase = pod["status"]["phase"].as_str().unwrap_or("Unknown");
+    let statuses = pod["status"]["initContainerStatuses"]
+        .as_array()
+        .into_iter()
+        .chain(pod["status"]["containerStatuses"].as_array())
+        .flatten();
+    for c in statuses {
+        let state = &c["state"];
+        if let Some(reason) = state["waiting"]["reason"].as_str() {
+            return reason.to_string();
         }
-        if issues.len() > 10 {
-            rtk.push_str(&format!("  ... +{} more", issues.len() - 10));
+        if let Some(reason) = state["terminated"]["reason"].as_str() {
+            if reason != "Completed" {
+                return reason.to_string();
+            }
         }
     }
+    pod["status"]["reason"]
+        .as_str()
+        .unwrap_or(phase)
+        .to_string()
+}
+
+fn is_healthy_status(status: &str) -> bool {
+    matches!(status, "Running" | "Succeeded" | "Completed")
+}
+
+/// Events kept from each `kubectl describe` Events table
+const MAX_DESCRIBE_EVENTS: usize = 5;
+
+/// Top-level `kubectl describe` sections that are almost always noise
+const DESCRIBE_NOISE_SECTIONS: [&str; 5] = [
+    "Annotations",
+    "Volumes",
+    "Tolerations",
+    "Node-Selectors",
+    "QoS Class",
+];
+
+/// Nested `kubectl describe` fields (with their children) dropped as noise
+const DESCRIBE_NOISE_FIELDS: [&str; 5] = [
+    "Container ID",
+    "Image ID",
+    "Mounts",
+    "Host Port",
+    "Host Ports",
+];
+
+/// Run `kubectl describe` with noise sections dropped and events deduplicated
+pub fn run_kubectl_describe(args: &[String], verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
 
+    if verbose > 0 {
+        eprintln!("Running: kubectl describe {}", args.join(" "));
+    }
+
+    let output = Command::new("kubectl")
+        .arg("describe")
+        .args(args)
+        .output()
+        .context("Failed to run kubectl describe")?;
+    let raw = String::from_utf8_lossy(&output.stdout).to_string();
+    if !output.status.success() {
+        eprint!("{}", String::from_utf8_lossy(&output.stderr));
+        std::process::exit(output.status.code().unwrap_or(1));
+    }
+
+    let rtk = format_kubectl_describe(&raw);
     print!("{}", rtk);
-    timer.track("kubectl get pods", "rtk kubectl pods", &raw, &rtk);
+    timer.track(
+        &format!("kubectl describe {}", args.join(" ")),
+        &format!("rtk kubectl describe {}", args.join(" ")),
+        &raw,
+        &rtk,
+    );
     Ok(())
 }
 
+/// Compact `kubectl describe` output: noise sections and fields are dropped
+/// and each Events table is reduced to its last few distinct events
+pub fn format_kubectl_describe(raw: &str) -> String {
+    let mut out = String::new();
+    // Indent of the field being skipped; its deeper lines are skipped too
+    let mut skip_indent: Option<usize> = None;
+    let mut events: Option<EventsTable> = None;
+
+    for line in raw.lines() {
+        let indent = line.len() - line.trim_start().len();
+        let trimmed = line.trim();
+        let top_level = indent == 0 && !trimmed.is_empty();
+
+        if let Some(table) = events.as_mut() {
+            if !top_level && !trimmed.is_empty() {
+                table.push(line);
+                continue;
+            }
+            out.push_str(&events.take().map(|t| t.render()).unwrap_or_default());
+        }
+
+        if let Some(skip) = skip_indent {
+            if trimmed.is_empty() || indent > skip {
+                continue;
+            }
+            skip_indent = None;
+        }
+
+        let key = trimmed.split(':').next().unwrap_or("");
+        if top_level && key == "Events" {
+            events = Some(EventsTable::default());
+            if trimmed.ends_with("<none>") {
+                out.push_str("Events: <none>\n");
+                events = None;
+            }
+            continue;
+        }
+        let noise = if top_level {
+            DESCRIBE_NOISE_SECTIONS.contains(&key)
+        } else {
+            DESCRIBE_NOISE_FIELDS.contains(&key)
+        };
+        if noise && trimmed.contains(':') {
+            skip_indent = Some(indent);
+            continue;
+        }
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    if let Some(table) = events {
+        out.push_str(&table.render());
+    }
+    out
+}
+
+/// The `Type Reason Age From Message` table of `kubectl describe`
+#[derive(Default)]
+struct EventsTable {
+    /// Byte offset where the Message column starts
+    message_col: Option<usize>,
+    /// (type, reason, age, message), oldest first
+    rows: Vec<(String, String, String, String)>,
+}
+
+impl EventsTable {
+    fn push(&mut self, line: &str) {
+        let trimmed = line.trim();
+        if trimmed.starts_with("Type") && trimmed.contains("Message") {
+            self.message_col = line.find("Message");
+            return;
+        }
+        if trimmed.starts_with("----") {
+            return;
+        }
+        let mut fields = trimmed.split_whitespace();
+        let kind = fields.next().unwrap_or("").to_string();
+        let reason = fields.next().unwrap_or("").to_string();
+        let (head, message) = match self.message_col {
+            Some(col) if line.is_char_boundary(col) && col < line.len() => {
+                (&line[..col], line[col..].trim().to_string())
+            }
+            _ => (line, String::new()),
+        };
+        // Age is the third column, possibly "2m (x40 over 10m)"; From is last
+        let columns: Vec<&str> = head.split_whitespace().skip(2).collect();
+        let age = columns[..columns.len().saturating_sub(1)].join(" ");
+        self.rows.push((kind, reason, age, message));
+    }
+
+    fn render(&self) -> String {
+        let total = self.rows.len();
+        // Latest occurrence of each distinct (type, reason, message)
+        let mut distinct: Vec<&(String, String, String, String)> = Vec::new();
+        for row in self.rows.iter().rev() {
+            if !distinct
+                .iter()
+                .any(|d| d.0 == row.0 && d.1 == row.1 && d.3 == row.3)
+            {
+                distinct.push(row);
+            }
+        }
+        let shown: Vec<_> = distinct.iter().take(MAX_DESCRIBE_EVENTS).rev().collect();
+
+        let mut out = if shown.len() < total {
+            format!("Events (last {} distinct of {}):\n", shown.len(), total)
+        } else {
+            "Events:\n".to_string()
+        };
+        for (kind, reason, age, message) in shown {
+            out.push_str(&format!("  {} {} {}: {}\n", kind, reason, age, message));
+        }
+        out
+    }
+}
+
 fn kubectl_services(args: &[String], _verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
@@ -692,6 +1231,30 @@ pub fn run_compose_passthrough(args: &[OsString], verbose: u8) -> Result<()> {
     Ok(())
 }
 
+/// `kubectl get pods ...` (any `-o` format) gets the grouped pod summary;
+/// other resources pass through
+pub fn run_kubectl_get(args: &[String], verbose: u8) -> Result<()> {
+    if !matches!(
+        args.first().map(String::as_str),
+        Some("pods" | "pod" | "po")
+    ) {
+        let mut passthrough = vec![OsString::from("get")];
+        passthrough.extend(args.iter().map(OsString::from));
+        return run_kubectl_passthrough(&passthrough, verbose);
+    }
+
+    let mut rest = Vec::new();
+    let mut iter = args[1..].iter();
+    while let Some(arg) = iter.next() {
+        if arg == "-o" || arg == "--output" {
+            iter.next();
+        } else if !arg.starts_with("-o") && !arg.starts_with("--output=") {
+            rest.push(arg.clone());
+        }
+    }
+    kubectl_pods(&rest, verbose)
+}
+
 /// Runs an unsupported kubectl subcommand by passing it through directly
 pub fn run_kubectl_passthrough(args: &[OsString], verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
@@ -719,6 +1282,7 @@ pub fn run_kubectl_passthrough(args: &[OsString], verbose: u8) -> Result<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::stable::assert_format;
 
     // ── format_compose_ps ──────────────────────────────────
 
@@ -834,6 +1398,224 @@ api-1  | Connected to database";
         );
     }
 
+    // ── docker ps / images / build ─────────────────────────
+
+    #[test]
+    fn test_format_docker_ps_table() {
+        let raw = "api\tghcr.io/acme/api:1.4\tUp 3 hours (healthy)\t0.0.0.0:8080->8080/tcp\t12.3kB (virtual 150MB)\n\
+                   db\tpostgres:16\tExited (1) 2 days ago\t\t1MB (virtual 400MB)\n";
+        let out = format_docker_ps(raw);
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(
+            lines[0],
+            "🐳 2 containers (1 up, 1 stopped), 988.6KB writable"
+        );
+        assert_eq!(lines[1], "  NAME  IMAGE        STATUS           PORTS");
+        assert_eq!(lines[2], "  api   api:1.4      up 3h (healthy)  8080");
+        assert_eq!(lines[3], "  db    postgres:16  exited(1) 2d     -");
+        assert_format!("docker_ps", out);
+        assert_eq!(format_docker_ps(""), "🐳 0 containers\n");
+    }
+
+    #[test]
+    fn test_format_docker_images_rollup() {
+        let raw = "myapp\tlatest\t1.2GB\t2 hours ago\n\
+                   myapp\t1.1\t1.1GB\t3 days ago\n\
+                   <none>\t<none>\t300MB\t4 days ago\n\
+                   redis\t7\t130MB\t5 weeks ago\n";
+        let out = format_docker_images(raw);
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(
+            lines[0],
+            "🐳 4 images in 2 repos (2.5GB), 1 dangling (286.1MB)"
+        );
+        assert_eq!(lines[1], "  REPOSITORY  TAGS         SIZE     NEWEST");
+        assert_eq!(lines[2], "  myapp       latest, 1.1  2.1GB    2h");
+        assert_eq!(lines[3], "  redis       7            124.0MB  5w");
+    }
+
+    #[test]
+    fn test_parse_docker_size() {
+        assert_eq!(parse_docker_size("12.3kB"), 12_300);
+        assert_eq!(parse_docker_size("1.5GB"), 1_500_000_000);
+        assert_eq!(parse_docker_size("0B"), 0);
+        assert_eq!(parse_docker_size("N/A"), 0);
+    }
+
+    const BUILD_PLAIN: &str = "\
+#0 building with \"default\" instance using docker driver
+
+#1 [internal] load build definition from Dockerfile
+#1 transferring dockerfile: 372B done
+#1 DONE 0.0s
+
+#2 [internal] load metadata for docker.io/library/rust:1.75
+#2 DONE 1.1s
+
+#3 [builder 1/3] FROM docker.io/library/rust:1.75@sha256:abc
+#3 DONE 0.0s
+
+#4 [builder 2/3] WORKDIR /app
+#4 CACHED
+
+#5 [builder 3/3] RUN cargo build --release
+#5 0.512    Compiling app v0.1.0 (/app)
+#5 44.90    Finished release [optimized] target(s) in 44.3s
+#5 DONE 45.2s
+
+#6 [stage-1 1/2] FROM docker.io/library/debian:bookworm-slim
+#6 CACHED
+
+#7 [stage-1 2/2] COPY --from=builder /app/target/release/app /usr/local/bin/
+#7 DONE 0.1s
+
+#8 exporting to image
+#8 exporting layers 0.2s done
+#8 naming to docker.io/library/myapp:latest done
+#8 DONE 0.3s
+";
+
+    #[test]
+    fn test_format_docker_build_stages() {
+        let out = format_docker_build(BUILD_PLAIN, true);
+        assert_eq!(
+            out,
+            "🐳 Build ok: myapp:latest\n  \
+             builder  3 steps, 1 cached, 45.2s\n  \
+             stage-1  2 steps, 1 cached, 0.1s\n  \
+             📊 5 steps, 2 cached (40%), 45.3s"
+        );
+        assert!(!out.contains("Compiling"));
+    }
+
+    #[test]
+    fn test_format_docker_build_error_verbatim() {
+        let raw = "\
+#5 [3/3] RUN cargo build --release
+#5 0.512 error[E0425]: cannot find value `x` in this scope
+#5 ERROR: process \"/bin/sh -c cargo build --release\" did not complete successfully: exit code: 101
+------
+ > [3/3] RUN cargo build --release:
+0.512 error[E0425]: cannot find value `x` in this scope
+------
+ERROR: failed to solve: process \"/bin/sh -c cargo build --release\" did not complete successfully: exit code: 101
+";
+        let out = format_docker_build(raw, false);
+        assert!(out.starts_with("🐳 Build failed\n  build  1 steps, 0 cached, 0.0s\n"));
+        assert!(out.contains("------\n > [3/3] RUN cargo build --release:\n0.512 error[E0425]"));
+        assert!(out.ends_with("exit code: 101"));
+    }
+
+    // ── kubectl pods / describe ────────────────────────────
+
+    fn pod(name: &str, phase: &str, waiting: Option<&str>, restarts: i64) -> serde_json::Value {
+        let state = match waiting {
+            Some(reason) => serde_json::json!({"waiting": {"reason": reason}}),
+            None => serde_json::json!({"running": {}}),
+        };
+        serde_json::json!({
+            "metadata": {"namespace": "prod", "name": name},
+            "spec": {"nodeName": "node-1"},
+            "status": {
+                "phase": phase,
+                "containerStatuses": [
+                    {"ready": waiting.is_none(), "restartCount": restarts, "state": state}
+                ]
+            }
+        })
+    }
+
+    #[test]
+    fn test_format_kubectl_pods_groups_by_status() {
+        let mut items: Vec<serde_json::Value> = (0..14)
+            .map(|i| pod(&format!("web-{}", i), "Running", None, 0))
+            .collect();
+        items.push(pod("api-7f9c", "Running", Some("CrashLoopBackOff"), 12));
+        items.push(pod("worker-x", "Running", Some("CrashLoopBackOff"), 3));
+        items.push(pod("migrate", "Pending", Some("ImagePullBackOff"), 0));
+        let out = format_kubectl_pods(&serde_json::json!({ "items": items }));
+        assert_eq!(
+            out,
+            "☸️  17 pods (15 restarts)\n\
+             \x20 Running 14\n\
+             \x20 ⚠️  CrashLoopBackOff 2:\n\
+             \x20   prod/api-7f9c (12 restarts) @node-1\n\
+             \x20   prod/worker-x (3 restarts) @node-1\n\
+             \x20 ⚠️  ImagePullBackOff 1:\n\
+             \x20   prod/migrate @node-1\n"
+        );
+        assert_eq!(
+            format_kubectl_pods(&serde_json::json!({"items": []})),
+            "☸️  No pods found\n"
+        );
+    }
+
+    #[test]
+    fn test_pod_status_terminating_and_completed() {
+        let mut p = pod("job-1", "Succeeded", None, 0);
+        p["status"]["containerStatuses"][0]["state"] =
+            serde_json::json!({"terminated": {"reason": "Completed"}});
+        assert_eq!(pod_status(&p), "Succeeded");
+        p["metadata"]["deletionTimestamp"] = serde_json::json!("2024-01-01T00:00:00Z");
+        assert_eq!(pod_status(&p), "Terminating");
+    }
+
+    #[test]
+    fn test_format_kubectl_describe_drops_noise_and_dedupes_events() {
+        let raw = "\
+Name:             api-7f9c
+Namespace:        prod
+Annotations:      kubectl.kubernetes.io/restartedAt: 2024-01-01
+                  checksum/config: abc123
+Status:           Running
+Containers:
+  api:
+    Container ID:   containerd://0123456789abcdef
+    Image:          ghcr.io/acme/api:1.4
+    State:          Waiting
+      Reason:       CrashLoopBackOff
+    Mounts:
+      /var/run/secrets/kubernetes.io/serviceaccount from kube-api-access (ro)
+    Restart Count:  12
+Volumes:
+  kube-api-access:
+    Type:                    Projected
+QoS Class:                   BestEffort
+Tolerations:                 node.kubernetes.io/not-ready:NoExecute op=Exists for 300s
+Events:
+  Type     Reason     Age                  From               Message
+  ----     ------     ----                 ----               -------
+  Normal   Scheduled  10m                  default-scheduler  Successfully assigned prod/api-7f9c to node-1
+  Normal   Pulled     9m                   kubelet            Container image already present
+  Normal   Created    9m                   kubelet            Created container api
+  Normal   Started    9m                   kubelet            Started container api
+  Normal   Pulled     8m                   kubelet            Container image already present
+  Warning  Unhealthy  8m                   kubelet            Liveness probe failed: connection refused
+  Warning  BackOff    2m (x40 over 9m)     kubelet            Back-off restarting failed container
+";
+        let out = format_kubectl_describe(raw);
+        assert_eq!(
+            out,
+            "\
+Name:             api-7f9c
+Namespace:        prod
+Status:           Running
+Containers:
+  api:
+    Image:          ghcr.io/acme/api:1.4
+    State:          Waiting
+      Reason:       CrashLoopBackOff
+    Restart Count:  12
+Events (last 5 distinct of 7):
+  Normal Created 9m: Created container api
+  Normal Started 9m: Started container api
+  Normal Pulled 8m: Container image already present
+  Warning Unhealthy 8m: Liveness probe failed: connection refused
+  Warning BackOff 2m (x40 over 9m): Back-off restarting failed container
+"
+        );
+    }
+
     // ── compact_ports (existing, previously untested) ──────
 
     #[test]
@@ -0,0 +1,629 @@
+//! `rtk count`: lines, words and bytes for files or directories, with code,
+//! comment and blank lines per language and file counts per extension, in
+//! place of `wc`, `cloc` and `find | wc -l` pipelines.
+//!
+//! Comments are found by a small lexer per language (line and block
+//! comments, string literals, Python docstrings), which is enough for
+//! counting and needs no parser.
+
+use crate::tracking;
+use crate::utils::format_bytes;
+use crate::walk;
+use anyhow::Result;
+use ignore::WalkBuilder;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Languages listed before the rest are summed up
+const MAX_LANGUAGES: usize = 12;
+/// Extensions listed
+const MAX_EXTENSIONS: usize = 10;
+/// Bytes checked for a NUL to tell binary files apart
+const BINARY_SNIFF: usize = 8192;
+
+/// Comment syntax of a language
+#[derive(Debug)]
+struct Syntax {
+    name: &'static str,
+    extensions: &'static [&'static str],
+    /// File names without a telling extension (`Makefile`)
+    file_names: &'static [&'static str],
+    line: &'static [&'static str],
+    block: Option<(&'static str, &'static str)>,
+    /// Quotes opening string literals, inside which nothing is a comment
+    quotes: &'static [char],
+    /// `"""` and `'''` opening a line are comments (Python docstrings)
+    docstrings: bool,
+}
+
+const C_LIKE: &[&str] = &["//"];
+const HASH: &[&str] = &["#"];
+const DOUBLE: &[char] = &['"'];
+const BOTH: &[char] = &['"', '\''];
+const C_BLOCK: Option<(&str, &str)> = Some(("/*", "*/"));
+const XML_BLOCK: Option<(&str, &str)> = Some(("<!--", "-->"));
+
+const fn syntax(
+    name: &'static str,
+    extensions: &'static [&'static str],
+    line: &'static [&'static str],
+    block: Option<(&'static str, &'static str)>,
+    quotes: &'static [char],
+) -> Syntax {
+    Syntax {
+        name,
+        extensions,
+        file_names: &[],
+        line,
+        block,
+        quotes,
+        docstrings: false,
+    }
+}
+
+const LANGUAGES: &[Syntax] = &[
+    // Rust quotes only `"`: `'` also starts lifetimes
+    syntax("Rust", &["rs"], C_LIKE, C_BLOCK, DOUBLE),
+    Syntax {
+        docstrings: true,
+        ..syntax("Python", &["py", "pyw", "pyi"], HASH, None, BOTH)
+    },
+    syntax(
+        "JavaScript",
+        &["js", "mjs", "cjs", "jsx"],
+        C_LIKE,
+        C_BLOCK,
+        &['"', '\'', '`'],
+    ),
+    syntax(
+        "TypeScript",
+        &["ts", "tsx", "mts", "cts"],
+        C_LIKE,
+        C_BLOCK,
+        &['"', '\'', '`'],
+    ),
+    syntax("Go", &["go"], C_LIKE, C_BLOCK, &['"', '`']),
+    syntax("C", &["c", "h"], C_LIKE, C_BLOCK, BOTH),
+    syntax(
+        "C++",
+        &["cpp", "cc", "cxx", "hpp", "hh", "hxx"],
+        C_LIKE,
+        C_BLOCK,
+        BOTH,
+    ),
+    syntax("C#", &["cs"], C_LIKE, C_BLOCK, BOTH),
+    syntax("Java", &["java"], C_LIKE, C_BLOCK, BOTH),
+    syntax("Kotlin", &["kt", "kts"], C_LIKE, C_BLOCK, BOTH),
+    syntax("Swift", &["swift"], C_LIKE, C_BLOCK, DOUBLE),
+    syntax("Scala", &["scala"], C_LIKE, C_BLOCK, DOUBLE),
+    syntax("PHP", &["php"], &["//", "#"], C_BLOCK, BOTH),
+    syntax("Ruby", &["rb"], HASH, Some(("=begin", "=end")), BOTH),
+    Syntax {
+        file_names: &["Makefile", "makefile", "GNUmakefile"],
+        ..syntax("Make", &["mk"], HASH, None, BOTH)
+    },
+    Syntax {
+        file_names: &["Dockerfile"],
+        ..syntax("Dockerfile", &["dockerfile"], HASH, None, BOTH)
+    },
+    syntax("Shell", &["sh", "bash", "zsh", "fish"], HASH, None, BOTH),
+    syntax(
+        "PowerShell",
+        &["ps1", "psm1"],
+        HASH,
+        Some(("<#", "#>")),
+        BOTH,
+    ),
+    syntax("Lua", &["lua"], &["--"], Some(("--[[", "]]")), BOTH),
+    syntax("SQL", &["sql"], &["--"], C_BLOCK, &['\'']),
+    syntax(
+        "HTML",
+        &["html", "htm", "vue", "svelte"],
+        &[],
+        XML_BLOCK,
+        &[],
+    ),
+    syntax("XML", &["xml", "svg", "xsd"], &[], XML_BLOCK, &[]),
+    syntax("CSS", &["css"], &[], C_BLOCK, BOTH),
+    syntax("SCSS", &["scss", "sass", "less"], C_LIKE, C_BLOCK, BOTH),
+    syntax("YAML", &["yml", "yaml"], HASH, None, BOTH),
+    syntax("TOML", &["toml"], HASH, None, BOTH),
+    syntax("JSON", &["json"], &[], None, DOUBLE),
+    syntax("Markdown", &["md", "markdown"], &[], XML_BLOCK, &[]),
+];
+
+fn language_of(path: &Path) -> Option<&'static Syntax> {
+    let name = path.file_name()?.to_str()?;
+    if let Some(syntax) = LANGUAGES.iter().find(|s| s.file_names.contains(&name)) {
+        return Some(syntax);
+    }
+    let ext = path.extension()?.to_str()?.to_lowercase();
+    LANGUAGES
+        .iter()
+        .find(|s| s.extensions.contains(&ext.as_str()))
+}
+
+/// Code, comment and blank lines
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct Lines {
+    code: usize,
+    comment: usize,
+    blank: usize,
+}
+
+impl Lines {
+    fn add(&mut self, other: Lines) {
+        self.code += other.code;
+        self.comment += other.comment;
+        self.blank += other.blank;
+    }
+
+    fn total(&self) -> usize {
+        self.code + self.comment + self.blank
+    }
+}
+
+/// Classify each line of `text`: blank, comment only, or holding code
+fn count_lines(text: &str, syntax: &Syntax) -> Lines {
+    let mut lines = Lines::default();
+    let mut in_block: Option<&'static str> = None;
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            lines.blank += 1;
+        } else if scan_line(trimmed, syntax, &mut in_block) {
+            lines.code += 1;
+        } else {
+            lines.comment += 1;
+        }
+    }
+    lines
+}
+
+/// Whether `line` holds code outside comments. `in_block` carries the end
+/// of an open block comment from one line to the next.
+fn scan_line(line: &str, syntax: &Syntax, in_block: &mut Option<&'static str>) -> bool {
+    let mut code = false;
+    let mut rest = line;
+    while !rest.is_empty() {
+        if let Some(end) = *in_block {
+            match rest.find(end) {
+                Some(i) => {
+                    rest = &rest[i + end.len()..];
+                    *in_block = None;
+                    continue;
+                }
+                None => return code,
+            }
+        }
+        if syntax.docstrings && !code {
+            if let Some(quote) = ["\"\"\"", "'''"].into_iter().find(|q| rest.starts_with(q)) {
+                *in_block = Some(quote);
+                rest = &rest[quote.len()..];
+                continue;
+            }
+        }
+        // Blocks first: Lua's `--[[` also starts with its line comment
+        if let Some((start, end)) = syntax.block {
+            if rest.starts_with(start) {
+                *in_block = Some(end);
+                rest = &rest[start.len()..];
+                continue;
+            }
+        }
+        if syntax.line.iter().any(|l| rest.starts_with(l)) {
+            return code;
+        }
+        let c = rest.chars().next().expect("rest is not empty");
+        rest = &rest[c.len_utf8()..];
+        if syntax.quotes.contains(&c) {
+            code = true;
+            // Strings end on this line; an unterminated one ends it
+            match string_end(rest, c) {
+                Some(i) => rest = &rest[i..],
+                None => return code,
+            }
+        } else if !c.is_whitespace() {
+            code = true;
+        }
+    }
+    code
+}
+
+/// Offset just past the closing `quote` in `text`, skipping escapes
+fn string_end(text: &str, quote: char) -> Option<usize> {
+    let mut escaped = false;
+    for (i, c) in text.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == quote {
+            return Some(i + c.len_utf8());
+        }
+    }
+    None
+}
+
+/// What one file holds
+#[derive(Debug, Clone, PartialEq)]
+struct FileCount {
+    /// Lowercased extension, empty without one
+    extension: String,
+    language: Option<&'static str>,
+    bytes: u64,
+    /// `None` for binary files
+    text: Option<TextCount>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct TextCount {
+    lines: Lines,
+    words: usize,
+}
+
+fn count_file(path: &Path, bytes: &[u8]) -> FileCount {
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let syntax = language_of(path);
+    let binary = bytes[..bytes.len().min(BINARY_SNIFF)].contains(&0);
+    let text = (!binary).then(|| {
+        let text = String::from_utf8_lossy(bytes);
+        let lines = match syntax {
+            Some(syntax) => count_lines(&text, syntax),
+            // Without known comment syntax every non-blank line is content
+            None => {
+                let blank = text.lines().filter(|l| l.trim().is_empty()).count();
+                Lines {
+                    code: text.lines().count() - blank,
+                    comment: 0,
+                    blank,
+                }
+            }
+        };
+        TextCount {
+            lines,
+            words: text.split_whitespace().count(),
+        }
+    });
+    FileCount {
+        extension,
+        language: syntax.map(|s| s.name),
+        bytes: bytes.len() as u64,
+        text,
+    }
+}
+
+#[derive(Debug, Default)]
+struct Report {
+    files: usize,
+    lines: usize,
+    words: usize,
+    bytes: u64,
+    binary: usize,
+    /// Per language: files and lines
+    languages: BTreeMap<&'static str, (usize, Lines)>,
+    /// Text files in no known language: files and lines
+    other: (usize, usize),
+    extensions: BTreeMap<String, usize>,
+}
+
+fn summarize(counts: &[FileCount]) -> Report {
+    let mut report = Report::default();
+    for count in counts {
+        report.files += 1;
+        report.bytes += count.bytes;
+        *report
+            .extensions
+            .entry(count.extension.clone())
+            .or_default() += 1;
+        let Some(text) = count.text else {
+            report.binary += 1;
+            continue;
+        };
+        report.lines += text.lines.total();
+        report.words += text.words;
+        match count.language {
+            Some(name) => {
+                let entry = report.languages.entry(name).or_default();
+                entry.0 += 1;
+                entry.1.add(text.lines);
+            }
+            None => {
+                report.other.0 += 1;
+                report.other.1 += text.lines.total();
+            }
+        }
+    }
+    report
+}
+
+fn format_report(report: &Report) -> String {
+    let mut out = format!(
+        "📊 {} files: {} lines, {} words, {}",
+        report.files,
+        report.lines,
+        report.words,
+        format_bytes(report.bytes)
+    );
+    if report.files == 0 {
+        return out;
+    }
+
+    let mut languages: Vec<(&str, usize, Lines)> = report
+        .languages
+        .iter()
+        .map(|(name, (files, lines))| (*name, *files, *lines))
+        .collect();
+    languages.sort_by(|a, b| b.2.code.cmp(&a.2.code).then(a.0.cmp(b.0)));
+    let mut total = Lines::default();
+    for (_, _, lines) in &languages {
+        total.add(*lines);
+    }
+    let shown = languages.len().min(MAX_LANGUAGES);
+    let mut rows: Vec<[String; 5]> = vec![[
+        "Language".into(),
+        "Files".into(),
+        "Code".into(),
+        "Comment".into(),
+        "Blank".into(),
+    ]];
+    let row = |name: String, files: usize, lines: Lines| {
+        [
+            name,
+            files.to_string(),
+            lines.code.to_string(),
+            lines.comment.to_string(),
+            lines.blank.to_string(),
+        ]
+    };
+    for (name, files, lines) in &languages[..shown] {
+        rows.push(row(name.to_string(), *files, *lines));
+    }
+    if languages.len() > shown {
+        let mut rest = Lines::default();
+        let mut files = 0;
+        for (_, f, lines) in &languages[shown..] {
+            rest.add(*lines);
+            files += f;
+        }
+        rows.push(row(
+            format!("+{} more", languages.len() - shown),
+            files,
+            rest,
+        ));
+    }
+    if languages.len() > 1 {
+        let files = languages.iter().map(|(_, f, _)| f).sum();
+        rows.push(row("Total".into(), files, total));
+    }
+    if !languages.is_empty() {
+        out.push_str(&format_table(&rows));
+    }
+
+    let (other_files, other_lines) = report.other;
+    if other_files > 0 || report.binary > 0 {
+        let mut parts = Vec::new();
+        if other_files > 0 {
+            parts.push(format!(
+                "{} other text files ({} lines)",
+                other_files, other_lines
+            ));
+        }
+        if report.binary > 0 {
+            parts.push(format!("{} binary", report.binary));
+        }
+        out.push_str(&format!("\n  +{}", parts.join(", ")));
+    }
+
+    let mut extensions: Vec<(&String, &usize)> = report.extensions.iter().collect();
+    extensions.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+    let listed: Vec<String> = extensions
+        .iter()
+        .take(MAX_EXTENSIONS)
+        .map(|(ext, n)| {
+            let ext = if ext.is_empty() {
+                "(none)".to_string()
+            } else {
+                format!(".{}", ext)
+            };
+            format!("{} {}", ext, n)
+        })
+        .collect();
+    out.push_str(&format!("\nExtensions: {}", listed.join(", ")));
+    if extensions.len() > MAX_EXTENSIONS {
+        out.push_str(&format!(", +{} more", extensions.len() - MAX_EXTENSIONS));
+    }
+    out
+}
+
+/// Left-aligned first column, right-aligned numbers
+fn format_table(rows: &[[String; 5]]) -> String {
+    let mut widths = [0; 5];
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let mut out = String::new();
+    for row in rows {
+        out.push_str(&format!("\n  {:<w$}", row[0], w = widths[0]));
+        for (cell, width) in row[1..].iter().zip(&widths[1..]) {
+            out.push_str(&format!("  {:>w$}", cell, w = width));
+        }
+    }
+    out
+}
+
+/// Files under `paths` (gitignored ones too with `all`) and their counts
+fn count_paths(paths: &[PathBuf], all: bool) -> (Vec<(PathBuf, FileCount)>, Vec<String>) {
+    let mut builder = WalkBuilder::new(&paths[0]);
+    for path in &paths[1..] {
+        builder.add(path);
+    }
+    builder.hidden(false);
+    if all {
+        builder.standard_filters(false);
+    } else {
+        builder.require_git(false);
+    }
+    builder.filter_entry(|entry| entry.file_name() != ".git");
+    let walked = walk::walk(&builder, |entry| {
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            return None;
+        }
+        let bytes = std::fs::read(entry.path()).ok()?;
+        Some((entry.path().to_path_buf(), count_file(entry.path(), &bytes)))
+    });
+    (walked.items, walked.errors)
+}
+
+/// `wc -l -w -c` of every file, as the raw output rtk replaces
+fn wc_listing(files: &[(PathBuf, FileCount)]) -> String {
+    files
+        .iter()
+        .filter_map(|(path, count)| {
+            let text = count.text?;
+            Some(format!(
+                "{:>8} {:>8} {:>8} {}",
+                text.lines.total(),
+                text.words,
+                count.bytes,
+                path.display()
+            ))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn run(paths: &[PathBuf], all: bool, verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+    let paths: Vec<PathBuf> = if paths.is_empty() {
+        vec![PathBuf::from(".")]
+    } else {
+        paths.to_vec()
+    };
+    if let Some(missing) = paths.iter().find(|p| !p.exists()) {
+        anyhow::bail!("No such file or directory: {}", missing.display());
+    }
+
+    let (files, errors) = count_paths(&paths, all);
+    if verbose > 0 {
+        eprintln!("Counted {} files", files.len());
+        for error in &errors {
+            eprintln!("count: {}", error);
+        }
+    }
+    let counts: Vec<FileCount> = files.iter().map(|(_, c)| c.clone()).collect();
+    let output = format_report(&summarize(&counts));
+    println!("{}", output);
+
+    let shown: Vec<String> = paths.iter().map(|p| p.display().to_string()).collect();
+    timer.track(
+        &format!("cloc {}", shown.join(" ")),
+        &format!("rtk count {}", shown.join(" ")),
+        &wc_listing(&files),
+        &output,
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines_of(name: &str, text: &str) -> Lines {
+        count_lines(text, language_of(Path::new(name)).unwrap())
+    }
+
+    #[test]
+    fn test_rust_comments_and_strings() {
+        let text = "\
+//! Module doc
+use std::fmt; // trailing comment is still code
+
+/* block
+   comment */
+let url = \"http://example.com\"; /* x */
+fn f<'a>(s: &'a str) {}
+";
+        assert_eq!(
+            lines_of("lib.rs", text),
+            Lines {
+                code: 3,
+                comment: 3,
+                blank: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_python_docstrings_and_hash() {
+        let text = "\
+\"\"\"Module docstring
+spanning lines.
+\"\"\"
+import os  # comment
+# only a comment
+x = \"# not a comment\"
+";
+        assert_eq!(
+            lines_of("a.py", text),
+            Lines {
+                code: 2,
+                comment: 4,
+                blank: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_language_by_name_and_extension() {
+        assert_eq!(language_of(Path::new("Makefile")).unwrap().name, "Make");
+        assert_eq!(
+            language_of(Path::new("src/App.TSX")).unwrap().name,
+            "TypeScript"
+        );
+        assert!(language_of(Path::new("notes.txt")).is_none());
+    }
+
+    #[test]
+    fn test_report() {
+        let counts = vec![
+            count_file(Path::new("src/main.rs"), b"fn main() {}\n// hi\n\n"),
+            count_file(Path::new("src/lib.rs"), b"pub mod a;\n"),
+            count_file(Path::new("build.py"), b"print(1)\n"),
+            count_file(Path::new("notes.txt"), b"two words\n"),
+            count_file(Path::new("logo.png"), b"\x89PNG\0\0"),
+        ];
+        let out = format_report(&summarize(&counts));
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines[0], "📊 5 files: 6 lines, 11 words, 56B");
+        assert_eq!(lines[1], "  Language  Files  Code  Comment  Blank");
+        assert_eq!(lines[2], "  Rust          2     2        1      1");
+        assert_eq!(lines[3], "  Python        1     1        0      0");
+        assert_eq!(lines[4], "  Total         3     3        1      1");
+        assert_eq!(lines[5], "  +1 other text files (1 lines), 1 binary");
+        assert_eq!(lines[6], "Extensions: .rs 2, .png 1, .py 1, .txt 1");
+    }
+
+    #[test]
+    fn test_count_paths_respects_gitignore() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(".gitignore"), "build/\n").unwrap();
+        std::fs::create_dir(dir.path().join("build")).unwrap();
+        std::fs::write(dir.path().join("build/out.js"), "x\n").unwrap();
+        std::fs::write(dir.path().join("a.rs"), "fn a() {}\n").unwrap();
+
+        let (files, _) = count_paths(&[dir.path().to_path_buf()], false);
+        let names: Vec<String> = files
+            .iter()
+            .map(|(p, _)| p.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(names, vec![".gitignore", "a.rs"]);
+        let (files, _) = count_paths(&[dir.path().to_path_buf()], true);
+        assert_eq!(files.len(), 3);
+    }
+}
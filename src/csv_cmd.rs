@@ -0,0 +1,493 @@
+use crate::tracking;
+use crate::utils::truncate;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+/// Delimiters tried by auto-detection, in order of preference on ties
+const DELIMITERS: [char; 4] = [',', '\t', ';', '|'];
+
+/// Lines inspected when detecting the delimiter
+const SNIFF_LINES: usize = 10;
+
+/// Distinct values tracked per column before reporting "N+"
+const MAX_DISTINCT: usize = 10_000;
+
+/// Sample values shown per column
+const SAMPLES: usize = 3;
+
+/// Max chars of a sample value or preview cell
+const MAX_CELL_LEN: usize = 30;
+
+/// Cell values treated as missing
+const NULL_MARKERS: [&str; 6] = ["", "NA", "N/A", "null", "NULL", "None"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum ColumnType {
+    /// Only nulls
+    Empty,
+    Bool,
+    Int,
+    Float,
+    Date,
+    String,
+}
+
+#[derive(Debug, Serialize)]
+struct ColumnStats {
+    name: String,
+    #[serde(rename = "type")]
+    kind: ColumnType,
+    nulls: usize,
+    distinct: usize,
+    /// Distinct count stopped at MAX_DISTINCT
+    distinct_capped: bool,
+    samples: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct CsvReport {
+    rows: usize,
+    delimiter: char,
+    columns: Vec<ColumnStats>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    preview: Vec<Vec<String>>,
+}
+
+/// Summarize a CSV/TSV file column by column instead of dumping rows.
+/// `rows` previews the first data rows; `delimiter` overrides detection.
+pub fn run(
+    file: &Path,
+    rows: usize,
+    delimiter: Option<&str>,
+    format: &str,
+    verbose: u8,
+) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    let (content, label) = if file == Path::new("-") {
+        let mut content = String::new();
+        io::stdin()
+            .lock()
+            .read_to_string(&mut content)
+            .context("Failed to read from stdin")?;
+        (content, "-".to_string())
+    } else {
+        let content = fs::read_to_string(file)
+            .with_context(|| format!("Failed to read file: {}", file.display()))?;
+        (content, file.display().to_string())
+    };
+
+    let delimiter = match delimiter {
+        Some(d) => parse_delimiter(d)?,
+        None if file.extension().is_some_and(|e| e == "tsv") => '\t',
+        None => detect_delimiter(&content),
+    };
+
+    if verbose > 0 {
+        eprintln!("Analyzing CSV: {} (delimiter {:?})", label, delimiter);
+    }
+
+    let report = analyze(&content, delimiter, rows);
+    let output = match format {
+        "json" => serde_json::to_string_pretty(&report)?,
+        _ => format_report(&label, &report),
+    };
+    println!("{}", output);
+
+    timer.track(&format!("cat {}", label), "rtk csv", &content, &output);
+    Ok(())
+}
+
+fn parse_delimiter(arg: &str) -> Result<char> {
+    match arg {
+        "tab" | "\\t" | "\t" => Ok('\t'),
+        _ => {
+            let mut chars = arg.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Ok(c),
+                _ => anyhow::bail!("Delimiter must be a single character (or 'tab'): {}", arg),
+            }
+        }
+    }
+}
+
+/// Pick the candidate that splits the first lines into the same, highest
+/// number of fields. Quoted sections are ignored.
+fn detect_delimiter(content: &str) -> char {
+    let lines: Vec<&str> = content
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .take(SNIFF_LINES)
+        .collect();
+
+    let mut best = (',', 0);
+    for delim in DELIMITERS {
+        let counts: Vec<usize> = lines
+            .iter()
+            .map(|line| count_unquoted(line, delim))
+            .collect();
+        let Some(&first) = counts.first() else {
+            break;
+        };
+        // A line cut by a quoted newline may disagree; require most lines to match
+        let consistent = counts.iter().filter(|&&c| c == first).count() * 2 > counts.len();
+        if first > best.1 && consistent {
+            best = (delim, first);
+        }
+    }
+    best.0
+}
+
+fn count_unquoted(line: &str, delim: char) -> usize {
+    let mut quoted = false;
+    line.chars()
+        .filter(|&c| {
+            if c == '"' {
+                quoted = !quoted;
+            }
+            c == delim && !quoted
+        })
+        .count()
+}
+
+/// Split CSV text into records (RFC 4180: quoted fields, doubled quotes,
+/// newlines inside quotes, CRLF line endings). Blank lines are skipped.
+fn parse_records(content: &str, delim: char) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if quoted {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => quoted = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+
+        match c {
+            '"' if field.is_empty() => quoted = true,
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                if record.len() == 1 && record[0].is_empty() {
+                    record.clear();
+                } else {
+                    records.push(std::mem::take(&mut record));
+                }
+            }
+            c if c == delim => record.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records
+}
+
+fn is_null(value: &str) -> bool {
+    NULL_MARKERS.contains(&value.trim())
+}
+
+fn value_type(value: &str) -> ColumnType {
+    let v = value.trim();
+    if v.eq_ignore_ascii_case("true") || v.eq_ignore_ascii_case("false") {
+        ColumnType::Bool
+    } else if v.parse::<i64>().is_ok() {
+        ColumnType::Int
+    } else if v.parse::<f64>().is_ok() {
+        ColumnType::Float
+    } else if is_date(v) {
+        ColumnType::Date
+    } else {
+        ColumnType::String
+    }
+}
+
+/// `YYYY-MM-DD`, optionally followed by a time
+fn is_date(v: &str) -> bool {
+    let b = v.as_bytes();
+    b.len() >= 10
+        && b[..4].iter().all(u8::is_ascii_digit)
+        && b[4] == b'-'
+        && b[5..7].iter().all(u8::is_ascii_digit)
+        && b[7] == b'-'
+        && b[8..10].iter().all(u8::is_ascii_digit)
+        && (b.len() == 10 || matches!(b[10], b'T' | b' '))
+}
+
+/// Widen a column type to cover a new value type
+fn widen(current: ColumnType, seen: ColumnType) -> ColumnType {
+    use ColumnType::*;
+    match (current, seen) {
+        (Empty, t) => t,
+        (a, b) if a == b => a,
+        (Int, Float) | (Float, Int) => Float,
+        _ => String,
+    }
+}
+
+fn analyze(content: &str, delim: char, preview_rows: usize) -> CsvReport {
+    let mut records = parse_records(content, delim).into_iter();
+    let header = records.next().unwrap_or_default();
+
+    let mut seen_names = HashSet::new();
+    let mut columns: Vec<ColumnStats> = header
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let name = name.trim();
+            let name = if name.is_empty() || !seen_names.insert(name.to_string()) {
+                format!("col{}", i + 1)
+            } else {
+                name.to_string()
+            };
+            ColumnStats {
+                name,
+                kind: ColumnType::Empty,
+                nulls: 0,
+                distinct: 0,
+                distinct_capped: false,
+                samples: Vec::new(),
+            }
+        })
+        .collect();
+    let mut distinct: Vec<HashSet<String>> = vec![HashSet::new(); columns.len()];
+
+    let mut rows = 0;
+    let mut preview = Vec::new();
+    for record in records {
+        rows += 1;
+        if preview.len() < preview_rows {
+            preview.push(record.clone());
+        }
+
+        for (i, col) in columns.iter_mut().enumerate() {
+            // Short rows: missing trailing cells count as null
+            let value = record.get(i).map(String::as_str).unwrap_or("");
+            if is_null(value) {
+                col.nulls += 1;
+                continue;
+            }
+            col.kind = widen(col.kind, value_type(value));
+
+            let set = &mut distinct[i];
+            if set.len() < MAX_DISTINCT {
+                if set.insert(value.to_string()) && col.samples.len() < SAMPLES {
+                    col.samples.push(truncate(value.trim(), MAX_CELL_LEN));
+                }
+            } else if !set.contains(value) {
+                col.distinct_capped = true;
+            }
+        }
+    }
+
+    for (col, set) in columns.iter_mut().zip(&distinct) {
+        col.distinct = set.len();
+    }
+
+    CsvReport {
+        rows,
+        delimiter: delim,
+        columns,
+        preview,
+    }
+}
+
+fn format_report(label: &str, report: &CsvReport) -> String {
+    if report.columns.is_empty() {
+        return format!("{}: empty", label);
+    }
+
+    let mut out = format!(
+        "{}: {} rows × {} columns (delimiter {})\n",
+        label,
+        report.rows,
+        report.columns.len(),
+        match report.delimiter {
+            '\t' => "tab".to_string(),
+            c => format!("'{}'", c),
+        }
+    );
+
+    let name_width = report
+        .columns
+        .iter()
+        .map(|c| c.name.chars().count().min(MAX_CELL_LEN))
+        .max()
+        .unwrap_or(0);
+
+    for col in &report.columns {
+        let null_pct = if report.rows > 0 {
+            col.nulls as f64 / report.rows as f64 * 100.0
+        } else {
+            0.0
+        };
+        let distinct = if col.distinct_capped {
+            format!("{}+", col.distinct)
+        } else {
+            col.distinct.to_string()
+        };
+        let mut line = format!(
+            "  {:<width$}  {:<6} {:>5.1}% null  {:>6} distinct",
+            truncate(&col.name, MAX_CELL_LEN),
+            type_label(col.kind),
+            null_pct,
+            distinct,
+            width = name_width
+        );
+        if !col.samples.is_empty() {
+            line.push_str(&format!("  e.g. {}", col.samples.join(", ")));
+        }
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+
+    if !report.preview.is_empty() {
+        out.push('\n');
+        out.push_str(&format_preview(report));
+    }
+
+    out.trim_end().to_string()
+}
+
+fn type_label(kind: ColumnType) -> &'static str {
+    match kind {
+        ColumnType::Empty => "empty",
+        ColumnType::Bool => "bool",
+        ColumnType::Int => "int",
+        ColumnType::Float => "float",
+        ColumnType::Date => "date",
+        ColumnType::String => "string",
+    }
+}
+
+/// First rows as an aligned table, cells cut to MAX_CELL_LEN
+fn format_preview(report: &CsvReport) -> String {
+    let header: Vec<String> = report.columns.iter().map(|c| c.name.clone()).collect();
+    let rows: Vec<Vec<String>> = std::iter::once(header)
+        .chain(report.preview.iter().cloned())
+        .map(|row| {
+            row.iter()
+                .map(|c| truncate(c.trim(), MAX_CELL_LEN))
+                .collect()
+        })
+        .collect();
+
+    let mut widths = vec![0; report.columns.len()];
+    for row in &rows {
+        for (w, cell) in widths.iter_mut().zip(row) {
+            *w = (*w).max(cell.chars().count());
+        }
+    }
+
+    let mut out = String::new();
+    for row in &rows {
+        let cells: Vec<String> = widths
+            .iter()
+            .enumerate()
+            .map(|(i, w)| {
+                format!(
+                    "{:<w$}",
+                    row.get(i).map(String::as_str).unwrap_or(""),
+                    w = *w
+                )
+            })
+            .collect();
+        out.push_str(cells.join(" | ").trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_delimiter() {
+        assert_eq!(detect_delimiter("a,b,c\n1,2,3\n"), ',');
+        assert_eq!(detect_delimiter("a\tb\tc\n1\t2\t3\n"), '\t');
+        assert_eq!(detect_delimiter("a;b\n\"1,5\";2\n\"2,5\";3\n"), ';');
+        assert_eq!(detect_delimiter("single\n1\n"), ',');
+    }
+
+    #[test]
+    fn test_parse_records_quotes_and_crlf() {
+        let records = parse_records("a,b\r\n\"x, \"\"y\"\"\",\"multi\nline\"\r\n\n3,\n", ',');
+        assert_eq!(
+            records,
+            vec![
+                vec!["a", "b"],
+                vec!["x, \"y\"", "multi\nline"],
+                vec!["3", ""],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_analyze_types_nulls_distinct() {
+        let csv = "id,price,active,created,name,notes\n\
+                   1,9.5,true,2024-01-02,alice,\n\
+                   2,10,false,2024-01-03T10:00:00,bob,NA\n\
+                   3,,true,2024-01-04,alice,\n";
+        let report = analyze(csv, ',', 0);
+        assert_eq!(report.rows, 3);
+        let kinds: Vec<ColumnType> = report.columns.iter().map(|c| c.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ColumnType::Int,
+                ColumnType::Float,
+                ColumnType::Bool,
+                ColumnType::Date,
+                ColumnType::String,
+                ColumnType::Empty
+            ]
+        );
+        assert_eq!(report.columns[1].nulls, 1);
+        assert_eq!(report.columns[4].distinct, 2);
+        assert_eq!(report.columns[4].samples, vec!["alice", "bob"]);
+        assert_eq!(report.columns[5].nulls, 3);
+    }
+
+    #[test]
+    fn test_analyze_header_names_and_short_rows() {
+        let report = analyze("a,,a\n1\n", ',', 0);
+        let names: Vec<&str> = report.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["a", "col2", "col3"]);
+        assert_eq!(report.columns[2].nulls, 1);
+    }
+
+    #[test]
+    fn test_format_report_with_preview() {
+        let report = analyze("id\tname\n1\talice\n2\tbob\n3\tcarol\n", '\t', 2);
+        let out = format_report("users.tsv", &report);
+        assert!(out.starts_with("users.tsv: 3 rows × 2 columns (delimiter tab)"));
+        assert!(out.contains("  id    int      0.0% null       3 distinct  e.g. 1, 2, 3"));
+        assert!(out.contains("id | name\n1  | alice\n2  | bob"));
+        assert!(!out.contains("3  | carol"));
+    }
+
+    #[test]
+    fn test_parse_delimiter() {
+        assert_eq!(parse_delimiter("tab").unwrap(), '\t');
+        assert_eq!(parse_delimiter(";").unwrap(), ';');
+        assert!(parse_delimiter(";;").is_err());
+    }
+}
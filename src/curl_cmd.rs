@@ -47,7 +47,8 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     Ok(())
 }
 
-fn filter_curl_output(output: &str) -> String {
+/// Schema for JSON bodies, truncated lines for anything else
+pub fn filter_curl_output(output: &str) -> String {
     let trimmed = output.trim();
 
     // Try JSON detection: starts with { or [
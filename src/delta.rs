@@ -0,0 +1,125 @@
+//! Cross-run deltas: the cargo, lint and test wrappers remember what the
+//! previous run of the same command in the same directory reported, and
+//! label this run's findings NEW, FIXED or STILL FAILING against it, so an
+//! agent fixing issues one at a time sees what its last edit changed.
+
+use crate::tracking;
+
+/// Keys listed per label before the rest are counted
+const MAX_LISTED: usize = 10;
+
+/// This run's findings against the previous run's
+#[derive(Debug, Default, PartialEq)]
+pub struct Delta {
+    pub new: Vec<String>,
+    pub fixed: Vec<String>,
+    pub still_failing: usize,
+}
+
+impl Delta {
+    pub fn between(previous: &[String], current: &[String]) -> Self {
+        Self {
+            new: current
+                .iter()
+                .filter(|k| !previous.contains(k))
+                .cloned()
+                .collect(),
+            fixed: previous
+                .iter()
+                .filter(|k| !current.contains(k))
+                .cloned()
+                .collect(),
+            still_failing: current.iter().filter(|k| previous.contains(k)).count(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.new.is_empty() && self.fixed.is_empty() && self.still_failing == 0
+    }
+
+    /// Counts line, then the NEW and FIXED keys; STILL FAILING ones were
+    /// seen last time and are only counted
+    pub fn format(&self) -> String {
+        let mut out = format!(
+            "Since last run: {} NEW, {} FIXED, {} STILL FAILING",
+            self.new.len(),
+            self.fixed.len(),
+            self.still_failing
+        );
+        for (label, keys) in [("NEW", &self.new), ("FIXED", &self.fixed)] {
+            for key in keys.iter().take(MAX_LISTED) {
+                out.push_str(&format!("\n  {} {}", label, key));
+            }
+            if keys.len() > MAX_LISTED {
+                out.push_str(&format!(
+                    "\n  ... +{} more {}",
+                    keys.len() - MAX_LISTED,
+                    label
+                ));
+            }
+        }
+        out
+    }
+}
+
+/// Compare `keys` with the previous run of `command` in the current
+/// directory, and remember them for the next run. `None` on the first run,
+/// when tracking is disabled, or when neither run reported anything.
+pub fn track(command: &str, keys: &[String]) -> Option<Delta> {
+    let cwd = std::env::current_dir().ok()?;
+    let scope = format!("{}\t{}", cwd.display(), command.trim());
+    let keys = dedup(keys);
+    let previous = tracking::swap_diagnostics(&scope, &keys)?;
+    let delta = Delta::between(&previous, &keys);
+    (!delta.is_empty()).then_some(delta)
+}
+
+/// `filtered` followed by the delta against the previous run, if any
+pub fn annotate(filtered: String, command: &str, keys: &[String]) -> String {
+    match track(command, keys) {
+        Some(delta) => format!("{}\n\n{}", filtered, delta.format()),
+        None => filtered,
+    }
+}
+
+fn dedup(keys: &[String]) -> Vec<String> {
+    let mut out: Vec<String> = Vec::with_capacity(keys.len());
+    for key in keys {
+        if !out.contains(key) {
+            out.push(key.clone());
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stable::assert_format;
+
+    fn keys(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_between() {
+        let delta = Delta::between(&keys(&["a", "b", "c"]), &keys(&["b", "c", "d"]));
+        assert_eq!(delta.new, keys(&["d"]));
+        assert_eq!(delta.fixed, keys(&["a"]));
+        assert_eq!(delta.still_failing, 2);
+        assert!(Delta::between(&[], &[]).is_empty());
+    }
+
+    #[test]
+    fn test_format_lists_new_and_fixed_only() {
+        let delta = Delta::between(&keys(&["a", "b"]), &keys(&["b", "c"]));
+        assert_eq!(
+            delta.format(),
+            "Since last run: 1 NEW, 1 FIXED, 1 STILL FAILING\n  NEW c\n  FIXED a"
+        );
+        let many: Vec<String> = (0..12).map(|n| n.to_string()).collect();
+        let formatted = Delta::between(&[], &many).format();
+        assert!(formatted.contains("... +2 more NEW"));
+        assert_format!("delta", formatted);
+    }
+}
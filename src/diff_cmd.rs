@@ -1,8 +1,12 @@
+use crate::git::{parse_numstat, FileStat};
 use crate::tracking;
 use crate::utils::truncate;
-use anyhow::Result;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::process::Command;
 
 /// Ultra-condensed diff - only changed lines, no context
 pub fn run(file1: &Path, file2: &Path, verbose: u8) -> Result<()> {
@@ -65,6 +69,29 @@ pub fn run(file1: &Path, file2: &Path, verbose: u8) -> Result<()> {
     Ok(())
 }
 
+/// What `rtk diff` compares, from its positional arguments
+#[derive(Debug, PartialEq)]
+pub enum DiffInput {
+    /// Two existing files
+    Files(PathBuf, PathBuf),
+    /// `-`: a unified diff piped in
+    Stdin,
+    /// Anything else goes to `git diff`: nothing, revisions, ranges, paths
+    Git,
+}
+
+impl DiffInput {
+    pub fn from_args(args: &[String]) -> Self {
+        match args {
+            [dash] if dash == "-" => DiffInput::Stdin,
+            [a, b] if !a.starts_with('-') && Path::new(a).is_file() && Path::new(b).is_file() => {
+                DiffInput::Files(PathBuf::from(a), PathBuf::from(b))
+            }
+            _ => DiffInput::Git,
+        }
+    }
+}
+
 /// Run diff from stdin (piped command output)
 pub fn run_stdin(_verbose: u8) -> Result<()> {
     use std::io::{self, Read};
@@ -82,6 +109,444 @@ pub fn run_stdin(_verbose: u8) -> Result<()> {
     Ok(())
 }
 
+/// Summarize `git diff` per file and per enclosing function.
+///
+/// Hunks are grouped by the function context git prints after `@@` so a file
+/// touched in ten places inside the same function collapses to one line.
+/// Files listed in `expand` keep their changed lines.
+pub fn run_git(args: &[String], expand: &[String], format: &str, verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    let mut cmd = Command::new("git");
+    // Paths exactly as in the tree, whatever diff.noprefix/mnemonicPrefix say
+    cmd.args(["diff", "--no-prefix"]);
+    for arg in args {
+        cmd.arg(arg);
+    }
+
+    if verbose > 0 {
+        eprintln!("Running: git diff {}", args.join(" "));
+    }
+
+    let output = cmd.output().context("Failed to run git diff")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        eprintln!("{}", stderr.trim());
+        std::process::exit(output.status.code().unwrap_or(1));
+    }
+
+    let raw = String::from_utf8_lossy(&output.stdout).to_string();
+    let files = summarize_git_diff(&raw);
+
+    let rtk = if format == "json" {
+        serde_json::to_string_pretty(&files)?
+    } else {
+        format_git_summary(&files, expand)
+    };
+    println!("{}", rtk);
+
+    timer.track(
+        &format!("git diff {}", args.join(" ")),
+        &format!("rtk diff {}", args.join(" ")),
+        &raw,
+        &rtk,
+    );
+
+    Ok(())
+}
+
+/// Changes to one file in a git diff
+#[derive(Debug, Serialize)]
+struct FileDiff {
+    path: String,
+    added: usize,
+    removed: usize,
+    binary: bool,
+    sections: Vec<DiffSection>,
+}
+
+/// Hunks sharing the same enclosing function (git's `@@ ... @@ context`)
+#[derive(Debug, Serialize)]
+struct DiffSection {
+    context: String,
+    hunks: usize,
+    added: usize,
+    removed: usize,
+    #[serde(skip)]
+    lines: Vec<String>,
+}
+
+/// Per-file summary of a `git diff --no-prefix`. Paths come from the
+/// `rename to` and `+++`/`---` headers; the `diff --git` line only names
+/// unchanged-path files (binary, mode changes), where both halves match.
+fn summarize_git_diff(diff: &str) -> Vec<FileDiff> {
+    let mut files: Vec<FileDiff> = Vec::new();
+    // Between `diff --git` and the first hunk: `---`/`+++` are headers
+    let mut in_header = false;
+
+    for line in diff.lines() {
+        if let Some(paths) = line.strip_prefix("diff --git ") {
+            files.push(FileDiff {
+                path: same_paths(paths).unwrap_or_else(|| "unknown".to_string()),
+                added: 0,
+                removed: 0,
+                binary: false,
+                sections: Vec::new(),
+            });
+            in_header = true;
+            continue;
+        }
+
+        let Some(file) = files.last_mut() else {
+            continue;
+        };
+
+        if in_header {
+            let path = line
+                .strip_prefix("rename to ")
+                .or_else(|| line.strip_prefix("copy to "))
+                .or_else(|| line.strip_prefix("+++ "))
+                // Deleted files: `+++ /dev/null`, the path is on `---`
+                .or_else(|| line.strip_prefix("--- "))
+                .map(header_path)
+                .filter(|path| path != "/dev/null");
+            if let Some(path) = path {
+                file.path = path;
+                continue;
+            }
+        }
+
+        if line.starts_with("Binary files") {
+            file.binary = true;
+        } else if let Some(rest) = line.strip_prefix("@@") {
+            in_header = false;
+            let context = rest
+                .split_once("@@")
+                .map(|(range, ctx)| {
+                    let ctx = ctx.trim();
+                    if ctx.is_empty() {
+                        format!("@@ {} @@", range.trim())
+                    } else {
+                        ctx.trim_end_matches('{').trim().to_string()
+                    }
+                })
+                .unwrap_or_default();
+
+            match file.sections.iter_mut().find(|s| s.context == context) {
+                Some(section) => section.hunks += 1,
+                None => file.sections.push(DiffSection {
+                    context,
+                    hunks: 1,
+                    added: 0,
+                    removed: 0,
+                    lines: Vec::new(),
+                }),
+            }
+        } else if in_header {
+            // index, mode and similarity lines
+        } else if line.starts_with('+') {
+            file.added += 1;
+            if let Some(section) = file.sections.last_mut() {
+                section.added += 1;
+                section.lines.push(line.to_string());
+            }
+        } else if line.starts_with('-') {
+            file.removed += 1;
+            if let Some(section) = file.sections.last_mut() {
+                section.removed += 1;
+                section.lines.push(line.to_string());
+            }
+        }
+    }
+
+    files
+}
+
+/// The path of `diff --git <path> <path>` when both are the same, which is
+/// all a header with spaces in its paths can be split by
+fn same_paths(paths: &str) -> Option<String> {
+    if paths.starts_with('"') {
+        let (old, new) = paths.split_once("\" \"")?;
+        let (old, new) = (
+            unquote(&format!("{}\"", old)),
+            unquote(&format!("\"{}", new)),
+        );
+        return (old == new).then_some(new);
+    }
+    let mid = paths.len() / 2;
+    let (old, new) = (paths.get(..mid)?, paths.get(mid + 1..)?);
+    (paths.len() % 2 == 1 && old == new).then(|| new.to_string())
+}
+
+/// Path of a `+++`, `---` or `rename to` header: git quotes names with
+/// special characters and ends those with spaces with a tab
+fn header_path(path: &str) -> String {
+    let path = path.trim_end_matches('\t');
+    if path.starts_with('"') {
+        unquote(path)
+    } else {
+        path.to_string()
+    }
+}
+
+/// A C-style quoted git path, octal escapes being UTF-8 bytes
+fn unquote(quoted: &str) -> String {
+    let inner = quoted
+        .strip_prefix('"')
+        .and_then(|q| q.strip_suffix('"'))
+        .unwrap_or(quoted);
+    let mut bytes = Vec::with_capacity(inner.len());
+    let mut chars = inner.bytes().peekable();
+    while let Some(b) = chars.next() {
+        if b != b'\\' {
+            bytes.push(b);
+            continue;
+        }
+        match chars.next() {
+            Some(b't') => bytes.push(b'\t'),
+            Some(b'n') => bytes.push(b'\n'),
+            Some(d @ b'0'..=b'7') => {
+                let mut value = u32::from(d - b'0');
+                for _ in 0..2 {
+                    match chars.peek() {
+                        Some(&d @ b'0'..=b'7') => {
+                            value = value * 8 + u32::from(d - b'0');
+                            chars.next();
+                        }
+                        _ => break,
+                    }
+                }
+                bytes.push(value as u8);
+            }
+            Some(other) => bytes.push(other),
+            None => bytes.push(b'\\'),
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+fn format_git_summary(files: &[FileDiff], expand: &[String]) -> String {
+    if files.is_empty() {
+        return "✅ No changes".to_string();
+    }
+
+    let total_added: usize = files.iter().map(|f| f.added).sum();
+    let total_removed: usize = files.iter().map(|f| f.removed).sum();
+
+    let mut out = Vec::new();
+    out.push(format!(
+        "📊 {} files, +{} -{}",
+        files.len(),
+        total_added,
+        total_removed
+    ));
+
+    for file in files {
+        if file.binary {
+            out.push(format!("📄 {} (binary)", file.path));
+            continue;
+        }
+
+        out.push(format!(
+            "📄 {} (+{} -{})",
+            file.path, file.added, file.removed
+        ));
+        let expanded = expand
+            .iter()
+            .any(|e| file.path == *e || file.path.ends_with(&format!("/{}", e)));
+
+        for section in &file.sections {
+            let hunks = if section.hunks > 1 {
+                format!(" x{}", section.hunks)
+            } else {
+                String::new()
+            };
+            out.push(format!(
+                "  {} (+{} -{}){}",
+                truncate(&section.context, 70),
+                section.added,
+                section.removed,
+                hunks
+            ));
+
+            if expanded {
+                for line in &section.lines {
+                    out.push(format!("    {}", truncate(line, 100)));
+                }
+            }
+        }
+    }
+
+    out.join("\n")
+}
+
+/// Heatmap levels below the top one
+const HEATMAP_DEPTH: usize = 3;
+
+/// Subdirectories listed per directory, hottest first
+const HEATMAP_CHILDREN: usize = 8;
+
+/// Width of the intensity bar of the hottest directory
+const HEATMAP_BAR: usize = 12;
+
+/// Width of the directory column
+const HEATMAP_NAME_WIDTH: usize = 32;
+
+/// Changes under one directory of a heatmap
+#[derive(Debug, Default, Serialize)]
+struct DirHeat {
+    files: usize,
+    added: usize,
+    removed: usize,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    dirs: BTreeMap<String, DirHeat>,
+}
+
+impl DirHeat {
+    fn churn(&self) -> usize {
+        self.added + self.removed
+    }
+}
+
+/// Per-directory change intensity of `git diff`: files changed and churn
+/// (lines added + removed) as a small tree, for refactors too large for
+/// per-file stats
+pub fn run_heatmap(args: &[String], format: &str, verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    if verbose > 0 {
+        eprintln!("Running: git diff --numstat {}", args.join(" "));
+    }
+    let output = Command::new("git")
+        .args(["diff", "--numstat"])
+        .args(args)
+        .output()
+        .context("Failed to run git diff")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        eprintln!("{}", stderr.trim());
+        std::process::exit(output.status.code().unwrap_or(1));
+    }
+
+    let raw = String::from_utf8_lossy(&output.stdout).to_string();
+    let heat = heatmap(&parse_numstat(&raw));
+    let rtk = if format == "json" {
+        serde_json::to_string_pretty(&heat)?
+    } else {
+        format_heatmap(&heat)
+    };
+    println!("{}", rtk);
+
+    timer.track(
+        &format!("git diff --stat {}", args.join(" ")),
+        &format!("rtk diff --heatmap {}", args.join(" ")),
+        &raw,
+        &rtk,
+    );
+    Ok(())
+}
+
+/// Path after a rename: `src/{old => new}/a.rs` → `src/new/a.rs`,
+/// `a.rs => b.rs` → `b.rs`
+fn renamed_path(path: &str) -> String {
+    if let (Some(open), Some(close)) = (path.find('{'), path.find('}')) {
+        if let Some((_, new)) = path[open + 1..close].split_once(" => ") {
+            let joined = format!("{}{}{}", &path[..open], new, &path[close + 1..]);
+            return joined.replace("//", "/");
+        }
+    }
+    match path.split_once(" => ") {
+        Some((_, new)) => new.to_string(),
+        None => path.to_string(),
+    }
+}
+
+/// Totals per directory; the root holds the files at the top level
+fn heatmap(stats: &[FileStat]) -> DirHeat {
+    let mut root = DirHeat::default();
+    for stat in stats {
+        let path = renamed_path(&stat.path);
+        let (added, removed) = (stat.added.unwrap_or(0), stat.removed.unwrap_or(0));
+        let mut node = &mut root;
+        let mut dirs: Vec<&str> = path.split('/').collect();
+        dirs.pop();
+        loop {
+            node.files += 1;
+            node.added += added;
+            node.removed += removed;
+            let Some((dir, rest)) = dirs.split_first() else {
+                break;
+            };
+            node = node.dirs.entry(dir.to_string()).or_default();
+            dirs = rest.to_vec();
+        }
+    }
+    root
+}
+
+fn format_heatmap(root: &DirHeat) -> String {
+    if root.files == 0 {
+        return "✅ No changes".to_string();
+    }
+    let mut out = vec![format!(
+        "🔥 {} files, +{} -{} by directory:",
+        root.files, root.added, root.removed
+    )];
+    let hottest = root.dirs.values().map(DirHeat::churn).max().unwrap_or(0);
+    let direct = root.files - root.dirs.values().map(|d| d.files).sum::<usize>();
+    push_heat_dirs(&mut out, root, 0, hottest.max(1));
+    if direct > 0 {
+        out.push(format!("  ({} files at the top level)", direct));
+    }
+    out.join("\n")
+}
+
+/// One line per subdirectory of `node`, hottest first, recursing up to
+/// [`HEATMAP_DEPTH`]. Chains of single directories print as one path.
+fn push_heat_dirs(out: &mut Vec<String>, node: &DirHeat, depth: usize, hottest: usize) {
+    let mut dirs: Vec<(&String, &DirHeat)> = node.dirs.iter().collect();
+    dirs.sort_by(|a, b| {
+        b.1.churn()
+            .cmp(&a.1.churn())
+            .then(b.1.files.cmp(&a.1.files))
+    });
+    for (name, dir) in dirs.iter().take(HEATMAP_CHILDREN) {
+        let mut name = format!("{}/", name);
+        let mut dir = *dir;
+        while dir.dirs.len() == 1 {
+            let (child_name, child) = dir.dirs.iter().next().expect("one subdirectory");
+            if child.files != dir.files {
+                break;
+            }
+            name.push_str(&format!("{}/", child_name));
+            dir = child;
+        }
+        let label = format!("{}{}", "  ".repeat(depth + 1), name);
+        let filled = (dir.churn() * HEATMAP_BAR).div_ceil(hottest).max(1);
+        out.push(format!(
+            "{:<nw$} {:>4}F {:>6}  {}",
+            truncate(&label, HEATMAP_NAME_WIDTH),
+            dir.files,
+            dir.churn(),
+            "█".repeat(filled.min(HEATMAP_BAR)),
+            nw = HEATMAP_NAME_WIDTH
+        ));
+        if depth + 1 < HEATMAP_DEPTH {
+            push_heat_dirs(out, dir, depth + 1, hottest);
+        }
+    }
+    if dirs.len() > HEATMAP_CHILDREN {
+        let rest = &dirs[HEATMAP_CHILDREN..];
+        out.push(format!(
+            "{}... +{} dirs ({}F, {} lines)",
+            "  ".repeat(depth + 1),
+            rest.len(),
+            rest.iter().map(|(_, d)| d.files).sum::<usize>(),
+            rest.iter().map(|(_, d)| d.churn()).sum::<usize>()
+        ));
+    }
+}
+
 #[derive(Debug)]
 enum DiffChange {
     Added(usize, String),
@@ -215,6 +680,25 @@ fn condense_unified_diff(diff: &str) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_diff_input_from_args() {
+        let args = |a: &[&str]| a.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(DiffInput::from_args(&args(&["-"])), DiffInput::Stdin);
+        assert_eq!(
+            DiffInput::from_args(&args(&["Cargo.toml", "Cargo.lock"])),
+            DiffInput::Files("Cargo.toml".into(), "Cargo.lock".into())
+        );
+        for git in [
+            &[][..],
+            &["HEAD~1"],
+            &["main..feature"],
+            &["HEAD", "Cargo.toml"],
+            &["HEAD~1", "--", "src"],
+        ] {
+            assert_eq!(DiffInput::from_args(&args(git)), DiffInput::Git);
+        }
+    }
+
     // --- similarity ---
 
     #[test]
@@ -359,9 +843,141 @@ diff --git a/b.rs b/b.rs
         assert!(result.contains("b.rs"));
     }
 
+    // --- summarize_git_diff ---
+
+    const GIT_DIFF: &str = r#"diff --git src/main.rs src/main.rs
+index 1234567..89abcde 100644
+--- src/main.rs
++++ src/main.rs
+@@ -10,6 +10,7 @@ fn main() {
+     let cli = Cli::parse();
++    let verbose = cli.verbose;
+     match cli.command {
+@@ -40,7 +41,7 @@ fn main() {
+-        Commands::Ls { args } => ls::run(&args)?,
++        Commands::Ls { args } => ls::run(&args, verbose)?,
+@@ -90,3 +91,4 @@ impl Cli {
++    fn new() -> Self { todo!() }
+diff --git logo.png logo.png
+Binary files logo.png and logo.png differ
+"#;
+
+    #[test]
+    fn test_summarize_git_diff_groups_by_function() {
+        let files = summarize_git_diff(GIT_DIFF);
+        assert_eq!(files.len(), 2);
+
+        let main = &files[0];
+        assert_eq!(main.path, "src/main.rs");
+        assert_eq!(main.added, 3);
+        assert_eq!(main.removed, 1);
+        assert_eq!(main.sections.len(), 2);
+        assert_eq!(main.sections[0].context, "fn main()");
+        assert_eq!(main.sections[0].hunks, 2);
+        assert_eq!(main.sections[1].context, "impl Cli");
+
+        assert!(files[1].binary);
+    }
+
+    #[test]
+    fn test_summarize_git_diff_paths_from_headers() {
+        let diff = "\
+diff --git src/old name.rs src/new b/name.rs
+similarity index 90%
+rename from src/old name.rs
+rename to src/new b/name.rs
+index 1234567..89abcde 100644
+--- src/old name.rs\t
++++ src/new b/name.rs\t
+@@ -1,2 +1,2 @@
+-old
++++counter;
+diff --git a b/c a b/c
+deleted file mode 100644
+--- a b/c
++++ /dev/null
+@@ -1 +0,0 @@
+--- a b/c
+diff --git \"caf\\303\\251.txt\" \"caf\\303\\251.txt\"
+index 1234567..89abcde 100644
+--- \"caf\\303\\251.txt\"
++++ \"caf\\303\\251.txt\"
+@@ -1 +1 @@
+-a
++b
+diff --git img b/x.png img b/x.png
+Binary files img b/x.png and img b/x.png differ
+";
+        let files = summarize_git_diff(diff);
+        let paths: Vec<&str> = files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            ["src/new b/name.rs", "a b/c", "café.txt", "img b/x.png"]
+        );
+        // `+++` and `---` inside hunks are content
+        assert_eq!((files[0].added, files[0].removed), (1, 1));
+        assert_eq!((files[1].added, files[1].removed), (0, 1));
+        assert!(files[3].binary);
+    }
+
+    #[test]
+    fn test_format_git_summary_collapsed() {
+        let files = summarize_git_diff(GIT_DIFF);
+        let out = format_git_summary(&files, &[]);
+        assert!(out.contains("📊 2 files, +3 -1"));
+        assert!(out.contains("fn main() (+2 -1) x2"));
+        assert!(out.contains("logo.png (binary)"));
+        assert!(!out.contains("let verbose"));
+    }
+
+    #[test]
+    fn test_format_git_summary_expanded() {
+        let files = summarize_git_diff(GIT_DIFF);
+        let out = format_git_summary(&files, &["main.rs".to_string()]);
+        assert!(out.contains("+    let verbose = cli.verbose;"));
+    }
+
+    #[test]
+    fn test_format_git_summary_no_changes() {
+        assert_eq!(format_git_summary(&[], &[]), "✅ No changes");
+    }
+
     #[test]
     fn test_condense_unified_diff_empty() {
         let result = condense_unified_diff("");
         assert!(result.is_empty());
     }
+
+    // --- heatmap ---
+
+    #[test]
+    fn test_renamed_path() {
+        assert_eq!(renamed_path("src/{old => new}/a.rs"), "src/new/a.rs");
+        assert_eq!(renamed_path("src/{ => nested}/a.rs"), "src/nested/a.rs");
+        assert_eq!(renamed_path("a.rs => b/c.rs"), "b/c.rs");
+        assert_eq!(renamed_path("src/main.rs"), "src/main.rs");
+    }
+
+    #[test]
+    fn test_format_heatmap() {
+        let numstat = "10\t5\tsrc/cmds/git/status.rs\n\
+                       20\t0\tsrc/cmds/git/log.rs\n\
+                       3\t3\tsrc/core/config.rs\n\
+                       1\t0\ttests/fixtures/deep/x.txt\n\
+                       -\t-\tassets/logo.png\n\
+                       2\t2\tREADME.md\n";
+        let heat = heatmap(&parse_numstat(numstat));
+        assert_eq!((heat.files, heat.added, heat.removed), (6, 36, 10));
+        assert_eq!(
+            format_heatmap(&heat),
+            "🔥 6 files, +36 -10 by directory:\n\
+             \x20 src/                              3F     41  ████████████\n\
+             \x20   cmds/git/                       2F     35  ███████████\n\
+             \x20   core/                           1F      6  ██\n\
+             \x20 tests/fixtures/deep/              1F      1  █\n\
+             \x20 assets/                           1F      0  █\n\
+             \x20 (1 files at the top level)"
+        );
+        assert_eq!(format_heatmap(&DirHeat::default()), "✅ No changes");
+    }
 }
@@ -0,0 +1,415 @@
+//! Disk usage summary: totals split between source (what `.gitignore`
+//! keeps), build artifacts (`target/`, `node_modules/`, `.venv/`, anything
+//! gitignored) and `.git`, then the largest top-level entries and the
+//! largest source files.
+
+use crate::tracking;
+use crate::utils::format_bytes;
+use crate::walk;
+use anyhow::Result;
+use ignore::WalkBuilder;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, MAIN_SEPARATOR};
+
+/// Directories that only hold generated or downloaded files, gitignored or not
+const ARTIFACT_DIRS: &[&str] = &[
+    "target",
+    "node_modules",
+    ".venv",
+    "venv",
+    "__pycache__",
+    ".pytest_cache",
+    ".mypy_cache",
+    ".ruff_cache",
+    ".tox",
+    ".gradle",
+    ".next",
+    ".nuxt",
+    ".turbo",
+];
+
+/// Artifact roots named on the artifacts line
+const MAX_ARTIFACT_ROOTS: usize = 3;
+
+/// Source files listed
+const MAX_SOURCE_FILES: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Source,
+    Artifact,
+    Git,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct Usage {
+    bytes: u64,
+    files: usize,
+}
+
+impl Usage {
+    fn add(&mut self, bytes: u64) {
+        self.bytes += bytes;
+        self.files += 1;
+    }
+}
+
+#[derive(Debug, Default)]
+struct Report {
+    total: Usage,
+    source: Usage,
+    artifacts: Usage,
+    git: Usage,
+    /// Top-level entries: usage and the part of it that is artifacts
+    entries: BTreeMap<String, (Usage, u64)>,
+    /// `target/`, `web/node_modules/`: where the artifacts live
+    artifact_roots: BTreeMap<String, Usage>,
+    source_files: Vec<(String, u64)>,
+}
+
+fn components(rel: &str) -> Vec<&str> {
+    rel.split(MAIN_SEPARATOR).collect()
+}
+
+/// `kept` holds the files a gitignore-aware walk lists
+fn classify(rel: &str, kept: &HashSet<String>) -> Kind {
+    let parts = components(rel);
+    if parts.contains(&".git") {
+        Kind::Git
+    } else if parts[..parts.len() - 1]
+        .iter()
+        .any(|p| ARTIFACT_DIRS.contains(p))
+        || !kept.contains(rel)
+    {
+        Kind::Artifact
+    } else {
+        Kind::Source
+    }
+}
+
+/// The first `depth` components of `rel`, with a trailing `/` when that is
+/// a directory
+fn prefix(rel: &str, depth: usize) -> String {
+    let parts = components(rel);
+    let name = parts[..depth].join("/");
+    if depth < parts.len() {
+        format!("{}/", name)
+    } else {
+        name
+    }
+}
+
+/// The artifact directory holding `rel`, else its top-level entry
+fn artifact_root(rel: &str) -> String {
+    let parts = components(rel);
+    let depth = parts[..parts.len() - 1]
+        .iter()
+        .position(|p| ARTIFACT_DIRS.contains(p))
+        .map_or(1, |i| i + 1);
+    prefix(rel, depth)
+}
+
+fn summarize(files: &[(String, u64)], kept: &HashSet<String>) -> Report {
+    let mut report = Report::default();
+    for (rel, bytes) in files {
+        let bytes = *bytes;
+        let kind = classify(rel, kept);
+        report.total.add(bytes);
+
+        let entry = report.entries.entry(prefix(rel, 1)).or_default();
+        entry.0.add(bytes);
+
+        match kind {
+            Kind::Source => {
+                report.source.add(bytes);
+                report
+                    .source_files
+                    .push((rel.replace(MAIN_SEPARATOR, "/"), bytes));
+            }
+            Kind::Artifact => {
+                report.artifacts.add(bytes);
+                entry.1 += bytes;
+                report
+                    .artifact_roots
+                    .entry(artifact_root(rel))
+                    .or_default()
+                    .add(bytes);
+            }
+            Kind::Git => report.git.add(bytes),
+        }
+    }
+    report
+        .source_files
+        .sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    report.source_files.truncate(MAX_SOURCE_FILES);
+    report
+}
+
+fn plural(n: usize) -> &'static str {
+    if n == 1 {
+        ""
+    } else {
+        "s"
+    }
+}
+
+fn usage_line(label: &str, usage: Usage) -> String {
+    format!(
+        "  {:<10}{:>9}  {} file{}",
+        label,
+        format_bytes(usage.bytes),
+        usage.files,
+        plural(usage.files)
+    )
+}
+
+fn format_report(root: &str, report: &Report, limit: usize) -> String {
+    let mut out = vec![format!(
+        "💾 {}: {} in {} file{}",
+        root,
+        format_bytes(report.total.bytes),
+        report.total.files,
+        plural(report.total.files)
+    )];
+    if report.total.files == 0 {
+        return out.join("\n");
+    }
+
+    out.push(usage_line("source", report.source));
+    if report.artifacts.files > 0 {
+        let mut roots: Vec<(&String, &Usage)> = report.artifact_roots.iter().collect();
+        roots.sort_by_key(|(_, usage)| std::cmp::Reverse(usage.bytes));
+        let names: Vec<String> = roots
+            .iter()
+            .take(MAX_ARTIFACT_ROOTS)
+            .map(|(name, _)| name.to_string())
+            .collect();
+        let more = roots.len().saturating_sub(MAX_ARTIFACT_ROOTS);
+        let more = if more > 0 {
+            format!(" +{}", more)
+        } else {
+            String::new()
+        };
+        out.push(format!(
+            "{} ({}{})",
+            usage_line("artifacts", report.artifacts),
+            names.join(", "),
+            more
+        ));
+    }
+    if report.git.files > 0 {
+        out.push(usage_line(".git", report.git));
+    }
+
+    let mut entries: Vec<(&String, &(Usage, u64))> = report.entries.iter().collect();
+    entries.sort_by(|a, b| b.1 .0.bytes.cmp(&a.1 .0.bytes).then_with(|| a.0.cmp(b.0)));
+    out.push(String::new());
+    out.push("Largest:".to_string());
+    for (name, (usage, artifact_bytes)) in entries.iter().take(limit) {
+        let tag = if *artifact_bytes == 0 {
+            String::new()
+        } else if *artifact_bytes == usage.bytes {
+            "  artifacts".to_string()
+        } else {
+            format!("  {}% artifacts", artifact_bytes * 100 / usage.bytes.max(1))
+        };
+        out.push(format!(
+            "  {:>9}  {}{}",
+            format_bytes(usage.bytes),
+            name,
+            tag
+        ));
+    }
+    if entries.len() > limit {
+        out.push(format!("  ... +{} more", entries.len() - limit));
+    }
+
+    if !report.source_files.is_empty() {
+        out.push(String::new());
+        out.push("Largest source files:".to_string());
+        for (path, bytes) in &report.source_files {
+            out.push(format!("  {:>9}  {}", format_bytes(*bytes), path));
+        }
+    }
+    out.join("\n")
+}
+
+/// What `du -h` prints: every directory with its total
+fn du_listing(root: &str, files: &[(String, u64)]) -> String {
+    let mut dirs: BTreeMap<String, u64> = BTreeMap::new();
+    for (rel, bytes) in files {
+        let parts = components(rel);
+        for depth in 0..parts.len() {
+            *dirs.entry(parts[..depth].join("/")).or_default() += bytes;
+        }
+    }
+    dirs.iter()
+        .map(|(dir, bytes)| {
+            let path = if dir.is_empty() {
+                root.to_string()
+            } else {
+                format!("{}/{}", root, dir)
+            };
+            format!("{}\t{}", format_bytes(*bytes), path)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Files below `path` with their sizes, relative to `path`
+fn list_files(path: &Path, all: bool) -> Vec<(String, u64)> {
+    let mut builder = WalkBuilder::new(path);
+    builder.hidden(false);
+    if all {
+        builder.standard_filters(false);
+    } else {
+        builder.require_git(false);
+        builder.filter_entry(|entry| entry.file_name() != ".git");
+    }
+    walk::walk(&builder, |entry| {
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            return None;
+        }
+        let rel = entry.path().strip_prefix(path).ok()?;
+        let bytes = entry.metadata().map(|m| m.len()).unwrap_or(0);
+        Some((rel.to_string_lossy().to_string(), bytes))
+    })
+    .items
+}
+
+pub fn run(path: &Path, limit: usize, verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+    if !path.is_dir() {
+        anyhow::bail!("Not a directory: {}", path.display());
+    }
+    if verbose > 0 {
+        eprintln!("Disk usage: {}", path.display());
+    }
+
+    // Two walks: everything, then what .gitignore keeps
+    let files = list_files(path, true);
+    let kept: HashSet<String> = list_files(path, false)
+        .into_iter()
+        .map(|(rel, _)| rel)
+        .collect();
+
+    let root = path.display().to_string();
+    let report = summarize(&files, &kept);
+    let output = format_report(&root, &report, limit);
+    println!("{}", output);
+
+    let raw = du_listing(&root, &files);
+    timer.track(&format!("du -h {}", root), "rtk du", &raw, &output);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn sample() -> (Vec<(String, u64)>, HashSet<String>) {
+        let files: Vec<(String, u64)> = [
+            ("src/main.rs", 2_000),
+            ("src/lib.rs", 1_000),
+            ("assets/logo.png", 50_000),
+            ("target/debug/rtk", 5_000_000),
+            ("target/debug/deps/a.rlib", 3_000_000),
+            ("web/node_modules/react/index.js", 400_000),
+            ("web/app.js", 3_000),
+            ("app.log", 20_000),
+            (".git/objects/pack/p.pack", 900_000),
+        ]
+        .iter()
+        .map(|(p, b)| (p.replace('/', std::path::MAIN_SEPARATOR_STR), *b))
+        .collect();
+        let kept = ["src/main.rs", "src/lib.rs", "assets/logo.png", "web/app.js"]
+            .iter()
+            .map(|p| p.replace('/', std::path::MAIN_SEPARATOR_STR))
+            .collect();
+        (files, kept)
+    }
+
+    #[test]
+    fn test_classify_and_roots() {
+        let (files, kept) = sample();
+        let report = summarize(&files, &kept);
+        assert_eq!(
+            report.source,
+            Usage {
+                bytes: 56_000,
+                files: 4
+            }
+        );
+        assert_eq!(
+            report.artifacts,
+            Usage {
+                bytes: 8_420_000,
+                files: 4
+            }
+        );
+        assert_eq!(
+            report.git,
+            Usage {
+                bytes: 900_000,
+                files: 1
+            }
+        );
+        let roots: Vec<&str> = report.artifact_roots.keys().map(|k| k.as_str()).collect();
+        assert_eq!(roots, vec!["app.log", "target/", "web/node_modules/"]);
+        assert_eq!(
+            report.source_files[0],
+            ("assets/logo.png".to_string(), 50_000)
+        );
+    }
+
+    #[test]
+    fn test_format_report() {
+        let (files, kept) = sample();
+        let output = format_report(".", &summarize(&files, &kept), 3);
+        assert_eq!(
+            output,
+            "\
+💾 .: 8.9MB in 9 files
+  source       54.7KB  4 files
+  artifacts     8.0MB  4 files (target/, web/node_modules/, app.log)
+  .git        878.9KB  1 file
+
+Largest:
+      7.6MB  target/  artifacts
+    878.9KB  .git/
+    393.6KB  web/  99% artifacts
+  ... +3 more
+
+Largest source files:
+     48.8KB  assets/logo.png
+      2.9KB  web/app.js
+      2.0KB  src/main.rs
+      1000B  src/lib.rs"
+        );
+    }
+
+    #[test]
+    fn test_run_on_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(".gitignore"), "dist/\n").unwrap();
+        fs::create_dir_all(dir.path().join("dist")).unwrap();
+        fs::write(dir.path().join("dist/bundle.js"), "x".repeat(100)).unwrap();
+        fs::write(dir.path().join("main.rs"), "fn main() {}").unwrap();
+
+        let files = list_files(dir.path(), true);
+        let kept: HashSet<String> = list_files(dir.path(), false)
+            .into_iter()
+            .map(|(rel, _)| rel)
+            .collect();
+        let report = summarize(&files, &kept);
+        assert_eq!(
+            report.artifacts,
+            Usage {
+                bytes: 100,
+                files: 1
+            }
+        );
+        assert_eq!(report.source.files, 2);
+    }
+}
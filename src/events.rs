@@ -0,0 +1,123 @@
+//! Live tracking events: with `RTK_EVENTS_SOCKET` or
+//! `tracking.events_socket` set, every recorded command is also written as
+//! one JSON line to that Unix domain socket (or FIFO, or Windows named
+//! pipe), so a dashboard or the MCP server can follow rtk usage without
+//! polling the history database.
+//!
+//! Delivery is best effort: when nothing listens, the event is dropped and
+//! the command is unaffected.
+
+use serde::Serialize;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Longest a slow listener can hold up a command
+const WRITE_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// A command recorded in the history, as sent to listeners
+#[derive(Debug, Serialize)]
+pub struct CommandEvent<'a> {
+    /// Always `"command"`, so listeners can tell future event kinds apart
+    pub event: &'static str,
+    /// RFC 3339 timestamp (UTC)
+    pub timestamp: String,
+    pub original_cmd: &'a str,
+    pub rtk_cmd: &'a str,
+    pub input_tokens: usize,
+    pub output_tokens: usize,
+    pub saved_tokens: usize,
+    pub savings_pct: f64,
+    pub exec_time_ms: u64,
+}
+
+/// Where events go: `RTK_EVENTS_SOCKET`, else `tracking.events_socket`
+pub fn target() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os("RTK_EVENTS_SOCKET").filter(|p| !p.is_empty()) {
+        return Some(PathBuf::from(path));
+    }
+    crate::config::Config::get().tracking.events_socket.clone()
+}
+
+/// Send `event` to `path`, ignoring any failure
+pub fn emit(path: &Path, event: &CommandEvent) {
+    if let Ok(mut line) = serde_json::to_string(event) {
+        line.push('\n');
+        let _ = send(path, line.as_bytes());
+    }
+}
+
+#[cfg(unix)]
+fn send(path: &Path, line: &[u8]) -> io::Result<()> {
+    use std::os::unix::fs::{FileTypeExt, OpenOptionsExt};
+    use std::os::unix::net::UnixStream;
+
+    if std::fs::metadata(path)?.file_type().is_fifo() {
+        // Non-blocking open fails right away (ENXIO) when no reader is there
+        let mut fifo = std::fs::OpenOptions::new()
+            .write(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open(path)?;
+        return fifo.write_all(line);
+    }
+    let mut stream = UnixStream::connect(path)?;
+    stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+    stream.write_all(line)
+}
+
+#[cfg(windows)]
+fn send(path: &Path, line: &[u8]) -> io::Result<()> {
+    // Named pipes (`\\.\pipe\rtk-events`) open like files
+    let mut pipe = std::fs::OpenOptions::new().write(true).open(path)?;
+    pipe.write_all(line)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn send(_path: &Path, _line: &[u8]) -> io::Result<()> {
+    Err(io::Error::from(io::ErrorKind::Unsupported))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader};
+    use std::os::unix::net::UnixListener;
+
+    fn event<'a>() -> CommandEvent<'a> {
+        CommandEvent {
+            event: "command",
+            timestamp: "2026-01-01T00:00:00+00:00".into(),
+            original_cmd: "git status",
+            rtk_cmd: "rtk git status",
+            input_tokens: 100,
+            output_tokens: 20,
+            saved_tokens: 80,
+            savings_pct: 80.0,
+            exec_time_ms: 5,
+        }
+    }
+
+    #[test]
+    fn test_emit_to_unix_socket() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("events.sock");
+        let listener = UnixListener::bind(&path).unwrap();
+
+        emit(&path, &event());
+
+        let (stream, _) = listener.accept().unwrap();
+        let mut line = String::new();
+        BufReader::new(stream).read_line(&mut line).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(value["event"], "command");
+        assert_eq!(value["rtk_cmd"], "rtk git status");
+        assert_eq!(value["saved_tokens"], 80);
+    }
+
+    #[test]
+    fn test_emit_without_listener_is_silent() {
+        let dir = tempfile::tempdir().unwrap();
+        emit(&dir.path().join("missing.sock"), &event());
+        assert!(send(&dir.path().join("missing.sock"), b"x").is_err());
+    }
+}
@@ -0,0 +1,319 @@
+//! `rtk explain`: what rtk left out of a tracked command's output. Raw
+//! lines are matched against what was shown; the runs that were not are
+//! listed diff-style with what they were (progress, stack frames, repeats,
+//! lines from one file), so nothing important goes unnoticed.
+
+use crate::tracking::Tracker;
+use crate::utils::{strip_ansi, truncate};
+use anyhow::{Context, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::{BTreeMap, HashSet};
+
+/// Omitted runs listed before the rest are counted
+const MAX_RUNS: usize = 30;
+
+/// Characters of a raw line looked up in the shown output (lines shown
+/// truncated still match)
+const MATCH_PREFIX_CHARS: usize = 40;
+
+lazy_static! {
+    static ref PROGRESS_RE: Regex = Regex::new(
+        r"(?i)^\s*(?:compiling|checking|downloading|downloaded|fetching|installing|resolving|updating|building|finished|running|fresh)\b|\d{1,3}(?:\.\d+)?%|[\[|][#=>\-.█░▒▓ ]{4,}[\]|]"
+    )
+    .unwrap();
+    static ref FRAME_RE: Regex =
+        Regex::new(r#"^\s+at\s|^\s+File ".+", line \d+|^\s*\d+:\s+\S+::|^\s+\.\.\. \d+ more"#).unwrap();
+    /// `path/file.ext:12:` or `path/file.ext-12-` (grep, compiler output)
+    static ref FILE_RE: Regex = Regex::new(r"^\s*([\w./\\-]+\.\w+)[:-]\d+[:-]").unwrap();
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Kind {
+    Progress,
+    Frame,
+    Repeat,
+    File(String),
+    Blank,
+    Other,
+}
+
+impl Kind {
+    fn label(&self) -> String {
+        match self {
+            Kind::Progress => "progress".to_string(),
+            Kind::Frame => "stack frames".to_string(),
+            Kind::Repeat => "repeated".to_string(),
+            Kind::File(file) => format!("from {}", file),
+            Kind::Blank => "blank".to_string(),
+            Kind::Other => "other".to_string(),
+        }
+    }
+}
+
+fn classify(line: &str, repeat: bool) -> Kind {
+    if line.trim().is_empty() {
+        Kind::Blank
+    } else if repeat {
+        Kind::Repeat
+    } else if FRAME_RE.is_match(line) {
+        Kind::Frame
+    } else if PROGRESS_RE.is_match(line) {
+        Kind::Progress
+    } else if let Some(caps) = FILE_RE.captures(line) {
+        Kind::File(caps[1].to_string())
+    } else {
+        Kind::Other
+    }
+}
+
+/// A run of consecutive raw lines, 1-based and inclusive
+#[derive(Debug, PartialEq)]
+struct Run {
+    first: usize,
+    last: usize,
+    /// `None` when shown
+    omitted: Option<BTreeMap<Kind, usize>>,
+    sample: String,
+}
+
+/// Which raw lines made it into `shown`, as runs
+fn runs(raw: &str, shown: &str) -> Vec<Run> {
+    let raw = strip_ansi(raw);
+    let shown = strip_ansi(shown);
+    let mut seen: HashSet<&str> = HashSet::new();
+    let mut runs: Vec<Run> = Vec::new();
+
+    for (i, line) in raw.lines().enumerate() {
+        let n = i + 1;
+        let text = line.trim();
+        let key: String = text.chars().take(MATCH_PREFIX_CHARS).collect();
+        let repeat = !seen.insert(text);
+        // Very short lines (`}`, `--`) match anywhere: they follow their run
+        let trivial = text.chars().count() <= 3;
+        let kept = if trivial {
+            runs.last().is_some_and(|r| r.omitted.is_none())
+        } else {
+            !repeat && shown.contains(&key)
+        };
+
+        match runs.last_mut() {
+            Some(run) if run.omitted.is_none() == kept => {
+                run.last = n;
+                if let Some(kinds) = &mut run.omitted {
+                    *kinds.entry(classify(line, repeat)).or_default() += 1;
+                }
+            }
+            _ => runs.push(Run {
+                first: n,
+                last: n,
+                omitted: (!kept).then(|| BTreeMap::from([(classify(line, repeat), 1)])),
+                sample: text.to_string(),
+            }),
+        }
+        if let Some(run) = runs.last_mut() {
+            if run.sample.is_empty() {
+                run.sample = text.to_string();
+            }
+        }
+    }
+    runs
+}
+
+fn range(run: &Run) -> String {
+    if run.first == run.last {
+        run.first.to_string()
+    } else {
+        format!("{}-{}", run.first, run.last)
+    }
+}
+
+fn describe(kinds: &BTreeMap<Kind, usize>) -> String {
+    let total: usize = kinds.values().sum();
+    let mut by_count: Vec<(&Kind, &usize)> = kinds.iter().collect();
+    by_count.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+    if let [(kind, _)] = by_count[..] {
+        return match kind {
+            Kind::File(file) => format!("{} lines from {}", total, file),
+            Kind::Frame => format!("{} stack frames", total),
+            kind => format!(
+                "{} {} line{}",
+                total,
+                kind.label(),
+                if total == 1 { "" } else { "s" }
+            ),
+        };
+    }
+    let parts: Vec<String> = by_count
+        .iter()
+        .map(|(kind, n)| format!("{} {}", n, kind.label()))
+        .collect();
+    format!("{} lines: {}", total, parts.join(", "))
+}
+
+fn format_explain(header: &str, raw: &str, shown: &str) -> String {
+    let runs = runs(raw, shown);
+    let raw_lines = raw.lines().count();
+    let omitted_runs: Vec<&Run> = runs.iter().filter(|r| r.omitted.is_some()).collect();
+    let omitted: usize = omitted_runs.iter().map(|r| r.last - r.first + 1).sum();
+
+    let mut out = vec![format!(
+        "🔎 {}: {} raw lines, {} shown, {} omitted",
+        header,
+        raw_lines,
+        shown.lines().count(),
+        omitted
+    )];
+    if omitted == 0 {
+        out.push("Nothing omitted: every raw line appears in the output".to_string());
+        return out.join("\n");
+    }
+
+    let mut listed = 0;
+    for run in &runs {
+        if listed == MAX_RUNS {
+            break;
+        }
+        match &run.omitted {
+            None => out.push(format!("  = {:<11} kept", range(run))),
+            Some(kinds) => {
+                listed += 1;
+                let sample = if run.sample.is_empty() {
+                    String::new()
+                } else {
+                    format!("  │ {}", truncate(&run.sample, 50))
+                };
+                out.push(format!(
+                    "  - {:<11} {}{}",
+                    range(run),
+                    describe(kinds),
+                    sample
+                ));
+            }
+        }
+    }
+    if omitted_runs.len() > MAX_RUNS {
+        out.push(format!(
+            "  ... +{} more omitted runs",
+            omitted_runs.len() - MAX_RUNS
+        ));
+    }
+
+    let mut totals: BTreeMap<Kind, usize> = BTreeMap::new();
+    for run in &omitted_runs {
+        for (kind, n) in run.omitted.iter().flatten() {
+            // Files are summed together in the total line
+            let kind = match kind {
+                Kind::File(_) => Kind::File(String::new()),
+                kind => kind.clone(),
+            };
+            *totals.entry(kind).or_default() += n;
+        }
+    }
+    let files: HashSet<&String> = omitted_runs
+        .iter()
+        .flat_map(|r| r.omitted.iter().flatten())
+        .filter_map(|(kind, _)| match kind {
+            Kind::File(file) => Some(file),
+            _ => None,
+        })
+        .collect();
+    let parts: Vec<String> = totals
+        .iter()
+        .map(|(kind, n)| match kind {
+            Kind::File(_) => format!(
+                "{} from {} file{}",
+                n,
+                files.len(),
+                if files.len() == 1 { "" } else { "s" }
+            ),
+            kind => format!("{} {}", n, kind.label()),
+        })
+        .collect();
+    out.push(format!("Omitted: {}", parts.join(", ")));
+    out.join("\n")
+}
+
+/// Explain entry `entry` of `rtk replay` (1 = most recent)
+pub fn run(entry: usize, verbose: u8) -> Result<()> {
+    let tracker = Tracker::new().context("Failed to initialize tracking database")?;
+    let replays = tracker.get_replays()?;
+    let selected = entry
+        .checked_sub(1)
+        .and_then(|i| replays.get(i))
+        .with_context(|| {
+            format!(
+                "No tracked output #{} ({} available, see `rtk replay`)",
+                entry,
+                replays.len()
+            )
+        })?;
+    if verbose > 0 {
+        eprintln!("Explaining: {}", selected.original_cmd);
+    }
+
+    let raw = tracker
+        .get_replay_output(selected.id, true)?
+        .context("Replay entry was removed")?;
+    let shown = tracker
+        .get_replay_output(selected.id, false)?
+        .context("Replay entry was removed")?;
+    let header = format!("#{} {}", entry, selected.rtk_cmd);
+    println!("{}", format_explain(&header, &raw, &shown));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(classify("   Compiling serde v1.0.0", false), Kind::Progress);
+        assert_eq!(classify("[=====>    ] 45%", false), Kind::Progress);
+        assert_eq!(
+            classify("\tat org.junit.Runner.run(Runner.java:12)", false),
+            Kind::Frame
+        );
+        assert_eq!(
+            classify("src/main.rs:12:    let x = 1;", false),
+            Kind::File("src/main.rs".into())
+        );
+        assert_eq!(classify("warning: unused", true), Kind::Repeat);
+        assert_eq!(classify("  ", false), Kind::Blank);
+    }
+
+    #[test]
+    fn test_format_explain() {
+        let raw = "\
+   Compiling serde v1.0.0
+   Compiling rtk v0.1.0
+
+running 3 tests
+test a ... ok
+test b ... ok
+test c ... FAILED
+warning: flaky retry
+warning: flaky retry
+failures: c";
+        let shown = "running 3 tests\ntest c ... FAILED\nwarning: flaky retry (×2)\nfailures: c";
+        assert_eq!(
+            format_explain("#1 rtk cargo test", raw, shown),
+            "\
+🔎 #1 rtk cargo test: 10 raw lines, 4 shown, 6 omitted
+  - 1-3         3 lines: 2 progress, 1 blank  │ Compiling serde v1.0.0
+  = 4           kept
+  - 5-6         2 other lines  │ test a ... ok
+  = 7-8         kept
+  - 9           1 repeated line  │ warning: flaky retry
+  = 10          kept
+Omitted: 2 progress, 1 repeated, 1 blank, 2 other"
+        );
+    }
+
+    #[test]
+    fn test_nothing_omitted() {
+        let out = format_explain("#1 rtk ls", "a.rs\nb.rs", "a.rs\nb.rs");
+        assert!(out.ends_with("Nothing omitted: every raw line appears in the output"));
+    }
+}
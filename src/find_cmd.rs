@@ -1,11 +1,12 @@
 use crate::tracking;
+use crate::walk;
 use anyhow::Result;
 use ignore::WalkBuilder;
 use std::collections::HashMap;
 use std::path::Path;
 
 /// Match a filename against a glob pattern (supports `*` and `?`).
-fn glob_match(pattern: &str, name: &str) -> bool {
+pub(crate) fn glob_match(pattern: &str, name: &str) -> bool {
     glob_match_inner(pattern.as_bytes(), name.as_bytes())
 }
 
@@ -41,42 +42,28 @@ pub fn run(
 
     let want_dirs = file_type == "d";
 
-    let walker = WalkBuilder::new(path)
+    let mut walker = WalkBuilder::new(path);
+    walker
         .hidden(true) // skip hidden files/dirs
         .git_ignore(true) // respect .gitignore
         .git_global(true)
-        .git_exclude(true)
-        .build();
-
-    let mut files: Vec<String> = Vec::new();
-
-    for entry in walker {
-        let entry = match entry {
-            Ok(e) => e,
-            Err(_) => continue,
-        };
+        .git_exclude(true);
 
+    let walked = walk::walk(&walker, |entry| {
         let ft = entry.file_type();
-        let is_dir = ft.as_ref().map_or(false, |t| t.is_dir());
+        let is_dir = ft.is_some_and(|t| t.is_dir());
 
         // Filter by type
-        if want_dirs && !is_dir {
-            continue;
-        }
-        if !want_dirs && is_dir {
-            continue;
+        if want_dirs != is_dir {
+            return None;
         }
 
         let entry_path = entry.path();
 
         // Get filename for glob matching
-        let name = match entry_path.file_name() {
-            Some(n) => n.to_string_lossy(),
-            None => continue,
-        };
-
+        let name = entry_path.file_name()?.to_string_lossy();
         if !glob_match(effective_pattern, &name) {
-            continue;
+            return None;
         }
 
         // Store path relative to search root
@@ -86,11 +73,10 @@ pub fn run(
             .to_string_lossy()
             .to_string();
 
-        if !display_path.is_empty() {
-            files.push(display_path);
-        }
-    }
+        (!display_path.is_empty()).then_some(display_path)
+    });
 
+    let mut files = walked.items;
     files.sort();
 
     let raw_output = files.join("\n");
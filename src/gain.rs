@@ -1,10 +1,11 @@
 use crate::display_helpers::{format_duration, print_period_table};
+use crate::output;
 use crate::tracking::{DayStats, MonthStats, Tracker, WeekStats};
+use crate::utils::format_bytes;
 use crate::utils::format_tokens;
 use anyhow::{Context, Result};
 use colored::Colorize; // added: terminal colors
 use serde::Serialize;
-use std::io::IsTerminal; // added: TTY detection for graceful degradation
 
 pub fn run(
     graph: bool,
@@ -240,8 +241,64 @@ pub fn run(
 // ── Display helpers (TTY-aware) ── // added: entire section
 
 /// Format text with bold styling (TTY-aware). // added
+/// `rtk gc`: compact history past the retention window, enforce the size
+/// cap and reclaim disk space
+pub fn run_gc(days: Option<u32>, max_mb: Option<u64>, verbose: u8) -> Result<()> {
+    let tracker = Tracker::new().context("Failed to initialize tracking database")?;
+    let mut retention = tracker.retention();
+    if let Some(days) = days {
+        retention.history_days = days as i64;
+    }
+    if let Some(max_mb) = max_mb {
+        retention.max_bytes = max_mb * 1024 * 1024;
+    }
+    let tracker = tracker.with_retention(retention);
+
+    if verbose > 0 {
+        eprintln!(
+            "Retention: {} days, {}",
+            retention.history_days,
+            format_bytes(retention.max_bytes)
+        );
+    }
+
+    let before = tracker.file_bytes()?;
+    let report = tracker
+        .gc()
+        .context("Failed to compact tracking database")?;
+    let after = tracker.file_bytes()?;
+
+    println!("{}", format_gc_report(&report, before, after));
+    Ok(())
+}
+
+fn format_gc_report(report: &crate::tracking::GcReport, before: u64, after: u64) -> String {
+    let mut out = String::new();
+    if report.compacted_rows > 0 {
+        out.push_str(&format!(
+            "🧹 Compacted {} commands into {} daily aggregates\n",
+            report.compacted_rows, report.aggregate_rows
+        ));
+    }
+    if report.pruned_rows > 0 {
+        out.push_str(&format!(
+            "🗑️  Pruned {} oldest rows to fit the size cap\n",
+            report.pruned_rows
+        ));
+    }
+    if out.is_empty() {
+        out.push_str("✓ Nothing to compact\n");
+    }
+    out.push_str(&format!(
+        "Database: {} → {}",
+        format_bytes(before),
+        format_bytes(after)
+    ));
+    out
+}
+
 fn styled(text: &str, strong: bool) -> String {
-    if !std::io::stdout().is_terminal() {
+    if !output::color_enabled() {
         return text.to_string();
     }
     if strong {
@@ -258,7 +315,7 @@ fn print_kpi(label: &str, value: String) {
 
 /// Colorize percentage based on savings tier (TTY-aware). // added
 fn colorize_pct_cell(pct: f64, padded: &str) -> String {
-    if !std::io::stdout().is_terminal() {
+    if !output::color_enabled() {
         return padded.to_string();
     }
     if pct >= 70.0 {
@@ -289,7 +346,7 @@ fn truncate_for_column(text: &str, width: usize) -> String {
 
 /// Style command names with cyan+bold (TTY-aware). // added
 fn style_command_cell(cmd: &str) -> String {
-    if !std::io::stdout().is_terminal() {
+    if !output::color_enabled() {
         return cmd.to_string();
     }
     cmd.bright_cyan().bold().to_string()
@@ -304,7 +361,7 @@ fn mini_bar(value: usize, max: usize, width: usize) -> String {
     let filled = filled.min(width);
     let mut bar = "█".repeat(filled);
     bar.push_str(&"░".repeat(width - filled));
-    if std::io::stdout().is_terminal() {
+    if output::color_enabled() {
         bar.cyan().to_string()
     } else {
         bar
@@ -316,7 +373,7 @@ fn print_efficiency_meter(pct: f64) {
     let width = 24usize;
     let filled = (((pct / 100.0) * width as f64).round() as usize).min(width);
     let meter = format!("{}{}", "█".repeat(filled), "░".repeat(width - filled));
-    if std::io::stdout().is_terminal() {
+    if output::color_enabled() {
         let pct_str = format!("{pct:.1}%");
         let colored_pct = if pct >= 70.0 {
             pct_str.green().bold().to_string()
@@ -26,7 +26,7 @@ lazy_static! {
 /// Filter markdown body to remove noise while preserving meaningful content.
 /// Removes HTML comments, badge lines, image-only lines, horizontal rules,
 /// and collapses excessive blank lines. Preserves code blocks untouched.
-fn filter_markdown_body(body: &str) -> String {
+pub(crate) fn filter_markdown_body(body: &str) -> String {
     if body.is_empty() {
         return String::new();
     }
@@ -229,6 +229,131 @@ fn run_show(args: &[String], max_lines: Option<usize>, verbose: u8) -> Result<()
     Ok(())
 }
 
+/// One changed file of a commit, from `--numstat` (`None` for binary files)
+#[derive(Debug, PartialEq)]
+pub(crate) struct FileStat {
+    pub(crate) path: String,
+    pub(crate) added: Option<usize>,
+    pub(crate) removed: Option<usize>,
+}
+
+pub(crate) fn parse_numstat(numstat: &str) -> Vec<FileStat> {
+    numstat
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(3, '\t');
+            let added = parts.next()?;
+            let removed = parts.next()?;
+            let path = parts.next()?;
+            Some(FileStat {
+                path: path.to_string(),
+                added: added.parse().ok(),
+                removed: removed.parse().ok(),
+            })
+        })
+        .collect()
+}
+
+/// A path matches a filter containing `*`/`?` as a glob, any other filter
+/// as a substring (`src/parser` selects the whole directory)
+fn matches_filter(path: &str, filters: &[String]) -> bool {
+    filters.iter().any(|f| {
+        if f.contains(['*', '?']) {
+            crate::find_cmd::glob_match(f, path)
+        } else {
+            path.contains(f.as_str())
+        }
+    })
+}
+
+/// Commit header (`%h`, `%an`, `%ar`, `%s`, `%b` separated by NUL) and the
+/// changed files, matching ones marked with `▸`
+fn format_commit_overview(meta: &str, files: &[FileStat], filters: &[String]) -> String {
+    let fields: Vec<&str> = meta.split('\0').collect();
+    let field = |i: usize| fields.get(i).map_or("", |f| f.trim());
+    let mut out = format!("{} {}\n  {}, {}\n", field(0), field(3), field(1), field(2));
+    for line in field(4).lines().filter(|l| !l.trim().is_empty()).take(3) {
+        out.push_str(&format!("  {}\n", line.trim()));
+    }
+
+    let (added, removed) = files.iter().fold((0, 0), |(a, r), f| {
+        (a + f.added.unwrap_or(0), r + f.removed.unwrap_or(0))
+    });
+    out.push_str(&format!(
+        "\n{} files +{} -{}\n",
+        files.len(),
+        added,
+        removed
+    ));
+    for file in files {
+        let counts = match (file.added, file.removed) {
+            (Some(a), Some(r)) => format!("+{} -{}", a, r),
+            _ => "bin".to_string(),
+        };
+        let marker = if !filters.is_empty() && matches_filter(&file.path, filters) {
+            "▸"
+        } else {
+            " "
+        };
+        out.push_str(&format!("{} {:>11}  {}\n", marker, counts, file.path));
+    }
+    out
+}
+
+/// `rtk show`: commit metadata and changed files with +/- counts; hunks
+/// only for the files matching `filters`
+pub fn run_show_commit(rev: &str, filters: &[String], max_lines: usize, verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    let git = |args: &[&str]| -> Result<String> {
+        if verbose > 0 {
+            eprintln!("git {}", args.join(" "));
+        }
+        let output = Command::new("git")
+            .args(args)
+            .output()
+            .context("Failed to run git show")?;
+        if !output.status.success() {
+            eprint!("{}", String::from_utf8_lossy(&output.stderr));
+            std::process::exit(output.status.code().unwrap_or(1));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    };
+
+    let meta = git(&["show", "-s", "--format=%h%x00%an%x00%ar%x00%s%x00%b", rev])?;
+    let files = parse_numstat(&git(&["show", "--numstat", "--format=", rev])?);
+    let mut output = format_commit_overview(&meta, &files, filters);
+
+    let selected: Vec<&str> = files
+        .iter()
+        .filter(|f| matches_filter(&f.path, filters))
+        .map(|f| f.path.as_str())
+        .collect();
+    if !selected.is_empty() {
+        let mut args = vec!["show", "--format=", rev, "--"];
+        args.extend(&selected);
+        output.push_str(&compact_diff(&git(&args)?, max_lines));
+    } else if filters.is_empty() && !files.is_empty() {
+        output.push_str(&format!("\nHunks: rtk show {} <path or glob>\n", rev));
+    } else if !filters.is_empty() {
+        output.push_str(&format!(
+            "\nNo changed file matches {}\n",
+            filters.join(" ")
+        ));
+    }
+    let output = output.trim_end().to_string();
+    println!("{}", output);
+
+    let raw = git(&["show", rev])?;
+    timer.track(
+        &format!("git show {}", rev),
+        format!("rtk show {} {}", rev, filters.join(" ")).trim_end(),
+        &raw,
+        &output,
+    );
+    Ok(())
+}
+
 pub(crate) fn compact_diff(diff: &str, max_lines: usize) -> String {
     let mut result = Vec::new();
     let mut current_file = String::new();
@@ -311,9 +436,20 @@ fn run_log(args: &[String], _max_lines: Option<usize>, verbose: u8) -> Result<()
         arg.starts_with('-') && arg.chars().nth(1).map_or(false, |c| c.is_ascii_digit())
     });
 
+    // --graph-lite is an RTK flag: git gets --graph, we compress the topology
+    let graph_lite = args.iter().any(|arg| arg == "--graph-lite");
+    let args: Vec<String> = args
+        .iter()
+        .filter(|arg| *arg != "--graph-lite")
+        .cloned()
+        .collect();
+
     // Apply RTK defaults only if user didn't specify them
     if !has_format_flag {
-        cmd.args(["--pretty=format:%h %s (%ar) <%an>"]);
+        cmd.arg(format!("--pretty=format:{}", COMPACT_LOG_FORMAT));
+    }
+    if graph_lite {
+        cmd.arg("--graph");
     }
 
     let limit = if !has_limit_flag {
@@ -330,15 +466,17 @@ fn run_log(args: &[String], _max_lines: Option<usize>, verbose: u8) -> Result<()
     };
 
     // Only add --no-merges if user didn't explicitly request merge commits
-    let wants_merges = args
-        .iter()
-        .any(|arg| arg == "--merges" || arg == "--min-parents=2");
+    // (merges are the interesting part of --graph-lite topology)
+    let wants_merges = graph_lite
+        || args
+            .iter()
+            .any(|arg| arg == "--merges" || arg == "--min-parents=2");
     if !wants_merges {
         cmd.arg("--no-merges");
     }
 
     // Pass all user arguments
-    for arg in args {
+    for arg in &args {
         cmd.arg(arg);
     }
 
@@ -357,8 +495,12 @@ fn run_log(args: &[String], _max_lines: Option<usize>, verbose: u8) -> Result<()
         eprintln!("Git log output:");
     }
 
-    // Post-process: truncate long messages, cap lines
-    let filtered = filter_log_output(&stdout, limit);
+    // Post-process: compact our own format, otherwise truncate long messages and cap lines
+    let filtered = if has_format_flag {
+        filter_log_output(&stdout, limit)
+    } else {
+        format_compact_log(&stdout, limit, graph_lite)
+    };
     println!("{}", filtered);
 
     timer.track(
@@ -371,6 +513,95 @@ fn run_log(args: &[String], _max_lines: Option<usize>, verbose: u8) -> Result<()
     Ok(())
 }
 
+/// Pretty format for the default compact log: hash, author, relative date, subject
+const COMPACT_LOG_FORMAT: &str = "%h%x1f%an%x1f%ar%x1f%s";
+
+/// Render `COMPACT_LOG_FORMAT` lines as `abc1234 PS 3d subject`.
+///
+/// With `graph`, the `--graph` prefix is kept but squeezed (`| * ` → `|*`) and
+/// graph-only lines that don't change topology (`| |`) are dropped.
+fn format_compact_log(output: &str, limit: usize, graph: bool) -> String {
+    let mut lines = Vec::new();
+    let mut commits = 0;
+
+    for line in output.lines() {
+        let (prefix, rest) = if graph {
+            let split = line
+                .find(|c: char| !matches!(c, '*' | '|' | '/' | '\\' | '_' | ' ' | '.' | '-'))
+                .unwrap_or(line.len());
+            (squeeze_graph(&line[..split]), &line[split..])
+        } else {
+            (String::new(), line)
+        };
+
+        let fields: Vec<&str> = rest.split('\x1f').collect();
+        if fields.len() < 4 {
+            // Graph-only line: keep it only if it shows a branch or merge
+            if graph && prefix.contains(['/', '\\']) {
+                lines.push(prefix);
+            }
+            continue;
+        }
+
+        if commits >= limit {
+            break;
+        }
+        commits += 1;
+
+        let head = format!(
+            "{}{} {} {}",
+            prefix,
+            fields[0],
+            author_initials(fields[1]),
+            compact_relative_date(fields[2])
+        );
+        let room = 80usize.saturating_sub(head.chars().count() + 1).max(20);
+        lines.push(format!(
+            "{} {}",
+            head,
+            crate::utils::truncate(fields[3], room)
+        ));
+    }
+
+    lines.join("\n").trim().to_string()
+}
+
+/// Drop the padding git puts between graph columns: `| | * ` → `||*`
+fn squeeze_graph(prefix: &str) -> String {
+    prefix.chars().filter(|c| *c != ' ').collect()
+}
+
+/// "Patrick Szymkowiak" → "PS", "dependabot[bot]" → "D"
+fn author_initials(name: &str) -> String {
+    name.split(|c: char| c.is_whitespace() || c == '-' || c == '.')
+        .filter_map(|w| w.chars().next())
+        .filter(|c| c.is_alphanumeric())
+        .take(3)
+        .flat_map(|c| c.to_uppercase())
+        .collect()
+}
+
+/// "3 days ago" → "3d", "1 year, 2 months ago" → "1y"
+fn compact_relative_date(date: &str) -> String {
+    let mut parts = date.split_whitespace();
+    let (Some(n), Some(unit)) = (parts.next(), parts.next()) else {
+        return date.to_string();
+    };
+
+    let suffix = match unit.trim_end_matches(',') {
+        u if u.starts_with("second") => "s",
+        u if u.starts_with("minute") => "m",
+        u if u.starts_with("hour") => "h",
+        u if u.starts_with("day") => "d",
+        u if u.starts_with("week") => "w",
+        u if u.starts_with("month") => "mo",
+        u if u.starts_with("year") => "y",
+        _ => return date.to_string(),
+    };
+
+    format!("{}{}", n, suffix)
+}
+
 /// Filter git log output: truncate long messages, cap lines
 fn filter_log_output(output: &str, limit: usize) -> String {
     let lines: Vec<&str> = output.lines().collect();
@@ -391,7 +622,59 @@ fn filter_log_output(output: &str, limit: usize) -> String {
 }
 
 /// Format porcelain output into compact RTK status display
-fn format_status_output(porcelain: &str) -> String {
+/// `main...origin/main ↑1 ↓2` from the `## ` header of `git status -b`
+fn format_branch(header: &str) -> String {
+    let (name, tracking) = match header.split_once(" [") {
+        Some((name, rest)) => (name, Some(rest.trim_end_matches(']'))),
+        None => (header, None),
+    };
+    match tracking {
+        Some("gone") => format!("{} (upstream gone)", name),
+        Some(delta) => {
+            let arrows: Vec<String> = delta
+                .split(", ")
+                .filter_map(|part| {
+                    let (dir, n) = part.split_once(' ')?;
+                    match dir {
+                        "ahead" => Some(format!("↑{}", n)),
+                        "behind" => Some(format!("↓{}", n)),
+                        _ => None,
+                    }
+                })
+                .collect();
+            format!("{} {}", name, arrows.join(" "))
+        }
+        None if !name.contains("...") && !name.contains(' ') => {
+            format!("{} (no upstream)", name)
+        }
+        None => name.to_string(),
+    }
+}
+
+/// `label: N files: a, b, c ... +2 more` on one line
+fn file_summary(label: &str, files: &[&str], shown: usize) -> String {
+    let mut line = format!(
+        "{}: {} files: {}",
+        label,
+        files.len(),
+        files
+            .iter()
+            .take(shown)
+            .copied()
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    if files.len() > shown {
+        line.push_str(&format!(" ... +{} more", files.len() - shown));
+    }
+    line.push('\n');
+    line
+}
+
+/// Branch with upstream delta, last commit, then one line per kind of
+/// change: the few lines `git status`, `git branch -vv` and
+/// `git rev-list --count` would otherwise take a screen for
+fn format_status_output(porcelain: &str, last_commit: Option<&str>) -> String {
     let lines: Vec<&str> = porcelain.lines().collect();
 
     if lines.is_empty() {
@@ -402,21 +685,18 @@ fn format_status_output(porcelain: &str) -> String {
 
     // Parse branch info
     if let Some(branch_line) = lines.first() {
-        if branch_line.starts_with("##") {
-            let branch = branch_line.trim_start_matches("## ");
-            output.push_str(&format!("📌 {}\n", branch));
+        if let Some(header) = branch_line.strip_prefix("## ") {
+            output.push_str(&format!("📌 {}\n", format_branch(header)));
+            if let Some(commit) = last_commit {
+                output.push_str(&format!("   {}\n", commit));
+            }
         }
     }
 
-    // Count changes by type
-    let mut staged = 0;
-    let mut modified = 0;
-    let mut untracked = 0;
-    let mut conflicts = 0;
-
     let mut staged_files = Vec::new();
     let mut modified_files = Vec::new();
     let mut untracked_files = Vec::new();
+    let mut conflicts = 0;
 
     for line in lines.iter().skip(1) {
         if line.len() < 3 {
@@ -426,62 +706,39 @@ fn format_status_output(porcelain: &str) -> String {
         let file = line.get(3..).unwrap_or("");
 
         match status.chars().next().unwrap_or(' ') {
-            'M' | 'A' | 'D' | 'R' | 'C' => {
-                staged += 1;
-                staged_files.push(file);
-            }
+            'M' | 'A' | 'D' | 'R' | 'C' => staged_files.push(file),
             'U' => conflicts += 1,
             _ => {}
         }
 
-        match status.chars().nth(1).unwrap_or(' ') {
-            'M' | 'D' => {
-                modified += 1;
-                modified_files.push(file);
-            }
-            _ => {}
+        if let 'M' | 'D' = status.chars().nth(1).unwrap_or(' ') {
+            modified_files.push(file);
         }
 
         if status == "??" {
-            untracked += 1;
             untracked_files.push(file);
         }
     }
 
-    // Build summary
-    if staged > 0 {
-        output.push_str(&format!("✅ Staged: {} files\n", staged));
-        for f in staged_files.iter().take(5) {
-            output.push_str(&format!("   {}\n", f));
-        }
-        if staged_files.len() > 5 {
-            output.push_str(&format!("   ... +{} more\n", staged_files.len() - 5));
-        }
+    if !staged_files.is_empty() {
+        output.push_str(&file_summary("✅ Staged", &staged_files, 5));
     }
-
-    if modified > 0 {
-        output.push_str(&format!("📝 Modified: {} files\n", modified));
-        for f in modified_files.iter().take(5) {
-            output.push_str(&format!("   {}\n", f));
-        }
-        if modified_files.len() > 5 {
-            output.push_str(&format!("   ... +{} more\n", modified_files.len() - 5));
-        }
+    if !modified_files.is_empty() {
+        output.push_str(&file_summary("📝 Modified", &modified_files, 5));
     }
-
-    if untracked > 0 {
-        output.push_str(&format!("❓ Untracked: {} files\n", untracked));
-        for f in untracked_files.iter().take(3) {
-            output.push_str(&format!("   {}\n", f));
-        }
-        if untracked_files.len() > 3 {
-            output.push_str(&format!("   ... +{} more\n", untracked_files.len() - 3));
-        }
+    if !untracked_files.is_empty() {
+        output.push_str(&file_summary("❓ Untracked", &untracked_files, 3));
     }
-
     if conflicts > 0 {
         output.push_str(&format!("⚠️  Conflicts: {} files\n", conflicts));
     }
+    if staged_files.is_empty()
+        && modified_files.is_empty()
+        && untracked_files.is_empty()
+        && conflicts == 0
+    {
+        output.push_str("✨ Clean working tree\n");
+    }
 
     output.trim_end().to_string()
 }
@@ -574,7 +831,16 @@ fn run_status(args: &[String], verbose: u8) -> Result<()> {
     let formatted = if !stderr.is_empty() && stderr.contains("not a git repository") {
         "Not a git repository".to_string()
     } else {
-        format_status_output(&stdout)
+        // Fails on a branch without commits: no commit line then
+        let last_commit = Command::new("git")
+            .args(["log", "-1", "--format=%h %s (%cr)"])
+            .output()
+            .ok()
+            .filter(|o| o.status.success())
+            .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+            .filter(|c| !c.is_empty())
+            .map(|c| crate::utils::truncate(&c, 72));
+        format_status_output(&stdout, last_commit.as_deref())
     };
 
     println!("{}", formatted);
@@ -1326,6 +1592,7 @@ pub fn run_passthrough(args: &[OsString], verbose: u8) -> Result<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::stable::assert_format;
 
     #[test]
     fn test_compact_diff() {
@@ -1385,14 +1652,14 @@ mod tests {
     #[test]
     fn test_format_status_output_clean() {
         let porcelain = "";
-        let result = format_status_output(porcelain);
+        let result = format_status_output(porcelain, None);
         assert_eq!(result, "Clean working tree");
     }
 
     #[test]
     fn test_format_status_output_modified_files() {
         let porcelain = "## main...origin/main\n M src/main.rs\n M src/lib.rs\n";
-        let result = format_status_output(porcelain);
+        let result = format_status_output(porcelain, None);
         assert!(result.contains("📌 main...origin/main"));
         assert!(result.contains("📝 Modified: 2 files"));
         assert!(result.contains("src/main.rs"));
@@ -1404,7 +1671,7 @@ mod tests {
     #[test]
     fn test_format_status_output_untracked_files() {
         let porcelain = "## feature/new\n?? temp.txt\n?? debug.log\n?? test.sh\n";
-        let result = format_status_output(porcelain);
+        let result = format_status_output(porcelain, None);
         assert!(result.contains("📌 feature/new"));
         assert!(result.contains("❓ Untracked: 3 files"));
         assert!(result.contains("temp.txt"));
@@ -1421,7 +1688,7 @@ M  staged.rs
 A  added.rs
 ?? untracked.txt
 "#;
-        let result = format_status_output(porcelain);
+        let result = format_status_output(porcelain, None);
         assert!(result.contains("📌 main"));
         assert!(result.contains("✅ Staged: 2 files"));
         assert!(result.contains("staged.rs"));
@@ -1430,6 +1697,7 @@ A  added.rs
         assert!(result.contains("modified.rs"));
         assert!(result.contains("❓ Untracked: 1 files"));
         assert!(result.contains("untracked.txt"));
+        assert_format!("git_status", result);
     }
 
     #[test]
@@ -1444,7 +1712,7 @@ M  file5.rs
 M  file6.rs
 M  file7.rs
 "#;
-        let result = format_status_output(porcelain);
+        let result = format_status_output(porcelain, None);
         assert!(result.contains("✅ Staged: 7 files"));
         assert!(result.contains("file1.rs"));
         assert!(result.contains("file5.rs"));
@@ -1488,6 +1756,62 @@ M  file7.rs
         assert_eq!(result.lines().count(), 5);
     }
 
+    #[test]
+    fn test_format_compact_log() {
+        let output = "abc1234\x1fPatrick Szymkowiak\x1f2 days ago\x1ffix: typo\n\
+                      def5678\x1fagent\x1f3 weeks ago\x1ffeat: add log\n";
+        let result = format_compact_log(output, 10, false);
+        assert_eq!(
+            result,
+            "abc1234 PS 2d fix: typo\ndef5678 A 3w feat: add log"
+        );
+    }
+
+    #[test]
+    fn test_format_compact_log_caps_and_truncates() {
+        let output = (0..20)
+            .map(|i| format!("h{}\x1fA B\x1f1 hour ago\x1f{}", i, "x".repeat(100)))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let result = format_compact_log(&output, 5, false);
+        assert_eq!(result.lines().count(), 5);
+        assert!(result.lines().all(|l| l.chars().count() <= 80));
+        assert!(result.contains("..."));
+    }
+
+    #[test]
+    fn test_format_compact_log_graph_lite() {
+        let output = "*   abc1234\x1fA B\x1f1 day ago\x1fMerge branch 'x'\n\
+                      |\\  \n\
+                      | * def5678\x1fA B\x1f2 days ago\x1fwork on x\n\
+                      | | \n\
+                      |/  \n\
+                      * 0123456\x1fA B\x1f3 days ago\x1fbase\n";
+        let result = format_compact_log(output, 10, true);
+        let lines: Vec<&str> = result.lines().collect();
+        assert_eq!(lines[0], "*abc1234 AB 1d Merge branch 'x'");
+        assert_eq!(lines[1], "|\\");
+        assert_eq!(lines[2], "|*def5678 AB 2d work on x");
+        assert_eq!(lines[3], "|/");
+        assert_eq!(lines[4], "*0123456 AB 3d base");
+    }
+
+    #[test]
+    fn test_compact_relative_date() {
+        assert_eq!(compact_relative_date("10 seconds ago"), "10s");
+        assert_eq!(compact_relative_date("5 minutes ago"), "5m");
+        assert_eq!(compact_relative_date("2 months ago"), "2mo");
+        assert_eq!(compact_relative_date("1 year, 2 months ago"), "1y");
+        assert_eq!(compact_relative_date("yesterday"), "yesterday");
+    }
+
+    #[test]
+    fn test_author_initials() {
+        assert_eq!(author_initials("Patrick Szymkowiak"), "PS");
+        assert_eq!(author_initials("dependabot[bot]"), "D");
+        assert_eq!(author_initials("Jean-Luc Picard"), "JLP");
+    }
+
     #[test]
     fn test_filter_status_with_args() {
         let output = r#"On branch main
@@ -1537,10 +1861,69 @@ no changes added to commit (use "git add" and/or "git commit -a")
         assert!(result.contains("..."));
     }
 
+    #[test]
+    fn test_parse_numstat_and_filters() {
+        let files =
+            parse_numstat("12\t3\tsrc/grep_cmd.rs\n-\t-\tassets/logo.png\n1\t0\tREADME.md\n");
+        assert_eq!(files.len(), 3);
+        assert_eq!(files[1].added, None);
+
+        let filters = vec!["*.rs".to_string(), "READ".to_string()];
+        assert!(matches_filter("src/grep_cmd.rs", &filters));
+        assert!(matches_filter("README.md", &filters));
+        assert!(!matches_filter("assets/logo.png", &filters));
+        assert!(!matches_filter("src/a.rs", &[]));
+    }
+
+    #[test]
+    fn test_format_commit_overview() {
+        let meta = "abc1234\0Ada\x003 days ago\0Fix parser\0Handles nested groups.\n\nCloses #12\n";
+        let files = parse_numstat("12\t3\tsrc/parser.rs\n-\t-\tlogo.png\n");
+        let out = format_commit_overview(meta, &files, &["parser".to_string()]);
+        assert_eq!(
+            out,
+            "abc1234 Fix parser\n  Ada, 3 days ago\n  Handles nested groups.\n  Closes #12\n\n\
+             2 files +12 -3\n▸      +12 -3  src/parser.rs\n          bin  logo.png\n"
+        );
+    }
+
+    #[test]
+    fn test_format_status_output_compact() {
+        let porcelain = "## main...origin/main [ahead 2, behind 1]\nM  a.rs\n M b.rs\n?? c.txt\n";
+        let result = format_status_output(porcelain, Some("abc1234 Fix parser (2 hours ago)"));
+        assert_eq!(
+            result,
+            "📌 main...origin/main ↑2 ↓1\n   abc1234 Fix parser (2 hours ago)\n\
+             ✅ Staged: 1 files: a.rs\n📝 Modified: 1 files: b.rs\n❓ Untracked: 1 files: c.txt"
+        );
+    }
+
+    #[test]
+    fn test_format_branch() {
+        assert_eq!(format_branch("main...origin/main"), "main...origin/main");
+        assert_eq!(
+            format_branch("dev...origin/dev [behind 3]"),
+            "dev...origin/dev ↓3"
+        );
+        assert_eq!(
+            format_branch("old...origin/old [gone]"),
+            "old...origin/old (upstream gone)"
+        );
+        assert_eq!(format_branch("feature/x"), "feature/x (no upstream)");
+        assert_eq!(
+            format_branch("No commits yet on main"),
+            "No commits yet on main"
+        );
+        assert_eq!(
+            format_status_output("## main...origin/main\n", None),
+            "📌 main...origin/main\n✨ Clean working tree"
+        );
+    }
+
     #[test]
     fn test_format_status_output_thai_filename() {
         let porcelain = "## main\n M สวัสดี.txt\n?? ทดสอบ.rs\n";
-        let result = format_status_output(porcelain);
+        let result = format_status_output(porcelain, None);
         // Should not panic
         assert!(result.contains("📌 main"));
         assert!(result.contains("สวัสดี.txt"));
@@ -1550,7 +1933,7 @@ no changes added to commit (use "git add" and/or "git commit -a")
     #[test]
     fn test_format_status_output_emoji_filename() {
         let porcelain = "## main\nA  🎉-party.txt\n M 日本語ファイル.rs\n";
-        let result = format_status_output(porcelain);
+        let result = format_status_output(porcelain, None);
         assert!(result.contains("📌 main"));
     }
 
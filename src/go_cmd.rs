@@ -1,5 +1,5 @@
 use crate::tracking;
-use crate::utils::truncate;
+use crate::utils::{self, truncate};
 use anyhow::{Context, Result};
 use serde::Deserialize;
 use std::collections::HashMap;
@@ -58,10 +58,7 @@ pub fn run_test(args: &[String], verbose: u8) -> Result<()> {
     let stderr = String::from_utf8_lossy(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
 
-    let exit_code = output
-        .status
-        .code()
-        .unwrap_or(if output.status.success() { 0 } else { 1 });
+    let exit_code = utils::exit_code(output.status);
     let filtered = filter_go_test_json(&stdout);
 
     if let Some(hint) = crate::tee::tee_and_hint(&raw, "go_test", exit_code) {
@@ -83,9 +80,7 @@ pub fn run_test(args: &[String], verbose: u8) -> Result<()> {
     );
 
     // Preserve exit code for CI/CD
-    if !output.status.success() {
-        std::process::exit(exit_code);
-    }
+    utils::exit_with("go test", exit_code, verbose);
 
     Ok(())
 }
@@ -112,10 +107,7 @@ pub fn run_build(args: &[String], verbose: u8) -> Result<()> {
     let stderr = String::from_utf8_lossy(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
 
-    let exit_code = output
-        .status
-        .code()
-        .unwrap_or(if output.status.success() { 0 } else { 1 });
+    let exit_code = utils::exit_code(output.status);
     let filtered = filter_go_build(&raw);
 
     if let Some(hint) = crate::tee::tee_and_hint(&raw, "go_build", exit_code) {
@@ -136,9 +128,7 @@ pub fn run_build(args: &[String], verbose: u8) -> Result<()> {
     );
 
     // Preserve exit code for CI/CD
-    if !output.status.success() {
-        std::process::exit(exit_code);
-    }
+    utils::exit_with("go build", exit_code, verbose);
 
     Ok(())
 }
@@ -165,10 +155,7 @@ pub fn run_vet(args: &[String], verbose: u8) -> Result<()> {
     let stderr = String::from_utf8_lossy(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
 
-    let exit_code = output
-        .status
-        .code()
-        .unwrap_or(if output.status.success() { 0 } else { 1 });
+    let exit_code = utils::exit_code(output.status);
     let filtered = filter_go_vet(&raw);
 
     if let Some(hint) = crate::tee::tee_and_hint(&raw, "go_vet", exit_code) {
@@ -189,9 +176,7 @@ pub fn run_vet(args: &[String], verbose: u8) -> Result<()> {
     );
 
     // Preserve exit code for CI/CD
-    if !output.status.success() {
-        std::process::exit(exit_code);
-    }
+    utils::exit_with("go vet", exit_code, verbose);
 
     Ok(())
 }
@@ -1,253 +1,3879 @@
+use crate::archive;
+use crate::filter::Language;
+use crate::index::{self, Index};
+use crate::layout;
+use crate::outline_cmd;
+use crate::output;
+use crate::paths;
+use crate::sarif;
+use crate::symbols_cmd;
 use crate::tracking;
+use crate::utils::{self, clusters};
+use crate::walk;
 use anyhow::{Context, Result};
+use colored::Colorize;
+use ignore::overrides::OverrideBuilder;
+use ignore::WalkBuilder;
 use regex::Regex;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::hash::{Hash, Hasher};
+use std::path::Path;
 use std::process::Command;
 
-pub fn run(
-    pattern: &str,
-    path: &str,
-    max_line_len: usize,
-    max_results: usize,
-    context_only: bool,
-    file_type: Option<&str>,
-    extra_args: &[String],
-    verbose: u8,
-) -> Result<()> {
-    let timer = tracking::TimedExecution::start();
+/// Machine-readable grep result for `--format json`
+#[derive(Debug, Serialize)]
+struct GrepReport {
+    pattern: String,
+    /// With several patterns: what each of them matched
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    patterns: Vec<PatternCount>,
+    total: usize,
+    files: Vec<GrepFile>,
+    /// Minified files, lockfiles and sourcemaps whose matches were not shown
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    collapsed: Vec<Collapsed>,
+    truncated: bool,
+    #[serde(flatten)]
+    diagnostics: Diagnostics,
+}
 
-    if verbose > 0 {
-        eprintln!("grep: '{}' in {}", pattern, path);
-    }
+/// A file whose matches are noise, reported as a count only
+#[derive(Debug, Clone, Serialize, PartialEq)]
+struct Collapsed {
+    path: String,
+    matches: usize,
+    /// `minified`, `lockfile` or `sourcemap`
+    kind: &'static str,
+}
+
+/// Matches of one of several patterns (`-e a -e b`)
+#[derive(Debug, Clone, Serialize, PartialEq)]
+struct PatternCount {
+    pattern: String,
+    matches: usize,
+    files: usize,
+}
+
+/// What the search tool wrote to stderr, split by severity. Messages with
+/// the same cause are merged (`Permission denied: ./a, ./b`).
+#[derive(Debug, Default, Clone, Serialize, PartialEq)]
+struct Diagnostics {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    errors: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    warnings: Vec<String>,
+    /// Messages before merging
+    #[serde(skip)]
+    error_count: usize,
+    #[serde(skip)]
+    warning_count: usize,
+}
+
+/// Diagnostics listed per severity before the rest are counted
+const MAX_DIAGNOSTICS: usize = 5;
 
-    // Fix: convert BRE alternation \| → | for rg (which uses PCRE-style regex)
-    let rg_pattern = pattern.replace(r"\|", "|");
+/// Paths listed for one merged cause
+const MAX_DIAGNOSTIC_PATHS: usize = 3;
 
-    let mut rg_cmd = Command::new("rg");
-    rg_cmd.args(["-n", "--no-heading", &rg_pattern, path]);
+/// Prefixes the search tools put before each message
+const TOOL_PREFIXES: [&str; 4] = ["rg: ", "grep: ", "ugrep: ", "git: "];
+
+/// Split stderr of rg/grep/ugrep/git grep into messages. A message ending
+/// in `:` opens a block (rg's multi-line regex errors) that runs up to the
+/// next tool prefix; indented lines always continue. Carets under the
+/// pattern are dropped.
+fn stderr_messages(stderr: &str) -> Vec<String> {
+    let mut messages: Vec<String> = Vec::new();
+    let mut open = false;
+    for line in stderr.lines() {
+        let text = line.trim();
+        if text.is_empty() || text.chars().all(|c| matches!(c, '^' | '~' | ' ')) {
+            continue;
+        }
+        let unprefixed = TOOL_PREFIXES
+            .iter()
+            .find_map(|prefix| text.strip_prefix(prefix));
+        let continues = line.starts_with(char::is_whitespace) || (open && unprefixed.is_none());
+        match messages.last_mut() {
+            Some(last) if continues => {
+                last.push(' ');
+                last.push_str(text);
+            }
+            _ => {
+                let text = unprefixed.unwrap_or(text);
+                open = text.ends_with(':');
+                messages.push(text.to_string());
+            }
+        }
+    }
+    messages
+}
 
-    if let Some(ft) = file_type {
-        rg_cmd.arg("--type").arg(ft);
+/// Merge messages sharing a cause (the part after `path: `), in order of
+/// first appearance
+fn merge_causes(messages: &[String]) -> Vec<String> {
+    let mut causes: Vec<(&str, Vec<&str>)> = Vec::new();
+    for message in messages {
+        let (subject, cause) = message.split_once(": ").unwrap_or(("", message));
+        match causes.iter_mut().find(|(c, _)| *c == cause) {
+            Some((_, subjects)) => subjects.push(subject),
+            None => causes.push((cause, vec![subject])),
+        }
     }
+    causes
+        .into_iter()
+        .map(|(cause, subjects)| match subjects[..] {
+            [""] => cause.to_string(),
+            [subject] => format!("{}: {}", subject, cause),
+            _ => {
+                let mut listed: Vec<&str> = subjects
+                    .iter()
+                    .take(MAX_DIAGNOSTIC_PATHS)
+                    .copied()
+                    .collect();
+                let more = subjects.len().saturating_sub(MAX_DIAGNOSTIC_PATHS);
+                let more = format!("+{} more", more);
+                if subjects.len() > MAX_DIAGNOSTIC_PATHS {
+                    listed.push(&more);
+                }
+                format!("{}: {}", cause, listed.join(", "))
+            }
+        })
+        .collect()
+}
 
-    for arg in extra_args {
-        // Fix: skip grep-ism -r flag (rg is recursive by default; rg -r means --replace)
-        if arg == "-r" || arg == "--recursive" {
+fn classify_stderr(stderr: &str) -> Diagnostics {
+    let (warnings, errors): (Vec<String>, Vec<String>) =
+        stderr_messages(stderr).into_iter().partition(|message| {
+            let lower = message.to_lowercase();
+            lower.contains("warning") || lower.starts_with("binary file")
+        });
+    Diagnostics {
+        errors: merge_causes(&errors),
+        warnings: merge_causes(&warnings),
+        error_count: errors.len(),
+        warning_count: warnings.len(),
+    }
+}
+
+/// Text block for `diagnostics`, empty when there are none
+fn format_diagnostics(diagnostics: &Diagnostics) -> String {
+    let mut out = String::new();
+    for (icon, what, count, messages) in [
+        ("❌", "error", diagnostics.error_count, &diagnostics.errors),
+        (
+            "⚠️",
+            "warning",
+            diagnostics.warning_count,
+            &diagnostics.warnings,
+        ),
+    ] {
+        if count == 0 {
             continue;
         }
-        rg_cmd.arg(arg);
+        out.push_str(&format!(
+            "{} {} {}{}:\n",
+            icon,
+            count,
+            what,
+            if count == 1 { "" } else { "s" }
+        ));
+        for message in messages.iter().take(MAX_DIAGNOSTICS) {
+            out.push_str(&format!("  {}\n", message));
+        }
+        if messages.len() > MAX_DIAGNOSTICS {
+            out.push_str(&format!(
+                "  ... +{} more\n",
+                messages.len() - MAX_DIAGNOSTICS
+            ));
+        }
     }
+    out
+}
+
+/// `--group-by dir` report for `--format json`
+#[derive(Debug, Serialize)]
+struct GrepDirReport {
+    pattern: String,
+    total: usize,
+    dirs: Vec<GrepDir>,
+    truncated: bool,
+    #[serde(flatten)]
+    diagnostics: Diagnostics,
+}
 
-    let output = rg_cmd
-        .output()
-        .or_else(|_| Command::new("grep").args(["-rn", pattern, path]).output())
-        .context("grep/rg failed")?;
+#[derive(Debug, Serialize, PartialEq)]
+struct GrepDir {
+    path: String,
+    matches: usize,
+    files: usize,
+}
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let exit_code = output.status.code().unwrap_or(1);
+/// `--group-by symbol` report for `--format json`
+#[derive(Debug, Serialize)]
+struct GrepSymbolReport {
+    pattern: String,
+    total: usize,
+    symbols: Vec<GrepSymbol>,
+    truncated: bool,
+    #[serde(flatten)]
+    diagnostics: Diagnostics,
+}
 
-    let raw_output = stdout.to_string();
+/// Matches inside one definition
+#[derive(Debug, Serialize, PartialEq)]
+struct GrepSymbol {
+    path: String,
+    /// `fn load`; none for matches outside any definition
+    symbol: Option<String>,
+    /// Line of the first match
+    line: usize,
+    matches: usize,
+}
 
-    if stdout.trim().is_empty() {
-        // Show stderr for errors (bad regex, missing file, etc.)
-        if exit_code == 2 {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            if !stderr.trim().is_empty() {
-                eprintln!("{}", stderr.trim());
-            }
+#[derive(Debug, Serialize)]
+struct GrepFile {
+    path: String,
+    count: usize,
+    matches: Vec<LineMatch>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    context: Vec<LineMatch>,
+    /// Other paths with the same content (symlinks, vendored copies)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    duplicates: Vec<String>,
+}
+
+/// One line of a search result
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct LineMatch {
+    /// 1-based line number
+    pub line: usize,
+    /// 1-based char column of the match, with `--column`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<usize>,
+    pub content: String,
+    /// With several patterns: the ones matching this line (0-based)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub patterns: Vec<usize>,
+    /// Innermost definition around the line (`fn load`), with `--scope`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+}
+
+impl LineMatch {
+    /// `42` or, with a column, `42:17`
+    fn label(&self) -> String {
+        match self.column {
+            Some(column) => format!("{}:{}", self.line, column),
+            None => self.line.to_string(),
         }
-        let msg = format!("🔍 0 for '{}'", pattern);
-        println!("{}", msg);
-        timer.track(
-            &format!("grep -rn '{}' {}", pattern, path),
-            "rtk grep",
-            &raw_output,
-            &msg,
-        );
-        if exit_code != 0 {
-            std::process::exit(exit_code);
+    }
+
+    /// `[1,3] ` for a line matching the first and third patterns
+    fn tags(&self) -> String {
+        if self.patterns.is_empty() {
+            String::new()
+        } else {
+            format!("{} ", pattern_tags(self.patterns.iter().copied()))
         }
-        return Ok(());
     }
+}
 
-    let mut by_file: HashMap<String, Vec<(usize, String)>> = HashMap::new();
-    let mut total = 0;
+/// `[1,3]`: 1-based pattern numbers
+fn pattern_tags(patterns: impl Iterator<Item = usize>) -> String {
+    let numbers: Vec<String> = patterns.map(|p| (p + 1).to_string()).collect();
+    format!("[{}]", numbers.join(","))
+}
 
-    for line in stdout.lines() {
-        let parts: Vec<&str> = line.splitn(3, ':').collect();
+/// Matches of one file, as returned by [`search`]
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct FileMatches {
+    pub path: String,
+    pub matches: Vec<LineMatch>,
+}
 
-        let (file, line_num, content) = if parts.len() == 3 {
-            let ln = parts[1].parse().unwrap_or(0);
-            (parts[0].to_string(), ln, parts[2])
-        } else if parts.len() == 2 {
-            let ln = parts[0].parse().unwrap_or(0);
-            (path.to_string(), ln, parts[1])
-        } else {
-            continue;
-        };
+/// Everything [`search`] needs besides the pattern and paths
+#[derive(Debug, Clone, Default)]
+pub struct SearchOptions {
+    /// File types as for `rtk grep -t` (`rust`, `py`, or any extension)
+    pub file_types: Vec<String>,
+    pub matching: MatchOptions,
+    /// Extra ripgrep flags
+    pub extra_args: Vec<String>,
+}
 
-        total += 1;
-        let cleaned = clean_line(content, max_line_len, context_only, pattern);
-        by_file.entry(file).or_default().push((line_num, cleaned));
+/// Lines of context around matches (-A/-B/-C)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ContextLines {
+    pub before: usize,
+    pub after: usize,
+}
+
+impl ContextLines {
+    fn is_empty(&self) -> bool {
+        self.before == 0 && self.after == 0
     }
+}
 
-    let mut rtk_output = String::new();
-    rtk_output.push_str(&format!("🔍 {} in {}F:\n\n", total, by_file.len()));
+/// Order of files in the report (--sort)
+#[derive(Debug, Clone, Copy, Default, PartialEq, clap::ValueEnum)]
+pub enum SortOrder {
+    /// Match density and path: `src/` before tests, tests before vendored code
+    Relevance,
+    /// Most matches first
+    Count,
+    #[default]
+    Path,
+    /// Most recently modified first
+    Mtime,
+}
 
-    let mut shown = 0;
-    let mut files: Vec<_> = by_file.iter().collect();
-    files.sort_by_key(|(f, _)| *f);
+/// Path components of vendored or generated code
+const VENDORED_DIRS: &[&str] = &[
+    "vendor",
+    "node_modules",
+    "third_party",
+    "dist",
+    "build",
+    "target",
+    "generated",
+];
 
-    for (file, matches) in files {
-        if shown >= max_results {
-            break;
+/// Path components of tests, fixtures and examples
+const TEST_DIRS: &[&str] = &[
+    "test",
+    "tests",
+    "spec",
+    "__tests__",
+    "testdata",
+    "fixtures",
+    "examples",
+    "benches",
+];
+
+/// Path components of the main sources
+const SOURCE_DIRS: &[&str] = &["src", "lib", "app", "pkg", "cmd"];
+
+/// Generated dependency lockfiles
+const LOCKFILES: &[&str] = &[
+    "package-lock.json",
+    "npm-shrinkwrap.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+    "bun.lock",
+    "Cargo.lock",
+    "poetry.lock",
+    "Pipfile.lock",
+    "uv.lock",
+    "Gemfile.lock",
+    "composer.lock",
+    "go.sum",
+    "flake.lock",
+];
+
+/// Lines longer than this are minified whatever they contain
+const MINIFIED_LINE_CHARS: usize = 500;
+
+/// Lines longer than this are minified when dense in symbols
+const DENSE_LINE_CHARS: usize = 200;
+
+/// Share of punctuation among the non-space chars of a minified line
+const SYMBOL_DENSITY: f64 = 0.3;
+
+/// Files handed to one grep invocation by the fallback
+const GREP_FILES_PER_RUN: usize = 500;
+
+/// Fewer files than this are not worth a grep process of their own
+const GREP_MIN_FILES_PER_RUN: usize = 50;
+
+/// Lines shown per file unless `--per-file` says otherwise
+pub const DEFAULT_PER_FILE: usize = 10;
+
+/// How matches are presented (--mark/--column/--sort/--per-file)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShowOptions {
+    pub sort: SortOrder,
+    /// Most lines shown for any one file
+    pub per_file: usize,
+    /// Wrap the matched text in `«»` (colored instead when color is on)
+    pub mark: bool,
+    /// Report the column where the match starts
+    pub column: bool,
+    /// Collapse minified files, lockfiles and sourcemaps to a match count
+    pub collapse: bool,
+    /// Report files with the same content once
+    pub dedup: bool,
+    /// Name the definition (function, class) each match is in
+    pub scope: bool,
+}
+
+impl Default for ShowOptions {
+    fn default() -> Self {
+        Self {
+            sort: SortOrder::default(),
+            per_file: DEFAULT_PER_FILE,
+            mark: false,
+            column: false,
+            collapse: true,
+            dedup: true,
+            scope: false,
         }
+    }
+}
 
-        let file_display = compact_path(file);
-        rtk_output.push_str(&format!("📄 {} ({}):\n", file_display, matches.len()));
+/// How the matched substring is set apart in a cleaned line
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Mark {
+    None,
+    Plain,
+    Ansi,
+}
 
-        for (line_num, content) in matches.iter().take(10) {
-            rtk_output.push_str(&format!("  {:>4}: {}\n", line_num, content));
-            shown += 1;
-            if shown >= max_results {
-                break;
+impl Mark {
+    fn wrap(self, matched: &str) -> String {
+        match self {
+            Mark::None => matched.to_string(),
+            Mark::Plain => format!("«{}»", matched),
+            Mark::Ansi => matched.red().bold().to_string(),
+        }
+    }
+}
+
+/// How the pattern is matched (-i/-s/-w/-F/-U/-P) and which files are read
+/// (--hidden/--no-ignore). Shared by the search and by `clean_line`, so
+/// truncation centers on the match rg actually found.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MatchOptions {
+    pub ignore_case: bool,
+    /// No smart case: a lowercase pattern only matches lowercase text
+    pub case_sensitive: bool,
+    pub word: bool,
+    pub fixed: bool,
+    /// Matches may span lines; each becomes one entry (ripgrep only)
+    pub multiline: bool,
+    /// PCRE2 syntax: lookaround, backreferences
+    pub pcre2: bool,
+    /// Search hidden files and directories too
+    pub hidden: bool,
+    /// Don't respect .gitignore, .ignore and .rgignore files
+    pub no_ignore: bool,
+    /// Search inside .gz, .tar(.gz) and .zip files too, members named
+    /// `archive!member`
+    pub search_zip: bool,
+}
+
+impl MatchOptions {
+    /// Smart case, like `rg -S`: patterns without an uppercase letter match
+    /// case-insensitively, unless -s/--case-sensitive. Resolved once so every
+    /// backend and `clean_line` agree on it.
+    pub fn smart_case(mut self, patterns: &[String]) -> Self {
+        if !self.case_sensitive && !patterns.iter().any(|p| has_uppercase(p, self.fixed)) {
+            self.ignore_case = true;
+        }
+        self
+    }
+}
+
+/// Whether `pattern` matches an uppercase letter literally: escapes such
+/// as `\S`, `\W` or `\p{Lu}` don't count, unless the pattern is fixed
+fn has_uppercase(pattern: &str, fixed: bool) -> bool {
+    if fixed {
+        return pattern.chars().any(char::is_uppercase);
+    }
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                // `\p{Lu}`: the class name goes with the escape
+                if let Some('p' | 'P') = chars.next() {
+                    if chars.clone().next() == Some('{') {
+                        chars.by_ref().find(|&c| c == '}');
+                    } else {
+                        chars.next();
+                    }
+                }
             }
+            c if c.is_uppercase() => return true,
+            _ => {}
         }
+    }
+    false
+}
+
+/// Locates the match within a result line
+struct Matcher {
+    regex: Option<Regex>,
+    /// `regex`, or the literal fallback as a case-insensitive regex
+    highlighter: Regex,
+    /// Case-insensitive literal search when the pattern isn't valid for the
+    /// regex crate (rg supports a few extra constructs)
+    literal: String,
+}
 
-        if matches.len() > 10 {
-            rtk_output.push_str(&format!("  +{}\n", matches.len() - 10));
+impl Matcher {
+    fn new(pattern: &str, opts: MatchOptions) -> Self {
+        Self::from_source(&regex_source(pattern, opts), pattern)
+    }
+
+    /// Matches any of `patterns`
+    fn any(patterns: &[String], opts: MatchOptions) -> Self {
+        if let [pattern] = patterns {
+            return Self::new(pattern, opts);
         }
-        rtk_output.push('\n');
+        let alternatives: Vec<String> = patterns
+            .iter()
+            .map(|p| format!("(?:{})", regex_source(p, opts)))
+            .collect();
+        Self::from_source(&alternatives.join("|"), &patterns[0])
     }
 
-    if total > shown {
-        rtk_output.push_str(&format!("... +{}\n", total - shown));
+    fn from_source(source: &str, pattern: &str) -> Self {
+        let regex = Regex::new(source).ok();
+        let literal = pattern.to_lowercase();
+        let highlighter = regex.clone().unwrap_or_else(|| {
+            Regex::new(&format!("(?i){}", regex::escape(&literal)))
+                .expect("escaped literal is a valid regex")
+        });
+        Self {
+            regex,
+            highlighter,
+            literal,
+        }
     }
 
-    print!("{}", rtk_output);
-    timer.track(
-        &format!("grep -rn '{}' {}", pattern, path),
-        "rtk grep",
-        &raw_output,
-        &rtk_output,
-    );
+    /// Set apart every match in `text`
+    fn mark(&self, text: &str, mark: Mark) -> String {
+        if mark == Mark::None {
+            return text.to_string();
+        }
+        self.highlighter
+            .replace_all(text, |caps: &regex::Captures| mark.wrap(&caps[0]))
+            .to_string()
+    }
+
+    /// Char offset of the first match in `line`
+    fn find(&self, line: &str) -> Option<usize> {
+        match &self.regex {
+            Some(re) => {
+                let start = re.find(line)?.start();
+                Some(line[..start].chars().count())
+            }
+            None => {
+                let lower = line.to_lowercase();
+                let pos = lower.find(&self.literal)?;
+                Some(lower[..pos].chars().count())
+            }
+        }
+    }
+}
 
-    if exit_code != 0 {
-        std::process::exit(exit_code);
+/// `pattern` as a regex-crate source, with -i/-w/-F applied the way rg does
+pub fn regex_source(pattern: &str, opts: MatchOptions) -> String {
+    let mut body = if opts.fixed {
+        regex::escape(pattern)
+    } else {
+        pattern.replace(r"\|", "|")
+    };
+    if opts.word {
+        body = format!(r"\b(?:{})\b", body);
+    }
+    if opts.ignore_case {
+        body = format!("(?i){}", body);
     }
+    body
+}
 
+/// Check every pattern before searching: the backends answer a bad regex
+/// with a cryptic error, or (grep's basic syntax) with no matches at all.
+/// -F patterns are literal and -P ones are left to PCRE2.
+fn check_patterns(patterns: &[String], opts: MatchOptions) -> Result<()> {
+    if opts.fixed || opts.pcre2 {
+        return Ok(());
+    }
+    for pattern in patterns {
+        if let Err(e) = regex_syntax::Parser::new().parse(pattern) {
+            anyhow::bail!("{}", pattern_error(pattern, &e));
+        }
+    }
     Ok(())
 }
 
-fn clean_line(line: &str, max_len: usize, context_only: bool, pattern: &str) -> String {
-    let trimmed = line.trim();
+/// Where `pattern` is wrong and how to fix it:
+///
+/// ```text
+/// Invalid regex 'load(': unclosed group
+///   load(
+///       ^
+///   try 'load\(' to match '(' literally, or -F to search the whole pattern as text
+/// ```
+fn pattern_error(pattern: &str, error: &regex_syntax::Error) -> String {
+    let (kind, start) = match error {
+        regex_syntax::Error::Parse(e) => (e.kind().to_string(), e.span().start.offset),
+        regex_syntax::Error::Translate(e) => (e.kind().to_string(), e.span().start.offset),
+        other => (other.to_string(), 0),
+    };
+    let column = pattern[..start].chars().count();
+    let hint = match pattern_fix(pattern, error) {
+        Fix::Escape(at) => {
+            let c = pattern[at..].chars().next().unwrap_or_default();
+            format!(
+                "try '{}\\{}' to match '{}' literally, or -F to search the whole pattern as text",
+                &pattern[..at],
+                &pattern[at..],
+                c
+            )
+        }
+        Fix::Pcre2 => "lookaround and backreferences need PCRE2: add -P".to_string(),
+        Fix::None => "use -F to search the pattern as text".to_string(),
+    };
+    format!(
+        "Invalid regex '{}': {}\n  {}\n  {}^\n  {}",
+        pattern,
+        kind,
+        pattern,
+        " ".repeat(column),
+        hint
+    )
+}
 
-    if context_only {
-        if let Ok(re) = Regex::new(&format!("(?i).{{0,20}}{}.*", regex::escape(pattern))) {
-            if let Some(m) = re.find(trimmed) {
-                let matched = m.as_str();
-                if matched.len() <= max_len {
-                    return matched.to_string();
+/// Suggested change to an invalid pattern
+#[derive(Debug, PartialEq)]
+enum Fix {
+    /// Escape the metacharacter at this byte offset
+    Escape(usize),
+    /// The construct exists in PCRE2 only
+    Pcre2,
+    None,
+}
+
+fn pattern_fix(pattern: &str, error: &regex_syntax::Error) -> Fix {
+    use regex_syntax::ast::ErrorKind;
+    let regex_syntax::Error::Parse(e) = error else {
+        return Fix::None;
+    };
+    let start = e.span().start.offset;
+    let at = |chars: &[char]| match pattern[start..].starts_with(chars) {
+        true => Fix::Escape(start),
+        false => Fix::None,
+    };
+    match e.kind() {
+        ErrorKind::GroupUnclosed => at(&['(']),
+        ErrorKind::GroupUnopened => at(&[')']),
+        ErrorKind::ClassUnclosed => at(&['[']),
+        ErrorKind::RepetitionMissing => at(&['*', '+', '?', '{']),
+        // The span may start after the brace
+        ErrorKind::RepetitionCountUnclosed
+        | ErrorKind::RepetitionCountDecimalEmpty
+        | ErrorKind::RepetitionCountInvalid => match at(&['{']) {
+            Fix::None => pattern[..start].rfind('{').map_or(Fix::None, Fix::Escape),
+            fix => fix,
+        },
+        ErrorKind::EscapeUnexpectedEof => Fix::Escape(pattern.len() - 1),
+        ErrorKind::UnsupportedLookAround | ErrorKind::UnsupportedBackreference => Fix::Pcre2,
+        _ => Fix::None,
+    }
+}
+
+/// Line of a file's output: a match, a context line, or a gap between
+/// non-adjacent context windows
+#[derive(Debug, PartialEq)]
+enum Shown<'a> {
+    Match(&'a LineMatch),
+    Context(usize, &'a str),
+    Gap,
+}
+
+/// Patterns given with `-e` plus those read from an `--any-of` file, and
+/// the search paths. Without either, the first positional arg is the
+/// pattern; with them, every positional arg is a path.
+pub fn resolve_patterns(
+    mut patterns: Vec<String>,
+    any_of: Option<&Path>,
+    mut positional: Vec<String>,
+) -> Result<(Vec<String>, Vec<String>)> {
+    if let Some(file) = any_of {
+        let content = std::fs::read_to_string(file)
+            .with_context(|| format!("Failed to read patterns from {}", file.display()))?;
+        let before = patterns.len();
+        patterns.extend(
+            content
+                .lines()
+                .map(|l| l.trim_end_matches('\r'))
+                .filter(|l| !l.is_empty())
+                .map(String::from),
+        );
+        if patterns.len() == before {
+            anyhow::bail!("No patterns in {}", file.display());
+        }
+    }
+    if patterns.is_empty() {
+        if positional.is_empty() {
+            anyhow::bail!("No pattern given");
+        }
+        patterns.push(positional.remove(0));
+    }
+    Ok((patterns, positional))
+}
+
+/// `'a'` or `'a', 'b'`
+fn quoted(patterns: &[String]) -> String {
+    let quoted: Vec<String> = patterns.iter().map(|p| format!("'{}'", p)).collect();
+    quoted.join(", ")
+}
+
+/// The plain grep command for the savings estimate
+fn grep_equivalent(patterns: &[String], path: &str) -> String {
+    match patterns {
+        [pattern] => format!("grep -rn '{}' {}", pattern, path),
+        _ => {
+            let args: Vec<String> = patterns.iter().map(|p| format!("-e '{}'", p)).collect();
+            format!("grep -rn {} {}", args.join(" "), path)
+        }
+    }
+}
+
+/// One search invocation: roots plus the globs restricting them
+#[derive(Debug, PartialEq)]
+struct SearchGroup {
+    roots: Vec<String>,
+    globs: Vec<String>,
+}
+
+/// Split trailing args into extra search paths and ripgrep flags: positional
+/// args before the first flag are paths (`rtk grep foo src/ tests/ -i`).
+pub fn split_paths(path: &str, extra_args: &[String]) -> (Vec<String>, Vec<String>) {
+    let first_flag = extra_args
+        .iter()
+        .position(|a| a.starts_with('-'))
+        .unwrap_or(extra_args.len());
+
+    let mut paths = vec![path.to_string()];
+    paths.extend(extra_args[..first_flag].iter().cloned());
+    (paths, extra_args[first_flag..].to_vec())
+}
+
+fn is_glob(path: &str) -> bool {
+    path.contains(['*', '?', '[', '{'])
+}
+
+/// `src/**/*.rs` → (`src`, `**/*.rs`): literal base dir and the glob below it
+fn split_glob(path: &str) -> (String, String) {
+    let parts: Vec<&str> = path.split('/').collect();
+    let first_glob = parts.iter().position(|p| is_glob(p)).unwrap_or(0);
+    let base = parts[..first_glob].join("/");
+    let base = if base.is_empty() {
+        ".".to_string()
+    } else {
+        base
+    };
+    (base, parts[first_glob..].join("/"))
+}
+
+/// Group paths into searches: literal paths share one search, each glob base
+/// gets its own so its `-g` filter doesn't leak onto the other paths.
+fn plan_search(paths: &[String]) -> Vec<SearchGroup> {
+    let mut literal = Vec::new();
+    let mut globbed: Vec<SearchGroup> = Vec::new();
+
+    for path in paths {
+        if !is_glob(path) {
+            literal.push(path.clone());
+            continue;
+        }
+        let (base, glob) = split_glob(path);
+        match globbed.iter_mut().find(|g| g.roots[0] == base) {
+            Some(group) => group.globs.push(glob),
+            None => globbed.push(SearchGroup {
+                roots: vec![base],
+                globs: vec![glob],
+            }),
+        }
+    }
+
+    let mut groups = Vec::new();
+    if !literal.is_empty() {
+        groups.push(SearchGroup {
+            roots: literal,
+            globs: Vec::new(),
+        });
+    }
+    groups.extend(globbed);
+    groups
+}
+
+/// The `-g GLOB` / `--glob=GLOB` values among the ripgrep args
+fn user_globs(extra_args: &[String]) -> Vec<String> {
+    let mut globs = Vec::new();
+    let mut iter = extra_args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "-g" || arg == "--glob" {
+            if let Some(glob) = iter.next() {
+                globs.push(glob.clone());
+            }
+        } else if let Some(glob) = arg.strip_prefix("--glob=") {
+            globs.push(glob.to_string());
+        }
+    }
+    globs
+}
+
+/// grep fallback: the files rg would search in `group`, walked with the same
+/// ignore rules (.gitignore, .ignore, .rgignore, hidden files) and globs.
+/// Explicit file roots are always kept. Returns the files and walk errors.
+fn list_files(
+    group: &SearchGroup,
+    type_globs: &[String],
+    user_globs: &[String],
+    opts: MatchOptions,
+) -> Result<(Vec<String>, Vec<String>)> {
+    let mut files = Vec::new();
+    let mut errors = Vec::new();
+    for root in &group.roots {
+        let mut globs = OverrideBuilder::new(root);
+        for glob in group.globs.iter().chain(user_globs) {
+            globs
+                .add(glob)
+                .with_context(|| format!("Invalid glob '{}'", glob))?;
+        }
+        let mut types = OverrideBuilder::new(root);
+        for glob in type_globs {
+            types.add(glob)?;
+        }
+        let types = types.build()?;
+
+        let respect_ignores = !opts.no_ignore;
+        let mut walker = WalkBuilder::new(root);
+        walker
+            .hidden(!opts.hidden)
+            .ignore(respect_ignores)
+            .parents(respect_ignores)
+            .git_ignore(respect_ignores)
+            .git_global(respect_ignores)
+            .git_exclude(respect_ignores)
+            .overrides(globs.build()?);
+        if respect_ignores {
+            walker.add_custom_ignore_filename(".rgignore");
+        }
+
+        let walked = walk::walk(&walker, |entry| {
+            let is_file = entry.file_type().is_some_and(|t| t.is_file());
+            let typed = type_globs.is_empty()
+                || entry.depth() == 0
+                || types.matched(entry.path(), false).is_whitelist();
+            (is_file && typed).then(|| entry.path().to_string_lossy().to_string())
+        });
+        files.extend(walked.items);
+        errors.extend(walked.errors.iter().map(|e| format!("grep: {}", e)));
+    }
+    Ok((files, errors))
+}
+
+/// Exit code of two merged searches, as grep: 0 if either matched, 2 if
+/// either failed, 1 otherwise
+fn merge_exit_codes(a: i32, b: i32) -> i32 {
+    match (a, b) {
+        (0, _) | (_, 0) => 0,
+        (2, _) | (_, 2) => 2,
+        _ => 1,
+    }
+}
+
+/// Pull -A/-B/-C context flags out of the ripgrep args: context is rendered
+/// by rtk, so rg must not emit its own context lines.
+pub fn take_context_args(
+    mut ctx: ContextLines,
+    extra_args: &[String],
+) -> (ContextLines, Vec<String>) {
+    let mut rest = Vec::new();
+    let mut iter = extra_args.iter();
+
+    while let Some(arg) = iter.next() {
+        let (flag, value) = match arg.as_str() {
+            "-A" | "--after-context" | "-B" | "--before-context" | "-C" | "--context" => {
+                (arg.as_str(), iter.next().cloned())
+            }
+            a => match a.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => (flag, Some(value.to_string())),
+                _ => match a.get(..2) {
+                    Some(flag @ ("-A" | "-B" | "-C")) if a.len() > 2 => {
+                        (flag, Some(a[2..].to_string()))
+                    }
+                    _ => (a, None),
+                },
+            },
+        };
+
+        let n = value.as_deref().and_then(|v| v.parse::<usize>().ok());
+        match (flag, n) {
+            ("-A" | "--after-context", Some(n)) => ctx.after = n,
+            ("-B" | "--before-context", Some(n)) => ctx.before = n,
+            ("-C" | "--context", Some(n)) => {
+                ctx = ContextLines {
+                    before: n,
+                    after: n,
+                }
+            }
+            _ => {
+                rest.push(arg.clone());
+                if flag == arg && value.is_some() {
+                    // Flag with a non-numeric value: keep both for rg to report
+                    rest.extend(value);
                 }
             }
         }
     }
 
-    if trimmed.len() <= max_len {
-        trimmed.to_string()
-    } else {
-        let lower = trimmed.to_lowercase();
-        let pattern_lower = pattern.to_lowercase();
-
-        if let Some(pos) = lower.find(&pattern_lower) {
-            let char_pos = lower[..pos].chars().count();
-            let chars: Vec<char> = trimmed.chars().collect();
-            let char_len = chars.len();
-
-            let start = char_pos.saturating_sub(max_len / 3);
-            let end = (start + max_len).min(char_len);
-            let start = if end == char_len {
-                end.saturating_sub(max_len)
-            } else {
-                start
-            };
+    (ctx, rest)
+}
 
-            let slice: String = chars[start..end].iter().collect();
-            if start > 0 && end < char_len {
-                format!("...{}...", slice)
-            } else if start > 0 {
-                format!("...{}", slice)
-            } else {
-                format!("{}...", slice)
+/// Pull -i/-s/-w/-F out of the ripgrep args so `clean_line` knows about them
+/// (they are passed to the search again from `MatchOptions`).
+pub fn take_match_args(
+    mut opts: MatchOptions,
+    extra_args: &[String],
+) -> (MatchOptions, Vec<String>) {
+    let mut rest = Vec::new();
+    for arg in extra_args {
+        match arg.as_str() {
+            "-i" | "--ignore-case" => opts.ignore_case = true,
+            "-s" | "--case-sensitive" => opts.case_sensitive = true,
+            // The default
+            "-S" | "--smart-case" => {}
+            "-w" | "--word-regexp" => opts.word = true,
+            "-F" | "--fixed-strings" => opts.fixed = true,
+            "-U" | "--multiline" => opts.multiline = true,
+            "-P" | "--pcre2" | "--perl-regexp" => opts.pcre2 = true,
+            "--hidden" | "-." => opts.hidden = true,
+            "--no-ignore" | "-u" => opts.no_ignore = true,
+            "-z" | "--search-zip" => opts.search_zip = true,
+            "-uu" | "-uuu" => {
+                opts.no_ignore = true;
+                opts.hidden = true;
             }
-        } else {
-            let t: String = trimmed.chars().take(max_len - 3).collect();
-            format!("{}...", t)
+            _ => rest.push(arg.clone()),
         }
     }
+    (opts, rest)
 }
 
-fn compact_path(path: &str) -> String {
-    if path.len() <= 50 {
-        return path.to_string();
+/// Merge the context windows of `matches` and lay out the file's lines:
+/// overlapping or adjacent windows are joined, gaps become `⋯`.
+fn with_context<'a>(
+    matches: &'a [LineMatch],
+    context: &'a HashMap<usize, String>,
+    ctx: ContextLines,
+) -> Vec<Shown<'a>> {
+    let mut shown = Vec::new();
+    let mut last_line = 0;
+
+    for hit in matches {
+        let line_num = &hit.line;
+        let start = line_num
+            .saturating_sub(ctx.before)
+            .max(1)
+            .max(last_line + 1);
+        if last_line > 0 && start > last_line + 1 {
+            shown.push(Shown::Gap);
+        }
+        for n in start..*line_num {
+            if let Some(text) = context.get(&n) {
+                shown.push(Shown::Context(n, text));
+            }
+        }
+        if *line_num > last_line {
+            shown.push(Shown::Match(hit));
+        }
+        last_line = last_line.max(*line_num);
+
+        // After-context stops at the next match, which opens its own window
+        for n in last_line + 1..=line_num + ctx.after {
+            let Some(text) = context.get(&n) else { break };
+            if matches.iter().any(|m| m.line == n) {
+                break;
+            }
+            shown.push(Shown::Context(n, text));
+            last_line = n;
+        }
     }
 
-    let parts: Vec<&str> = path.split('/').collect();
-    if parts.len() <= 3 {
-        return path.to_string();
+    shown
+}
+
+/// Fill in the definition each match is in, for the languages `rtk outline`
+/// knows; files that can't be read keep none
+fn resolve_symbols(file: &str, matches: &mut [LineMatch]) {
+    let lang = Path::new(file)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(Language::from_extension)
+        .unwrap_or(Language::Unknown);
+    if !outline_cmd::is_supported(lang) {
+        return;
     }
+    let Ok(content) = std::fs::read_to_string(file) else {
+        return;
+    };
+    let lines: Vec<usize> = matches.iter().map(|m| m.line).collect();
+    let symbols = symbols_cmd::enclosing(&content, lang, &lines);
+    for (hit, symbol) in matches.iter_mut().zip(symbols) {
+        hit.symbol = symbol;
+    }
+}
 
-    format!(
-        "{}/.../{}/{}",
-        parts[0],
-        parts[parts.len() - 2],
-        parts[parts.len() - 1]
-    )
+/// Cleaned context lines of `file` around its matches, keyed by line number
+fn read_context(
+    file: &str,
+    matches: &[LineMatch],
+    ctx: ContextLines,
+    max_line_len: usize,
+    matcher: &Matcher,
+) -> HashMap<usize, String> {
+    let Ok(content) = std::fs::read_to_string(file) else {
+        return HashMap::new();
+    };
+
+    content
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line))
+        .filter(|(n, _)| {
+            matches
+                .iter()
+                .any(|m| *n + ctx.before >= m.line && *n <= m.line + ctx.after)
+        })
+        .map(|(n, line)| {
+            (
+                n,
+                clean_line(line, max_line_len, false, matcher, Mark::None),
+            )
+        })
+        .collect()
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Built-in file types: ripgrep type name, accepted aliases, and the globs
+/// used by the grep fallback
+const FILE_TYPES: &[(&str, &[&str], &[&str])] = &[
+    ("rust", &["rs"], &["*.rs"]),
+    (
+        "ts",
+        &["typescript", "tsx"],
+        &["*.ts", "*.tsx", "*.mts", "*.cts"],
+    ),
+    (
+        "js",
+        &["javascript", "jsx"],
+        &["*.js", "*.jsx", "*.mjs", "*.cjs"],
+    ),
+    ("py", &["python"], &["*.py", "*.pyi"]),
+    ("go", &["golang"], &["*.go"]),
+    ("java", &[], &["*.java"]),
+    ("kotlin", &["kt"], &["*.kt", "*.kts"]),
+    ("c", &[], &["*.c", "*.h"]),
+    (
+        "cpp",
+        &["c++", "cxx"],
+        &["*.cpp", "*.cc", "*.cxx", "*.hpp", "*.hh", "*.h"],
+    ),
+    ("ruby", &["rb"], &["*.rb"]),
+    ("php", &[], &["*.php"]),
+    (
+        "sh",
+        &["shell", "bash", "zsh"],
+        &["*.sh", "*.bash", "*.zsh"],
+    ),
+    ("html", &["htm"], &["*.html", "*.htm"]),
+    ("css", &["scss"], &["*.css", "*.scss", "*.sass"]),
+    ("json", &[], &["*.json"]),
+    ("yaml", &["yml"], &["*.yaml", "*.yml"]),
+    ("toml", &[], &["*.toml"]),
+    ("md", &["markdown"], &["*.md", "*.markdown"]),
+    ("sql", &[], &["*.sql"]),
+];
 
-    #[test]
-    fn test_clean_line() {
-        let line = "            const result = someFunction();";
-        let cleaned = clean_line(line, 50, false, "result");
-        assert!(!cleaned.starts_with(' '));
-        assert!(cleaned.len() <= 50);
+/// Resolve a `-t` value to its ripgrep type name and fallback globs.
+/// Unknown names are passed to rg as-is and treated as an extension by grep.
+fn resolve_type(name: &str) -> (String, Vec<String>) {
+    let lower = name.to_lowercase();
+    match FILE_TYPES
+        .iter()
+        .find(|(rg, aliases, _)| *rg == lower || aliases.contains(&lower.as_str()))
+    {
+        Some((rg, _, globs)) => (
+            rg.to_string(),
+            globs.iter().map(|g| g.to_string()).collect(),
+        ),
+        None => (lower.clone(), vec![format!("*.{}", lower)]),
     }
+}
 
-    #[test]
-    fn test_compact_path() {
-        let path = "/Users/patrick/dev/project/src/components/Button.tsx";
-        let compact = compact_path(path);
-        assert!(compact.len() <= 60);
+/// A search tool `rtk grep` can run, as named in `search.backends`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Backend {
+    Rg,
+    Ugrep,
+    GitGrep,
+    Grep,
+    /// In-process search with the regex crate: needs no tool (no `-P`)
+    Builtin,
+}
+
+impl Backend {
+    pub fn name(self) -> &'static str {
+        match self {
+            Backend::Rg => "rg",
+            Backend::Ugrep => "ugrep",
+            Backend::GitGrep => "git-grep",
+            Backend::Grep => "grep",
+            Backend::Builtin => "builtin",
+        }
     }
 
-    #[test]
-    fn test_extra_args_accepted() {
-        // Test that the function signature accepts extra_args
-        // This is a compile-time test - if it compiles, the signature is correct
-        let _extra: Vec<String> = vec!["-i".to_string(), "-A".to_string(), "3".to_string()];
-        // No need to actually run - we're verifying the parameter exists
+    /// The command searching an explicit list of files (all but rg, which
+    /// walks the tree itself, and builtin)
+    fn file_list_command(self) -> Option<Command> {
+        let (program, args): (&str, &[&str]) = match self {
+            Backend::Ugrep => ("ugrep", &["-nHI", "-E"]),
+            Backend::GitGrep => ("git", &["grep", "--no-index", "-nI", "-E"]),
+            Backend::Grep => ("grep", &["-nHI"]),
+            Backend::Rg | Backend::Builtin => return None,
+        };
+        let mut cmd = Command::new(program);
+        cmd.args(args);
+        Some(cmd)
     }
+}
 
-    #[test]
-    fn test_clean_line_multibyte() {
-        // Thai text that exceeds max_len in bytes
-        let line = "  สวัสดีครับ นี่คือข้อความที่ยาวมากสำหรับทดสอบ  ";
-        let cleaned = clean_line(line, 20, false, "ครับ");
-        // Should not panic
-        assert!(!cleaned.is_empty());
+/// `[search]` in the config: the tools `rtk grep` runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchConfig {
+    /// Tried in order; one that is not installed is skipped, one that
+    /// fails reports its error
+    pub backends: Vec<Backend>,
+    /// Extra arguments per backend, e.g. `rg = ["--max-columns=500"]`
+    pub args: BTreeMap<String, Vec<String>>,
+}
+
+impl Default for SearchConfig {
+    /// rg, then grep, then the builtin search. Windows skips grep: it is
+    /// rarely installed there, and its stand-in findstr has no `|`, `+` or
+    /// `\b`, so the builtin search reads patterns the way rg would.
+    fn default() -> Self {
+        let backends = if cfg!(windows) {
+            vec![Backend::Rg, Backend::Builtin]
+        } else {
+            vec![Backend::Rg, Backend::Grep, Backend::Builtin]
+        };
+        Self {
+            backends,
+            args: BTreeMap::new(),
+        }
     }
+}
 
-    #[test]
+impl SearchConfig {
+    fn args(&self, backend: Backend) -> &[String] {
+        self.args.get(backend.name()).map_or(&[], Vec::as_slice)
+    }
+}
+
+/// From the global config only: a cloned repo's `.rtk.toml` could
+/// otherwise pick the backend and hand it `--pre` or
+/// `--open-files-in-pager`, which run programs
+fn search_config() -> SearchConfig {
+    crate::config::Config::global().search.clone()
+}
+
+/// Output of one search: stdout, stderr and grep-style exit code
+type SearchOutput = (String, String, i32);
+
+/// Lines of CRLF files come back with their `\r`, which would end up in
+/// the content
+fn search_output(output: std::process::Output) -> SearchOutput {
+    (
+        String::from_utf8_lossy(&output.stdout).replace("\r\n", "\n"),
+        String::from_utf8_lossy(&output.stderr).replace("\r\n", "\n"),
+        output.status.code().unwrap_or(1),
+    )
+}
+
+/// What one search group is: patterns, where, and how they match
+struct GroupSearch<'a> {
+    patterns: &'a [String],
+    group: &'a SearchGroup,
+    types: Vec<(String, Vec<String>)>,
+    opts: MatchOptions,
+    extra_args: &'a [String],
+    /// Same short flags for every tool
+    match_flags: Vec<&'static str>,
+}
+
+impl GroupSearch<'_> {
+    /// Patterns with BRE alternation `\|` turned into `|` for the tools
+    /// taking extended syntax
+    fn extended_patterns(&self) -> Vec<String> {
+        self.patterns
+            .iter()
+            .map(|pattern| {
+                if self.opts.fixed {
+                    pattern.to_string()
+                } else {
+                    pattern.replace(r"\|", "|")
+                }
+            })
+            .collect()
+    }
+
+    fn rg_command(&self, backend_args: &[String]) -> Command {
+        let mut rg_cmd = Command::new("rg");
+        rg_cmd.args(["-n", "--no-heading", "--with-filename"]);
+        rg_cmd.args(&self.match_flags);
+        if self.opts.hidden {
+            rg_cmd.arg("--hidden");
+        }
+        if self.opts.no_ignore {
+            rg_cmd.arg("--no-ignore");
+        }
+        if self.opts.multiline {
+            // JSON events keep each match whole, however many lines it spans
+            rg_cmd.args(["-U", "--json"]);
+        }
+        for pattern in self.extended_patterns() {
+            rg_cmd.arg("-e").arg(pattern);
+        }
+        rg_cmd.args(&self.group.roots);
+
+        for glob in &self.group.globs {
+            rg_cmd.arg("-g").arg(glob);
+        }
+
+        for (rg_type, _) in &self.types {
+            rg_cmd.arg("--type").arg(rg_type);
+        }
+
+        for arg in self.extra_args {
+            // Fix: skip grep-ism -r flag (rg is recursive by default; rg -r means --replace)
+            if arg == "-r" || arg == "--recursive" {
+                continue;
+            }
+            rg_cmd.arg(arg);
+        }
+        rg_cmd.args(backend_args);
+        rg_cmd
+    }
+
+    /// The files rg would search, for the tools that take a file list
+    fn files(&self) -> Result<(Vec<String>, Vec<String>)> {
+        let type_globs: Vec<String> = self
+            .types
+            .iter()
+            .flat_map(|(_, globs)| globs.iter().cloned())
+            .collect();
+        list_files(
+            self.group,
+            &type_globs,
+            &user_globs(self.extra_args),
+            self.opts,
+        )
+    }
+
+    /// The files that may match, from a fresh `rtk index` covering every
+    /// root, filtered by globs and types; `None` to search without it
+    fn indexed_files(&self, verbose: u8) -> Option<Vec<String>> {
+        let opts = self.opts;
+        if opts.hidden || opts.no_ignore || opts.pcre2 || opts.multiline || opts.search_zip {
+            return None;
+        }
+        // Other rg flags change what is searched or printed: rg alone knows
+        let mut args = self.extra_args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-g" | "--glob" => {
+                    args.next();
+                }
+                "-r" | "--recursive" => {}
+                a if a.starts_with("--glob=") => {}
+                _ => return None,
+            }
+        }
+        let sources: Vec<String> = self
+            .patterns
+            .iter()
+            .map(|p| regex_source(p, opts))
+            .collect();
+        let query = index::query(&sources);
+        if query == index::Query::All {
+            return None;
+        }
+        let index = Index::find(Path::new(&self.group.roots[0]))?;
+        let lookup = || -> Result<Option<Vec<String>>> {
+            let ids = index.matching(&query)?;
+            let type_globs: Vec<String> = self
+                .types
+                .iter()
+                .flat_map(|(_, globs)| globs.iter().cloned())
+                .collect();
+            let user_globs = user_globs(self.extra_args);
+            let (mut files, mut indexed, mut changed) = (Vec::new(), 0, 0);
+            for root in &self.group.roots {
+                let Some(candidates) = index.candidates(root, ids.as_ref())? else {
+                    return Ok(None);
+                };
+                let mut globs = OverrideBuilder::new(root);
+                for glob in self.group.globs.iter().chain(&user_globs) {
+                    globs.add(glob)?;
+                }
+                let globs = globs.build()?;
+                let mut types = OverrideBuilder::new(root);
+                for glob in &type_globs {
+                    types.add(glob)?;
+                }
+                let types = types.build()?;
+                indexed += candidates.indexed;
+                changed += candidates.changed;
+                files.extend(candidates.files.into_iter().filter(|file| {
+                    file == root
+                        || (!globs.matched(file, false).is_ignore()
+                            && (type_globs.is_empty() || types.matched(file, false).is_whitelist()))
+                }));
+            }
+            let scan = index::Candidates {
+                files,
+                indexed,
+                changed,
+            };
+            if scan.is_stale() {
+                if verbose > 0 {
+                    eprintln!(
+                        "grep: index of {} is stale ({} files changed), searching without it; \
+                         rtk index build refreshes it",
+                        index.root.display(),
+                        scan.changed
+                    );
+                }
+                return Ok(None);
+            }
+            if verbose > 0 {
+                eprintln!(
+                    "grep: index narrowed the search to {} of {} files",
+                    scan.files.len(),
+                    scan.indexed
+                );
+            }
+            Ok(Some(scan.files))
+        };
+        match lookup() {
+            Ok(files) => files,
+            Err(e) => {
+                if verbose > 0 {
+                    eprintln!("grep: index unusable, searching without it: {:#}", e);
+                }
+                None
+            }
+        }
+    }
+
+    /// Search `files` with a grep-like tool, one run per chunk of files,
+    /// side by side, merged in file order
+    fn run_file_list(
+        &self,
+        backend: Backend,
+        files: &[String],
+        walk_errors: &[String],
+        backend_args: &[String],
+    ) -> std::io::Result<SearchOutput> {
+        // grep reads basic regexes, as the pattern was given
+        let patterns = match backend {
+            Backend::Grep => self.patterns.to_vec(),
+            _ => self.extended_patterns(),
+        };
+        let mut stdout = String::new();
+        let mut stderr = walk_errors.join("\n");
+        let mut exit_code = if walk_errors.is_empty() { 1 } else { 2 };
+        let per_run = files
+            .len()
+            .div_ceil(walk::threads())
+            .clamp(GREP_MIN_FILES_PER_RUN, GREP_FILES_PER_RUN);
+        let chunks: Vec<&[String]> = files.chunks(per_run).collect();
+        let outputs = walk::shard(&chunks, |chunk| {
+            backend
+                .file_list_command()
+                .expect("backend takes a file list")
+                .args(&self.match_flags)
+                .args(backend_args)
+                .args(patterns.iter().flat_map(|p| ["-e", p.as_str()]))
+                .arg("--")
+                .args(*chunk)
+                .output()
+        });
+        for output in outputs {
+            let (out, err, code) = search_output(output?);
+            stdout.push_str(&out);
+            stderr.push_str(&err);
+            exit_code = merge_exit_codes(exit_code, code);
+        }
+        Ok((stdout, stderr, exit_code))
+    }
+
+    /// All patterns as one regex-crate regex, or the error grep would print
+    fn builtin_regex(&self) -> Result<Regex, String> {
+        if self.opts.pcre2 {
+            return Err("grep: -P (PCRE2) is not supported by the builtin search".to_string());
+        }
+        let source = self
+            .patterns
+            .iter()
+            .map(|p| format!("(?:{})", regex_source(p, self.opts)))
+            .collect::<Vec<_>>()
+            .join("|");
+        Regex::new(&source).map_err(|e| format!("grep: {}", e))
+    }
+
+    /// Search `files` in process with the regex crate, as grep -nHI would
+    fn run_builtin(&self, files: &[String], walk_errors: &[String]) -> SearchOutput {
+        let mut stderr: Vec<String> = walk_errors.to_vec();
+        let regex = match self.builtin_regex() {
+            Ok(regex) => regex,
+            Err(e) => {
+                stderr.push(e);
+                return (String::new(), stderr.join("\n"), 2);
+            }
+        };
+
+        let per_run = files
+            .len()
+            .div_ceil(walk::threads())
+            .clamp(GREP_MIN_FILES_PER_RUN, GREP_FILES_PER_RUN);
+        let chunks: Vec<&[String]> = files.chunks(per_run).collect();
+        let searched = walk::shard(&chunks, |chunk| {
+            let mut out = String::new();
+            let mut errors = Vec::new();
+            for file in *chunk {
+                let bytes = match std::fs::read(file) {
+                    Ok(bytes) => bytes,
+                    Err(e) => {
+                        errors.push(format!("grep: {}: {}", file, e));
+                        continue;
+                    }
+                };
+                // Binary files are skipped, as with -I
+                if bytes[..bytes.len().min(8192)].contains(&0) {
+                    continue;
+                }
+                push_matches(&mut out, file, &String::from_utf8_lossy(&bytes), &regex);
+            }
+            (out, errors)
+        });
+        let mut stdout = String::new();
+        for (out, errors) in searched {
+            stdout.push_str(&out);
+            stderr.extend(errors);
+        }
+        let exit_code = grep_exit_code(&stdout, &stderr);
+        (stdout, stderr.join("\n"), exit_code)
+    }
+
+    /// Search the members of the archives rg would search (`--search-zip`).
+    /// File types apply to member names.
+    fn run_archives(&self) -> Result<SearchOutput> {
+        let (files, mut stderr) =
+            list_files(self.group, &[], &user_globs(self.extra_args), self.opts)?;
+        let regex = match self.builtin_regex() {
+            Ok(regex) => regex,
+            Err(e) => {
+                stderr.push(e);
+                return Ok((String::new(), stderr.join("\n"), 2));
+            }
+        };
+        let mut types = OverrideBuilder::new("/");
+        for (_, globs) in &self.types {
+            for glob in globs {
+                types.add(glob)?;
+            }
+        }
+        let types = types.build()?;
+
+        let mut stdout = String::new();
+        for file in files.iter().filter(|f| archive::is_archive(Path::new(f))) {
+            let visited = archive::for_each_member(Path::new(file), |member, text| {
+                let name = member.rsplit(archive::MEMBER_SEP).next().unwrap_or(member);
+                if self.types.is_empty() || types.matched(name, false).is_whitelist() {
+                    push_matches(&mut stdout, member, text, &regex);
+                }
+            });
+            match visited {
+                Ok(skipped) => stderr.extend(skipped),
+                Err(e) => stderr.push(format!("grep: {:#}", e)),
+            }
+        }
+        let exit_code = grep_exit_code(&stdout, &stderr);
+        Ok((stdout, stderr.join("\n"), exit_code))
+    }
+}
+
+/// Append the lines of `text` matching `regex` as `path:line:content`
+fn push_matches(out: &mut String, path: &str, text: &str, regex: &Regex) {
+    for (i, line) in text.lines().enumerate() {
+        if regex.is_match(line) {
+            out.push_str(&format!("{}:{}:{}\n", path, i + 1, line));
+        }
+    }
+}
+
+/// grep's exit code for an in-process search: 0 if anything matched, 2 on
+/// errors, 1 otherwise
+fn grep_exit_code(stdout: &str, stderr: &[String]) -> i32 {
+    if !stdout.is_empty() {
+        0
+    } else if stderr.iter().any(|e| !e.starts_with("warning:")) {
+        2
+    } else {
+        1
+    }
+}
+
+/// Two searches as one
+fn merge_outputs(a: SearchOutput, b: SearchOutput) -> SearchOutput {
+    let mut stderr = a.1;
+    if !stderr.is_empty() && !stderr.ends_with('\n') && !b.1.is_empty() {
+        stderr.push('\n');
+    }
+    stderr.push_str(&b.1);
+    (a.0 + &b.0, stderr, merge_exit_codes(a.2, b.2))
+}
+
+/// Run one search group with the first installed backend of `config`. A
+/// backend that is installed but fails keeps its error: the next one
+/// would fail the same way.
+fn search_group(
+    patterns: &[String],
+    group: &SearchGroup,
+    file_types: &[String],
+    opts: MatchOptions,
+    extra_args: &[String],
+    config: &SearchConfig,
+    verbose: u8,
+) -> Result<SearchOutput> {
+    let mut match_flags = Vec::new();
+    if opts.ignore_case {
+        match_flags.push("-i");
+    }
+    if opts.word {
+        match_flags.push("-w");
+    }
+    if opts.fixed {
+        match_flags.push("-F");
+    }
+    if opts.pcre2 {
+        match_flags.push("-P");
+    }
+    let search = GroupSearch {
+        patterns,
+        group,
+        types: file_types.iter().map(|t| resolve_type(t)).collect(),
+        opts,
+        extra_args,
+        match_flags,
+    };
+
+    if opts.multiline {
+        let mut output = search
+            .rg_command(config.args(Backend::Rg))
+            .output()
+            .context("--multiline needs ripgrep (rg), which was not found")?;
+        output.stdout = join_multiline(&String::from_utf8_lossy(&output.stdout)).into_bytes();
+        return Ok(search_output(output));
+    }
+
+    // A fresh index narrows the files to read: searched in process
+    if let Some(files) = search.indexed_files(verbose) {
+        return Ok(search.run_builtin(&files, &[]));
+    }
+
+    // The file list is walked once, for the first backend that needs it
+    let mut files: Option<(Vec<String>, Vec<String>)> = None;
+    for &backend in &config.backends {
+        let backend_args = config.args(backend);
+        let attempt = match backend {
+            Backend::Rg => search.rg_command(backend_args).output().map(search_output),
+            Backend::Builtin => {
+                if files.is_none() {
+                    files = Some(search.files()?);
+                }
+                let (list, errors) = files.as_ref().expect("files listed");
+                Ok(search.run_builtin(list, errors))
+            }
+            _ => {
+                if files.is_none() {
+                    files = Some(search.files()?);
+                }
+                let (list, errors) = files.as_ref().expect("files listed");
+                search.run_file_list(backend, list, errors, backend_args)
+            }
+        };
+        match attempt {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                if verbose > 0 {
+                    eprintln!(
+                        "grep: {} not found, trying the next backend",
+                        backend.name()
+                    );
+                }
+            }
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to run {}", backend.name()));
+            }
+            Ok(output) => {
+                if verbose > 0 {
+                    eprintln!("grep: searched with {}", backend.name());
+                    if output.2 == 2 {
+                        eprintln!("grep: {} exited with an error", backend.name());
+                    }
+                }
+                if opts.search_zip {
+                    return Ok(merge_outputs(output, search.run_archives()?));
+                }
+                return Ok(output);
+            }
+        }
+    }
+    let tried: Vec<&str> = config.backends.iter().map(|b| b.name()).collect();
+    anyhow::bail!(
+        "No search backend installed (tried: {}); add \"builtin\" to search.backends",
+        tried.join(", ")
+    )
+}
+
+/// Turn `rg --json` match events into `path:line:content` result lines,
+/// the lines of each match joined into one compacted entry
+fn join_multiline(json_stream: &str) -> String {
+    let mut out = String::new();
+    for event in json_stream
+        .lines()
+        .filter_map(|l| serde_json::from_str::<serde_json::Value>(l).ok())
+        .filter(|e| e["type"] == "match")
+    {
+        let data = &event["data"];
+        // Non-UTF-8 paths and contents come as base64 `bytes`: skipped
+        let (Some(path), Some(text), Some(line)) = (
+            data["path"]["text"].as_str(),
+            data["lines"]["text"].as_str(),
+            data["line_number"].as_u64(),
+        ) else {
+            continue;
+        };
+        let joined: Vec<&str> = text
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .collect();
+        out.push_str(&format!("{}:{}:{}\n", path, line, joined.join(" ")));
+    }
+    out
+}
+
+/// Run every search group and merge their output. The exit code follows
+/// grep: 0 if anything matched, 2 if a search failed, 1 otherwise.
+fn run_searches(
+    patterns: &[String],
+    paths: &[String],
+    file_types: &[String],
+    opts: MatchOptions,
+    extra_args: &[String],
+    verbose: u8,
+) -> Result<(String, String, i32)> {
+    check_patterns(patterns, opts)?;
+    let config = search_config();
+    let mut stdout = String::new();
+    let mut stderr = String::new();
+    let mut exit_code = 1;
+    for group in plan_search(paths) {
+        let (out, err, code) = search_group(
+            patterns, &group, file_types, opts, extra_args, &config, verbose,
+        )?;
+        stdout.push_str(&out);
+        stderr.push_str(&err);
+        exit_code = merge_exit_codes(exit_code, code);
+    }
+    Ok((stdout, stderr, exit_code))
+}
+
+/// Split a `file:line:content` result line. Searching a single file may
+/// omit the file name, in which case it is `default_path`. A Windows drive
+/// (`C:\src\main.rs:12:...`) is part of the file name.
+fn parse_result_line<'a>(line: &'a str, default_path: &str) -> Option<(String, usize, &'a str)> {
+    let drive = drive_prefix_len(line);
+    let parts: Vec<&str> = line[drive..].splitn(3, ':').collect();
+    match parts.len() {
+        3 => Some((
+            line[..drive + parts[0].len()].to_string(),
+            parts[1].parse().unwrap_or(0),
+            parts[2],
+        )),
+        2 => Some((
+            default_path.to_string(),
+            parts[0].parse().unwrap_or(0),
+            parts[1],
+        )),
+        _ => None,
+    }
+}
+
+/// Length of a leading `C:\` or `C:/` drive, 0 without one
+fn drive_prefix_len(line: &str) -> usize {
+    match line.as_bytes() {
+        [letter, b':', b'\\' | b'/', ..] if letter.is_ascii_alphabetic() => 2,
+        _ => 0,
+    }
+}
+
+/// Search `paths` for `pattern` and return the matching lines, untruncated,
+/// grouped by file (sorted by path). No match is an empty list, not an
+/// error; an invalid pattern or unreadable path is.
+///
+/// Uses the first installed tool of `search.backends` in the config: `rg`,
+/// else `grep` (not on Windows), else a builtin search.
+///
+/// # Examples
+///
+/// ```no_run
+/// use rtk::grep::{search, SearchOptions};
+///
+/// let options = SearchOptions {
+///     file_types: vec!["rust".to_string()],
+///     ..Default::default()
+/// };
+/// for file in search("TODO", &["src".to_string()], &options)? {
+///     println!("{} ({} matches)", file.path, file.matches.len());
+/// }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn search(
+    pattern: &str,
+    paths: &[String],
+    options: &SearchOptions,
+) -> Result<Vec<FileMatches>> {
+    search_all(&[pattern.to_string()], paths, options)
+}
+
+/// [`search`] for lines matching any of `patterns`, in one pass
+pub fn search_all(
+    patterns: &[String],
+    paths: &[String],
+    options: &SearchOptions,
+) -> Result<Vec<FileMatches>> {
+    let (stdout, stderr, exit_code) = run_searches(
+        patterns,
+        paths,
+        &options.file_types,
+        options.matching,
+        &options.extra_args,
+        0,
+    )?;
+    if exit_code == 2 && stdout.trim().is_empty() {
+        anyhow::bail!("Search for {} failed: {}", quoted(patterns), stderr.trim());
+    }
+    Ok(group_results(&stdout, &paths.join(" ")))
+}
+
+fn group_results(stdout: &str, default_path: &str) -> Vec<FileMatches> {
+    let mut files: Vec<FileMatches> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for (path, line, content) in stdout
+        .lines()
+        .filter_map(|l| parse_result_line(l, default_path))
+    {
+        let i = *index.entry(path.clone()).or_insert_with(|| {
+            files.push(FileMatches {
+                path,
+                matches: Vec::new(),
+            });
+            files.len() - 1
+        });
+        files[i].matches.push(LineMatch {
+            line,
+            column: None,
+            content: content.to_string(),
+            patterns: Vec::new(),
+            symbol: None,
+        });
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    files
+}
+
+/// Search for `patterns` (several run in one pass, each line tagged with
+/// the patterns it matches)
+#[allow(clippy::too_many_arguments)]
+pub fn run(
+    patterns: &[String],
+    paths: &[String],
+    max_line_len: usize,
+    max_results: usize,
+    context_only: bool,
+    file_types: &[String],
+    match_options: MatchOptions,
+    context_lines: ContextLines,
+    group_by: &str,
+    show: ShowOptions,
+    extra_args: &[String],
+    format: &str,
+    verbose: u8,
+) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+    let path = paths.join(" ");
+
+    let pattern = patterns.join(" | ");
+    let original_cmd = grep_equivalent(patterns, &path);
+
+    if verbose > 0 {
+        eprintln!("grep: {} in {}", quoted(patterns), path);
+    }
+
+    // Merge all searches into one report
+    let (stdout, stderr, exit_code) = run_searches(
+        patterns,
+        paths,
+        file_types,
+        match_options,
+        extra_args,
+        verbose,
+    )?;
+
+    let raw_output = format!("{}{}", stdout, stderr);
+    // Bad regex, unreadable files, tool warnings: part of the report
+    let diagnostics = classify_stderr(&stderr);
+
+    if stdout.trim().is_empty() {
+        let msg = if format == "sarif" {
+            format_sarif(patterns, &[], &diagnostics)?
+        } else if format == "json" {
+            let report = GrepReport {
+                pattern: pattern.to_string(),
+                patterns: Vec::new(),
+                total: 0,
+                files: Vec::new(),
+                collapsed: Vec::new(),
+                truncated: false,
+                diagnostics,
+            };
+            serde_json::to_string_pretty(&report)?
+        } else {
+            format!(
+                "🔍 0 for {}\n{}",
+                quoted(patterns),
+                format_diagnostics(&diagnostics)
+            )
+            .trim_end()
+            .to_string()
+        };
+        println!("{}", msg);
+        timer.track(&original_cmd, "rtk grep", &raw_output, &msg);
+        utils::exit_with("grep", exit_code, verbose);
+        return Ok(());
+    }
+
+    let matcher = Matcher::any(patterns, match_options);
+    // Attribution of each line, only needed with several patterns
+    let per_pattern: Vec<Matcher> = match patterns {
+        [_] => Vec::new(),
+        _ => patterns
+            .iter()
+            .map(|p| Matcher::new(p, match_options))
+            .collect(),
+    };
+    let mark = if !matches!(format, "json" | "sarif") && output::color_enabled() {
+        Mark::Ansi
+    } else if show.mark {
+        Mark::Plain
+    } else {
+        Mark::None
+    };
+    let mut by_file: HashMap<String, Vec<LineMatch>> = HashMap::new();
+    let mut minified_lines: HashMap<String, usize> = HashMap::new();
+    let mut total = 0;
+
+    for (file, line, content) in stdout.lines().filter_map(|l| parse_result_line(l, &path)) {
+        total += 1;
+        if looks_minified(content) {
+            *minified_lines.entry(file.clone()).or_default() += 1;
+        }
+        let column = if show.column || format == "sarif" {
+            matcher.find(content).map(|c| c + 1)
+        } else {
+            None
+        };
+        by_file.entry(file).or_default().push(LineMatch {
+            line,
+            column,
+            content: clean_line(content, max_line_len, context_only, &matcher, mark),
+            patterns: (0..per_pattern.len())
+                .filter(|&i| per_pattern[i].find(content).is_some())
+                .collect(),
+            symbol: None,
+        });
+    }
+
+    // Dir counts and SARIF logs keep every file; elsewhere noise shrinks to
+    // one line
+    let every_file = group_by == "dir" || format == "sarif";
+    let mut collapsed = Vec::new();
+    if show.collapse && !every_file {
+        by_file.retain(|file, matches| {
+            let minified = minified_lines.get(file).copied().unwrap_or(0);
+            match noise_kind(file, minified, matches.len()) {
+                Some(kind) => {
+                    collapsed.push(Collapsed {
+                        path: file.clone(),
+                        matches: matches.len(),
+                        kind,
+                    });
+                    false
+                }
+                None => true,
+            }
+        });
+        collapsed.sort_by(|a, b| a.path.cmp(&b.path));
+    }
+
+    // Symlinked and mirrored files: one entry per content
+    let duplicates = if show.dedup && !every_file {
+        dedup_files(&mut by_file)
+    } else {
+        HashMap::new()
+    };
+    total -= duplicates
+        .iter()
+        .map(|(file, copies)| by_file[file].len() * copies.len())
+        .sum::<usize>();
+
+    // Definitions around the matches, from the files on disk
+    if show.scope || group_by == "symbol" {
+        for (file, matches) in by_file.iter_mut() {
+            resolve_symbols(file, matches);
+        }
+    }
+
+    let mut files: Vec<_> = by_file.iter().collect();
+    sort_files(&mut files, show.sort);
+    let counts = if per_pattern.is_empty() {
+        Vec::new()
+    } else {
+        count_patterns(patterns, &files)
+    };
+
+    let context: HashMap<String, HashMap<usize, String>> = if context_lines.is_empty() {
+        HashMap::new()
+    } else {
+        files
+            .iter()
+            .map(|(file, matches)| {
+                let lines = read_context(file, matches, context_lines, max_line_len, &matcher);
+                (file.to_string(), lines)
+            })
+            .collect()
+    };
+
+    let rtk_output = match (group_by, format) {
+        (_, "sarif") => format!("{}\n", format_sarif(patterns, &files, &diagnostics)?),
+        ("dir", "json") => {
+            let dirs = group_by_dir(&files);
+            let report = GrepDirReport {
+                pattern: pattern.to_string(),
+                total,
+                truncated: dirs.len() > max_results,
+                dirs: dirs.into_iter().take(max_results).collect(),
+                diagnostics,
+            };
+            format!("{}\n", serde_json::to_string_pretty(&report)?)
+        }
+        ("dir", _) => format!(
+            "{}{}",
+            format_dirs(&pattern, total, &files, max_results),
+            format_diagnostics(&diagnostics)
+        ),
+        ("symbol", "json") => {
+            let symbols = group_by_symbol(&files);
+            let report = GrepSymbolReport {
+                pattern: pattern.to_string(),
+                total,
+                truncated: symbols.len() > max_results,
+                symbols: symbols.into_iter().take(max_results).collect(),
+                diagnostics,
+            };
+            format!("{}\n", serde_json::to_string_pretty(&report)?)
+        }
+        ("symbol", _) => format!(
+            "{}{}",
+            format_symbols(&pattern, total, &files, max_results),
+            format_diagnostics(&diagnostics)
+        ),
+        (_, "json") => {
+            let takes = allocate(&files, max_results, show.per_file);
+            format_json(
+                &pattern,
+                &counts,
+                total,
+                &files,
+                &takes,
+                &collapsed,
+                &duplicates,
+                &context,
+                context_lines,
+                diagnostics,
+            )?
+        }
+        _ => {
+            let takes = allocate(&files, max_results, show.per_file);
+            let text = format_text(
+                total,
+                &counts,
+                &files,
+                &takes,
+                &collapsed,
+                &duplicates,
+                &context,
+                context_lines,
+            );
+            format!("{}{}", text, format_diagnostics(&diagnostics))
+        }
+    };
+
+    print!("{}", rtk_output);
+    timer.track(&original_cmd, "rtk grep", &raw_output, &rtk_output);
+
+    utils::exit_with("grep", exit_code, verbose);
+
+    Ok(())
+}
+
+/// `--format sarif`: one result per matching line, its rule the first
+/// pattern it matches (`pattern-1` ...), described by the pattern itself
+fn format_sarif(
+    patterns: &[String],
+    files: &[(&String, &Vec<LineMatch>)],
+    diagnostics: &Diagnostics,
+) -> Result<String> {
+    let rule = |i: usize| format!("pattern-{}", i + 1);
+    let descriptions: Vec<(String, String)> = patterns
+        .iter()
+        .enumerate()
+        .map(|(i, pattern)| (rule(i), pattern.clone()))
+        .collect();
+    let findings: Vec<sarif::Finding> = files
+        .iter()
+        .flat_map(|(file, matches)| {
+            matches.iter().map(|m| sarif::Finding {
+                rule: Some(rule(m.patterns.first().copied().unwrap_or(0))),
+                level: sarif::Level::Note,
+                message: m.content.trim().to_string(),
+                file: file.to_string(),
+                line: m.line,
+                column: m.column,
+            })
+        })
+        .collect();
+    let notifications: Vec<sarif::Notification> = diagnostics
+        .errors
+        .iter()
+        .map(|e| (sarif::Level::Error, e))
+        .chain(
+            diagnostics
+                .warnings
+                .iter()
+                .map(|w| (sarif::Level::Warning, w)),
+        )
+        .map(|(level, message)| sarif::Notification {
+            level,
+            message: message.clone(),
+        })
+        .collect();
+    sarif::render("rtk grep", &descriptions, &findings, &notifications)
+}
+
+/// Minified code: very long lines, or long lines mostly made of symbols
+fn looks_minified(line: &str) -> bool {
+    let len = line.chars().count();
+    if len > MINIFIED_LINE_CHARS {
+        return true;
+    }
+    if len <= DENSE_LINE_CHARS {
+        return false;
+    }
+    let (symbols, visible) = line
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .fold((0, 0), |(s, v), c| {
+            (s + c.is_ascii_punctuation() as usize, v + 1)
+        });
+    symbols as f64 / visible as f64 > SYMBOL_DENSITY
+}
+
+/// Why a file's matches are noise: a known lockfile or sourcemap name, a
+/// `.min.` name, or matching lines mostly minified
+fn noise_kind(path: &str, minified_lines: usize, matches: usize) -> Option<&'static str> {
+    let name = path.rsplit(['/', '\\']).next().unwrap_or(path);
+    if LOCKFILES.contains(&name) {
+        Some("lockfile")
+    } else if name.ends_with(".map") {
+        Some("sourcemap")
+    } else if name.contains(".min.") || minified_lines * 2 > matches {
+        Some("minified")
+    } else {
+        None
+    }
+}
+
+/// Drop files whose content another matched file has (symlinks, vendored
+/// or mirrored copies). Only files matching on the same lines can be equal,
+/// so only those are read and hashed; archive members are left alone. Each
+/// group keeps its path in the most relevant place (src/ before vendor/),
+/// mapped to the paths dropped for it.
+fn dedup_files(by_file: &mut HashMap<String, Vec<LineMatch>>) -> HashMap<String, Vec<String>> {
+    let mut same_lines: HashMap<Vec<usize>, Vec<&String>> = HashMap::new();
+    for (file, matches) in by_file.iter() {
+        let lines = matches.iter().map(|m| m.line).collect();
+        same_lines.entry(lines).or_default().push(file);
+    }
+
+    let mut duplicates = HashMap::new();
+    for candidates in same_lines.into_values().filter(|c| c.len() > 1) {
+        let mut by_content: HashMap<(usize, u64), Vec<&String>> = HashMap::new();
+        for file in candidates {
+            if let Ok(bytes) = std::fs::read(file.as_str()) {
+                let mut hasher = std::collections::hash_map::DefaultHasher::new();
+                bytes.hash(&mut hasher);
+                by_content
+                    .entry((bytes.len(), hasher.finish()))
+                    .or_default()
+                    .push(file);
+            }
+        }
+        for mut copies in by_content.into_values().filter(|c| c.len() > 1) {
+            copies.sort_by(|a, b| {
+                path_weight(b)
+                    .total_cmp(&path_weight(a))
+                    .then_with(|| a.len().cmp(&b.len()))
+                    .then_with(|| a.cmp(b))
+            });
+            let kept = copies[0].clone();
+            let mut dropped: Vec<String> = copies[1..].iter().map(|f| f.to_string()).collect();
+            dropped.sort();
+            duplicates.insert(kept, dropped);
+        }
+    }
+    for file in duplicates.values().flatten() {
+        by_file.remove(file);
+    }
+    duplicates
+}
+
+/// Lines and files matched by each pattern
+fn count_patterns(patterns: &[String], files: &[(&String, &Vec<LineMatch>)]) -> Vec<PatternCount> {
+    patterns
+        .iter()
+        .enumerate()
+        .map(|(i, pattern)| {
+            let per_file = files
+                .iter()
+                .map(|(_, m)| m.iter().filter(|l| l.patterns.contains(&i)).count());
+            PatternCount {
+                pattern: pattern.clone(),
+                matches: per_file.clone().sum(),
+                files: per_file.filter(|&n| n > 0).count(),
+            }
+        })
+        .collect()
+}
+
+/// Order files so the most useful come before the `max_results` cutoff
+fn sort_files(files: &mut [(&String, &Vec<LineMatch>)], order: SortOrder) {
+    files.sort_by_key(|(f, _)| *f);
+    match order {
+        SortOrder::Path => {}
+        SortOrder::Count => files.sort_by_key(|(_, m)| std::cmp::Reverse(m.len())),
+        SortOrder::Mtime => {
+            files.sort_by_cached_key(|(f, _)| {
+                let modified = std::fs::metadata(f.as_str()).and_then(|m| m.modified());
+                std::cmp::Reverse(modified.ok())
+            });
+        }
+        SortOrder::Relevance => {
+            let scores: HashMap<&str, f64> = files
+                .iter()
+                .map(|(f, m)| {
+                    let bytes = std::fs::metadata(f.as_str()).map_or(0, |m| m.len());
+                    (f.as_str(), relevance(f, m.len(), bytes))
+                })
+                .collect();
+            files.sort_by(|(a, _), (b, _)| scores[b.as_str()].total_cmp(&scores[a.as_str()]));
+        }
+    }
+}
+
+/// Matches per KB, weighted by where the file lives
+fn relevance(path: &str, matches: usize, bytes: u64) -> f64 {
+    let kb = (bytes as f64 / 1024.0).max(1.0);
+    matches as f64 / kb * path_weight(path)
+}
+
+fn path_weight(path: &str) -> f64 {
+    let dirs: Vec<&str> = path.split('/').rev().skip(1).collect();
+    let any = |names: &[&str]| dirs.iter().any(|d| names.contains(d));
+    if any(VENDORED_DIRS) {
+        0.1
+    } else if any(TEST_DIRS) {
+        0.5
+    } else if any(SOURCE_DIRS) {
+        2.0
+    } else {
+        1.0
+    }
+}
+
+/// Share `budget` lines between files, in order: one line each first,
+/// then another round for files that have more, never above `per_file`.
+/// One noisy file can't starve the others.
+fn allocate(files: &[(&String, &Vec<LineMatch>)], budget: usize, per_file: usize) -> Vec<usize> {
+    let caps: Vec<usize> = files.iter().map(|(_, m)| m.len().min(per_file)).collect();
+    let mut takes = vec![0; files.len()];
+    let mut left = budget;
+    let mut round = 0;
+    while left > 0 && caps.iter().any(|&cap| cap > round) {
+        for (take, &cap) in takes.iter_mut().zip(&caps) {
+            if left == 0 {
+                break;
+            }
+            if cap > round {
+                *take += 1;
+                left -= 1;
+            }
+        }
+        round += 1;
+    }
+    takes
+}
+
+#[allow(clippy::too_many_arguments)]
+fn format_text(
+    total: usize,
+    counts: &[PatternCount],
+    files: &[(&String, &Vec<LineMatch>)],
+    takes: &[usize],
+    collapsed: &[Collapsed],
+    duplicates: &HashMap<String, Vec<String>>,
+    context: &HashMap<String, HashMap<usize, String>>,
+    ctx: ContextLines,
+) -> String {
+    let mut rtk_output = String::new();
+    rtk_output.push_str(&format!(
+        "🔍 {} in {}F:\n",
+        total,
+        files.len() + collapsed.len()
+    ));
+    for (i, count) in counts.iter().enumerate() {
+        rtk_output.push_str(&format!("  [{}] {}: ", i + 1, count.pattern));
+        if count.matches == 0 {
+            rtk_output.push_str("0\n");
+        } else {
+            rtk_output.push_str(&format!("{} in {}F\n", count.matches, count.files));
+        }
+    }
+    rtk_output.push('\n');
+
+    let mut shown = 0;
+
+    for ((file, matches), &take) in files.iter().zip(takes) {
+        if take == 0 {
+            continue;
+        }
+
+        let file_display = paths::compact(file, layout::path_width());
+        let tags = if counts.is_empty() {
+            String::new()
+        } else {
+            let matched: BTreeSet<usize> =
+                matches.iter().flat_map(|m| &m.patterns).copied().collect();
+            format!(" {}", pattern_tags(matched.into_iter()))
+        };
+        let copies = match duplicates.get(*file) {
+            Some(copies) => format!(
+                " (+{} duplicate{})",
+                copies.len(),
+                if copies.len() == 1 { "" } else { "s" }
+            ),
+            None => String::new(),
+        };
+        rtk_output.push_str(&format!(
+            "📄 {} ({}){}{}:\n",
+            file_display,
+            matches.len(),
+            tags,
+            copies
+        ));
+        shown += take;
+
+        // With --scope, the definition is named whenever it changes
+        let mut scope: Option<&str> = None;
+        match context.get(*file) {
+            Some(lines) => {
+                for line in with_context(&matches[..take], lines, ctx) {
+                    match line {
+                        Shown::Match(hit) => {
+                            rtk_output.push_str(&scope_header(hit, &mut scope));
+                            rtk_output.push_str(&format!(
+                                "  {:>4}: {}{}\n",
+                                hit.label(),
+                                hit.tags(),
+                                hit.content
+                            ))
+                        }
+                        Shown::Context(n, text) => {
+                            rtk_output.push_str(&format!("  {:>4}- {}\n", n, text))
+                        }
+                        Shown::Gap => rtk_output.push_str("     ⋯\n"),
+                    }
+                }
+            }
+            None => {
+                for hit in &matches[..take] {
+                    rtk_output.push_str(&scope_header(hit, &mut scope));
+                    rtk_output.push_str(&format!(
+                        "  {:>4}: {}{}\n",
+                        hit.label(),
+                        hit.tags(),
+                        hit.content
+                    ));
+                }
+            }
+        }
+
+        if matches.len() > take {
+            rtk_output.push_str(&format!("  +{}\n", matches.len() - take));
+        }
+        rtk_output.push('\n');
+    }
+
+    for file in collapsed {
+        rtk_output.push_str(&format!(
+            "📄 {} ({}, {} matches suppressed)\n",
+            paths::compact(&file.path, layout::path_width()),
+            file.kind,
+            file.matches
+        ));
+        shown += file.matches;
+    }
+    if !collapsed.is_empty() {
+        rtk_output.push_str("  --no-collapse to show them\n\n");
+    }
+
+    if total > shown {
+        rtk_output.push_str(&format!("... +{}\n", total - shown));
+    }
+
+    rtk_output
+}
+
+/// `  in fn load:` when `hit` is in another definition than the match shown
+/// before it in its file
+fn scope_header<'a>(hit: &'a LineMatch, scope: &mut Option<&'a str>) -> String {
+    if hit.symbol.as_deref() == *scope {
+        return String::new();
+    }
+    *scope = hit.symbol.as_deref();
+    match scope {
+        Some(symbol) => format!("  in {}:\n", symbol),
+        None => "  top level:\n".to_string(),
+    }
+}
+
+/// Roll matches up to their directory, most matches first
+fn group_by_dir(files: &[(&String, &Vec<LineMatch>)]) -> Vec<GrepDir> {
+    let mut by_dir: HashMap<String, GrepDir> = HashMap::new();
+    for (file, matches) in files {
+        let dir = match std::path::Path::new(file.as_str()).parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => {
+                format!("{}/", parent.display())
+            }
+            _ => "./".to_string(),
+        };
+        let entry = by_dir.entry(dir.clone()).or_insert(GrepDir {
+            path: dir,
+            matches: 0,
+            files: 0,
+        });
+        entry.matches += matches.len();
+        entry.files += 1;
+    }
+
+    let mut dirs: Vec<GrepDir> = by_dir.into_values().collect();
+    dirs.sort_by(|a, b| b.matches.cmp(&a.matches).then_with(|| a.path.cmp(&b.path)));
+    dirs
+}
+
+fn format_dirs(
+    pattern: &str,
+    total: usize,
+    files: &[(&String, &Vec<LineMatch>)],
+    max_results: usize,
+) -> String {
+    let dirs = group_by_dir(files);
+    let mut out = format!("🔍 {} in {}F, {} dirs:\n\n", total, files.len(), dirs.len());
+
+    for dir in dirs.iter().take(max_results) {
+        out.push_str(&format!(
+            "📁 {} {} matches in {} files\n",
+            paths::compact(&dir.path, layout::path_width()),
+            dir.matches,
+            dir.files
+        ));
+    }
+    if dirs.len() > max_results {
+        out.push_str(&format!("... +{} dirs\n", dirs.len() - max_results));
+    }
+
+    if let Some(top) = dirs.first() {
+        out.push_str(&format!(
+            "\nDrill down: rtk grep '{}' {}\n",
+            pattern,
+            top.path.trim_end_matches('/')
+        ));
+    }
+    out
+}
+
+/// Matches per definition, most matches first
+fn group_by_symbol(files: &[(&String, &Vec<LineMatch>)]) -> Vec<GrepSymbol> {
+    let mut symbols: Vec<GrepSymbol> = Vec::new();
+    for (file, matches) in files {
+        let start = symbols.len();
+        for hit in matches.iter() {
+            match symbols[start..].iter_mut().find(|s| s.symbol == hit.symbol) {
+                Some(group) => group.matches += 1,
+                None => symbols.push(GrepSymbol {
+                    path: file.to_string(),
+                    symbol: hit.symbol.clone(),
+                    line: hit.line,
+                    matches: 1,
+                }),
+            }
+        }
+    }
+    symbols.sort_by(|a, b| {
+        b.matches
+            .cmp(&a.matches)
+            .then_with(|| a.path.cmp(&b.path))
+            .then_with(|| a.line.cmp(&b.line))
+    });
+    symbols
+}
+
+fn format_symbols(
+    pattern: &str,
+    total: usize,
+    files: &[(&String, &Vec<LineMatch>)],
+    max_results: usize,
+) -> String {
+    let symbols = group_by_symbol(files);
+    let mut out = format!(
+        "🔍 {} in {}F, {} symbols:\n\n",
+        total,
+        files.len(),
+        symbols.len()
+    );
+
+    for symbol in symbols.iter().take(max_results) {
+        out.push_str(&format!(
+            "  {} ({}:{}) {} matches\n",
+            symbol.symbol.as_deref().unwrap_or("top level"),
+            paths::compact(&symbol.path, layout::path_width()),
+            symbol.line,
+            symbol.matches
+        ));
+    }
+    if symbols.len() > max_results {
+        out.push_str(&format!("... +{} symbols\n", symbols.len() - max_results));
+    }
+
+    if let Some(top) = symbols.first() {
+        out.push_str(&format!(
+            "\nDrill down: rtk grep --scope '{}' {}\n",
+            pattern, top.path
+        ));
+    }
+    out
+}
+
+#[allow(clippy::too_many_arguments)]
+fn format_json(
+    pattern: &str,
+    counts: &[PatternCount],
+    total: usize,
+    files: &[(&String, &Vec<LineMatch>)],
+    takes: &[usize],
+    collapsed: &[Collapsed],
+    duplicates: &HashMap<String, Vec<String>>,
+    context: &HashMap<String, HashMap<usize, String>>,
+    ctx: ContextLines,
+    diagnostics: Diagnostics,
+) -> Result<String> {
+    let mut shown = 0;
+    let mut report_files = Vec::new();
+
+    for ((file, matches), &take) in files.iter().zip(takes) {
+        if take == 0 {
+            continue;
+        }
+        shown += take;
+        let context_lines = match context.get(*file) {
+            Some(lines) => with_context(&matches[..take], lines, ctx)
+                .into_iter()
+                .filter_map(|line| match line {
+                    Shown::Context(line, content) => Some(LineMatch {
+                        line,
+                        column: None,
+                        content: content.to_string(),
+                        patterns: Vec::new(),
+                        symbol: None,
+                    }),
+                    _ => None,
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+        report_files.push(GrepFile {
+            path: file.to_string(),
+            count: matches.len(),
+            matches: matches[..take].to_vec(),
+            context: context_lines,
+            duplicates: duplicates.get(*file).cloned().unwrap_or_default(),
+        });
+    }
+
+    let suppressed: usize = collapsed.iter().map(|c| c.matches).sum();
+    let report = GrepReport {
+        pattern: pattern.to_string(),
+        patterns: counts.to_vec(),
+        total,
+        files: report_files,
+        collapsed: collapsed.to_vec(),
+        truncated: total > shown + suppressed,
+        diagnostics,
+    };
+
+    Ok(format!("{}\n", serde_json::to_string_pretty(&report)?))
+}
+
+/// Trim `line` and cut it to about `max_len` chars around the match, which
+/// is then set apart according to `mark`
+fn clean_line(
+    line: &str,
+    max_len: usize,
+    context_only: bool,
+    matcher: &Matcher,
+    mark: Mark,
+) -> String {
+    let cleaned = shorten_line(line, max_len, context_only, matcher);
+    matcher.mark(&cleaned, mark)
+}
+
+/// Cut the trimmed `line` to at most `max_len` characters, ellipses
+/// included, keeping the match in view. Works on whole characters (see
+/// [`clusters`]), so multi-byte text is never split.
+fn shorten_line(line: &str, max_len: usize, context_only: bool, matcher: &Matcher) -> String {
+    let trimmed = line.trim();
+    let parts = clusters(trimmed);
+    // Character holding the first char of the match
+    let match_at = matcher.find(trimmed).map(|char_pos| {
+        let mut chars = 0;
+        parts
+            .iter()
+            .position(|part| {
+                chars += part.chars().count();
+                chars > char_pos
+            })
+            .unwrap_or(parts.len().saturating_sub(1))
+    });
+
+    if context_only {
+        // Up to 20 chars before the match, through the end of the line
+        if let Some(at) = match_at {
+            let from = at.saturating_sub(20);
+            if parts.len() - from <= max_len {
+                return parts[from..].concat();
+            }
+        }
+    }
+
+    if parts.len() <= max_len {
+        return trimmed.to_string();
+    }
+    let (start, end) = window(parts.len(), match_at.unwrap_or(0), max_len);
+    let dots = |cut: bool| {
+        if cut && max_len > 2 * ELLIPSIS.len() {
+            ELLIPSIS
+        } else {
+            ""
+        }
+    };
+    format!(
+        "{}{}{}",
+        dots(start > 0),
+        parts[start..end].concat(),
+        dots(end < parts.len())
+    )
+}
+
+const ELLIPSIS: &str = "...";
+
+/// Window of a `len`-char line (longer than `max_len`) around the char at
+/// `at`: a third of the room before it, the rest after. The ellipses marking
+/// each cut side fit in `max_len`; below 7 chars there is no room for them.
+fn window(len: usize, at: usize, max_len: usize) -> (usize, usize) {
+    let dots = ELLIPSIS.len();
+    if max_len <= 2 * dots {
+        let start = at.min(len - max_len);
+        return (start, start + max_len);
+    }
+    let room = max_len - 2 * dots;
+    let start = at.saturating_sub(room / 3);
+    if start == 0 {
+        return (0, max_len - dots);
+    }
+    let end = start + room;
+    if end >= len {
+        return (len - (max_len - dots), len);
+    }
+    (start, end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stable::assert_format;
+
+    fn matcher(pattern: &str) -> Matcher {
+        Matcher::new(pattern, MatchOptions::default())
+    }
+
+    #[test]
+    fn test_group_results_sorted_by_file() {
+        let stdout = "src/b.rs:3:fn b()\nsrc/a.rs:1:fn a()\nsrc/b.rs:9:fn c()\n5:single\n";
+        let files = group_results(stdout, "lib.rs");
+        let paths: Vec<&str> = files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, ["lib.rs", "src/a.rs", "src/b.rs"]);
+        assert_eq!(
+            files[2].matches,
+            vec![
+                LineMatch {
+                    line: 3,
+                    column: None,
+                    content: "fn b()".into(),
+                    patterns: Vec::new(),
+                    symbol: None,
+                },
+                LineMatch {
+                    line: 9,
+                    column: None,
+                    content: "fn c()".into(),
+                    patterns: Vec::new(),
+                    symbol: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_search_in_temp_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "alpha\nneedle here\n").unwrap();
+        std::fs::write(dir.path().join("b.txt"), "nothing\n").unwrap();
+        let root = dir.path().to_string_lossy().to_string();
+
+        let files = search(
+            "needle",
+            std::slice::from_ref(&root),
+            &SearchOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(files.len(), 1);
+        assert!(files[0].path.ends_with("a.txt"));
+        assert_eq!(files[0].matches[0].line, 2);
+        assert_eq!(files[0].matches[0].content, "needle here");
+
+        assert!(search("absent", &[root], &SearchOptions::default())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_clean_line() {
+        let line = "            const result = someFunction();";
+        let cleaned = clean_line(line, 50, false, &matcher("result"), Mark::None);
+        assert!(!cleaned.starts_with(' '));
+        assert!(cleaned.len() <= 50);
+    }
+
+    #[test]
+    fn test_extra_args_accepted() {
+        // Test that the function signature accepts extra_args
+        // This is a compile-time test - if it compiles, the signature is correct
+        let _extra: Vec<String> = vec!["-i".to_string(), "-A".to_string(), "3".to_string()];
+        // No need to actually run - we're verifying the parameter exists
+    }
+
+    #[test]
+    fn test_clean_line_multibyte() {
+        // Thai text that exceeds max_len in bytes
+        let line = "  สวัสดีครับ นี่คือข้อความที่ยาวมากสำหรับทดสอบ  ";
+        let cleaned = clean_line(line, 20, false, &matcher("ครับ"), Mark::None);
+        // Should not panic
+        assert!(!cleaned.is_empty());
+    }
+
+    #[test]
     fn test_clean_line_emoji() {
         let line = "🎉🎊🎈🎁🎂🎄 some text 🎃🎆🎇✨";
-        let cleaned = clean_line(line, 15, false, "text");
+        let cleaned = clean_line(line, 15, false, &matcher("text"), Mark::None);
         assert!(!cleaned.is_empty());
     }
 
+    #[test]
+    fn test_window() {
+        // Match near the start: only the end is cut
+        assert_eq!(window(100, 2, 20), (0, 17));
+        // Middle: both sides cut, 14 chars of room, a third before the match
+        assert_eq!(window(100, 50, 20), (46, 60));
+        // Near the end: only the start is cut
+        assert_eq!(window(100, 98, 20), (83, 100));
+        // No room for ellipses
+        assert_eq!(window(10, 9, 4), (6, 10));
+        assert_eq!(window(10, 3, 0), (3, 3));
+    }
+
+    /// xorshift: reproducible pseudo-random numbers for the property test
+    fn next(state: &mut u64) -> usize {
+        *state ^= *state << 13;
+        *state ^= *state >> 7;
+        *state ^= *state << 17;
+        *state as usize
+    }
+
+    #[test]
+    fn test_clean_line_properties_multibyte() {
+        let alphabet = [
+            "a",
+            "Z",
+            " ",
+            "_",
+            "é",
+            "e\u{301}",
+            "中",
+            "文",
+            "日本",
+            "ก",
+            "ที่",
+            "🎉",
+            "👍🏽",
+            "👨\u{200D}👩\u{200D}👧",
+            "🇫🇷",
+            "❤\u{FE0F}",
+            "İ",
+            "ß",
+        ];
+        let needle = matcher("needle");
+        let mut state = 0x9E37_79B9_7F4A_7C15;
+
+        for _ in 0..500 {
+            let len = next(&mut state) % 120;
+            let mut line: String = (0..len)
+                .map(|_| alphabet[next(&mut state) % alphabet.len()])
+                .collect();
+            if next(&mut state).is_multiple_of(2) {
+                let at = line.char_indices().map(|(i, _)| i).collect::<Vec<_>>();
+                let at = at
+                    .get(next(&mut state) % (at.len() + 1))
+                    .copied()
+                    .unwrap_or(line.len());
+                line.insert_str(at, "needle");
+            }
+            let trimmed = line.trim();
+            let bounds: Vec<usize> = clusters(trimmed)
+                .iter()
+                .scan(0, |pos, c| {
+                    *pos += c.len();
+                    Some(*pos)
+                })
+                .chain([0])
+                .collect();
+
+            for max_len in [0, 1, 5, 6, 7, 9, 12, 20, 40, 200] {
+                for context_only in [false, true] {
+                    let cleaned = clean_line(&line, max_len, context_only, &needle, Mark::None);
+                    let core = cleaned
+                        .trim_start_matches(ELLIPSIS)
+                        .trim_end_matches(ELLIPSIS);
+                    let fits = clusters(&cleaned).len() <= max_len;
+                    assert!(fits, "{:?} longer than {}: {:?}", line, max_len, cleaned);
+                    // A slice of the line cut between whole characters
+                    let whole = trimmed
+                        .match_indices(core)
+                        .any(|(i, _)| bounds.contains(&i) && bounds.contains(&(i + core.len())));
+                    assert!(whole, "{:?} cut mid-character: {:?}", line, cleaned);
+                    if trimmed.contains("needle") && max_len >= 20 {
+                        assert!(cleaned.contains("needle"), "{:?} lost the match", cleaned);
+                    }
+                }
+            }
+        }
+    }
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_split_paths() {
+        let (paths, extra) = split_paths("src", &strings(&["tests/", "-i", "-A", "3"]));
+        assert_eq!(paths, strings(&["src", "tests/"]));
+        assert_eq!(extra, strings(&["-i", "-A", "3"]));
+
+        let (paths, extra) = split_paths(".", &[]);
+        assert_eq!(paths, strings(&["."]));
+        assert!(extra.is_empty());
+    }
+
+    #[test]
+    fn test_split_glob() {
+        assert_eq!(
+            split_glob("src/**/*.rs"),
+            ("src".to_string(), "**/*.rs".to_string())
+        );
+        assert_eq!(split_glob("*.md"), (".".to_string(), "*.md".to_string()));
+    }
+
+    #[test]
+    fn test_plan_search_groups_globs_by_base() {
+        let groups = plan_search(&strings(&["src/**/*.rs", "tests/", "src/*.toml", "*.md"]));
+        assert_eq!(
+            groups,
+            vec![
+                SearchGroup {
+                    roots: strings(&["tests/"]),
+                    globs: Vec::new(),
+                },
+                SearchGroup {
+                    roots: strings(&["src"]),
+                    globs: strings(&["**/*.rs", "*.toml"]),
+                },
+                SearchGroup {
+                    roots: strings(&["."]),
+                    globs: strings(&["*.md"]),
+                },
+            ]
+        );
+    }
+
+    fn hit(line: usize, content: &str) -> LineMatch {
+        LineMatch {
+            line,
+            column: None,
+            content: content.to_string(),
+            patterns: Vec::new(),
+            symbol: None,
+        }
+    }
+
+    fn lines(range: std::ops::RangeInclusive<usize>) -> HashMap<usize, String> {
+        range.map(|n| (n, format!("line {}", n))).collect()
+    }
+
+    #[test]
+    fn test_with_context_merges_overlapping_windows() {
+        let matches = vec![hit(5, "m5"), hit(7, "m7")];
+        let context = lines(1..=20);
+        let ctx = ContextLines {
+            before: 1,
+            after: 1,
+        };
+        let shown = with_context(&matches, &context, ctx);
+        assert_eq!(
+            shown,
+            vec![
+                Shown::Context(4, "line 4"),
+                Shown::Match(&matches[0]),
+                Shown::Context(6, "line 6"),
+                Shown::Match(&matches[1]),
+                Shown::Context(8, "line 8"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_with_context_marks_gaps() {
+        let matches = vec![hit(2, "m2"), hit(10, "m10")];
+        let context = lines(1..=12);
+        let ctx = ContextLines {
+            before: 1,
+            after: 1,
+        };
+        let shown = with_context(&matches, &context, ctx);
+        assert_eq!(shown[3], Shown::Gap);
+        assert_eq!(shown.len(), 7);
+        // Window at end of file is clipped
+        assert_eq!(shown[6], Shown::Context(11, "line 11"));
+    }
+
+    #[test]
+    fn test_take_context_args() {
+        let (ctx, rest) = take_context_args(
+            ContextLines::default(),
+            &strings(&["-i", "-A", "3", "-B2", "--glob", "*.rs"]),
+        );
+        assert_eq!(
+            ctx,
+            ContextLines {
+                before: 2,
+                after: 3
+            }
+        );
+        assert_eq!(rest, strings(&["-i", "--glob", "*.rs"]));
+
+        let (ctx, rest) = take_context_args(ContextLines::default(), &strings(&["--context=4"]));
+        assert_eq!(
+            ctx,
+            ContextLines {
+                before: 4,
+                after: 4
+            }
+        );
+        assert!(rest.is_empty());
+
+        // Non-ASCII args pass through untouched
+        let (ctx, rest) =
+            take_context_args(ContextLines::default(), &strings(&["-g", "€*.rs", "é"]));
+        assert_eq!(ctx, ContextLines::default());
+        assert_eq!(rest, strings(&["-g", "€*.rs", "é"]));
+    }
+
+    #[test]
+    fn test_format_text_with_context() {
+        let file = "src/a.rs".to_string();
+        let matches = vec![hit(3, "fn target()")];
+        let mut context = HashMap::new();
+        context.insert(file.clone(), lines(1..=10));
+        let ctx = ContextLines {
+            before: 1,
+            after: 1,
+        };
+        let out = format_text(
+            1,
+            &[],
+            &[(&file, &matches)],
+            &[1],
+            &[],
+            &HashMap::new(),
+            &context,
+            ctx,
+        );
+        assert!(out.contains("     2- line 2\n     3: fn target()\n     4- line 4\n"));
+    }
+
+    #[test]
+    fn test_matcher_options() {
+        let line = "Config config = loadConfig(path);";
+        assert_eq!(matcher("config").find(line), Some(7));
+
+        let ignore_case = MatchOptions {
+            ignore_case: true,
+            ..Default::default()
+        };
+        assert_eq!(Matcher::new("config", ignore_case).find(line), Some(0));
+
+        // -w skips "loadConfig" when looking for a whole word
+        let word = MatchOptions {
+            ignore_case: true,
+            word: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            Matcher::new("Config", word).find("loadConfig(Config)"),
+            Some(11)
+        );
+
+        let fixed = MatchOptions {
+            fixed: true,
+            ..Default::default()
+        };
+        assert_eq!(Matcher::new("a.b(", fixed).find("axb( a.b("), Some(5));
+    }
+
+    #[test]
+    fn test_check_patterns() {
+        let patterns = |p: &str| vec![p.to_string()];
+        let err = check_patterns(&patterns("load(path"), MatchOptions::default())
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            err,
+            "Invalid regex 'load(path': unclosed group\n  load(path\n      ^\n  \
+             try 'load\\(path' to match '(' literally, or -F to search the whole pattern as text"
+        );
+
+        // Literal and PCRE2 patterns are not the regex crate's business
+        let fixed = MatchOptions {
+            fixed: true,
+            ..Default::default()
+        };
+        assert!(check_patterns(&patterns("load(path"), fixed).is_ok());
+        let pcre2 = MatchOptions {
+            pcre2: true,
+            ..Default::default()
+        };
+        assert!(check_patterns(&patterns("(?<=fn )load"), pcre2).is_ok());
+        assert!(check_patterns(&patterns(r"fn \w+\(|struct"), MatchOptions::default()).is_ok());
+    }
+
+    #[test]
+    fn test_smart_case() {
+        let smart = |p: &str, opts: MatchOptions| opts.smart_case(&[p.to_string()]).ignore_case;
+        let default = MatchOptions::default();
+        assert!(smart("config", default));
+        assert!(!smart("Config", default));
+        // Escapes aren't letters to match
+        assert!(smart(r"\w+\S\p{Lu}\PL", default));
+        assert!(!smart(r"\bLoad", default));
+        let fixed = MatchOptions {
+            fixed: true,
+            ..Default::default()
+        };
+        assert!(!smart(r"\S", fixed));
+        let case_sensitive = MatchOptions {
+            case_sensitive: true,
+            ..Default::default()
+        };
+        assert!(!smart("config", case_sensitive));
+
+        // One uppercase pattern makes the whole search case-sensitive
+        let patterns = vec!["parse".to_string(), "Config".to_string()];
+        assert!(!default.smart_case(&patterns).ignore_case);
+
+        // clean_line centers on the same match the search found
+        let matcher = Matcher::new("needle", default.smart_case(&["needle".to_string()]));
+        assert_eq!(matcher.find("a NEEDLE"), Some(2));
+    }
+
+    #[test]
+    fn test_pattern_fix() {
+        let fix = |p: &str| pattern_fix(p, &regex_syntax::Parser::new().parse(p).unwrap_err());
+        assert_eq!(fix("load(path"), Fix::Escape(4));
+        assert_eq!(fix("path)"), Fix::Escape(4));
+        assert_eq!(fix("v[0"), Fix::Escape(1));
+        assert_eq!(fix("*.rs"), Fix::Escape(0));
+        assert_eq!(fix("a{"), Fix::Escape(1));
+        assert_eq!(fix(r"C:\"), Fix::Escape(2));
+        assert_eq!(fix("(?<=fn )load"), Fix::Pcre2);
+        assert_eq!(fix(r"(a)\1"), Fix::Pcre2);
+    }
+
+    #[test]
+    fn test_clean_line_centers_on_case_insensitive_match() {
+        let line = format!("{} NEEDLE {}", "x".repeat(100), "y".repeat(100));
+        let opts = MatchOptions {
+            ignore_case: true,
+            ..Default::default()
+        };
+        let cleaned = clean_line(&line, 40, false, &Matcher::new("needle", opts), Mark::None);
+        assert!(cleaned.contains("NEEDLE"));
+        assert!(cleaned.starts_with("..."));
+    }
+
+    #[test]
+    fn test_clean_line_marks_match() {
+        let cleaned = clean_line(
+            "    let total = sum(x);",
+            50,
+            false,
+            &matcher("sum"),
+            Mark::Plain,
+        );
+        assert_eq!(cleaned, "let total = «sum»(x);");
+
+        // The marker follows the match into a truncated window
+        let line = format!("{} needle {}", "x".repeat(100), "y".repeat(100));
+        let cleaned = clean_line(&line, 40, false, &matcher("needle"), Mark::Plain);
+        assert!(cleaned.starts_with("...") && cleaned.contains("«needle»"));
+
+        colored::control::set_override(true);
+        let cleaned = clean_line("a foo b", 50, false, &matcher("foo"), Mark::Ansi);
+        assert_eq!(cleaned, format!("a {} b", "foo".red().bold()));
+    }
+
+    #[test]
+    fn test_format_text_with_column() {
+        let file = "src/a.rs".to_string();
+        let matches = vec![LineMatch {
+            line: 42,
+            column: Some(17),
+            content: "fn target()".to_string(),
+            patterns: Vec::new(),
+            symbol: None,
+        }];
+        let out = format_text(
+            1,
+            &[],
+            &[(&file, &matches)],
+            &[1],
+            &[],
+            &HashMap::new(),
+            &HashMap::new(),
+            ContextLines::default(),
+        );
+        assert!(out.contains("  42:17: fn target()\n"), "{}", out);
+
+        let json = serde_json::to_value(&matches[0]).unwrap();
+        assert_eq!(json["column"], 17);
+        assert!(serde_json::to_value(hit(1, "x"))
+            .unwrap()
+            .get("column")
+            .is_none());
+    }
+
+    #[test]
+    fn test_allocate_fair_share() {
+        let names: Vec<String> = (0..4).map(|i| format!("f{}", i)).collect();
+        let sizes = [30, 1, 5, 2];
+        let matches: Vec<Vec<LineMatch>> = sizes
+            .iter()
+            .map(|&n| (1..=n).map(|l| hit(l, "")).collect())
+            .collect();
+        let files: Vec<_> = names.iter().zip(&matches).collect();
+
+        // The noisy first file no longer eats the whole budget
+        assert_eq!(allocate(&files, 8, 10), [3, 1, 2, 2]);
+        // Unused share goes to files with more lines, up to the per-file cap
+        assert_eq!(allocate(&files, 50, 10), [10, 1, 5, 2]);
+        assert_eq!(allocate(&files, 50, 2), [2, 1, 2, 2]);
+        // Fewer lines than files: the first files get one each
+        assert_eq!(allocate(&files, 3, 10), [1, 1, 1, 0]);
+    }
+
+    #[test]
+    fn test_format_text_hides_unallocated() {
+        let (a, b) = ("a.rs".to_string(), "b.rs".to_string());
+        let many: Vec<LineMatch> = (1..=5).map(|l| hit(l, "x")).collect();
+        let one = vec![hit(1, "y")];
+        let files = vec![(&a, &many), (&b, &one)];
+        let out = format_text(
+            6,
+            &[],
+            &files,
+            &[2, 0],
+            &[],
+            &HashMap::new(),
+            &HashMap::new(),
+            ContextLines::default(),
+        );
+        assert!(
+            out.contains("📄 a.rs (5):\n     1: x\n     2: x\n  +3\n"),
+            "{}",
+            out
+        );
+        assert!(!out.contains("b.rs"));
+        assert!(out.ends_with("... +4\n"));
+        assert_format!("grep_text_budget", out);
+    }
+
+    #[test]
+    fn test_path_weight() {
+        assert!(path_weight("src/grep.rs") > path_weight("README.md"));
+        assert!(path_weight("README.md") > path_weight("tests/grep.rs"));
+        assert!(path_weight("tests/grep.rs") > path_weight("vendor/src/grep.rs"));
+        // Only directories count, not the file name
+        assert_eq!(path_weight("build"), 1.0);
+    }
+
+    #[test]
+    fn test_sort_files() {
+        let (a, b, c) = (
+            "vendor/lib/a.rs".to_string(),
+            "src/b.rs".to_string(),
+            "tests/c.rs".to_string(),
+        );
+        let (one, two, three) = (
+            vec![hit(1, "")],
+            vec![hit(1, ""), hit(2, "")],
+            vec![hit(1, ""), hit(2, ""), hit(3, "")],
+        );
+        let order = |order| {
+            let mut files = vec![(&c, &two), (&a, &three), (&b, &one)];
+            sort_files(&mut files, order);
+            files.iter().map(|(f, _)| f.as_str()).collect::<Vec<_>>()
+        };
+        assert_eq!(
+            order(SortOrder::Path),
+            ["src/b.rs", "tests/c.rs", "vendor/lib/a.rs"]
+        );
+        assert_eq!(
+            order(SortOrder::Count),
+            ["vendor/lib/a.rs", "tests/c.rs", "src/b.rs"]
+        );
+        // Missing files weigh 1KB: src (1 × 2.0) > tests (2 × 0.5) > vendor (3 × 0.1)
+        assert_eq!(
+            order(SortOrder::Relevance),
+            ["src/b.rs", "tests/c.rs", "vendor/lib/a.rs"]
+        );
+    }
+
+    #[test]
+    fn test_resolve_patterns() {
+        let (patterns, paths) =
+            resolve_patterns(Vec::new(), None, strings(&["foo", "src"])).unwrap();
+        assert_eq!((patterns, paths), (strings(&["foo"]), strings(&["src"])));
+
+        // With -e, every positional arg is a path
+        let (patterns, paths) =
+            resolve_patterns(strings(&["a", "b"]), None, strings(&["src"])).unwrap();
+        assert_eq!((patterns, paths), (strings(&["a", "b"]), strings(&["src"])));
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("patterns.txt");
+        std::fs::write(&file, "parse_config\r\n\nConfigError\n").unwrap();
+        let (patterns, paths) = resolve_patterns(strings(&["a"]), Some(&file), Vec::new()).unwrap();
+        assert_eq!(patterns, strings(&["a", "parse_config", "ConfigError"]));
+        assert!(paths.is_empty());
+
+        std::fs::write(&file, "\n").unwrap();
+        assert!(resolve_patterns(Vec::new(), Some(&file), Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_several_patterns_one_pass() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.rs"), "fn load() {}\nstruct Config;\n").unwrap();
+        std::fs::write(dir.path().join("b.rs"), "let c = Config::load();\n").unwrap();
+        let root = dir.path().to_string_lossy().to_string();
+
+        let patterns = strings(&["load", "Config"]);
+        let (stdout, _, exit_code) =
+            run_searches(&patterns, &[root], &[], MatchOptions::default(), &[], 0).unwrap();
+        assert_eq!(exit_code, 0);
+        assert_eq!(stdout.lines().count(), 3);
+
+        let any = Matcher::any(&patterns, MatchOptions::default());
+        assert_eq!(any.find("struct Config;"), Some(7));
+        assert_eq!(any.find("fn load()"), Some(3));
+    }
+
+    #[test]
+    fn test_backends_fall_through_to_builtin() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.rs"), "fn load() {}\nstruct Config;\n").unwrap();
+        std::fs::write(dir.path().join("b.bin"), b"Config\0\x01").unwrap();
+        let group = SearchGroup {
+            roots: vec![dir.path().to_string_lossy().to_string()],
+            globs: Vec::new(),
+        };
+        let search = |backends: Vec<Backend>, patterns: &[&str], opts: MatchOptions| {
+            let config = SearchConfig {
+                backends,
+                args: BTreeMap::new(),
+            };
+            search_group(&strings(patterns), &group, &[], opts, &[], &config, 0)
+        };
+
+        let builtin = search(
+            vec![Backend::Builtin],
+            &["config|load"],
+            MatchOptions {
+                ignore_case: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let a = dir.path().join("a.rs").to_string_lossy().to_string();
+        assert_eq!(
+            builtin,
+            (
+                format!("{}:1:fn load() {{}}\n{}:2:struct Config;\n", a, a),
+                String::new(),
+                0
+            )
+        );
+        // ugrep if installed, else the builtin search: the same lines
+        let chain = search(
+            vec![Backend::Ugrep, Backend::Builtin],
+            &["config|load"],
+            MatchOptions {
+                ignore_case: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(chain.0, builtin.0);
+
+        let (_, stderr, code) =
+            search(vec![Backend::Builtin], &["("], MatchOptions::default()).unwrap();
+        assert_eq!(code, 2);
+        assert!(stderr.contains("regex parse error"), "{}", stderr);
+
+        let none = search(Vec::new(), &["x"], MatchOptions::default());
+        assert!(none
+            .unwrap_err()
+            .to_string()
+            .contains("No search backend installed"));
+    }
+
+    #[test]
+    fn test_search_zip_names_members() {
+        use flate2::{write::GzEncoder, Compression};
+        use std::io::Write;
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.rs"), "// ERROR handling\n").unwrap();
+        let log = dir.path().join("app.log.gz");
+        let mut gz = GzEncoder::new(std::fs::File::create(&log).unwrap(), Compression::default());
+        gz.write_all(b"ok\nERROR boom\n").unwrap();
+        gz.finish().unwrap();
+
+        let group = SearchGroup {
+            roots: vec![dir.path().to_string_lossy().to_string()],
+            globs: Vec::new(),
+        };
+        let config = SearchConfig {
+            backends: vec![Backend::Builtin],
+            args: BTreeMap::new(),
+        };
+        let search = |search_zip, types: &[String]| {
+            let opts = MatchOptions {
+                search_zip,
+                ..Default::default()
+            };
+            search_group(&strings(&["ERROR"]), &group, types, opts, &[], &config, 0)
+                .unwrap()
+                .0
+        };
+
+        let member = format!("{}!app.log:2:ERROR boom", log.display());
+        let found = search(true, &[]);
+        assert!(found.lines().any(|l| l == member), "{}", found);
+        assert_eq!(found.lines().count(), 2);
+        assert!(!search(false, &[]).contains("app.log"));
+        // File types apply to member names
+        assert_eq!(search(true, &strings(&["log"])), format!("{}\n", member));
+    }
+
+    #[test]
+    fn test_classify_stderr() {
+        let stderr = "\
+rg: regex parse error:
+    (?:fn(
+       ^
+error: unclosed group
+rg: ./a/secret: Permission denied (os error 13)
+rg: ./b/secret: Permission denied (os error 13)
+grep: warning: stray \\ before -
+";
+        let diagnostics = classify_stderr(stderr);
+        assert_eq!(
+            diagnostics.errors,
+            strings(&[
+                "regex parse error: (?:fn( error: unclosed group",
+                "Permission denied (os error 13): ./a/secret, ./b/secret",
+            ])
+        );
+        assert_eq!(
+            diagnostics.warnings,
+            strings(&["warning: stray \\ before -"])
+        );
+        assert_eq!(
+            format_diagnostics(&diagnostics),
+            "❌ 3 errors:\n  regex parse error: (?:fn( error: unclosed group\n  \
+             Permission denied (os error 13): ./a/secret, ./b/secret\n\
+             ⚠️ 1 warning:\n  warning: stray \\ before -\n"
+        );
+        assert_eq!(format_diagnostics(&classify_stderr("")), "");
+    }
+
+    #[test]
+    fn test_merge_causes_caps_paths() {
+        let messages: Vec<String> = (1..=5)
+            .map(|i| format!("./f{}: No such file or directory (os error 2)", i))
+            .collect();
+        assert_eq!(
+            merge_causes(&messages),
+            strings(&["No such file or directory (os error 2): ./f1, ./f2, ./f3, +2 more"])
+        );
+    }
+
+    #[test]
+    fn test_search_config_from_toml() {
+        let config: SearchConfig = toml::from_str(
+            "backends = [\"git-grep\", \"builtin\"]\n[args]\nrg = [\"--max-columns=500\"]\n",
+        )
+        .unwrap();
+        assert_eq!(config.backends, vec![Backend::GitGrep, Backend::Builtin]);
+        assert_eq!(config.args(Backend::Rg), ["--max-columns=500"]);
+        assert!(config.args(Backend::Grep).is_empty());
+        let default = SearchConfig::default().backends;
+        if cfg!(windows) {
+            assert_eq!(default, vec![Backend::Rg, Backend::Builtin]);
+        } else {
+            assert_eq!(default, vec![Backend::Rg, Backend::Grep, Backend::Builtin]);
+        }
+    }
+
+    #[test]
+    fn test_format_text_tags_patterns() {
+        let tagged = |line, content: &str, patterns: Vec<usize>| LineMatch {
+            patterns,
+            ..hit(line, content)
+        };
+        let (a, b) = ("src/a.rs".to_string(), "src/b.rs".to_string());
+        let a_matches = vec![
+            tagged(1, "fn load() {}", vec![0]),
+            tagged(2, "struct Config;", vec![1]),
+        ];
+        let b_matches = vec![tagged(1, "let c = Config::load();", vec![0, 1])];
+        let files = vec![(&a, &a_matches), (&b, &b_matches)];
+        let counts = count_patterns(&strings(&["load", "Config", "Missing"]), &files);
+        assert_eq!(
+            counts[1],
+            PatternCount {
+                pattern: "Config".into(),
+                matches: 2,
+                files: 2,
+            }
+        );
+
+        let out = format_text(
+            3,
+            &counts,
+            &files,
+            &[2, 1],
+            &[],
+            &HashMap::new(),
+            &HashMap::new(),
+            ContextLines::default(),
+        );
+        assert_eq!(
+            out,
+            "🔍 3 in 2F:\n  [1] load: 2 in 2F\n  [2] Config: 2 in 2F\n  [3] Missing: 0\n\n\
+             📄 src/a.rs (2) [1,2]:\n     1: [1] fn load() {}\n     2: [2] struct Config;\n\n\
+             📄 src/b.rs (1) [1,2]:\n     1: [1,2] let c = Config::load();\n\n"
+        );
+    }
+
+    #[test]
+    fn test_noise_detection() {
+        let bundle = "!function(e,t){\"object\"==typeof exports&&(module.exports=t())}(this,function(){var n={};return n.a=function(e){return e&&e.b?e:{c:e}},n.d=function(e,t){for(var r in t)n.o(t,r)&&Object.defineProperty(e,r,{get:t[r]})},n});";
+        assert!(looks_minified(bundle));
+        assert!(looks_minified(&"a ".repeat(300)));
+        let prose = "This is a long line of documentation explaining, in plain words, how the parser handles nested groups and why it needs a stack; nothing here should look minified to the heuristic at all.";
+        assert!(!looks_minified(prose));
+        assert!(!looks_minified("fn f(a: &[u8]) -> Result<(), E> {"));
+
+        assert_eq!(noise_kind("web/package-lock.json", 0, 3), Some("lockfile"));
+        assert_eq!(noise_kind("dist/app.js.map", 0, 3), Some("sourcemap"));
+        assert_eq!(noise_kind("dist/app.min.js", 0, 3), Some("minified"));
+        assert_eq!(noise_kind("dist/app.js", 2, 3), Some("minified"));
+        assert_eq!(noise_kind("src/app.js", 1, 3), None);
+    }
+
+    #[test]
+    fn test_format_text_collapsed_files() {
+        let a = "src/a.js".to_string();
+        let matches = vec![hit(3, "fetchUser()")];
+        let collapsed = vec![Collapsed {
+            path: "dist/app.min.js".into(),
+            matches: 120,
+            kind: "minified",
+        }];
+        let out = format_text(
+            121,
+            &[],
+            &[(&a, &matches)],
+            &[1],
+            &collapsed,
+            &HashMap::new(),
+            &HashMap::new(),
+            ContextLines::default(),
+        );
+        assert_eq!(
+            out,
+            "🔍 121 in 2F:\n\n📄 src/a.js (1):\n     3: fetchUser()\n\n\
+             📄 dist/app.min.js (minified, 120 matches suppressed)\n  --no-collapse to show them\n\n"
+        );
+    }
+
+    #[test]
+    fn test_dedup_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = |p: &str| dir.path().join(p).to_string_lossy().into_owned();
+        for sub in ["src", "vendor/lib", "mirror"] {
+            std::fs::create_dir_all(dir.path().join(sub)).unwrap();
+        }
+        let content = "use x;\nfn target() {}\n";
+        std::fs::write(path("src/a.rs"), content).unwrap();
+        std::fs::write(path("vendor/lib/a.rs"), content).unwrap();
+        std::fs::write(path("mirror/a.rs"), content).unwrap();
+        // Same matched line, other content
+        std::fs::write(path("src/b.rs"), "use y;\nfn target() {}\n").unwrap();
+
+        let mut by_file: HashMap<String, Vec<LineMatch>> =
+            ["src/a.rs", "vendor/lib/a.rs", "mirror/a.rs", "src/b.rs"]
+                .iter()
+                .map(|p| (path(p), vec![hit(2, "fn target() {}")]))
+                .collect();
+        let duplicates = dedup_files(&mut by_file);
+        assert_eq!(
+            duplicates,
+            HashMap::from([(
+                path("src/a.rs"),
+                vec![path("mirror/a.rs"), path("vendor/lib/a.rs")]
+            )])
+        );
+        let mut left: Vec<&String> = by_file.keys().collect();
+        left.sort();
+        assert_eq!(left, vec![&path("src/a.rs"), &path("src/b.rs")]);
+
+        let a = "src/a.rs".to_string();
+        let matches = vec![hit(2, "fn target() {}")];
+        let out = format_text(
+            1,
+            &[],
+            &[(&a, &matches)],
+            &[1],
+            &[],
+            &HashMap::from([(a.clone(), vec!["vendor/a.rs".into(), "mirror/a.rs".into()])]),
+            &HashMap::new(),
+            ContextLines::default(),
+        );
+        assert!(
+            out.contains("📄 src/a.rs (1) (+2 duplicates):\n"),
+            "{}",
+            out
+        );
+    }
+
+    #[test]
+    fn test_format_sarif() {
+        let a = "src/a.rs".to_string();
+        let matches = vec![
+            LineMatch {
+                column: Some(4),
+                patterns: vec![1],
+                ..hit(2, "  fn load() {}")
+            },
+            hit(9, "load()"),
+        ];
+        let diagnostics = classify_stderr("grep: src/secret: Permission denied\n");
+        let patterns = vec!["parse".to_string(), "load".to_string()];
+        let out = format_sarif(&patterns, &[(&a, &matches)], &diagnostics).unwrap();
+        let log: serde_json::Value = serde_json::from_str(&out).unwrap();
+        let run = &log["runs"][0];
+
+        assert_eq!(run["tool"]["driver"]["rules"][0]["id"], "pattern-2");
+        assert_eq!(
+            run["tool"]["driver"]["rules"][0]["shortDescription"]["text"],
+            "load"
+        );
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0]["message"]["text"], "fn load() {}");
+        assert_eq!(results[0]["level"], "note");
+        assert_eq!(
+            results[0]["locations"][0]["physicalLocation"]["region"],
+            serde_json::json!({ "startLine": 2, "startColumn": 4 })
+        );
+        assert_eq!(results[1]["ruleId"], "pattern-1");
+        assert_eq!(run["invocations"][0]["executionSuccessful"], false);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_dedup_symlinked_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("a.rs");
+        let link = dir.path().join("link.rs");
+        std::fs::write(&target, "fn target() {}\n").unwrap();
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+        let (target, link) = (
+            target.to_string_lossy().into_owned(),
+            link.to_string_lossy().into_owned(),
+        );
+
+        let mut by_file = HashMap::from([
+            (target.clone(), vec![hit(1, "fn target() {}")]),
+            (link.clone(), vec![hit(1, "fn target() {}")]),
+        ]);
+        assert_eq!(dedup_files(&mut by_file)[&target], vec![link]);
+        assert_eq!(by_file.len(), 1);
+    }
+
+    #[test]
+    fn test_relevance_prefers_dense_files() {
+        assert!(relevance("src/a.rs", 5, 2048) > relevance("src/b.rs", 5, 200 * 1024));
+    }
+
+    #[test]
+    fn test_join_multiline() {
+        let stream = r#"{"type":"begin","data":{"path":{"text":"src/a.rs"}}}
+{"type":"match","data":{"path":{"text":"src/a.rs"},"lines":{"text":"pub fn run(\n    pattern: &str,\n\n) -> Result<()> {\n"},"line_number":12}}
+{"type":"match","data":{"path":{"bytes":"//4="},"lines":{"text":"x\n"},"line_number":1}}
+{"type":"end","data":{"path":{"text":"src/a.rs"}}}"#;
+        let joined = join_multiline(stream);
+        assert_eq!(
+            joined,
+            "src/a.rs:12:pub fn run( pattern: &str, ) -> Result<()> {\n"
+        );
+        assert_eq!(
+            parse_result_line(joined.trim_end(), "."),
+            Some((
+                "src/a.rs".to_string(),
+                12,
+                "pub fn run( pattern: &str, ) -> Result<()> {"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_result_line_windows_paths() {
+        assert_eq!(
+            parse_result_line(r"C:\src\main.rs:12:let x = 1;", "."),
+            Some((r"C:\src\main.rs".to_string(), 12, "let x = 1;"))
+        );
+        assert_eq!(
+            parse_result_line("src/a.rs:3:url: c:/x", "."),
+            Some(("src/a.rs".to_string(), 3, "url: c:/x"))
+        );
+    }
+
+    #[test]
+    fn test_multiline_search() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("a.rs"),
+            "fn one() {}\nfn two(\n    x: u32,\n) {}\n",
+        )
+        .unwrap();
+        let options = SearchOptions {
+            matching: MatchOptions {
+                multiline: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let Ok(files) = search(
+            r"fn two\([^)]*\)",
+            &[dir.path().to_string_lossy().to_string()],
+            &options,
+        ) else {
+            return; // ripgrep not installed
+        };
+        assert_eq!(files[0].matches.len(), 1);
+        assert_eq!(files[0].matches[0].line, 2);
+        assert_eq!(files[0].matches[0].content, "fn two( x: u32, ) {}");
+    }
+
+    #[test]
+    fn test_take_match_args() {
+        let (opts, rest) = take_match_args(
+            MatchOptions::default(),
+            &strings(&["-i", "--glob", "*.rs", "-F", "-U", "--pcre2"]),
+        );
+        assert!(opts.ignore_case && opts.fixed && !opts.word);
+        assert!(opts.multiline && opts.pcre2);
+        assert_eq!(rest, strings(&["--glob", "*.rs"]));
+
+        let (opts, rest) = take_match_args(MatchOptions::default(), &strings(&["-s", "-S"]));
+        assert!(opts.case_sensitive && rest.is_empty());
+
+        let (opts, rest) = take_match_args(MatchOptions::default(), &strings(&["--hidden", "-u"]));
+        assert!(opts.hidden && opts.no_ignore && rest.is_empty());
+        let (opts, _) = take_match_args(MatchOptions::default(), &strings(&["-uu"]));
+        assert!(opts.hidden && opts.no_ignore);
+    }
+
+    #[test]
+    fn test_group_by_dir() {
+        let (a, b, c) = (
+            "src/parser/a.rs".to_string(),
+            "src/parser/b.rs".to_string(),
+            "main.rs".to_string(),
+        );
+        let two = vec![hit(1, ""), hit(2, "")];
+        let one = vec![hit(1, "")];
+        let files = vec![(&a, &two), (&b, &two), (&c, &one)];
+
+        let dirs = group_by_dir(&files);
+        assert_eq!(
+            dirs[0],
+            GrepDir {
+                path: "src/parser/".to_string(),
+                matches: 4,
+                files: 2
+            }
+        );
+        assert_eq!(dirs[1].path, "./");
+
+        let out = format_dirs("foo", 5, &files, 50);
+        assert!(out.contains("📁 src/parser/ 4 matches in 2 files\n"));
+        assert!(out.contains("Drill down: rtk grep 'foo' src/parser"));
+    }
+
+    fn scoped(line: usize, symbol: Option<&str>) -> LineMatch {
+        LineMatch {
+            symbol: symbol.map(String::from),
+            ..hit(line, "load()")
+        }
+    }
+
+    #[test]
+    fn test_format_text_scope() {
+        let a = "src/a.rs".to_string();
+        let matches = vec![
+            scoped(1, None),
+            scoped(4, Some("fn load")),
+            scoped(6, Some("fn load")),
+            scoped(9, None),
+        ];
+        let out = format_text(
+            4,
+            &[],
+            &[(&a, &matches)],
+            &[4],
+            &[],
+            &HashMap::new(),
+            &HashMap::new(),
+            ContextLines::default(),
+        );
+        assert!(
+            out.contains(
+                "📄 src/a.rs (4):\n     1: load()\n  in fn load:\n     4: load()\n     6: load()\n  top level:\n     9: load()\n"
+            ),
+            "{}",
+            out
+        );
+    }
+
+    #[test]
+    fn test_group_by_symbol() {
+        let (a, b) = ("src/a.rs".to_string(), "src/b.rs".to_string());
+        let a_matches = vec![
+            scoped(2, Some("fn load")),
+            scoped(3, Some("fn load")),
+            scoped(8, None),
+        ];
+        let b_matches = vec![scoped(5, Some("fn load"))];
+        let files = vec![(&a, &a_matches), (&b, &b_matches)];
+
+        let symbols = group_by_symbol(&files);
+        assert_eq!(
+            symbols[0],
+            GrepSymbol {
+                path: a.clone(),
+                symbol: Some("fn load".into()),
+                line: 2,
+                matches: 2,
+            }
+        );
+        // Same name in another file: its own entry
+        assert_eq!((symbols[1].path.as_str(), symbols[1].line), ("src/a.rs", 8));
+        assert_eq!((symbols[2].path.as_str(), symbols[2].line), ("src/b.rs", 5));
+
+        let out = format_symbols("load", 4, &files, 50);
+        assert!(out.starts_with("🔍 4 in 2F, 3 symbols:\n\n  fn load (src/a.rs:2) 2 matches\n"));
+        assert!(out.contains("  top level (src/a.rs:8) 1 matches\n"));
+        assert!(out.contains("Drill down: rtk grep --scope 'load' src/a.rs"));
+    }
+
+    #[test]
+    fn test_resolve_type() {
+        assert_eq!(
+            resolve_type("rust"),
+            ("rust".to_string(), strings(&["*.rs"]))
+        );
+        assert_eq!(resolve_type("rs").0, "rust");
+        assert_eq!(resolve_type("TypeScript").0, "ts");
+        assert!(resolve_type("ts").1.contains(&"*.tsx".to_string()));
+        // Unknown: rg name as-is, extension glob for grep
+        assert_eq!(
+            resolve_type("zig"),
+            ("zig".to_string(), strings(&["*.zig"]))
+        );
+    }
+
+    #[test]
+    fn test_user_globs() {
+        let globs = user_globs(&strings(&["-i", "-g", "!vendor/*.js", "--glob=*.ts"]));
+        assert_eq!(globs, strings(&["!vendor/*.js", "*.ts"]));
+    }
+
+    #[test]
+    fn test_list_files_respects_ignores() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for file in [
+            "src/a.js",
+            "src/b.ts",
+            "node_modules/lib/x.js",
+            ".cache/y.js",
+            "vendor/z.js",
+        ] {
+            let path = root.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "needle\n").unwrap();
+        }
+        std::fs::write(root.join(".ignore"), "node_modules/\n").unwrap();
+        let group = SearchGroup {
+            roots: vec![root.to_string_lossy().to_string()],
+            globs: Vec::new(),
+        };
+        let list = |type_globs: &[&str], globs: &[&str], opts: MatchOptions| {
+            let (files, errors) =
+                list_files(&group, &strings(type_globs), &strings(globs), opts).unwrap();
+            assert!(errors.is_empty());
+            let mut names: Vec<String> = files
+                .iter()
+                .map(|f| {
+                    f.strip_prefix(group.roots[0].as_str())
+                        .unwrap()
+                        .trim_start_matches('/')
+                        .to_string()
+                })
+                .collect();
+            names.sort();
+            names
+        };
+
+        let defaults = MatchOptions::default();
+        assert_eq!(
+            list(&[], &[], defaults),
+            ["src/a.js", "src/b.ts", "vendor/z.js"]
+        );
+        assert_eq!(list(&["*.js"], &["!vendor/**"], defaults), ["src/a.js"]);
+
+        let hidden = MatchOptions {
+            hidden: true,
+            ..defaults
+        };
+        assert_eq!(
+            list(&["*.js"], &[], hidden),
+            [".cache/y.js", "src/a.js", "vendor/z.js"]
+        );
+        let no_ignore = MatchOptions {
+            no_ignore: true,
+            ..defaults
+        };
+        assert_eq!(
+            list(&["*.js"], &[], no_ignore),
+            ["node_modules/lib/x.js", "src/a.js", "vendor/z.js"]
+        );
+    }
+
     // Fix: BRE \| alternation is translated to PCRE | for rg
     #[test]
     fn test_bre_alternation_translated() {
@@ -0,0 +1,345 @@
+//! `rtk grep --tui`: the compact match list in a full-screen terminal UI,
+//! for humans using rtk directly. The selected match is shown with its
+//! surrounding lines in a preview pane, and `y` copies its `file:line`.
+//!
+//! Keys: ↑/↓ or k/j move, PgUp/PgDn jump a page, g/G go to the first/last
+//! match, Enter expands the preview to the whole screen, y copies, q quits.
+
+use crate::grep_cmd::{self, SearchOptions};
+use crate::paths;
+use anyhow::{bail, Result};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+use regex::Regex;
+use std::io::{IsTerminal, Write};
+
+/// Share of the screen given to the match list
+const LIST_PERCENT: u16 = 40;
+
+/// One match, flattened out of its file
+#[derive(Debug, Clone, PartialEq)]
+struct Hit {
+    path: String,
+    line: usize,
+    content: String,
+}
+
+impl Hit {
+    /// `file:line`, as copied to the clipboard
+    fn location(&self) -> String {
+        format!("{}:{}", self.path, self.line)
+    }
+}
+
+struct App {
+    hits: Vec<Hit>,
+    list: ListState,
+    highlighter: Option<Regex>,
+    /// Preview on the whole screen
+    expanded: bool,
+    status: String,
+    /// File of the current preview and its lines, read once per file
+    preview: Option<(String, Vec<String>)>,
+}
+
+/// Search like `rtk grep` and browse the results interactively
+pub fn run(patterns: &[String], paths: &[String], options: &SearchOptions) -> Result<()> {
+    if !std::io::stdout().is_terminal() {
+        bail!("--tui needs a terminal; drop it to print the compact report");
+    }
+    let files = grep_cmd::search_all(patterns, paths, options)?;
+    let hits = flatten(files);
+    if hits.is_empty() {
+        println!("🔍 0 for {}", patterns.join(" | "));
+        return Ok(());
+    }
+
+    let alternatives: Vec<String> = patterns
+        .iter()
+        .map(|p| format!("(?:{})", grep_cmd::regex_source(p, options.matching)))
+        .collect();
+    let mut app = App {
+        hits,
+        list: ListState::default().with_selected(Some(0)),
+        highlighter: Regex::new(&alternatives.join("|")).ok(),
+        expanded: false,
+        status: String::new(),
+        preview: None,
+    };
+
+    let mut terminal = ratatui::init();
+    let result = app.run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+fn flatten(files: Vec<grep_cmd::FileMatches>) -> Vec<Hit> {
+    files
+        .into_iter()
+        .flat_map(|file| {
+            let path = file.path;
+            file.matches.into_iter().map(move |m| Hit {
+                path: path.clone(),
+                line: m.line,
+                content: m.content,
+            })
+        })
+        .collect()
+}
+
+impl App {
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            let page = terminal.size()?.height as usize * LIST_PERCENT as usize / 100;
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc if !self.expanded => return Ok(()),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    return Ok(())
+                }
+                KeyCode::Esc | KeyCode::Char('q') => self.expanded = false,
+                KeyCode::Enter => self.expanded = !self.expanded,
+                KeyCode::Down | KeyCode::Char('j') => self.step(1),
+                KeyCode::Up | KeyCode::Char('k') => self.step(-1),
+                KeyCode::PageDown => self.step(page.max(1) as isize),
+                KeyCode::PageUp => self.step(-(page.max(1) as isize)),
+                KeyCode::Home | KeyCode::Char('g') => self.list.select(Some(0)),
+                KeyCode::End | KeyCode::Char('G') => self.list.select(Some(self.hits.len() - 1)),
+                KeyCode::Char('y') => self.copy(),
+                _ => {}
+            }
+        }
+    }
+
+    fn selected(&self) -> &Hit {
+        &self.hits[self.list.selected().unwrap_or(0)]
+    }
+
+    fn step(&mut self, by: isize) {
+        let current = self.list.selected().unwrap_or(0) as isize;
+        let last = self.hits.len() as isize - 1;
+        self.list
+            .select(Some((current + by).clamp(0, last) as usize));
+        self.status.clear();
+    }
+
+    fn copy(&mut self) {
+        let location = self.selected().location();
+        let mut stdout = std::io::stdout();
+        self.status = match stdout
+            .write_all(osc52(&location).as_bytes())
+            .and_then(|_| stdout.flush())
+        {
+            Ok(()) => format!("Copied {}", location),
+            Err(e) => format!("Copy failed: {}", e),
+        };
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, status] =
+            Layout::vertical([Constraint::Min(3), Constraint::Length(1)]).areas(frame.area());
+        if self.expanded {
+            self.draw_preview(frame, main);
+        } else {
+            let [list, preview] = Layout::vertical([
+                Constraint::Percentage(LIST_PERCENT),
+                Constraint::Percentage(100 - LIST_PERCENT),
+            ])
+            .areas(main);
+            self.draw_list(frame, list);
+            self.draw_preview(frame, preview);
+        }
+
+        let help = "↑↓/jk move  Enter expand  y copy file:line  q quit";
+        let text = if self.status.is_empty() {
+            help.to_string()
+        } else {
+            format!("{}  |  {}", self.status, help)
+        };
+        frame.render_widget(
+            Paragraph::new(text).style(Style::new().add_modifier(Modifier::DIM)),
+            status,
+        );
+    }
+
+    fn draw_list(&mut self, frame: &mut Frame, area: Rect) {
+        let width = area.width.saturating_sub(2) as usize;
+        let items: Vec<ListItem> = self
+            .hits
+            .iter()
+            .map(|hit| ListItem::new(list_label(hit, width)))
+            .collect();
+        let title = format!(
+            " {}/{} matches ",
+            self.list.selected().map_or(0, |i| i + 1),
+            self.hits.len()
+        );
+        let list = List::new(items)
+            .block(Block::bordered().title(title))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, area, &mut self.list);
+    }
+
+    fn draw_preview(&mut self, frame: &mut Frame, area: Rect) {
+        let hit = self.selected().clone();
+        if self
+            .preview
+            .as_ref()
+            .is_none_or(|(path, _)| *path != hit.path)
+        {
+            // Archive members and vanished files: the matched line only
+            let lines = std::fs::read_to_string(&hit.path)
+                .map(|content| content.lines().map(String::from).collect())
+                .unwrap_or_default();
+            self.preview = Some((hit.path.clone(), lines));
+        }
+        let file_lines = match &self.preview {
+            Some((_, lines)) if lines.len() >= hit.line => lines.as_slice(),
+            _ => std::slice::from_ref(&hit.content),
+        };
+        let matched = if file_lines.len() == 1 { 1 } else { hit.line };
+
+        let height = area.height.saturating_sub(2) as usize;
+        let (start, end) = window(file_lines.len(), matched, height);
+        let gutter = end.to_string().len();
+        let lines: Vec<Line> = (start..end)
+            .map(|n| {
+                let number = Span::styled(
+                    format!("{:>width$} ", n, width = gutter),
+                    Style::new().fg(Color::DarkGray),
+                );
+                let text = &file_lines[n - 1];
+                let mut spans = vec![number];
+                if n == matched {
+                    spans.extend(self.highlight(text));
+                    Line::from(spans).style(Style::new().add_modifier(Modifier::BOLD))
+                } else {
+                    spans.push(Span::raw(text.clone()));
+                    Line::from(spans)
+                }
+            })
+            .collect();
+        let title = format!(" {} ", hit.location());
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::bordered().title(title)),
+            area,
+        );
+    }
+
+    /// `text` with the pattern's matches in yellow
+    fn highlight(&self, text: &str) -> Vec<Span<'static>> {
+        let Some(re) = &self.highlighter else {
+            return vec![Span::raw(text.to_string())];
+        };
+        let mut spans = Vec::new();
+        let mut last = 0;
+        for m in re.find_iter(text).filter(|m| !m.is_empty()) {
+            spans.push(Span::raw(text[last..m.start()].to_string()));
+            spans.push(Span::styled(
+                m.as_str().to_string(),
+                Style::new().fg(Color::Yellow),
+            ));
+            last = m.end();
+        }
+        spans.push(Span::raw(text[last..].to_string()));
+        spans
+    }
+}
+
+/// `path:line  content`, the path compacted as in the text report
+fn list_label(hit: &Hit, width: usize) -> String {
+    let path = paths::compact(&hit.path, width / 2);
+    let label = format!("{}:{}  {}", path, hit.line, hit.content.trim());
+    crate::utils::truncate(&label, width)
+}
+
+/// 1-based `start..end` of the lines shown in `height` rows, with `line`
+/// a third of the way down when the file is longer than the pane
+fn window(total: usize, line: usize, height: usize) -> (usize, usize) {
+    if total <= height {
+        return (1, total + 1);
+    }
+    let start = line.saturating_sub(height / 3).max(1);
+    let start = start.min(total + 1 - height);
+    (start, start + height)
+}
+
+/// OSC 52 escape setting the system clipboard to `text`: works in most
+/// terminals, including over SSH, without a clipboard library
+fn osc52(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", base64(text.as_bytes()))
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, b)| n | (*b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grep_cmd::{FileMatches, LineMatch};
+
+    #[test]
+    fn test_flatten_keeps_file_order() {
+        let line = |n: usize| LineMatch {
+            line: n,
+            column: None,
+            content: format!("line {}", n),
+            patterns: Vec::new(),
+            symbol: None,
+        };
+        let hits = flatten(vec![
+            FileMatches {
+                path: "a.rs".into(),
+                matches: vec![line(3), line(9)],
+            },
+            FileMatches {
+                path: "b.rs".into(),
+                matches: vec![line(1)],
+            },
+        ]);
+        let locations: Vec<String> = hits.iter().map(Hit::location).collect();
+        assert_eq!(locations, vec!["a.rs:3", "a.rs:9", "b.rs:1"]);
+    }
+
+    #[test]
+    fn test_window() {
+        assert_eq!(window(5, 3, 10), (1, 6));
+        assert_eq!(window(100, 50, 9), (47, 56));
+        assert_eq!(window(100, 2, 9), (1, 10));
+        assert_eq!(window(100, 99, 9), (92, 101));
+    }
+
+    #[test]
+    fn test_osc52() {
+        assert_eq!(base64(b"src/main.rs:42"), "c3JjL21haW4ucnM6NDI=");
+        assert_eq!(base64(b"ab"), "YWI=");
+        assert_eq!(base64(b"abc"), "YWJj");
+        assert_eq!(osc52("a"), "\x1b]52;c;YQ==\x07");
+    }
+}
@@ -0,0 +1,345 @@
+//! `rtk help <tool> [query]`: the synopsis of a tool and the options whose
+//! text matches the query, from `<tool> --help` or its man page. A full man
+//! page costs thousands of tokens when the question is usually one flag.
+
+use crate::layout;
+use crate::tracking;
+use crate::utils::{strip_ansi, truncate};
+use anyhow::{bail, Result};
+use std::process::Command;
+
+/// Options listed when no query narrows them down
+const MAX_OPTIONS: usize = 40;
+/// Description lines kept per option
+const DESCRIPTION_LINES: usize = 2;
+/// Synopsis lines kept
+const MAX_SYNOPSIS: usize = 6;
+
+/// One option entry: its flags line and the description lines under it
+#[derive(Debug, PartialEq)]
+struct OptionEntry {
+    lines: Vec<String>,
+}
+
+impl OptionEntry {
+    fn matches(&self, query: &[String]) -> bool {
+        let text = self.lines.join(" ").to_lowercase();
+        query.iter().all(|word| text.contains(&word.to_lowercase()))
+    }
+
+    /// Flags line and the start of the description on one line
+    fn summary(&self) -> String {
+        let mut parts: Vec<String> = self
+            .lines
+            .iter()
+            .take(1 + DESCRIPTION_LINES)
+            .map(|l| collapse_spaces(l.trim()))
+            .collect();
+        parts.retain(|p| !p.is_empty());
+        parts.join("  ")
+    }
+}
+
+/// Show the synopsis of `tool` and its options matching `query`
+pub fn run(tool: &str, query: &[String], man: bool, verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    let (source, raw) = match fetch(tool, man) {
+        Some(found) => found,
+        None => bail!("No --help output or man page found for {}", tool),
+    };
+    if verbose > 0 {
+        eprintln!("Read {} ({} lines)", source, raw.lines().count());
+    }
+
+    let text = clean(&raw);
+    let filtered = format_help(tool, &text, query);
+    println!("{}", filtered);
+
+    let rtk_cmd = format!("rtk help {} {}", tool, query.join(" "));
+    timer.track(&source, rtk_cmd.trim(), &raw, &filtered);
+    Ok(())
+}
+
+/// `<tool> --help`, else `man <tool>` (the man page first with `man`)
+fn fetch(tool: &str, man: bool) -> Option<(String, String)> {
+    let help = || {
+        let output = Command::new(tool).arg("--help").output().ok()?;
+        // Some tools print their help on stderr, or exit non-zero after it
+        let text = format!(
+            "{}{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        looks_like_help(&text).then(|| (format!("{} --help", tool), text))
+    };
+    let man_page = || {
+        let output = Command::new("man")
+            .arg(tool)
+            .env("MANPAGER", "cat")
+            .env("MANWIDTH", "100")
+            .output()
+            .ok()?;
+        let text = String::from_utf8_lossy(&output.stdout).to_string();
+        (output.status.success() && !text.trim().is_empty())
+            .then(|| (format!("man {}", tool), text))
+    };
+    if man {
+        man_page().or_else(help)
+    } else {
+        help().or_else(man_page)
+    }
+}
+
+/// Help text lists options or a usage line; an error like "unknown option"
+/// does neither
+fn looks_like_help(text: &str) -> bool {
+    text.lines().any(|l| {
+        let t = l.trim_start().to_lowercase();
+        t.starts_with("usage") || t.starts_with('-')
+    })
+}
+
+/// Without escape codes and man's overstrike bold/underline (`X\bX`, `_\bX`)
+fn clean(text: &str) -> String {
+    let text = strip_ansi(text);
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c == '\u{8}' {
+            out.pop();
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+fn collapse_spaces(line: &str) -> String {
+    let mut out = String::new();
+    let mut spaces = 0;
+    for c in line.chars() {
+        if c == ' ' {
+            spaces += 1;
+            continue;
+        }
+        if spaces > 0 {
+            out.push_str(if spaces > 1 { "  " } else { " " });
+            spaces = 0;
+        }
+        out.push(c);
+    }
+    out
+}
+
+fn indent(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// A man section header (`SYNOPSIS`, `OPTIONS`): unindented capitals
+fn is_section_header(line: &str) -> bool {
+    !line.is_empty()
+        && indent(line) == 0
+        && line.chars().any(|c| c.is_alphabetic())
+        && !line.chars().any(|c| c.is_lowercase())
+}
+
+/// Usage lines of `--help` output, or the SYNOPSIS section of a man page
+fn synopsis(text: &str) -> Vec<String> {
+    let lines: Vec<&str> = text.lines().collect();
+    if let Some(start) = lines.iter().position(|l| l.trim() == "SYNOPSIS") {
+        return lines[start + 1..]
+            .iter()
+            .take_while(|l| !is_section_header(l))
+            .filter(|l| !l.trim().is_empty())
+            .map(|l| collapse_spaces(l.trim()))
+            .take(MAX_SYNOPSIS)
+            .collect();
+    }
+
+    let mut out = Vec::new();
+    let mut in_usage = false;
+    for line in &lines {
+        let lower = line.trim_start().to_lowercase();
+        if lower.starts_with("usage") {
+            in_usage = true;
+            out.push(collapse_spaces(line.trim()));
+        } else if in_usage && !line.trim().is_empty() && indent(line) > 0 && !lower.starts_with('-')
+        {
+            // Alternative forms on the following lines
+            out.push(collapse_spaces(line.trim()));
+        } else {
+            in_usage = false;
+        }
+    }
+    out.truncate(MAX_SYNOPSIS);
+    out
+}
+
+/// Option entries: a line starting with `-` and the more indented lines
+/// that follow it
+fn options(text: &str) -> Vec<OptionEntry> {
+    let mut entries: Vec<OptionEntry> = Vec::new();
+    let mut current: Option<(usize, OptionEntry)> = None;
+
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        let is_option = trimmed.starts_with('-')
+            && trimmed[1..]
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_alphanumeric() || c == '-');
+        if is_option {
+            entries.extend(current.take().map(|(_, e)| e));
+            current = Some((
+                indent(line),
+                OptionEntry {
+                    lines: vec![line.to_string()],
+                },
+            ));
+        } else if trimmed.is_empty() {
+            // Paragraph breaks inside a description keep the entry open
+            continue;
+        } else if let Some((entry_indent, entry)) = &mut current {
+            if indent(line) > *entry_indent {
+                entry.lines.push(line.to_string());
+            } else {
+                entries.extend(current.take().map(|(_, e)| e));
+            }
+        }
+    }
+    entries.extend(current.map(|(_, e)| e));
+    entries
+}
+
+fn format_help(tool: &str, text: &str, query: &[String]) -> String {
+    let synopsis = synopsis(text);
+    let options = options(text);
+    let width = layout::line_width(160, 2);
+
+    let mut out = String::new();
+    if !synopsis.is_empty() {
+        out.push_str(&format!("{} synopsis:\n", tool));
+        for line in &synopsis {
+            out.push_str(&format!("  {}\n", truncate(line, width)));
+        }
+    }
+
+    let matching: Vec<&OptionEntry> = options.iter().filter(|o| o.matches(query)).collect();
+    if query.is_empty() {
+        out.push_str(&format!("Options ({}):\n", options.len()));
+    } else {
+        out.push_str(&format!(
+            "Options matching \"{}\" ({} of {}):\n",
+            query.join(" "),
+            matching.len(),
+            options.len()
+        ));
+    }
+    let limit = if query.is_empty() {
+        MAX_OPTIONS
+    } else {
+        usize::MAX
+    };
+    for option in matching.iter().take(limit) {
+        out.push_str(&format!("  {}\n", truncate(&option.summary(), width)));
+    }
+    if matching.len() > limit {
+        out.push_str(&format!(
+            "  ... +{} more (add a query: rtk help {} <word>)\n",
+            matching.len() - limit,
+            tool
+        ));
+    }
+    if matching.is_empty() && !query.is_empty() {
+        out.push_str("  (none)\n");
+    }
+    out.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HELP: &str = "\
+Usage: tar [OPTION...] [FILE]...
+GNU 'tar' saves many files together into a single tape or disk archive.
+
+ Main operation mode:
+
+  -c, --create               create a new archive
+  -t, --list                 list the contents of an archive
+  -x, --extract, --get       extract files from an archive
+
+ Compression options:
+
+  -z, --gzip, --gunzip, --ungzip   filter the archive through gzip
+      --to-stdout            extract files to standard output,
+                             useful with pipes
+";
+
+    const MAN: &str = "\
+TAR(1)                      General Commands Manual                     TAR(1)
+
+N\u{8}NA\u{8}AM\u{8}ME\u{8}E
+       tar - an archiving utility
+
+S\u{8}SY\u{8}YN\u{8}NO\u{8}OP\u{8}PS\u{8}SI\u{8}IS\u{8}S
+       tar [-] A --catenate --concatenate | c --create [options]
+
+       tar -x [options] [pathname ...]
+
+OPTIONS
+       -x, --extract, --get
+              Extract files from an archive.
+
+              Arguments are optional.
+
+       -v, --verbose
+              Verbosely list files processed.
+";
+
+    fn words(query: &str) -> Vec<String> {
+        query.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_help_query_filters_options() {
+        let out = format_help("tar", HELP, &words("extract"));
+        assert!(out.starts_with("tar synopsis:\n  Usage: tar [OPTION...] [FILE]..."));
+        assert!(out.contains("Options matching \"extract\" (2 of 5):"));
+        assert!(out.contains("-x, --extract, --get  extract files from an archive"));
+        assert!(out.contains("--to-stdout  extract files to standard output,  useful with pipes"));
+        assert!(!out.contains("--create"));
+    }
+
+    #[test]
+    fn test_man_page_synopsis_and_options() {
+        let text = clean(MAN);
+        assert_eq!(
+            synopsis(&text),
+            vec![
+                "tar [-] A --catenate --concatenate | c --create [options]",
+                "tar -x [options] [pathname ...]"
+            ]
+        );
+        let options = options(&text);
+        assert_eq!(options.len(), 2);
+        assert!(options[0].matches(&words("optional EXTRACT")));
+        assert!(!options[1].matches(&words("extract")));
+    }
+
+    #[test]
+    fn test_no_query_lists_all_and_no_match_says_so() {
+        let out = format_help("tar", HELP, &[]);
+        assert!(out.contains("Options (5):"));
+        let out = format_help("tar", HELP, &words("bzip3"));
+        assert!(out.ends_with("(0 of 5):\n  (none)"));
+    }
+
+    #[test]
+    fn test_clean_overstrike() {
+        assert_eq!(clean("B\u{8}Bo\u{8}ol\u{8}ld\u{8}d _\u{8}u"), "Bold u");
+        assert!(!looks_like_help("tar: unknown option -- '-help'"));
+        assert!(looks_like_help("usage: tar [-x]"));
+    }
+}
@@ -0,0 +1,229 @@
+//! `rtk history`: the tracked invocations as a searchable command history,
+//! with when each ran and what it saved, and `--rerun N` to run one again.
+//!
+//! Entries are numbered in the whole history (1 = most recent), so a number
+//! seen in a filtered list can be rerun without repeating the filter.
+
+use crate::tracking::{HistoryEntry, Tracker};
+use crate::utils::{self, format_tokens, truncate};
+use anyhow::{bail, Context, Result};
+use std::process::Command;
+
+/// Width of the command column
+const COMMAND_WIDTH: usize = 48;
+
+/// Which entries to list
+#[derive(Debug, Default)]
+pub struct HistoryFilter {
+    /// Text in the rtk or the original command line, case-insensitive
+    pub query: Option<String>,
+    /// Subcommand words, e.g. `git` or `git log`
+    pub command: Option<String>,
+}
+
+impl HistoryFilter {
+    fn matches(&self, entry: &HistoryEntry) -> bool {
+        if let Some(command) = &self.command {
+            let wanted: Vec<&str> = command
+                .trim_start_matches("rtk ")
+                .split_whitespace()
+                .collect();
+            let words: Vec<&str> = entry.rtk_cmd.split_whitespace().skip(1).collect();
+            if !words.starts_with(&wanted) {
+                return false;
+            }
+        }
+        match &self.query {
+            Some(query) => {
+                let query = query.to_lowercase();
+                entry.rtk_cmd.to_lowercase().contains(&query)
+                    || entry.original_cmd.to_lowercase().contains(&query)
+            }
+            None => true,
+        }
+    }
+}
+
+/// List the latest `limit` entries matching `filter`, or run entry `rerun`
+/// again
+pub fn run(filter: &HistoryFilter, limit: usize, rerun: Option<usize>, verbose: u8) -> Result<()> {
+    let tracker = Tracker::new().context("Failed to initialize tracking database")?;
+    let history = tracker.get_history()?;
+
+    let Some(n) = rerun else {
+        let numbered: Vec<(usize, &HistoryEntry)> = history
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| (i + 1, entry))
+            .filter(|(_, entry)| filter.matches(entry))
+            .collect();
+        println!("{}", format_list(&numbered, history.len(), limit));
+        return Ok(());
+    };
+
+    let entry = n
+        .checked_sub(1)
+        .and_then(|i| history.get(i))
+        .with_context(|| {
+            format!(
+                "No history entry #{} ({} recorded, see `rtk history`)",
+                n,
+                history.len()
+            )
+        })?;
+    let args = rerun_args(entry)?;
+    if verbose > 0 {
+        eprintln!("Rerunning #{}: {}", n, entry.rtk_cmd);
+    }
+    let exe = std::env::current_exe().context("Cannot locate the rtk binary")?;
+    let status = Command::new(exe)
+        .args(args)
+        .status()
+        .with_context(|| format!("Failed to rerun {}", entry.rtk_cmd))?;
+    utils::exit_with("rtk", status.code().unwrap_or(1), verbose);
+    Ok(())
+}
+
+/// The arguments `entry` ran with. `rtk_cmd` is only a label (`rtk grep`
+/// for any search), so entries without a stored argv can't be rerun.
+fn rerun_args(entry: &HistoryEntry) -> Result<&[String]> {
+    match &entry.argv {
+        Some(args) if !args.is_empty() => Ok(args),
+        _ => bail!(
+            "'{}' can't be rerun: its arguments were not kept (redacted by \
+             tracking.redact, or recorded by an older rtk)",
+            entry.rtk_cmd
+        ),
+    }
+}
+
+fn format_list(numbered: &[(usize, &HistoryEntry)], recorded: usize, limit: usize) -> String {
+    if numbered.is_empty() {
+        return if recorded == 0 {
+            "No tracked commands yet".to_string()
+        } else {
+            format!("No matching commands among {} recorded", recorded)
+        };
+    }
+
+    let mut out = if numbered.len() == recorded {
+        format!("📜 {} commands (rtk history --rerun N):\n", recorded)
+    } else {
+        format!(
+            "📜 {} of {} commands (rtk history --rerun N):\n",
+            numbered.len(),
+            recorded
+        )
+    };
+    for (n, entry) in numbered.iter().take(limit) {
+        let saved = if entry.saved_tokens == 0 {
+            String::new()
+        } else {
+            format!(
+                "{} saved ({:.0}%)",
+                format_tokens(entry.saved_tokens),
+                entry.savings_pct
+            )
+        };
+        out.push_str(
+            format!(
+                "{:>5}  {}  {:<width$} {}",
+                format!("#{}", n),
+                entry.timestamp.format("%m-%d %H:%M"),
+                truncate(&entry.rtk_cmd, COMMAND_WIDTH),
+                saved,
+                width = COMMAND_WIDTH
+            )
+            .trim_end(),
+        );
+        out.push('\n');
+    }
+    if numbered.len() > limit {
+        out.push_str(&format!(
+            "... +{} older (--limit)\n",
+            numbered.len() - limit
+        ));
+    }
+    out.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn entry(rtk_cmd: &str, original_cmd: &str, saved_tokens: usize) -> HistoryEntry {
+        HistoryEntry {
+            timestamp: Utc.with_ymd_and_hms(2026, 3, 4, 10, 30, 0).unwrap(),
+            original_cmd: original_cmd.into(),
+            rtk_cmd: rtk_cmd.into(),
+            saved_tokens,
+            savings_pct: 80.0,
+            argv: None,
+        }
+    }
+
+    #[test]
+    fn test_filter() {
+        let log = entry("rtk git log -n 5", "git log -n 5", 100);
+        let grep = entry("rtk grep", "grep -rn 'parse_config' src", 100);
+        let by_command = |command: &str| HistoryFilter {
+            command: Some(command.into()),
+            ..Default::default()
+        };
+        assert!(by_command("git").matches(&log));
+        assert!(by_command("git log").matches(&log));
+        assert!(by_command("rtk git log").matches(&log));
+        assert!(!by_command("git status").matches(&log));
+        assert!(!by_command("gi").matches(&log));
+
+        // The original command line keeps the arguments rtk_cmd may lack
+        let by_text = HistoryFilter {
+            query: Some("PARSE_CONFIG".into()),
+            ..Default::default()
+        };
+        assert!(by_text.matches(&grep));
+        assert!(!by_text.matches(&log));
+    }
+
+    #[test]
+    fn test_format_list() {
+        let log = entry("rtk git log -n 5", "git log -n 5", 1_500);
+        let ls = entry("rtk ls", "ls -la", 0);
+        let grep = entry("rtk grep", "grep -rn foo .", 20);
+        let out = format_list(&[(1, &log), (3, &ls), (12, &grep)], 12, 2);
+        assert_eq!(
+            out,
+            format!(
+                "📜 3 of 12 commands (rtk history --rerun N):\n   \
+                 #1  03-04 10:30  {:<48} 1.5K saved (80%)\n   \
+                 #3  03-04 10:30  rtk ls\n\
+                 ... +1 older (--limit)",
+                "rtk git log -n 5"
+            )
+        );
+        assert_eq!(format_list(&[], 0, 20), "No tracked commands yet");
+        assert_eq!(
+            format_list(&[], 4, 20),
+            "No matching commands among 4 recorded"
+        );
+    }
+
+    #[test]
+    fn test_rerun_args() {
+        // The stored argv, not the display string: a quoted pattern stays one argument
+        let grep = HistoryEntry {
+            argv: Some(vec!["grep".into(), "fn main".into(), "src".into()]),
+            ..entry("rtk grep", "grep -rn 'fn main' src", 10)
+        };
+        assert_eq!(rerun_args(&grep).unwrap(), ["grep", "fn main", "src"]);
+
+        assert!(rerun_args(&entry("rtk grep", "grep -rn foo .", 10)).is_err());
+        assert!(rerun_args(&entry("rtk git diff (passthrough)", "git diff", 0)).is_err());
+        let empty = HistoryEntry {
+            argv: Some(Vec::new()),
+            ..entry("rtk", "rtk", 0)
+        };
+        assert!(rerun_args(&empty).is_err());
+    }
+}
@@ -0,0 +1,322 @@
+//! HTTP requests summarized for the context window: status line, timing,
+//! a few useful headers and the body's schema instead of its payload.
+//!
+//! Requests go through a blocking reqwest client. Like `curl -i`, redirects
+//! are not followed so the `location` header shows up in the summary, and
+//! `HTTP(S)_PROXY` is honored.
+
+use crate::curl_cmd;
+use crate::json_cmd;
+use crate::tracking;
+use crate::utils::format_bytes;
+use anyhow::{Context, Result};
+use reqwest::blocking::Client;
+use reqwest::redirect::Policy;
+use std::time::{Duration, Instant};
+
+/// Give up on the request after this many seconds
+const TIMEOUT_SECS: u64 = 30;
+
+const METHODS: [&str; 7] = ["GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS"];
+
+/// Response headers worth showing; everything else is counted
+const SELECTED_HEADERS: [&str; 9] = [
+    "content-type",
+    "content-length",
+    "location",
+    "cache-control",
+    "etag",
+    "retry-after",
+    "www-authenticate",
+    "x-request-id",
+    "x-ratelimit-remaining",
+];
+
+#[derive(Debug, PartialEq)]
+struct Response {
+    version: String,
+    status: u16,
+    reason: String,
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+pub fn run(
+    method: &str,
+    url: &str,
+    data: Option<&str>,
+    headers: &[String],
+    max_depth: usize,
+    verbose: u8,
+) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    let method = method.to_uppercase();
+    if !METHODS.contains(&method.as_str()) {
+        anyhow::bail!(
+            "Unsupported method '{}' (expected one of {})",
+            method,
+            METHODS.join(", ")
+        );
+    }
+
+    let client = client(false)?;
+    let mut request = client.request(method.parse()?, url);
+    for header in headers {
+        let (name, value) = parse_header(header)?;
+        request = request.header(name, value);
+    }
+    if let Some(data) = data {
+        let body = match data.strip_prefix('@') {
+            Some(path) => std::fs::read(path)
+                .with_context(|| format!("Failed to read request body from {}", path))?,
+            None => data.as_bytes().to_vec(),
+        };
+        let has_content_type = headers
+            .iter()
+            .any(|h| h.to_lowercase().starts_with("content-type:"));
+        if !has_content_type && looks_like_json(&String::from_utf8_lossy(&body)) {
+            request = request.header("Content-Type", "application/json");
+        }
+        request = request.body(body);
+    }
+
+    if verbose > 0 {
+        eprintln!("Sending: {} {}", method, url);
+    }
+
+    let started = Instant::now();
+    let response = request
+        .send()
+        .and_then(read_response)
+        .map_err(|err| request_error(&method, url, &err))?;
+    let elapsed_ms = started.elapsed().as_millis();
+
+    let raw = format_raw(&response);
+    let summary = format_response(&method, url, &response, elapsed_ms, max_depth);
+    println!("{}", summary);
+
+    timer.track(
+        &format!("curl -i -X {} {}", method, url),
+        &format!("rtk http {} {}", method, url),
+        &raw,
+        &summary,
+    );
+    Ok(())
+}
+
+/// Blocking client giving up after TIMEOUT_SECS; redirects are followed
+/// only when `follow_redirects`, otherwise they are the response
+pub fn client(follow_redirects: bool) -> Result<Client> {
+    let redirect = if follow_redirects {
+        Policy::default()
+    } else {
+        Policy::none()
+    };
+    Client::builder()
+        .timeout(Duration::from_secs(TIMEOUT_SECS))
+        .redirect(redirect)
+        .build()
+        .context("Failed to build HTTP client")
+}
+
+/// `Name: value` from a `-H` argument
+fn parse_header(header: &str) -> Result<(&str, &str)> {
+    match header.split_once(':') {
+        Some((name, value)) if !name.trim().is_empty() => Ok((name.trim(), value.trim())),
+        _ => anyhow::bail!("Invalid header '{}' (expected 'Name: value')", header),
+    }
+}
+
+fn read_response(response: reqwest::blocking::Response) -> reqwest::Result<Response> {
+    let version = format!("{:?}", response.version());
+    let status = response.status();
+    let headers = response
+        .headers()
+        .iter()
+        .map(|(name, value)| {
+            let value = String::from_utf8_lossy(value.as_bytes()).to_string();
+            (name.as_str().to_string(), value)
+        })
+        .collect();
+    let body = response.bytes()?;
+    Ok(Response {
+        version,
+        status: status.as_u16(),
+        reason: status.canonical_reason().unwrap_or("").to_string(),
+        headers,
+        body: String::from_utf8_lossy(&body).to_string(),
+    })
+}
+
+fn request_error(method: &str, url: &str, err: &reqwest::Error) -> anyhow::Error {
+    anyhow::anyhow!("{} {} {}", method, url, error_reason(err))
+}
+
+/// Why a request failed, without reqwest's nested error wrapping
+pub fn error_reason(err: &reqwest::Error) -> String {
+    if err.is_timeout() {
+        format!("timed out after {}s", TIMEOUT_SECS)
+    } else {
+        let mut cause: &dyn std::error::Error = err;
+        while let Some(source) = cause.source() {
+            cause = source;
+        }
+        let kind = if err.is_connect() {
+            "connection failed"
+        } else if err.is_builder() {
+            "invalid request"
+        } else if err.is_body() || err.is_decode() {
+            "failed to read the response"
+        } else {
+            "request failed"
+        };
+        format!("{}: {}", kind, cause)
+    }
+}
+
+/// The response as `curl -i` would print it
+fn format_raw(response: &Response) -> String {
+    let mut raw = format!(
+        "{} {} {}\r\n",
+        response.version, response.status, response.reason
+    );
+    for (name, value) in &response.headers {
+        raw.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    raw.push_str("\r\n");
+    raw.push_str(&response.body);
+    raw
+}
+
+fn looks_like_json(data: &str) -> bool {
+    let data = data.trim_start();
+    data.starts_with('{') || data.starts_with('[')
+}
+
+fn format_response(
+    method: &str,
+    url: &str,
+    response: &Response,
+    elapsed_ms: u128,
+    max_depth: usize,
+) -> String {
+    let icon = if response.status < 400 { "✓" } else { "✗" };
+    let mut out = format!(
+        "{} {} {} → {} ({}ms, {})\n",
+        icon,
+        method,
+        url,
+        format!("{} {}", response.status, response.reason).trim_end(),
+        elapsed_ms,
+        format_bytes(response.body.len() as u64)
+    );
+
+    let mut hidden = 0;
+    for (name, value) in &response.headers {
+        if SELECTED_HEADERS.contains(&name.as_str()) {
+            out.push_str(&format!("  {}: {}\n", name, value));
+        } else {
+            hidden += 1;
+        }
+    }
+    if hidden > 0 {
+        out.push_str(&format!("  (+{} headers, {})\n", hidden, response.version));
+    }
+
+    let body = response.body.trim();
+    if !body.is_empty() {
+        out.push('\n');
+        match json_cmd::filter_json_string(body, max_depth) {
+            Ok(schema) => out.push_str(&schema),
+            Err(_) => out.push_str(&curl_cmd::filter_curl_output(body)),
+        }
+    }
+    out.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn created() -> Response {
+        Response {
+            version: "HTTP/1.1".into(),
+            status: 201,
+            reason: "Created".into(),
+            headers: vec![
+                ("content-type".into(), "application/json".into()),
+                ("location".into(), "/users/42".into()),
+                ("date".into(), "Mon, 01 Jan 2024 00:00:00 GMT".into()),
+                ("server".into(), "nginx".into()),
+            ],
+            body: "{\"id\": 42, \"name\": \"Ada\", \"roles\": [\"admin\"]}".into(),
+        }
+    }
+
+    #[test]
+    fn test_format_raw_like_curl() {
+        let raw = format_raw(&created());
+        assert!(raw.starts_with("HTTP/1.1 201 Created\r\ncontent-type: application/json\r\n"));
+        assert!(raw.ends_with(
+            "server: nginx\r\n\r\n{\"id\": 42, \"name\": \"Ada\", \"roles\": [\"admin\"]}"
+        ));
+    }
+
+    #[test]
+    fn test_format_response_json_schema() {
+        let out = format_response("POST", "https://api.test/users", &created(), 142, 5);
+        let mut lines = out.lines();
+        assert_eq!(
+            lines.next().unwrap(),
+            "✓ POST https://api.test/users → 201 Created (142ms, 45B)"
+        );
+        assert_eq!(lines.next().unwrap(), "  content-type: application/json");
+        assert_eq!(lines.next().unwrap(), "  location: /users/42");
+        assert_eq!(lines.next().unwrap(), "  (+2 headers, HTTP/1.1)");
+        assert!(out.contains("int"), "{}", out);
+        assert!(!out.contains("Ada"));
+    }
+
+    #[test]
+    fn test_format_response_error_text_body() {
+        let response = Response {
+            version: "HTTP/2.0".into(),
+            status: 404,
+            reason: String::new(),
+            headers: vec![("content-type".into(), "text/plain".into())],
+            body: "not found\n".into(),
+        };
+        let out = format_response("GET", "https://api.test/x", &response, 9, 5);
+        assert!(out.starts_with("✗ GET https://api.test/x → 404 (9ms, 10B)"));
+        assert!(out.ends_with("\n\nnot found"));
+    }
+
+    #[test]
+    fn test_parse_header() {
+        assert_eq!(
+            parse_header("Authorization:  Bearer x").unwrap(),
+            ("Authorization", "Bearer x")
+        );
+        assert!(parse_header("no colon").is_err());
+        assert!(parse_header(": empty name").is_err());
+    }
+
+    #[test]
+    fn test_request_error_names_the_failure() {
+        // Nothing listens on port 1: the connection is refused locally
+        let err = Client::new().get("http://127.0.0.1:1/").send().unwrap_err();
+        let message = request_error("GET", "http://127.0.0.1:1/", &err).to_string();
+        assert!(
+            message.starts_with("GET http://127.0.0.1:1/ connection failed: "),
+            "{}",
+            message
+        );
+    }
+
+    #[test]
+    fn test_looks_like_json() {
+        assert!(looks_like_json(" {\"a\": 1}"));
+        assert!(!looks_like_json("a=1&b=2"));
+    }
+}
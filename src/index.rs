@@ -0,0 +1,855 @@
+//! Trigram index for `rtk grep`. `rtk index build` records the three-byte
+//! sequences of every file rg would search under a root. A search then only
+//! reads the files holding the trigrams its patterns require, instead of
+//! the whole tree: sub-second on large monorepos.
+//!
+//! A search uses the index when it covers the searched paths and is fresh.
+//! Files changed since the build (mtime or size) and files added to changed
+//! directories are searched as well. Past [`MAX_STALE_PERCENT`] of them, or
+//! with options the index doesn't model (--hidden, --no-ignore, -P, -U,
+//! --search-zip, other rg flags), the search runs without it.
+//!
+//! Indexes live in `~/.local/share/rtk/index/` (or `RTK_INDEX_DIR`), one
+//! SQLite file per root. `RTK_NO_INDEX=1` ignores them.
+
+use crate::utils::format_bytes;
+use crate::walk;
+use anyhow::{bail, Context, Result};
+use chrono::{Local, TimeZone};
+use ignore::WalkBuilder;
+use regex_syntax::hir::{Class, Hir, HirKind};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::hash::Hasher;
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// Bumped when the layout changes: older indexes are ignored
+const SCHEMA_VERSION: &str = "1";
+
+/// Larger files are not indexed: searched every time
+const MAX_FILE_SIZE: u64 = 8 * 1024 * 1024;
+
+/// Files changed since the build, in percent of the indexed ones, past
+/// which the index is stale and searches run without it
+pub const MAX_STALE_PERCENT: usize = 10;
+
+/// Exact strings tracked for a part of a pattern before only its trigrams
+/// are kept
+const MAX_STRINGS: usize = 64;
+
+/// Largest character class expanded into alternatives
+const MAX_CLASS: u32 = 8;
+
+/// `RTK_NO_INDEX` turns the index off
+pub fn is_enabled() -> bool {
+    !matches!(std::env::var("RTK_NO_INDEX").ok().as_deref(), Some(v) if !v.is_empty() && v != "0")
+}
+
+fn index_dir() -> Option<PathBuf> {
+    if let Ok(dir) = std::env::var("RTK_INDEX_DIR") {
+        return Some(PathBuf::from(dir));
+    }
+    dirs::data_local_dir().map(|d| d.join("rtk").join("index"))
+}
+
+/// Index file of the canonical `root`
+fn index_path(root: &Path) -> Option<PathBuf> {
+    let mut hasher = DefaultHasher::new();
+    hasher.write(root.to_string_lossy().as_bytes());
+    Some(index_dir()?.join(format!("{:016x}.db", hasher.finish())))
+}
+
+/// Walker with rg's default rules: ignore files (.gitignore, .ignore,
+/// .rgignore), no hidden files
+fn walker(path: &Path) -> WalkBuilder {
+    let mut walker = WalkBuilder::new(path);
+    walker.add_custom_ignore_filename(".rgignore");
+    walker
+}
+
+fn mtime(meta: &fs::Metadata) -> i64 {
+    meta.modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_nanos() as i64)
+}
+
+/// `root`-relative path with forward slashes
+fn relative(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+fn trigram(bytes: &[u8]) -> u32 {
+    (bytes[0].to_ascii_lowercase() as u32) << 16
+        | (bytes[1].to_ascii_lowercase() as u32) << 8
+        | bytes[2].to_ascii_lowercase() as u32
+}
+
+/// Distinct trigrams of `bytes`, ASCII lowercased so one index serves
+/// case-insensitive searches too. Searches are line-based: trigrams
+/// across a newline can't be required.
+fn trigrams(bytes: &[u8]) -> Vec<u32> {
+    let mut trigrams: Vec<u32> = bytes
+        .windows(3)
+        .filter(|w| !w.contains(&b'\n'))
+        .map(trigram)
+        .collect();
+    trigrams.sort_unstable();
+    trigrams.dedup();
+    trigrams
+}
+
+/// Sorted file ids as varint deltas
+fn encode(ids: &[u32]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(ids.len());
+    let mut previous = 0;
+    for &id in ids {
+        let mut delta = id - previous;
+        previous = id;
+        while delta >= 0x80 {
+            out.push((delta as u8) | 0x80);
+            delta >>= 7;
+        }
+        out.push(delta as u8);
+    }
+    out
+}
+
+fn decode(bytes: &[u8]) -> Vec<u32> {
+    let mut ids = Vec::new();
+    let (mut previous, mut delta, mut shift) = (0u32, 0u32, 0);
+    for &byte in bytes {
+        delta |= ((byte & 0x7f) as u32) << shift;
+        if byte & 0x80 == 0 {
+            previous += delta;
+            ids.push(previous);
+            delta = 0;
+            shift = 0;
+        } else {
+            shift += 7;
+        }
+    }
+    ids
+}
+
+/// The trigrams a file must contain for a pattern to match one of its lines
+#[derive(Debug, Clone, PartialEq)]
+pub enum Query {
+    /// Any file may match
+    All,
+    /// Every one of these
+    Trigrams(Vec<u32>),
+    And(Vec<Query>),
+    Or(Vec<Query>),
+}
+
+fn and(queries: Vec<Query>) -> Query {
+    let mut trigrams = Vec::new();
+    let mut rest = Vec::new();
+    let mut pending = queries;
+    while let Some(query) = pending.pop() {
+        match query {
+            Query::All => {}
+            Query::Trigrams(t) => trigrams.extend(t),
+            Query::And(inner) => pending.extend(inner),
+            or => rest.push(or),
+        }
+    }
+    trigrams.sort_unstable();
+    trigrams.dedup();
+    if !trigrams.is_empty() {
+        rest.insert(0, Query::Trigrams(trigrams));
+    }
+    match rest.len() {
+        0 => Query::All,
+        1 => rest.pop().expect("one query"),
+        _ => Query::And(rest),
+    }
+}
+
+fn or(queries: Vec<Query>) -> Query {
+    let mut alternatives = Vec::new();
+    for query in queries {
+        match query {
+            Query::All => return Query::All,
+            Query::Or(inner) => alternatives.extend(inner),
+            query => alternatives.push(query),
+        }
+    }
+    alternatives.dedup();
+    match alternatives.len() {
+        0 => Query::All,
+        1 => alternatives.pop().expect("one query"),
+        _ => Query::Or(alternatives),
+    }
+}
+
+/// What the index can require of files matching any of `sources` (regex
+/// crate syntax). A pattern that doesn't parse requires nothing.
+pub fn query(sources: &[String]) -> Query {
+    or(sources
+        .iter()
+        .map(|source| match regex_syntax::Parser::new().parse(source) {
+            Ok(hir) => analyze(&hir).into_query(),
+            Err(_) => Query::All,
+        })
+        .collect())
+}
+
+/// A part of a pattern: the exact (lowercased) strings it matches when
+/// there are few, else what it requires
+enum Info {
+    Exact(Vec<Vec<u8>>),
+    Inexact(Query),
+}
+
+impl Info {
+    fn into_query(self) -> Query {
+        match self {
+            Info::Exact(strings) => strings_query(&strings),
+            Info::Inexact(query) => query,
+        }
+    }
+}
+
+/// One of `strings` is in the file: all the trigrams of one of them
+fn strings_query(strings: &[Vec<u8>]) -> Query {
+    or(strings
+        .iter()
+        .map(|s| {
+            let trigrams = trigrams(s);
+            if trigrams.is_empty() {
+                Query::All
+            } else {
+                Query::Trigrams(trigrams)
+            }
+        })
+        .collect())
+}
+
+fn analyze(hir: &Hir) -> Info {
+    match hir.kind() {
+        HirKind::Empty | HirKind::Look(_) => Info::Exact(vec![Vec::new()]),
+        HirKind::Literal(literal) => Info::Exact(vec![literal.0.to_ascii_lowercase()]),
+        HirKind::Class(class) => match class_strings(class) {
+            Some(strings) => Info::Exact(strings),
+            None => Info::Inexact(Query::All),
+        },
+        HirKind::Capture(capture) => analyze(&capture.sub),
+        HirKind::Repetition(repetition) if repetition.min > 0 => {
+            Info::Inexact(analyze(&repetition.sub).into_query())
+        }
+        HirKind::Repetition(_) => Info::Inexact(Query::All),
+        HirKind::Concat(parts) => concat(parts),
+        HirKind::Alternation(parts) => alternation(parts),
+    }
+}
+
+/// The strings of a small class, `[Kk\u{212A}]` for `(?i)k`
+fn class_strings(class: &Class) -> Option<Vec<Vec<u8>>> {
+    let mut strings: Vec<Vec<u8>> = match class {
+        Class::Unicode(class) => {
+            let size: u32 = class
+                .ranges()
+                .iter()
+                .map(|r| r.end() as u32 - r.start() as u32 + 1)
+                .sum();
+            if size > MAX_CLASS {
+                return None;
+            }
+            class
+                .iter()
+                .flat_map(|r| (r.start() as u32..=r.end() as u32).filter_map(char::from_u32))
+                .map(|c| c.to_string().into_bytes().to_ascii_lowercase())
+                .collect()
+        }
+        Class::Bytes(class) => {
+            let size: u32 = class
+                .ranges()
+                .iter()
+                .map(|r| r.end() as u32 - r.start() as u32 + 1)
+                .sum();
+            if size > MAX_CLASS {
+                return None;
+            }
+            class
+                .iter()
+                .flat_map(|r| r.start()..=r.end())
+                .map(|b| vec![b.to_ascii_lowercase()])
+                .collect()
+        }
+    };
+    strings.sort();
+    strings.dedup();
+    Some(strings)
+}
+
+fn concat(parts: &[Hir]) -> Info {
+    let mut queries = Vec::new();
+    let mut current: Option<Vec<Vec<u8>>> = Some(vec![Vec::new()]);
+    let mut exact = true;
+    for part in parts {
+        match (analyze(part), current.take()) {
+            (Info::Exact(next), Some(strings)) if strings.len() * next.len() <= MAX_STRINGS => {
+                current = Some(
+                    strings
+                        .iter()
+                        .flat_map(|s| next.iter().map(move |n| [s.as_slice(), n].concat()))
+                        .collect(),
+                );
+            }
+            (info, strings) => {
+                exact = false;
+                if let Some(strings) = strings {
+                    queries.push(strings_query(&strings));
+                }
+                match info {
+                    Info::Exact(next) => current = Some(next),
+                    Info::Inexact(query) => queries.push(query),
+                }
+            }
+        }
+    }
+    match current {
+        Some(strings) if exact => Info::Exact(strings),
+        current => {
+            if let Some(strings) = current {
+                queries.push(strings_query(&strings));
+            }
+            Info::Inexact(and(queries))
+        }
+    }
+}
+
+fn alternation(parts: &[Hir]) -> Info {
+    let infos: Vec<Info> = parts.iter().map(analyze).collect();
+    if infos.iter().all(|info| matches!(info, Info::Exact(_))) {
+        let mut union: Vec<Vec<u8>> = infos
+            .iter()
+            .flat_map(|info| match info {
+                Info::Exact(strings) => strings.clone(),
+                Info::Inexact(_) => Vec::new(),
+            })
+            .collect();
+        union.sort();
+        union.dedup();
+        if union.len() <= MAX_STRINGS {
+            return Info::Exact(union);
+        }
+    }
+    Info::Inexact(or(infos.into_iter().map(Info::into_query).collect()))
+}
+
+/// A file or directory met while building
+enum Walked {
+    /// Path, mtime, size, trigrams (`None`: too large to index)
+    File(String, i64, i64, Option<Vec<u32>>),
+    Dir(String, i64),
+}
+
+/// Index the files rg would search under `root` into `path`
+fn build(root: &Path, path: &Path) -> Result<(usize, usize)> {
+    let walked = walk::walk(&walker(root), |entry| {
+        let meta = entry.metadata().ok()?;
+        let rel = relative(root, entry.path());
+        if meta.is_dir() {
+            return Some(Walked::Dir(rel, mtime(&meta)));
+        }
+        if !meta.is_file() {
+            return None;
+        }
+        let trigrams = if meta.len() > MAX_FILE_SIZE {
+            None
+        } else {
+            let bytes = fs::read(entry.path()).ok()?;
+            // Binary files are never searched (-I): nothing to find in them
+            if bytes[..bytes.len().min(8192)].contains(&0) {
+                Some(Vec::new())
+            } else {
+                Some(trigrams(&bytes))
+            }
+        };
+        Some(Walked::File(rel, mtime(&meta), meta.len() as i64, trigrams))
+    });
+
+    let _ = fs::remove_file(path);
+    let mut conn = Connection::open(path)
+        .with_context(|| format!("Failed to create index {}", path.display()))?;
+    conn.execute_batch(
+        "PRAGMA journal_mode = OFF;
+         PRAGMA synchronous = OFF;
+         CREATE TABLE meta (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+         CREATE TABLE files (id INTEGER PRIMARY KEY, path TEXT NOT NULL,
+                             mtime INTEGER NOT NULL, size INTEGER NOT NULL,
+                             indexed INTEGER NOT NULL);
+         CREATE TABLE dirs (path TEXT PRIMARY KEY, mtime INTEGER NOT NULL);
+         CREATE TABLE trigrams (trigram INTEGER PRIMARY KEY, files BLOB NOT NULL);",
+    )?;
+
+    let tx = conn.transaction()?;
+    let mut postings: HashMap<u32, Vec<u32>> = HashMap::new();
+    let mut files = 0;
+    {
+        let mut insert_file = tx.prepare(
+            "INSERT INTO files (id, path, mtime, size, indexed) VALUES (?1, ?2, ?3, ?4, ?5)",
+        )?;
+        let mut insert_dir = tx.prepare("INSERT INTO dirs (path, mtime) VALUES (?1, ?2)")?;
+        for item in walked.items {
+            match item {
+                Walked::Dir(rel, mtime) => {
+                    insert_dir.execute(params![rel, mtime])?;
+                }
+                Walked::File(rel, mtime, size, trigrams) => {
+                    let id = files as u32;
+                    files += 1;
+                    insert_file.execute(params![id, rel, mtime, size, trigrams.is_some()])?;
+                    for trigram in trigrams.unwrap_or_default() {
+                        postings.entry(trigram).or_default().push(id);
+                    }
+                }
+            }
+        }
+        let mut insert_trigram =
+            tx.prepare("INSERT INTO trigrams (trigram, files) VALUES (?1, ?2)")?;
+        for (trigram, ids) in &postings {
+            insert_trigram.execute(params![trigram, encode(ids)])?;
+        }
+        let built = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        for (key, value) in [
+            ("version", SCHEMA_VERSION.to_string()),
+            ("root", root.to_string_lossy().into_owned()),
+            ("built", built.to_string()),
+        ] {
+            tx.execute(
+                "INSERT INTO meta (key, value) VALUES (?1, ?2)",
+                params![key, value],
+            )?;
+        }
+    }
+    tx.commit()?;
+    Ok((files, postings.len()))
+}
+
+/// An index opened for searching
+pub struct Index {
+    conn: Connection,
+    /// Canonical directory it was built for
+    pub root: PathBuf,
+    /// Build time, Unix seconds
+    pub built: i64,
+}
+
+/// Files to search under one root
+#[derive(Debug, Default)]
+pub struct Candidates {
+    /// Paths as the walk from the root gives them (`root/sub/file`)
+    pub files: Vec<String>,
+    /// Indexed files under the root
+    pub indexed: usize,
+    /// Files changed or added since the build, searched whatever their
+    /// content
+    pub changed: usize,
+}
+
+impl Candidates {
+    /// Too much changed since the build for the index to be worth it
+    pub fn is_stale(&self) -> bool {
+        self.changed * 100 > self.indexed * MAX_STALE_PERCENT
+    }
+}
+
+impl Index {
+    fn open(path: &Path) -> Result<Self> {
+        let conn = Connection::open(path)?;
+        let meta = |key: &str| -> Result<Option<String>> {
+            Ok(conn
+                .query_row("SELECT value FROM meta WHERE key = ?1", [key], |row| {
+                    row.get(0)
+                })
+                .optional()?)
+        };
+        if meta("version")?.as_deref() != Some(SCHEMA_VERSION) {
+            bail!("index {} is from another rtk version", path.display());
+        }
+        let root = PathBuf::from(meta("root")?.unwrap_or_default());
+        let built = meta("built")?.and_then(|b| b.parse().ok()).unwrap_or(0);
+        Ok(Index { conn, root, built })
+    }
+
+    /// The index covering `path`: built for it or for its nearest indexed
+    /// ancestor
+    pub fn find(path: &Path) -> Option<Self> {
+        if !is_enabled() {
+            return None;
+        }
+        let path = path.canonicalize().ok()?;
+        path.ancestors()
+            .filter_map(index_path)
+            .find(|p| p.exists())
+            .and_then(|p| Index::open(&p).ok())
+    }
+
+    /// Ids of the files that may match `query`, `None` when all may
+    pub fn matching(&self, query: &Query) -> Result<Option<HashSet<u32>>> {
+        Ok(match query {
+            Query::All => None,
+            Query::Trigrams(trigrams) => {
+                let mut ids: Option<HashSet<u32>> = None;
+                for &trigram in trigrams {
+                    let posting = self.posting(trigram)?;
+                    let next: HashSet<u32> = match ids {
+                        Some(ids) => posting.into_iter().filter(|id| ids.contains(id)).collect(),
+                        None => posting.into_iter().collect(),
+                    };
+                    if next.is_empty() {
+                        return Ok(Some(next));
+                    }
+                    ids = Some(next);
+                }
+                ids
+            }
+            Query::And(queries) => {
+                let mut ids: Option<HashSet<u32>> = None;
+                for query in queries {
+                    if let Some(next) = self.matching(query)? {
+                        ids = Some(match ids {
+                            Some(ids) => ids.intersection(&next).copied().collect(),
+                            None => next,
+                        });
+                    }
+                }
+                ids
+            }
+            Query::Or(queries) => {
+                let mut ids = HashSet::new();
+                for query in queries {
+                    match self.matching(query)? {
+                        Some(next) => ids.extend(next),
+                        None => return Ok(None),
+                    }
+                }
+                Some(ids)
+            }
+        })
+    }
+
+    fn posting(&self, trigram: u32) -> Result<Vec<u32>> {
+        let blob: Option<Vec<u8>> = self
+            .conn
+            .query_row(
+                "SELECT files FROM trigrams WHERE trigram = ?1",
+                [trigram],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(blob.map(|b| decode(&b)).unwrap_or_default())
+    }
+
+    /// Files under the search root `root` (as given) that may match: the
+    /// indexed ones among `ids` (all with `None`), files too large to index,
+    /// and every file changed or added since the build. `None` when the
+    /// index doesn't cover `root`.
+    pub fn candidates(&self, root: &str, ids: Option<&HashSet<u32>>) -> Result<Option<Candidates>> {
+        let Ok(absolute) = Path::new(root).canonicalize() else {
+            return Ok(None);
+        };
+        let Ok(prefix) = absolute.strip_prefix(&self.root) else {
+            return Ok(None);
+        };
+        let mut candidates = Candidates::default();
+        if absolute.is_file() {
+            // Explicit files are always searched
+            candidates.files.push(root.to_string());
+            return Ok(Some(candidates));
+        }
+        let prefix = relative(Path::new(""), prefix);
+        let under = |path: &str| {
+            prefix.is_empty()
+                || path == prefix
+                || path
+                    .strip_prefix(&prefix)
+                    .is_some_and(|p| p.starts_with('/'))
+        };
+        // `root`-relative display path of an index path
+        let display = |path: &str| {
+            let rest = path[prefix.len()..].trim_start_matches('/');
+            Path::new(root).join(rest).to_string_lossy().into_owned()
+        };
+
+        let mut known = HashSet::new();
+        let mut statement = self
+            .conn
+            .prepare("SELECT id, path, mtime, size, indexed FROM files")?;
+        let rows = statement.query_map([], |row| {
+            Ok((
+                row.get::<_, u32>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, i64>(3)?,
+                row.get::<_, bool>(4)?,
+            ))
+        })?;
+        for row in rows {
+            let (id, path, built_mtime, size, indexed) = row?;
+            if !under(&path) {
+                continue;
+            }
+            candidates.indexed += 1;
+            let Ok(meta) = fs::metadata(self.root.join(&path)) else {
+                // Removed since the build
+                continue;
+            };
+            let changed = mtime(&meta) != built_mtime || meta.len() as i64 != size;
+            if changed {
+                candidates.changed += 1;
+            }
+            if changed || !indexed || ids.is_none_or(|ids| ids.contains(&id)) {
+                candidates.files.push(display(&path));
+            }
+            known.insert(path);
+        }
+
+        // A directory changes when entries are added: its new files (and
+        // whole new subdirectories) are searched
+        let mut statement = self.conn.prepare("SELECT path, mtime FROM dirs")?;
+        let dirs: HashMap<String, i64> = statement
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<_>>()?;
+        for (dir, built_mtime) in dirs.iter().filter(|(dir, _)| under(dir)) {
+            let path = self.root.join(dir);
+            if fs::metadata(&path)
+                .ok()
+                .is_none_or(|meta| mtime(&meta) == *built_mtime)
+            {
+                continue;
+            }
+            let mut entries = walker(&path);
+            entries.max_depth(Some(1));
+            for entry in entries.build().flatten().filter(|e| e.depth() == 1) {
+                let rel = relative(&self.root, entry.path());
+                let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+                if is_dir && !dirs.contains_key(&rel) {
+                    for entry in walker(entry.path()).build().flatten() {
+                        if entry.file_type().is_some_and(|t| t.is_file()) {
+                            candidates.changed += 1;
+                            candidates
+                                .files
+                                .push(display(&relative(&self.root, entry.path())));
+                        }
+                    }
+                } else if !is_dir && !known.contains(&rel) {
+                    candidates.changed += 1;
+                    candidates.files.push(display(&rel));
+                }
+            }
+        }
+        candidates.files.sort();
+        candidates.files.dedup();
+        Ok(Some(candidates))
+    }
+}
+
+/// Directory `path` resolves to, the index root by default: the
+/// repository root, else the current directory
+fn resolve_root(path: Option<&str>) -> Result<PathBuf> {
+    let path = match path {
+        Some(path) => PathBuf::from(path),
+        None => crate::paths::repo_root()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| PathBuf::from(".")),
+    };
+    let root = path
+        .canonicalize()
+        .with_context(|| format!("Cannot index {}", path.display()))?;
+    if !root.is_dir() {
+        bail!("Cannot index {}: not a directory", path.display());
+    }
+    Ok(root)
+}
+
+/// `rtk index build [PATH]`
+pub fn run_build(path: Option<&str>, verbose: u8) -> Result<()> {
+    let root = resolve_root(path)?;
+    let index = index_path(&root).context("No data directory for the index")?;
+    if let Some(dir) = index.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    if verbose > 0 {
+        eprintln!("Indexing {} into {}", root.display(), index.display());
+    }
+    let started = Instant::now();
+    // Built aside, so a search never opens a half-written index
+    let partial = index.with_extension("partial");
+    let (files, trigrams) = build(&root, &partial)?;
+    fs::rename(&partial, &index).with_context(|| format!("Failed to write {}", index.display()))?;
+    let bytes = fs::metadata(&index).map_or(0, |m| m.len());
+    println!(
+        "🗂️  Indexed {} files under {}: {} trigrams, {} ({:.1}s)",
+        files,
+        root.display(),
+        trigrams,
+        format_bytes(bytes),
+        started.elapsed().as_secs_f64()
+    );
+    println!("rtk grep uses it while fresh; rerun rtk index build after large changes");
+    Ok(())
+}
+
+/// `rtk index status [PATH]`: which index covers PATH and how much changed
+pub fn run_status(path: Option<&str>, _verbose: u8) -> Result<()> {
+    let root = resolve_root(path)?;
+    let Some(index) = Index::find(&root) else {
+        println!(
+            "No index covers {} (build one with rtk index build)",
+            root.display()
+        );
+        return Ok(());
+    };
+    let Some(scan) = index.candidates(&index.root.to_string_lossy(), None)? else {
+        bail!("Index of {} is unreadable", index.root.display());
+    };
+    let built = Local
+        .timestamp_opt(index.built, 0)
+        .single()
+        .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default();
+    println!(
+        "🗂️  Index of {}: {} files, built {}",
+        index.root.display(),
+        scan.indexed,
+        built
+    );
+    if scan.is_stale() {
+        println!(
+            "  stale: {} files changed since, searches run without it (rtk index build)",
+            scan.changed
+        );
+    } else {
+        println!("  fresh: {} files changed since", scan.changed);
+    }
+    Ok(())
+}
+
+/// `rtk index clear [PATH]`: remove the index built for PATH
+pub fn run_clear(path: Option<&str>, _verbose: u8) -> Result<()> {
+    let root = resolve_root(path)?;
+    let index = index_path(&root).context("No data directory for the index")?;
+    fs::remove_file(&index).with_context(|| format!("No index built for {}", root.display()))?;
+    println!("🗑️  Removed the index of {}", root.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tri(s: &str) -> u32 {
+        trigram(s.as_bytes())
+    }
+
+    #[test]
+    fn test_encode_roundtrip() {
+        let ids = vec![0, 1, 5, 300, 70_000, 70_001];
+        assert_eq!(decode(&encode(&ids)), ids);
+        assert!(decode(&encode(&[])).is_empty());
+    }
+
+    #[test]
+    fn test_trigrams() {
+        assert_eq!(trigrams(b"AbCd"), vec![tri("abc"), tri("bcd")]);
+        // Never across lines
+        assert_eq!(trigrams(b"ab\ncd"), Vec::<u32>::new());
+    }
+
+    #[test]
+    fn test_query() {
+        let q = |s: &str| query(&[s.to_string()]);
+        assert_eq!(q("Load"), Query::Trigrams(vec![tri("loa"), tri("oad")]));
+        assert_eq!(q("a.c"), Query::All);
+        assert_eq!(q(r"\w+"), Query::All);
+        // Alternatives: either set
+        assert_eq!(
+            q("fooo|barr"),
+            Query::Or(vec![
+                Query::Trigrams(vec![tri("arr"), tri("bar")]),
+                Query::Trigrams(vec![tri("foo"), tri("ooo")]),
+            ])
+        );
+        // Small classes expand, repetitions keep their required part
+        assert_eq!(q("ab[cd]"), query(&["abc|abd".to_string()]));
+        assert_eq!(
+            q(r"parse\s+config"),
+            Query::Trigrams(vec![
+                tri("ars"),
+                tri("con"),
+                tri("fig"),
+                tri("nfi"),
+                tri("onf"),
+                tri("par"),
+                tri("rse"),
+            ])
+        );
+        assert_eq!(q("(?:abcd)?x"), Query::All);
+        // Case-insensitive patterns still narrow: `k` also matches the
+        // Kelvin sign
+        assert_eq!(
+            q("(?i)toke"),
+            Query::Or(vec![
+                Query::Trigrams(vec![tri("oke"), tri("tok")]),
+                Query::Trigrams(trigrams("to\u{212A}e".as_bytes())),
+            ])
+        );
+        assert_eq!(q("fn"), Query::All);
+        // One pattern requiring nothing: any file may match
+        assert_eq!(query(&["load".to_string(), ".".to_string()]), Query::All);
+        assert_eq!(q("("), Query::All);
+    }
+
+    #[test]
+    fn test_candidates() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        fs::create_dir(root.join("src")).unwrap();
+        fs::write(root.join("src/a.rs"), "fn load_config() {}\n").unwrap();
+        fs::write(root.join("src/b.rs"), "fn save() {}\n").unwrap();
+        fs::write(root.join("notes.txt"), "nothing here\n").unwrap();
+        let db = root.join("index.db");
+        build(&root, &db).unwrap();
+        let index = Index::open(&db).unwrap();
+        assert_eq!(index.root, root);
+
+        let ids = index
+            .matching(&query(&["load_config".to_string()]))
+            .unwrap();
+        let src = root.join("src").to_string_lossy().into_owned();
+        let found = index.candidates(&src, ids.as_ref()).unwrap().unwrap();
+        assert_eq!(found.files, vec![format!("{}/a.rs", src)]);
+        assert_eq!((found.indexed, found.changed), (2, 0));
+
+        // Changed and added files are searched whatever their content
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        fs::write(root.join("src/b.rs"), "fn saved() {}\n").unwrap();
+        fs::write(root.join("src/c.rs"), "fn other() {}\n").unwrap();
+        let found = index.candidates(&src, ids.as_ref()).unwrap().unwrap();
+        assert_eq!(
+            found.files,
+            vec![
+                format!("{}/a.rs", src),
+                format!("{}/b.rs", src),
+                format!("{}/c.rs", src),
+            ]
+        );
+        assert_eq!(found.changed, 2);
+        assert!(found.is_stale());
+
+        assert!(index.candidates("/", None).unwrap().is_none());
+    }
+}
@@ -1,23 +1,103 @@
+use crate::cache;
+use crate::http_cmd;
+use crate::layout;
+use crate::output;
 use crate::tracking;
 use anyhow::{Context, Result};
+use serde::de::{self, Deserialize, DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::Serialize;
 use serde_json::Value;
-use std::fs;
-use std::io::{self, Read};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufReader, Read};
 use std::path::Path;
 
-/// Show JSON structure without values
-pub fn run(file: &Path, max_depth: usize, verbose: u8) -> Result<()> {
+/// Refuse remote documents larger than this
+const MAX_FETCH_BYTES: u64 = 50 * 1024 * 1024;
+
+/// Files larger than this are streamed instead of loaded into memory
+const STREAM_THRESHOLD: u64 = 64 * 1024 * 1024;
+
+/// Show JSON structure without values. `path` selects a subtree first;
+/// `values` prints that subtree's values instead of its schema; `stats`
+/// adds per-field statistics of the records (array elements, NDJSON lines).
+///
+/// Large files and NDJSON (`.ndjson`, `.jsonl`) are streamed: the schema is
+/// built while parsing, so memory stays bounded whatever the file size.
+#[allow(clippy::too_many_arguments)]
+pub fn run(
+    file: &Path,
+    max_depth: usize,
+    path: Option<&str>,
+    values: bool,
+    stats: bool,
+    stream: bool,
+    format: &str,
+    verbose: u8,
+) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
         eprintln!("Analyzing JSON: {}", file.display());
     }
 
+    let selecting = path.is_some() || values;
+    if stream && selecting {
+        anyhow::bail!("--path and --values need the whole document; drop --stream");
+    }
+
+    let size = fs::metadata(file)
+        .with_context(|| format!("Failed to read file: {}", file.display()))?
+        .len();
+    if stream || (!selecting && (size > STREAM_THRESHOLD || is_ndjson(file))) {
+        if verbose > 0 {
+            eprintln!("Streaming {} bytes", size);
+        }
+        let open =
+            || File::open(file).with_context(|| format!("Failed to read file: {}", file.display()));
+        // Hashing is one more pass over the file, far cheaper than parsing
+        let key = if cache::is_enabled() {
+            let options = cache_options(max_depth, None, false, stats, format);
+            Some(cache::key_reader(
+                "json --stream",
+                &options,
+                BufReader::new(open()?),
+            )?)
+        } else {
+            None
+        };
+        if let Some(schema) = key.as_deref().and_then(cache::get) {
+            print_schema(&schema, false, format);
+            timer.track_streamed(
+                &format!("cat {}", file.display()),
+                "rtk json",
+                size,
+                &schema,
+            );
+            return Ok(());
+        }
+
+        let (schema, bytes) = stream_schema(open()?, max_depth, stats, format)
+            .with_context(|| format!("Failed to parse JSON: {}", file.display()))?;
+        if let Some(key) = key {
+            cache::put(&key, &schema);
+        }
+        print_schema(&schema, false, format);
+        timer.track_streamed(
+            &format!("cat {}", file.display()),
+            "rtk json",
+            bytes,
+            &schema,
+        );
+        return Ok(());
+    }
+
     let content = fs::read_to_string(file)
         .with_context(|| format!("Failed to read file: {}", file.display()))?;
 
-    let schema = filter_json_string(&content, max_depth)?;
-    println!("{}", schema);
+    let schema = cached_render(&content, max_depth, path, values, stats, format)?;
+    print_schema(&schema, values, format);
     timer.track(
         &format!("cat {}", file.display()),
         "rtk json",
@@ -28,139 +108,1579 @@ pub fn run(file: &Path, max_depth: usize, verbose: u8) -> Result<()> {
 }
 
 /// Show JSON structure from stdin
-pub fn run_stdin(max_depth: usize, verbose: u8) -> Result<()> {
+pub fn run_stdin(
+    max_depth: usize,
+    path: Option<&str>,
+    values: bool,
+    stats: bool,
+    stream: bool,
+    format: &str,
+    verbose: u8,
+) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
     if verbose > 0 {
         eprintln!("Analyzing JSON from stdin");
     }
 
+    if stream {
+        if path.is_some() || values {
+            anyhow::bail!("--path and --values need the whole document; drop --stream");
+        }
+        let (schema, bytes) = stream_schema(io::stdin().lock(), max_depth, stats, format)?;
+        print_schema(&schema, false, format);
+        timer.track_streamed("cat - (stdin)", "rtk json -", bytes, &schema);
+        return Ok(());
+    }
+
     let mut content = String::new();
     io::stdin()
         .lock()
         .read_to_string(&mut content)
         .context("Failed to read from stdin")?;
 
-    let schema = filter_json_string(&content, max_depth)?;
-    println!("{}", schema);
+    let schema = cached_render(&content, max_depth, path, values, stats, format)?;
+    print_schema(&schema, values, format);
     timer.track("cat - (stdin)", "rtk json -", &content, &schema);
     Ok(())
 }
 
+/// Show JSON structure of a remote document (http/https URL)
+pub fn run_url(
+    url: &str,
+    max_depth: usize,
+    path: Option<&str>,
+    values: bool,
+    stats: bool,
+    format: &str,
+    verbose: u8,
+) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    if verbose > 0 {
+        eprintln!("Fetching JSON: {}", url);
+    }
+
+    let content = fetch_url(url)?;
+    let schema = cached_render(&content, max_depth, path, values, stats, format)?;
+    print_schema(&schema, values, format);
+    timer.track(&format!("curl -s {}", url), "rtk json", &content, &schema);
+    Ok(())
+}
+
+/// Infer the schema of a document and print it as `output`: `json-schema`
+/// (draft 2020-12), `json` (rtk's own schema) or `text`. For NDJSON the
+/// schema describes one record.
+pub fn run_schema(source: &str, max_depth: usize, output: &str, verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    if verbose > 0 {
+        eprintln!("Inferring schema: {}", source);
+    }
+
+    let content = read_source(source)?;
+    let schema = if is_ndjson(Path::new(source)) {
+        let mut merged: Option<Schema> = None;
+        for record in serde_json::Deserializer::from_str(&content)
+            .into_iter::<Value>()
+            .take(ARRAY_SAMPLE)
+        {
+            let schema = Schema::from_value(&record.context("Failed to parse JSON")?, 0, max_depth);
+            match &mut merged {
+                Some(acc) => acc.merge(schema),
+                None => merged = Some(schema),
+            }
+        }
+        merged.context("Failed to parse JSON: empty input")?
+    } else {
+        let value: Value = serde_json::from_str(&content).context("Failed to parse JSON")?;
+        Schema::from_value(&value, 0, max_depth)
+    };
+
+    let rendered = match output {
+        "json-schema" => serde_json::to_string_pretty(&json_schema(&schema.to_node()))?,
+        "json" => serde_json::to_string_pretty(&schema.to_node())?,
+        "text" => schema.render(0),
+        other => anyhow::bail!(
+            "Unknown schema output '{}' (expected json-schema, json, text)",
+            other
+        ),
+    };
+    println!("{}", rendered);
+
+    timer.track(
+        &format!("cat {}", source),
+        "rtk json schema",
+        &content,
+        &rendered,
+    );
+    Ok(())
+}
+
+/// Content of a file, of stdin (`-`) or of an http(s) URL
+pub(crate) fn read_source(source: &str) -> Result<String> {
+    if source == "-" {
+        let mut content = String::new();
+        io::stdin()
+            .lock()
+            .read_to_string(&mut content)
+            .context("Failed to read from stdin")?;
+        Ok(content)
+    } else if is_url(source) {
+        fetch_url(source)
+    } else {
+        fs::read_to_string(source).with_context(|| format!("Failed to read file: {}", source))
+    }
+}
+
+fn is_ndjson(file: &Path) -> bool {
+    matches!(
+        file.extension().and_then(|e| e.to_str()),
+        Some("ndjson" | "jsonl")
+    )
+}
+
+pub fn is_url(arg: &str) -> bool {
+    arg.starts_with("http://") || arg.starts_with("https://")
+}
+
+/// Download `url`, following redirects, bounded by the HTTP timeout and
+/// MAX_FETCH_BYTES (also enforced while reading, for chunked responses
+/// without Content-Length).
+fn fetch_url(url: &str) -> Result<String> {
+    let response = http_cmd::client(true)?
+        .get(url)
+        .header("Accept", "application/json")
+        .send()
+        .map_err(|err| {
+            anyhow::anyhow!("Failed to fetch {}: {}", url, http_cmd::error_reason(&err))
+        })?;
+    let status = response.status();
+    if !status.is_success() {
+        anyhow::bail!("Failed to fetch {}: HTTP {}", url, status);
+    }
+
+    let mut body = Vec::new();
+    response
+        .take(MAX_FETCH_BYTES + 1)
+        .read_to_end(&mut body)
+        .with_context(|| format!("Failed to read response from {}", url))?;
+    if body.len() as u64 > MAX_FETCH_BYTES {
+        anyhow::bail!(
+            "Response from {} exceeds {}MB limit",
+            url,
+            MAX_FETCH_BYTES / (1024 * 1024)
+        );
+    }
+
+    String::from_utf8(body).with_context(|| format!("Response from {} is not UTF-8", url))
+}
+
+/// Print a rendered schema, keys colored when the terminal allows it
+fn print_schema(schema: &str, values: bool, format: &str) {
+    if values || format == "json" {
+        println!("{}", schema);
+        return;
+    }
+    // Schema lines are cut to the terminal; values are never altered
+    let schema = layout::fit_lines(schema);
+    if output::color_enabled() {
+        println!("{}", output::color_schema_keys(&schema));
+    } else {
+        println!("{}", schema);
+    }
+}
+
 /// Parse a JSON string and return its schema representation.
 /// Useful for piping JSON from other commands (e.g., `gh api`, `curl`).
 pub fn filter_json_string(json_str: &str, max_depth: usize) -> Result<String> {
     let value: Value = serde_json::from_str(json_str).context("Failed to parse JSON")?;
-    Ok(extract_schema(&value, 0, max_depth))
+    Ok(extract_schema(&value, max_depth))
 }
 
-fn extract_schema(value: &Value, depth: usize, max_depth: usize) -> String {
-    let indent = "  ".repeat(depth);
+fn render(
+    json_str: &str,
+    max_depth: usize,
+    path: Option<&str>,
+    values: bool,
+    stats: bool,
+    format: &str,
+) -> Result<String> {
+    let root: Value = serde_json::from_str(json_str).context("Failed to parse JSON")?;
+    let value = match path {
+        Some(path) => select(&root, path)?,
+        None => &root,
+    };
 
-    if depth > max_depth {
-        return format!("{}...", indent);
+    if values {
+        return Ok(serde_json::to_string_pretty(value)?);
     }
 
-    match value {
-        Value::Null => format!("{}null", indent),
-        Value::Bool(_) => format!("{}bool", indent),
-        Value::Number(n) => {
-            if n.is_i64() {
-                format!("{}int", indent)
-            } else {
-                format!("{}float", indent)
+    let stats = stats.then(|| ValueStats::of(value, max_depth));
+    match format {
+        "json" => {
+            let schema = extract_schema_value(value, max_depth);
+            Ok(serde_json::to_string_pretty(&with_stats(schema, stats))?)
+        }
+        _ => Ok(render_with_stats(extract_schema(value, max_depth), stats)),
+    }
+}
+
+/// [`render`] through the result cache, keyed by the content and options
+fn cached_render(
+    json_str: &str,
+    max_depth: usize,
+    path: Option<&str>,
+    values: bool,
+    stats: bool,
+    format: &str,
+) -> Result<String> {
+    let options = cache_options(max_depth, path, values, stats, format);
+    let key = cache::key("json", &options, json_str.as_bytes());
+    cache::get_or_render(&key, || {
+        render(json_str, max_depth, path, values, stats, format)
+    })
+}
+
+fn cache_options(
+    max_depth: usize,
+    path: Option<&str>,
+    values: bool,
+    stats: bool,
+    format: &str,
+) -> String {
+    format!(
+        "depth={} path={:?} values={} stats={} format={}",
+        max_depth, path, values, stats, format
+    )
+}
+
+/// `{"schema": ..., "stats": ...}` when statistics were asked for
+fn with_stats(schema: Value, stats: Option<ValueStats>) -> Value {
+    match stats {
+        Some(stats) => serde_json::json!({ "schema": schema, "stats": stats.to_value() }),
+        None => schema,
+    }
+}
+
+fn render_with_stats(schema: String, stats: Option<ValueStats>) -> String {
+    match stats {
+        Some(stats) => format!("{}\n\n{}", schema, stats.render()),
+        None => schema,
+    }
+}
+
+/// Build the schema of a document (or of a sequence of documents, as in
+/// NDJSON) while parsing it from `reader`, without materializing values.
+///
+/// Several top-level documents render as an array of records. Returns the
+/// rendered schema and the number of bytes read.
+///
+/// With `stats`, each top-level document is parsed into a value to collect
+/// field statistics: memory is bounded by the largest document rather
+/// than the whole input.
+fn stream_schema<R: Read>(
+    reader: R,
+    max_depth: usize,
+    stats: bool,
+    format: &str,
+) -> Result<(String, u64)> {
+    let mut counter = CountingReader {
+        inner: reader,
+        count: 0,
+    };
+    let mut de = serde_json::Deserializer::from_reader(BufReader::new(&mut counter));
+
+    let mut records = 0;
+    let mut merged: Option<Schema> = None;
+    let mut value_stats = stats.then(ValueStats::default);
+    while de.end().is_err() {
+        records += 1;
+        if let Some(value_stats) = &mut value_stats {
+            let value = Value::deserialize(&mut de).context("Failed to parse JSON")?;
+            value_stats.add_records(&value, max_depth);
+            if records <= ARRAY_SAMPLE {
+                let schema = Schema::from_value(&value, 0, max_depth);
+                match &mut merged {
+                    Some(acc) => acc.merge(schema),
+                    None => merged = Some(schema),
+                }
             }
+            continue;
         }
-        Value::String(s) => {
-            if s.len() > 50 {
-                format!("{}string[{}]", indent, s.len())
-            } else if s.is_empty() {
-                format!("{}string", indent)
-            } else {
-                // Check if it looks like a URL, date, etc.
-                if s.starts_with("http") {
-                    format!("{}url", indent)
-                } else if s.contains('-') && s.len() == 10 {
-                    format!("{}date?", indent)
+        if records > ARRAY_SAMPLE {
+            // Still parsed (validated and counted), just not merged
+            IgnoredAny::deserialize(&mut de).context("Failed to parse JSON")?;
+            continue;
+        }
+        let schema = SchemaSeed {
+            depth: 0,
+            max_depth,
+        }
+        .deserialize(&mut de)
+        .context("Failed to parse JSON")?;
+        match &mut merged {
+            Some(acc) => acc.merge(schema),
+            None => merged = Some(schema),
+        }
+    }
+
+    let schema = match (records, merged) {
+        (_, None) => anyhow::bail!("Failed to parse JSON: empty input"),
+        (1, Some(schema)) => schema,
+        (len, Some(items)) => Schema {
+            array: Some(ArraySchema {
+                items: Some(Box::new(items)),
+                len,
+            }),
+            ..Default::default()
+        },
+    };
+    drop(de);
+
+    let rendered = match format {
+        "json" => {
+            let node = serde_json::to_value(schema.to_node())?;
+            serde_json::to_string_pretty(&with_stats(node, value_stats))?
+        }
+        _ => render_with_stats(schema.render(0), value_stats),
+    };
+    Ok((rendered, counter.count))
+}
+
+/// Counts bytes pulled through it, for tracking streamed input
+struct CountingReader<R> {
+    inner: R,
+    count: u64,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.count += n as u64;
+        Ok(n)
+    }
+}
+
+/// Path segment: object key or array index (negative counts from the end)
+#[derive(Debug, PartialEq)]
+enum Segment {
+    Key(String),
+    Index(i64),
+}
+
+/// Parse a dotted path: `.items[3].name`, `items[-1]`, `.["odd.key"]`
+fn parse_path(path: &str) -> Result<Vec<Segment>> {
+    let mut segments = Vec::new();
+    let mut chars = path.chars().peekable();
+    let mut key = String::new();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '.' => {
+                if !key.is_empty() {
+                    segments.push(Segment::Key(std::mem::take(&mut key)));
+                }
+            }
+            '[' => {
+                if !key.is_empty() {
+                    segments.push(Segment::Key(std::mem::take(&mut key)));
+                }
+                let inner: String = chars.by_ref().take_while(|&c| c != ']').collect();
+                let inner = inner.trim();
+                if let Some(quoted) = inner.strip_prefix('"').and_then(|s| s.strip_suffix('"')) {
+                    segments.push(Segment::Key(quoted.to_string()));
                 } else {
-                    format!("{}string", indent)
+                    let index = inner.parse().with_context(|| {
+                        format!("Invalid array index '[{}]' in {}", inner, path)
+                    })?;
+                    segments.push(Segment::Index(index));
+                }
+            }
+            c => key.push(c),
+        }
+    }
+    if !key.is_empty() {
+        segments.push(Segment::Key(key));
+    }
+    Ok(segments)
+}
+
+/// Select a subtree by JSON Pointer (`/items/3/name`) or dotted path
+/// (`.items[3].name`). Errors name the deepest part that resolved.
+fn select<'a>(root: &'a Value, path: &str) -> Result<&'a Value> {
+    if path.starts_with('/') || path.is_empty() {
+        return root
+            .pointer(path)
+            .with_context(|| format!("JSON Pointer not found: {}", path));
+    }
+
+    let mut current = root;
+    let mut resolved = String::new();
+
+    for segment in parse_path(path)? {
+        let next = match (&segment, current) {
+            (Segment::Key(key), Value::Object(map)) => map.get(key).with_context(|| {
+                let mut keys: Vec<&str> = map.keys().map(String::as_str).take(10).collect();
+                if map.len() > 10 {
+                    keys.push("...");
                 }
+                format!(
+                    "No key '{}' at {} (keys: {})",
+                    key,
+                    display_path(&resolved),
+                    keys.join(", ")
+                )
+            })?,
+            (Segment::Index(i), Value::Array(arr)) => {
+                let idx = if *i < 0 { arr.len() as i64 + i } else { *i };
+                usize::try_from(idx)
+                    .ok()
+                    .and_then(|idx| arr.get(idx))
+                    .with_context(|| {
+                        format!(
+                            "Index [{}] out of range at {} ({} elements)",
+                            i,
+                            display_path(&resolved),
+                            arr.len()
+                        )
+                    })?
             }
+            (segment, other) => anyhow::bail!(
+                "Cannot take {} of {} at {}",
+                match segment {
+                    Segment::Key(k) => format!("key '{}'", k),
+                    Segment::Index(i) => format!("index [{}]", i),
+                },
+                value_kind(other),
+                display_path(&resolved)
+            ),
+        };
+
+        match &segment {
+            Segment::Key(k) => resolved.push_str(&format!(".{}", k)),
+            Segment::Index(i) => resolved.push_str(&format!("[{}]", i)),
         }
-        Value::Array(arr) => {
-            if arr.is_empty() {
-                format!("{}[]", indent)
+        current = next;
+    }
+
+    Ok(current)
+}
+
+fn display_path(resolved: &str) -> &str {
+    if resolved.is_empty() {
+        "root"
+    } else {
+        resolved
+    }
+}
+
+fn value_kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "bool",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Max array elements inspected when inferring an array's item schema
+const ARRAY_SAMPLE: usize = 1000;
+
+/// Max object keys listed in text output
+const MAX_KEYS: usize = 15;
+
+/// Inferred schema of a JSON value, as returned by [`schema`]. Serializes
+/// to the `rtk json --format json` output.
+///
+/// Objects become `{"type": "object", "keys": {...}}` (optional keys
+/// carry `"optional": true`), arrays carry their length and the merged
+/// schema of their elements, mixed types become
+/// `{"type": "union", "variants": [...]}`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum SchemaNode {
+    Null,
+    Bool,
+    Int,
+    Float,
+    String {
+        /// Longest sample
+        len: usize,
+        #[serde(skip)]
+        kind: StringKind,
+    },
+    Array {
+        /// Longest array seen
+        len: usize,
+        /// Merged schema of the elements; `None` for empty arrays
+        #[serde(skip_serializing_if = "Option::is_none")]
+        items: Option<Box<SchemaNode>>,
+    },
+    Object {
+        keys: BTreeMap<String, Field>,
+    },
+    /// Values of different types at the same place
+    Union {
+        variants: Vec<SchemaNode>,
+    },
+    /// Container past the max depth
+    Truncated,
+}
+
+/// Object key in a [`SchemaNode::Object`]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Field {
+    #[serde(flatten)]
+    pub schema: SchemaNode,
+    /// Missing from some of the sampled objects
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub optional: bool,
+}
+
+/// Inferred schema of one or more JSON values. Array elements are merged
+/// into one schema: keys missing from some elements become optional and
+/// differing types become unions.
+#[derive(Debug, Default, Clone, PartialEq)]
+struct Schema {
+    /// Primitive types seen, in order of first appearance
+    prims: Vec<Prim>,
+    object: Option<ObjectSchema>,
+    array: Option<ArraySchema>,
+    /// Containers past max depth
+    truncated: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Prim {
+    Null,
+    Bool,
+    Int,
+    Float,
+    Str { max_len: usize, kind: StringKind },
+}
+
+/// What a string looks like, from its samples
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StringKind {
+    Plain,
+    Url,
+    Date,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+struct ObjectSchema {
+    /// Key → (merged schema, number of samples holding the key)
+    keys: BTreeMap<String, (Schema, usize)>,
+    samples: usize,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+struct ArraySchema {
+    items: Option<Box<Schema>>,
+    /// Longest array seen
+    len: usize,
+}
+
+impl Prim {
+    fn of(value: &Value) -> Option<Prim> {
+        Some(match value {
+            Value::Null => Prim::Null,
+            Value::Bool(_) => Prim::Bool,
+            Value::Number(n) if n.is_i64() => Prim::Int,
+            Value::Number(_) => Prim::Float,
+            Value::String(s) => Prim::string(s),
+            _ => return None,
+        })
+    }
+
+    fn string(s: &str) -> Prim {
+        Prim::Str {
+            max_len: s.len(),
+            kind: if s.starts_with("http") {
+                StringKind::Url
+            } else if s.contains('-') && s.len() == 10 {
+                StringKind::Date
             } else {
-                let first_schema = extract_schema(&arr[0], depth + 1, max_depth);
-                let trimmed = first_schema.trim();
-                if arr.len() == 1 {
-                    format!("{}[\n{}\n{}]", indent, first_schema, indent)
-                } else {
-                    format!("{}[{}] ({})", indent, trimmed, arr.len())
+                StringKind::Plain
+            },
+        }
+    }
+
+    /// Fold `other` into `self` when both are the same type
+    fn absorb(&mut self, other: &Prim) -> bool {
+        match (self, other) {
+            (
+                Prim::Str { max_len, kind },
+                Prim::Str {
+                    max_len: len,
+                    kind: other_kind,
+                },
+            ) => {
+                *max_len = (*max_len).max(*len);
+                if kind != other_kind {
+                    *kind = StringKind::Plain;
                 }
+                true
+            }
+            (a, b) => a == b,
+        }
+    }
+
+    fn label(&self) -> String {
+        match self {
+            Prim::Null => "null".to_string(),
+            Prim::Bool => "bool".to_string(),
+            Prim::Int => "int".to_string(),
+            Prim::Float => "float".to_string(),
+            Prim::Str { max_len, .. } if *max_len > 50 => format!("string[{}]", max_len),
+            Prim::Str { max_len: 0, .. } => "string".to_string(),
+            Prim::Str { kind, .. } => match kind {
+                StringKind::Url => "url",
+                StringKind::Date => "date?",
+                StringKind::Plain => "string",
             }
+            .to_string(),
+        }
+    }
+
+    fn to_node(&self) -> SchemaNode {
+        match self {
+            Prim::Str { max_len, kind } => SchemaNode::String {
+                len: *max_len,
+                kind: *kind,
+            },
+            Prim::Null => SchemaNode::Null,
+            Prim::Bool => SchemaNode::Bool,
+            Prim::Int => SchemaNode::Int,
+            Prim::Float => SchemaNode::Float,
         }
-        Value::Object(map) => {
-            if map.is_empty() {
-                format!("{}{{}}", indent)
+    }
+}
+
+impl Schema {
+    fn from_value(value: &Value, depth: usize, max_depth: usize) -> Schema {
+        let mut schema = Schema::default();
+        if let Some(prim) = Prim::of(value) {
+            schema.prims.push(prim);
+            return schema;
+        }
+        if depth > max_depth {
+            schema.truncated = true;
+            return schema;
+        }
+
+        match value {
+            Value::Array(arr) => {
+                let items = arr
+                    .iter()
+                    .take(ARRAY_SAMPLE)
+                    .map(|v| Schema::from_value(v, depth + 1, max_depth))
+                    .reduce(|mut acc, s| {
+                        acc.merge(s);
+                        acc
+                    });
+                schema.array = Some(ArraySchema {
+                    items: items.map(Box::new),
+                    len: arr.len(),
+                });
+            }
+            Value::Object(map) => {
+                let keys = map
+                    .iter()
+                    .map(|(k, v)| (k.clone(), (Schema::from_value(v, depth + 1, max_depth), 1)))
+                    .collect();
+                schema.object = Some(ObjectSchema { keys, samples: 1 });
+            }
+            _ => {}
+        }
+        schema
+    }
+
+    fn merge(&mut self, other: Schema) {
+        for prim in other.prims {
+            if !self.prims.iter_mut().any(|p| p.absorb(&prim)) {
+                self.prims.push(prim);
+            }
+        }
+        self.truncated |= other.truncated;
+
+        if let Some(other_obj) = other.object {
+            let obj = self.object.get_or_insert_with(ObjectSchema::default);
+            obj.samples += other_obj.samples;
+            for (key, (schema, count)) in other_obj.keys {
+                match obj.keys.get_mut(&key) {
+                    Some((existing, seen)) => {
+                        existing.merge(schema);
+                        *seen += count;
+                    }
+                    None => {
+                        obj.keys.insert(key, (schema, count));
+                    }
+                }
+            }
+        }
+
+        if let Some(other_arr) = other.array {
+            let arr = self.array.get_or_insert_with(ArraySchema::default);
+            arr.len = arr.len.max(other_arr.len);
+            arr.items = match (arr.items.take(), other_arr.items) {
+                (Some(mut a), Some(b)) => {
+                    a.merge(*b);
+                    Some(a)
+                }
+                (a, b) => a.or(b),
+            };
+        }
+    }
+
+    /// Only primitive types: rendered inline after `key:`
+    fn is_simple(&self) -> bool {
+        self.object.is_none() && self.array.is_none() && !self.truncated
+    }
+
+    fn render(&self, depth: usize) -> String {
+        let indent = "  ".repeat(depth);
+
+        let mut variants: Vec<String> = self.prims.iter().map(Prim::label).collect();
+        if let Some(arr) = &self.array {
+            variants.push(arr.render(depth).trim_start().to_string());
+        }
+        if let Some(obj) = &self.object {
+            variants.push(obj.render(depth).trim_start().to_string());
+        }
+        if self.truncated {
+            variants.push("...".to_string());
+        }
+
+        format!("{}{}", indent, variants.join(" | "))
+    }
+
+    fn to_node(&self) -> SchemaNode {
+        let mut variants: Vec<SchemaNode> = self.prims.iter().map(Prim::to_node).collect();
+
+        if let Some(arr) = &self.array {
+            variants.push(SchemaNode::Array {
+                len: arr.len,
+                items: arr.items.as_ref().map(|items| Box::new(items.to_node())),
+            });
+        }
+        if let Some(obj) = &self.object {
+            let keys = obj
+                .keys
+                .iter()
+                .map(|(key, (schema, seen))| {
+                    let field = Field {
+                        schema: schema.to_node(),
+                        optional: *seen < obj.samples,
+                    };
+                    (key.clone(), field)
+                })
+                .collect();
+            variants.push(SchemaNode::Object { keys });
+        }
+        if self.truncated {
+            variants.push(SchemaNode::Truncated);
+        }
+
+        match variants.len() {
+            1 => variants.remove(0),
+            _ => SchemaNode::Union { variants },
+        }
+    }
+}
+
+impl ArraySchema {
+    fn render(&self, depth: usize) -> String {
+        let indent = "  ".repeat(depth);
+        match &self.items {
+            None => format!("{}[]", indent),
+            Some(items) if self.len == 1 => {
+                format!("{}[\n{}\n{}]", indent, items.render(depth + 1), indent)
+            }
+            Some(items) => format!(
+                "{}[{}] ({})",
+                indent,
+                items.render(depth + 1).trim(),
+                self.len
+            ),
+        }
+    }
+}
+
+impl ObjectSchema {
+    fn render(&self, depth: usize) -> String {
+        let indent = "  ".repeat(depth);
+        if self.keys.is_empty() {
+            return format!("{}{{}}", indent);
+        }
+
+        let mut lines = vec![format!("{}{{", indent)];
+        let count = self.keys.len();
+
+        for (i, (key, (schema, seen))) in self.keys.iter().enumerate() {
+            // Keys missing from some samples are optional
+            let name = if *seen < self.samples {
+                format!("{}?", key)
             } else {
-                let mut lines = vec![format!("{}{{", indent)];
-                let mut keys: Vec<_> = map.keys().collect();
-                keys.sort();
-
-                for (i, key) in keys.iter().enumerate() {
-                    let val = &map[*key];
-                    let val_schema = extract_schema(val, depth + 1, max_depth);
-                    let val_trimmed = val_schema.trim();
-
-                    // Inline simple types
-                    let is_simple = matches!(
-                        val,
-                        Value::Null | Value::Bool(_) | Value::Number(_) | Value::String(_)
-                    );
-
-                    if is_simple {
-                        if i < keys.len() - 1 {
-                            lines.push(format!("{}  {}: {},", indent, key, val_trimmed));
-                        } else {
-                            lines.push(format!("{}  {}: {}", indent, key, val_trimmed));
-                        }
-                    } else {
-                        lines.push(format!("{}  {}:", indent, key));
-                        lines.push(val_schema);
+                key.clone()
+            };
+
+            if schema.is_simple() {
+                let sep = if i < count - 1 { "," } else { "" };
+                lines.push(format!("{}  {}: {}{}", indent, name, schema.render(0), sep));
+            } else {
+                lines.push(format!("{}  {}:", indent, name));
+                lines.push(schema.render(depth + 1));
+            }
+
+            // Limit keys shown
+            if i >= MAX_KEYS {
+                lines.push(format!("{}  ... +{} more keys", indent, count - i - 1));
+                break;
+            }
+        }
+        lines.push(format!("{}}}", indent));
+        lines.join("\n")
+    }
+}
+
+/// Builds a [`Schema`] straight from the deserializer, mirroring
+/// [`Schema::from_value`] (same sampling and depth rules) without holding
+/// the parsed value in memory.
+struct SchemaSeed {
+    depth: usize,
+    max_depth: usize,
+}
+
+impl SchemaSeed {
+    fn prim(prim: Prim) -> Schema {
+        Schema {
+            prims: vec![prim],
+            ..Default::default()
+        }
+    }
+
+    fn child(&self) -> SchemaSeed {
+        SchemaSeed {
+            depth: self.depth + 1,
+            max_depth: self.max_depth,
+        }
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for SchemaSeed {
+    type Value = Schema;
+
+    fn deserialize<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<Schema, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for SchemaSeed {
+    type Value = Schema;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("any JSON value")
+    }
+
+    fn visit_unit<E>(self) -> Result<Schema, E> {
+        Ok(Self::prim(Prim::Null))
+    }
+
+    fn visit_bool<E>(self, _: bool) -> Result<Schema, E> {
+        Ok(Self::prim(Prim::Bool))
+    }
+
+    fn visit_i64<E>(self, _: i64) -> Result<Schema, E> {
+        Ok(Self::prim(Prim::Int))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Schema, E> {
+        // Same split as serde_json's Number::is_i64
+        Ok(Self::prim(if i64::try_from(v).is_ok() {
+            Prim::Int
+        } else {
+            Prim::Float
+        }))
+    }
+
+    fn visit_f64<E>(self, _: f64) -> Result<Schema, E> {
+        Ok(Self::prim(Prim::Float))
+    }
+
+    fn visit_str<E>(self, s: &str) -> Result<Schema, E> {
+        Ok(Self::prim(Prim::string(s)))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Schema, A::Error> {
+        let mut schema = Schema::default();
+        if self.depth > self.max_depth {
+            while seq.next_element::<IgnoredAny>()?.is_some() {}
+            schema.truncated = true;
+            return Ok(schema);
+        }
+
+        let mut items: Option<Schema> = None;
+        let mut len = 0;
+        loop {
+            if len < ARRAY_SAMPLE {
+                match seq.next_element_seed(self.child())? {
+                    Some(item) => match &mut items {
+                        Some(acc) => acc.merge(item),
+                        None => items = Some(item),
+                    },
+                    None => break,
+                }
+            } else if seq.next_element::<IgnoredAny>()?.is_none() {
+                break;
+            }
+            len += 1;
+        }
+        schema.array = Some(ArraySchema {
+            items: items.map(Box::new),
+            len,
+        });
+        Ok(schema)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Schema, A::Error> {
+        let mut schema = Schema::default();
+        if self.depth > self.max_depth {
+            while map.next_entry::<IgnoredAny, IgnoredAny>()?.is_some() {}
+            schema.truncated = true;
+            return Ok(schema);
+        }
+
+        let mut keys = BTreeMap::new();
+        while let Some(key) = map.next_key::<String>()? {
+            let value = map.next_value_seed(self.child())?;
+            keys.insert(key, (value, 1));
+        }
+        schema.object = Some(ObjectSchema { keys, samples: 1 });
+        Ok(schema)
+    }
+}
+
+/// Infer the schema of `value`, merging array elements (first
+/// `ARRAY_SAMPLE` of each array) and stopping below `max_depth`.
+///
+/// # Examples
+///
+/// ```
+/// use rtk::json::{schema, SchemaNode};
+///
+/// let value = serde_json::json!([{"id": 1}, {"id": 2, "name": "x"}]);
+/// let SchemaNode::Array { len, items: Some(items) } = schema(&value, 5) else {
+///     panic!("expected an array");
+/// };
+/// assert_eq!(len, 2);
+/// let SchemaNode::Object { keys } = *items else {
+///     panic!("expected objects");
+/// };
+/// assert!(keys["name"].optional);
+/// assert_eq!(keys["id"].schema, SchemaNode::Int);
+/// ```
+pub fn schema(value: &Value, max_depth: usize) -> SchemaNode {
+    Schema::from_value(value, 0, max_depth).to_node()
+}
+
+/// `$schema` of the documents produced by [`json_schema`]
+pub const JSON_SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Convert an inferred schema into a JSON Schema (draft 2020-12) document,
+/// for validators and code generators.
+///
+/// Keys present in every sampled object are `required`; unions of plain
+/// types become a `type` list, other unions `anyOf`; containers cut by the
+/// max depth accept anything.
+///
+/// # Examples
+///
+/// ```
+/// use rtk::json::{json_schema, schema};
+///
+/// let value = serde_json::json!([{"id": 1}, {"id": 2, "name": null}]);
+/// let doc = json_schema(&schema(&value, 5));
+/// assert_eq!(doc["type"], "array");
+/// assert_eq!(doc["items"]["properties"]["id"]["type"], "integer");
+/// assert_eq!(doc["items"]["required"], serde_json::json!(["id"]));
+/// ```
+pub fn json_schema(node: &SchemaNode) -> Value {
+    let mut doc = serde_json::Map::new();
+    doc.insert("$schema".to_string(), Value::from(JSON_SCHEMA_DIALECT));
+    if let Value::Object(body) = json_schema_node(node) {
+        doc.extend(body);
+    }
+    Value::Object(doc)
+}
+
+fn json_schema_node(node: &SchemaNode) -> Value {
+    use serde_json::json;
+    match node {
+        SchemaNode::Null => json!({"type": "null"}),
+        SchemaNode::Bool => json!({"type": "boolean"}),
+        SchemaNode::Int => json!({"type": "integer"}),
+        SchemaNode::Float => json!({"type": "number"}),
+        SchemaNode::String {
+            kind: StringKind::Url,
+            ..
+        } => json!({"type": "string", "format": "uri"}),
+        SchemaNode::String { .. } => json!({"type": "string"}),
+        SchemaNode::Array { items: None, .. } => json!({"type": "array"}),
+        SchemaNode::Array {
+            items: Some(items), ..
+        } => json!({"type": "array", "items": json_schema_node(items)}),
+        SchemaNode::Object { keys } => {
+            let properties: serde_json::Map<String, Value> = keys
+                .iter()
+                .map(|(key, field)| (key.clone(), json_schema_node(&field.schema)))
+                .collect();
+            let required: Vec<&String> = keys
+                .iter()
+                .filter(|(_, field)| !field.optional)
+                .map(|(key, _)| key)
+                .collect();
+            let mut object = json!({"type": "object", "properties": properties});
+            if !required.is_empty() {
+                object["required"] = json!(required);
+            }
+            object
+        }
+        SchemaNode::Union { variants } => {
+            let schemas: Vec<Value> = variants.iter().map(json_schema_node).collect();
+            // Plain `{"type": ...}` variants merge into one type list
+            let mut types: Vec<&str> = Vec::new();
+            for schema in &schemas {
+                match schema.as_object() {
+                    Some(object) if object.len() == 1 => {
+                        types.extend(object.get("type").and_then(Value::as_str))
                     }
+                    _ => return json!({ "anyOf": schemas }),
+                }
+            }
+            // integer is a subset of number
+            if types.contains(&"number") {
+                types.retain(|t| *t != "integer");
+            }
+            match types.as_slice() {
+                [single] => json!({ "type": single }),
+                _ => json!({ "type": types }),
+            }
+        }
+        SchemaNode::Truncated => json!({}),
+    }
+}
+
+/// Parse `json_str` and infer its schema (see [`schema`])
+pub fn schema_str(json_str: &str, max_depth: usize) -> Result<SchemaNode> {
+    let value: Value = serde_json::from_str(json_str).context("Failed to parse JSON")?;
+    Ok(schema(&value, max_depth))
+}
 
-                    // Limit keys shown
-                    if i >= 15 {
-                        lines.push(format!("{}  ... +{} more keys", indent, keys.len() - i - 1));
-                        break;
+fn extract_schema_value(value: &Value, max_depth: usize) -> Value {
+    serde_json::to_value(schema(value, max_depth)).unwrap_or(Value::Null)
+}
+
+fn extract_schema(value: &Value, max_depth: usize) -> String {
+    Schema::from_value(value, 0, max_depth).render(0)
+}
+
+/// Distinct strings tracked per field; past this the count is a lower bound
+const MAX_DISTINCT: usize = 10_000;
+
+/// Fields listed by `--stats`
+const MAX_STATS_FIELDS: usize = 30;
+
+/// Fields with at most this many distinct strings list them
+const ENUM_VALUES: usize = 5;
+
+/// Values of one field across all records
+#[derive(Debug, Default, Serialize)]
+struct FieldStats {
+    /// Times the field was present
+    count: usize,
+    nulls: usize,
+    #[serde(skip_serializing_if = "is_zero")]
+    trues: usize,
+    #[serde(skip_serializing_if = "is_zero")]
+    falses: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max: Option<f64>,
+    /// String values seen, repeats included
+    #[serde(skip)]
+    texts: usize,
+    /// Distinct strings, in order of first appearance
+    #[serde(skip)]
+    strings: Vec<String>,
+    #[serde(skip)]
+    seen: HashSet<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    distinct: Option<usize>,
+    /// Type names seen, in order of first appearance
+    types: Vec<&'static str>,
+}
+
+fn is_zero(n: &usize) -> bool {
+    *n == 0
+}
+
+impl FieldStats {
+    fn add(&mut self, value: &Value) {
+        self.count += 1;
+        let kind = match value {
+            Value::Null => {
+                self.nulls += 1;
+                "null"
+            }
+            Value::Bool(b) => {
+                if *b {
+                    self.trues += 1;
+                } else {
+                    self.falses += 1;
+                }
+                "bool"
+            }
+            Value::Number(n) => {
+                let x = n.as_f64().unwrap_or(0.0);
+                self.min = Some(self.min.map_or(x, |m| m.min(x)));
+                self.max = Some(self.max.map_or(x, |m| m.max(x)));
+                if n.is_f64() {
+                    "float"
+                } else {
+                    "int"
+                }
+            }
+            Value::String(s) => {
+                self.texts += 1;
+                if self.seen.len() < MAX_DISTINCT && self.seen.insert(s.clone()) {
+                    if self.strings.len() <= ENUM_VALUES {
+                        self.strings.push(s.clone());
                     }
+                    self.distinct = Some(self.seen.len());
                 }
-                lines.push(format!("{}}}", indent));
-                lines.join("\n")
+                "string"
             }
+            Value::Array(_) => "array",
+            Value::Object(_) => "object",
+        };
+        if !self.types.contains(&kind) {
+            self.types.push(kind);
         }
     }
+
+    fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let (Some(min), Some(max)) = (self.min, self.max) {
+            parts.push(format!("min {}, max {}", number(min), number(max)));
+        }
+        if let Some(distinct) = self.distinct {
+            let plus = if distinct >= MAX_DISTINCT { "+" } else { "" };
+            // Few values repeated over many records: list them
+            if distinct <= ENUM_VALUES && self.texts > distinct {
+                let values: Vec<String> = self
+                    .strings
+                    .iter()
+                    .map(|s| format!("{:?}", crate::utils::truncate(s, 20)))
+                    .collect();
+                parts.push(format!("{} distinct: {}", distinct, values.join(", ")));
+            } else {
+                parts.push(format!("{}{} distinct", distinct, plus));
+            }
+        }
+        if self.trues + self.falses > 0 {
+            parts.push(format!("{} true, {} false", self.trues, self.falses));
+        }
+        if self.nulls > 0 {
+            parts.push(format!("{}% null", self.nulls * 100 / self.count));
+        }
+        parts.join(", ")
+    }
+}
+
+fn number(x: f64) -> String {
+    if x.fract() == 0.0 && x.abs() < 1e15 {
+        format!("{}", x as i64)
+    } else {
+        format!("{}", x)
+    }
+}
+
+/// Per-field statistics of a set of records (array elements or NDJSON
+/// lines). Fields are dotted paths, `[]` standing for nested array items.
+#[derive(Debug, Default)]
+struct ValueStats {
+    records: usize,
+    fields: Vec<(String, FieldStats)>,
+    index: HashMap<String, usize>,
+}
+
+impl ValueStats {
+    fn of(value: &Value, max_depth: usize) -> Self {
+        let mut stats = Self::default();
+        stats.add_records(value, max_depth);
+        stats
+    }
+
+    /// Records of `value`: the elements of a top-level array, or the value
+    fn add_records(&mut self, value: &Value, max_depth: usize) {
+        match value {
+            Value::Array(items) => items.iter().for_each(|v| self.add_record(v, max_depth)),
+            other => self.add_record(other, max_depth),
+        }
+    }
+
+    fn add_record(&mut self, record: &Value, max_depth: usize) {
+        self.records += 1;
+        self.add(String::new(), record, 0, max_depth);
+    }
+
+    fn add(&mut self, path: String, value: &Value, depth: usize, max_depth: usize) {
+        match value {
+            Value::Object(map) if depth < max_depth => {
+                for (key, child) in map {
+                    self.add(format!("{}.{}", path, key), child, depth + 1, max_depth);
+                }
+            }
+            Value::Array(items) if depth < max_depth => {
+                let path = format!("{}[]", path);
+                for item in items {
+                    self.add(path.clone(), item, depth + 1, max_depth);
+                }
+            }
+            _ => {
+                let path = if path.is_empty() {
+                    ".".to_string()
+                } else {
+                    path
+                };
+                let i = match self.index.get(&path) {
+                    Some(&i) => i,
+                    None => {
+                        self.index.insert(path.clone(), self.fields.len());
+                        self.fields.push((path, FieldStats::default()));
+                        self.fields.len() - 1
+                    }
+                };
+                self.fields[i].1.add(value);
+            }
+        }
+    }
+
+    fn render(&self) -> String {
+        let mut out = format!("Stats ({} records):\n", self.records);
+        let shown = &self.fields[..self.fields.len().min(MAX_STATS_FIELDS)];
+        let width = shown.iter().map(|(p, _)| p.len()).max().unwrap_or(0);
+        let type_width = shown
+            .iter()
+            .map(|(_, f)| f.types.join("|").len())
+            .max()
+            .unwrap_or(0);
+        for (path, field) in shown {
+            out.push_str(&format!(
+                "  {:<width$}  {:<type_width$}  {}\n",
+                path,
+                field.types.join("|"),
+                field.describe(),
+                width = width,
+                type_width = type_width
+            ));
+        }
+        if self.fields.len() > MAX_STATS_FIELDS {
+            out.push_str(&format!(
+                "  ... +{} more fields\n",
+                self.fields.len() - MAX_STATS_FIELDS
+            ));
+        }
+        out.trim_end().to_string()
+    }
+
+    fn to_value(&self) -> Value {
+        let fields: serde_json::Map<String, Value> = self
+            .fields
+            .iter()
+            .map(|(path, field)| {
+                (
+                    path.clone(),
+                    serde_json::to_value(field).unwrap_or(Value::Null),
+                )
+            })
+            .collect();
+        serde_json::json!({ "records": self.records, "fields": fields })
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serde_json::json;
 
     #[test]
     fn test_extract_schema_simple() {
         let json: Value = serde_json::from_str(r#"{"name": "test", "count": 42}"#).unwrap();
-        let schema = extract_schema(&json, 0, 5);
+        let schema = extract_schema(&json, 5);
         assert!(schema.contains("name"));
         assert!(schema.contains("string"));
         assert!(schema.contains("int"));
     }
 
+    #[test]
+    fn test_extract_schema_value() {
+        let json: Value = serde_json::from_str(r#"{"name": "test", "tags": ["a", "b"]}"#).unwrap();
+        let schema = extract_schema_value(&json, 5);
+        assert_eq!(schema["type"], "object");
+        assert_eq!(schema["keys"]["name"]["type"], "string");
+        assert_eq!(schema["keys"]["tags"]["type"], "array");
+        assert_eq!(schema["keys"]["tags"]["len"], 2);
+        assert_eq!(schema["keys"]["tags"]["items"]["type"], "string");
+    }
+
+    #[test]
+    fn test_value_stats() {
+        let json = json!([
+            {"id": 1, "status": "active", "email": "a@x.io", "score": 4.5},
+            {"id": 2, "status": "banned", "email": null, "score": 9},
+            {"id": 7, "status": "active", "email": "c@x.io", "admin": true}
+        ]);
+        let stats = ValueStats::of(&json, 5);
+        assert_eq!(stats.records, 3);
+        let out = stats.render();
+        assert!(out.starts_with("Stats (3 records):\n"), "{}", out);
+        assert!(
+            out.contains("  .id      int          min 1, max 7\n"),
+            "{}",
+            out
+        );
+        assert!(
+            out.contains("2 distinct: \"active\", \"banned\""),
+            "{}",
+            out
+        );
+        assert!(out.contains("string|null  2 distinct, 33% null"), "{}", out);
+        assert!(out.contains("min 4.5, max 9"), "{}", out);
+        assert!(out.ends_with("1 true, 0 false"), "{}", out);
+
+        let value = stats.to_value();
+        assert_eq!(value["fields"][".email"]["nulls"], 1);
+        assert_eq!(value["fields"][".status"]["distinct"], 2);
+    }
+
+    #[test]
+    fn test_stats_in_render_and_stream() {
+        let content = r#"[{"tags": ["a", "b"]}, {"tags": ["a"]}]"#;
+        let out = render(content, 5, None, false, true, "text").unwrap();
+        assert!(out.contains("\n\nStats (2 records):\n  .tags[]  string  2 distinct: \"a\", \"b\""));
+
+        let ndjson = "{\"n\": 1}\n{\"n\": 3}\n";
+        let (streamed, _) = stream_schema(ndjson.as_bytes(), 5, true, "json").unwrap();
+        let value: Value = serde_json::from_str(&streamed).unwrap();
+        assert_eq!(value["schema"]["len"], 2);
+        assert_eq!(value["stats"]["fields"][".n"]["max"], 3.0);
+    }
+
+    #[test]
+    fn test_json_schema_document() {
+        let value = json!({
+            "id": 1,
+            "site": "https://example.com",
+            "score": [1, 2.5],
+            "owner": [{"name": "a"}, {"name": null, "tags": []}],
+            "mixed": [1, {"a": 1}]
+        });
+        let doc = json_schema(&schema(&value, 5));
+        assert_eq!(doc["$schema"], JSON_SCHEMA_DIALECT);
+        assert_eq!(doc["type"], "object");
+        assert_eq!(
+            doc["required"],
+            json!(["id", "mixed", "owner", "score", "site"])
+        );
+        let props = &doc["properties"];
+        assert_eq!(props["site"], json!({"type": "string", "format": "uri"}));
+        // int | float is just a number
+        assert_eq!(props["score"]["items"], json!({"type": "number"}));
+        let owner = &props["owner"]["items"];
+        assert_eq!(
+            owner["properties"]["name"]["type"],
+            json!(["string", "null"])
+        );
+        assert_eq!(owner["properties"]["tags"], json!({"type": "array"}));
+        assert_eq!(owner["required"], json!(["name"]));
+        assert_eq!(
+            props["mixed"]["items"]["anyOf"][0],
+            json!({"type": "integer"})
+        );
+        assert_eq!(props["mixed"]["items"]["anyOf"][1]["type"], "object");
+
+        let truncated = json_schema(&schema(&json!({"a": {"b": 1}}), 0));
+        assert_eq!(truncated["properties"]["a"], json!({}));
+    }
+
     #[test]
     fn test_extract_schema_array() {
         let json: Value = serde_json::from_str(r#"{"items": [1, 2, 3]}"#).unwrap();
-        let schema = extract_schema(&json, 0, 5);
+        let schema = extract_schema(&json, 5);
         assert!(schema.contains("items"));
         assert!(schema.contains("(3)"));
     }
+
+    #[test]
+    fn test_heterogeneous_array_optional_and_union() {
+        let json: Value = serde_json::from_str(
+            r#"[{"id": 1, "email": "a@b.c"}, {"id": "x2"}, {"id": 3, "email": null}]"#,
+        )
+        .unwrap();
+        let schema = extract_schema(&json, 5);
+        assert!(schema.contains("email?: string | null"), "{}", schema);
+        assert!(schema.contains("id: int | string"), "{}", schema);
+        assert!(schema.ends_with("(3)"));
+    }
+
+    #[test]
+    fn test_homogeneous_array_has_no_markers() {
+        let json: Value =
+            serde_json::from_str(r#"{"users": [{"name": "a"}, {"name": "b"}]}"#).unwrap();
+        let schema = extract_schema(&json, 5);
+        assert!(schema.contains("name: string"));
+        assert!(!schema.contains('?'));
+        assert!(!schema.contains('|'));
+    }
+
+    #[test]
+    fn test_schema_value_union_and_optional() {
+        let json: Value = serde_json::from_str(r#"[{"a": 1}, {"a": 1.5, "b": true}]"#).unwrap();
+        let schema = extract_schema_value(&json, 5);
+        let keys = &schema["items"]["keys"];
+        assert_eq!(keys["a"]["type"], "union");
+        assert_eq!(keys["a"]["variants"][1]["type"], "float");
+        assert_eq!(keys["b"]["optional"], true);
+        assert!(keys["a"].get("optional").is_none());
+    }
+
+    #[test]
+    fn test_merged_strings_keep_longest() {
+        let long = "x".repeat(80);
+        let json: Value = json!(["short", long]);
+        assert_eq!(extract_schema(&json, 5), "[string[80]] (2)");
+    }
+
+    fn stream_text(input: &str) -> String {
+        stream_schema(input.as_bytes(), 5, false, "text").unwrap().0
+    }
+
+    #[test]
+    fn test_stream_matches_in_memory_schema() {
+        let input = r#"{"users": [{"id": 1, "email": "a@b.c"}, {"id": "x"}], "meta": {"deep": {"er": [1]}}}"#;
+        let value: Value = serde_json::from_str(input).unwrap();
+        for depth in [1, 5] {
+            let (streamed, bytes) = stream_schema(input.as_bytes(), depth, false, "text").unwrap();
+            assert_eq!(streamed, extract_schema(&value, depth));
+            assert_eq!(bytes, input.len() as u64);
+        }
+        let (streamed, _) = stream_schema(input.as_bytes(), 5, false, "json").unwrap();
+        let expected = serde_json::to_string_pretty(&extract_schema_value(&value, 5)).unwrap();
+        assert_eq!(streamed, expected);
+    }
+
+    #[test]
+    fn test_stream_ndjson_records() {
+        let input = "{\"id\": 1, \"tag\": \"a\"}\n{\"id\": 2}\n\n{\"id\": 3.5}\n";
+        let out = stream_text(input);
+        assert!(out.contains("id: int | float"), "{}", out);
+        assert!(out.contains("tag?: string"), "{}", out);
+        assert!(out.ends_with("(3)"), "{}", out);
+    }
+
+    #[test]
+    fn test_stream_long_array_counts_past_sample() {
+        let input = format!("[{}]", vec!["1"; ARRAY_SAMPLE + 5].join(","));
+        assert_eq!(stream_text(&input), format!("[int] ({})", ARRAY_SAMPLE + 5));
+    }
+
+    #[test]
+    fn test_stream_errors() {
+        assert!(stream_schema("".as_bytes(), 5, false, "text").is_err());
+        assert!(stream_schema("{\"a\": ".as_bytes(), 5, false, "text").is_err());
+        assert!(stream_schema("{\"a\": 1} oops".as_bytes(), 5, false, "text").is_err());
+    }
+
+    #[test]
+    fn test_is_ndjson() {
+        assert!(is_ndjson(Path::new("export.ndjson")));
+        assert!(is_ndjson(Path::new("logs/events.jsonl")));
+        assert!(!is_ndjson(Path::new("package.json")));
+    }
+
+    #[test]
+    fn test_is_url() {
+        assert!(is_url("https://api.example.com/foo"));
+        assert!(is_url("http://localhost:8080/x.json"));
+        assert!(!is_url("data/https.json"));
+        assert!(!is_url("-"));
+    }
+
+    #[test]
+    fn test_fetch_url_reports_failure() {
+        // Nothing listens on port 9: the request fails fast, error names the URL
+        let err = fetch_url("http://127.0.0.1:9/x.json").unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("Failed to fetch http://127.0.0.1:9/x.json"));
+    }
+
+    #[test]
+    fn test_parse_path() {
+        assert_eq!(
+            parse_path(".items[3].name").unwrap(),
+            vec![
+                Segment::Key("items".to_string()),
+                Segment::Index(3),
+                Segment::Key("name".to_string())
+            ]
+        );
+        assert_eq!(
+            parse_path(r#"data["a.b"][-1]"#).unwrap(),
+            vec![
+                Segment::Key("data".to_string()),
+                Segment::Key("a.b".to_string()),
+                Segment::Index(-1)
+            ]
+        );
+        assert!(parse_path(".items[x]").is_err());
+    }
+
+    #[test]
+    fn test_select_dotted_and_pointer() {
+        let json: Value =
+            serde_json::from_str(r#"{"items": [{"name": "a"}, {"name": "b", "tags": [1]}]}"#)
+                .unwrap();
+        assert_eq!(select(&json, ".items[1].name").unwrap(), "b");
+        assert_eq!(select(&json, "items[-1].name").unwrap(), "b");
+        assert_eq!(select(&json, "/items/0/name").unwrap(), "a");
+        assert_eq!(select(&json, ".").unwrap(), &json);
+    }
+
+    #[test]
+    fn test_select_errors_name_resolved_prefix() {
+        let json: Value = serde_json::from_str(r#"{"items": [{"name": "a"}]}"#).unwrap();
+        let err = select(&json, ".items[5]").unwrap_err().to_string();
+        assert_eq!(err, "Index [5] out of range at .items (1 elements)");
+        let err = select(&json, ".items[0].id").unwrap_err().to_string();
+        assert_eq!(err, "No key 'id' at .items[0] (keys: name)");
+        let err = select(&json, ".items.name").unwrap_err().to_string();
+        assert_eq!(err, "Cannot take key 'name' of array at .items");
+    }
+
+    #[test]
+    fn test_render_path_schema_and_values() {
+        let content = r#"{"data": {"user": {"id": 7, "roles": ["admin"]}}}"#;
+        let schema = render(content, 5, Some(".data.user"), false, false, "text").unwrap();
+        assert!(schema.contains("id: int"));
+        assert!(!schema.contains("data"));
+        let values = render(content, 5, Some(".data.user.roles"), true, false, "text").unwrap();
+        assert_eq!(values, "[\n  \"admin\"\n]");
+    }
 }
@@ -0,0 +1,460 @@
+//! `rtk json diff`: structural comparison of two JSON documents. Keys
+//! added, removed or changed are listed as a tree of the paths leading to
+//! them, instead of a line diff of the pretty-printed documents.
+//!
+//! With `--schema-only` both documents are reduced to their inferred
+//! schemas first (see [`json_cmd::schema`]), so only new or missing keys
+//! and type changes remain; arrays compare their merged item schemas.
+
+use crate::json_cmd::{self, SchemaNode};
+use crate::tracking;
+use crate::utils::truncate;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// Changes listed in text output
+const MAX_CHANGES: usize = 50;
+
+/// Values shown in a change are cut to this many chars
+const MAX_VALUE_CHARS: usize = 40;
+
+/// Step from a value to one of its children
+#[derive(Debug, Clone, PartialEq)]
+enum Step {
+    Key(String),
+    Index(usize),
+    /// Item schema of an array (schema-only)
+    Items,
+}
+
+impl Step {
+    fn label(&self) -> String {
+        match self {
+            Step::Key(key) => key.clone(),
+            Step::Index(i) => format!("[{}]", i),
+            Step::Items => "[]".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum ChangeKind {
+    Added,
+    Removed,
+    /// Different type at the same path
+    Type,
+    /// Same type, different value
+    Value,
+    /// Array length; its elements are reported below it
+    Length,
+}
+
+/// One difference between the documents
+#[derive(Debug, PartialEq, Serialize)]
+struct Change {
+    /// Dotted path, as accepted by `rtk json --path`
+    path: String,
+    #[serde(skip)]
+    steps: Vec<Step>,
+    change: ChangeKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    from: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    to: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct DiffReport {
+    added: usize,
+    removed: usize,
+    changed: usize,
+    changes: Vec<Change>,
+}
+
+/// Compare two documents: files, `-` for stdin (one side only) or URLs
+pub fn run(
+    old: &str,
+    new: &str,
+    schema_only: bool,
+    max_depth: usize,
+    format: &str,
+    verbose: u8,
+) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    if verbose > 0 {
+        eprintln!("Comparing JSON: {} vs {}", old, new);
+    }
+    if old == "-" && new == "-" {
+        anyhow::bail!("Only one side can be read from stdin");
+    }
+
+    let old_text = json_cmd::read_source(old)?;
+    let new_text = json_cmd::read_source(new)?;
+    let parse = |text: &str, name: &str| -> Result<Value> {
+        serde_json::from_str(text).with_context(|| format!("Failed to parse JSON: {}", name))
+    };
+    let (old_value, new_value) = (parse(&old_text, old)?, parse(&new_text, new)?);
+
+    let report = if schema_only {
+        let shape = |value: &Value| shape(&json_cmd::schema(value, max_depth));
+        compare(&shape(&old_value), &shape(&new_value), true)
+    } else {
+        compare(&old_value, &new_value, false)
+    };
+
+    let output = match format {
+        "json" => serde_json::to_string_pretty(&report)?,
+        _ => format_report(old, new, &report, schema_only),
+    };
+    println!("{}", output);
+
+    timer.track(
+        &format!("diff {} {}", old, new),
+        "rtk json diff",
+        &format!("{}\n---\n{}", old_text, new_text),
+        &output,
+    );
+    Ok(())
+}
+
+/// A schema as a value: objects keep their keys, arrays hold their item
+/// schema, and everything else becomes its type name
+fn shape(node: &SchemaNode) -> Value {
+    match node {
+        SchemaNode::Object { keys } => Value::Object(
+            keys.iter()
+                .map(|(key, field)| (key.clone(), shape(&field.schema)))
+                .collect(),
+        ),
+        SchemaNode::Array { items, .. } => {
+            Value::Array(items.iter().map(|items| shape(items)).collect())
+        }
+        other => Value::String(type_name(other)),
+    }
+}
+
+fn type_name(node: &SchemaNode) -> String {
+    match node {
+        SchemaNode::Null => "null".to_string(),
+        SchemaNode::Bool => "bool".to_string(),
+        SchemaNode::Int => "int".to_string(),
+        SchemaNode::Float => "float".to_string(),
+        SchemaNode::String { .. } => "string".to_string(),
+        SchemaNode::Array { .. } => "array".to_string(),
+        SchemaNode::Object { .. } => "object".to_string(),
+        SchemaNode::Union { variants } => variants
+            .iter()
+            .map(type_name)
+            .collect::<Vec<_>>()
+            .join(" | "),
+        SchemaNode::Truncated => "...".to_string(),
+    }
+}
+
+fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "bool",
+        Value::Number(n) if n.is_f64() => "float",
+        Value::Number(_) => "int",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Type of a value, or the type name a shape leaf carries
+fn type_of(value: &Value, shapes: bool) -> String {
+    match value {
+        Value::String(name) if shapes => name.clone(),
+        other => kind(other).to_string(),
+    }
+}
+
+/// Short description of an added or removed value
+fn summary(value: &Value, shapes: bool) -> String {
+    match value {
+        Value::Object(map) => format!("object{{{}}}", map.len()),
+        Value::Array(_) if shapes => "array".to_string(),
+        Value::Array(items) => format!("array[{}]", items.len()),
+        Value::String(name) if shapes => name.clone(),
+        scalar => truncate(&scalar.to_string(), MAX_VALUE_CHARS),
+    }
+}
+
+/// Compare `old` and `new`; `shapes` when both are [`shape`]s
+fn compare(old: &Value, new: &Value, shapes: bool) -> DiffReport {
+    let mut changes = Vec::new();
+    diff(old, new, shapes, &mut Vec::new(), &mut changes);
+    let count = |kinds: &[ChangeKind]| changes.iter().filter(|c| kinds.contains(&c.change)).count();
+    DiffReport {
+        added: count(&[ChangeKind::Added]),
+        removed: count(&[ChangeKind::Removed]),
+        changed: count(&[ChangeKind::Type, ChangeKind::Value]),
+        changes,
+    }
+}
+
+fn diff(old: &Value, new: &Value, shapes: bool, steps: &mut Vec<Step>, out: &mut Vec<Change>) {
+    match (old, new) {
+        (Value::Object(a), Value::Object(b)) => diff_objects(a, b, shapes, steps, out),
+        (Value::Array(a), Value::Array(b)) => {
+            if !shapes && a.len() != b.len() {
+                out.push(change(steps, ChangeKind::Length, a.len(), b.len()));
+            }
+            for i in 0..a.len().max(b.len()) {
+                steps.push(if shapes { Step::Items } else { Step::Index(i) });
+                match (a.get(i), b.get(i)) {
+                    (Some(x), Some(y)) => diff(x, y, shapes, steps, out),
+                    (Some(x), None) => out.push(side(steps, ChangeKind::Removed, x, shapes)),
+                    (None, Some(y)) => out.push(side(steps, ChangeKind::Added, y, shapes)),
+                    (None, None) => {}
+                }
+                steps.pop();
+            }
+        }
+        _ => {
+            let (from, to) = (type_of(old, shapes), type_of(new, shapes));
+            if from != to {
+                out.push(change(steps, ChangeKind::Type, from, to));
+            } else if !shapes && old != new {
+                let short = |v: &Value| truncate(&v.to_string(), MAX_VALUE_CHARS);
+                out.push(change(steps, ChangeKind::Value, short(old), short(new)));
+            }
+        }
+    }
+}
+
+/// Keys in the order of the old document, then keys only in the new one
+fn diff_objects(
+    a: &Map<String, Value>,
+    b: &Map<String, Value>,
+    shapes: bool,
+    steps: &mut Vec<Step>,
+    out: &mut Vec<Change>,
+) {
+    for (key, x) in a {
+        steps.push(Step::Key(key.clone()));
+        match b.get(key) {
+            Some(y) => diff(x, y, shapes, steps, out),
+            None => out.push(side(steps, ChangeKind::Removed, x, shapes)),
+        }
+        steps.pop();
+    }
+    for (key, y) in b.iter().filter(|(key, _)| !a.contains_key(*key)) {
+        steps.push(Step::Key(key.clone()));
+        out.push(side(steps, ChangeKind::Added, y, shapes));
+        steps.pop();
+    }
+}
+
+fn change(steps: &[Step], kind: ChangeKind, from: impl ToString, to: impl ToString) -> Change {
+    Change {
+        path: dotted_path(steps),
+        steps: steps.to_vec(),
+        change: kind,
+        from: Some(from.to_string()),
+        to: Some(to.to_string()),
+    }
+}
+
+/// Value present on one side only
+fn side(steps: &[Step], kind: ChangeKind, value: &Value, shapes: bool) -> Change {
+    let summary = Some(summary(value, shapes));
+    let (from, to) = match kind {
+        ChangeKind::Removed => (summary, None),
+        _ => (None, summary),
+    };
+    Change {
+        path: dotted_path(steps),
+        steps: steps.to_vec(),
+        change: kind,
+        from,
+        to,
+    }
+}
+
+/// `.items[3].name`, with odd keys quoted: `.["a.b"]`
+fn dotted_path(steps: &[Step]) -> String {
+    if steps.is_empty() {
+        return ".".to_string();
+    }
+    steps
+        .iter()
+        .map(|step| match step {
+            Step::Key(key) if key.is_empty() || key.contains(|c: char| ".[]\" ".contains(c)) => {
+                format!(".[{:?}]", key)
+            }
+            Step::Key(key) => format!(".{}", key),
+            other => other.label(),
+        })
+        .collect()
+}
+
+fn format_report(old: &str, new: &str, report: &DiffReport, schema_only: bool) -> String {
+    if report.changes.is_empty() {
+        return if schema_only {
+            "✅ Same structure".to_string()
+        } else {
+            "✅ Documents are identical".to_string()
+        };
+    }
+
+    let mut out = format!(
+        "📊 {} → {}{}\n",
+        old,
+        new,
+        if schema_only { " (schema)" } else { "" }
+    );
+    out.push_str(&format!(
+        "   +{} added, -{} removed, ~{} changed\n\n",
+        report.added, report.removed, report.changed
+    ));
+
+    // Parents already printed as headers
+    let mut open: Vec<&Step> = Vec::new();
+    for change in report.changes.iter().take(MAX_CHANGES) {
+        let (last, parents) = match change.steps.split_last() {
+            Some((last, parents)) => (last.label(), parents),
+            None => ("root".to_string(), &[][..]),
+        };
+        let common = open
+            .iter()
+            .zip(parents)
+            .take_while(|(a, b)| **a == *b)
+            .count();
+        open.truncate(common);
+        for step in &parents[common..] {
+            out.push_str(&format!("{}{}:\n", indent(open.len()), step.label()));
+            open.push(step);
+        }
+
+        let from = change.from.as_deref().unwrap_or_default();
+        let to = change.to.as_deref().unwrap_or_default();
+        let line = match change.change {
+            ChangeKind::Added => format!("+ {}: {}", last, to),
+            ChangeKind::Removed => format!("- {}: {}", last, from),
+            ChangeKind::Type | ChangeKind::Value => format!("~ {}: {} → {}", last, from, to),
+            ChangeKind::Length => format!("{}: [{} → {}]", last, from, to),
+        };
+        out.push_str(&format!("{}{}\n", indent(open.len()), line));
+        if change.change == ChangeKind::Length {
+            open.extend(change.steps.last());
+        }
+    }
+    if report.changes.len() > MAX_CHANGES {
+        out.push_str(&format!(
+            "... +{} more changes\n",
+            report.changes.len() - MAX_CHANGES
+        ));
+    }
+    out.trim_end().to_string()
+}
+
+fn indent(depth: usize) -> String {
+    "  ".repeat(depth + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_compare_values() {
+        let old = json!({"user": {"name": "Bob", "age": 30, "nick": "b"}, "tags": ["a"]});
+        let new =
+            json!({"user": {"name": "Robert", "age": "30", "email": "r@x.io"}, "tags": ["a", "b"]});
+        let report = compare(&old, &new, false);
+        assert_eq!((report.added, report.removed, report.changed), (2, 1, 2));
+
+        let paths: Vec<(&str, ChangeKind)> = report
+            .changes
+            .iter()
+            .map(|c| (c.path.as_str(), c.change))
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                (".user.name", ChangeKind::Value),
+                (".user.age", ChangeKind::Type),
+                (".user.nick", ChangeKind::Removed),
+                (".user.email", ChangeKind::Added),
+                (".tags", ChangeKind::Length),
+                (".tags[1]", ChangeKind::Added),
+            ]
+        );
+        assert_eq!(report.changes[1].from.as_deref(), Some("int"));
+        assert_eq!(report.changes[1].to.as_deref(), Some("string"));
+    }
+
+    #[test]
+    fn test_compare_schema_only_ignores_values() {
+        let old = json!({"items": [{"id": 1, "name": "a"}], "total": 1});
+        let new = json!({"items": [{"id": 2, "name": "b"}, {"id": 3, "name": null, "tag": "x"}], "total": 2});
+        let shape = |v: &Value| shape(&json_cmd::schema(v, 5));
+        let report = compare(&shape(&old), &shape(&new), true);
+        let paths: Vec<(&str, ChangeKind)> = report
+            .changes
+            .iter()
+            .map(|c| (c.path.as_str(), c.change))
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                (".items[].name", ChangeKind::Type),
+                (".items[].tag", ChangeKind::Added),
+            ]
+        );
+        assert_eq!(report.changes[0].to.as_deref(), Some("string | null"));
+
+        let same = compare(
+            &shape(&old),
+            &shape(&json!({"items": [], "total": 9})),
+            true,
+        );
+        assert_eq!(same.removed, 1);
+        assert!(compare(&shape(&old), &shape(&old), true).changes.is_empty());
+    }
+
+    #[test]
+    fn test_dotted_path() {
+        assert_eq!(dotted_path(&[]), ".");
+        assert_eq!(
+            dotted_path(&[
+                Step::Key("items".into()),
+                Step::Index(3),
+                Step::Key("a.b".into())
+            ]),
+            ".items[3].[\"a.b\"]"
+        );
+    }
+
+    #[test]
+    fn test_format_report_tree() {
+        let old = json!({"user": {"name": "Bob", "nick": "b"}, "tags": [1, 2], "v": 1});
+        let new = json!({"user": {"name": "Robert"}, "tags": [1, 3, 4], "v": 1});
+        let report = compare(&old, &new, false);
+        let expected = "📊 a.json → b.json
+   +1 added, -1 removed, ~2 changed
+
+  user:
+    ~ name: \"Bob\" → \"Robert\"
+    - nick: \"b\"
+  tags: [2 → 3]
+    ~ [1]: 2 → 3
+    + [2]: 4";
+        assert_eq!(format_report("a.json", "b.json", &report, false), expected);
+
+        let same = compare(&old, &old, false);
+        assert_eq!(
+            format_report("a.json", "b.json", &same, false),
+            "✅ Documents are identical"
+        );
+        let root = compare(&json!([]), &json!({}), false);
+        assert!(format_report("a", "b", &root, false).ends_with("  ~ root: array → object"));
+    }
+}
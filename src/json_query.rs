@@ -0,0 +1,706 @@
+//! `rtk json query`: a jq subset for everyday extraction. Each result is
+//! printed as compact JSON on its own line, cut to the usual line length,
+//! and the number of results is capped like grep's.
+//!
+//! Supported: `.`, `.key`, `."odd key"`, `.[n]` (negative from the end),
+//! `.[]` over arrays and objects, `|`, `,`, `select(...)` with `==`, `!=`,
+//! `<`, `<=`, `>`, `>=`, `and`, `or`, object construction (`{id, mail:
+//! .email}`), literals, parentheses and the `length`, `keys` and `not`
+//! builtins. Several documents in the input (NDJSON) are each run through
+//! the filter, as jq does.
+
+use crate::json_cmd;
+use crate::tracking;
+use crate::utils::truncate;
+use anyhow::{Context, Result};
+use serde_json::{Map, Value};
+use std::cmp::Ordering;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/// Parsed filter
+#[derive(Debug, PartialEq)]
+enum Filter {
+    Identity,
+    Key(String),
+    Index(i64),
+    /// `.[]`: every element (or object value)
+    Iterate,
+    Literal(Value),
+    Pipe(Box<Filter>, Box<Filter>),
+    Comma(Box<Filter>, Box<Filter>),
+    Compare(Box<Filter>, Op, Box<Filter>),
+    And(Box<Filter>, Box<Filter>),
+    Or(Box<Filter>, Box<Filter>),
+    Select(Box<Filter>),
+    Object(Vec<(String, Filter)>),
+    Length,
+    Keys,
+    Not,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Dot,
+    Ident(String),
+    Str(String),
+    Num(f64),
+    Op(Op),
+    Punct(char),
+}
+
+fn tokenize(query: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = query.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '.' => {
+                chars.next();
+                tokens.push(Token::Dot);
+            }
+            '"' => {
+                chars.next();
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => text.push('\n'),
+                            Some('t') => text.push('\t'),
+                            Some(other) => text.push(other),
+                            None => anyhow::bail!("Unterminated string in query"),
+                        },
+                        Some(other) => text.push(other),
+                        None => anyhow::bail!("Unterminated string in query"),
+                    }
+                }
+                tokens.push(Token::Str(text));
+            }
+            '=' | '!' | '<' | '>' => {
+                chars.next();
+                let eq = chars.next_if_eq(&'=').is_some();
+                let op = match (c, eq) {
+                    ('=', true) => Op::Eq,
+                    ('!', true) => Op::Ne,
+                    ('<', false) => Op::Lt,
+                    ('<', true) => Op::Le,
+                    ('>', false) => Op::Gt,
+                    ('>', true) => Op::Ge,
+                    _ => anyhow::bail!("Unexpected '{}' in query (comparisons use ==, !=)", c),
+                };
+                tokens.push(Token::Op(op));
+            }
+            '|' | ',' | ':' | '(' | ')' | '[' | ']' | '{' | '}' => {
+                chars.next();
+                tokens.push(Token::Punct(c));
+            }
+            c if c.is_ascii_digit() || c == '-' => {
+                let mut text = String::new();
+                text.push(c);
+                chars.next();
+                while let Some(c) = chars.next_if(|c| c.is_ascii_digit() || *c == '.') {
+                    text.push(c);
+                }
+                let n = text
+                    .parse()
+                    .with_context(|| format!("Invalid number '{}' in query", text))?;
+                tokens.push(Token::Num(n));
+            }
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut ident = String::new();
+                while let Some(c) = chars.next_if(|c| c.is_alphanumeric() || *c == '_') {
+                    ident.push(c);
+                }
+                tokens.push(Token::Ident(ident));
+            }
+            other => anyhow::bail!("Unexpected '{}' in query", other),
+        }
+    }
+    Ok(tokens)
+}
+
+/// Recursive descent over the tokens; precedence from loosest to
+/// tightest: `|`, `,`, `or`, `and`, comparisons, suffixes
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(&Token::Punct(c)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<()> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            anyhow::bail!("Expected '{}' in query, found {}", c, self.found())
+        }
+    }
+
+    fn eat_word(&mut self, word: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Ident(w)) if w == word) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn found(&self) -> String {
+        match self.peek() {
+            None => "end of query".to_string(),
+            Some(Token::Dot) => "'.'".to_string(),
+            Some(Token::Ident(w)) => format!("'{}'", w),
+            Some(Token::Str(s)) => format!("\"{}\"", s),
+            Some(Token::Num(n)) => format!("{}", n),
+            Some(Token::Op(op)) => format!("{:?}", op),
+            Some(Token::Punct(c)) => format!("'{}'", c),
+        }
+    }
+
+    fn pipe(&mut self) -> Result<Filter> {
+        let mut left = self.comma()?;
+        while self.eat('|') {
+            left = Filter::Pipe(Box::new(left), Box::new(self.comma()?));
+        }
+        Ok(left)
+    }
+
+    fn comma(&mut self) -> Result<Filter> {
+        let mut left = self.or()?;
+        while self.eat(',') {
+            left = Filter::Comma(Box::new(left), Box::new(self.or()?));
+        }
+        Ok(left)
+    }
+
+    fn or(&mut self) -> Result<Filter> {
+        let mut left = self.and()?;
+        while self.eat_word("or") {
+            left = Filter::Or(Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Filter> {
+        let mut left = self.compare()?;
+        while self.eat_word("and") {
+            left = Filter::And(Box::new(left), Box::new(self.compare()?));
+        }
+        Ok(left)
+    }
+
+    fn compare(&mut self) -> Result<Filter> {
+        let left = self.postfix()?;
+        if let Some(&Token::Op(op)) = self.peek() {
+            self.pos += 1;
+            let right = self.postfix()?;
+            return Ok(Filter::Compare(Box::new(left), op, Box::new(right)));
+        }
+        Ok(left)
+    }
+
+    /// A term followed by any number of `.key`, `[n]`, `[]`, `["key"]`
+    fn postfix(&mut self) -> Result<Filter> {
+        let mut filter = self.term()?;
+        loop {
+            let step = match self.peek() {
+                Some(Token::Dot) => {
+                    self.pos += 1;
+                    match self.next() {
+                        Some(Token::Ident(key)) | Some(Token::Str(key)) => Filter::Key(key),
+                        Some(Token::Punct('[')) => self.bracket()?,
+                        _ => anyhow::bail!("Expected a key after '.' in query"),
+                    }
+                }
+                Some(Token::Punct('[')) => {
+                    self.pos += 1;
+                    self.bracket()?
+                }
+                _ => return Ok(filter),
+            };
+            filter = chain(filter, step);
+        }
+    }
+
+    /// Inside `[...]`, the opening bracket already consumed
+    fn bracket(&mut self) -> Result<Filter> {
+        let step = match self.next() {
+            Some(Token::Punct(']')) => return Ok(Filter::Iterate),
+            Some(Token::Num(n)) if n.fract() == 0.0 => Filter::Index(n as i64),
+            Some(Token::Str(key)) => Filter::Key(key),
+            _ => anyhow::bail!("Expected an index, a quoted key or ']' in '[...]'"),
+        };
+        self.expect(']')?;
+        Ok(step)
+    }
+
+    fn term(&mut self) -> Result<Filter> {
+        match self.next() {
+            Some(Token::Dot) => match self.peek() {
+                Some(Token::Ident(_)) | Some(Token::Str(_)) => match self.next() {
+                    Some(Token::Ident(key)) | Some(Token::Str(key)) => Ok(Filter::Key(key)),
+                    _ => unreachable!(),
+                },
+                _ => Ok(Filter::Identity),
+            },
+            Some(Token::Str(s)) => Ok(Filter::Literal(Value::String(s))),
+            Some(Token::Num(n)) => Ok(Filter::Literal(number(n))),
+            Some(Token::Punct('(')) => {
+                let inner = self.pipe()?;
+                self.expect(')')?;
+                Ok(inner)
+            }
+            Some(Token::Punct('{')) => self.object(),
+            Some(Token::Ident(word)) => match word.as_str() {
+                "true" => Ok(Filter::Literal(Value::Bool(true))),
+                "false" => Ok(Filter::Literal(Value::Bool(false))),
+                "null" => Ok(Filter::Literal(Value::Null)),
+                "length" => Ok(Filter::Length),
+                "keys" => Ok(Filter::Keys),
+                "not" => Ok(Filter::Not),
+                "select" => {
+                    self.expect('(')?;
+                    let condition = self.pipe()?;
+                    self.expect(')')?;
+                    Ok(Filter::Select(Box::new(condition)))
+                }
+                other => anyhow::bail!(
+                    "Unsupported function '{}' (rtk json query is a jq subset: select, length, keys, not)",
+                    other
+                ),
+            },
+            _ => {
+                self.pos -= 1;
+                anyhow::bail!("Unexpected {} in query", self.found())
+            }
+        }
+    }
+
+    /// `{a, "b c": .x, d: .y | length}`, the opening brace already consumed
+    fn object(&mut self) -> Result<Filter> {
+        let mut entries = Vec::new();
+        if self.eat('}') {
+            return Ok(Filter::Object(entries));
+        }
+        loop {
+            let key = match self.next() {
+                Some(Token::Ident(key)) | Some(Token::Str(key)) => key,
+                _ => anyhow::bail!("Expected a key in object construction"),
+            };
+            let value = if self.eat(':') {
+                // A value stops at ',' so that the next entry can follow
+                let mut value = self.or()?;
+                while self.eat('|') {
+                    value = Filter::Pipe(Box::new(value), Box::new(self.or()?));
+                }
+                value
+            } else {
+                Filter::Key(key.clone())
+            };
+            entries.push((key, value));
+            if self.eat('}') {
+                return Ok(Filter::Object(entries));
+            }
+            self.expect(',')?;
+        }
+    }
+}
+
+fn chain(left: Filter, step: Filter) -> Filter {
+    match left {
+        Filter::Identity => step,
+        left => Filter::Pipe(Box::new(left), Box::new(step)),
+    }
+}
+
+fn number(n: f64) -> Value {
+    if n.fract() == 0.0 && n.abs() < i64::MAX as f64 {
+        Value::from(n as i64)
+    } else {
+        serde_json::Number::from_f64(n).map_or(Value::Null, Value::Number)
+    }
+}
+
+/// Parse a query into a filter
+fn parse(query: &str) -> Result<Filter> {
+    let mut parser = Parser {
+        tokens: tokenize(query)?,
+        pos: 0,
+    };
+    if parser.peek().is_none() {
+        return Ok(Filter::Identity);
+    }
+    let filter = parser.pipe()?;
+    if parser.peek().is_some() {
+        anyhow::bail!("Unexpected {} in query", parser.found());
+    }
+    Ok(filter)
+}
+
+/// Run `filter` on `input`, returning every output
+fn eval(filter: &Filter, input: &Value) -> Result<Vec<Value>> {
+    Ok(match filter {
+        Filter::Identity => vec![input.clone()],
+        Filter::Key(key) => match input {
+            Value::Object(map) => vec![map.get(key).cloned().unwrap_or(Value::Null)],
+            Value::Null => vec![Value::Null],
+            other => anyhow::bail!("Cannot take key '{}' of {}", key, kind(other)),
+        },
+        Filter::Index(i) => match input {
+            Value::Array(arr) => {
+                let idx = if *i < 0 { arr.len() as i64 + i } else { *i };
+                let item = usize::try_from(idx).ok().and_then(|idx| arr.get(idx));
+                vec![item.cloned().unwrap_or(Value::Null)]
+            }
+            Value::Null => vec![Value::Null],
+            other => anyhow::bail!("Cannot take index [{}] of {}", i, kind(other)),
+        },
+        Filter::Iterate => match input {
+            Value::Array(arr) => arr.clone(),
+            Value::Object(map) => map.values().cloned().collect(),
+            other => anyhow::bail!("Cannot iterate over {}", kind(other)),
+        },
+        Filter::Literal(value) => vec![value.clone()],
+        Filter::Pipe(left, right) => {
+            let mut out = Vec::new();
+            for value in eval(left, input)? {
+                out.extend(eval(right, &value)?);
+            }
+            out
+        }
+        Filter::Comma(left, right) => {
+            let mut out = eval(left, input)?;
+            out.extend(eval(right, input)?);
+            out
+        }
+        Filter::Compare(left, op, right) => {
+            let mut out = Vec::new();
+            for r in eval(right, input)? {
+                for l in eval(left, input)? {
+                    out.push(Value::Bool(compare(&l, *op, &r)));
+                }
+            }
+            out
+        }
+        Filter::And(left, right) | Filter::Or(left, right) => {
+            let is_and = matches!(filter, Filter::And(..));
+            let mut out = Vec::new();
+            for l in eval(left, input)? {
+                // Short-circuit like jq: the right side only runs when needed
+                if truthy(&l) != is_and {
+                    out.push(Value::Bool(!is_and));
+                    continue;
+                }
+                for r in eval(right, input)? {
+                    out.push(Value::Bool(truthy(&r)));
+                }
+            }
+            out
+        }
+        Filter::Select(condition) => {
+            let keep = eval(condition, input)?.iter().any(truthy);
+            if keep {
+                vec![input.clone()]
+            } else {
+                Vec::new()
+            }
+        }
+        Filter::Object(entries) => {
+            // Keys with several outputs produce one object per combination
+            let mut objects = vec![Map::new()];
+            for (key, value) in entries {
+                let values = eval(value, input)?;
+                objects = objects
+                    .into_iter()
+                    .flat_map(|object| {
+                        values.iter().map(move |v| {
+                            let mut object = object.clone();
+                            object.insert(key.clone(), v.clone());
+                            object
+                        })
+                    })
+                    .collect();
+            }
+            objects.into_iter().map(Value::Object).collect()
+        }
+        Filter::Length => vec![match input {
+            Value::Null => Value::from(0),
+            Value::Bool(_) => anyhow::bail!("bool has no length"),
+            Value::Number(n) => number(n.as_f64().unwrap_or(0.0).abs()),
+            Value::String(s) => Value::from(s.chars().count()),
+            Value::Array(arr) => Value::from(arr.len()),
+            Value::Object(map) => Value::from(map.len()),
+        }],
+        Filter::Keys => vec![match input {
+            Value::Object(map) => {
+                let mut keys: Vec<&String> = map.keys().collect();
+                keys.sort();
+                Value::Array(keys.into_iter().cloned().map(Value::String).collect())
+            }
+            Value::Array(arr) => Value::Array((0..arr.len()).map(Value::from).collect()),
+            other => anyhow::bail!("{} has no keys", kind(other)),
+        }],
+        Filter::Not => vec![Value::Bool(!truthy(input))],
+    })
+}
+
+fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "bool",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// `false` and `null` are false, everything else is true
+fn truthy(value: &Value) -> bool {
+    !matches!(value, Value::Null | Value::Bool(false))
+}
+
+fn compare(left: &Value, op: Op, right: &Value) -> bool {
+    let ordering = order(left, right);
+    match op {
+        Op::Eq => ordering == Ordering::Equal,
+        Op::Ne => ordering != Ordering::Equal,
+        Op::Lt => ordering == Ordering::Less,
+        Op::Le => ordering != Ordering::Greater,
+        Op::Gt => ordering == Ordering::Greater,
+        Op::Ge => ordering != Ordering::Less,
+    }
+}
+
+/// jq's ordering: null < false < true < numbers < strings < arrays < objects
+fn order(left: &Value, right: &Value) -> Ordering {
+    fn rank(value: &Value) -> u8 {
+        match value {
+            Value::Null => 0,
+            Value::Bool(false) => 1,
+            Value::Bool(true) => 2,
+            Value::Number(_) => 3,
+            Value::String(_) => 4,
+            Value::Array(_) => 5,
+            Value::Object(_) => 6,
+        }
+    }
+    match (left, right) {
+        (Value::Number(a), Value::Number(b)) => {
+            let (a, b) = (a.as_f64().unwrap_or(0.0), b.as_f64().unwrap_or(0.0));
+            a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+        }
+        (Value::String(a), Value::String(b)) => a.cmp(b),
+        (Value::Array(a), Value::Array(b)) => a
+            .iter()
+            .zip(b)
+            .map(|(x, y)| order(x, y))
+            .find(|o| o.is_ne())
+            .unwrap_or_else(|| a.len().cmp(&b.len())),
+        (Value::Object(_), Value::Object(_)) => {
+            if left == right {
+                Ordering::Equal
+            } else {
+                left.to_string().cmp(&right.to_string())
+            }
+        }
+        _ => rank(left).cmp(&rank(right)),
+    }
+}
+
+/// Run `query` over every document of `input`
+fn query_str(query: &str, input: &str) -> Result<Vec<Value>> {
+    let filter = parse(query)?;
+    let mut results = Vec::new();
+    for document in serde_json::Deserializer::from_str(input).into_iter::<Value>() {
+        let document = document.context("Failed to parse JSON")?;
+        results.extend(eval(&filter, &document)?);
+    }
+    Ok(results)
+}
+
+/// One compact line per result, cut to `max_len`, at most `max` lines
+fn format_results(results: &[Value], max: usize, max_len: usize) -> String {
+    let mut lines: Vec<String> = results
+        .iter()
+        .take(max)
+        .map(|value| truncate(&value.to_string(), max_len))
+        .collect();
+    if results.len() > max {
+        lines.push(format!("... +{} more (--max to show)", results.len() - max));
+    }
+    lines.join("\n")
+}
+
+/// Run a query on a file, `-` for stdin, or a URL
+pub fn run(
+    query: &str,
+    source: &str,
+    max: usize,
+    max_len: usize,
+    format: &str,
+    verbose: u8,
+) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    if verbose > 0 {
+        eprintln!("Querying JSON: {} {}", source, query);
+    }
+
+    let content = json_cmd::read_source(source)?;
+    let results = query_str(query, &content).with_context(|| format!("Query failed: {}", query))?;
+
+    let output = match format {
+        "json" => serde_json::to_string_pretty(&results)?,
+        _ => format_results(&results, max, max_len),
+    };
+    if !output.is_empty() {
+        println!("{}", output);
+    }
+
+    timer.track(
+        &format!("jq '{}' {}", query, source),
+        "rtk json query",
+        &content,
+        &output,
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn users() -> String {
+        json!({"users": [
+            {"name": "ada", "email": "ada@x.io", "age": 36, "admin": true},
+            {"name": "bob", "email": "bob@x.io", "age": 25},
+            {"name": "cy", "age": 41, "tags": ["a", "b"]}
+        ]})
+        .to_string()
+    }
+
+    #[test]
+    fn test_parse_paths() {
+        assert_eq!(parse(".").unwrap(), Filter::Identity);
+        assert_eq!(parse(".a").unwrap(), Filter::Key("a".into()));
+        assert_eq!(
+            parse(".a[0]").unwrap(),
+            Filter::Pipe(
+                Box::new(Filter::Key("a".into())),
+                Box::new(Filter::Index(0))
+            )
+        );
+        assert_eq!(
+            parse(".[\"odd key\"]").unwrap(),
+            Filter::Key("odd key".into())
+        );
+        assert_eq!(parse(".[]").unwrap(), Filter::Iterate);
+        assert!(parse(".a |").is_err());
+        assert!(parse("map(.a)").is_err());
+        assert!(parse(".a = 1").is_err());
+    }
+
+    #[test]
+    fn test_field_access_and_iteration() {
+        let input = users();
+        assert_eq!(
+            query_str(".users[] | .email", &input).unwrap(),
+            vec![json!("ada@x.io"), json!("bob@x.io"), Value::Null]
+        );
+        assert_eq!(
+            query_str(".users[-1].tags[0]", &input).unwrap(),
+            vec![json!("a")]
+        );
+        assert_eq!(
+            query_str(".users | length, (.[0] | keys)", &input).unwrap(),
+            vec![json!(3), json!(["admin", "age", "email", "name"])]
+        );
+        // Missing keys and indexes are null, as in jq
+        assert_eq!(
+            query_str(".nope.deeper[3]", &input).unwrap(),
+            vec![Value::Null]
+        );
+        assert!(query_str(".users.name", &input).is_err());
+    }
+
+    #[test]
+    fn test_select() {
+        let input = users();
+        assert_eq!(
+            query_str(".users[] | select(.age > 30) | .name", &input).unwrap(),
+            vec![json!("ada"), json!("cy")]
+        );
+        assert_eq!(
+            query_str(".users[] | select(.admin) | .name", &input).unwrap(),
+            vec![json!("ada")]
+        );
+        assert_eq!(
+            query_str(
+                ".users[] | select(.name == \"bob\" or .age >= 41 and (.email | not)) | .age",
+                &input
+            )
+            .unwrap(),
+            vec![json!(25), json!(41)]
+        );
+        assert_eq!(
+            query_str(".users[] | select(.tags != null) | .name", &input).unwrap(),
+            vec![json!("cy")]
+        );
+    }
+
+    #[test]
+    fn test_object_construction_and_ndjson() {
+        let input = "{\"id\":1,\"user\":{\"email\":\"a@x\"}}\n{\"id\":2,\"user\":{}}\n";
+        assert_eq!(
+            query_str("{id, mail: .user.email}", input).unwrap(),
+            vec![
+                json!({"id": 1, "mail": "a@x"}),
+                json!({"id": 2, "mail": null})
+            ]
+        );
+    }
+
+    #[test]
+    fn test_format_results_caps_lines() {
+        let results: Vec<Value> = (0..5)
+            .map(|i| json!({"n": i, "pad": "x".repeat(50)}))
+            .collect();
+        let text = format_results(&results, 3, 20);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].chars().count() <= 20);
+        assert_eq!(lines[3], "... +2 more (--max to show)");
+    }
+}
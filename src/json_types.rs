@@ -0,0 +1,379 @@
+//! `rtk json types`: Rust or TypeScript declarations for a JSON document,
+//! generated from its merged schema (see [`json_cmd::schema`]).
+//!
+//! Every object becomes a named struct / interface, named after the key
+//! holding it (array keys singularized: `users` → `User`). Keys missing
+//! from some samples are optional, `T | null` unions nullable; other
+//! unions fall back to `serde_json::Value` in Rust.
+
+use crate::json_cmd::{self, SchemaNode};
+use crate::tracking;
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// Target language of the declarations
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum Lang {
+    Rust,
+    #[value(alias = "typescript")]
+    Ts,
+}
+
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "static", "struct", "trait", "true", "type", "unsafe", "use", "where",
+    "while", "abstract", "become", "box", "do", "final", "macro", "override", "priv", "typeof",
+    "unsized", "virtual", "yield", "try", "gen",
+];
+
+/// Print the declarations for `source` (file, `-` or URL); `name` is the
+/// root type's
+pub fn run(source: &str, lang: Lang, name: &str, max_depth: usize, verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    if verbose > 0 {
+        eprintln!("Generating {:?} types: {}", lang, source);
+    }
+
+    let content = json_cmd::read_source(source)?;
+    let value: Value = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse JSON: {}", source))?;
+    let output = generate(&json_cmd::schema(&value, max_depth), lang, name);
+    println!("{}", output);
+
+    timer.track(
+        &format!("quicktype {}", source),
+        "rtk json types",
+        &content,
+        &output,
+    );
+    Ok(())
+}
+
+/// Declarations of `node` and of every object nested in it, root first
+fn generate(node: &SchemaNode, lang: Lang, name: &str) -> String {
+    let mut gen = Generator {
+        lang,
+        decls: Vec::new(),
+        names: HashSet::new(),
+    };
+    let root = pascal_case(name);
+    if let SchemaNode::Object { .. } = node {
+        gen.type_of(node, &root);
+    } else {
+        // Arrays and scalars get an alias; an array's objects are its items
+        let slot = gen.reserve(&root);
+        let target = gen.type_of(node, &root);
+        gen.decls[slot].1 = match lang {
+            Lang::Rust => format!("pub type {} = {};", gen.decls[slot].0, target),
+            Lang::Ts => format!("export type {} = {};", gen.decls[slot].0, target),
+        };
+    }
+
+    let decls: Vec<&str> = gen.decls.iter().map(|(_, d)| d.as_str()).collect();
+    match lang {
+        Lang::Rust => format!(
+            "use serde::{{Deserialize, Serialize}};\n\n{}",
+            decls.join("\n\n")
+        ),
+        Lang::Ts => decls.join("\n\n"),
+    }
+}
+
+struct Generator {
+    lang: Lang,
+    /// (type name, declaration), in emission order
+    decls: Vec<(String, String)>,
+    names: HashSet<String>,
+}
+
+impl Generator {
+    /// Claim a unique type name, keeping its place in the output
+    fn reserve(&mut self, name: &str) -> usize {
+        let mut unique = name.to_string();
+        let mut n = 2;
+        while !self.names.insert(unique.clone()) {
+            unique = format!("{}{}", name, n);
+            n += 1;
+        }
+        self.decls.push((unique, String::new()));
+        self.decls.len() - 1
+    }
+
+    /// Type expression for `node`; objects are declared under `hint`
+    fn type_of(&mut self, node: &SchemaNode, hint: &str) -> String {
+        let rust = self.lang == Lang::Rust;
+        match node {
+            SchemaNode::Null if rust => "Option<serde_json::Value>".to_string(),
+            SchemaNode::Null => "null".to_string(),
+            SchemaNode::Bool if rust => "bool".to_string(),
+            SchemaNode::Bool => "boolean".to_string(),
+            SchemaNode::Int if rust => "i64".to_string(),
+            SchemaNode::Float if rust => "f64".to_string(),
+            SchemaNode::Int | SchemaNode::Float => "number".to_string(),
+            SchemaNode::String { .. } if rust => "String".to_string(),
+            SchemaNode::String { .. } => "string".to_string(),
+            SchemaNode::Truncated if rust => "serde_json::Value".to_string(),
+            SchemaNode::Truncated => "unknown".to_string(),
+            SchemaNode::Array { items, .. } => {
+                let item = match items {
+                    Some(items) => self.type_of(items, &singular(hint)),
+                    None if rust => "serde_json::Value".to_string(),
+                    None => "unknown".to_string(),
+                };
+                if rust {
+                    format!("Vec<{}>", item)
+                } else if item.contains(' ') {
+                    format!("({})[]", item)
+                } else {
+                    format!("{}[]", item)
+                }
+            }
+            SchemaNode::Object { keys } => {
+                let slot = self.reserve(hint);
+                let name = self.decls[slot].0.clone();
+                let fields: Vec<String> = keys
+                    .iter()
+                    .map(|(key, field)| {
+                        let ty = self.type_of(&field.schema, &pascal_case(key));
+                        if rust {
+                            rust_field(key, &ty, field.optional)
+                        } else {
+                            ts_field(key, &ty, field.optional)
+                        }
+                    })
+                    .collect();
+                self.decls[slot].1 = if rust {
+                    format!(
+                        "#[derive(Debug, Clone, Serialize, Deserialize)]\npub struct {} {{\n{}}}",
+                        name,
+                        fields.concat()
+                    )
+                } else {
+                    format!("export interface {} {{\n{}}}", name, fields.concat())
+                };
+                name
+            }
+            SchemaNode::Union { variants } => self.union(variants, hint),
+        }
+    }
+
+    fn union(&mut self, variants: &[SchemaNode], hint: &str) -> String {
+        let nullable = variants.contains(&SchemaNode::Null);
+        let rest: Vec<&SchemaNode> = variants
+            .iter()
+            .filter(|v| **v != SchemaNode::Null)
+            .collect();
+        if self.lang == Lang::Ts {
+            let mut types: Vec<String> = Vec::new();
+            for variant in variants {
+                let ty = self.type_of(variant, hint);
+                if !types.contains(&ty) {
+                    types.push(ty);
+                }
+            }
+            return types.join(" | ");
+        }
+
+        let numbers = rest
+            .iter()
+            .all(|v| matches!(v, SchemaNode::Int | SchemaNode::Float));
+        let inner = match rest.as_slice() {
+            [single] => self.type_of(single, hint),
+            _ if numbers => "f64".to_string(),
+            _ => "serde_json::Value".to_string(),
+        };
+        if nullable && inner != "serde_json::Value" {
+            format!("Option<{}>", inner)
+        } else {
+            inner
+        }
+    }
+}
+
+fn rust_field(key: &str, ty: &str, optional: bool) -> String {
+    let ident = snake_case(key);
+    let mut out = String::new();
+    let bare = ident.trim_start_matches("r#");
+    if bare != key {
+        out.push_str(&format!("    #[serde(rename = {:?})]\n", key));
+    }
+    if optional {
+        let ty = if ty.starts_with("Option<") {
+            ty.to_string()
+        } else {
+            format!("Option<{}>", ty)
+        };
+        out.push_str("    #[serde(default, skip_serializing_if = \"Option::is_none\")]\n");
+        out.push_str(&format!("    pub {}: {},\n", ident, ty));
+    } else {
+        out.push_str(&format!("    pub {}: {},\n", ident, ty));
+    }
+    out
+}
+
+fn ts_field(key: &str, ty: &str, optional: bool) -> String {
+    let valid = key.chars().next().is_some_and(|c| !c.is_ascii_digit())
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    let name = if valid {
+        key.to_string()
+    } else {
+        format!("{:?}", key)
+    };
+    format!("  {}{}: {};\n", name, if optional { "?" } else { "" }, ty)
+}
+
+/// Words of a key: split on separators and lower→upper case changes
+fn words(key: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut prev_lower = false;
+    for c in key.chars() {
+        if !c.is_alphanumeric() {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            prev_lower = false;
+            continue;
+        }
+        if c.is_uppercase() && prev_lower && !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+        prev_lower = c.is_lowercase() || c.is_ascii_digit();
+        word.push(c);
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+fn snake_case(key: &str) -> String {
+    let ident = words(key)
+        .iter()
+        .map(|w| w.to_lowercase())
+        .collect::<Vec<_>>()
+        .join("_");
+    match ident.chars().next() {
+        None => "field".to_string(),
+        Some(c) if c.is_ascii_digit() => format!("_{}", ident),
+        _ if RUST_KEYWORDS.contains(&ident.as_str()) => format!("r#{}", ident),
+        _ => ident,
+    }
+}
+
+fn pascal_case(key: &str) -> String {
+    let name: String = words(key)
+        .iter()
+        .map(|w| {
+            let mut chars = w.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect();
+    match name.chars().next() {
+        None => "Item".to_string(),
+        Some(c) if c.is_ascii_digit() => format!("T{}", name),
+        _ => name,
+    }
+}
+
+/// Item type name for an array type name: `Users` → `User`
+fn singular(name: &str) -> String {
+    if let Some(stem) = name.strip_suffix("ies") {
+        format!("{}y", stem)
+    } else if name.len() > 3 && name.ends_with('s') && !name.ends_with("ss") {
+        name[..name.len() - 1].to_string()
+    } else {
+        format!("{}Item", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn types(value: Value, lang: Lang) -> String {
+        generate(&json_cmd::schema(&value, 5), lang, "root")
+    }
+
+    #[test]
+    fn test_names() {
+        assert_eq!(snake_case("firstName"), "first_name");
+        assert_eq!(snake_case("content-type"), "content_type");
+        assert_eq!(snake_case("type"), "r#type");
+        assert_eq!(snake_case("2fa"), "_2fa");
+        assert_eq!(pascal_case("billing_address"), "BillingAddress");
+        assert_eq!(pascal_case("userID"), "UserID");
+        assert_eq!(singular("Users"), "User");
+        assert_eq!(singular("Categories"), "Category");
+        assert_eq!(singular("Address"), "AddressItem");
+    }
+
+    #[test]
+    fn test_generate_rust() {
+        let value = json!({
+            "id": 1,
+            "firstName": "Ann",
+            "type": "admin",
+            "score": [1, 2.5],
+            "users": [{"name": "a", "email": null}, {"name": "b", "email": "b@x.io", "age": 3}]
+        });
+        let expected = r#"use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Root {
+    #[serde(rename = "firstName")]
+    pub first_name: String,
+    pub id: i64,
+    pub score: Vec<f64>,
+    pub r#type: String,
+    pub users: Vec<User>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct User {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub age: Option<i64>,
+    pub email: Option<String>,
+    pub name: String,
+}"#;
+        assert_eq!(types(value, Lang::Rust), expected);
+    }
+
+    #[test]
+    fn test_generate_typescript() {
+        let value = json!([
+            {"id": 1, "content-type": "x", "tags": ["a"], "meta": {"ok": true}},
+            {"id": 2, "content-type": null, "tags": [], "meta": {"ok": false}}
+        ]);
+        let expected = r#"export type Root = RootItem[];
+
+export interface RootItem {
+  "content-type": string | null;
+  id: number;
+  meta: Meta;
+  tags: string[];
+}
+
+export interface Meta {
+  ok: boolean;
+}"#;
+        assert_eq!(types(value, Lang::Ts), expected);
+    }
+
+    #[test]
+    fn test_duplicate_names_are_numbered() {
+        let value = json!({"a": {"item": {"x": 1}}, "b": {"item": {"y": "z"}}});
+        let out = types(value, Lang::Ts);
+        assert!(out.contains("export interface Item {\n  x: number;\n}"));
+        assert!(out.contains("export interface Item2 {\n  y: string;\n}"));
+    }
+}
@@ -0,0 +1,127 @@
+//! Output width: rtk sizes its lines, paths and table columns to the
+//! terminal (or to `--width`). When stdout is piped and no width is given,
+//! the fixed defaults apply, so captured output does not depend on the
+//! window it was produced in. `--stable-output` always uses the defaults.
+
+use crate::utils::truncate;
+use std::io::IsTerminal;
+use std::sync::OnceLock;
+
+/// Narrower widths are treated as this, so columns never collapse to nothing
+pub const MIN_WIDTH: usize = 40;
+
+/// Path budget when the width is unknown
+pub const DEFAULT_PATH_WIDTH: usize = 50;
+
+static WIDTH: OnceLock<Option<usize>> = OnceLock::new();
+
+/// Resolve the width once, at startup: `--width` when given, otherwise the
+/// terminal's
+pub fn set_width(width: Option<usize>) {
+    let _ = WIDTH.set(width.or_else(detect));
+}
+
+/// Columns available to rtk's output, `None` when unknown (piped output)
+pub fn width() -> Option<usize> {
+    *WIDTH.get_or_init(detect)
+}
+
+fn detect() -> Option<usize> {
+    if crate::stable::enabled() || !std::io::stdout().is_terminal() {
+        return None;
+    }
+    terminal_columns().or_else(|| {
+        std::env::var("COLUMNS")
+            .ok()
+            .and_then(|c| c.parse().ok())
+            .filter(|&c| c > 0)
+    })
+}
+
+#[cfg(unix)]
+fn terminal_columns() -> Option<usize> {
+    let mut size = libc::winsize {
+        ws_row: 0,
+        ws_col: 0,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    };
+    // SAFETY: TIOCGWINSZ only writes into the winsize we own
+    let ok = unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) } == 0;
+    (ok && size.ws_col > 0).then_some(size.ws_col as usize)
+}
+
+#[cfg(not(unix))]
+fn terminal_columns() -> Option<usize> {
+    None
+}
+
+/// Room for a line after `reserved` columns of prefix, or `fallback`
+/// when the width is unknown
+pub fn line_width(fallback: usize, reserved: usize) -> usize {
+    fit(width(), fallback, reserved)
+}
+
+fn fit(width: Option<usize>, fallback: usize, reserved: usize) -> usize {
+    match width {
+        Some(w) => w.max(MIN_WIDTH).saturating_sub(reserved),
+        None => fallback,
+    }
+}
+
+/// Budget for a path shown ahead of other details: two fifths of the line
+pub fn path_width() -> usize {
+    path_fit(width())
+}
+
+fn path_fit(width: Option<usize>) -> usize {
+    width.map_or(DEFAULT_PATH_WIDTH, |w| w.max(MIN_WIDTH) * 2 / 5)
+}
+
+/// Cut the lines of `text` longer than the width; unchanged when the width
+/// is unknown
+pub fn fit_lines(text: &str) -> String {
+    fit_lines_to(text, width())
+}
+
+fn fit_lines_to(text: &str, width: Option<usize>) -> String {
+    let Some(width) = width else {
+        return text.to_string();
+    };
+    let width = width.max(MIN_WIDTH);
+    text.lines()
+        .map(|line| truncate(line, width))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fit_uses_fallback_without_width() {
+        assert_eq!(fit(None, 80, 8), 80);
+        assert_eq!(fit(Some(120), 80, 8), 112);
+        // Tiny terminals still leave room for something
+        assert_eq!(fit(Some(10), 80, 8), MIN_WIDTH - 8);
+    }
+
+    #[test]
+    fn test_path_fit() {
+        assert_eq!(path_fit(None), DEFAULT_PATH_WIDTH);
+        assert_eq!(path_fit(Some(200)), 80);
+        assert_eq!(path_fit(Some(20)), 16);
+    }
+
+    #[test]
+    fn test_fit_lines_to() {
+        let text = format!("short\n{}", "x".repeat(60));
+        assert_eq!(fit_lines_to(&text, None), text);
+        let fitted = fit_lines_to(&text, Some(40));
+        let lines: Vec<&str> = fitted.lines().collect();
+        assert_eq!(lines[0], "short");
+        assert_eq!(lines[1].chars().count(), 40);
+        assert!(lines[1].ends_with("..."));
+    }
+}
@@ -0,0 +1,141 @@
+//! rtk (Rust Token Killer) filters and compresses command output before it
+//! reaches an LLM context window.
+//!
+//! The `rtk` binary is a thin CLI over this library, so other tools and
+//! agent frameworks can embed the same compaction without spawning a
+//! process. The modules behind the subcommands are internal; what is meant
+//! for embedding is re-exported under short names:
+//!
+//! ```no_run
+//! use rtk::{grep, json};
+//!
+//! let files = grep::search("fn main", &["src".to_string()], &grep::SearchOptions::default())?;
+//! for file in &files {
+//!     println!("{}: {} matches", file.path, file.matches.len());
+//! }
+//!
+//! let value = serde_json::json!({"id": 1, "tags": ["a", "b"]});
+//! let schema = json::schema(&value, 5);
+//! println!("{}", serde_json::to_string_pretty(&schema)?);
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+pub(crate) mod archive;
+pub(crate) mod bench_cmd;
+pub(crate) mod blame_cmd;
+pub(crate) mod branches_cmd;
+pub(crate) mod budget;
+pub(crate) mod cache;
+pub(crate) mod cargo_cmd;
+pub(crate) mod cat_cmd;
+pub(crate) mod cc_economics;
+pub(crate) mod ccusage;
+pub(crate) mod ci_cmd;
+mod cli;
+pub(crate) mod completions;
+pub(crate) mod config;
+pub(crate) mod container;
+pub(crate) mod count_cmd;
+pub(crate) mod csv_cmd;
+pub(crate) mod curl_cmd;
+pub(crate) mod delta;
+pub(crate) mod deps;
+pub(crate) mod diff_cmd;
+pub(crate) mod discover;
+pub(crate) mod display_helpers;
+pub(crate) mod du_cmd;
+pub(crate) mod env_cmd;
+pub(crate) mod events;
+pub(crate) mod explain_cmd;
+pub(crate) mod filter;
+pub(crate) mod find_cmd;
+pub(crate) mod format_cmd;
+pub(crate) mod gain;
+pub(crate) mod gh_cmd;
+pub(crate) mod git;
+pub(crate) mod go_cmd;
+pub(crate) mod golangci_cmd;
+pub(crate) mod grep_cmd;
+pub(crate) mod grep_tui;
+pub(crate) mod help_cmd;
+pub(crate) mod history_cmd;
+pub(crate) mod hook_audit_cmd;
+pub(crate) mod http_cmd;
+pub(crate) mod index;
+pub(crate) mod init;
+pub(crate) mod json_cmd;
+pub(crate) mod json_diff;
+pub(crate) mod json_query;
+pub(crate) mod json_types;
+pub(crate) mod layout;
+pub(crate) mod learn;
+pub(crate) mod lint_cmd;
+pub(crate) mod local_llm;
+pub(crate) mod log_cmd;
+pub(crate) mod logsum_cmd;
+pub(crate) mod ls;
+pub(crate) mod mcp;
+pub(crate) mod next_cmd;
+pub(crate) mod npm_cmd;
+pub(crate) mod outline_cmd;
+pub(crate) mod output;
+pub(crate) mod parser;
+pub(crate) mod paths;
+pub(crate) mod pip_cmd;
+pub(crate) mod pipe_cmd;
+pub(crate) mod playwright_cmd;
+pub(crate) mod plugin;
+pub(crate) mod pnpm_cmd;
+pub(crate) mod pr_cmd;
+pub(crate) mod prettier_cmd;
+pub(crate) mod prisma_cmd;
+pub(crate) mod ps_cmd;
+pub(crate) mod pytest_cmd;
+pub(crate) mod read;
+pub(crate) mod recall_cmd;
+pub(crate) mod redact;
+pub(crate) mod replace_cmd;
+pub(crate) mod replay_cmd;
+pub(crate) mod ruff_cmd;
+pub(crate) mod runner;
+pub(crate) mod sample_cmd;
+pub(crate) mod sarif;
+pub(crate) mod slots;
+pub(crate) mod stable;
+pub(crate) mod stats_cmd;
+pub(crate) mod summary;
+pub(crate) mod symbols_cmd;
+pub(crate) mod tee;
+pub(crate) mod test_cmd;
+pub(crate) mod todo_cmd;
+pub(crate) mod tokenizer;
+pub(crate) mod toml_cmd;
+pub(crate) mod trace_cmd;
+pub(crate) mod tracking;
+pub(crate) mod tree;
+pub(crate) mod tree_cmd;
+pub(crate) mod tsc_cmd;
+pub(crate) mod utils;
+pub(crate) mod vitest_cmd;
+pub(crate) mod walk;
+pub(crate) mod watch;
+pub(crate) mod wc_cmd;
+pub(crate) mod wget_cmd;
+pub(crate) mod xml_cmd;
+pub(crate) mod yaml_cmd;
+
+pub use cli::run;
+
+/// Typed code search (`rtk grep` without the formatting)
+pub mod grep {
+    pub use crate::grep_cmd::{search, FileMatches, LineMatch, MatchOptions, SearchOptions};
+}
+
+/// Typed JSON schema inference (`rtk json` without the formatting), and
+/// the rendered summary
+pub mod json {
+    pub use crate::json_cmd::{
+        filter_json_string, json_schema, schema, schema_str, Field, SchemaNode, StringKind,
+        JSON_SCHEMA_DIALECT,
+    };
+}
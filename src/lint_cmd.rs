@@ -1,43 +1,76 @@
-use crate::ruff_cmd;
+use crate::delta;
+use crate::layout;
+use crate::paths;
+use crate::sarif;
 use crate::tracking;
-use crate::utils::{package_manager_exec, truncate};
+use crate::utils::{self, package_manager_exec, truncate};
 use anyhow::{Context, Result};
 use regex::Regex;
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::path::Path;
 use std::process::Command;
 
-#[derive(Debug, Deserialize, Serialize)]
+/// Rules shown, most severe and most frequent first
+const MAX_RULES: usize = 15;
+/// Locations listed under each rule
+const LOCATIONS_PER_RULE: usize = 3;
+
+/// Severity of a finding, most severe first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
+impl Severity {
+    fn label(self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Info => "info",
+        }
+    }
+}
+
+/// One finding, whichever linter reported it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub file: String,
+    pub line: usize,
+    pub column: Option<usize>,
+    /// Rule or code: `prefer-const`, `F401`, `clippy::needless_return`
+    pub rule: Option<String>,
+    pub severity: Severity,
+    pub message: String,
+}
+
+#[derive(Debug, Deserialize)]
 struct EslintMessage {
     #[serde(rename = "ruleId")]
     rule_id: Option<String>,
     severity: u8,
     message: String,
+    #[serde(default)]
     line: usize,
+    #[serde(default)]
     column: usize,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize)]
 struct EslintResult {
     #[serde(rename = "filePath")]
     file_path: String,
     messages: Vec<EslintMessage>,
-    #[serde(rename = "errorCount")]
-    error_count: usize,
-    #[serde(rename = "warningCount")]
-    warning_count: usize,
 }
 
 #[derive(Debug, Deserialize)]
 struct PylintDiagnostic {
     #[serde(rename = "type")]
     msg_type: String, // "warning", "error", "convention", "refactor"
-    #[allow(dead_code)]
-    module: String,
-    #[allow(dead_code)]
-    obj: String,
     line: usize,
-    #[allow(dead_code)]
     column: usize,
     path: String,
     symbol: String, // rule code like "unused-variable"
@@ -46,26 +79,101 @@ struct PylintDiagnostic {
     message_id: String, // e.g., "W0612"
 }
 
+#[derive(Debug, Deserialize)]
+struct RuffLocation {
+    row: usize,
+    column: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct RuffDiagnostic {
+    code: Option<String>,
+    message: String,
+    location: RuffLocation,
+    filename: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GolangciPosition {
+    #[serde(rename = "Filename")]
+    filename: String,
+    #[serde(rename = "Line")]
+    line: usize,
+    #[serde(rename = "Column")]
+    column: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct GolangciIssue {
+    #[serde(rename = "FromLinter")]
+    from_linter: String,
+    #[serde(rename = "Text")]
+    text: String,
+    #[serde(rename = "Severity", default)]
+    severity: String,
+    #[serde(rename = "Pos")]
+    pos: GolangciPosition,
+}
+
+#[derive(Debug, Deserialize)]
+struct GolangciOutput {
+    #[serde(rename = "Issues", default)]
+    issues: Option<Vec<GolangciIssue>>,
+}
+
 /// Check if a linter is Python-based (uses pip/pipx, not npm/pnpm)
 fn is_python_linter(linter: &str) -> bool {
     matches!(linter, "ruff" | "pylint" | "mypy" | "flake8")
 }
 
-pub fn run(args: &[String], verbose: u8) -> Result<()> {
+/// Linter for the project in `dir`, from its manifest files
+fn detect_linter(dir: &Path) -> &'static str {
+    let has = |name: &str| dir.join(name).exists();
+    if has("Cargo.toml") {
+        "clippy"
+    } else if has("go.mod") {
+        "golangci-lint"
+    } else if has("pyproject.toml") || has("ruff.toml") || has("setup.py") {
+        "ruff"
+    } else {
+        "eslint"
+    }
+}
+
+/// Name used in rtk's summary lines
+fn display_name(linter: &str) -> &str {
+    match linter {
+        "eslint" => "ESLint",
+        "ruff" => "Ruff",
+        "pylint" => "Pylint",
+        "mypy" => "Mypy",
+        "clippy" => "cargo clippy",
+        other => other,
+    }
+}
+
+pub fn run(args: &[String], format: &str, verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
-    // Detect linter name (first arg if not a path/flag, else default to eslint)
+    // Linter name is the first arg unless it's a path/flag; otherwise it's
+    // detected from the project files
     let is_path_or_flag = args.is_empty()
         || args[0].starts_with('-')
         || args[0].contains('/')
         || args[0].contains('.');
 
-    let linter = if is_path_or_flag { "eslint" } else { &args[0] };
+    let linter = if is_path_or_flag {
+        detect_linter(Path::new("."))
+    } else {
+        args[0].as_str()
+    };
 
     // Python linters use Command::new() directly (they're on PATH via pip/pipx)
     // JS linters use package_manager_exec (npx/pnpm exec)
-    let mut cmd = if is_python_linter(linter) {
+    let mut cmd = if is_python_linter(linter) || linter == "golangci-lint" {
         Command::new(linter)
+    } else if linter == "clippy" {
+        Command::new("cargo")
     } else {
         package_manager_exec(linter)
     };
@@ -87,34 +195,43 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
                 cmd.arg("--output-format=json2");
             }
         }
-        "mypy" => {
-            // mypy uses default text output (no special flags)
+        "clippy" => {
+            cmd.arg("clippy").arg("--message-format=json");
+        }
+        "golangci-lint" => {
+            cmd.arg("run").arg("--out-format=json");
         }
         _ => {
-            // Other linters: no special formatting
+            // mypy uses its default text output; other linters: no special formatting
         }
     }
 
-    // Add user arguments (skip first if it was the linter name, and skip "check" for ruff if we added it)
+    // Add user arguments (skip first if it was the linter name, and skip the
+    // subcommand if we already added it)
     let start_idx = if is_path_or_flag {
         0
-    } else if linter == "ruff" && !args.is_empty() && args[0] == "ruff" {
-        // Skip "ruff" and "check" if we already added "check"
-        if args.len() > 1 && args[1] == "check" {
+    } else {
+        let added = match linter {
+            "ruff" => "check",
+            "golangci-lint" => "run",
+            _ => "",
+        };
+        if !added.is_empty() && args.get(1).map(String::as_str) == Some(added) {
             2
         } else {
             1
         }
-    } else {
-        1
     };
 
     for arg in &args[start_idx..] {
-        // Skip --output-format if we already added it
-        if linter == "ruff" && arg.starts_with("--output-format") {
+        // Skip format flags we already added
+        if matches!(linter, "ruff" | "pylint") && arg.starts_with("--output-format") {
             continue;
         }
-        if linter == "pylint" && arg.starts_with("--output-format") {
+        if linter == "clippy" && arg.starts_with("--message-format") {
+            continue;
+        }
+        if linter == "golangci-lint" && arg.starts_with("--out-format") {
             continue;
         }
         cmd.arg(arg);
@@ -157,26 +274,35 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     let stderr = String::from_utf8_lossy(&output.stderr);
     let raw = format!("{}\n{}", stdout, stderr);
 
-    // Dispatch to appropriate filter based on linter
-    let filtered = match linter {
-        "eslint" => filter_eslint_json(&stdout),
-        "ruff" => {
-            // Reuse ruff_cmd's JSON parser
-            if !stdout.trim().is_empty() {
-                ruff_cmd::filter_ruff_check_json(&stdout)
-            } else {
-                "✓ Ruff: No issues found".to_string()
-            }
+    let parsed = parse_lint_output(linter, &stdout, &raw);
+    let exit_code = utils::exit_code(output.status);
+    if format == "sarif" {
+        let log = format_sarif(linter, &parsed)?;
+        println!("{}", log);
+        if let Some(hint) = crate::tee::tee_and_hint(&raw, "lint", exit_code) {
+            eprintln!("{}", hint);
         }
-        "pylint" => filter_pylint_json(&stdout),
-        "mypy" => filter_mypy_output(&raw),
-        _ => filter_generic_lint(&raw),
+        timer.track(
+            &format!("{} {}", linter, args.join(" ")),
+            &format!("rtk lint {} {}", linter, args.join(" ")),
+            &raw,
+            &log,
+        );
+        utils::exit_with(linter, exit_code, verbose);
+        return Ok(());
+    }
+
+    // Only parsed runs are compared: unparsed output would read as all FIXED
+    let keys: Option<Vec<String>> = match &parsed {
+        Some(Ok(diagnostics)) => Some(diagnostics.iter().map(delta_key).collect()),
+        _ => None,
     };
+    let mut filtered = format_lint_output(linter, &stdout, &raw, parsed);
+    if let Some(keys) = keys {
+        let command = format!("lint {} {}", linter, args.join(" "));
+        filtered = delta::annotate(filtered, &command, &keys);
+    }
 
-    let exit_code = output
-        .status
-        .code()
-        .unwrap_or(if output.status.success() { 0 } else { 1 });
     if let Some(hint) = crate::tee::tee_and_hint(&raw, "lint", exit_code) {
         println!("{}\n{}", filtered, hint);
     } else {
@@ -190,340 +316,320 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
         &filtered,
     );
 
-    if !output.status.success() {
-        std::process::exit(output.status.code().unwrap_or(1));
-    }
-
+    utils::exit_with(linter, exit_code, verbose);
     Ok(())
 }
 
-/// Filter ESLint JSON output - group by rule and file
-fn filter_eslint_json(output: &str) -> String {
-    let results: Result<Vec<EslintResult>, _> = serde_json::from_str(output);
-
-    let results = match results {
-        Ok(r) => r,
-        Err(e) => {
-            // Fallback if JSON parsing fails
-            return format!(
-                "ESLint output (JSON parse failed: {})\n{}",
-                e,
-                truncate(output, 500)
-            );
-        }
-    };
-
-    // Count total issues
-    let total_errors: usize = results.iter().map(|r| r.error_count).sum();
-    let total_warnings: usize = results.iter().map(|r| r.warning_count).sum();
-    let total_files = results.iter().filter(|r| !r.messages.is_empty()).count();
-
-    if total_errors == 0 && total_warnings == 0 {
-        return "✓ ESLint: No issues found".to_string();
-    }
-
-    // Group messages by rule
-    let mut by_rule: HashMap<String, usize> = HashMap::new();
-    for result in &results {
-        for msg in &result.messages {
-            if let Some(rule) = &msg.rule_id {
-                *by_rule.entry(rule.clone()).or_insert(0) += 1;
-            }
-        }
-    }
-
-    // Group by file
-    let mut by_file: Vec<(&EslintResult, usize)> = results
-        .iter()
-        .filter(|r| !r.messages.is_empty())
-        .map(|r| (r, r.messages.len()))
-        .collect();
-    by_file.sort_by(|a, b| b.1.cmp(&a.1));
-
-    // Build output
-    let mut result = String::new();
-    result.push_str(&format!(
-        "ESLint: {} errors, {} warnings in {} files\n",
-        total_errors, total_warnings, total_files
-    ));
-    result.push_str("═══════════════════════════════════════\n");
-
-    // Show top rules
-    let mut rule_counts: Vec<_> = by_rule.iter().collect();
-    rule_counts.sort_by(|a, b| b.1.cmp(a.1));
-
-    if !rule_counts.is_empty() {
-        result.push_str("Top rules:\n");
-        for (rule, count) in rule_counts.iter().take(10) {
-            result.push_str(&format!("  {} ({}x)\n", rule, count));
-        }
-        result.push('\n');
-    }
-
-    // Show top files with most issues
-    result.push_str("Top files:\n");
-    for (file_result, count) in by_file.iter().take(10) {
-        let short_path = compact_path(&file_result.file_path);
-        result.push_str(&format!("  {} ({} issues)\n", short_path, count));
-
-        // Show top 3 rules in this file
-        let mut file_rules: HashMap<String, usize> = HashMap::new();
-        for msg in &file_result.messages {
-            if let Some(rule) = &msg.rule_id {
-                *file_rules.entry(rule.clone()).or_insert(0) += 1;
+/// Diagnostics in `linter`'s output; `None` when rtk doesn't know its
+/// format, or mypy printed something other than diagnostics
+fn parse_lint_output(
+    linter: &str,
+    stdout: &str,
+    raw: &str,
+) -> Option<Result<Vec<Diagnostic>, serde_json::Error>> {
+    Some(match linter {
+        "eslint" => parse_eslint_json(stdout),
+        "ruff" if stdout.trim().is_empty() => Ok(Vec::new()),
+        "ruff" => parse_ruff_json(stdout),
+        "pylint" => parse_pylint_json(stdout),
+        "golangci-lint" => parse_golangci_json(stdout),
+        "clippy" => Ok(parse_clippy_json(stdout)),
+        "mypy" => {
+            let diagnostics = parse_mypy_output(raw);
+            if diagnostics.is_empty() && !raw.contains("Success") && !raw.trim().is_empty() {
+                return None;
             }
+            Ok(diagnostics)
         }
+        _ => return None,
+    })
+}
 
-        let mut file_rule_counts: Vec<_> = file_rules.iter().collect();
-        file_rule_counts.sort_by(|a, b| b.1.cmp(a.1));
-
-        for (rule, count) in file_rule_counts.iter().take(3) {
-            result.push_str(&format!("    {} ({})\n", rule, count));
-        }
-    }
-
-    if by_file.len() > 10 {
-        result.push_str(&format!("\n... +{} more files\n", by_file.len() - 10));
+fn format_lint_output(
+    linter: &str,
+    stdout: &str,
+    raw: &str,
+    parsed: Option<Result<Vec<Diagnostic>, serde_json::Error>>,
+) -> String {
+    match parsed {
+        Some(Ok(diagnostics)) => format_lint_report(display_name(linter), &diagnostics),
+        Some(Err(e)) => format!(
+            "{} output (JSON parse failed: {})\n{}",
+            display_name(linter),
+            e,
+            truncate(stdout, 500)
+        ),
+        // Nothing matched and no success line: show what mypy said
+        None if linter == "mypy" => format!("Mypy output:\n{}", truncate(raw, 500)),
+        None => filter_generic_lint(raw),
     }
-
-    result.trim().to_string()
 }
 
-/// Filter pylint JSON2 output - group by symbol and file
-fn filter_pylint_json(output: &str) -> String {
-    let diagnostics: Result<Vec<PylintDiagnostic>, _> = serde_json::from_str(output);
-
-    let diagnostics = match diagnostics {
-        Ok(d) => d,
-        Err(e) => {
-            // Fallback if JSON parsing fails
-            return format!(
-                "Pylint output (JSON parse failed: {})\n{}",
-                e,
-                truncate(output, 500)
-            );
-        }
+/// `--format sarif`: every diagnostic; output rtk can't parse is reported as
+/// a failed run rather than as a clean one
+fn format_sarif(
+    linter: &str,
+    parsed: &Option<Result<Vec<Diagnostic>, serde_json::Error>>,
+) -> Result<String> {
+    let (findings, notifications) = match parsed {
+        Some(Ok(diagnostics)) => (diagnostics.iter().map(to_finding).collect(), Vec::new()),
+        Some(Err(e)) => (Vec::new(), vec![unparsed(linter, &e.to_string())]),
+        None => (Vec::new(), vec![unparsed(linter, "unknown format")]),
     };
+    sarif::render(linter, &[], &findings, &notifications)
+}
 
-    if diagnostics.is_empty() {
-        return "✓ Pylint: No issues found".to_string();
-    }
-
-    // Count by type
-    let mut errors = 0;
-    let mut warnings = 0;
-    let mut conventions = 0;
-    let mut refactors = 0;
-
-    for diag in &diagnostics {
-        match diag.msg_type.as_str() {
-            "error" => errors += 1,
-            "warning" => warnings += 1,
-            "convention" => conventions += 1,
-            "refactor" => refactors += 1,
-            _ => {}
-        }
-    }
-
-    // Count unique files
-    let unique_files: std::collections::HashSet<_> = diagnostics.iter().map(|d| &d.path).collect();
-    let total_files = unique_files.len();
-
-    // Group by symbol (rule code)
-    let mut by_symbol: HashMap<String, usize> = HashMap::new();
-    for diag in &diagnostics {
-        let key = format!("{} ({})", diag.symbol, diag.message_id);
-        *by_symbol.entry(key).or_insert(0) += 1;
+fn to_finding(diag: &Diagnostic) -> sarif::Finding {
+    sarif::Finding {
+        rule: diag.rule.clone(),
+        level: match diag.severity {
+            Severity::Error => sarif::Level::Error,
+            Severity::Warning => sarif::Level::Warning,
+            Severity::Info => sarif::Level::Note,
+        },
+        message: diag.message.clone(),
+        file: diag.file.clone(),
+        line: diag.line,
+        column: diag.column,
     }
+}
 
-    // Group by file
-    let mut by_file: HashMap<&str, usize> = HashMap::new();
-    for diag in &diagnostics {
-        *by_file.entry(&diag.path).or_insert(0) += 1;
+fn unparsed(linter: &str, reason: &str) -> sarif::Notification {
+    sarif::Notification {
+        level: sarif::Level::Error,
+        message: format!("rtk could not parse the {} output ({})", linter, reason),
     }
+}
 
-    let mut file_counts: Vec<_> = by_file.iter().collect();
-    file_counts.sort_by(|a, b| b.1.cmp(a.1));
-
-    // Build output
-    let mut result = String::new();
-    result.push_str(&format!(
-        "Pylint: {} issues in {} files\n",
-        diagnostics.len(),
-        total_files
-    ));
-
-    if errors > 0 || warnings > 0 {
-        result.push_str(&format!("  {} errors, {} warnings", errors, warnings));
-        if conventions > 0 || refactors > 0 {
-            result.push_str(&format!(
-                ", {} conventions, {} refactors",
-                conventions, refactors
-            ));
-        }
-        result.push('\n');
-    }
-
-    result.push_str("═══════════════════════════════════════\n");
-
-    // Show top symbols (rules)
-    let mut symbol_counts: Vec<_> = by_symbol.iter().collect();
-    symbol_counts.sort_by(|a, b| b.1.cmp(a.1));
-
-    if !symbol_counts.is_empty() {
-        result.push_str("Top rules:\n");
-        for (symbol, count) in symbol_counts.iter().take(10) {
-            result.push_str(&format!("  {} ({}x)\n", symbol, count));
-        }
-        result.push('\n');
+/// Identity of a diagnostic across runs: file, rule and message, but not
+/// the line, which moves with unrelated edits
+fn delta_key(diag: &Diagnostic) -> String {
+    let file = paths::compact(&diag.file, usize::MAX);
+    match &diag.rule {
+        Some(rule) => format!("{}: {}: {}", file, rule, diag.message),
+        None => format!("{}: {}", file, diag.message),
     }
+}
 
-    // Show top files
-    result.push_str("Top files:\n");
-    for (file, count) in file_counts.iter().take(10) {
-        let short_path = compact_path(file);
-        result.push_str(&format!("  {} ({} issues)\n", short_path, count));
-
-        // Show top 3 rules in this file
-        let mut file_symbols: HashMap<String, usize> = HashMap::new();
-        for diag in diagnostics.iter().filter(|d| &d.path == *file) {
-            let key = format!("{} ({})", diag.symbol, diag.message_id);
-            *file_symbols.entry(key).or_insert(0) += 1;
-        }
+/// ESLint `-f json` output
+fn parse_eslint_json(output: &str) -> Result<Vec<Diagnostic>, serde_json::Error> {
+    let results: Vec<EslintResult> = serde_json::from_str(output)?;
+    Ok(results
+        .into_iter()
+        .flat_map(|result| {
+            let file = result.file_path;
+            result.messages.into_iter().map(move |msg| Diagnostic {
+                file: file.clone(),
+                line: msg.line,
+                column: Some(msg.column),
+                rule: msg.rule_id,
+                severity: if msg.severity >= 2 {
+                    Severity::Error
+                } else {
+                    Severity::Warning
+                },
+                message: msg.message,
+            })
+        })
+        .collect())
+}
 
-        let mut file_symbol_counts: Vec<_> = file_symbols.iter().collect();
-        file_symbol_counts.sort_by(|a, b| b.1.cmp(a.1));
+/// `ruff check --output-format=json` output. Ruff has no severities; only
+/// syntax errors (no code) are errors.
+fn parse_ruff_json(output: &str) -> Result<Vec<Diagnostic>, serde_json::Error> {
+    let diagnostics: Vec<RuffDiagnostic> = serde_json::from_str(output)?;
+    Ok(diagnostics
+        .into_iter()
+        .map(|d| Diagnostic {
+            file: d.filename,
+            line: d.location.row,
+            column: Some(d.location.column),
+            severity: if d.code.is_some() {
+                Severity::Warning
+            } else {
+                Severity::Error
+            },
+            rule: d.code,
+            message: d.message,
+        })
+        .collect())
+}
 
-        for (symbol, count) in file_symbol_counts.iter().take(3) {
-            result.push_str(&format!("    {} ({})\n", symbol, count));
-        }
-    }
+/// Pylint `--output-format=json2` output
+fn parse_pylint_json(output: &str) -> Result<Vec<Diagnostic>, serde_json::Error> {
+    let diagnostics: Vec<PylintDiagnostic> = serde_json::from_str(output)?;
+    Ok(diagnostics
+        .into_iter()
+        .map(|d| Diagnostic {
+            file: d.path,
+            line: d.line,
+            column: Some(d.column),
+            rule: Some(format!("{} ({})", d.symbol, d.message_id)),
+            severity: match d.msg_type.as_str() {
+                "error" | "fatal" => Severity::Error,
+                "warning" => Severity::Warning,
+                _ => Severity::Info,
+            },
+            message: d.message,
+        })
+        .collect())
+}
 
-    if file_counts.len() > 10 {
-        result.push_str(&format!("\n... +{} more files\n", file_counts.len() - 10));
-    }
+/// golangci-lint `--out-format=json` output
+fn parse_golangci_json(output: &str) -> Result<Vec<Diagnostic>, serde_json::Error> {
+    let parsed: GolangciOutput = serde_json::from_str(output)?;
+    Ok(parsed
+        .issues
+        .unwrap_or_default()
+        .into_iter()
+        .map(|issue| Diagnostic {
+            file: issue.pos.filename,
+            line: issue.pos.line,
+            column: Some(issue.pos.column),
+            rule: Some(issue.from_linter),
+            severity: if issue.severity == "error" {
+                Severity::Error
+            } else {
+                Severity::Warning
+            },
+            message: issue.text,
+        })
+        .collect())
+}
 
-    result.trim().to_string()
+/// `cargo clippy --message-format=json` output. Diagnostics repeated for
+/// each target (lib, bin, test) are kept once.
+fn parse_clippy_json(output: &str) -> Vec<Diagnostic> {
+    let mut seen = HashSet::new();
+    output
+        .lines()
+        .filter(|line| line.starts_with('{'))
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .filter(|record| record["reason"] == "compiler-message")
+        .filter_map(|record| {
+            let message = &record["message"];
+            let severity = match message["level"].as_str()? {
+                "error" => Severity::Error,
+                "warning" => Severity::Warning,
+                _ => return None,
+            };
+            let spans = message["spans"].as_array()?;
+            // Summaries ("aborting due to ...", "N warnings emitted") carry no span
+            let span = spans
+                .iter()
+                .find(|s| s["is_primary"].as_bool() == Some(true))
+                .or(spans.first())?;
+            Some(Diagnostic {
+                file: span["file_name"].as_str()?.to_string(),
+                line: span["line_start"].as_u64().unwrap_or(0) as usize,
+                column: span["column_start"].as_u64().map(|c| c as usize),
+                rule: message["code"]["code"].as_str().map(String::from),
+                severity,
+                message: message["message"].as_str()?.to_string(),
+            })
+        })
+        .filter(|d| seen.insert((d.file.clone(), d.line, d.column, d.message.clone())))
+        .collect()
 }
 
-/// Filter mypy text output - parse and group by error code and file
-fn filter_mypy_output(output: &str) -> String {
-    // Regex pattern: path/to/file.py:line: error: message [error-code]
+/// mypy text output: `path/to/file.py:line: error: message [error-code]`
+fn parse_mypy_output(output: &str) -> Vec<Diagnostic> {
     let re = Regex::new(r"^(.+\.py):(\d+): (error|warning|note): (.+?) \[(.+?)\]").unwrap();
+    output
+        .lines()
+        .filter_map(|line| re.captures(line))
+        .map(|caps| Diagnostic {
+            file: caps[1].to_string(),
+            line: caps[2].parse().unwrap_or(0),
+            column: None,
+            rule: Some(caps[5].to_string()),
+            severity: match &caps[3] {
+                "error" => Severity::Error,
+                "warning" => Severity::Warning,
+                _ => Severity::Info,
+            },
+            message: caps[4].to_string(),
+        })
+        .collect()
+}
 
-    let mut issues: Vec<(String, String, String, String)> = Vec::new(); // (file, line, level, code)
-    let mut errors = 0;
-    let mut warnings = 0;
-    let mut notes = 0;
-
-    for line in output.lines() {
-        if let Some(caps) = re.captures(line) {
-            let file = caps.get(1).map_or("", |m| m.as_str());
-            let line_num = caps.get(2).map_or("", |m| m.as_str());
-            let level = caps.get(3).map_or("", |m| m.as_str());
-            let code = caps.get(5).map_or("", |m| m.as_str());
-
-            match level {
-                "error" => errors += 1,
-                "warning" => warnings += 1,
-                "note" => notes += 1,
-                _ => {}
-            }
-
-            issues.push((
-                file.to_string(),
-                line_num.to_string(),
-                level.to_string(),
-                code.to_string(),
-            ));
-        }
-    }
-
-    if issues.is_empty() {
-        // Check if mypy output contains "Success" or similar
-        if output.contains("Success") || output.trim().is_empty() {
-            return "✓ Mypy: No issues found".to_string();
-        }
-        // Fallback to generic output if no regex matches
-        return format!("Mypy output:\n{}", truncate(output, 500));
-    }
-
-    // Count unique files
-    let unique_files: std::collections::HashSet<_> = issues.iter().map(|(f, _, _, _)| f).collect();
-    let total_files = unique_files.len();
-
-    // Group by error code
-    let mut by_code: HashMap<String, usize> = HashMap::new();
-    for (_, _, _, code) in &issues {
-        *by_code.entry(code.clone()).or_insert(0) += 1;
+/// Diagnostics grouped by rule with counts, most severe then most frequent
+/// first; each rule shows its first message and its first few locations
+pub fn format_lint_report(tool: &str, diagnostics: &[Diagnostic]) -> String {
+    if diagnostics.is_empty() {
+        return format!("✓ {}: No issues found", tool);
     }
 
-    // Group by file
-    let mut by_file: HashMap<&str, usize> = HashMap::new();
-    for (file, _, _, _) in &issues {
-        *by_file.entry(file.as_str()).or_insert(0) += 1;
-    }
+    let count = |severity| {
+        diagnostics
+            .iter()
+            .filter(|d| d.severity == severity)
+            .count()
+    };
+    let files: HashSet<&str> = diagnostics.iter().map(|d| d.file.as_str()).collect();
 
-    let mut file_counts: Vec<_> = by_file.iter().collect();
-    file_counts.sort_by(|a, b| b.1.cmp(a.1));
+    let mut result = format!(
+        "{}: {} issues in {} files ({} errors, {} warnings)\n",
+        tool,
+        diagnostics.len(),
+        files.len(),
+        count(Severity::Error),
+        count(Severity::Warning)
+    );
+    result.push_str("═══════════════════════════════════════\n");
 
-    // Build output
-    let mut result = String::new();
-    result.push_str(&format!(
-        "Mypy: {} issues in {} files\n",
-        issues.len(),
-        total_files
-    ));
-
-    if errors > 0 || warnings > 0 {
-        result.push_str(&format!("  {} errors, {} warnings", errors, warnings));
-        if notes > 0 {
-            result.push_str(&format!(", {} notes", notes));
+    // Rules in order of first appearance, so ties keep the linter's order
+    let mut by_rule: Vec<(&str, Vec<&Diagnostic>)> = Vec::new();
+    for diag in diagnostics {
+        let rule = diag.rule.as_deref().unwrap_or(&diag.message);
+        match by_rule.iter_mut().find(|(r, _)| *r == rule) {
+            Some((_, group)) => group.push(diag),
+            None => by_rule.push((rule, vec![diag])),
         }
-        result.push('\n');
     }
-
-    result.push_str("═══════════════════════════════════════\n");
-
-    // Show top error codes
-    let mut code_counts: Vec<_> = by_code.iter().collect();
-    code_counts.sort_by(|a, b| b.1.cmp(a.1));
-
-    if !code_counts.is_empty() {
-        result.push_str("Top error codes:\n");
-        for (code, count) in code_counts.iter().take(10) {
-            result.push_str(&format!("  {} ({}x)\n", code, count));
+    by_rule.sort_by_key(|(_, group)| {
+        let severity = group.iter().map(|d| d.severity).min();
+        (severity, std::cmp::Reverse(group.len()))
+    });
+
+    let message_width = layout::line_width(100, 20);
+    for (rule, group) in by_rule.iter().take(MAX_RULES) {
+        let first = group[0];
+        let mut header = format!("{} {} ({}x)", first.severity.label(), rule, group.len());
+        if first.rule.is_some() {
+            header.push_str(&format!(": {}", first.message));
         }
+        result.push_str(&truncate(&header, message_width));
         result.push('\n');
-    }
-
-    // Show top files
-    result.push_str("Top files:\n");
-    for (file, count) in file_counts.iter().take(10) {
-        let short_path = compact_path(file);
-        result.push_str(&format!("  {} ({} issues)\n", short_path, count));
-
-        // Show top 3 error codes in this file
-        let mut file_codes: HashMap<String, usize> = HashMap::new();
-        for (_f, _, _, code) in issues.iter().filter(|(f, _, _, _)| f == *file) {
-            *file_codes.entry(code.clone()).or_insert(0) += 1;
+        for diag in group.iter().take(LOCATIONS_PER_RULE) {
+            result.push_str(&format!("  {}\n", location(diag)));
         }
-
-        let mut file_code_counts: Vec<_> = file_codes.iter().collect();
-        file_code_counts.sort_by(|a, b| b.1.cmp(a.1));
-
-        for (code, count) in file_code_counts.iter().take(3) {
-            result.push_str(&format!("    {} ({})\n", code, count));
+        if group.len() > LOCATIONS_PER_RULE {
+            result.push_str(&format!(
+                "  ... +{} more\n",
+                group.len() - LOCATIONS_PER_RULE
+            ));
         }
     }
-
-    if file_counts.len() > 10 {
-        result.push_str(&format!("\n... +{} more files\n", file_counts.len() - 10));
+    if by_rule.len() > MAX_RULES {
+        result.push_str(&format!(
+            "\n... +{} more rules\n",
+            by_rule.len() - MAX_RULES
+        ));
     }
 
     result.trim().to_string()
 }
 
+/// `file:line[:col]`, with the path compacted
+fn location(diag: &Diagnostic) -> String {
+    let file = paths::compact(&diag.file, layout::path_width());
+    match diag.column {
+        Some(col) => format!("{}:{}:{}", file, diag.line, col),
+        None => format!("{}:{}", file, diag.line),
+    }
+}
+
 /// Filter generic linter output (fallback for non-ESLint linters)
 fn filter_generic_lint(output: &str) -> String {
     let mut warnings = 0;
@@ -561,25 +667,15 @@ fn filter_generic_lint(output: &str) -> String {
     result.trim().to_string()
 }
 
-/// Compact file path (remove common prefixes)
-fn compact_path(path: &str) -> String {
-    // Remove common prefixes like /Users/..., /home/..., C:\
-    let path = path.replace('\\', "/");
-
-    if let Some(pos) = path.rfind("/src/") {
-        format!("src/{}", &path[pos + 5..])
-    } else if let Some(pos) = path.rfind("/lib/") {
-        format!("lib/{}", &path[pos + 5..])
-    } else if let Some(pos) = path.rfind('/') {
-        path[pos + 1..].to_string()
-    } else {
-        path
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::stable::assert_format;
+
+    /// Condensed report for `linter`'s output, without a previous run
+    fn filter_lint_output(linter: &str, stdout: &str, raw: &str) -> String {
+        format_lint_output(linter, stdout, raw, parse_lint_output(linter, stdout, raw))
+    }
 
     #[test]
     fn test_filter_eslint_json() {
@@ -621,30 +717,17 @@ mod tests {
             }
         ]"#;
 
-        let result = filter_eslint_json(json);
+        let result = filter_lint_output("eslint", json, json);
         assert!(result.contains("ESLint:"));
         assert!(result.contains("prefer-const"));
         assert!(result.contains("no-unused-vars"));
         assert!(result.contains("src/utils.ts"));
     }
 
-    #[test]
-    fn test_compact_path() {
-        assert_eq!(
-            compact_path("/Users/foo/project/src/utils.ts"),
-            "src/utils.ts"
-        );
-        assert_eq!(
-            compact_path("C:\\Users\\project\\src\\api.ts"),
-            "src/api.ts"
-        );
-        assert_eq!(compact_path("simple.ts"), "simple.ts");
-    }
-
     #[test]
     fn test_filter_pylint_json_no_issues() {
         let output = "[]";
-        let result = filter_pylint_json(output);
+        let result = filter_lint_output("pylint", output, output);
         assert!(result.contains("✓ Pylint"));
         assert!(result.contains("No issues found"));
     }
@@ -687,7 +770,7 @@ mod tests {
             }
         ]"#;
 
-        let result = filter_pylint_json(json);
+        let result = filter_lint_output("pylint", json, json);
         assert!(result.contains("3 issues"));
         assert!(result.contains("2 files"));
         assert!(result.contains("1 errors, 2 warnings"));
@@ -700,7 +783,7 @@ mod tests {
     #[test]
     fn test_filter_mypy_no_issues() {
         let output = "Success: no issues found in 5 source files";
-        let result = filter_mypy_output(output);
+        let result = filter_lint_output("mypy", "", output);
         assert!(result.contains("✓ Mypy"));
         assert!(result.contains("No issues found"));
     }
@@ -713,7 +796,7 @@ src/utils.py:20: error: Name "foo" is not defined [name-defined]
 src/utils.py:25: warning: Unused "type: ignore" comment [unused-ignore]
 Found 4 errors in 2 files (checked 5 source files)"#;
 
-        let result = filter_mypy_output(output);
+        let result = filter_lint_output("mypy", "", output);
         assert!(result.contains("4 issues"));
         assert!(result.contains("2 files"));
         assert!(result.contains("3 errors, 1 warnings"));
@@ -724,6 +807,110 @@ Found 4 errors in 2 files (checked 5 source files)"#;
         assert!(result.contains("utils.py"));
     }
 
+    #[test]
+    fn test_format_lint_report_limits_locations() {
+        let diagnostics: Vec<Diagnostic> = (1..=5)
+            .map(|line| Diagnostic {
+                file: "src/a.ts".into(),
+                line,
+                column: Some(1),
+                rule: Some("prefer-const".into()),
+                severity: Severity::Warning,
+                message: "Use const".into(),
+            })
+            .chain(std::iter::once(Diagnostic {
+                file: "src/b.ts".into(),
+                line: 9,
+                column: None,
+                rule: Some("no-undef".into()),
+                severity: Severity::Error,
+                message: "x is not defined".into(),
+            }))
+            .collect();
+        let result = format_lint_report("ESLint", &diagnostics);
+        assert!(result.starts_with("ESLint: 6 issues in 2 files (1 errors, 5 warnings)"));
+        // Errors come first even when rarer
+        let error_at = result
+            .find("error no-undef (1x): x is not defined")
+            .unwrap();
+        let warning_at = result.find("warning prefer-const (5x): Use const").unwrap();
+        assert!(error_at < warning_at);
+        assert!(result.contains("src/b.ts:9\n"));
+        assert!(result.contains("src/a.ts:3:1"));
+        assert!(!result.contains("src/a.ts:4:1"));
+        assert!(result.contains("... +2 more"));
+        assert_format!("lint_report", result);
+    }
+
+    #[test]
+    fn test_parse_clippy_json_dedupes_targets() {
+        let record = r#"{"reason":"compiler-message","message":{"level":"warning","message":"unneeded `return` statement","code":{"code":"clippy::needless_return"},"spans":[{"file_name":"src/lib.rs","line_start":4,"column_start":5,"is_primary":true}]}}"#;
+        let summary = r#"{"reason":"compiler-message","message":{"level":"warning","message":"2 warnings emitted","code":null,"spans":[]}}"#;
+        let output = format!("{}\n{}\n{}\n", record, record, summary);
+        let diagnostics = parse_clippy_json(&output);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].rule.as_deref(),
+            Some("clippy::needless_return")
+        );
+        assert_eq!(diagnostics[0].line, 4);
+    }
+
+    #[test]
+    fn test_filter_golangci_and_ruff() {
+        let golangci = r#"{"Issues":[{"FromLinter":"errcheck","Text":"Error return value not checked","Severity":"","Pos":{"Filename":"main.go","Line":42,"Column":5}}]}"#;
+        let result = filter_lint_output("golangci-lint", golangci, golangci);
+        assert!(result.contains("warning errcheck (1x)"));
+        assert!(result.contains("main.go:42:5"));
+        assert_eq!(
+            filter_lint_output("golangci-lint", r#"{"Issues":null}"#, ""),
+            "✓ golangci-lint: No issues found"
+        );
+
+        let ruff = r#"[{"code":"F401","message":"`os` imported but unused","location":{"row":1,"column":8},"filename":"src/main.py"}]"#;
+        let result = filter_lint_output("ruff", ruff, ruff);
+        assert!(result.contains("warning F401 (1x)"));
+        assert_eq!(
+            filter_lint_output("ruff", "", ""),
+            "✓ Ruff: No issues found"
+        );
+    }
+
+    #[test]
+    fn test_format_sarif() {
+        let ruff = r#"[{"code":"F401","message":"`os` imported but unused","location":{"row":1,"column":8},"filename":"src/main.py"}]"#;
+        let out = format_sarif("ruff", &parse_lint_output("ruff", ruff, ruff)).unwrap();
+        let log: Value = serde_json::from_str(&out).unwrap();
+        let result = &log["runs"][0]["results"][0];
+        assert_eq!(result["ruleId"], "F401");
+        assert_eq!(result["level"], "warning");
+        assert_eq!(
+            result["locations"][0]["physicalLocation"]["region"]["startColumn"],
+            8
+        );
+
+        // Unparsed output is a failed run, not a clean one
+        let out = format_sarif("eslint", &parse_lint_output("eslint", "oops", "oops")).unwrap();
+        let log: Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(log["runs"][0]["results"], serde_json::json!([]));
+        assert_eq!(
+            log["runs"][0]["invocations"][0]["executionSuccessful"],
+            false
+        );
+    }
+
+    #[test]
+    fn test_detect_linter() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(detect_linter(dir.path()), "eslint");
+        std::fs::write(dir.path().join("pyproject.toml"), "").unwrap();
+        assert_eq!(detect_linter(dir.path()), "ruff");
+        std::fs::write(dir.path().join("go.mod"), "").unwrap();
+        assert_eq!(detect_linter(dir.path()), "golangci-lint");
+        std::fs::write(dir.path().join("Cargo.toml"), "").unwrap();
+        assert_eq!(detect_linter(dir.path()), "clippy");
+    }
+
     #[test]
     fn test_is_python_linter() {
         assert!(is_python_linter("ruff"));
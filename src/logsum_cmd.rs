@@ -0,0 +1,603 @@
+//! `rtk logsum`: summary of an application or server log instead of its
+//! last few thousand lines. Lines are clustered into templates, their
+//! variable parts (ids, numbers, durations, paths) masked as `<*>`, and
+//! each template is reported with its count and first/last occurrence,
+//! followed by a timeline of the error rate.
+//!
+//! Timestamps are recognized in ISO 8601 (`2024-05-01T10:00:00Z`), access
+//! log (`[01/May/2024:10:00:00 +0000]`) and syslog (`May  1 10:00:00`)
+//! form. Indented lines without one (stack traces) belong to the entry
+//! above them and are not clustered on their own.
+
+use crate::tracking;
+use crate::utils::truncate;
+use anyhow::{Context, Result};
+use chrono::{NaiveDate, NaiveDateTime};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+/// Share of equal tokens for a line to join a template
+const SIMILARITY: f64 = 0.5;
+
+/// Templates compared per token count; past it, new lines open new ones
+const MAX_CANDIDATES: usize = 200;
+
+/// Template text is cut to this many chars
+const TEMPLATE_CHARS: usize = 90;
+
+/// Bucket sizes of the timeline, in seconds, from which the first giving
+/// at most the requested number of buckets is used
+const BUCKET_SECS: [i64; 12] = [
+    1,
+    10,
+    30,
+    60,
+    300,
+    900,
+    1800,
+    3600,
+    3 * 3600,
+    6 * 3600,
+    12 * 3600,
+    86400,
+];
+
+const WILDCARD: &str = "<*>";
+
+lazy_static! {
+    static ref ISO_TS: Regex = Regex::new(
+        r"^\[?(\d{4}-\d{2}-\d{2})[T ](\d{2}:\d{2}:\d{2})(?:[.,]\d+)?(?:Z|[+-]\d{2}:?\d{2})?\]?\s*"
+    )
+    .unwrap();
+    static ref CLF_TS: Regex =
+        Regex::new(r"\[(\d{2}/\w{3}/\d{4}):(\d{2}:\d{2}:\d{2})(?: [+-]\d{4})?\]\s*").unwrap();
+    static ref SYSLOG_TS: Regex =
+        Regex::new(r"^(\w{3}) +(\d{1,2}) (\d{2}:\d{2}:\d{2})\s+").unwrap();
+    static ref LEVEL: Regex =
+        Regex::new(r"(?i)\b(fatal|panic|crit(?:ical)?|error|err|warn(?:ing)?)\b").unwrap();
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Level {
+    Other,
+    Warn,
+    Error,
+}
+
+/// Lines sharing one shape
+#[derive(Debug)]
+struct Template {
+    tokens: Vec<String>,
+    count: usize,
+    /// Most severe level among its lines
+    level: Level,
+    first: Option<NaiveDateTime>,
+    last: Option<NaiveDateTime>,
+}
+
+impl Template {
+    fn text(&self) -> String {
+        self.tokens.join(" ")
+    }
+}
+
+/// Clusters lines into templates, Drain-style: a line joins the most similar
+/// template with as many tokens, the positions that differ becoming `<*>`
+#[derive(Default)]
+struct Miner {
+    templates: Vec<Template>,
+    by_len: HashMap<usize, Vec<usize>>,
+}
+
+impl Miner {
+    fn add(&mut self, tokens: Vec<String>, level: Level, time: Option<NaiveDateTime>) {
+        let candidates = self.by_len.entry(tokens.len()).or_default();
+        let best = candidates
+            .iter()
+            .take(MAX_CANDIDATES)
+            .map(|&i| (i, similarity(&self.templates[i].tokens, &tokens)))
+            .filter(|&(_, score)| score >= SIMILARITY)
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(i, _)| i);
+
+        let template = match best {
+            Some(i) => {
+                let template = &mut self.templates[i];
+                for (kept, token) in template.tokens.iter_mut().zip(&tokens) {
+                    if kept != token {
+                        *kept = generalize(kept, token);
+                    }
+                }
+                template
+            }
+            None => {
+                candidates.push(self.templates.len());
+                self.templates.push(Template {
+                    tokens,
+                    count: 0,
+                    level,
+                    first: None,
+                    last: None,
+                });
+                self.templates.last_mut().expect("just pushed")
+            }
+        };
+        template.count += 1;
+        template.level = template.level.max(level);
+        if let Some(time) = time {
+            template.first = Some(template.first.map_or(time, |t| t.min(time)));
+            template.last = Some(template.last.map_or(time, |t| t.max(time)));
+        }
+    }
+}
+
+/// `<*>` for two different tokens, keeping the key of `key=value` pairs
+/// with the same key
+fn generalize(a: &str, b: &str) -> String {
+    match (a.split_once('='), b.split_once('=')) {
+        (Some((key, _)), Some((other, _))) if key == other && !key.is_empty() => {
+            format!("{}={}", key, WILDCARD)
+        }
+        _ => WILDCARD.to_string(),
+    }
+}
+
+/// Share of positions where the tokens agree. As in Drain, a `<*>` is not
+/// evidence of similarity: only equal tokens (or the same key) count.
+fn similarity(template: &[String], tokens: &[String]) -> f64 {
+    if tokens.is_empty() {
+        return 1.0;
+    }
+    let equal = template
+        .iter()
+        .zip(tokens)
+        .filter(|(a, b)| agrees(a, b))
+        .count();
+    equal as f64 / tokens.len() as f64
+}
+
+fn agrees(template: &str, token: &str) -> bool {
+    template == token
+        || template
+            .strip_suffix(WILDCARD)
+            .and_then(|key| key.strip_suffix('='))
+            .is_some_and(|key| token.split_once('=').is_some_and(|(k, _)| k == key))
+}
+
+/// Timestamp of a line, and the line without it
+fn split_timestamp(line: &str) -> (Option<NaiveDateTime>, String) {
+    if let Some(caps) = ISO_TS.captures(line) {
+        let time = NaiveDateTime::parse_from_str(
+            &format!("{} {}", &caps[1], &caps[2]),
+            "%Y-%m-%d %H:%M:%S",
+        )
+        .ok();
+        return (time, line[caps[0].len()..].to_string());
+    }
+    if let Some(caps) = CLF_TS.captures(line) {
+        let time = NaiveDateTime::parse_from_str(
+            &format!("{} {}", &caps[1], &caps[2]),
+            "%d/%b/%Y %H:%M:%S",
+        )
+        .ok();
+        let whole = caps.get(0).expect("group 0");
+        let rest = format!("{}{}", &line[..whole.start()], &line[whole.end()..]);
+        return (time, rest);
+    }
+    if let Some(caps) = SYSLOG_TS.captures(line) {
+        // Syslog has no year: a leap year keeps Feb 29 valid
+        let time = NaiveDateTime::parse_from_str(
+            &format!("2000 {} {} {}", &caps[1], &caps[2], &caps[3]),
+            "%Y %b %d %H:%M:%S",
+        )
+        .ok();
+        if time.is_some() {
+            return (time, line[caps[0].len()..].to_string());
+        }
+    }
+    (None, line.to_string())
+}
+
+fn level(line: &str) -> Level {
+    match LEVEL.captures(line) {
+        Some(caps) if caps[1].to_lowercase().starts_with("warn") => Level::Warn,
+        Some(_) => Level::Error,
+        None => Level::Other,
+    }
+}
+
+/// Tokens of a line, the obviously variable ones already masked: anything
+/// with a digit, absolute paths, and the values of `key=value` pairs that
+/// are themselves variable
+fn tokenize(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(|token| {
+            if let Some((key, value)) = token.split_once('=') {
+                if !key.is_empty() && is_variable(value) {
+                    return format!("{}={}", key, WILDCARD);
+                }
+            }
+            if is_variable(token) {
+                WILDCARD.to_string()
+            } else {
+                token.to_string()
+            }
+        })
+        .collect()
+}
+
+fn is_variable(token: &str) -> bool {
+    token.chars().any(|c| c.is_ascii_digit())
+        || (token.starts_with('/') && token[1..].contains('/'))
+}
+
+struct Summary {
+    lines: usize,
+    /// Indented lines without a timestamp, counted with the entry above
+    continuations: usize,
+    templates: Vec<Template>,
+    /// Time and level of each timestamped entry
+    events: Vec<(NaiveDateTime, Level)>,
+}
+
+fn summarize(content: &str) -> Summary {
+    let mut miner = Miner::default();
+    let mut events = Vec::new();
+    let mut lines = 0;
+    let mut continuations = 0;
+    let mut seen_timestamp = false;
+
+    for line in content.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        lines += 1;
+        let (time, rest) = split_timestamp(line);
+        if time.is_none() && seen_timestamp && line.starts_with(char::is_whitespace) {
+            continuations += 1;
+            continue;
+        }
+        let level = level(&rest);
+        if let Some(time) = time {
+            seen_timestamp = true;
+            events.push((time, level));
+        }
+        miner.add(tokenize(&rest), level, time);
+    }
+
+    let mut templates = miner.templates;
+    templates.sort_by(|a, b| b.count.cmp(&a.count).then(b.level.cmp(&a.level)));
+    Summary {
+        lines,
+        continuations,
+        templates,
+        events,
+    }
+}
+
+/// Size of the timeline buckets so that the span fits in `buckets`
+fn bucket_secs(span_secs: i64, buckets: usize) -> i64 {
+    let buckets = buckets.max(1) as i64;
+    BUCKET_SECS
+        .iter()
+        .copied()
+        .find(|&secs| span_secs / secs < buckets)
+        .unwrap_or_else(|| (span_secs / buckets + 1).max(86400))
+}
+
+fn bucket_label(secs: i64) -> String {
+    match secs {
+        s if s % 86400 == 0 => format!("{}d", s / 86400),
+        s if s % 3600 == 0 => format!("{}h", s / 3600),
+        s if s % 60 == 0 => format!("{}m", s / 60),
+        s => format!("{}s", s),
+    }
+}
+
+/// Times are shown without the date when the whole log is within a day
+fn time_format(events: &[(NaiveDateTime, Level)]) -> &'static str {
+    let first = events.iter().map(|e| e.0.date()).min();
+    let last = events.iter().map(|e| e.0.date()).max();
+    if first == last {
+        "%H:%M:%S"
+    } else {
+        "%m-%d %H:%M"
+    }
+}
+
+/// Lines per bucket, with the share of errors; empty buckets included so
+/// gaps in the log are visible
+fn timeline(events: &[(NaiveDateTime, Level)], buckets: usize) -> Vec<String> {
+    let (Some(first), Some(last)) = (
+        events.iter().map(|e| e.0).min(),
+        events.iter().map(|e| e.0).max(),
+    ) else {
+        return Vec::new();
+    };
+    let secs = bucket_secs((last - first).num_seconds(), buckets);
+    // Buckets start on multiples of their size, as a clock would show them
+    let epoch = NaiveDate::from_ymd_opt(1970, 1, 1)
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .expect("valid epoch");
+    let start = (first - epoch).num_seconds() / secs * secs;
+    let count = ((last - epoch).num_seconds() - start) / secs + 1;
+
+    let mut totals = vec![(0usize, 0usize); count as usize];
+    for (time, level) in events {
+        let i = (((*time - epoch).num_seconds() - start) / secs) as usize;
+        totals[i].0 += 1;
+        if *level == Level::Error {
+            totals[i].1 += 1;
+        }
+    }
+
+    let format = time_format(events);
+    let max_rate = totals.iter().map(|&(n, e)| rate(e, n)).fold(0.0, f64::max);
+    let mut rows = vec![format!("Error rate ({} buckets):", bucket_label(secs))];
+    for (i, &(lines, errors)) in totals.iter().enumerate() {
+        let at = epoch + chrono::Duration::seconds(start + i as i64 * secs);
+        let bar_len = if max_rate > 0.0 {
+            (rate(errors, lines) / max_rate * 20.0).round() as usize
+        } else {
+            0
+        };
+        rows.push(format!(
+            "  {:<14} {:<20} {:>5.1}% {}/{}",
+            at.format(format).to_string(),
+            "█".repeat(bar_len),
+            rate(errors, lines) * 100.0,
+            errors,
+            lines
+        ));
+    }
+    rows
+}
+
+fn rate(part: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 / total as f64
+    }
+}
+
+fn format_summary(summary: &Summary, top: usize, buckets: usize) -> String {
+    if summary.lines == 0 {
+        return "📊 empty log".to_string();
+    }
+    let count = |level| {
+        summary.events.iter().filter(|e| e.1 == level).count().max(
+            // Logs without timestamps count per template instead
+            summary
+                .templates
+                .iter()
+                .filter(|t| t.level == level && t.first.is_none())
+                .map(|t| t.count)
+                .sum(),
+        )
+    };
+    let (errors, warnings) = (count(Level::Error), count(Level::Warn));
+    let format = time_format(&summary.events);
+    let show = |time: Option<NaiveDateTime>| time.map(|t| t.format(format).to_string());
+
+    let mut out = format!(
+        "📊 {} lines, {} templates",
+        summary.lines,
+        summary.templates.len()
+    );
+    let first = summary.events.iter().map(|e| e.0).min();
+    let last = summary.events.iter().map(|e| e.0).max();
+    if let (Some(first), Some(last)) = (show(first), show(last)) {
+        out.push_str(&format!(", {} → {}", first, last));
+    }
+    out.push_str(&format!(
+        "\n   ❌ {} errors ({:.1}%)  ⚠️  {} warnings",
+        errors,
+        rate(errors, summary.lines - summary.continuations) * 100.0,
+        warnings
+    ));
+    if summary.continuations > 0 {
+        out.push_str(&format!(
+            "  ↳ {} continuation lines folded",
+            summary.continuations
+        ));
+    }
+
+    out.push_str(&format!(
+        "\n\nTemplates ({} of {}):",
+        top.min(summary.templates.len()),
+        summary.templates.len()
+    ));
+    let width = summary
+        .templates
+        .first()
+        .map_or(1, |t| t.count.to_string().len());
+    for template in summary.templates.iter().take(top) {
+        let icon = match template.level {
+            Level::Error => "❌",
+            Level::Warn => "⚠️ ",
+            Level::Other => "  ",
+        };
+        let mut line = format!(
+            "\n  {} ×{:<width$} {}",
+            icon,
+            template.count,
+            truncate(&template.text(), TEMPLATE_CHARS),
+            width = width
+        );
+        match (show(template.first), show(template.last)) {
+            (Some(first), Some(last)) if first == last => line.push_str(&format!("  @ {}", first)),
+            (Some(first), Some(last)) => line.push_str(&format!("  {} → {}", first, last)),
+            _ => {}
+        }
+        out.push_str(&line);
+    }
+    if summary.templates.len() > top {
+        let rest: usize = summary.templates[top..].iter().map(|t| t.count).sum();
+        out.push_str(&format!(
+            "\n  ... +{} templates ({} lines)",
+            summary.templates.len() - top,
+            rest
+        ));
+    }
+
+    let rows = timeline(&summary.events, buckets);
+    if !rows.is_empty() {
+        out.push_str("\n\n");
+        out.push_str(&rows.join("\n"));
+    }
+    out
+}
+
+/// Summarize a log file, or stdin when `file` is `None`
+pub fn run(file: Option<&Path>, top: usize, buckets: usize, verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    let (content, source) = match file {
+        Some(file) => {
+            if verbose > 0 {
+                eprintln!("Summarizing log: {}", file.display());
+            }
+            let bytes =
+                fs::read(file).with_context(|| format!("Failed to read {}", file.display()))?;
+            (
+                String::from_utf8_lossy(&bytes).to_string(),
+                file.display().to_string(),
+            )
+        }
+        None => {
+            let mut bytes = Vec::new();
+            io::stdin()
+                .lock()
+                .read_to_end(&mut bytes)
+                .context("Failed to read from stdin")?;
+            (String::from_utf8_lossy(&bytes).to_string(), "-".to_string())
+        }
+    };
+
+    let summary = summarize(&content);
+    let output = format_summary(&summary, top, buckets);
+    println!("{}", output);
+
+    timer.track(
+        &format!("tail -n 5000 {}", source),
+        "rtk logsum",
+        &content,
+        &output,
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> String {
+        let mut log = String::new();
+        for i in 0..6 {
+            log.push_str(&format!(
+                "2024-05-01T10:0{}:00Z INFO GET /api/users/{} 200 in {}ms\n",
+                i,
+                i * 7,
+                10 + i
+            ));
+        }
+        log.push_str("2024-05-01T10:03:10Z ERROR db timeout after 5000ms user=alice\n");
+        log.push_str("    at db::query (src/db.rs:42)\n");
+        log.push_str("2024-05-01T10:04:20Z ERROR db timeout after 3000ms user=bob\n");
+        log.push_str("2024-05-01T10:05:30Z WARN cache miss ratio high\n");
+        log
+    }
+
+    #[test]
+    fn test_split_timestamp_formats() {
+        let (time, rest) = split_timestamp("2024-05-01 10:00:00,123 INFO up");
+        assert_eq!(time.unwrap().to_string(), "2024-05-01 10:00:00");
+        assert_eq!(rest, "INFO up");
+
+        let (time, rest) =
+            split_timestamp("10.0.0.1 - - [01/May/2024:10:00:00 +0000] \"GET / HTTP/1.1\" 200 12");
+        assert_eq!(time.unwrap().to_string(), "2024-05-01 10:00:00");
+        assert_eq!(rest, "10.0.0.1 - - \"GET / HTTP/1.1\" 200 12");
+
+        let (time, rest) = split_timestamp("May  1 10:00:00 host sshd[42]: accepted");
+        assert_eq!(
+            time.unwrap().format("%m-%d %H:%M").to_string(),
+            "05-01 10:00"
+        );
+        assert_eq!(rest, "host sshd[42]: accepted");
+
+        assert_eq!(split_timestamp("plain line").0, None);
+    }
+
+    #[test]
+    fn test_tokenize_and_level() {
+        assert_eq!(
+            tokenize("GET /api/users/42 took=12ms user=bob from /var/log/app"),
+            ["GET", "<*>", "took=<*>", "user=bob", "from", "<*>"]
+        );
+        assert_eq!(level("ERROR db down"), Level::Error);
+        assert_eq!(level("level=warning slow"), Level::Warn);
+        // Words containing a level name are not levels
+        assert_eq!(level("INFO 0 errors, terrific"), Level::Other);
+    }
+
+    #[test]
+    fn test_summarize_clusters_lines() {
+        let summary = summarize(&sample());
+        assert_eq!(summary.lines, 10);
+        assert_eq!(summary.continuations, 1);
+        let texts: Vec<(String, usize)> = summary
+            .templates
+            .iter()
+            .map(|t| (t.text(), t.count))
+            .collect();
+        assert_eq!(
+            texts,
+            vec![
+                ("INFO GET <*> <*> in <*>".to_string(), 6),
+                ("ERROR db timeout after <*> user=<*>".to_string(), 2),
+                ("WARN cache miss ratio high".to_string(), 1),
+            ]
+        );
+        let errors = &summary.templates[1];
+        assert_eq!(errors.level, Level::Error);
+        assert_eq!(errors.first.unwrap().to_string(), "2024-05-01 10:03:10");
+        assert_eq!(errors.last.unwrap().to_string(), "2024-05-01 10:04:20");
+    }
+
+    #[test]
+    fn test_bucket_secs() {
+        assert_eq!(bucket_secs(0, 10), 1);
+        assert_eq!(bucket_secs(330, 10), 60);
+        assert_eq!(bucket_secs(3 * 86400, 10), 12 * 3600);
+        assert_eq!(bucket_label(900), "15m");
+        assert_eq!(bucket_label(86400), "1d");
+    }
+
+    #[test]
+    fn test_format_summary() {
+        let output = format_summary(&summarize(&sample()), 2, 10);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], "📊 10 lines, 3 templates, 10:00:00 → 10:05:30");
+        assert_eq!(
+            lines[1],
+            "   ❌ 2 errors (22.2%)  ⚠️  1 warnings  ↳ 1 continuation lines folded"
+        );
+        assert_eq!(lines[3], "Templates (2 of 3):");
+        assert_eq!(
+            lines[5],
+            "  ❌ ×2 ERROR db timeout after <*> user=<*>  10:03:10 → 10:04:20"
+        );
+        assert_eq!(lines[6], "  ... +1 templates (1 lines)");
+        assert_eq!(lines[8], "Error rate (1m buckets):");
+        // 10:00 .. 10:05, one row per minute
+        assert_eq!(lines.len(), 15);
+        assert!(lines[12].starts_with("  10:03:00"));
+        assert!(lines[12].ends_with("50.0% 1/2"));
+    }
+}
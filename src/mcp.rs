@@ -0,0 +1,703 @@
+//! `rtk serve --mcp`: Model Context Protocol server over stdio.
+//!
+//! Each tool maps its structured arguments to the equivalent `rtk`
+//! invocation and runs it as a child of the current executable, so results
+//! (and tracking) are exactly those of the CLI. Messages are JSON-RPC 2.0,
+//! one per line.
+
+use anyhow::{Context, Result};
+use serde_json::{json, Map, Value};
+use std::io::{self, BufRead, Write};
+use std::process::{Command, Stdio};
+
+/// Protocol revision answered when the client doesn't ask for one
+const PROTOCOL_VERSION: &str = "2024-11-05";
+
+/// Git subcommands exposed to agents: read-only ones
+const GIT_SUBCOMMANDS: [&str; 5] = ["status", "log", "diff", "show", "branch"];
+
+/// What agents may pass to a git subcommand. Anything else is refused: an
+/// open passthrough reaches `branch -D`, `diff --output=<file>` and friends.
+struct GitRule {
+    subcommand: &'static str,
+    /// Flags, optionally with an attached `=value`
+    flags: &'static [&'static str],
+    /// Flags requiring a value, attached (`-n5`, `--since=1.week`) or next
+    value_flags: &'static [&'static str],
+    /// Revisions and paths
+    positionals: bool,
+}
+
+const GIT_RULES: [GitRule; 5] = [
+    GitRule {
+        subcommand: "status",
+        flags: &[
+            "-s",
+            "--short",
+            "-b",
+            "--branch",
+            "--porcelain",
+            "-u",
+            "--untracked-files",
+            "--ignored",
+        ],
+        value_flags: &[],
+        positionals: true,
+    },
+    GitRule {
+        subcommand: "log",
+        flags: &[
+            "--oneline",
+            "--stat",
+            "--shortstat",
+            "--name-only",
+            "--name-status",
+            "-p",
+            "--patch",
+            "--graph",
+            "--all",
+            "--merges",
+            "--no-merges",
+            "--first-parent",
+            "--reverse",
+            "--decorate",
+            "--follow",
+            "--abbrev-commit",
+        ],
+        value_flags: &[
+            "-n",
+            "--max-count",
+            "--skip",
+            "--since",
+            "--until",
+            "--after",
+            "--before",
+            "--author",
+            "--grep",
+            "--format",
+            "--pretty",
+            "-S",
+            "-G",
+        ],
+        positionals: true,
+    },
+    GitRule {
+        subcommand: "diff",
+        flags: &[
+            "--stat",
+            "--shortstat",
+            "--numstat",
+            "--name-only",
+            "--name-status",
+            "--cached",
+            "--staged",
+            "--merge-base",
+            "-w",
+            "--ignore-all-space",
+            "-b",
+            "--ignore-space-change",
+            "--word-diff",
+            "-R",
+        ],
+        value_flags: &["-U", "--unified", "--diff-filter"],
+        positionals: true,
+    },
+    GitRule {
+        subcommand: "show",
+        flags: &[
+            "--stat",
+            "--shortstat",
+            "--name-only",
+            "--name-status",
+            "--oneline",
+            "-p",
+            "--patch",
+            "-s",
+            "--no-patch",
+        ],
+        value_flags: &["-U", "--unified", "--format", "--pretty"],
+        positionals: true,
+    },
+    GitRule {
+        subcommand: "branch",
+        // Listing only: a name would create, rename or delete a branch
+        flags: &[
+            "-a",
+            "--all",
+            "-r",
+            "--remotes",
+            "-v",
+            "-vv",
+            "--verbose",
+            "--list",
+            "--merged",
+        ],
+        value_flags: &[],
+        positionals: false,
+    },
+];
+
+type Args = Map<String, Value>;
+
+struct Tool {
+    name: &'static str,
+    description: &'static str,
+    /// JSON Schema properties and the required ones
+    properties: fn() -> Value,
+    required: &'static [&'static str],
+    /// Structured arguments → `rtk` argv
+    argv: fn(&Args) -> Result<Vec<String>>,
+}
+
+const TOOLS: &[Tool] = &[
+    Tool {
+        name: "grep",
+        description: "Search files for a regex; matches grouped by file, lines truncated",
+        properties: || {
+            json!({
+                "pattern": { "type": "string", "description": "Regex (or literal with fixed_string)" },
+                "path": { "type": "string", "description": "File, directory or glob (default: .)" },
+                "file_type": { "type": "string", "description": "Restrict to a file type, e.g. rust, py, ts" },
+                "ignore_case": { "type": "boolean" },
+                "case_sensitive": { "type": "boolean", "description": "Match case even for lowercase patterns (default: smart case)" },
+                "fixed_string": { "type": "boolean" },
+                "context": { "type": "integer", "description": "Lines of context around matches" },
+                "max_results": { "type": "integer" },
+                "per_file": { "type": "integer", "description": "Most lines shown per file" },
+                "scope": { "type": "boolean", "description": "Name the function or class around each match" }
+            })
+        },
+        required: &["pattern"],
+        argv: |args| {
+            let mut argv = vec!["grep".to_string()];
+            push_value(&mut argv, args, "file_type", "--type")?;
+            push_flag(&mut argv, args, "ignore_case", "-i");
+            push_flag(&mut argv, args, "case_sensitive", "-s");
+            push_flag(&mut argv, args, "fixed_string", "-F");
+            push_value(&mut argv, args, "context", "-C")?;
+            push_value(&mut argv, args, "max_results", "--max")?;
+            push_value(&mut argv, args, "per_file", "--per-file")?;
+            push_flag(&mut argv, args, "scope", "--scope");
+            argv.push("--".to_string());
+            argv.push(required(args, "pattern")?);
+            argv.push(optional(args, "path").unwrap_or_else(|| ".".to_string()));
+            Ok(argv)
+        },
+    },
+    Tool {
+        name: "json",
+        description: "Schema of a JSON file or URL (keys and types, no values), or one subtree",
+        properties: || {
+            json!({
+                "file": { "type": "string", "description": "JSON file path or http(s) URL" },
+                "depth": { "type": "integer" },
+                "path": { "type": "string", "description": "Subtree: JSON Pointer or dotted path" },
+                "values": { "type": "boolean", "description": "Values of the subtree instead of its schema" }
+            })
+        },
+        required: &["file"],
+        argv: |args| {
+            let mut argv = vec!["json".to_string()];
+            push_value(&mut argv, args, "depth", "--depth")?;
+            push_value(&mut argv, args, "path", "--path")?;
+            push_flag(&mut argv, args, "values", "--values");
+            argv.push("--".to_string());
+            argv.push(required(args, "file")?);
+            Ok(argv)
+        },
+    },
+    Tool {
+        name: "tree",
+        description: "Directory tree with sizes, respecting .gitignore",
+        properties: || {
+            json!({
+                "path": { "type": "string" },
+                "depth": { "type": "integer" }
+            })
+        },
+        required: &[],
+        argv: |args| {
+            let mut argv = vec!["tree".to_string()];
+            push_value(&mut argv, args, "depth", "-L")?;
+            argv.extend(path(args, "path")?);
+            Ok(argv)
+        },
+    },
+    Tool {
+        name: "diff",
+        description: "Changed lines between two files, or a summary of git diff when none given",
+        properties: || {
+            json!({
+                "file1": { "type": "string" },
+                "file2": { "type": "string" },
+                "staged": { "type": "boolean", "description": "Summarize staged changes" }
+            })
+        },
+        required: &[],
+        argv: |args| {
+            let mut argv = vec!["diff".to_string()];
+            push_flag(&mut argv, args, "staged", "--staged");
+            argv.extend(path(args, "file1")?);
+            argv.extend(path(args, "file2")?);
+            Ok(argv)
+        },
+    },
+    Tool {
+        name: "read",
+        description: "Read a file with comments/boilerplate filtered out",
+        properties: || {
+            json!({
+                "file": { "type": "string" },
+                "level": { "type": "string", "enum": ["none", "minimal", "aggressive"] },
+                "max_lines": { "type": "integer" }
+            })
+        },
+        required: &["file"],
+        argv: |args| {
+            let mut argv = vec!["read".to_string()];
+            push_value(&mut argv, args, "level", "--level")?;
+            push_value(&mut argv, args, "max_lines", "--max-lines")?;
+            argv.push("--".to_string());
+            argv.push(required(args, "file")?);
+            Ok(argv)
+        },
+    },
+    Tool {
+        name: "find",
+        description: "Find files by glob, grouped by directory",
+        properties: || {
+            json!({
+                "pattern": { "type": "string", "description": "Glob, e.g. *.rs" },
+                "path": { "type": "string" },
+                "max_results": { "type": "integer" }
+            })
+        },
+        required: &["pattern"],
+        argv: |args| {
+            let mut argv = vec!["find".to_string()];
+            push_value(&mut argv, args, "max_results", "--max")?;
+            argv.push("--".to_string());
+            argv.push(required(args, "pattern")?);
+            argv.push(optional(args, "path").unwrap_or_else(|| ".".to_string()));
+            Ok(argv)
+        },
+    },
+    Tool {
+        name: "ls",
+        description: "Compact directory listing",
+        properties: || json!({ "path": { "type": "string" } }),
+        required: &[],
+        argv: |args| {
+            let mut argv = vec!["ls".to_string()];
+            argv.extend(path(args, "path")?);
+            Ok(argv)
+        },
+    },
+    Tool {
+        name: "git",
+        description: "Compact output of read-only git commands: status, log, diff, show, branch",
+        properties: || {
+            json!({
+                "subcommand": { "type": "string", "enum": GIT_SUBCOMMANDS },
+                "args": { "type": "array", "items": { "type": "string" } }
+            })
+        },
+        required: &["subcommand"],
+        argv: |args| {
+            let subcommand = required(args, "subcommand")?;
+            if !GIT_SUBCOMMANDS.contains(&subcommand.as_str()) {
+                anyhow::bail!(
+                    "Unsupported git subcommand '{}' (expected one of {})",
+                    subcommand,
+                    GIT_SUBCOMMANDS.join(", ")
+                );
+            }
+            let extra: Vec<String> = match args.get("args").and_then(Value::as_array) {
+                Some(extra) => extra
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(String::from)
+                    .collect(),
+                None => Vec::new(),
+            };
+            check_git_args(&subcommand, &extra)?;
+
+            let mut argv = vec!["git".to_string(), subcommand];
+            argv.extend(extra);
+            Ok(argv)
+        },
+    },
+];
+
+pub fn run(verbose: u8) -> Result<()> {
+    let stdin = io::stdin();
+    let mut stdout = io::stdout().lock();
+
+    for line in stdin.lock().lines() {
+        let line = line.context("Failed to read from stdin")?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if verbose > 0 {
+            eprintln!("mcp ← {}", line);
+        }
+
+        let response = match serde_json::from_str::<Value>(&line) {
+            Ok(message) => handle_message(&message),
+            Err(e) => Some(error_response(
+                Value::Null,
+                -32700,
+                &format!("Parse error: {}", e),
+            )),
+        };
+
+        if let Some(response) = response {
+            writeln!(stdout, "{}", response)?;
+            stdout.flush()?;
+        }
+    }
+    Ok(())
+}
+
+/// Response to one message; notifications (no `id`) get none
+fn handle_message(message: &Value) -> Option<Value> {
+    let id = message.get("id")?.clone();
+    let method = message.get("method").and_then(Value::as_str).unwrap_or("");
+    let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+    let result = match method {
+        "initialize" => Ok(initialize(&params)),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(list_tools()),
+        "tools/call" => call_tool(&params),
+        _ => {
+            return Some(error_response(
+                id,
+                -32601,
+                &format!("Unknown method: {}", method),
+            ))
+        }
+    };
+
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(e) => error_response(id, -32602, &e.to_string()),
+    })
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message }
+    })
+}
+
+fn initialize(params: &Value) -> Value {
+    let version = params
+        .get("protocolVersion")
+        .and_then(Value::as_str)
+        .unwrap_or(PROTOCOL_VERSION);
+    json!({
+        "protocolVersion": version,
+        "capabilities": { "tools": {} },
+        "serverInfo": { "name": "rtk", "version": env!("CARGO_PKG_VERSION") }
+    })
+}
+
+fn list_tools() -> Value {
+    let tools: Vec<Value> = TOOLS
+        .iter()
+        .map(|tool| {
+            json!({
+                "name": tool.name,
+                "description": tool.description,
+                "inputSchema": {
+                    "type": "object",
+                    "properties": (tool.properties)(),
+                    "required": tool.required,
+                }
+            })
+        })
+        .collect();
+    json!({ "tools": tools })
+}
+
+/// Unknown tools and bad arguments are protocol errors; a failing command
+/// is a tool result with `isError`, so the agent sees its message
+fn call_tool(params: &Value) -> Result<Value> {
+    let name = params
+        .get("name")
+        .and_then(Value::as_str)
+        .context("Missing tool name")?;
+    let tool = TOOLS
+        .iter()
+        .find(|t| t.name == name)
+        .with_context(|| format!("Unknown tool: {}", name))?;
+    let empty = Map::new();
+    let args = params
+        .get("arguments")
+        .and_then(Value::as_object)
+        .unwrap_or(&empty);
+    let argv = (tool.argv)(args)?;
+
+    let exe = std::env::current_exe().context("Cannot locate the rtk executable")?;
+    let output = Command::new(exe)
+        .args(&argv)
+        .stdin(Stdio::null())
+        // The agent needs the error message, not a backtrace
+        .env("RUST_LIB_BACKTRACE", "0")
+        .output()
+        .with_context(|| format!("Failed to run rtk {}", argv.join(" ")))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    // grep exits 1 on no match but still prints a result
+    let is_error = !output.status.success() && stdout.trim().is_empty();
+    let text = if is_error {
+        stderr.trim()
+    } else {
+        stdout.trim_end()
+    };
+
+    Ok(json!({
+        "content": [{ "type": "text", "text": text }],
+        "isError": is_error,
+    }))
+}
+
+/// Refuse any git argument outside the subcommand's [`GitRule`]
+fn check_git_args(subcommand: &str, args: &[String]) -> Result<()> {
+    let rule = GIT_RULES
+        .iter()
+        .find(|r| r.subcommand == subcommand)
+        .with_context(|| format!("Unsupported git subcommand '{}'", subcommand))?;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--" || !arg.starts_with('-') || arg == "-" {
+            if rule.positionals {
+                // Everything after `--` is a path
+                if arg == "--" {
+                    return Ok(());
+                }
+                continue;
+            }
+            anyhow::bail!(
+                "git {} takes no revisions or names here (got '{}')",
+                subcommand,
+                arg
+            );
+        }
+
+        let name = arg.split_once('=').map_or(arg.as_str(), |(name, _)| name);
+        if rule.flags.contains(&name) {
+            continue;
+        }
+        if rule.value_flags.contains(&name) {
+            if name == arg {
+                match iter.next() {
+                    Some(value) if !value.starts_with('-') => {}
+                    _ => anyhow::bail!("git {} {} needs a value", subcommand, arg),
+                }
+            }
+            continue;
+        }
+        // Short value flag with the value attached: `-n5`, `-U0`
+        let attached = rule
+            .value_flags
+            .iter()
+            .any(|f| !f.starts_with("--") && arg.len() > f.len() && arg.starts_with(f));
+        if !attached {
+            anyhow::bail!(
+                "Unsupported flag '{}' for git {} (read-only flags only)",
+                arg,
+                subcommand
+            );
+        }
+    }
+    Ok(())
+}
+
+fn required(args: &Args, key: &str) -> Result<String> {
+    optional(args, key).with_context(|| format!("Missing argument: {}", key))
+}
+
+/// Strings as-is, numbers and booleans in their CLI spelling
+fn optional(args: &Args, key: &str) -> Option<String> {
+    match args.get(key)? {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// Agent-supplied values never start with `-`: rtk's global flags (`--out`,
+/// `--tee`) and the passthrough tools' own would parse them. Positionals
+/// of clap-parsed commands go after `--` instead, so a pattern may.
+fn not_a_flag(key: &str, value: String) -> Result<String> {
+    if value.starts_with('-') {
+        anyhow::bail!("{} can't start with '-' (got '{}')", key, value);
+    }
+    Ok(value)
+}
+
+/// Path of a command that parses its own arguments, where `--` isn't a separator
+fn path(args: &Args, key: &str) -> Result<Option<String>> {
+    optional(args, key)
+        .map(|value| not_a_flag(key, value))
+        .transpose()
+}
+
+fn push_value(argv: &mut Vec<String>, args: &Args, key: &str, flag: &str) -> Result<()> {
+    if let Some(value) = optional(args, key) {
+        argv.push(flag.to_string());
+        argv.push(not_a_flag(key, value)?);
+    }
+    Ok(())
+}
+
+fn push_flag(argv: &mut Vec<String>, args: &Args, key: &str, flag: &str) {
+    if args.get(key).and_then(Value::as_bool) == Some(true) {
+        argv.push(flag.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(method: &str, params: Value) -> Value {
+        json!({ "jsonrpc": "2.0", "id": 7, "method": method, "params": params })
+    }
+
+    #[test]
+    fn test_initialize_and_list() {
+        let init = handle_message(&request("initialize", json!({}))).unwrap();
+        assert_eq!(init["id"], 7);
+        assert_eq!(init["result"]["protocolVersion"], PROTOCOL_VERSION);
+        assert_eq!(init["result"]["serverInfo"]["name"], "rtk");
+
+        let list = handle_message(&request("tools/list", Value::Null)).unwrap();
+        let tools = list["result"]["tools"].as_array().unwrap();
+        assert_eq!(tools.len(), TOOLS.len());
+        let grep = tools.iter().find(|t| t["name"] == "grep").unwrap();
+        assert_eq!(grep["inputSchema"]["required"], json!(["pattern"]));
+    }
+
+    #[test]
+    fn test_notifications_and_errors() {
+        let notification = json!({ "jsonrpc": "2.0", "method": "notifications/initialized" });
+        assert!(handle_message(&notification).is_none());
+
+        let unknown = handle_message(&request("resources/list", Value::Null)).unwrap();
+        assert_eq!(unknown["error"]["code"], -32601);
+
+        let bad = handle_message(&request("tools/call", json!({ "name": "rm" }))).unwrap();
+        assert_eq!(bad["error"]["code"], -32602);
+        assert!(bad["error"]["message"].as_str().unwrap().contains("rm"));
+    }
+
+    fn argv(tool: &str, args: Value) -> Result<Vec<String>> {
+        let tool = TOOLS.iter().find(|t| t.name == tool).unwrap();
+        (tool.argv)(args.as_object().unwrap())
+    }
+
+    #[test]
+    fn test_tool_argv() {
+        assert_eq!(
+            argv(
+                "grep",
+                json!({ "pattern": "fn main", "ignore_case": true, "max_results": 5 })
+            )
+            .unwrap(),
+            ["grep", "-i", "--max", "5", "--", "fn main", "."]
+        );
+        assert_eq!(
+            argv("tree", json!({ "path": "src", "depth": 2 })).unwrap(),
+            ["tree", "-L", "2", "src"]
+        );
+        assert_eq!(
+            argv("read", json!({ "file": "a.rs", "level": "none" })).unwrap(),
+            ["read", "--level", "none", "--", "a.rs"]
+        );
+        assert_eq!(
+            argv("git", json!({ "subcommand": "log", "args": ["-n", "3"] })).unwrap(),
+            ["git", "log", "-n", "3"]
+        );
+        assert!(argv("git", json!({ "subcommand": "push" })).is_err());
+        assert!(argv("json", json!({})).is_err());
+    }
+
+    #[test]
+    fn test_arguments_cannot_become_flags() {
+        // `--out=<file>` as a pattern would have rtk write the file
+        let grep = argv(
+            "grep",
+            json!({ "pattern": "--out=/tmp/mcp_out", "path": "NEEDLE" }),
+        )
+        .unwrap();
+        assert_eq!(grep, ["grep", "--", "--out=/tmp/mcp_out", "NEEDLE"]);
+        let find = argv("find", json!({ "pattern": "--tee", "path": "-x" })).unwrap();
+        assert_eq!(find, ["find", "--", "--tee", "-x"]);
+        let json = argv("json", json!({ "file": "--budget=1" })).unwrap();
+        assert_eq!(json.last().unwrap(), "--budget=1");
+        assert_eq!(json[json.len() - 2], "--");
+
+        // Values of flags, and paths of commands parsing their own arguments
+        assert!(argv("grep", json!({ "pattern": "x", "context": "--tee" })).is_err());
+        assert!(argv("json", json!({ "file": "a.json", "path": "--out=x" })).is_err());
+        assert!(argv("read", json!({ "file": "a.rs", "level": "--format=json" })).is_err());
+        assert!(argv("tree", json!({ "path": "-o/tmp/x" })).is_err());
+        assert!(argv("tree", json!({ "depth": "--out=x" })).is_err());
+        assert!(argv("ls", json!({ "path": "--out=/tmp/x" })).is_err());
+        assert!(argv("diff", json!({ "file1": "--output=/tmp/x" })).is_err());
+        assert!(argv("diff", json!({ "file1": "a", "file2": "--tee" })).is_err());
+    }
+
+    fn git(subcommand: &str, args: &[&str]) -> Result<Vec<String>> {
+        argv("git", json!({ "subcommand": subcommand, "args": args }))
+    }
+
+    #[test]
+    fn test_git_allows_read_only_args() {
+        assert!(git("log", &["--oneline", "-n", "3", "-n5", "main..HEAD"]).is_ok());
+        assert!(git("log", &["--since=1.week", "--author", "ada", "--", "src"]).is_ok());
+        assert!(git("diff", &["--stat", "-U0", "main", "--", "--output=x"]).is_ok());
+        assert!(git("status", &["-s", "--untracked-files=no"]).is_ok());
+        assert!(git("show", &["--stat", "--format=%h %s", "HEAD~1"]).is_ok());
+        assert!(git("branch", &["-a", "-v", "--list", "--merged"]).is_ok());
+        assert!(git("branch", &["-r", "--merged=main"]).is_ok());
+    }
+
+    #[test]
+    fn test_git_refuses_writes() {
+        // Branch names create, rename or delete branches
+        assert!(git("branch", &["zz-x"]).is_err());
+        assert!(git("branch", &["-D", "x"]).is_err());
+        assert!(git("branch", &["-m", "a", "b"]).is_err());
+        assert!(git("branch", &["--list", "--", "x"]).is_err());
+        // --output / -o write files
+        for subcommand in GIT_SUBCOMMANDS {
+            assert!(
+                git(subcommand, &["--output=/tmp/x"]).is_err(),
+                "{}",
+                subcommand
+            );
+            assert!(
+                git(subcommand, &["--output", "/tmp/x"]).is_err(),
+                "{}",
+                subcommand
+            );
+            assert!(
+                git(subcommand, &["-o", "/tmp/x"]).is_err(),
+                "{}",
+                subcommand
+            );
+        }
+        // A value flag can't smuggle another flag in as its value
+        assert!(git("log", &["-n", "--output=/tmp/x"]).is_err());
+        assert!(git("diff", &["--ext-diff"]).is_err());
+    }
+}
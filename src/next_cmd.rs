@@ -1,23 +1,18 @@
 use crate::tracking;
-use crate::utils::{strip_ansi, truncate};
+use crate::utils::{self, strip_ansi, truncate};
 use anyhow::{Context, Result};
 use regex::Regex;
-use std::process::Command;
 
 pub fn run(args: &[String], verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
     // Try next directly first, fallback to npx if not found
-    let next_exists = Command::new("which")
-        .arg("next")
-        .output()
-        .map(|o| o.status.success())
-        .unwrap_or(false);
+    let next_exists = utils::tool_exists("next");
 
     let mut cmd = if next_exists {
-        Command::new("next")
+        utils::tool_command("next")
     } else {
-        let mut c = Command::new("npx");
+        let mut c = utils::tool_command("npx");
         c.arg("next");
         c
     };
@@ -1,11 +1,23 @@
 use crate::tracking;
+use crate::utils;
 use anyhow::{Context, Result};
-use std::process::Command;
+
+/// npm subcommands that install dependencies instead of running a script
+const INSTALL_COMMANDS: [&str; 3] = ["ci", "install", "i"];
 
 pub fn run(args: &[String], verbose: u8, skip_env: bool) -> Result<()> {
+    match args.first().map(String::as_str) {
+        // `rtk npm run ci` forces the script over the npm subcommand
+        Some("run") => run_script(&args[1..], verbose, skip_env),
+        Some(sub) if INSTALL_COMMANDS.contains(&sub) => run_install(args, verbose),
+        _ => run_script(args, verbose, skip_env),
+    }
+}
+
+fn run_script(args: &[String], verbose: u8, skip_env: bool) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
-    let mut cmd = Command::new("npm");
+    let mut cmd = utils::tool_command("npm");
     cmd.arg("run");
 
     for arg in args {
@@ -42,6 +54,111 @@ pub fn run(args: &[String], verbose: u8, skip_env: bool) -> Result<()> {
     Ok(())
 }
 
+/// Run `npm ci` / `npm install` keeping only the install summary
+fn run_install(args: &[String], verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    if verbose > 0 {
+        eprintln!("Running: npm {}", args.join(" "));
+    }
+
+    let output = utils::tool_command("npm")
+        .args(args)
+        .output()
+        .context("Failed to run npm")?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let raw = format!("{}\n{}", stdout, stderr);
+
+    let filtered = filter_install_output(&raw);
+    println!("{}", filtered);
+
+    timer.track(
+        &format!("npm {}", args.join(" ")),
+        &format!("rtk npm {}", args.join(" ")),
+        &raw,
+        &filtered,
+    );
+
+    if !output.status.success() {
+        std::process::exit(output.status.code().unwrap_or(1));
+    }
+
+    Ok(())
+}
+
+/// Filter `npm ci` / `npm install` / `pnpm install` output: keep package
+/// counts, peer-dependency warnings and the audit summary; error blocks are
+/// kept verbatim. Progress, deprecation and funding lines are dropped.
+pub fn filter_install_output(output: &str) -> String {
+    let mut result: Vec<&str> = Vec::new();
+    // Inside a multi-line pnpm peer-dependency tree
+    let mut in_peer_tree = false;
+    // Once pnpm reports an error, everything after it is the error block
+    let mut in_pnpm_error = false;
+
+    for line in output.lines() {
+        let trimmed = line.trim();
+        if is_install_progress(trimmed) {
+            continue;
+        }
+        if in_pnpm_error {
+            result.push(line);
+            continue;
+        }
+        if trimmed.starts_with("ERR_PNPM_") {
+            in_pnpm_error = true;
+            result.push(line);
+            continue;
+        }
+        if trimmed.starts_with("npm ERR!") || trimmed.starts_with("npm error") {
+            result.push(line);
+            continue;
+        }
+
+        if in_peer_tree {
+            if trimmed == "." || trimmed.starts_with(['├', '└', '│', '┌']) {
+                result.push(line);
+                continue;
+            }
+            in_peer_tree = false;
+        }
+
+        let lower = trimmed.to_lowercase();
+        if lower.contains("warn") && lower.contains("peer") {
+            result.push(trimmed);
+            in_peer_tree = !trimmed.starts_with("npm");
+            continue;
+        }
+
+        let is_count = trimmed.starts_with("added ")
+            || trimmed.starts_with("removed ")
+            || trimmed.starts_with("changed ")
+            || trimmed.starts_with("up to date")
+            || trimmed.starts_with("Packages: ")
+            || trimmed.starts_with("Done in ");
+        let is_audit = lower.contains("vulnerabilit") && !lower.starts_with("npm warn");
+        if is_count || is_audit {
+            result.push(trimmed);
+        }
+    }
+
+    if result.is_empty() {
+        "ok ✓".to_string()
+    } else {
+        result.join("\n")
+    }
+}
+
+/// Progress and spinner lines from npm (`⸨░░░⸩ reify:`) and pnpm
+/// (`Progress: resolved 120, reused 118`, `++++++++---`)
+fn is_install_progress(line: &str) -> bool {
+    line.starts_with("Progress: ")
+        || line.contains('⸨')
+        || line.contains("reify:")
+        || (!line.is_empty() && line.chars().all(|c| c == '+' || c == '-'))
+}
+
 /// Filter npm run output - strip boilerplate, progress bars, npm WARN
 fn filter_npm_output(output: &str) -> String {
     let mut result = Vec::new();
@@ -100,6 +217,73 @@ npm notice
         assert!(result.contains("Build completed"));
     }
 
+    #[test]
+    fn test_filter_install_output_npm() {
+        let output = r#"npm warn deprecated inflight@1.0.6: This module is not supported
+npm warn ERESOLVE overriding peer dependency
+npm warn While resolving: react-dom@18.2.0
+⸨░░░░░░░░░░⸩ ⠙ reify:lodash: timing reifyNode
+added 312 packages, removed 4 packages, and audited 316 packages in 9s
+
+48 packages are looking for funding
+  run `npm fund` for details
+
+2 vulnerabilities (1 moderate, 1 high)
+
+To address all issues, run:
+  npm audit fix
+"#;
+        let result = filter_install_output(output);
+        assert_eq!(
+            result,
+            "npm warn ERESOLVE overriding peer dependency\n\
+             added 312 packages, removed 4 packages, and audited 316 packages in 9s\n\
+             2 vulnerabilities (1 moderate, 1 high)"
+        );
+    }
+
+    #[test]
+    fn test_filter_install_output_pnpm_peer_tree_and_error() {
+        let output = r#"Progress: resolved 1, reused 0, downloaded 0, added 0
+Progress: resolved 120, reused 118, downloaded 2, added 120, done
+ WARN  Issues with peer dependencies found
+.
+└─┬ @testing-library/react 12.1.5
+  └── ✕ unmet peer react@"<18.0.0": found 18.2.0
+
+Packages: +120 -3
+++++++++++++++++++++++++++++++++++++++---
+dependencies:
++ react 18.2.0
+
+Done in 3.4s
+"#;
+        let result = filter_install_output(output);
+        assert!(result.starts_with("WARN  Issues with peer dependencies found\n"));
+        assert!(result.contains("  └── ✕ unmet peer react"));
+        assert!(result.contains("Packages: +120 -3\nDone in 3.4s"));
+        assert!(!result.contains("Progress"));
+        assert!(!result.contains("+ react"));
+
+        let output = r#"Progress: resolved 3, reused 3, downloaded 0, added 0
+ ERR_PNPM_OUTDATED_LOCKFILE  Cannot install with "frozen-lockfile"
+
+Note that in CI environments this setting is true by default.
+"#;
+        let result = filter_install_output(output);
+        assert_eq!(
+            result,
+            " ERR_PNPM_OUTDATED_LOCKFILE  Cannot install with \"frozen-lockfile\"\n\n\
+             Note that in CI environments this setting is true by default."
+        );
+    }
+
+    #[test]
+    fn test_filter_install_output_npm_error_verbatim() {
+        let output = "npm error code EUSAGE\nnpm error\nnpm error The `npm ci` command can only install with an existing package-lock.json\n";
+        assert_eq!(filter_install_output(output), output.trim_end());
+    }
+
     #[test]
     fn test_filter_npm_output_empty() {
         let output = "\n\n\n";
@@ -0,0 +1,530 @@
+//! `rtk outline`: the signatures of a source file with their line numbers,
+//! nested as in the source, so a long file can be navigated before reading it.
+//!
+//! Files are parsed with tree-sitter and declarations picked out by a
+//! per-language query, so items nested in bodies and signatures spanning
+//! lines are found, while look-alikes in strings and comments are not.
+
+use crate::cache;
+use crate::filter::Language;
+use crate::tracking;
+use anyhow::{Context, Result};
+use lazy_static::lazy_static;
+use std::fs;
+use std::path::Path;
+use tree_sitter::{Node, Parser, Query, QueryCursor, StreamingIterator, Tree};
+
+/// Longer signatures are cut
+const MAX_SIGNATURE_CHARS: usize = 160;
+
+const RUST_QUERY: &str = r#"
+[
+  (function_item)
+  (function_signature_item)
+  (struct_item)
+  (enum_item)
+  (union_item)
+  (trait_item)
+  (impl_item)
+  (mod_item)
+  (type_item)
+  (macro_definition)
+] @item
+"#;
+
+/// Declarations shared by JavaScript and TypeScript
+const JS_QUERY: &str = r#"
+[
+  (function_declaration)
+  (generator_function_declaration)
+  (class_declaration)
+  (method_definition)
+] @item
+(lexical_declaration
+  (variable_declarator
+    value: [(arrow_function) (function_expression)] @function)) @item
+"#;
+
+const TS_QUERY: &str = r#"
+[
+  (function_signature)
+  (abstract_class_declaration)
+  (interface_declaration)
+  (type_alias_declaration)
+  (enum_declaration)
+  (internal_module)
+] @item
+"#;
+
+const PYTHON_QUERY: &str = r#"
+[
+  (function_definition)
+  (class_definition)
+] @item
+"#;
+
+const GO_QUERY: &str = r#"
+[
+  (function_declaration)
+  (method_declaration)
+  (type_declaration)
+] @item
+"#;
+
+lazy_static! {
+    static ref RUST: Grammar = Grammar::new(tree_sitter_rust::LANGUAGE.into(), RUST_QUERY);
+    static ref TYPESCRIPT: Grammar = Grammar::new(
+        tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+        &format!("{}{}", JS_QUERY, TS_QUERY)
+    );
+    static ref TSX: Grammar = Grammar::new(
+        tree_sitter_typescript::LANGUAGE_TSX.into(),
+        &format!("{}{}", JS_QUERY, TS_QUERY)
+    );
+    static ref JAVASCRIPT: Grammar =
+        Grammar::new(tree_sitter_javascript::LANGUAGE.into(), JS_QUERY);
+    static ref PYTHON: Grammar = Grammar::new(tree_sitter_python::LANGUAGE.into(), PYTHON_QUERY);
+    static ref GO: Grammar = Grammar::new(tree_sitter_go::LANGUAGE.into(), GO_QUERY);
+}
+
+/// A tree-sitter grammar and the query capturing its declarations
+struct Grammar {
+    language: tree_sitter::Language,
+    query: Query,
+}
+
+impl Grammar {
+    fn new(language: tree_sitter::Language, query: &str) -> Self {
+        let query = Query::new(&language, query).expect("valid outline query");
+        Grammar { language, query }
+    }
+
+    fn parse(&self, content: &str) -> Option<Tree> {
+        let mut parser = Parser::new();
+        parser.set_language(&self.language).ok()?;
+        parser.parse(content, None)
+    }
+}
+
+/// One declaration of the outline
+#[derive(Debug, PartialEq)]
+pub(crate) struct Item {
+    pub(crate) line: usize,
+    /// Last line of the body, `line` for one-line declarations
+    pub(crate) end: usize,
+    pub(crate) depth: usize,
+    pub(crate) signature: String,
+}
+
+/// Languages whose declarations are recognized
+pub(crate) fn is_supported(lang: Language) -> bool {
+    matches!(
+        lang,
+        Language::Rust
+            | Language::TypeScript
+            | Language::JavaScript
+            | Language::Python
+            | Language::Go
+    )
+}
+
+pub fn run(file: &Path, verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    if verbose > 0 {
+        eprintln!("Outlining: {}", file.display());
+    }
+
+    let content = fs::read_to_string(file)
+        .with_context(|| format!("Failed to read file: {}", file.display()))?;
+    let lang = file
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(Language::from_extension)
+        .unwrap_or(Language::Unknown);
+    if !is_supported(lang) {
+        anyhow::bail!(
+            "No outline for {} (supported: Rust, TypeScript/JavaScript, Python, Go)",
+            file.display()
+        );
+    }
+
+    let display = file.display().to_string();
+    let key = cache::key("outline", &display, content.as_bytes());
+    let output = cache::get_or_render(&key, || {
+        let items = outline(&content, lang);
+        Ok(format_outline(&display, content.lines().count(), &items))
+    })?;
+    println!("{}", output);
+
+    timer.track(
+        &format!("cat {}", file.display()),
+        "rtk outline",
+        &content,
+        &output,
+    );
+    Ok(())
+}
+
+pub(crate) fn outline(content: &str, lang: Language) -> Vec<Item> {
+    let (grammar, tree) = match lang {
+        Language::Rust => (&*RUST, RUST.parse(content)),
+        Language::JavaScript => (&*JAVASCRIPT, JAVASCRIPT.parse(content)),
+        Language::Python => (&*PYTHON, PYTHON.parse(content)),
+        Language::Go => (&*GO, GO.parse(content)),
+        Language::TypeScript => {
+            // `.ts` and `.tsx` share a Language: JSX only parses as TSX
+            match TYPESCRIPT.parse(content) {
+                Some(tree) if !tree.root_node().has_error() => (&*TYPESCRIPT, Some(tree)),
+                _ => (&*TSX, TSX.parse(content)),
+            }
+        }
+        _ => return Vec::new(),
+    };
+    let Some(tree) = tree else {
+        return Vec::new();
+    };
+
+    let item_index = grammar.query.capture_index_for_name("item");
+    let function_index = grammar.query.capture_index_for_name("function");
+
+    // (declaration, where its signature stops)
+    let mut found: Vec<(Node, usize)> = Vec::new();
+    let mut cursor = QueryCursor::new();
+    let mut matches = cursor.matches(&grammar.query, tree.root_node(), content.as_bytes());
+    while let Some(m) = matches.next() {
+        let capture = |index| {
+            m.captures
+                .iter()
+                .find(|c| Some(c.index) == index)
+                .map(|c| c.node)
+        };
+        let Some(node) = capture(item_index) else {
+            continue;
+        };
+        // `const f = () => {}` stops at the function's body
+        let body_owner = capture(function_index).unwrap_or(node);
+        found.push((node, signature_end(node, body_owner)));
+    }
+    found.sort_by_key(|(node, _)| node.start_byte());
+    found.dedup_by_key(|(node, _)| node.id());
+
+    let mut items = Vec::new();
+    // End bytes of the enclosing declarations
+    let mut open: Vec<usize> = Vec::new();
+    for (node, sig_end) in found {
+        // `export class A` is listed with its `export`
+        let decl = match node.parent() {
+            Some(parent) if parent.kind() == "export_statement" => parent,
+            _ => node,
+        };
+        while open.last().is_some_and(|&end| end <= decl.start_byte()) {
+            open.pop();
+        }
+        items.push(Item {
+            line: decl.start_position().row + 1,
+            end: decl.end_position().row + 1,
+            depth: open.len(),
+            signature: signature(
+                &content[decl.start_byte()..sig_end],
+                lang,
+                sig_end == node.end_byte(),
+            ),
+        });
+        open.push(decl.end_byte());
+    }
+    items
+}
+
+/// Byte where the declaration's signature ends: its body, else the macro
+/// name, else the whole declaration
+fn signature_end(node: Node, body_owner: Node) -> usize {
+    if let Some(body) = body_owner.child_by_field_name("body") {
+        return body.start_byte();
+    }
+    if node.kind() == "macro_definition" {
+        if let Some(name) = node.child_by_field_name("name") {
+            return name.end_byte();
+        }
+    }
+    node.end_byte()
+}
+
+/// A declaration's text up to its body, on one line, without its opener.
+/// `bodyless` declarations (`struct A { .. }`, `type T struct { .. }`) are
+/// cut at their first brace instead.
+fn signature(text: &str, lang: Language, bodyless: bool) -> String {
+    let mut sig = text
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace("( ", "(")
+        .replace(" )", ")")
+        .replace(",)", ")")
+        .replace("< ", "<")
+        .replace(" >", ">")
+        .replace(",>", ">");
+    if bodyless {
+        if let Some(body) = sig.find(" {").or_else(|| sig.find("{ ")) {
+            if !sig[..body].ends_with('=') {
+                sig.truncate(body);
+            }
+        }
+    }
+    let sig = sig.trim_end_matches('{').trim_end_matches(';').trim_end();
+    let sig = if lang == Language::Python {
+        sig.trim_end_matches(':')
+    } else {
+        sig
+    };
+    crate::utils::truncate(sig, MAX_SIGNATURE_CHARS)
+}
+
+fn format_outline(path: &str, total_lines: usize, items: &[Item]) -> String {
+    if items.is_empty() {
+        return format!("📑 {} ({} lines): no declarations found", path, total_lines);
+    }
+    let mut out = format!(
+        "📑 {} ({} lines, {} items):\n",
+        path,
+        total_lines,
+        items.len()
+    );
+    for item in items {
+        out.push_str(&format!(
+            "{:>5}  {}{}\n",
+            item.line,
+            "  ".repeat(item.depth),
+            item.signature
+        ));
+    }
+    out.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signatures(content: &str, lang: Language) -> Vec<(usize, usize, String)> {
+        outline(content, lang)
+            .into_iter()
+            .map(|i| (i.line, i.depth, i.signature))
+            .collect()
+    }
+
+    #[test]
+    fn test_outline_rust() {
+        let content = r#"use std::fs;
+
+/// Doc
+pub struct Config {
+    name: String,
+}
+
+impl Config {
+    pub fn new(
+        name: &str,
+        verbose: bool,
+    ) -> Self {
+        let x = fn_call();
+        Self { name: name.into() }
+    }
+
+    pub(crate) async fn load(&self) -> Result<()> {}
+}
+
+macro_rules! hello {
+    () => {};
+}
+"#;
+        assert_eq!(
+            signatures(content, Language::Rust),
+            vec![
+                (4, 0, "pub struct Config".to_string()),
+                (8, 0, "impl Config".to_string()),
+                (
+                    9,
+                    1,
+                    "pub fn new(name: &str, verbose: bool) -> Self".to_string()
+                ),
+                (
+                    17,
+                    1,
+                    "pub(crate) async fn load(&self) -> Result<()>".to_string()
+                ),
+                (20, 0, "macro_rules! hello".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_outline_typescript() {
+        let content = "export class UserService {
+  private cache = new Map();
+
+  constructor(private db: Db) {}
+
+  async load(id: string): Promise<User> {
+    if (cached) {
+      return cached;
+    }
+    await this.db.get(id);
+  }
+}
+
+export const handler = async (req: Request) => {
+  return ok();
+};
+
+export interface User {
+  id: string;
+}
+
+export type Id = string;
+";
+        assert_eq!(
+            signatures(content, Language::TypeScript),
+            vec![
+                (1, 0, "export class UserService".to_string()),
+                (4, 1, "constructor(private db: Db)".to_string()),
+                (6, 1, "async load(id: string): Promise<User>".to_string()),
+                (
+                    14,
+                    0,
+                    "export const handler = async (req: Request) =>".to_string()
+                ),
+                (18, 0, "export interface User".to_string()),
+                (22, 0, "export type Id = string".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_outline_python_and_go() {
+        let python = "class Repo(Base):\n    def get(\n        self, id\n    ) -> User:\n        pass\n\nasync def main():\n    pass\n";
+        assert_eq!(
+            signatures(python, Language::Python),
+            vec![
+                (1, 0, "class Repo(Base)".to_string()),
+                (2, 1, "def get(self, id) -> User".to_string()),
+                (7, 0, "async def main()".to_string()),
+            ]
+        );
+
+        let go = "type Server struct {\n\taddr string\n}\n\nfunc (s *Server) Run(ctx context.Context) error {\n\treturn nil\n}\n";
+        assert_eq!(
+            signatures(go, Language::Go),
+            vec![
+                (1, 0, "type Server struct".to_string()),
+                (
+                    5,
+                    0,
+                    "func (s *Server) Run(ctx context.Context) error".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_outline_nested_and_multiline() {
+        let content = r#"/// fn not_an_item() {}
+const DOC: &str = "fn in_a_string() {}";
+const RAW: &str = r"
+impl Fake {}
+";
+
+pub fn parse<
+    T: Default,
+    U,
+>(input: &str) -> T {
+    fn helper() {}
+    struct Local;
+    T::default()
+}
+
+mod outer {
+    mod inner {
+        pub trait Walk {}
+    }
+}
+"#;
+        assert_eq!(
+            signatures(content, Language::Rust),
+            vec![
+                (
+                    7,
+                    0,
+                    "pub fn parse<T: Default, U>(input: &str) -> T".to_string()
+                ),
+                (11, 1, "fn helper()".to_string()),
+                (12, 1, "struct Local".to_string()),
+                (16, 0, "mod outer".to_string()),
+                (17, 1, "mod inner".to_string()),
+                (18, 2, "pub trait Walk".to_string()),
+            ]
+        );
+
+        let ts = "// function commented() {}\nconst s = `class InString {}`;\nexport function render({ a, b }: Props) {\n  return <div>{a}</div>;\n}\n";
+        assert_eq!(
+            signatures(ts, Language::TypeScript),
+            vec![(3, 0, "export function render({ a, b }: Props)".to_string())]
+        );
+
+        let python = "def f():\n    \"\"\"\n    def in_docstring():\n    \"\"\"\n    def inner():\n        pass\n";
+        assert_eq!(
+            signatures(python, Language::Python),
+            vec![
+                (1, 0, "def f()".to_string()),
+                (5, 1, "def inner()".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_item_end() {
+        let rust = "impl Config {\n    pub fn new(\n        name: &str,\n    ) -> Self {\n        Self {}\n    }\n\n    fn unit();\n}\nstruct Unit;\n";
+        let ends: Vec<(usize, usize)> = outline(rust, Language::Rust)
+            .iter()
+            .map(|item| (item.line, item.end))
+            .collect();
+        assert_eq!(ends, vec![(1, 9), (2, 6), (8, 8), (10, 10)]);
+
+        let python = "class Repo:\n    def get(\n        self\n    ):\n        pass\n\nx = 1\n";
+        let ends: Vec<(usize, usize)> = outline(python, Language::Python)
+            .iter()
+            .map(|item| (item.line, item.end))
+            .collect();
+        assert_eq!(ends, vec![(1, 5), (2, 5)]);
+
+        // Ranges follow the syntax, not the indentation of string contents
+        let rust = "fn a() {\n    let s = \"\nx\n\";\n    done();\n}\nfn b() {}\n";
+        let ends: Vec<(usize, usize)> = outline(rust, Language::Rust)
+            .iter()
+            .map(|item| (item.line, item.end))
+            .collect();
+        assert_eq!(ends, vec![(1, 6), (7, 7)]);
+    }
+
+    #[test]
+    fn test_format_outline() {
+        let items = vec![
+            Item {
+                line: 8,
+                end: 200,
+                depth: 0,
+                signature: "impl Config".into(),
+            },
+            Item {
+                line: 120,
+                end: 140,
+                depth: 1,
+                signature: "pub fn new() -> Self".into(),
+            },
+        ];
+        assert_eq!(
+            format_outline("src/config.rs", 300, &items),
+            "📑 src/config.rs (300 lines, 2 items):\n    8  impl Config\n  120    pub fn new() -> Self"
+        );
+        assert!(format_outline("a.rs", 3, &[]).ends_with("no declarations found"));
+    }
+}
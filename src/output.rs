@@ -0,0 +1,143 @@
+//! Terminal handling shared by all commands: escape codes are stripped from
+//! captured child output before any filter parses it, and rtk's own output
+//! is colored only when `--color` allows it.
+
+use crate::utils::strip_ansi;
+use colored::Colorize;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::io::IsTerminal;
+use std::process::Output;
+use std::sync::OnceLock;
+
+/// Global `--format` values
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, clap::ValueEnum, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// Compact text for humans and agents
+    #[default]
+    Text,
+    /// Machine-readable output (grep, json, lint, ...)
+    Json,
+    /// SARIF 2.1.0 log of grep matches or lint findings
+    Sarif,
+}
+
+impl OutputFormat {
+    /// Value parser of the global `--format`: clap checks and lists the
+    /// values, the field keeps the name as a string
+    pub fn parser() -> impl clap::builder::TypedValueParser<Value = String> {
+        use clap::builder::TypedValueParser;
+        clap::builder::EnumValueParser::<Self>::new().map(|format| format.as_str().to_string())
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            OutputFormat::Text => "text",
+            OutputFormat::Json => "json",
+            OutputFormat::Sarif => "sarif",
+        }
+    }
+
+    /// A document on stdout that hints must not break
+    pub fn machine_readable(self) -> bool {
+        self != OutputFormat::Text
+    }
+}
+
+/// `--color` values
+#[derive(Debug, Clone, Copy, Default, PartialEq, clap::ValueEnum)]
+pub enum ColorChoice {
+    /// Color when stdout is a terminal and `NO_COLOR` is unset
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+static COLOR: OnceLock<bool> = OnceLock::new();
+
+lazy_static! {
+    /// Key of a schema line: `  name: string`, `  tags?:`
+    static ref SCHEMA_KEY_RE: Regex = Regex::new(r"^(\s*)([^\s:\[\]{}.][^:]*?\??):").unwrap();
+}
+
+/// Resolve `--color` once, at startup. Also drives the `colored` crate, so
+/// every `.green()` in the codebase follows the same choice.
+pub fn set_color(choice: ColorChoice) {
+    let enabled = !crate::stable::enabled()
+        && match choice {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => auto_color(),
+        };
+    let _ = COLOR.set(enabled);
+    colored::control::set_override(color_enabled());
+}
+
+/// Whether rtk's own output should carry colors
+pub fn color_enabled() -> bool {
+    *COLOR.get_or_init(auto_color)
+}
+
+fn auto_color() -> bool {
+    std::io::stdout().is_terminal()
+        && std::env::var_os("NO_COLOR").is_none()
+        && !crate::stable::enabled()
+}
+
+/// Stdout and stderr of a child process as text, without escape codes
+/// and with Windows line endings turned into `\n`
+pub fn captured(output: &Output) -> (String, String) {
+    (
+        normalize(&String::from_utf8_lossy(&output.stdout)),
+        normalize(&String::from_utf8_lossy(&output.stderr)),
+    )
+}
+
+/// Captured text as filters expect it: no escape codes, `\n` line endings.
+/// `str::lines` already drops a `\r` before `\n`, but filters that split,
+/// slice or compare lines themselves would keep it.
+pub fn normalize(text: &str) -> String {
+    strip_ansi(text).replace("\r\n", "\n")
+}
+
+/// Color the keys of an `rtk json` schema
+pub fn color_schema_keys(text: &str) -> String {
+    text.lines()
+        .map(|line| {
+            SCHEMA_KEY_RE
+                .replace(line, |caps: &regex::Captures| {
+                    format!("{}{}:", &caps[1], caps[2].cyan())
+                })
+                .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_color_schema_keys() {
+        colored::control::set_override(true);
+        let out = color_schema_keys("{\n  id: int,\n  tags?:\n  ... +3 more keys\n}");
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines[1], format!("  {}: int,", "id".cyan()));
+        assert_eq!(lines[2], format!("  {}:", "tags?".cyan()));
+        assert_eq!(lines[3], "  ... +3 more keys");
+        assert_eq!(lines[4], "}");
+    }
+
+    #[test]
+    fn test_normalize_crlf() {
+        assert_eq!(
+            normalize("\x1b[31merror\x1b[0m: a\r\nb\r\n"),
+            "error: a\nb\n"
+        );
+    }
+}
@@ -0,0 +1,205 @@
+//! Path display: absolute paths inside the current repository are shown
+//! relative to its root, the home directory becomes `~`, and paths longer
+//! than their budget lose components from the middle. The filename and its
+//! parent directory always survive, so a shortened path still says which
+//! file it is.
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// `--path-style` values
+#[derive(Debug, Clone, Copy, Default, PartialEq, clap::ValueEnum)]
+pub enum PathStyle {
+    /// Repo-relative or `~`-prefixed, elided in the middle when too long
+    #[default]
+    Repo,
+    /// Paths exactly as the wrapped tool printed them
+    Full,
+    /// Parent directory and filename only
+    Short,
+}
+
+/// Path separators as printed by tools: `/`, and `\` on Windows
+const SEPARATORS: [char; 2] = ['/', '\\'];
+
+static STYLE: OnceLock<PathStyle> = OnceLock::new();
+static REPO_ROOT: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+/// Resolve `--path-style` once, at startup
+pub fn set_style(style: PathStyle) {
+    let _ = STYLE.set(style);
+}
+
+pub fn style() -> PathStyle {
+    *STYLE.get_or_init(PathStyle::default)
+}
+
+/// Nearest directory holding a `.git` entry, from the current directory up
+pub fn repo_root() -> Option<&'static Path> {
+    REPO_ROOT
+        .get_or_init(|| {
+            let cwd = std::env::current_dir().ok()?;
+            find_repo_root(&cwd)
+        })
+        .as_deref()
+}
+
+fn find_repo_root(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .map(Path::to_path_buf)
+}
+
+/// `path` as shown by rtk under the current `--path-style`, at most
+/// `max_len` characters unless the filename and parent alone are longer
+pub fn compact(path: &str, max_len: usize) -> String {
+    let home = dirs::home_dir();
+    compact_with(path, max_len, style(), repo_root(), home.as_deref())
+}
+
+fn compact_with(
+    path: &str,
+    max_len: usize,
+    style: PathStyle,
+    root: Option<&Path>,
+    home: Option<&Path>,
+) -> String {
+    match style {
+        PathStyle::Full => path.to_string(),
+        PathStyle::Short => {
+            let parts: Vec<&str> = path.split(SEPARATORS).filter(|p| !p.is_empty()).collect();
+            match parts.len() {
+                0 => path.to_string(),
+                1 => parts[0].to_string(),
+                n => format!("{}{}{}", parts[n - 2], separator(path), parts[n - 1]),
+            }
+        }
+        PathStyle::Repo => elide_middle(&relativize(path, root, home), max_len),
+    }
+}
+
+/// Absolute paths under the repo root made root-relative, those under the
+/// home directory prefixed with `~`; relative paths are already relative to
+/// where the user is and stay as they are
+fn relativize(path: &str, root: Option<&Path>, home: Option<&Path>) -> String {
+    let as_path = Path::new(path);
+    if !as_path.is_absolute() {
+        return path
+            .strip_prefix("./")
+            .or_else(|| path.strip_prefix(".\\"))
+            .unwrap_or(path)
+            .to_string();
+    }
+    if let Some(rest) = root.and_then(|r| as_path.strip_prefix(r).ok()) {
+        if !rest.as_os_str().is_empty() {
+            return rest.display().to_string();
+        }
+    }
+    if let Some(rest) = home.and_then(|h| as_path.strip_prefix(h).ok()) {
+        return format!("~{}{}", std::path::MAIN_SEPARATOR, rest.display());
+    }
+    path.to_string()
+}
+
+/// Leading components dropped for `...` until `path` fits `max_len`; the
+/// last two components are never dropped
+fn elide_middle(path: &str, max_len: usize) -> String {
+    if path.chars().count() <= max_len {
+        return path.to_string();
+    }
+    let sep = separator(path);
+    let parts: Vec<&str> = path.split(SEPARATORS).collect();
+    if parts.len() <= 3 {
+        return path.to_string();
+    }
+    let tail = parts[parts.len() - 2..].join(&sep.to_string());
+    let mut head = String::new();
+    for part in &parts[..parts.len() - 2] {
+        let candidate = format!("{}{}{}", head, part, sep);
+        // room for ".../" ahead of the tail
+        if candidate.chars().count() + 4 + tail.chars().count() > max_len {
+            break;
+        }
+        head = candidate;
+    }
+    format!("{}...{}{}", head, sep, tail)
+}
+
+/// The separator `path` is written with, to rejoin its components
+fn separator(path: &str) -> char {
+    if path.contains('\\') && !path.contains('/') {
+        '\\'
+    } else {
+        '/'
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo(path: &str, max_len: usize) -> String {
+        compact_with(
+            path,
+            max_len,
+            PathStyle::Repo,
+            Some(Path::new("/home/dev/project")),
+            Some(Path::new("/home/dev")),
+        )
+    }
+
+    #[test]
+    fn test_repo_relative_and_home() {
+        assert_eq!(repo("/home/dev/project/src/main.rs", 80), "src/main.rs");
+        assert_eq!(repo("/home/dev/other/lib.rs", 80), "~/other/lib.rs");
+        assert_eq!(repo("/etc/hosts", 80), "/etc/hosts");
+        assert_eq!(repo("./src/lib.rs", 80), "src/lib.rs");
+        // The root itself is not turned into an empty path
+        assert_eq!(repo("/home/dev/project", 80), "~/project");
+    }
+
+    #[test]
+    fn test_elide_middle_keeps_parent_and_file() {
+        let path = "/home/dev/project/src/components/forms/inputs/Button.tsx";
+        assert_eq!(repo(path, 80), "src/components/forms/inputs/Button.tsx");
+        assert_eq!(repo(path, 30), "src/.../inputs/Button.tsx");
+        assert_eq!(repo(path, 10), ".../inputs/Button.tsx");
+        let compact = elide_middle("a/b/c/d/e/f.rs", 10);
+        assert!(compact.ends_with("e/f.rs"));
+        assert!(compact.chars().count() <= 10);
+    }
+
+    #[test]
+    fn test_full_and_short_styles() {
+        let path = "/home/dev/project/src/cmd/main.rs";
+        let full = compact_with(path, 10, PathStyle::Full, None, None);
+        assert_eq!(full, path);
+        let short = compact_with(path, 80, PathStyle::Short, None, None);
+        assert_eq!(short, "cmd/main.rs");
+        assert_eq!(
+            compact_with("main.rs", 80, PathStyle::Short, None, None),
+            "main.rs"
+        );
+    }
+
+    #[test]
+    fn test_windows_separators() {
+        let path = r"src\components\forms\inputs\Button.tsx";
+        assert_eq!(elide_middle(path, 30), r"src\...\inputs\Button.tsx");
+        assert_eq!(
+            compact_with(path, 80, PathStyle::Short, None, None),
+            r"inputs\Button.tsx"
+        );
+        assert_eq!(repo(r".\src\lib.rs", 80), r"src\lib.rs");
+    }
+
+    #[test]
+    fn test_find_repo_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("a/b");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::create_dir(dir.path().join(".git")).unwrap();
+        assert_eq!(find_repo_root(&nested).as_deref(), Some(dir.path()));
+    }
+}
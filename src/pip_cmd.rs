@@ -1,4 +1,5 @@
 use crate::tracking;
+use crate::utils;
 use anyhow::{Context, Result};
 use serde::Deserialize;
 use std::process::Command;
@@ -155,14 +156,7 @@ fn run_passthrough(base_cmd: &str, args: &[String], verbose: u8) -> Result<(Stri
 
 /// Check if a command exists in PATH
 fn which_command(cmd: &str) -> Option<String> {
-    Command::new("which")
-        .arg(cmd)
-        .output()
-        .ok()
-        .filter(|o| o.status.success())
-        .and_then(|o| String::from_utf8(o.stdout).ok())
-        .map(|s| s.trim().to_string())
-        .filter(|s| !s.is_empty())
+    utils::find_in_path(cmd).map(|path| path.display().to_string())
 }
 
 /// Filter pip list JSON output
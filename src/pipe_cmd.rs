@@ -0,0 +1,244 @@
+//! Generic compaction for output no dedicated filter knows: ANSI codes and
+//! progress-bar redraws are dropped, repeated lines are counted, and long
+//! output keeps its head and tail with the middle elided. Stack traces are
+//! compacted by [`trace_cmd`](crate::trace_cmd) first.
+
+use crate::trace_cmd;
+use crate::tracking;
+use crate::utils::{strip_ansi, truncate};
+use anyhow::{Context, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::io::Read;
+use std::process::{Command, Stdio};
+
+/// Longer lines are cut (minified bundles, base64 blobs)
+const MAX_LINE_CHARS: usize = 300;
+
+/// Error lines quoted from the elided middle
+const MAX_ELIDED_ERRORS: usize = 5;
+
+lazy_static! {
+    /// Progress bars and percentages: `[=====>   ] 45%`, `45% |███`, `fetch 12.5%`
+    static ref PROGRESS_RE: Regex =
+        Regex::new(r"^\s*(?:\S.{0,60}?)?(?:[\[|][#=>\-.█▉▊▋▌▍▎▏░▒▓ ]{4,}[\]|]|\d{1,3}(?:\.\d+)?%)").unwrap();
+    static ref ERROR_RE: Regex =
+        Regex::new(r"(?i)\b(?:error|fatal|failed|failure|panic(?:ked)?|exception)\b").unwrap();
+}
+
+/// Compact the output of `command`, or of stdin when no command is given.
+/// A single argument is a shell command line (`rtk pipe "make | tee log"`);
+/// several are the program and its arguments. Keeps the command's exit code.
+pub fn run(command: &[String], max_lines: usize, verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    let (raw, exit_code, label) = if command.is_empty() {
+        let mut buf = Vec::new();
+        std::io::stdin()
+            .read_to_end(&mut buf)
+            .context("Failed to read stdin")?;
+        (String::from_utf8_lossy(&buf).to_string(), 0, None)
+    } else {
+        let mut cmd = if let [line] = command {
+            shell(line)
+        } else {
+            let mut cmd = Command::new(&command[0]);
+            cmd.args(&command[1..]);
+            cmd
+        };
+        let command = command.join(" ");
+        if verbose > 0 {
+            eprintln!("Running: {}", command);
+        }
+        let output = cmd
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .output()
+            .with_context(|| format!("Failed to run {}", command))?;
+        let raw = format!(
+            "{}{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        (raw, output.status.code().unwrap_or(1), Some(command))
+    };
+
+    let mut compacted = compact(&raw, max_lines);
+    if let Some(hint) = crate::tee::tee_and_hint(&raw, "pipe", exit_code) {
+        compacted.push('\n');
+        compacted.push_str(&hint);
+    }
+    println!("{}", compacted);
+
+    match &label {
+        Some(command) => timer.track(command, "rtk pipe", &raw, &compacted),
+        None => timer.track("(stdin)", "rtk pipe", &raw, &compacted),
+    }
+
+    if exit_code != 0 {
+        std::process::exit(exit_code);
+    }
+    Ok(())
+}
+
+fn shell(command: &str) -> Command {
+    if cfg!(target_os = "windows") {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    }
+}
+
+/// Apply every heuristic, bounding the result to about `max_lines` lines
+pub fn compact(raw: &str, max_lines: usize) -> String {
+    let mut clean = strip_ansi(raw);
+    if trace_cmd::has_trace(&clean) {
+        clean = trace_cmd::compact_traces(&clean);
+    }
+
+    // Carriage returns redraw the line: only the final state matters
+    let lines: Vec<&str> = clean
+        .lines()
+        .map(|line| line.rsplit('\r').find(|s| !s.is_empty()).unwrap_or(""))
+        .collect();
+
+    let lines = collapse_progress(&lines);
+    let lines = dedup(&lines);
+    elide_middle(&lines, max_lines).join("\n")
+}
+
+fn is_progress(line: &str) -> bool {
+    PROGRESS_RE.is_match(line) && !ERROR_RE.is_match(line)
+}
+
+/// Runs of progress lines shrink to their last line
+fn collapse_progress<'a>(lines: &[&'a str]) -> Vec<&'a str> {
+    let mut out = Vec::with_capacity(lines.len());
+    for (i, line) in lines.iter().enumerate() {
+        let next_is_progress = lines.get(i + 1).is_some_and(|next| is_progress(next));
+        if is_progress(line) && next_is_progress {
+            continue;
+        }
+        out.push(*line);
+    }
+    out
+}
+
+/// Consecutive identical lines become one with a count; blank runs become
+/// one blank line. Long lines are cut.
+fn dedup(lines: &[&str]) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i].trim_end();
+        let mut run = 1;
+        while i + run < lines.len() && lines[i + run].trim_end() == line {
+            run += 1;
+        }
+        i += run;
+
+        if line.is_empty() {
+            if out.last().is_some_and(|l| !l.is_empty()) {
+                out.push(String::new());
+            }
+            continue;
+        }
+        let line = truncate(line, MAX_LINE_CHARS);
+        if run > 1 {
+            out.push(format!("{} (×{})", line, run));
+        } else {
+            out.push(line);
+        }
+    }
+    while out.last().is_some_and(|l| l.is_empty()) {
+        out.pop();
+    }
+    out
+}
+
+/// Keep the head and tail; errors from the middle are still quoted
+fn elide_middle(lines: &[String], max_lines: usize) -> Vec<String> {
+    if lines.len() <= max_lines {
+        return lines.to_vec();
+    }
+    let head = max_lines / 2;
+    let tail = max_lines - head;
+    let middle = &lines[head..lines.len() - tail];
+
+    let errors: Vec<&String> = middle.iter().filter(|l| ERROR_RE.is_match(l)).collect();
+    let mut out = lines[..head].to_vec();
+    out.push(format!("... {} lines elided ...", middle.len()));
+    for line in errors.iter().take(MAX_ELIDED_ERRORS) {
+        out.push(format!("  {}", line));
+    }
+    if errors.len() > MAX_ELIDED_ERRORS {
+        out.push(format!(
+            "  ... +{} more error lines",
+            errors.len() - MAX_ELIDED_ERRORS
+        ));
+    }
+    out.extend_from_slice(&lines[lines.len() - tail..]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compact_progress_and_ansi() {
+        let raw = "\x1b[32mDownloading\x1b[0m\n\
+                   [====      ] 40%\n\
+                   [========  ] 80%\n\
+                   [==========] 100%\n\
+                   fetch 10%\rfetch 50%\rfetch done\n\
+                   Done";
+        assert_eq!(
+            compact(raw, 50),
+            "Downloading\n[==========] 100%\nfetch done\nDone"
+        );
+    }
+
+    #[test]
+    fn test_compact_dedup_counts() {
+        let raw = "warning: unused\nwarning: unused\nwarning: unused\n\n\n\nok\n\n";
+        assert_eq!(compact(raw, 50), "warning: unused (×3)\n\nok");
+    }
+
+    #[test]
+    fn test_compact_detects_traces() {
+        let raw = "npm start\n\
+                   Error: boom\n    at main (/app/index.js:3:9)\n\
+                   \x20   at Module._compile (node:internal/modules/cjs/loader:1256:14)\n\
+                   \x20   at node:internal/main/run_main_module:23:47";
+        assert_eq!(
+            compact(raw, 50),
+            "npm start\n💥 Error: boom\n    at main (/app/index.js:3:9)\n    ... 2 framework frames"
+        );
+    }
+
+    #[test]
+    fn test_elide_middle_keeps_errors() {
+        let mut raw: Vec<String> = (1..=100).map(|i| format!("line {}", i)).collect();
+        raw[50] = "error: disk full".to_string();
+        let out = compact(&raw.join("\n"), 10);
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines[0], "line 1");
+        assert_eq!(lines[5], "... 90 lines elided ...");
+        assert_eq!(lines[6], "  error: disk full");
+        assert_eq!(lines.last(), Some(&"line 100"));
+        assert_eq!(lines.len(), 12);
+    }
+
+    #[test]
+    fn test_progress_keeps_errors() {
+        assert!(is_progress("  45% |████      | 45/100"));
+        assert!(is_progress("Downloading foo 12.5%"));
+        assert!(!is_progress("error: 100% of tests failed"));
+        assert!(!is_progress("compiling serde v1.0"));
+    }
+}
@@ -0,0 +1,212 @@
+//! Plugins: `rtk <name> ...` for a name rtk has no filter for runs
+//! `<name> ...`, then pipes its output through an `rtk-<name>` executable
+//! and prints what that returns, tracked like any built-in filter.
+//!
+//! Plugins are looked up in `~/.config/rtk/plugins/` (or `RTK_PLUGIN_DIR`),
+//! then on `PATH`. A plugin gets the tool's arguments as its own, and
+//! `RTK_COMMAND` and `RTK_EXIT_CODE` in its environment. When it fails,
+//! the raw output is printed instead. WASM modules are not supported.
+
+use crate::{tracking, utils};
+use anyhow::{Context, Result};
+use std::ffi::{OsStr, OsString};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Directories searched before `PATH`
+fn plugin_dirs() -> Vec<PathBuf> {
+    if let Some(dir) = std::env::var_os("RTK_PLUGIN_DIR") {
+        return vec![PathBuf::from(dir)];
+    }
+    dirs::config_dir()
+        .map(|d| vec![d.join("rtk").join("plugins")])
+        .unwrap_or_default()
+}
+
+/// The `rtk-<name>` executable in the first of `dirs` that has one; on
+/// Windows also `rtk-<name>.cmd`, `.bat` and the rest of `PATHEXT`
+fn find_in(dirs: impl IntoIterator<Item = PathBuf>, name: &str) -> Option<PathBuf> {
+    utils::find_in_dirs(&format!("rtk-{}", name), dirs)
+}
+
+/// The plugin for `name`, if one is installed
+pub fn find(name: &str) -> Option<PathBuf> {
+    if name.is_empty() || name.contains(['/', '\\']) {
+        return None;
+    }
+    let path_dirs = std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).collect::<Vec<_>>())
+        .unwrap_or_default();
+    find_in(plugin_dirs().into_iter().chain(path_dirs), name)
+}
+
+/// The subcommand of an rtk command line (its first non-flag argument)
+/// and where it is
+fn subcommand(args: &[OsString]) -> Option<(usize, String)> {
+    args.iter()
+        .map(|arg| arg.to_string_lossy())
+        .enumerate()
+        .find(|(_, arg)| !arg.starts_with('-'))
+        .map(|(i, arg)| (i, arg.to_string()))
+}
+
+/// Run the plugin named by an rtk command line rtk itself does not know
+/// (`args` without the program name). `None` when no plugin matches.
+pub fn dispatch(args: &[OsString]) -> Option<Result<()>> {
+    let (index, name) = subcommand(args)?;
+    let plugin = find(&name)?;
+
+    let flags = &args[..index];
+    let verbose = flags
+        .iter()
+        .map(|flag| match flag.to_str() {
+            Some("-v" | "--verbose") => 1,
+            Some("-vv") => 2,
+            Some("-vvv") => 3,
+            _ => 0,
+        })
+        .sum();
+    if flags.iter().any(|flag| flag == "--no-track") {
+        std::env::set_var("RTK_NO_TRACK", "1");
+    }
+    Some(run(&plugin, &name, &args[index + 1..], verbose))
+}
+
+/// Feed `raw` to `plugin` and return what it prints
+fn filter(
+    plugin: &Path,
+    name: &str,
+    args: &[OsString],
+    raw: &str,
+    exit_code: i32,
+) -> Result<String> {
+    let mut child = Command::new(plugin)
+        .args(args)
+        .env("RTK_COMMAND", name)
+        .env("RTK_EXIT_CODE", exit_code.to_string())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .with_context(|| format!("Failed to run {}", plugin.display()))?;
+
+    // Written from a thread: a plugin printing as it reads would otherwise
+    // block on a full stdout pipe while we block on its stdin
+    let mut stdin = child.stdin.take().context("No stdin for the plugin")?;
+    let input = raw.to_string();
+    let writer = std::thread::spawn(move || {
+        // A plugin may stop reading early; that is not an error
+        let _ = stdin.write_all(input.as_bytes());
+    });
+    let output = child
+        .wait_with_output()
+        .with_context(|| format!("Failed to run {}", plugin.display()))?;
+    let _ = writer.join();
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "{} exited with {}",
+            plugin.display(),
+            output.status.code().unwrap_or(1)
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .trim_end()
+        .to_string())
+}
+
+/// Run `name` with `args`, compact its output with `plugin`, and keep the
+/// tool's exit code
+fn run(plugin: &Path, name: &str, args: &[OsString], verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+    let command = std::iter::once(OsStr::new(name))
+        .chain(args.iter().map(OsString::as_os_str))
+        .map(|arg| arg.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join(" ");
+    if verbose > 0 {
+        eprintln!("Running: {} (plugin {})", command, plugin.display());
+    }
+
+    let output = Command::new(name)
+        .args(args)
+        .output()
+        .with_context(|| format!("Failed to run {}", name))?;
+    let raw = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let exit_code = output.status.code().unwrap_or(1);
+
+    let filtered = match filter(plugin, name, args, &raw, exit_code) {
+        Ok(filtered) => filtered,
+        Err(e) => {
+            eprintln!("rtk: plugin failed, showing raw output: {:#}", e);
+            raw.trim_end().to_string()
+        }
+    };
+    println!("{}", filtered);
+
+    timer.track(&command, &format!("rtk {}", command), &raw, &filtered);
+
+    if exit_code != 0 {
+        std::process::exit(exit_code);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subcommand_skips_flags() {
+        let args: Vec<OsString> = ["-v", "--no-track", "terraform", "plan"]
+            .iter()
+            .map(OsString::from)
+            .collect();
+        assert_eq!(subcommand(&args), Some((2, "terraform".to_string())));
+        assert_eq!(subcommand(&[OsString::from("-v")]), None);
+    }
+
+    #[cfg(unix)]
+    fn script(dir: &Path, name: &str, body: &str) -> PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+        let path = dir.join(name);
+        std::fs::write(&path, format!("#!/bin/sh\n{}\n", body)).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_find_in_first_dir_with_executable() {
+        let first = tempfile::tempdir().unwrap();
+        let second = tempfile::tempdir().unwrap();
+        // Not executable: skipped
+        std::fs::write(first.path().join("rtk-tf"), "").unwrap();
+        let plugin = script(second.path(), "rtk-tf", "cat");
+        let dirs = [first.path().to_path_buf(), second.path().to_path_buf()];
+        assert_eq!(find_in(dirs.clone(), "tf"), Some(plugin));
+        assert_eq!(find_in(dirs, "other"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_filter_pipes_output_through_plugin() {
+        let dir = tempfile::tempdir().unwrap();
+        let plugin = script(
+            dir.path(),
+            "rtk-tf",
+            r#"grep -v '^Refreshing'; echo "$RTK_COMMAND $1 exit=$RTK_EXIT_CODE""#,
+        );
+        let raw = "Refreshing a\nRefreshing b\nPlan: 1 to add\n";
+        let filtered = filter(&plugin, "terraform", &[OsString::from("plan")], raw, 2).unwrap();
+        assert_eq!(filtered, "Plan: 1 to add\nterraform plan exit=2");
+
+        let failing = script(dir.path(), "rtk-bad", "exit 3");
+        assert!(filter(&failing, "terraform", &[], raw, 0).is_err());
+    }
+}
@@ -1,9 +1,9 @@
 use crate::tracking;
+use crate::utils;
 use anyhow::{Context, Result};
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::ffi::OsString;
-use std::process::Command;
 
 use crate::parser::{
     emit_degradation_warning, emit_passthrough_warning, truncate_output, Dependency,
@@ -294,7 +294,7 @@ pub fn run(cmd: PnpmCommand, args: &[String], verbose: u8) -> Result<()> {
 fn run_list(depth: usize, args: &[String], verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
-    let mut cmd = Command::new("pnpm");
+    let mut cmd = utils::tool_command("pnpm");
     cmd.arg("list");
     cmd.arg(format!("--depth={}", depth));
     cmd.arg("--json");
@@ -350,7 +350,7 @@ fn run_list(depth: usize, args: &[String], verbose: u8) -> Result<()> {
 fn run_outdated(args: &[String], verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
-    let mut cmd = Command::new("pnpm");
+    let mut cmd = utils::tool_command("pnpm");
     cmd.arg("outdated");
     cmd.arg("--format");
     cmd.arg("json");
@@ -411,7 +411,7 @@ fn run_install(packages: &[String], args: &[String], verbose: u8) -> Result<()>
         }
     }
 
-    let mut cmd = Command::new("pnpm");
+    let mut cmd = utils::tool_command("pnpm");
     cmd.arg("install");
 
     for pkg in packages {
@@ -430,12 +430,8 @@ fn run_install(packages: &[String], args: &[String], verbose: u8) -> Result<()>
     let stdout = String::from_utf8_lossy(&output.stdout);
     let stderr = String::from_utf8_lossy(&output.stderr);
 
-    if !output.status.success() {
-        anyhow::bail!("pnpm install failed: {}", stderr);
-    }
-
     let combined = format!("{}{}", stdout, stderr);
-    let filtered = filter_pnpm_install(&combined);
+    let filtered = crate::npm_cmd::filter_install_output(&combined);
 
     println!("{}", filtered);
 
@@ -446,46 +442,11 @@ fn run_install(packages: &[String], args: &[String], verbose: u8) -> Result<()>
         &filtered,
     );
 
-    Ok(())
-}
-
-/// Filter pnpm install output - remove progress bars, keep summary
-fn filter_pnpm_install(output: &str) -> String {
-    let mut result = Vec::new();
-    let mut saw_progress = false;
-
-    for line in output.lines() {
-        // Skip progress bars
-        if line.contains("Progress") || line.contains('│') || line.contains('%') {
-            saw_progress = true;
-            continue;
-        }
-
-        if saw_progress && line.trim().is_empty() {
-            continue;
-        }
-
-        // Keep error lines
-        if line.contains("ERR") || line.contains("error") || line.contains("ERROR") {
-            result.push(line.to_string());
-            continue;
-        }
-
-        // Keep summary lines
-        if line.contains("packages in")
-            || line.contains("dependencies")
-            || line.starts_with('+')
-            || line.starts_with('-')
-        {
-            result.push(line.trim().to_string());
-        }
+    if !output.status.success() {
+        std::process::exit(output.status.code().unwrap_or(1));
     }
 
-    if result.is_empty() {
-        "ok ✓".to_string()
-    } else {
-        result.join("\n")
-    }
+    Ok(())
 }
 
 /// Runs an unsupported pnpm subcommand by passing it through directly
@@ -495,7 +456,7 @@ pub fn run_passthrough(args: &[OsString], verbose: u8) -> Result<()> {
     if verbose > 0 {
         eprintln!("pnpm passthrough: {:?}", args);
     }
-    let status = Command::new("pnpm")
+    let status = utils::tool_command("pnpm")
         .args(args)
         .status()
         .context("Failed to run pnpm")?;
@@ -0,0 +1,718 @@
+//! `rtk pr`: one bounded summary of a pull request (GitHub, through `gh`)
+//! or merge request (GitLab, through `glab`): description, CI status,
+//! reviews, review comments and changed files, for agent code reviews that
+//! would otherwise need a view, a checks, a diff and an API call.
+//!
+//! The provider comes from the PR URL when one is given, else from the
+//! `origin` remote (a host containing `gitlab` means GitLab); `--provider`
+//! overrides it.
+
+use crate::gh_cmd::filter_markdown_body;
+use crate::tracking;
+use crate::utils::truncate;
+use anyhow::{bail, Context, Result};
+use clap::ValueEnum;
+use serde_json::Value;
+use std::process::Command;
+
+/// Description lines kept
+const MAX_BODY_LINES: usize = 20;
+
+/// Changed files listed, largest change first
+const MAX_FILES: usize = 20;
+
+/// Review comments listed
+const MAX_COMMENTS: usize = 15;
+
+/// Width of one comment
+const COMMENT_WIDTH: usize = 160;
+
+/// Where the pull request lives
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum Provider {
+    Github,
+    Gitlab,
+}
+
+/// CI outcome of one check or job
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CheckStatus {
+    Pass,
+    Fail,
+    Pending,
+    Skipped,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Check {
+    name: String,
+    status: CheckStatus,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct ChangedFile {
+    path: String,
+    added: usize,
+    removed: usize,
+}
+
+/// A comment, on a line of a file when `path` is set
+#[derive(Debug, Clone, PartialEq)]
+struct Comment {
+    author: String,
+    path: Option<String>,
+    line: Option<u64>,
+    body: String,
+}
+
+/// A pull or merge request, whichever the provider
+#[derive(Debug, Default, PartialEq)]
+struct PullRequest {
+    number: u64,
+    title: String,
+    /// `open`, `merged`, `closed`
+    state: String,
+    draft: bool,
+    author: String,
+    url: String,
+    base: String,
+    head: String,
+    body: String,
+    /// Latest review per reviewer: (reviewer, `approved`, `changes
+    /// requested`, `commented`)
+    reviews: Vec<(String, String)>,
+    checks: Vec<Check>,
+    files: Vec<ChangedFile>,
+    comments: Vec<Comment>,
+}
+
+/// Summarize the pull request `selector` (number, URL or branch; the
+/// current branch's by default)
+pub fn run(selector: Option<&str>, provider: Option<Provider>, verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+    let provider = provider.unwrap_or_else(|| detect_provider(selector));
+    if verbose > 0 {
+        eprintln!("Pull request from {:?}", provider);
+    }
+
+    let (pr, raw, original) = match provider {
+        Provider::Github => fetch_github(selector, verbose)?,
+        Provider::Gitlab => fetch_gitlab(selector, verbose)?,
+    };
+    let output = format_pr(&pr);
+    println!("{}", output);
+    timer.track(&original, "rtk pr", &raw, &output);
+    Ok(())
+}
+
+fn detect_provider(selector: Option<&str>) -> Provider {
+    let remote = match selector {
+        Some(s) if s.contains("://") => s.to_string(),
+        _ => Command::new("git")
+            .args(["remote", "get-url", "origin"])
+            .output()
+            .map(|o| String::from_utf8_lossy(&o.stdout).into_owned())
+            .unwrap_or_default(),
+    };
+    if remote.contains("gitlab") {
+        Provider::Gitlab
+    } else {
+        Provider::Github
+    }
+}
+
+/// Stdout of `program args`, or its stderr as the error
+fn cli(program: &str, args: &[&str], verbose: u8) -> Result<String> {
+    if verbose > 0 {
+        eprintln!("Running: {} {}", program, args.join(" "));
+    }
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .with_context(|| format!("Failed to run {} (is it installed?)", program))?;
+    if !output.status.success() {
+        bail!(
+            "{} {} failed: {}",
+            program,
+            args.first().copied().unwrap_or_default(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn parse_json(text: &str, what: &str) -> Result<Value> {
+    serde_json::from_str(text).with_context(|| format!("Failed to parse {}", what))
+}
+
+/// `api --paginate` output: one JSON array per page, back to back, as one
+/// array
+fn parse_pages(text: &str, what: &str) -> Result<Value> {
+    let mut items = Vec::new();
+    for page in serde_json::Deserializer::from_str(text).into_iter::<Value>() {
+        match page.with_context(|| format!("Failed to parse {}", what))? {
+            Value::Array(page) => items.extend(page),
+            other => return Ok(other),
+        }
+    }
+    Ok(Value::Array(items))
+}
+
+/// Pull request, raw output, and the command it stands for
+fn fetch_github(selector: Option<&str>, verbose: u8) -> Result<(PullRequest, String, String)> {
+    let mut args = vec!["pr", "view"];
+    args.extend(selector);
+    args.extend([
+        "--json",
+        "number,title,state,isDraft,author,url,baseRefName,headRefName,body,\
+         reviews,comments,statusCheckRollup,files",
+    ]);
+    let view_raw = cli("gh", &args, verbose)?;
+    let view = parse_json(&view_raw, "gh pr view output")?;
+
+    // Inline review comments are only in the REST API, of the PR's own repo
+    let (host, repo) = github_repo(view["url"].as_str().unwrap_or_default())
+        .context("Unexpected pull request URL")?;
+    let endpoint = format!("repos/{}/pulls/{}/comments", repo, view["number"]);
+    let mut api = vec!["api", "--paginate", endpoint.as_str()];
+    if host != "github.com" {
+        api.extend(["--hostname", host.as_str()]);
+    }
+    let comments_raw = cli("gh", &api, verbose)?;
+    let comments = parse_pages(&comments_raw, "review comments")?;
+
+    let original = format!(
+        "gh pr view {} --comments && gh pr checks && gh pr diff",
+        selector.unwrap_or_default()
+    );
+    Ok((
+        from_github(&view, &comments),
+        format!("{}\n{}", view_raw, comments_raw),
+        original,
+    ))
+}
+
+/// (`github.com`, `owner/repo`) of `https://github.com/owner/repo/pull/12`
+fn github_repo(url: &str) -> Option<(String, String)> {
+    let rest = url.split_once("://")?.1;
+    let parts: Vec<&str> = rest.split('/').collect();
+    match parts[..] {
+        [host, owner, repo, "pull", ..] => Some((host.to_string(), format!("{}/{}", owner, repo))),
+        _ => None,
+    }
+}
+
+fn from_github(view: &Value, review_comments: &Value) -> PullRequest {
+    let text = |v: &Value| v.as_str().unwrap_or_default().to_string();
+    let state = match view["state"].as_str() {
+        Some("MERGED") => "merged",
+        Some("CLOSED") => "closed",
+        _ => "open",
+    };
+
+    // Latest review of each reviewer
+    let mut reviews: Vec<(String, String)> = Vec::new();
+    let mut comments = Vec::new();
+    for review in view["reviews"].as_array().into_iter().flatten() {
+        let author = text(&review["author"]["login"]);
+        let state = match review["state"].as_str() {
+            Some("APPROVED") => "approved",
+            Some("CHANGES_REQUESTED") => "changes requested",
+            Some("DISMISSED") => "dismissed",
+            _ => "commented",
+        };
+        if !review["body"]
+            .as_str()
+            .unwrap_or_default()
+            .trim()
+            .is_empty()
+        {
+            comments.push(Comment {
+                author: author.clone(),
+                path: None,
+                line: None,
+                body: text(&review["body"]),
+            });
+        }
+        match reviews.iter_mut().find(|(a, _)| *a == author) {
+            // A comment doesn't undo an approval or a change request
+            Some(entry) if state != "commented" => entry.1 = state.to_string(),
+            Some(_) => {}
+            None => reviews.push((author, state.to_string())),
+        }
+    }
+    for comment in view["comments"].as_array().into_iter().flatten() {
+        comments.push(Comment {
+            author: text(&comment["author"]["login"]),
+            path: None,
+            line: None,
+            body: text(&comment["body"]),
+        });
+    }
+    for comment in review_comments.as_array().into_iter().flatten() {
+        comments.push(Comment {
+            author: text(&comment["user"]["login"]),
+            path: Some(text(&comment["path"])),
+            line: comment["line"]
+                .as_u64()
+                .or_else(|| comment["original_line"].as_u64()),
+            body: text(&comment["body"]),
+        });
+    }
+
+    let checks = view["statusCheckRollup"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|check| {
+            // CheckRun: name, status, conclusion; StatusContext: context, state
+            let name = check["name"]
+                .as_str()
+                .or_else(|| check["context"].as_str())
+                .unwrap_or("?");
+            let outcome = check["conclusion"]
+                .as_str()
+                .filter(|c| !c.is_empty())
+                .or_else(|| check["state"].as_str())
+                .unwrap_or_default();
+            let status = match outcome {
+                "SUCCESS" | "NEUTRAL" => CheckStatus::Pass,
+                "SKIPPED" => CheckStatus::Skipped,
+                "FAILURE" | "ERROR" | "TIMED_OUT" | "CANCELLED" | "ACTION_REQUIRED"
+                | "STARTUP_FAILURE" => CheckStatus::Fail,
+                _ => CheckStatus::Pending,
+            };
+            Check {
+                name: name.to_string(),
+                status,
+            }
+        })
+        .collect();
+
+    let files = view["files"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|file| ChangedFile {
+            path: text(&file["path"]),
+            added: file["additions"].as_u64().unwrap_or(0) as usize,
+            removed: file["deletions"].as_u64().unwrap_or(0) as usize,
+        })
+        .collect();
+
+    PullRequest {
+        number: view["number"].as_u64().unwrap_or(0),
+        title: text(&view["title"]),
+        state: state.to_string(),
+        draft: view["isDraft"].as_bool().unwrap_or(false),
+        author: text(&view["author"]["login"]),
+        url: text(&view["url"]),
+        base: text(&view["baseRefName"]),
+        head: text(&view["headRefName"]),
+        body: text(&view["body"]),
+        reviews,
+        checks,
+        files,
+        comments,
+    }
+}
+
+fn fetch_gitlab(selector: Option<&str>, verbose: u8) -> Result<(PullRequest, String, String)> {
+    let mut args = vec!["mr", "view"];
+    args.extend(selector);
+    args.extend(["--output", "json"]);
+    let view_raw = cli("glab", &args, verbose)?;
+    let view = parse_json(&view_raw, "glab mr view output")?;
+
+    let mr = format!(
+        "projects/{}/merge_requests/{}",
+        view["project_id"], view["iid"]
+    );
+    let api = |endpoint: &str| -> Result<(Value, String)> {
+        let raw = cli("glab", &["api", "--paginate", endpoint], verbose)?;
+        Ok((parse_pages(&raw, endpoint)?, raw))
+    };
+    let (changes, changes_raw) = api(&format!("{}/changes", mr))?;
+    let (discussions, discussions_raw) = api(&format!("{}/discussions", mr))?;
+    let (approvals, _) = api(&format!("{}/approvals", mr))?;
+    let jobs = match view["head_pipeline"]["id"].as_u64() {
+        Some(id) => {
+            api(&format!(
+                "projects/{}/pipelines/{}/jobs",
+                view["project_id"], id
+            ))?
+            .0
+        }
+        None => Value::Null,
+    };
+
+    let original = format!(
+        "glab mr view {} --comments && glab ci status && glab mr diff",
+        selector.unwrap_or_default()
+    );
+    Ok((
+        from_gitlab(&view, &changes, &discussions, &approvals, &jobs),
+        format!("{}\n{}\n{}", view_raw, changes_raw, discussions_raw),
+        original,
+    ))
+}
+
+fn from_gitlab(
+    view: &Value,
+    changes: &Value,
+    discussions: &Value,
+    approvals: &Value,
+    jobs: &Value,
+) -> PullRequest {
+    let text = |v: &Value| v.as_str().unwrap_or_default().to_string();
+    let state = match view["state"].as_str() {
+        Some("merged") => "merged",
+        Some("closed") => "closed",
+        _ => "open",
+    };
+
+    let reviews = approvals["approved_by"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|a| (text(&a["user"]["username"]), "approved".to_string()))
+        .collect();
+
+    let status = |s: &str| match s {
+        "success" => CheckStatus::Pass,
+        "failed" | "canceled" => CheckStatus::Fail,
+        "skipped" | "manual" => CheckStatus::Skipped,
+        _ => CheckStatus::Pending,
+    };
+    let mut checks: Vec<Check> = jobs
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|job| Check {
+            name: text(&job["name"]),
+            status: status(job["status"].as_str().unwrap_or_default()),
+        })
+        .collect();
+    if checks.is_empty() {
+        if let Some(pipeline) = view["head_pipeline"]["status"].as_str() {
+            checks.push(Check {
+                name: "pipeline".to_string(),
+                status: status(pipeline),
+            });
+        }
+    }
+
+    let files = changes["changes"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|change| {
+            let diff = change["diff"].as_str().unwrap_or_default();
+            ChangedFile {
+                path: text(&change["new_path"]),
+                added: diff
+                    .lines()
+                    .filter(|l| l.starts_with('+') && !l.starts_with("+++"))
+                    .count(),
+                removed: diff
+                    .lines()
+                    .filter(|l| l.starts_with('-') && !l.starts_with("---"))
+                    .count(),
+            }
+        })
+        .collect();
+
+    let comments = discussions
+        .as_array()
+        .into_iter()
+        .flatten()
+        .flat_map(|d| d["notes"].as_array().into_iter().flatten())
+        .filter(|note| !note["system"].as_bool().unwrap_or(false))
+        .map(|note| Comment {
+            author: text(&note["author"]["username"]),
+            path: note["position"]["new_path"].as_str().map(str::to_string),
+            line: note["position"]["new_line"].as_u64(),
+            body: text(&note["body"]),
+        })
+        .collect();
+
+    PullRequest {
+        number: view["iid"].as_u64().unwrap_or(0),
+        title: text(&view["title"]),
+        state: state.to_string(),
+        draft: view["draft"].as_bool().unwrap_or(false),
+        author: text(&view["author"]["username"]),
+        url: text(&view["web_url"]),
+        base: text(&view["target_branch"]),
+        head: text(&view["source_branch"]),
+        body: text(&view["description"]),
+        reviews,
+        checks,
+        files,
+        comments,
+    }
+}
+
+/// `2/4 passed, 1 failed: lint, 1 pending: e2e`
+fn format_checks(checks: &[Check]) -> String {
+    let named = |status: CheckStatus| -> Vec<&str> {
+        checks
+            .iter()
+            .filter(|c| c.status == status)
+            .map(|c| c.name.as_str())
+            .collect()
+    };
+    let passed = named(CheckStatus::Pass).len();
+    let mut out = format!("{}/{} passed", passed, checks.len());
+    for (status, label) in [
+        (CheckStatus::Fail, "failed"),
+        (CheckStatus::Pending, "pending"),
+    ] {
+        let names = named(status);
+        if !names.is_empty() {
+            out.push_str(&format!(
+                ", {} {}: {}",
+                names.len(),
+                label,
+                truncate(&names.join(", "), 80)
+            ));
+        }
+    }
+    let skipped = named(CheckStatus::Skipped).len();
+    if skipped > 0 {
+        out.push_str(&format!(", {} skipped", skipped));
+    }
+    out
+}
+
+fn format_pr(pr: &PullRequest) -> String {
+    let icon = match pr.state.as_str() {
+        "merged" => "🟣",
+        "closed" => "🔴",
+        _ => "🟢",
+    };
+    let mut out = vec![format!(
+        "{} PR #{}: {}{}",
+        icon,
+        pr.number,
+        pr.title,
+        if pr.draft { " [draft]" } else { "" }
+    )];
+    let added: usize = pr.files.iter().map(|f| f.added).sum();
+    let removed: usize = pr.files.iter().map(|f| f.removed).sum();
+    out.push(format!(
+        "  {}: {} → {} | +{} -{} in {}F | {}",
+        pr.author,
+        pr.head,
+        pr.base,
+        added,
+        removed,
+        pr.files.len(),
+        pr.state
+    ));
+    out.push(format!("  {}", pr.url));
+
+    if !pr.checks.is_empty() {
+        out.push(format!("Checks: {}", format_checks(&pr.checks)));
+    }
+    if !pr.reviews.is_empty() {
+        let reviews: Vec<String> = pr
+            .reviews
+            .iter()
+            .map(|(who, state)| {
+                let mark = match state.as_str() {
+                    "approved" => "✓ ",
+                    "changes requested" => "✗ ",
+                    _ => "",
+                };
+                format!("{} {}{}", who, mark, state)
+            })
+            .collect();
+        out.push(format!("Reviews: {}", reviews.join(", ")));
+    }
+
+    let body = filter_markdown_body(&pr.body);
+    if !body.trim().is_empty() {
+        out.push("Description:".to_string());
+        let lines: Vec<&str> = body.lines().collect();
+        for line in lines.iter().take(MAX_BODY_LINES) {
+            out.push(format!("  {}", line));
+        }
+        if lines.len() > MAX_BODY_LINES {
+            out.push(format!("  ... +{} lines", lines.len() - MAX_BODY_LINES));
+        }
+    }
+
+    if !pr.files.is_empty() {
+        out.push(format!("Files ({}):", pr.files.len()));
+        let mut files: Vec<&ChangedFile> = pr.files.iter().collect();
+        files.sort_by_key(|f| std::cmp::Reverse(f.added + f.removed));
+        for file in files.iter().take(MAX_FILES) {
+            out.push(format!("  {} +{} -{}", file.path, file.added, file.removed));
+        }
+        if files.len() > MAX_FILES {
+            out.push(format!("  ... +{} files", files.len() - MAX_FILES));
+        }
+    }
+
+    if !pr.comments.is_empty() {
+        let inline = pr.comments.iter().filter(|c| c.path.is_some()).count();
+        out.push(format!(
+            "Comments ({}, {} inline):",
+            pr.comments.len(),
+            inline
+        ));
+        for comment in pr.comments.iter().take(MAX_COMMENTS) {
+            let location = match (&comment.path, comment.line) {
+                (Some(path), Some(line)) => format!("{}:{} ", path, line),
+                (Some(path), None) => format!("{} ", path),
+                _ => String::new(),
+            };
+            let body = comment
+                .body
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ");
+            out.push(format!(
+                "  {}{}: {}",
+                location,
+                comment.author,
+                truncate(&body, COMMENT_WIDTH)
+            ));
+        }
+        if pr.comments.len() > MAX_COMMENTS {
+            out.push(format!(
+                "  ... +{} comments",
+                pr.comments.len() - MAX_COMMENTS
+            ));
+        }
+    }
+    out.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_pages() {
+        assert_eq!(
+            parse_pages("[{\"a\":\"][\"}][{\"b\":2}]\n", "x").unwrap(),
+            json!([{ "a": "][" }, { "b": 2 }])
+        );
+        assert_eq!(
+            parse_pages("{\"changes\":[]}", "x").unwrap(),
+            json!({ "changes": [] })
+        );
+    }
+
+    #[test]
+    fn test_github_repo() {
+        assert_eq!(
+            github_repo("https://github.com/rtk-ai/rtk/pull/42"),
+            Some(("github.com".into(), "rtk-ai/rtk".into()))
+        );
+        assert_eq!(github_repo("not a url"), None);
+    }
+
+    #[test]
+    fn test_from_github() {
+        let view = json!({
+            "number": 42, "title": "Add x", "state": "OPEN", "isDraft": false,
+            "author": { "login": "alice" }, "url": "https://github.com/o/r/pull/42",
+            "baseRefName": "main", "headRefName": "feat/x", "body": "Adds x.",
+            "reviews": [
+                { "author": { "login": "bob" }, "state": "CHANGES_REQUESTED", "body": "Needs tests" },
+                { "author": { "login": "bob" }, "state": "COMMENTED", "body": "" },
+                { "author": { "login": "carol" }, "state": "APPROVED", "body": "" }
+            ],
+            "comments": [{ "author": { "login": "dave" }, "body": "Nice" }],
+            "statusCheckRollup": [
+                { "name": "build", "status": "COMPLETED", "conclusion": "SUCCESS" },
+                { "name": "lint", "status": "COMPLETED", "conclusion": "FAILURE" },
+                { "name": "e2e", "status": "IN_PROGRESS", "conclusion": "" },
+                { "context": "ci/legacy", "state": "SUCCESS" }
+            ],
+            "files": [
+                { "path": "src/x.rs", "additions": 40, "deletions": 2 },
+                { "path": "README.md", "additions": 3, "deletions": 0 }
+            ]
+        });
+        let inline = json!([
+            { "user": { "login": "bob" }, "path": "src/x.rs", "line": 12, "body": "unwrap?" }
+        ]);
+        let pr = from_github(&view, &inline);
+        assert_eq!(
+            pr.reviews,
+            vec![
+                ("bob".to_string(), "changes requested".to_string()),
+                ("carol".to_string(), "approved".to_string())
+            ]
+        );
+        assert_eq!(pr.checks[2].status, CheckStatus::Pending);
+        assert_eq!(pr.checks[3].name, "ci/legacy");
+        assert_eq!(pr.comments.len(), 3);
+        assert_eq!(pr.comments[2].line, Some(12));
+
+        assert_eq!(
+            format_pr(&pr),
+            "🟢 PR #42: Add x\n\
+             \x20 alice: feat/x → main | +43 -2 in 2F | open\n\
+             \x20 https://github.com/o/r/pull/42\n\
+             Checks: 2/4 passed, 1 failed: lint, 1 pending: e2e\n\
+             Reviews: bob ✗ changes requested, carol ✓ approved\n\
+             Description:\n\
+             \x20 Adds x.\n\
+             Files (2):\n\
+             \x20 src/x.rs +40 -2\n\
+             \x20 README.md +3 -0\n\
+             Comments (3, 1 inline):\n\
+             \x20 bob: Needs tests\n\
+             \x20 dave: Nice\n\
+             \x20 src/x.rs:12 bob: unwrap?"
+        );
+    }
+
+    #[test]
+    fn test_from_gitlab() {
+        let view = json!({
+            "iid": 7, "project_id": 3, "title": "Fix y", "state": "merged", "draft": false,
+            "author": { "username": "erin" }, "web_url": "https://gitlab.com/g/p/-/merge_requests/7",
+            "target_branch": "main", "source_branch": "fix/y", "description": "",
+            "head_pipeline": { "id": 99, "status": "failed" }
+        });
+        let changes = json!({ "changes": [
+            { "new_path": "lib/y.py", "diff": "@@ -1 +1 @@\n-old\n+new\n+more\n" }
+        ]});
+        let discussions = json!([
+            { "notes": [
+                { "author": { "username": "frank" }, "body": "why?", "system": false,
+                  "position": { "new_path": "lib/y.py", "new_line": 1 } },
+                { "author": { "username": "erin" }, "body": "added 1 commit", "system": true }
+            ]}
+        ]);
+        let approvals = json!({ "approved_by": [{ "user": { "username": "frank" } }] });
+        let jobs = json!([
+            { "name": "test", "status": "failed" },
+            { "name": "deploy", "status": "manual" }
+        ]);
+        let pr = from_gitlab(&view, &changes, &discussions, &approvals, &jobs);
+        assert_eq!(pr.state, "merged");
+        assert_eq!(
+            pr.files,
+            vec![ChangedFile {
+                path: "lib/y.py".into(),
+                added: 2,
+                removed: 1
+            }]
+        );
+        assert_eq!(pr.comments.len(), 1);
+        assert_eq!(pr.comments[0].path.as_deref(), Some("lib/y.py"));
+        assert_eq!(
+            format_checks(&pr.checks),
+            "0/2 passed, 1 failed: test, 1 skipped"
+        );
+        assert_eq!(pr.reviews, vec![("frank".into(), "approved".into())]);
+    }
+}
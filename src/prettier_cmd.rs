@@ -20,8 +20,7 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
         .output()
         .context("Failed to run prettier (try: npm install -g prettier)")?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    let (stdout, stderr) = crate::output::captured(&output);
     let raw = format!("{}\n{}", stdout, stderr);
 
     let filtered = filter_prettier_output(&raw);
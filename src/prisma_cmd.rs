@@ -1,4 +1,5 @@
 use crate::tracking;
+use crate::utils;
 use anyhow::{Context, Result};
 use std::process::Command;
 
@@ -26,16 +27,12 @@ pub fn run(cmd: PrismaCommand, args: &[String], verbose: u8) -> Result<()> {
 
 /// Create a Command that will run prisma (tries global first, then npx)
 fn create_prisma_command() -> Command {
-    let prisma_exists = Command::new("which")
-        .arg("prisma")
-        .output()
-        .map(|o| o.status.success())
-        .unwrap_or(false);
+    let prisma_exists = utils::tool_exists("prisma");
 
     if prisma_exists {
-        Command::new("prisma")
+        utils::tool_command("prisma")
     } else {
-        let mut c = Command::new("npx");
+        let mut c = utils::tool_command("npx");
         c.arg("prisma");
         c
     }
@@ -61,7 +58,8 @@ fn run_generate(args: &[String], verbose: u8) -> Result<()> {
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!("prisma generate failed: {}", stderr);
+        eprintln!("prisma generate failed: {}", stderr.trim());
+        utils::exit_with("prisma", utils::exit_code(output.status), verbose);
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
@@ -112,7 +110,8 @@ fn run_migrate(subcommand: MigrateSubcommand, args: &[String], verbose: u8) -> R
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!("prisma migrate failed: {}", stderr);
+        eprintln!("prisma migrate failed: {}", stderr.trim());
+        utils::exit_with("prisma", utils::exit_code(output.status), verbose);
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
@@ -150,7 +149,8 @@ fn run_db_push(args: &[String], verbose: u8) -> Result<()> {
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!("prisma db push failed: {}", stderr);
+        eprintln!("prisma db push failed: {}", stderr.trim());
+        utils::exit_with("prisma", utils::exit_code(output.status), verbose);
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
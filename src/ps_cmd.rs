@@ -0,0 +1,320 @@
+//! Compact process listing: replaces `ps aux` with a fixed-width table of
+//! the top processes by CPU or memory.
+//!
+//! Processes spawned by a parent running the same executable (browser
+//! helpers, worker pools, `postgres` backends) are folded into their
+//! topmost ancestor's row, with their usage summed: `chrome (+23)`.
+
+use crate::tracking;
+use crate::utils::{format_bytes, truncate};
+use anyhow::Result;
+use std::collections::HashMap;
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+
+/// Max chars of the command column
+const MAX_COMMAND_LEN: usize = 60;
+
+#[derive(Debug, Clone, PartialEq)]
+struct Proc {
+    pid: u32,
+    ppid: u32,
+    cpu: f64,
+    mem: f64,
+    /// Resident set size in KB
+    rss_kb: u64,
+    args: String,
+}
+
+/// One table row: a process and its same-executable descendants
+#[derive(Debug)]
+struct ProcGroup {
+    pid: u32,
+    cpu: f64,
+    mem: f64,
+    rss_kb: u64,
+    /// Folded descendants
+    children: usize,
+    args: String,
+}
+
+pub fn run(pattern: Option<&str>, sort_by_mem: bool, limit: usize, verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    let procs = collect();
+    if verbose > 0 {
+        eprintln!("Read {} processes", procs.len());
+    }
+
+    let raw = format_raw(&procs);
+    let filtered = format_table(&procs, pattern, sort_by_mem, limit);
+    print!("{}", filtered);
+
+    timer.track("ps aux", "rtk ps", &raw, &filtered);
+    Ok(())
+}
+
+/// Snapshot of the process table. %CPU is averaged over each process's
+/// lifetime, as `ps` reports it, so a single sample is enough.
+fn collect() -> Vec<Proc> {
+    let mut sys = System::new();
+    sys.refresh_memory();
+    sys.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        true,
+        ProcessRefreshKind::nothing()
+            .with_cpu()
+            .with_memory()
+            .with_cmd(UpdateKind::Always)
+            .without_tasks(),
+    );
+    let total_memory = sys.total_memory().max(1) as f64;
+
+    let mut procs: Vec<Proc> = sys
+        .processes()
+        .values()
+        .map(|p| {
+            let args = if p.cmd().is_empty() {
+                // Kernel threads have no command line: show `[name]` like ps
+                format!("[{}]", p.name().to_string_lossy())
+            } else {
+                p.cmd()
+                    .iter()
+                    .map(|arg| arg.to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            };
+            let run_ms = p.run_time().saturating_mul(1000);
+            let cpu = if run_ms > 0 {
+                p.accumulated_cpu_time() as f64 * 100.0 / run_ms as f64
+            } else {
+                0.0
+            };
+            Proc {
+                pid: p.pid().as_u32(),
+                ppid: p.parent().map_or(0, |pid| pid.as_u32()),
+                cpu,
+                mem: p.memory() as f64 * 100.0 / total_memory,
+                rss_kb: p.memory() / 1024,
+                args,
+            }
+        })
+        .collect();
+    procs.sort_by_key(|p| p.pid);
+    procs
+}
+
+/// Full `ps aux`-style listing, the output `rtk ps` stands in for
+fn format_raw(procs: &[Proc]) -> String {
+    let mut out = String::from("PID PPID %CPU %MEM RSS COMMAND\n");
+    for p in procs {
+        out.push_str(&format!(
+            "{} {} {:.1} {:.1} {} {}\n",
+            p.pid, p.ppid, p.cpu, p.mem, p.rss_kb, p.args
+        ));
+    }
+    out
+}
+
+/// Executable name without its directory: `/usr/bin/python3 app.py` → `python3`
+fn exe_name(args: &str) -> &str {
+    if args.starts_with('[') {
+        // Kernel thread: `[kworker/0:1]`
+        return args;
+    }
+    let exe = args.split_whitespace().next().unwrap_or("");
+    exe.rsplit(['/', '\\']).next().unwrap_or(exe)
+}
+
+/// Command line with the executable's directory dropped
+fn short_command(args: &str) -> String {
+    if args.starts_with('[') {
+        return args.to_string();
+    }
+    let exe = args.split_whitespace().next().unwrap_or("");
+    let rest = &args[exe.len()..];
+    format!("{}{}", exe_name(args), rest)
+}
+
+/// Fold each process into its topmost ancestor running the same executable
+fn group(procs: &[Proc]) -> Vec<ProcGroup> {
+    let by_pid: HashMap<u32, &Proc> = procs.iter().map(|p| (p.pid, p)).collect();
+
+    let mut groups: Vec<ProcGroup> = Vec::new();
+    let mut index: HashMap<u32, usize> = HashMap::new();
+
+    for proc in procs {
+        let exe = exe_name(&proc.args);
+        let mut root = proc;
+        // Bounded walk: pid reuse could otherwise form a cycle
+        for _ in 0..procs.len() {
+            match by_pid.get(&root.ppid) {
+                Some(parent) if parent.pid != root.pid && exe_name(&parent.args) == exe => {
+                    root = parent
+                }
+                _ => break,
+            }
+        }
+
+        let idx = *index.entry(root.pid).or_insert_with(|| {
+            groups.push(ProcGroup {
+                pid: root.pid,
+                cpu: 0.0,
+                mem: 0.0,
+                rss_kb: 0,
+                children: 0,
+                args: root.args.clone(),
+            });
+            groups.len() - 1
+        });
+        let group = &mut groups[idx];
+        group.cpu += proc.cpu;
+        group.mem += proc.mem;
+        group.rss_kb += proc.rss_kb;
+        if proc.pid != root.pid {
+            group.children += 1;
+        }
+    }
+    groups
+}
+
+fn format_table(procs: &[Proc], pattern: Option<&str>, sort_by_mem: bool, limit: usize) -> String {
+    let own_pid = std::process::id();
+    let pattern = pattern.map(str::to_lowercase);
+    let selected: Vec<Proc> = procs
+        .iter()
+        .filter(|p| p.pid != own_pid && p.ppid != own_pid)
+        .filter(|p| match &pattern {
+            Some(pat) => p.args.to_lowercase().contains(pat.as_str()),
+            None => true,
+        })
+        .cloned()
+        .collect();
+
+    if selected.is_empty() {
+        return "No matching processes\n".to_string();
+    }
+
+    let mut groups = group(&selected);
+    if sort_by_mem {
+        groups.sort_by_key(|g| std::cmp::Reverse(g.rss_kb));
+    } else {
+        groups.sort_by(|a, b| b.cpu.total_cmp(&a.cpu));
+    }
+
+    let mut out = format!(
+        "{:>7} {:>6} {:>5} {:>7}  COMMAND\n",
+        "PID", "%CPU", "%MEM", "RSS"
+    );
+    for g in groups.iter().take(limit) {
+        let mut command = truncate(&short_command(&g.args), MAX_COMMAND_LEN);
+        if g.children > 0 {
+            command.push_str(&format!(" (+{})", g.children));
+        }
+        out.push_str(&format!(
+            "{:>7} {:>6.1} {:>5.1} {:>7}  {}\n",
+            g.pid,
+            g.cpu,
+            g.mem,
+            format_bytes(g.rss_kb * 1024),
+            command
+        ));
+    }
+
+    let total_cpu: f64 = selected.iter().map(|p| p.cpu).sum();
+    let total_rss: u64 = selected.iter().map(|p| p.rss_kb).sum();
+    out.push_str(&format!(
+        "{} processes in {} groups ({:.1}% CPU, {} RSS), top {} by {}\n",
+        selected.len(),
+        groups.len(),
+        total_cpu,
+        format_bytes(total_rss * 1024),
+        limit.min(groups.len()),
+        if sort_by_mem { "memory" } else { "CPU" }
+    ));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn proc(pid: u32, ppid: u32, cpu: f64, rss_kb: u64, args: &str) -> Proc {
+        Proc {
+            pid,
+            ppid,
+            cpu,
+            mem: 1.0,
+            rss_kb,
+            args: args.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_collect_lists_current_process() {
+        let procs = collect();
+        let own = procs
+            .iter()
+            .find(|p| p.pid == std::process::id())
+            .expect("current process listed");
+        assert!(own.rss_kb > 0);
+        assert!(!own.args.is_empty());
+        assert!(format_raw(&procs).starts_with("PID PPID %CPU %MEM RSS COMMAND\n"));
+    }
+
+    #[test]
+    fn test_short_command() {
+        assert_eq!(
+            short_command("/usr/local/bin/node server.js --port 3000"),
+            "node server.js --port 3000"
+        );
+        assert_eq!(exe_name("postgres: writer"), "postgres:");
+        assert_eq!(short_command("[kworker/R-rcu_gp]"), "[kworker/R-rcu_gp]");
+        assert_eq!(exe_name(r"C:\Tools\app\app.exe --flag"), "app.exe");
+    }
+
+    #[test]
+    fn test_group_folds_same_executable_descendants() {
+        let procs = vec![
+            proc(1, 0, 0.0, 100, "/sbin/init"),
+            proc(10, 1, 5.0, 1000, "/opt/chrome/chrome --type=browser"),
+            proc(11, 10, 2.0, 500, "/opt/chrome/chrome --type=renderer"),
+            proc(12, 11, 1.0, 500, "/opt/chrome/chrome --type=gpu"),
+            proc(20, 10, 3.0, 200, "/usr/bin/python3 helper.py"),
+        ];
+        let groups = group(&procs);
+        assert_eq!(groups.len(), 3);
+        let chrome = groups.iter().find(|g| g.pid == 10).unwrap();
+        assert_eq!(chrome.children, 2);
+        assert_eq!(chrome.cpu, 8.0);
+        assert_eq!(chrome.rss_kb, 2000);
+    }
+
+    #[test]
+    fn test_format_table_sort_limit_and_filter() {
+        let procs = vec![
+            proc(10, 1, 1.0, 900_000, "/usr/bin/postgres -D /data"),
+            proc(11, 10, 0.5, 100_000, "/usr/bin/postgres -D /data"),
+            proc(20, 1, 50.0, 10_000, "/usr/bin/node build.js"),
+        ];
+        let out = format_table(&procs, None, false, 1);
+        let rows: Vec<&str> = out.lines().collect();
+        assert!(rows[1].ends_with("node build.js"), "{}", out);
+        assert_eq!(rows.len(), 3);
+        assert!(rows[2].starts_with("3 processes in 2 groups"));
+
+        let out = format_table(&procs, None, true, 5);
+        assert!(out
+            .lines()
+            .nth(1)
+            .unwrap()
+            .ends_with("postgres -D /data (+1)"));
+        assert!(out.contains("976.6MB"));
+
+        let out = format_table(&procs, Some("NODE"), false, 5);
+        assert!(!out.contains("postgres"));
+        assert_eq!(
+            format_table(&procs, Some("nope"), false, 5),
+            "No matching processes\n"
+        );
+    }
+}
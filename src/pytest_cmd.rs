@@ -1,5 +1,5 @@
 use crate::tracking;
-use crate::utils::truncate;
+use crate::utils::{self, truncate};
 use anyhow::{Context, Result};
 use std::process::Command;
 
@@ -47,16 +47,12 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
         .output()
         .context("Failed to run pytest. Is it installed? Try: pip install pytest")?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    let (stdout, stderr) = crate::output::captured(&output);
     let raw = format!("{}\n{}", stdout, stderr);
 
     let filtered = filter_pytest_output(&stdout);
 
-    let exit_code = output
-        .status
-        .code()
-        .unwrap_or(if output.status.success() { 0 } else { 1 });
+    let exit_code = utils::exit_code(output.status);
     if let Some(hint) = crate::tee::tee_and_hint(&raw, "pytest", exit_code) {
         println!("{}\n{}", filtered, hint);
     } else {
@@ -76,9 +72,7 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     );
 
     // Preserve exit code for CI/CD
-    if !output.status.success() {
-        std::process::exit(exit_code);
-    }
+    utils::exit_with("pytest", exit_code, verbose);
 
     Ok(())
 }
@@ -1,4 +1,5 @@
 use crate::filter::{self, FilterLevel, Language};
+use crate::redact;
 use crate::tracking;
 use anyhow::{Context, Result};
 use std::fs;
@@ -60,6 +61,7 @@ pub fn run(
     } else {
         filtered.clone()
     };
+    let rtk_output = redact::scrub(&rtk_output);
     println!("{}", rtk_output);
     timer.track(
         &format!("cat {}", file.display()),
@@ -126,6 +128,7 @@ pub fn run_stdin(
     } else {
         filtered.clone()
     };
+    let rtk_output = redact::scrub(&rtk_output);
     println!("{}", rtk_output);
 
     timer.track("cat - (stdin)", "rtk read -", &content, &rtk_output);
@@ -0,0 +1,254 @@
+//! `rtk recall`: read back the output saved with `--save NAME`, whole or in
+//! slices (lines about a file, lines matching a regex, a line range),
+//! instead of running the command again.
+
+use crate::slots::{self, SlotInfo};
+use crate::tracking;
+use crate::utils::{format_bytes, truncate};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use regex::Regex;
+use std::str::FromStr;
+
+/// Width of the command column in the slot list
+const COMMAND_WIDTH: usize = 50;
+
+/// Which lines of a slot to print; every given filter must match
+#[derive(Debug, Default)]
+pub struct Slice {
+    /// Lines mentioning one of these paths
+    pub files: Vec<String>,
+    /// Lines matching this regex
+    pub grep: Option<String>,
+    /// Lines of the slot by number
+    pub lines: Option<LineRange>,
+}
+
+impl Slice {
+    fn is_empty(&self) -> bool {
+        self.files.is_empty() && self.grep.is_none() && self.lines.is_none()
+    }
+}
+
+/// 1-based inclusive range: `10:20`, `10:` (to the end), `:20`, or `10`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LineRange {
+    pub start: usize,
+    pub end: Option<usize>,
+}
+
+impl FromStr for LineRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let number = |n: &str| {
+            n.trim()
+                .parse::<usize>()
+                .map_err(|_| format!("'{}' is not a line range (10:20, 10:, :20, 10)", s))
+        };
+        let (start, end) = match s.split_once(':') {
+            Some((start, end)) => (
+                if start.is_empty() { 1 } else { number(start)? },
+                if end.is_empty() {
+                    None
+                } else {
+                    Some(number(end)?)
+                },
+            ),
+            None => {
+                let n = number(s)?;
+                (n, Some(n))
+            }
+        };
+        Ok(LineRange {
+            start: start.max(1),
+            end,
+        })
+    }
+}
+
+impl LineRange {
+    fn contains(&self, line: usize) -> bool {
+        line >= self.start && self.end.is_none_or(|end| line <= end)
+    }
+}
+
+/// List the slots, print a slice of slot `name`, or remove it
+pub fn run(name: Option<&str>, slice: &Slice, remove: bool, verbose: u8) -> Result<()> {
+    let Some(name) = name else {
+        println!("{}", format_list(&slots::list()?));
+        return Ok(());
+    };
+    if remove {
+        slots::remove(name)?;
+        println!("Removed slot '{}'", name);
+        return Ok(());
+    }
+
+    let timer = tracking::TimedExecution::start();
+    let slot = slots::load(name)?;
+    if verbose > 0 {
+        eprintln!("Slot '{}': output of {}", name, slot.command);
+    }
+    let output = if slice.is_empty() {
+        slot.content.clone()
+    } else {
+        let lines = select(&slot.content, slice)?;
+        let total = slot.content.lines().count();
+        let mut out = format!(
+            "📦 {}: {} of {} lines ({})\n",
+            name,
+            lines.len(),
+            total,
+            slot.command
+        );
+        for line in lines {
+            out.push_str(line);
+            out.push('\n');
+        }
+        out
+    };
+    print!("{}", output);
+    if !output.is_empty() && !output.ends_with('\n') {
+        println!();
+    }
+    timer.track(
+        &slot.command,
+        &format!("rtk recall {}", name),
+        &slot.content,
+        &output,
+    );
+    Ok(())
+}
+
+/// Lines of `content` kept by `slice`, in order
+fn select<'a>(content: &'a str, slice: &Slice) -> Result<Vec<&'a str>> {
+    let regex = slice
+        .grep
+        .as_deref()
+        .map(|p| Regex::new(p).with_context(|| format!("Invalid --grep pattern '{}'", p)))
+        .transpose()?;
+    let files: Vec<&str> = slice
+        .files
+        .iter()
+        .map(|f| f.strip_prefix("./").unwrap_or(f))
+        .collect();
+    Ok(content
+        .lines()
+        .enumerate()
+        .filter(|(i, _)| slice.lines.is_none_or(|range| range.contains(i + 1)))
+        .map(|(_, line)| line)
+        .filter(|line| files.is_empty() || files.iter().any(|f| line.contains(f)))
+        .filter(|line| regex.as_ref().is_none_or(|re| re.is_match(line)))
+        .collect())
+}
+
+fn format_list(slots: &[SlotInfo]) -> String {
+    if slots.is_empty() {
+        return "No saved slots (save an output with --save NAME)".to_string();
+    }
+    let width = slots.iter().map(|s| s.name.len()).max().unwrap_or(0);
+    let mut out = format!("📦 {} slots (rtk recall NAME):\n", slots.len());
+    for slot in slots {
+        let saved = slot
+            .saved
+            .map(|t| DateTime::<Local>::from(t).format("%m-%d %H:%M").to_string())
+            .unwrap_or_default();
+        out.push_str(&format!(
+            "  {:<width$}  {:>7}  {:<11}  {}\n",
+            slot.name,
+            format_bytes(slot.bytes),
+            saved,
+            truncate(&slot.command, COMMAND_WIDTH),
+            width = width
+        ));
+    }
+    out.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GREP: &str = "src/lib.rs:3:fn parse()\n\
+                        src/main.rs:10:parse(args)\n\
+                        tests/parse.rs:1:use parse;\n\
+                        src/lib.rs:42:// parse again\n";
+
+    #[test]
+    fn test_line_range() {
+        assert_eq!(
+            "10:20".parse(),
+            Ok(LineRange {
+                start: 10,
+                end: Some(20)
+            })
+        );
+        assert_eq!(
+            "10:".parse(),
+            Ok(LineRange {
+                start: 10,
+                end: None
+            })
+        );
+        assert_eq!(
+            ":20".parse(),
+            Ok(LineRange {
+                start: 1,
+                end: Some(20)
+            })
+        );
+        assert_eq!(
+            "7".parse(),
+            Ok(LineRange {
+                start: 7,
+                end: Some(7)
+            })
+        );
+        assert!("a:b".parse::<LineRange>().is_err());
+    }
+
+    #[test]
+    fn test_select() {
+        let by_file = Slice {
+            files: vec!["./src/lib.rs".into()],
+            ..Default::default()
+        };
+        assert_eq!(
+            select(GREP, &by_file).unwrap(),
+            vec!["src/lib.rs:3:fn parse()", "src/lib.rs:42:// parse again"]
+        );
+
+        // Filters combine
+        let combined = Slice {
+            files: vec!["src/lib.rs".into(), "tests/".into()],
+            grep: Some(r"^\S+:\d+:(fn|use) ".into()),
+            lines: Some(":3".parse().unwrap()),
+        };
+        assert_eq!(
+            select(GREP, &combined).unwrap(),
+            vec!["src/lib.rs:3:fn parse()", "tests/parse.rs:1:use parse;"]
+        );
+
+        let bad = Slice {
+            grep: Some("parse(".into()),
+            ..Default::default()
+        };
+        assert!(select(GREP, &bad).is_err());
+    }
+
+    #[test]
+    fn test_format_list() {
+        let slots = vec![SlotInfo {
+            name: "foo-matches".into(),
+            command: "grep -rn 'foo' src".into(),
+            bytes: 2048,
+            saved: None,
+        }];
+        assert_eq!(
+            format_list(&slots),
+            "📦 1 slots (rtk recall NAME):\n  foo-matches    2.0KB               grep -rn 'foo' src"
+        );
+        assert!(format_list(&[]).starts_with("No saved slots"));
+    }
+}
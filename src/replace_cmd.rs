@@ -0,0 +1,237 @@
+//! Search-and-replace across files. Without `--write` nothing is touched:
+//! the changes are previewed per file, so a bulk edit can be checked before
+//! it is applied.
+
+use crate::grep_cmd::{self, MatchOptions, SearchOptions};
+use crate::tracking;
+use crate::utils::truncate;
+use anyhow::{Context, Result};
+use regex::{NoExpand, Regex};
+use std::fs;
+
+/// Changed lines previewed per file
+const PREVIEW_LINES: usize = 10;
+
+/// Preview width of a changed line
+const MAX_LINE_CHARS: usize = 100;
+
+/// One changed line
+#[derive(Debug, PartialEq)]
+struct LineChange {
+    line: usize,
+    before: String,
+    after: String,
+}
+
+/// Pending edit of one file
+#[derive(Debug)]
+struct FileEdit {
+    path: String,
+    replacements: usize,
+    changes: Vec<LineChange>,
+    content: String,
+}
+
+/// Replace `pattern` with `replacement` in the files under `paths`. The
+/// replacement may refer to capture groups (`$1`, `${name}`) unless the
+/// pattern is a literal (-F).
+pub fn run(
+    pattern: &str,
+    replacement: &str,
+    paths: &[String],
+    file_types: &[String],
+    matching: MatchOptions,
+    write: bool,
+    verbose: u8,
+) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    let regex = Regex::new(&grep_cmd::regex_source(pattern, matching))
+        .with_context(|| format!("Invalid pattern '{}'", pattern))?;
+    let options = SearchOptions {
+        file_types: file_types.to_vec(),
+        matching,
+        extra_args: Vec::new(),
+    };
+    let files = grep_cmd::search(pattern, paths, &options)?;
+    if verbose > 0 {
+        eprintln!("replace: '{}' matched in {} files", pattern, files.len());
+    }
+
+    let mut edits = Vec::new();
+    for file in files {
+        let content = match fs::read_to_string(&file.path) {
+            Ok(content) => content,
+            Err(e) => {
+                eprintln!("⚠️  skipped {}: {}", file.path, e);
+                continue;
+            }
+        };
+        if let Some(edit) = plan_edit(&file.path, &content, &regex, replacement, matching.fixed) {
+            edits.push(edit);
+        }
+    }
+
+    if write {
+        for edit in &edits {
+            fs::write(&edit.path, &edit.content)
+                .with_context(|| format!("Failed to write {}", edit.path))?;
+        }
+    }
+
+    let rtk_output = format_edits(pattern, &edits, write);
+    print!("{}", rtk_output);
+
+    let raw: String = edits
+        .iter()
+        .flat_map(|e| &e.changes)
+        .map(|c| format!("-{}\n+{}\n", c.before, c.after))
+        .collect();
+    timer.track(
+        &format!(
+            "sed -i 's/{}/{}/g' {}",
+            pattern,
+            replacement,
+            paths.join(" ")
+        ),
+        "rtk replace",
+        &raw,
+        &rtk_output,
+    );
+    Ok(())
+}
+
+/// Apply the replacement line by line, as ripgrep matches. `None` when
+/// nothing changes.
+fn plan_edit(
+    path: &str,
+    content: &str,
+    regex: &Regex,
+    replacement: &str,
+    literal: bool,
+) -> Option<FileEdit> {
+    let mut new_content = String::with_capacity(content.len());
+    let mut changes = Vec::new();
+    let mut replacements = 0;
+
+    for (i, line) in content.split_inclusive('\n').enumerate() {
+        let body = line.trim_end_matches(['\n', '\r']);
+        let ending = &line[body.len()..];
+        let count = regex.find_iter(body).count();
+        if count == 0 {
+            new_content.push_str(line);
+            continue;
+        }
+        let after = if literal {
+            regex.replace_all(body, NoExpand(replacement))
+        } else {
+            regex.replace_all(body, replacement)
+        };
+        new_content.push_str(&after);
+        new_content.push_str(ending);
+        if after != body {
+            replacements += count;
+            changes.push(LineChange {
+                line: i + 1,
+                before: body.to_string(),
+                after: after.into_owned(),
+            });
+        }
+    }
+
+    (!changes.is_empty()).then(|| FileEdit {
+        path: path.to_string(),
+        replacements,
+        changes,
+        content: new_content,
+    })
+}
+
+fn format_edits(pattern: &str, edits: &[FileEdit], written: bool) -> String {
+    if edits.is_empty() {
+        return format!("🔍 0 replacements for '{}'\n", pattern);
+    }
+
+    let total: usize = edits.iter().map(|e| e.replacements).sum();
+    let mut out = if written {
+        format!("✅ {} replacements in {}F written:\n\n", total, edits.len())
+    } else {
+        format!(
+            "✏️  {} replacements in {}F (dry run, --write to apply):\n\n",
+            total,
+            edits.len()
+        )
+    };
+
+    for edit in edits {
+        out.push_str(&format!("📄 {} ({}):\n", edit.path, edit.replacements));
+        if written {
+            continue;
+        }
+        for change in edit.changes.iter().take(PREVIEW_LINES) {
+            out.push_str(&format!(
+                "  {:>4}- {}\n        + {}\n",
+                change.line,
+                truncate(change.before.trim(), MAX_LINE_CHARS),
+                truncate(change.after.trim(), MAX_LINE_CHARS)
+            ));
+        }
+        if edit.changes.len() > PREVIEW_LINES {
+            out.push_str(&format!(
+                "  +{} lines\n",
+                edit.changes.len() - PREVIEW_LINES
+            ));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_edit_keeps_line_endings() {
+        let regex = Regex::new(r"foo(\d)").unwrap();
+        let content = "let foo1 = foo2;\r\nunchanged\nfoo3";
+        let edit = plan_edit("a.rs", content, &regex, "bar$1", false).unwrap();
+        assert_eq!(edit.content, "let bar1 = bar2;\r\nunchanged\nbar3");
+        assert_eq!(edit.replacements, 3);
+        assert_eq!(
+            edit.changes[0],
+            LineChange {
+                line: 1,
+                before: "let foo1 = foo2;".into(),
+                after: "let bar1 = bar2;".into(),
+            }
+        );
+        assert_eq!(edit.changes[1].line, 3);
+
+        assert!(plan_edit("a.rs", "nothing", &regex, "x", false).is_none());
+    }
+
+    #[test]
+    fn test_plan_edit_literal_replacement() {
+        let regex = Regex::new(&regex::escape("a.b")).unwrap();
+        let edit = plan_edit("a.rs", "a.b axb", &regex, "$1", true).unwrap();
+        assert_eq!(edit.content, "$1 axb");
+    }
+
+    #[test]
+    fn test_format_edits_preview() {
+        let regex = Regex::new("old").unwrap();
+        let edit = plan_edit("src/a.rs", "    old_name();\nx\n", &regex, "new", false).unwrap();
+        let out = format_edits("old", &[edit], false);
+        assert!(out.starts_with("✏️  1 replacements in 1F (dry run"));
+        assert!(out.contains("📄 src/a.rs (1):\n     1- old_name();\n        + new_name();\n"));
+
+        let edit = plan_edit("src/a.rs", "old\n", &regex, "new", false).unwrap();
+        let out = format_edits("old", &[edit], true);
+        assert_eq!(
+            out,
+            "✅ 1 replacements in 1F written:\n\n📄 src/a.rs (1):\n"
+        );
+        assert!(format_edits("old", &[], false).starts_with("🔍 0 replacements"));
+    }
+}
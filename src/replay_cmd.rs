@@ -0,0 +1,91 @@
+//! `rtk replay`: re-print the output of a recent tracked invocation, so
+//! something rtk condensed can be recovered without running it again.
+
+use crate::tracking::{ReplayEntry, Tracker};
+use crate::utils::{format_bytes, truncate};
+use anyhow::{Context, Result};
+
+/// Without an entry number, list the invocations that can be replayed.
+/// Entry 1 is the most recent.
+pub fn run(entry: Option<usize>, raw: bool, verbose: u8) -> Result<()> {
+    let tracker = Tracker::new().context("Failed to initialize tracking database")?;
+    let replays = tracker.get_replays()?;
+
+    let Some(n) = entry else {
+        println!("{}", format_list(&replays));
+        return Ok(());
+    };
+
+    let selected = n
+        .checked_sub(1)
+        .and_then(|i| replays.get(i))
+        .with_context(|| {
+            format!(
+                "No replay entry #{} ({} available, see `rtk replay`)",
+                n,
+                replays.len()
+            )
+        })?;
+    if verbose > 0 {
+        eprintln!(
+            "Replaying {} output of: {}",
+            if raw { "raw" } else { "filtered" },
+            selected.original_cmd
+        );
+    }
+    let output = tracker
+        .get_replay_output(selected.id, raw)?
+        .context("Replay entry was removed")?;
+    print!("{}", output);
+    if !output.ends_with('\n') {
+        println!();
+    }
+    Ok(())
+}
+
+fn format_list(replays: &[ReplayEntry]) -> String {
+    if replays.is_empty() {
+        return "No replayable commands (tracking.replay_entries = 0, or nothing tracked yet)"
+            .to_string();
+    }
+
+    let mut out = format!(
+        "🔁 {} replayable commands (rtk replay N [--raw]):\n",
+        replays.len()
+    );
+    for (i, entry) in replays.iter().enumerate() {
+        out.push_str(&format!(
+            "{:>3}  {}  {:<40} {} → {}\n",
+            format!("#{}", i + 1),
+            entry.timestamp.format("%m-%d %H:%M"),
+            truncate(&entry.rtk_cmd, 40),
+            format_bytes(entry.raw_bytes),
+            format_bytes(entry.filtered_bytes)
+        ));
+    }
+    out.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn test_format_list() {
+        let replays = vec![ReplayEntry {
+            id: 7,
+            timestamp: Utc.with_ymd_and_hms(2026, 3, 4, 10, 30, 0).unwrap(),
+            original_cmd: "git log".into(),
+            rtk_cmd: "rtk git log".into(),
+            raw_bytes: 20_480,
+            filtered_bytes: 512,
+        }];
+        let out = format_list(&replays);
+        assert_eq!(
+            out.lines().nth(1).unwrap(),
+            format!(" #1  03-04 10:30  {:<40} 20.0KB → 512B", "rtk git log")
+        );
+        assert!(format_list(&[]).starts_with("No replayable commands"));
+    }
+}
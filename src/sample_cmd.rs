@@ -0,0 +1,249 @@
+//! `rtk sample`: the first and last records of a big JSON, JSON Lines, CSV
+//! or text file, with the middle left out and counted. JSON, JSON Lines
+//! and CSV samples stay parseable: the count goes to stderr instead of
+//! into the data.
+
+use crate::redact;
+use crate::tracking;
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Json,
+    JsonLines,
+    Csv,
+    Lines,
+}
+
+fn detect(file: &Path, content: &str) -> Kind {
+    let ext = file
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match ext.as_str() {
+        "json" => Kind::Json,
+        "jsonl" | "ndjson" => Kind::JsonLines,
+        "csv" | "tsv" => Kind::Csv,
+        _ if content.trim_start().starts_with(['[', '{'])
+            && serde_json::from_str::<Value>(content).is_ok() =>
+        {
+            Kind::Json
+        }
+        _ => Kind::Lines,
+    }
+}
+
+/// What [`sample`] kept
+#[derive(Debug, PartialEq)]
+struct Sample {
+    text: String,
+    /// What was left out, for stderr when the text must stay parseable
+    note: Option<String>,
+}
+
+/// Cut every array longer than `2 * k` down to its first and last `k`
+/// items, noting `(path, kept, total)` for each
+fn sample_value(value: &mut Value, k: usize, path: &str, cut: &mut Vec<(String, usize, usize)>) {
+    match value {
+        Value::Array(items) => {
+            // jq paths: `.`, `.items`, `.[0].ids`
+            let path = if path.is_empty() { "." } else { path };
+            let total = items.len();
+            if total > 2 * k {
+                items.drain(k..total - k);
+                cut.push((path.to_string(), 2 * k, total));
+            }
+            for (i, item) in items.iter_mut().enumerate() {
+                sample_value(item, k, &format!("{}[{}]", path, i), cut);
+            }
+        }
+        Value::Object(map) => {
+            for (key, item) in map.iter_mut() {
+                sample_value(item, k, &format!("{}.{}", path, key), cut);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn sample_json(content: &str, k: usize) -> Result<Sample> {
+    let mut value: Value = serde_json::from_str(content).context("Invalid JSON")?;
+    let mut cut = Vec::new();
+    sample_value(&mut value, k, "", &mut cut);
+    let note = (!cut.is_empty()).then(|| {
+        let parts: Vec<String> = cut
+            .iter()
+            .map(|(path, kept, total)| format!("{} {} of {}", path, kept, total))
+            .collect();
+        format!("✂️  sample: kept {}", parts.join(", "))
+    });
+    Ok(Sample {
+        text: serde_json::to_string_pretty(&value)?,
+        note,
+    })
+}
+
+/// Records of CSV text, quoted newlines kept inside their record
+fn csv_records(content: &str) -> Vec<&str> {
+    let mut records = Vec::new();
+    let mut start = 0;
+    let mut quoted = false;
+    for (i, c) in content.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '\n' if !quoted => {
+                records.push(content[start..i].trim_end_matches('\r'));
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if start < content.len() {
+        records.push(&content[start..]);
+    }
+    records.retain(|r| !r.trim().is_empty());
+    records
+}
+
+/// First and last `k` of `records`, and how many were left out
+fn head_tail<'a>(records: &[&'a str], k: usize) -> (Vec<&'a str>, Vec<&'a str>, usize) {
+    if records.len() <= 2 * k {
+        return (records.to_vec(), Vec::new(), 0);
+    }
+    (
+        records[..k].to_vec(),
+        records[records.len() - k..].to_vec(),
+        records.len() - 2 * k,
+    )
+}
+
+fn sample(kind: Kind, content: &str, k: usize) -> Result<Sample> {
+    let (header, records, what) = match kind {
+        Kind::Json => return sample_json(content, k),
+        Kind::Csv => {
+            let records = csv_records(content);
+            match records.split_first() {
+                Some((header, rows)) => (Some(*header), rows.to_vec(), "rows"),
+                None => (None, Vec::new(), "rows"),
+            }
+        }
+        Kind::JsonLines => (
+            None,
+            content.lines().filter(|l| !l.trim().is_empty()).collect(),
+            "records",
+        ),
+        Kind::Lines => (None, content.lines().collect(), "lines"),
+    };
+
+    let (head, tail, elided) = head_tail(&records, k);
+    let mut lines: Vec<String> = header.into_iter().chain(head).map(String::from).collect();
+    let summary = format!("{} of {} {} elided", elided, records.len(), what);
+    let mut note = None;
+    if elided > 0 {
+        if kind == Kind::Lines {
+            lines.push(format!("... {} ...", summary));
+        } else {
+            note = Some(format!("✂️  sample: {}", summary));
+        }
+    }
+    lines.extend(tail.into_iter().map(String::from));
+    Ok(Sample {
+        text: lines.join("\n"),
+        note,
+    })
+}
+
+/// Print the first and last `records` records of `file` (- for stdin)
+pub fn run(file: &Path, records: usize, verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    let (content, label) = if file == Path::new("-") {
+        let mut content = String::new();
+        io::stdin()
+            .lock()
+            .read_to_string(&mut content)
+            .context("Failed to read from stdin")?;
+        (content, "-".to_string())
+    } else {
+        let content = fs::read_to_string(file)
+            .with_context(|| format!("Failed to read file: {}", file.display()))?;
+        (content, file.display().to_string())
+    };
+
+    let kind = detect(file, &content);
+    if verbose > 0 {
+        eprintln!("Sampling {} as {:?}", label, kind);
+    }
+
+    let sample = sample(kind, &content, records.max(1))?;
+    let text = redact::scrub(&sample.text);
+    println!("{}", text);
+    if let Some(note) = &sample.note {
+        eprintln!("{}", note);
+    }
+
+    timer.track(&format!("cat {}", label), "rtk sample", &content, &text);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(detect(Path::new("a.json"), ""), Kind::Json);
+        assert_eq!(detect(Path::new("a.ndjson"), ""), Kind::JsonLines);
+        assert_eq!(detect(Path::new("a.tsv"), ""), Kind::Csv);
+        assert_eq!(detect(Path::new("-"), " [1, 2]"), Kind::Json);
+        assert_eq!(detect(Path::new("-"), "[INFO] up"), Kind::Lines);
+    }
+
+    #[test]
+    fn test_json_sample_stays_valid() {
+        let items: Vec<String> = (1..=10).map(|i| i.to_string()).collect();
+        let content = format!(
+            r#"{{"name": "x", "items": [{}], "tags": ["a", "b"]}}"#,
+            items.join(",")
+        );
+        let sample = sample(Kind::Json, &content, 2).unwrap();
+        let value: Value = serde_json::from_str(&sample.text).unwrap();
+        assert_eq!(value["items"], serde_json::json!([1, 2, 9, 10]));
+        assert_eq!(value["tags"], serde_json::json!(["a", "b"]));
+        assert_eq!(sample.note.unwrap(), "✂️  sample: kept .items 4 of 10");
+    }
+
+    #[test]
+    fn test_nested_json_paths() {
+        let content = r#"[{"ids": [1, 2, 3, 4, 5]}, 2, 3, 4]"#;
+        let sample = sample(Kind::Json, content, 1).unwrap();
+        let value: Value = serde_json::from_str(&sample.text).unwrap();
+        assert_eq!(value, serde_json::json!([{"ids": [1, 5]}, 4]));
+        assert_eq!(
+            sample.note.unwrap(),
+            "✂️  sample: kept . 2 of 4, .[0].ids 2 of 5"
+        );
+    }
+
+    #[test]
+    fn test_csv_keeps_header_and_quoted_newlines() {
+        let content = "id,note\n1,a\n2,\"multi\nline\"\n3,c\n4,d\n5,e\n";
+        let sample = sample(Kind::Csv, content, 2).unwrap();
+        assert_eq!(sample.text, "id,note\n1,a\n2,\"multi\nline\"\n4,d\n5,e");
+        assert_eq!(sample.note.unwrap(), "✂️  sample: 1 of 5 rows elided");
+    }
+
+    #[test]
+    fn test_lines_marker_inline() {
+        let content = "1\n2\n3\n4\n5\n6\n";
+        let cut = sample(Kind::Lines, content, 2).unwrap();
+        assert_eq!(cut.text, "1\n2\n... 2 of 6 lines elided ...\n5\n6");
+        assert_eq!(cut.note, None);
+        // Short files are untouched
+        assert_eq!(sample(Kind::Lines, "a\nb", 5).unwrap().text, "a\nb");
+    }
+}
@@ -0,0 +1,260 @@
+//! SARIF 2.1.0 output (`--format sarif`) for commands that report findings
+//! at file locations (`rtk lint`, `rtk grep`), for GitHub code scanning
+//! and IDE SARIF viewers.
+//!
+//! Every finding is kept: unlike rtk's summaries, a SARIF log is read by
+//! tools, not by the model. Paths inside the repository are made relative
+//! to its root (`%SRCROOT%`), the others become `file://` URIs.
+
+use crate::paths;
+use anyhow::Result;
+use serde_json::{json, Value};
+use std::path::Path;
+
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// SARIF `level` of a finding or notification
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Level {
+    Error,
+    Warning,
+    Note,
+}
+
+impl Level {
+    fn as_str(self) -> &'static str {
+        match self {
+            Level::Error => "error",
+            Level::Warning => "warning",
+            Level::Note => "note",
+        }
+    }
+}
+
+/// One result at a file location
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    pub rule: Option<String>,
+    pub level: Level,
+    pub message: String,
+    pub file: String,
+    /// 1-based; 0 when the tool didn't say
+    pub line: usize,
+    /// 1-based
+    pub column: Option<usize>,
+}
+
+/// What the tool run produced besides findings: stderr errors, warnings
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notification {
+    pub level: Level,
+    pub message: String,
+}
+
+/// SARIF log of one run of `tool`. `descriptions` give the rules a short
+/// description (rule id, text); rules are listed in order of first use.
+pub fn render(
+    tool: &str,
+    descriptions: &[(String, String)],
+    findings: &[Finding],
+    notifications: &[Notification],
+) -> Result<String> {
+    let cwd = std::env::current_dir().unwrap_or_default();
+    let root = paths::repo_root();
+    Ok(serde_json::to_string_pretty(&log(
+        tool,
+        descriptions,
+        findings,
+        notifications,
+        &|file| artifact_location(file, &cwd, root),
+    ))?)
+}
+
+fn log(
+    tool: &str,
+    descriptions: &[(String, String)],
+    findings: &[Finding],
+    notifications: &[Notification],
+    location: &dyn Fn(&str) -> Value,
+) -> Value {
+    let mut rule_ids: Vec<&str> = Vec::new();
+    for rule in findings.iter().filter_map(|f| f.rule.as_deref()) {
+        if !rule_ids.contains(&rule) {
+            rule_ids.push(rule);
+        }
+    }
+    let rules: Vec<Value> = rule_ids
+        .iter()
+        .map(|id| {
+            let mut rule = json!({ "id": id });
+            if let Some((_, text)) = descriptions.iter().find(|(rule, _)| rule == id) {
+                rule["shortDescription"] = json!({ "text": text });
+            }
+            rule
+        })
+        .collect();
+
+    let results: Vec<Value> = findings
+        .iter()
+        .map(|finding| {
+            let mut region = json!({ "startLine": finding.line.max(1) });
+            if let Some(column) = finding.column.filter(|&c| c > 0) {
+                region["startColumn"] = json!(column);
+            }
+            let mut result = json!({
+                "level": finding.level.as_str(),
+                "message": { "text": finding.message },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": location(&finding.file),
+                        "region": region,
+                    }
+                }],
+            });
+            if let Some(rule) = &finding.rule {
+                result["ruleId"] = json!(rule);
+                result["ruleIndex"] = json!(rule_ids.iter().position(|id| id == rule));
+            }
+            result
+        })
+        .collect();
+
+    let invocation = json!({
+        "executionSuccessful": !notifications.iter().any(|n| n.level == Level::Error),
+        "toolExecutionNotifications": notifications
+            .iter()
+            .map(|n| json!({ "level": n.level.as_str(), "message": { "text": n.message } }))
+            .collect::<Vec<_>>(),
+    });
+
+    json!({
+        "$schema": SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": { "driver": { "name": tool, "rules": rules } },
+            "invocations": [invocation],
+            "results": results,
+        }]
+    })
+}
+
+/// `artifactLocation` of `file`: root-relative under the repository,
+/// an absolute `file://` URI elsewhere
+fn artifact_location(file: &str, cwd: &Path, root: Option<&Path>) -> Value {
+    let absolute = cwd.join(file.strip_prefix("./").unwrap_or(file));
+    match root.and_then(|r| absolute.strip_prefix(r).ok()) {
+        Some(rest) => json!({
+            "uri": encode_uri(&rest.to_string_lossy()),
+            "uriBaseId": "%SRCROOT%",
+        }),
+        None => json!({ "uri": format!("file://{}", encode_uri(&absolute.to_string_lossy())) }),
+    }
+}
+
+/// Forward slashes, and the characters a URI can't hold as-is escaped
+fn encode_uri(path: &str) -> String {
+    let mut uri = String::with_capacity(path.len());
+    for c in path.replace('\\', "/").chars() {
+        match c {
+            ' ' => uri.push_str("%20"),
+            '#' => uri.push_str("%23"),
+            '%' => uri.push_str("%25"),
+            '?' => uri.push_str("%3F"),
+            _ => uri.push(c),
+        }
+    }
+    uri
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log() {
+        let findings = vec![
+            Finding {
+                rule: Some("no-unused-vars".into()),
+                level: Level::Error,
+                message: "'x' is unused".into(),
+                file: "src/a.js".into(),
+                line: 3,
+                column: Some(7),
+            },
+            Finding {
+                rule: None,
+                level: Level::Note,
+                message: "no rule".into(),
+                file: "src/b.js".into(),
+                line: 0,
+                column: None,
+            },
+        ];
+        let descriptions = vec![("no-unused-vars".to_string(), "Unused".to_string())];
+        let notifications = vec![Notification {
+            level: Level::Warning,
+            message: "skipped a binary file".into(),
+        }];
+        let log = log(
+            "eslint",
+            &descriptions,
+            &findings,
+            &notifications,
+            &|file| json!({ "uri": file }),
+        );
+        let run = &log["runs"][0];
+
+        assert_eq!(log["version"], "2.1.0");
+        assert_eq!(run["tool"]["driver"]["name"], "eslint");
+        assert_eq!(
+            run["tool"]["driver"]["rules"],
+            json!([{ "id": "no-unused-vars", "shortDescription": { "text": "Unused" } }])
+        );
+        assert_eq!(
+            run["results"][0],
+            json!({
+                "ruleId": "no-unused-vars",
+                "ruleIndex": 0,
+                "level": "error",
+                "message": { "text": "'x' is unused" },
+                "locations": [{ "physicalLocation": {
+                    "artifactLocation": { "uri": "src/a.js" },
+                    "region": { "startLine": 3, "startColumn": 7 },
+                }}],
+            })
+        );
+        // No rule, no line: still a valid result
+        assert!(run["results"][1].get("ruleId").is_none());
+        assert_eq!(
+            run["results"][1]["locations"][0]["physicalLocation"]["region"],
+            json!({ "startLine": 1 })
+        );
+        assert_eq!(run["invocations"][0]["executionSuccessful"], true);
+        assert_eq!(
+            run["invocations"][0]["toolExecutionNotifications"][0]["level"],
+            "warning"
+        );
+    }
+
+    #[test]
+    fn test_artifact_location() {
+        let cwd = Path::new("/repo/app");
+        let root = Some(Path::new("/repo"));
+        assert_eq!(
+            artifact_location("./src/main rs#1.rs", cwd, root),
+            json!({ "uri": "app/src/main%20rs%231.rs", "uriBaseId": "%SRCROOT%" })
+        );
+        assert_eq!(
+            artifact_location("/repo/lib.rs", cwd, root),
+            json!({ "uri": "lib.rs", "uriBaseId": "%SRCROOT%" })
+        );
+        assert_eq!(
+            artifact_location("/usr/include/stdio.h", cwd, root),
+            json!({ "uri": "file:///usr/include/stdio.h" })
+        );
+        assert_eq!(
+            artifact_location("src/lib.rs", cwd, None),
+            json!({ "uri": "file:///repo/app/src/lib.rs" })
+        );
+    }
+}
@@ -0,0 +1,208 @@
+//! Named result slots: `--save NAME` keeps the full raw output of a command
+//! under a name, and `rtk recall NAME` reads slices of it back later, so a
+//! multi-step workflow doesn't have to run the same search twice.
+//!
+//! Slots live in `~/.local/share/rtk/slots/` (or `RTK_SLOTS_DIR`), one
+//! file per name, the command that produced it on the first line. Saving
+//! under an existing name replaces it.
+
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::SystemTime;
+
+const EXTENSION: &str = "slot";
+
+/// First line of a slot file, before the command
+const HEADER: &str = "rtk-slot: ";
+
+/// A saved output
+#[derive(Debug, Clone, PartialEq)]
+pub struct Slot {
+    pub name: String,
+    /// Command whose raw output it is, e.g. `grep -rn 'foo' src`
+    pub command: String,
+    pub content: String,
+}
+
+/// Name, command, size in bytes and modification time of every slot
+#[derive(Debug)]
+pub struct SlotInfo {
+    pub name: String,
+    pub command: String,
+    pub bytes: u64,
+    pub saved: Option<SystemTime>,
+}
+
+static REQUESTED: OnceLock<Option<(String, bool)>> = OnceLock::new();
+
+/// Resolve `--save NAME` once, at startup. With `hint_to_stderr` (JSON
+/// output) the confirmation doesn't break the document.
+pub fn request(name: Option<String>, hint_to_stderr: bool) -> Result<()> {
+    if let Some(name) = &name {
+        check_name(name)?;
+    }
+    let _ = REQUESTED.set(name.map(|n| (n, hint_to_stderr)));
+    Ok(())
+}
+
+/// `--save NAME`: keep `raw` under the requested name and say how to get it
+/// back. Called when the command is tracked, like `--out`.
+pub fn save_requested(raw: &str, original_cmd: &str) {
+    let Some((name, hint_to_stderr)) = REQUESTED.get().and_then(Option::as_ref) else {
+        return;
+    };
+    let hint = match save(name, original_cmd, raw) {
+        Ok(()) => format!(
+            "[saved as '{}': rtk recall {} --file/--grep/--lines]",
+            name, name
+        ),
+        Err(e) => format!("rtk: cannot save '{}': {:#}", name, e),
+    };
+    if *hint_to_stderr {
+        eprintln!("{}", hint);
+    } else {
+        println!("{}", hint);
+    }
+}
+
+/// Streamed and passthrough output is never held in memory: nothing to save
+pub fn warn_not_captured() {
+    if let Some(Some((name, _))) = REQUESTED.get() {
+        eprintln!(
+            "rtk: --save {}: this command streams its output, nothing was saved",
+            name
+        );
+    }
+}
+
+/// Names are file names: letters, digits, `.`, `_`, `-`, not starting with
+/// a dot
+pub fn check_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'));
+    if !valid {
+        bail!(
+            "Invalid slot name '{}': use letters, digits, '.', '_' and '-'",
+            name
+        );
+    }
+    Ok(())
+}
+
+fn slots_dir() -> Option<PathBuf> {
+    if let Ok(dir) = std::env::var("RTK_SLOTS_DIR") {
+        return Some(PathBuf::from(dir));
+    }
+    dirs::data_local_dir().map(|d| d.join("rtk").join("slots"))
+}
+
+fn slot_path(name: &str) -> Result<PathBuf> {
+    let dir = slots_dir().context("Cannot determine the slots directory")?;
+    Ok(dir.join(format!("{}.{}", name, EXTENSION)))
+}
+
+/// Store `content` under `name`, replacing any previous slot of that name
+pub fn save(name: &str, command: &str, content: &str) -> Result<()> {
+    check_name(name)?;
+    let path = slot_path(name)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    let command = command.replace('\n', " ");
+    fs::write(&path, format!("{}{}\n{}", HEADER, command, content))
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// The slot saved as `name`
+pub fn load(name: &str) -> Result<Slot> {
+    check_name(name)?;
+    let path = slot_path(name)?;
+    let text = fs::read_to_string(&path).with_context(|| {
+        format!(
+            "No slot '{}' (save one with --save {}, list them with rtk recall)",
+            name, name
+        )
+    })?;
+    let (command, content) = parse(&text);
+    Ok(Slot {
+        name: name.to_string(),
+        command: command.to_string(),
+        content: content.to_string(),
+    })
+}
+
+/// Every slot, most recently saved first
+pub fn list() -> Result<Vec<SlotInfo>> {
+    let Some(dir) = slots_dir() else {
+        return Ok(Vec::new());
+    };
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return Ok(Vec::new());
+    };
+    let mut slots = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().is_none_or(|ext| ext != EXTENSION) {
+            continue;
+        }
+        let Some(name) = path.file_stem().map(|s| s.to_string_lossy().into_owned()) else {
+            continue;
+        };
+        let text = fs::read_to_string(&path).unwrap_or_default();
+        let (command, content) = parse(&text);
+        let saved = entry.metadata().and_then(|m| m.modified()).ok();
+        slots.push(SlotInfo {
+            name,
+            command: command.to_string(),
+            bytes: content.len() as u64,
+            saved,
+        });
+    }
+    slots.sort_by(|a, b| b.saved.cmp(&a.saved).then_with(|| a.name.cmp(&b.name)));
+    Ok(slots)
+}
+
+/// Remove the slot saved as `name`
+pub fn remove(name: &str) -> Result<()> {
+    check_name(name)?;
+    let path = slot_path(name)?;
+    fs::remove_file(&path).with_context(|| format!("No slot '{}'", name))
+}
+
+/// Command and content of a slot file
+fn parse(text: &str) -> (&str, &str) {
+    match text.strip_prefix(HEADER) {
+        Some(rest) => rest.split_once('\n').unwrap_or((rest, "")),
+        None => ("", text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_name() {
+        for name in ["foo-matches", "run.2", "a_b"] {
+            assert!(check_name(name).is_ok(), "{}", name);
+        }
+        for name in ["", ".hidden", "../x", "a/b", "with space"] {
+            assert!(check_name(name).is_err(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse("rtk-slot: rtk grep foo src\nsrc/a.rs:1:foo\n"),
+            ("rtk grep foo src", "src/a.rs:1:foo\n")
+        );
+        assert_eq!(parse("rtk-slot: rtk ls"), ("rtk ls", ""));
+        assert_eq!(parse("no header"), ("", "no header"));
+    }
+}
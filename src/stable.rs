@@ -0,0 +1,87 @@
+//! Versioned output formats. The compact text formats are meant for humans
+//! and agents, but scripts parse them too; `--stable-output` is the promise
+//! they can rely on:
+//!
+//! - the formats only change together with [`FORMAT_VERSION`], so a parser
+//!   written against version N can pin it (`--stable-output=N`) and fails
+//!   with a clear error instead of silently misreading a newer rtk;
+//! - nothing depends on the environment: no colors, and the fixed default
+//!   widths instead of the terminal's (`--width` still applies).
+//!
+//! The guarantee is enforced by the snapshot tests ([`assert_format!`]):
+//! snapshots are named after the format version, so changing a format means
+//! either bumping the version (and recording new snapshots) or failing them.
+
+use anyhow::{bail, Result};
+use std::sync::OnceLock;
+
+/// Version of the compact output formats. Bump it when any snapshot under
+/// `src/snapshots/` has to change.
+pub const FORMAT_VERSION: u32 = 1;
+
+static STABLE: OnceLock<bool> = OnceLock::new();
+
+/// Resolve `--stable-output` once, at startup: `Some(None)` for the flag
+/// alone, `Some(Some(n))` for a pinned version. Nested rtk invocations
+/// inherit it through `RTK_STABLE_OUTPUT`.
+pub fn set(requested: Option<Option<u32>>) -> Result<()> {
+    let requested = match requested {
+        Some(version) => Some(version.unwrap_or(FORMAT_VERSION)),
+        None => std::env::var("RTK_STABLE_OUTPUT")
+            .ok()
+            .filter(|v| !v.is_empty())
+            .map(|v| v.parse().unwrap_or(FORMAT_VERSION)),
+    };
+    if let Some(version) = requested {
+        check(version)?;
+        std::env::set_var("RTK_STABLE_OUTPUT", version.to_string());
+    }
+    let _ = STABLE.set(requested.is_some());
+    Ok(())
+}
+
+/// Whether the output must not depend on the terminal
+pub fn enabled() -> bool {
+    *STABLE.get_or_init(|| std::env::var_os("RTK_STABLE_OUTPUT").is_some())
+}
+
+fn check(version: u32) -> Result<()> {
+    if version != FORMAT_VERSION {
+        bail!(
+            "--stable-output={} requested, but this rtk produces output format {}",
+            version,
+            FORMAT_VERSION
+        );
+    }
+    Ok(())
+}
+
+/// Snapshot test of a compact format, recorded in `src/snapshots/` as
+/// `rtk__<module>__tests__<name>@v<FORMAT_VERSION>.snap`. Review new or
+/// changed snapshots with `cargo insta review`.
+#[cfg(test)]
+macro_rules! assert_format {
+    ($name:expr, $output:expr) => {
+        insta::with_settings!({
+            snapshot_suffix => format!("v{}", $crate::stable::FORMAT_VERSION),
+            omit_expression => true,
+        }, {
+            insta::assert_snapshot!($name, $output);
+        })
+    };
+}
+
+#[cfg(test)]
+pub(crate) use assert_format;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_pins_the_version() {
+        assert!(check(FORMAT_VERSION).is_ok());
+        let err = check(FORMAT_VERSION + 1).unwrap_err().to_string();
+        assert!(err.contains(&format!("output format {}", FORMAT_VERSION)));
+    }
+}
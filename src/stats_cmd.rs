@@ -0,0 +1,456 @@
+use crate::layout;
+use crate::tracking::{command_group, CommandStats, ExportRecord, Tracker};
+use crate::utils::{format_bytes, format_tokens, truncate};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Number of "top offenders" shown below the per-command table
+const OFFENDERS: usize = 5;
+
+/// Command column width when the terminal width is unknown
+const COMMAND_WIDTH: usize = 24;
+
+/// Columns taken by a row besides the command (runs, sizes, tokens, %)
+const ROW_COLUMNS: usize = 39;
+
+#[derive(Serialize)]
+struct StatsReport {
+    runs: usize,
+    input_bytes: u64,
+    output_bytes: u64,
+    input_tokens: usize,
+    output_tokens: usize,
+    saved_tokens: usize,
+    savings_pct: f64,
+    commands: Vec<CommandStats>,
+}
+
+/// Show raw vs filtered output volume per command, and the commands that
+/// still emit the most output after filtering.
+pub fn run(top: usize, format: &str, _verbose: u8) -> Result<()> {
+    let tracker = Tracker::new().context("Failed to initialize tracking database")?;
+    let commands = tracker
+        .get_command_stats()
+        .context("Failed to load command stats from database")?;
+
+    let report = build_report(commands);
+
+    match format {
+        "json" => println!("{}", serde_json::to_string_pretty(&report)?),
+        _ => print!("{}", format_report(&report, top, command_width())),
+    }
+
+    Ok(())
+}
+
+/// Dump the tracking history for dashboards: one row per record (`csv`,
+/// `json`) or cumulative per-command OTLP metrics (`otlp`), printed or, with
+/// `endpoint`, POSTed to an OpenTelemetry collector's `/v1/metrics`.
+pub fn export(
+    format: &str,
+    since_days: Option<i64>,
+    endpoint: Option<&str>,
+    verbose: u8,
+) -> Result<()> {
+    if endpoint.is_some() && format != "otlp" {
+        anyhow::bail!("--endpoint needs --format otlp");
+    }
+    let tracker = Tracker::new().context("Failed to initialize tracking database")?;
+    let since = since_days.map(|days| Utc::now() - Duration::days(days));
+    let records = tracker
+        .get_records(since)
+        .context("Failed to load tracking history from database")?;
+    if verbose > 0 {
+        eprintln!("stats export: {} records", records.len());
+    }
+
+    let output = match format {
+        "csv" => export_csv(&records),
+        "json" => serde_json::to_string_pretty(&records)? + "\n",
+        "otlp" => serde_json::to_string(&export_otlp(&records, &resource(), Utc::now()))? + "\n",
+        other => anyhow::bail!("Unknown export format '{}' (csv, json, otlp)", other),
+    };
+
+    match endpoint {
+        Some(endpoint) => send_otlp(endpoint, &output),
+        None => {
+            print!("{}", output);
+            Ok(())
+        }
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn export_csv(records: &[ExportRecord]) -> String {
+    let mut out = String::from(
+        "timestamp,original_cmd,rtk_cmd,runs,input_tokens,output_tokens,saved_tokens,savings_pct,exec_time_ms,input_bytes,output_bytes\n",
+    );
+    for r in records {
+        out.push_str(&format!(
+            "{},{},{},{},{},{},{},{:.2},{},{},{}\n",
+            r.timestamp,
+            csv_field(&r.original_cmd),
+            csv_field(&r.rtk_cmd),
+            r.runs,
+            r.input_tokens,
+            r.output_tokens,
+            r.saved_tokens,
+            r.savings_pct,
+            r.exec_time_ms,
+            r.input_bytes,
+            r.output_bytes
+        ));
+    }
+    out
+}
+
+/// Who the metrics come from, so a collector can tell developers apart
+fn resource() -> Vec<(&'static str, String)> {
+    let mut attributes = vec![("service.name", "rtk".to_string())];
+    if let Some(host) = std::env::var("HOSTNAME")
+        .ok()
+        .or_else(|| std::env::var("COMPUTERNAME").ok())
+    {
+        attributes.push(("host.name", host));
+    }
+    if let Some(user) = std::env::var("USER")
+        .ok()
+        .or_else(|| std::env::var("USERNAME").ok())
+    {
+        attributes.push(("user.name", user));
+    }
+    attributes
+}
+
+fn unix_nanos(time: DateTime<Utc>) -> String {
+    time.timestamp_nanos_opt().unwrap_or(0).to_string()
+}
+
+/// OTLP/JSON `ExportMetricsServiceRequest`: monotonic cumulative sums per
+/// command group, from the oldest exported record to `now`
+fn export_otlp(records: &[ExportRecord], resource: &[(&str, String)], now: DateTime<Utc>) -> Value {
+    // group -> (runs, input, output, saved)
+    let mut groups: BTreeMap<String, [usize; 4]> = BTreeMap::new();
+    for r in records {
+        let totals = groups.entry(command_group(&r.rtk_cmd)).or_default();
+        totals[0] += r.runs;
+        totals[1] += r.input_tokens;
+        totals[2] += r.output_tokens;
+        totals[3] += r.saved_tokens;
+    }
+    let start = records
+        .first()
+        .and_then(|r| DateTime::parse_from_rfc3339(&r.timestamp).ok())
+        .map_or(now, |t| t.with_timezone(&Utc));
+
+    let attribute = |key: &str, value: &str| json!({"key": key, "value": {"stringValue": value}});
+    let metric = |name: &str, description: &str, unit: &str, index: usize| {
+        let points: Vec<Value> = groups
+            .iter()
+            .map(|(group, totals)| {
+                json!({
+                    "attributes": [attribute("rtk.command", group)],
+                    "startTimeUnixNano": unix_nanos(start),
+                    "timeUnixNano": unix_nanos(now),
+                    "asInt": totals[index].to_string(),
+                })
+            })
+            .collect();
+        json!({
+            "name": name,
+            "description": description,
+            "unit": unit,
+            // AGGREGATION_TEMPORALITY_CUMULATIVE
+            "sum": {"aggregationTemporality": 2, "isMonotonic": true, "dataPoints": points},
+        })
+    };
+
+    json!({
+        "resourceMetrics": [{
+            "resource": {
+                "attributes": resource.iter().map(|(k, v)| attribute(k, v)).collect::<Vec<_>>(),
+            },
+            "scopeMetrics": [{
+                "scope": {"name": "rtk", "version": env!("CARGO_PKG_VERSION")},
+                "metrics": [
+                    metric("rtk.commands", "Commands run through rtk", "{command}", 0),
+                    metric("rtk.tokens.input", "Estimated tokens of raw output", "{token}", 1),
+                    metric("rtk.tokens.output", "Estimated tokens of filtered output", "{token}", 2),
+                    metric("rtk.tokens.saved", "Estimated tokens saved", "{token}", 3),
+                ],
+            }],
+        }],
+    })
+}
+
+/// POST the payload with curl rather than pulling in an HTTP client
+fn send_otlp(endpoint: &str, payload: &str) -> Result<()> {
+    let url = format!("{}/v1/metrics", endpoint.trim_end_matches('/'));
+    let mut child = Command::new("curl")
+        .args(["-sS", "--fail", "-X", "POST"])
+        .args(["-H", "Content-Type: application/json"])
+        .args(["--data-binary", "@-", &url])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()
+        .context("Failed to run curl (needed for --endpoint)")?;
+    child
+        .stdin
+        .take()
+        .context("Failed to open curl stdin")?
+        .write_all(payload.as_bytes())?;
+    let status = child.wait()?;
+    if !status.success() {
+        anyhow::bail!("Sending metrics to {} failed", url);
+    }
+    println!("✓ metrics sent to {}", url);
+    Ok(())
+}
+
+fn build_report(commands: Vec<CommandStats>) -> StatsReport {
+    let input_tokens: usize = commands.iter().map(|c| c.input_tokens).sum();
+    let saved_tokens: usize = commands.iter().map(|c| c.saved_tokens).sum();
+
+    StatsReport {
+        runs: commands.iter().map(|c| c.runs).sum(),
+        input_bytes: commands.iter().map(|c| c.input_bytes).sum(),
+        output_bytes: commands.iter().map(|c| c.output_bytes).sum(),
+        input_tokens,
+        output_tokens: commands.iter().map(|c| c.output_tokens).sum(),
+        saved_tokens,
+        savings_pct: if input_tokens > 0 {
+            (saved_tokens as f64 / input_tokens as f64) * 100.0
+        } else {
+            0.0
+        },
+        commands,
+    }
+}
+
+/// `cmd_width` is the width of the command column
+fn format_report(report: &StatsReport, top: usize, cmd_width: usize) -> String {
+    if report.runs == 0 {
+        return "No tracking data yet.\nRun some rtk commands to start tracking savings.\n"
+            .to_string();
+    }
+
+    let mut out = String::new();
+    out.push_str(&format!(
+        "RTK Stats ({} runs, {} commands)\n",
+        report.runs,
+        report.commands.len()
+    ));
+    out.push_str(&format!("{}\n", "═".repeat(cmd_width + ROW_COLUMNS)));
+    out.push_str(&format!(
+        "Raw output:      {:>8}  ≈{} tokens\n",
+        format_bytes(report.input_bytes),
+        format_tokens(report.input_tokens)
+    ));
+    out.push_str(&format!(
+        "Filtered output: {:>8}  ≈{} tokens\n",
+        format_bytes(report.output_bytes),
+        format_tokens(report.output_tokens)
+    ));
+    out.push_str(&format!(
+        "Saved:           {:>8}  ≈{} tokens ({:.1}%)\n\n",
+        format_bytes(report.input_bytes.saturating_sub(report.output_bytes)),
+        format_tokens(report.saved_tokens),
+        report.savings_pct
+    ));
+
+    out.push_str(&format!(
+        "{:<cmd_width$} {:>5} {:>8} {:>8} {:>7} {:>6}\n",
+        "Command",
+        "Runs",
+        "Raw",
+        "Out",
+        "Saved",
+        "Save%",
+        cmd_width = cmd_width
+    ));
+    out.push_str(&format!("{}\n", "─".repeat(cmd_width + ROW_COLUMNS)));
+    for cmd in report.commands.iter().take(top) {
+        out.push_str(&format_row(cmd, cmd_width));
+    }
+    if report.commands.len() > top {
+        out.push_str(&format!("... +{} more\n", report.commands.len() - top));
+    }
+
+    // Offenders: most tokens still reaching the context after filtering
+    let mut offenders: Vec<&CommandStats> = report
+        .commands
+        .iter()
+        .filter(|c| c.output_tokens > 0)
+        .collect();
+    offenders.sort_by_key(|c| std::cmp::Reverse(c.output_tokens));
+
+    if !offenders.is_empty() {
+        out.push_str("\nTop offenders (most output after filtering)\n");
+        out.push_str(&format!("{}\n", "─".repeat(cmd_width + ROW_COLUMNS)));
+        for cmd in offenders.iter().take(OFFENDERS) {
+            out.push_str(&format_row(cmd, cmd_width));
+        }
+    }
+
+    out
+}
+
+/// Command column sized to the terminal, within bounds that keep the
+/// command readable and the table compact
+fn command_width() -> usize {
+    layout::line_width(COMMAND_WIDTH, ROW_COLUMNS).clamp(12, 60)
+}
+
+fn format_row(cmd: &CommandStats, cmd_width: usize) -> String {
+    format!(
+        "{:<cmd_width$} {:>5} {:>8} {:>8} {:>7} {:>5.1}%\n",
+        truncate(&cmd.command, cmd_width),
+        cmd.runs,
+        format_bytes(cmd.input_bytes),
+        format_bytes(cmd.output_bytes),
+        format_tokens(cmd.saved_tokens),
+        cmd.savings_pct,
+        cmd_width = cmd_width
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(command: &str, runs: usize, input: usize, output: usize) -> CommandStats {
+        CommandStats {
+            command: command.to_string(),
+            runs,
+            input_bytes: (input * 4) as u64,
+            output_bytes: (output * 4) as u64,
+            input_tokens: input,
+            output_tokens: output,
+            saved_tokens: input - output,
+            savings_pct: (input - output) as f64 / input as f64 * 100.0,
+        }
+    }
+
+    #[test]
+    fn test_build_report_totals() {
+        let report = build_report(vec![
+            stats("rtk git log", 3, 1000, 100),
+            stats("rtk read", 2, 1000, 900),
+        ]);
+        assert_eq!(report.runs, 5);
+        assert_eq!(report.input_tokens, 2000);
+        assert_eq!(report.saved_tokens, 1000);
+        assert_eq!(report.savings_pct, 50.0);
+    }
+
+    #[test]
+    fn test_format_report_offenders() {
+        let report = build_report(vec![
+            stats("rtk git log", 3, 1000, 100),
+            stats("rtk read", 2, 1000, 900),
+        ]);
+        let out = format_report(&report, 10, COMMAND_WIDTH);
+        assert!(out.contains("RTK Stats (5 runs, 2 commands)"));
+        let offenders = out.split("Top offenders").nth(1).unwrap();
+        let first = offenders.lines().nth(2).unwrap();
+        assert!(first.starts_with("rtk read"));
+    }
+
+    #[test]
+    fn test_format_report_top_limit() {
+        let report = build_report(vec![
+            stats("rtk a", 1, 10, 1),
+            stats("rtk b", 1, 10, 1),
+            stats("rtk c", 1, 10, 1),
+        ]);
+        let out = format_report(&report, 2, COMMAND_WIDTH);
+        assert!(out.contains("... +1 more"));
+    }
+
+    #[test]
+    fn test_format_report_command_width() {
+        let long = "rtk grep 'fn main' src --max-len 120";
+        let report = build_report(vec![stats(long, 1, 10, 1)]);
+        let narrow = format_report(&report, 10, COMMAND_WIDTH);
+        assert!(narrow.contains("rtk grep 'fn main' sr..."));
+        let wide = format_report(&report, 10, 40);
+        assert!(wide.contains(long));
+        assert!(wide.contains(&"─".repeat(40 + ROW_COLUMNS)));
+    }
+
+    fn record(timestamp: &str, rtk_cmd: &str, input: usize, output: usize) -> ExportRecord {
+        ExportRecord {
+            timestamp: timestamp.to_string(),
+            original_cmd: rtk_cmd.trim_start_matches("rtk ").to_string(),
+            rtk_cmd: rtk_cmd.to_string(),
+            runs: 1,
+            input_tokens: input,
+            output_tokens: output,
+            saved_tokens: input - output,
+            savings_pct: (input - output) as f64 / input as f64 * 100.0,
+            exec_time_ms: 12,
+            input_bytes: (input * 4) as u64,
+            output_bytes: (output * 4) as u64,
+        }
+    }
+
+    #[test]
+    fn test_export_csv_quotes_commands() {
+        let out = export_csv(&[record(
+            "2026-03-01T10:00:00+00:00",
+            "rtk grep \"a,b\" src",
+            100,
+            25,
+        )]);
+        let row = out.lines().nth(1).unwrap();
+        assert_eq!(
+            row,
+            "2026-03-01T10:00:00+00:00,\"grep \"\"a,b\"\" src\",\"rtk grep \"\"a,b\"\" src\",1,100,25,75,75.00,12,400,100"
+        );
+    }
+
+    #[test]
+    fn test_export_otlp_sums_per_command_group() {
+        let records = vec![
+            record("2026-03-01T10:00:00+00:00", "rtk git log -5", 100, 20),
+            record("2026-03-02T10:00:00+00:00", "rtk git log --all", 300, 60),
+            record("2026-03-02T11:00:00+00:00", "rtk ls", 50, 10),
+        ];
+        let now = DateTime::parse_from_rfc3339("2026-03-03T00:00:00+00:00")
+            .unwrap()
+            .with_timezone(&Utc);
+        let otlp = export_otlp(&records, &[("service.name", "rtk".to_string())], now);
+
+        let scope = &otlp["resourceMetrics"][0]["scopeMetrics"][0];
+        let saved = &scope["metrics"][3];
+        assert_eq!(saved["name"], "rtk.tokens.saved");
+        assert_eq!(saved["sum"]["aggregationTemporality"], 2);
+        let points = saved["sum"]["dataPoints"].as_array().unwrap();
+        assert_eq!(points.len(), 2);
+        assert_eq!(
+            points[0]["attributes"][0]["value"]["stringValue"],
+            "rtk git log"
+        );
+        assert_eq!(points[0]["asInt"], "320");
+        assert_eq!(points[0]["startTimeUnixNano"], "1772359200000000000");
+        assert_eq!(scope["metrics"][0]["sum"]["dataPoints"][0]["asInt"], "2");
+    }
+
+    #[test]
+    fn test_format_report_empty() {
+        let out = format_report(&build_report(Vec::new()), 10, COMMAND_WIDTH);
+        assert!(out.starts_with("No tracking data yet."));
+    }
+}
@@ -27,8 +27,7 @@ pub fn run(command: &str, verbose: u8) -> Result<()> {
     }
     .context("Failed to execute command")?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    let (stdout, stderr) = crate::output::captured(&output);
     let raw = format!("{}\n{}", stdout, stderr);
 
     let summary = summarize_output(&raw, command, output.status.success());
@@ -0,0 +1,355 @@
+//! `rtk symbols`: where a name is defined across a project, with the kind
+//! of definition, instead of every line that mentions it.
+//!
+//! Definitions come from the same tree-sitter parse as `rtk outline`.
+
+use crate::filter::Language;
+use crate::outline_cmd;
+use crate::tracking;
+use crate::walk;
+use anyhow::Result;
+use ignore::WalkBuilder;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::path::Path;
+
+/// Files larger than this are skipped (bundles, generated code)
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+
+lazy_static! {
+    static ref IMPL_RE: Regex =
+        Regex::new(r"^(?:unsafe\s+)?impl\b(?:<[^>]*>)?\s+(?:\S+\s+for\s+)?(?:[\w]+::)*(\w+)").unwrap();
+    static ref MACRO_RE: Regex = Regex::new(r"\bmacro_rules!\s*(\w+)").unwrap();
+    static ref GO_METHOD_RE: Regex = Regex::new(r"^func\s+\([^)]*\)\s*(\w+)").unwrap();
+    static ref GO_TYPE_RE: Regex = Regex::new(r"^type\s+(\w+)\s+(struct|interface)?").unwrap();
+    static ref KEYWORD_RE: Regex = Regex::new(
+        r"\b(fn|struct|enum|trait|mod|type|union|class|interface|function\*?|def|func|namespace)\s+([A-Za-z_$][\w$]*)"
+    )
+    .unwrap();
+    static ref BINDING_RE: Regex = Regex::new(r"^(?:export\s+)?(?:const|let)\s+([\w$]+)").unwrap();
+    static ref METHOD_RE: Regex = Regex::new(r"([#\w$]+)\s*(?:<[^>]*>)?\(").unwrap();
+}
+
+/// One definition of the index
+#[derive(Debug, PartialEq)]
+struct Symbol {
+    kind: &'static str,
+    name: String,
+    path: String,
+    line: usize,
+    signature: String,
+}
+
+pub fn run(
+    name: &str,
+    path: &str,
+    kind: Option<&str>,
+    max_results: usize,
+    verbose: u8,
+) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    let mut walker = WalkBuilder::new(path);
+    walker
+        .hidden(true)
+        .git_ignore(true)
+        .git_global(true)
+        .git_exclude(true);
+
+    // Files are read and indexed on the walker threads
+    let walked = walk::walk(&walker, |entry| {
+        let file = entry.path();
+        let lang = file
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(Language::from_extension)
+            .unwrap_or(Language::Unknown);
+        let small = entry
+            .metadata()
+            .is_ok_and(|m| m.is_file() && m.len() <= MAX_FILE_BYTES);
+        if !outline_cmd::is_supported(lang) || !small {
+            return None;
+        }
+        let content = std::fs::read_to_string(file).ok()?;
+        let display = display_path(file);
+        // What `grep -rn name` would have printed, for the savings estimate
+        let mut raw = String::new();
+        for (i, line) in content.lines().enumerate() {
+            if line.contains(name) {
+                raw.push_str(&format!("{}:{}:{}\n", display, i + 1, line));
+            }
+        }
+        Some((index(&display, &content, lang), raw))
+    });
+
+    let scanned = walked.items.len();
+    let mut symbols = Vec::new();
+    let mut raw = String::new();
+    for (found, lines) in walked.items {
+        symbols.extend(found);
+        raw.push_str(&lines);
+    }
+    if verbose > 0 {
+        eprintln!(
+            "symbols: {} definitions in {} files",
+            symbols.len(),
+            scanned
+        );
+    }
+
+    symbols.sort_by(|a, b| a.path.cmp(&b.path).then(a.line.cmp(&b.line)));
+    let (found, exact) = lookup(&symbols, name, kind);
+    let output = format_symbols(name, &found, exact, max_results);
+    println!("{}", output);
+
+    timer.track(
+        &format!("grep -rn '{}' {}", name, path),
+        "rtk symbols",
+        &raw,
+        &output,
+    );
+    Ok(())
+}
+
+fn display_path(path: &Path) -> String {
+    let path = path.to_string_lossy();
+    path.strip_prefix("./").unwrap_or(&path).to_string()
+}
+
+/// Every definition of `content` with its kind and name
+fn index(path: &str, content: &str, lang: Language) -> Vec<Symbol> {
+    outline_cmd::outline(content, lang)
+        .into_iter()
+        .filter_map(|item| {
+            let (kind, name) = classify(&item.signature, lang)?;
+            Some(Symbol {
+                kind,
+                name,
+                path: path.to_string(),
+                line: item.line,
+                signature: item.signature,
+            })
+        })
+        .collect()
+}
+
+/// Innermost definition around each of `lines` (1-based) of `content`, as
+/// `fn name`; `None` at the top level
+pub(crate) fn enclosing(content: &str, lang: Language, lines: &[usize]) -> Vec<Option<String>> {
+    // In line order: a later definition containing a line is nested in the
+    // earlier ones
+    let scopes: Vec<(usize, usize, String)> = outline_cmd::outline(content, lang)
+        .into_iter()
+        .filter_map(|item| {
+            let (kind, name) = classify(&item.signature, lang)?;
+            Some((item.line, item.end, format!("{} {}", kind, name)))
+        })
+        .collect();
+    lines
+        .iter()
+        .map(|&line| {
+            scopes
+                .iter()
+                .rev()
+                .find(|(start, end, _)| (*start..=*end).contains(&line))
+                .map(|(_, _, label)| label.clone())
+        })
+        .collect()
+}
+
+/// Kind and name of a declaration signature
+fn classify(signature: &str, lang: Language) -> Option<(&'static str, String)> {
+    if lang == Language::Go {
+        if let Some(caps) = GO_METHOD_RE.captures(signature) {
+            return Some(("method", caps[1].to_string()));
+        }
+        if let Some(caps) = GO_TYPE_RE.captures(signature) {
+            let kind = match caps.get(2).map(|m| m.as_str()) {
+                Some("struct") => "struct",
+                Some("interface") => "interface",
+                _ => "type",
+            };
+            return Some((kind, caps[1].to_string()));
+        }
+    }
+    if let Some(caps) = IMPL_RE.captures(signature) {
+        return Some(("impl", caps[1].to_string()));
+    }
+    if let Some(caps) = MACRO_RE.captures(signature) {
+        return Some(("macro", caps[1].to_string()));
+    }
+    if let Some(caps) = KEYWORD_RE.captures(signature) {
+        let kind = match &caps[1] {
+            "fn" | "function" | "function*" | "def" | "func" => "fn",
+            "struct" => "struct",
+            "enum" => "enum",
+            "trait" => "trait",
+            "mod" => "mod",
+            "type" => "type",
+            "union" => "union",
+            "class" => "class",
+            "interface" => "interface",
+            _ => "namespace",
+        };
+        return Some((kind, caps[2].to_string()));
+    }
+    if let Some(caps) = BINDING_RE.captures(signature) {
+        return Some(("fn", caps[1].to_string()));
+    }
+    METHOD_RE
+        .captures(signature)
+        .map(|caps| ("method", caps[1].to_string()))
+}
+
+/// Definitions named exactly `name`; when there are none, names containing
+/// it, ignoring case. The flag tells which of the two was returned.
+fn lookup<'a>(symbols: &'a [Symbol], name: &str, kind: Option<&str>) -> (Vec<&'a Symbol>, bool) {
+    let of_kind = |s: &&Symbol| kind.is_none_or(|k| s.kind == k);
+    let exact: Vec<&Symbol> = symbols
+        .iter()
+        .filter(of_kind)
+        .filter(|s| s.name == name)
+        .collect();
+    if !exact.is_empty() {
+        return (exact, true);
+    }
+    let lower = name.to_lowercase();
+    let partial = symbols
+        .iter()
+        .filter(of_kind)
+        .filter(|s| s.name.to_lowercase().contains(&lower))
+        .collect();
+    (partial, false)
+}
+
+fn format_symbols(name: &str, found: &[&Symbol], exact: bool, max_results: usize) -> String {
+    if found.is_empty() {
+        return format!("🔎 0 definitions of '{}'", name);
+    }
+    let mut out = format!(
+        "🔎 {} {} of '{}':\n",
+        found.len(),
+        if exact {
+            "definitions"
+        } else {
+            "partial matches"
+        },
+        name
+    );
+    let width = found
+        .iter()
+        .take(max_results)
+        .map(|s| s.path.len() + s.line.to_string().len() + 1)
+        .max()
+        .unwrap_or(0);
+    for symbol in found.iter().take(max_results) {
+        out.push_str(&format!(
+            "  {:<9} {:<width$}  {}\n",
+            symbol.kind,
+            format!("{}:{}", symbol.path, symbol.line),
+            symbol.signature,
+            width = width
+        ));
+    }
+    if found.len() > max_results {
+        out.push_str(&format!("  ... +{}\n", found.len() - max_results));
+    }
+    out.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        let rust = |s| classify(s, Language::Rust);
+        assert_eq!(rust("pub struct Config"), Some(("struct", "Config".into())));
+        assert_eq!(
+            rust("impl<T: Clone> fmt::Display for Wrapper<T>"),
+            Some(("impl", "Wrapper".into()))
+        );
+        assert_eq!(
+            rust("pub(crate) async fn load(&self)"),
+            Some(("fn", "load".into()))
+        );
+        assert_eq!(rust("macro_rules! hello"), Some(("macro", "hello".into())));
+        assert_eq!(
+            rust("fn wrap(x: impl Into<String>)"),
+            Some(("fn", "wrap".into()))
+        );
+
+        let ts = |s| classify(s, Language::TypeScript);
+        assert_eq!(
+            ts("export const handler = async (req: Request) =>"),
+            Some(("fn", "handler".into()))
+        );
+        assert_eq!(
+            ts("async load(id: string): Promise<User>"),
+            Some(("method", "load".into()))
+        );
+
+        let go = |s| classify(s, Language::Go);
+        assert_eq!(
+            go("func (s *Server) Run(ctx context.Context) error"),
+            Some(("method", "Run".into()))
+        );
+        assert_eq!(go("type Server struct"), Some(("struct", "Server".into())));
+        assert_eq!(
+            classify("def get(self, id) -> User", Language::Python),
+            Some(("fn", "get".into()))
+        );
+    }
+
+    #[test]
+    fn test_lookup_exact_then_partial() {
+        let content =
+            "pub struct Config {}\nimpl Config {\n    fn load() {}\n}\nfn config_path() {}\n";
+        let symbols = index("src/config.rs", content, Language::Rust);
+
+        let (found, exact) = lookup(&symbols, "Config", None);
+        assert!(exact);
+        assert_eq!(found.len(), 2);
+        assert_eq!((found[1].kind, found[1].line), ("impl", 2));
+
+        let (found, _) = lookup(&symbols, "Config", Some("struct"));
+        assert_eq!(found.len(), 1);
+
+        let (found, exact) = lookup(&symbols, "config_", None);
+        assert!(!exact);
+        assert_eq!(found[0].name, "config_path");
+    }
+
+    #[test]
+    fn test_enclosing() {
+        let content = "use std::fs;\n\nimpl Config {\n    fn load() {\n        read();\n    }\n\n    fn save() {}\n}\n\nfn main() {\n    load();\n}\n";
+        assert_eq!(
+            enclosing(content, Language::Rust, &[1, 5, 7, 8, 12]),
+            vec![
+                None,
+                Some("fn load".to_string()),
+                Some("impl Config".to_string()),
+                Some("fn save".to_string()),
+                Some("fn main".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_format_symbols() {
+        let symbols = index(
+            "src/a.rs",
+            "pub struct Config {}\nimpl Config {}\n",
+            Language::Rust,
+        );
+        let (found, exact) = lookup(&symbols, "Config", None);
+        assert_eq!(
+            format_symbols("Config", &found, exact, 1),
+            "🔎 2 definitions of 'Config':\n  struct    src/a.rs:1  pub struct Config\n  ... +1"
+        );
+        assert_eq!(
+            format_symbols("Nope", &[], true, 10),
+            "🔎 0 definitions of 'Nope'"
+        );
+    }
+}
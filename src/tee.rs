@@ -1,5 +1,6 @@
 use crate::config::Config;
 use std::path::PathBuf;
+use std::sync::OnceLock;
 
 /// Minimum output size to tee (smaller outputs don't need recovery)
 const MIN_TEE_SIZE: usize = 500;
@@ -145,8 +146,8 @@ pub fn tee_raw(raw: &str, command_slug: &str, exit_code: i32) -> Option<PathBuf>
         return None;
     }
 
-    let config = Config::load().ok()?;
-    let tee_dir = get_tee_dir(&config)?;
+    let config = Config::get();
+    let tee_dir = get_tee_dir(config)?;
 
     let tee_dir = should_tee(&config.tee, raw.len(), exit_code, Some(tee_dir))?;
 
@@ -177,10 +178,83 @@ fn format_hint(path: &std::path::Path) -> String {
 /// Convenience: tee + format hint in one call.
 /// Returns hint string if file was written, None if skipped.
 pub fn tee_and_hint(raw: &str, command_slug: &str, exit_code: i32) -> Option<String> {
+    // `--out`/`--tee` write the same output once the command is tracked
+    if requested().is_some() {
+        return None;
+    }
     let path = tee_raw(raw, command_slug, exit_code)?;
     Some(format_hint(&path))
 }
 
+/// Where `--out FILE` / `--tee` send this invocation's raw output
+#[derive(Debug, Clone, PartialEq)]
+pub enum Requested {
+    /// `--out FILE`
+    File(PathBuf),
+    /// `--tee`: a new file in the tee directory
+    TeeDir,
+}
+
+static REQUESTED: OnceLock<Option<(Requested, bool)>> = OnceLock::new();
+
+/// Resolve `--out`/`--tee` once, at startup (`--out` wins). With
+/// `hint_to_stderr` (JSON output) the hint doesn't break the document.
+pub fn request(out: Option<PathBuf>, tee: bool, hint_to_stderr: bool) {
+    let requested = match (out, tee) {
+        (Some(path), _) => Some(Requested::File(path)),
+        (None, true) => Some(Requested::TeeDir),
+        (None, false) => None,
+    };
+    let _ = REQUESTED.set(requested.map(|r| (r, hint_to_stderr)));
+}
+
+fn requested() -> Option<&'static (Requested, bool)> {
+    REQUESTED.get().and_then(Option::as_ref)
+}
+
+/// `--out`/`--tee`: write the whole raw output (no size floor, no
+/// truncation, whatever the exit code) and print where it went after the
+/// summary. Called when the command is tracked, so every wrapper gets it.
+pub fn write_requested(raw: &str, rtk_cmd: &str) {
+    let Some((requested, hint_to_stderr)) = requested() else {
+        return;
+    };
+    let written = write_output(requested, raw, rtk_cmd, Config::get());
+    match written {
+        Ok(path) if *hint_to_stderr => eprintln!("{}", format_hint(&path)),
+        Ok(path) => println!("{}", format_hint(&path)),
+        Err(e) => eprintln!("rtk: {}", e),
+    }
+}
+
+fn write_output(
+    requested: &Requested,
+    raw: &str,
+    rtk_cmd: &str,
+    config: &Config,
+) -> Result<PathBuf, String> {
+    match requested {
+        Requested::File(path) => std::fs::write(path, raw)
+            .map(|_| path.clone())
+            .map_err(|e| format!("Cannot write {}: {}", path.display(), e)),
+        Requested::TeeDir => {
+            let slug = crate::tracking::command_group(rtk_cmd);
+            let slug = slug.strip_prefix("rtk ").unwrap_or(&slug);
+            get_tee_dir(config)
+                .and_then(|dir| write_tee_file(raw, slug, &dir, usize::MAX, config.tee.max_files))
+                .ok_or_else(|| "Cannot write to the tee directory".to_string())
+        }
+    }
+}
+
+/// Streamed and passthrough output is never held in memory: say so rather
+/// than leave `--out`/`--tee` silently unwritten
+pub fn warn_not_captured() {
+    if requested().is_some() {
+        eprintln!("rtk: --out/--tee: this command streams its output, nothing was saved");
+    }
+}
+
 /// TeeMode controls when tee writes files.
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
@@ -390,6 +464,33 @@ directory = "/tmp/rtk-tee"
         assert_eq!(deserialized.max_files, 10);
     }
 
+    #[test]
+    fn test_write_requested_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let raw = "x".repeat(DEFAULT_MAX_FILE_SIZE + 10);
+        let config = Config {
+            tee: TeeConfig {
+                directory: Some(dir.path().to_path_buf()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let out = dir.path().join("raw.txt");
+        let written = write_output(&Requested::File(out.clone()), "short", "rtk ls", &config);
+        assert_eq!(written, Ok(out.clone()));
+        assert_eq!(fs::read_to_string(&out).unwrap(), "short");
+
+        // Whole output, named after the command group
+        let written = write_output(&Requested::TeeDir, &raw, "rtk git log -n 5", &config).unwrap();
+        let name = written.file_name().unwrap().to_string_lossy().into_owned();
+        assert!(name.ends_with("_git_log.log"), "{}", name);
+        assert_eq!(fs::read_to_string(&written).unwrap(), raw);
+
+        let missing = dir.path().join("no/such/dir/raw.txt");
+        assert!(write_output(&Requested::File(missing), "x", "rtk ls", &config).is_err());
+    }
+
     #[test]
     fn test_tee_mode_serde() {
         // Test all modes via JSON
@@ -0,0 +1,597 @@
+//! Generic test runner summary: runs any test command (cargo test, pytest,
+//! jest/vitest, go test) and reports counts, failed test names and, per
+//! failure, the first assertion line and stack frame. `--full <test>`
+//! prints one failure's complete output instead.
+
+use crate::delta;
+use crate::tracking;
+use crate::utils;
+use anyhow::{Context, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::process::{Command, Stdio};
+
+/// Failures listed before the rest are counted
+const MAX_FAILURES: usize = 10;
+
+lazy_static! {
+    static ref CARGO_RESULT: Regex =
+        Regex::new(r"test result: \w+\. (\d+) passed; (\d+) failed; (\d+) ignored").unwrap();
+    static ref COUNT: Regex =
+        Regex::new(r"(\d+) (passed|failed|skipped|errors?|ignored|todo)\b").unwrap();
+    static ref LOCATION: Regex = Regex::new(r"[\w./\\-]+\.\w+:\d+(:\d+)?").unwrap();
+    static ref ASSERTION: Regex =
+        Regex::new(r"(?i)(assert|expect|error|exception|panicked at '|not equal|mismatch|\bgot\b)")
+            .unwrap();
+    /// Rust 1.73+ panic header: the message is on the next line
+    static ref PANIC_HEADER: Regex = Regex::new(r"panicked at [^']+:\d+:\d+:$").unwrap();
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Framework {
+    Cargo,
+    Pytest,
+    Jest,
+    Go,
+    Unknown,
+}
+
+impl Framework {
+    fn name(&self) -> &'static str {
+        match self {
+            Framework::Cargo => "cargo",
+            Framework::Pytest => "pytest",
+            Framework::Jest => "jest",
+            Framework::Go => "go",
+            Framework::Unknown => "tests",
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Failure {
+    name: String,
+    /// Full failure output, as printed by the runner
+    detail: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+struct TestReport {
+    passed: usize,
+    failed: usize,
+    skipped: usize,
+    failures: Vec<Failure>,
+}
+
+/// Run tests and show a compact report (or one failure in full)
+pub fn run(command: &str, full: Option<&str>, verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    if verbose > 0 {
+        eprintln!("Running tests: {}", command);
+    }
+
+    let output = if cfg!(target_os = "windows") {
+        Command::new("cmd")
+            .args(["/C", command])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .output()
+    } else {
+        Command::new("sh")
+            .args(["-c", command])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .output()
+    }
+    .context("Failed to execute test command")?;
+
+    let (stdout, stderr) = crate::output::captured(&output);
+    let raw = format!("{}\n{}", stdout, stderr);
+
+    let exit_code = utils::exit_code(output.status);
+
+    let framework = detect_framework(command, &raw);
+    let report = parse_report(framework, &raw);
+    let summary = match full {
+        Some(name) => format_full(&report, name),
+        // A failed run without named failures (build error, crash) can't be
+        // compared with the last one
+        None if framework == Framework::Unknown
+            || (exit_code != 0 && report.failures.is_empty()) =>
+        {
+            format_report(framework, &report, &raw, command, exit_code)
+        }
+        None => {
+            let failing: Vec<String> = report.failures.iter().map(|f| f.name.clone()).collect();
+            delta::annotate(
+                format_report(framework, &report, &raw, command, exit_code),
+                &format!("test {}", command),
+                &failing,
+            )
+        }
+    };
+
+    if let Some(hint) = crate::tee::tee_and_hint(&raw, "test", exit_code) {
+        println!("{}\n{}", summary, hint);
+    } else {
+        println!("{}", summary);
+    }
+    timer.track(command, "rtk test", &raw, &summary);
+
+    utils::exit_with(command, exit_code, verbose);
+    Ok(())
+}
+
+fn detect_framework(command: &str, output: &str) -> Framework {
+    if command.contains("cargo test") || output.contains("test result:") {
+        Framework::Cargo
+    } else if command.contains("pytest") || output.contains("short test summary") {
+        Framework::Pytest
+    } else if ["jest", "vitest", "npm test", "yarn test", "pnpm test"]
+        .iter()
+        .any(|c| command.contains(c))
+        || output.contains("Test Suites:")
+    {
+        Framework::Jest
+    } else if command.contains("go test") || output.contains("--- FAIL:") {
+        Framework::Go
+    } else {
+        Framework::Unknown
+    }
+}
+
+fn parse_report(framework: Framework, output: &str) -> TestReport {
+    match framework {
+        Framework::Cargo => parse_cargo(output),
+        Framework::Pytest => parse_pytest(output),
+        Framework::Jest => parse_jest(output),
+        Framework::Go => parse_go(output),
+        Framework::Unknown => TestReport::default(),
+    }
+}
+
+/// Sum the `N passed` / `N failed` / ... counts on `line`
+fn add_counts(report: &mut TestReport, line: &str) {
+    for caps in COUNT.captures_iter(line) {
+        let n: usize = caps[1].parse().unwrap_or(0);
+        match &caps[2] {
+            "passed" => report.passed += n,
+            "failed" | "error" | "errors" => report.failed += n,
+            _ => report.skipped += n,
+        }
+    }
+}
+
+/// Split output into blocks starting at lines where `header` returns a
+/// test name; a block ends at the next header or where `end` matches
+fn collect_blocks(
+    output: &str,
+    header: impl Fn(&str) -> Option<String>,
+    end: impl Fn(&str) -> bool,
+) -> Vec<Failure> {
+    let mut failures: Vec<Failure> = Vec::new();
+    let mut current: Option<Failure> = None;
+
+    for line in output.lines() {
+        if let Some(name) = header(line) {
+            failures.extend(current.take());
+            current = Some(Failure {
+                name,
+                detail: Vec::new(),
+            });
+        } else if end(line) {
+            failures.extend(current.take());
+        } else if let Some(failure) = &mut current {
+            failure.detail.push(line.to_string());
+        }
+    }
+    failures.extend(current);
+
+    for failure in &mut failures {
+        while failure.detail.last().is_some_and(|l| l.trim().is_empty()) {
+            failure.detail.pop();
+        }
+        while failure.detail.first().is_some_and(|l| l.trim().is_empty()) {
+            failure.detail.remove(0);
+        }
+    }
+    failures
+}
+
+fn parse_cargo(output: &str) -> TestReport {
+    let mut report = TestReport::default();
+    for caps in CARGO_RESULT.captures_iter(output) {
+        report.passed += caps[1].parse::<usize>().unwrap_or(0);
+        report.failed += caps[2].parse::<usize>().unwrap_or(0);
+        report.skipped += caps[3].parse::<usize>().unwrap_or(0);
+    }
+
+    report.failures = collect_blocks(
+        output,
+        |line| {
+            line.strip_prefix("---- ")
+                .and_then(|rest| rest.strip_suffix(" stdout ----"))
+                .map(String::from)
+        },
+        |line| line == "failures:" || line.starts_with("test result:"),
+    );
+
+    // Failures without captured output still appear as `test x ... FAILED`
+    for line in output.lines() {
+        if let Some(name) = line
+            .strip_prefix("test ")
+            .and_then(|rest| rest.strip_suffix(" ... FAILED"))
+        {
+            if !report.failures.iter().any(|f| f.name == name) {
+                report.failures.push(Failure {
+                    name: name.to_string(),
+                    detail: Vec::new(),
+                });
+            }
+        }
+    }
+    report
+}
+
+fn parse_pytest(output: &str) -> TestReport {
+    let mut report = TestReport::default();
+    if let Some(line) = output
+        .lines()
+        .rev()
+        .find(|l| l.starts_with('=') && COUNT.is_match(l))
+    {
+        add_counts(&mut report, line);
+    }
+
+    // `____ test_name ____` headers inside the FAILURES section
+    report.failures = collect_blocks(
+        output,
+        |line| {
+            let trimmed = line.trim();
+            (trimmed.starts_with("___") && trimmed.ends_with("___") && trimmed.contains(' '))
+                .then(|| trimmed.trim_matches('_').trim().to_string())
+                .filter(|name| !name.is_empty())
+        },
+        |line| line.starts_with('='),
+    );
+    report
+}
+
+fn parse_jest(output: &str) -> TestReport {
+    let mut report = TestReport::default();
+    if let Some(line) = output
+        .lines()
+        .find(|l| l.trim_start().starts_with("Tests:"))
+    {
+        add_counts(&mut report, line);
+    }
+
+    report.failures = collect_blocks(
+        output,
+        |line| {
+            line.trim_start()
+                .strip_prefix("● ")
+                .map(|name| name.trim().to_string())
+        },
+        |line| {
+            line.starts_with("Test Suites:")
+                || line.starts_with("PASS ")
+                || line.starts_with("FAIL ")
+        },
+    );
+    report
+}
+
+fn parse_go(output: &str) -> TestReport {
+    let mut report = TestReport::default();
+    for line in output.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("--- PASS:") {
+            report.passed += 1;
+        } else if trimmed.starts_with("--- FAIL:") {
+            report.failed += 1;
+        } else if trimmed.starts_with("--- SKIP:") {
+            report.skipped += 1;
+        }
+    }
+
+    report.failures = collect_blocks(
+        output,
+        |line| {
+            line.trim_start()
+                .strip_prefix("--- FAIL: ")
+                .map(|rest| rest.split(" (").next().unwrap_or(rest).to_string())
+        },
+        |line| !line.starts_with(' ') && !line.starts_with('\t'),
+    );
+    report
+}
+
+/// First assertion/error line and first `file:line` frame of a failure
+fn key_lines(detail: &[String]) -> Vec<&str> {
+    let lines: Vec<&str> = detail
+        .iter()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty())
+        .collect();
+
+    // pytest marks the failing comparison with `E`; Rust prints the panic
+    // message on the line after `panicked at file:line:col:`
+    let assertion = lines
+        .iter()
+        .find(|l| l.starts_with("E "))
+        .map(|l| l.trim_start_matches('E').trim())
+        .or_else(|| {
+            let header = lines.iter().position(|l| PANIC_HEADER.is_match(l))?;
+            lines.get(header + 1).copied()
+        })
+        .or_else(|| {
+            lines
+                .iter()
+                .find(|l| ASSERTION.is_match(l) && !PANIC_HEADER.is_match(l))
+                .copied()
+        })
+        .or_else(|| lines.first().copied());
+    let frame = lines.iter().find(|l| LOCATION.is_match(l)).copied();
+
+    let mut key = Vec::new();
+    key.extend(assertion);
+    if let Some(frame) = frame {
+        if Some(frame) != assertion {
+            key.push(frame);
+        }
+    }
+    key
+}
+
+fn format_report(
+    framework: Framework,
+    report: &TestReport,
+    raw: &str,
+    command: &str,
+    exit_code: i32,
+) -> String {
+    let failed = report.failed.max(report.failures.len());
+    if report.passed + failed + report.skipped == 0 {
+        // Unknown runner: exit status and the tail of the output
+        let mut out = if exit_code == 0 {
+            "✓ Tests passed".to_string()
+        } else {
+            format!("❌ Tests failed (exit code {})", exit_code)
+        };
+        out.push_str("\n📊 OUTPUT (last 5 lines):\n");
+        let lines: Vec<&str> = raw.lines().filter(|l| !l.trim().is_empty()).collect();
+        for line in &lines[lines.len().saturating_sub(5)..] {
+            out.push_str(&format!("  {}\n", line));
+        }
+        return out.trim_end().to_string();
+    }
+
+    let mut counts = format!("{} passed", report.passed);
+    if failed > 0 {
+        counts = format!("{} failed, {}", failed, counts);
+    }
+    if report.skipped > 0 {
+        counts.push_str(&format!(", {} skipped", report.skipped));
+    }
+
+    if failed == 0 {
+        return format!("✓ {} ({})", counts, framework.name());
+    }
+
+    let mut out = format!("❌ {} ({})\n", counts, framework.name());
+    for failure in report.failures.iter().take(MAX_FAILURES) {
+        out.push_str(&format!("  {}\n", failure.name));
+        for line in key_lines(&failure.detail) {
+            out.push_str(&format!("    {}\n", line));
+        }
+    }
+    if report.failures.len() > MAX_FAILURES {
+        out.push_str(&format!(
+            "  ... +{} more failures\n",
+            report.failures.len() - MAX_FAILURES
+        ));
+    }
+    if let Some(first) = report.failures.first() {
+        out.push_str(&format!(
+            "Full output: rtk test --full '{}' {}",
+            first.name, command
+        ));
+    }
+    out.trim_end().to_string()
+}
+
+/// Complete output of the failure whose name contains `name`
+fn format_full(report: &TestReport, name: &str) -> String {
+    let failure = report
+        .failures
+        .iter()
+        .find(|f| f.name == name)
+        .or_else(|| report.failures.iter().find(|f| f.name.contains(name)));
+
+    match failure {
+        Some(failure) => format!("❌ {}\n{}", failure.name, failure.detail.join("\n")),
+        None => {
+            let names: Vec<&str> = report.failures.iter().map(|f| f.name.as_str()).collect();
+            if names.is_empty() {
+                format!("No failure matching '{}' (no failures)", name)
+            } else {
+                format!(
+                    "No failure matching '{}'. Failed: {}",
+                    name,
+                    names.join(", ")
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CARGO_OUTPUT: &str = r#"
+running 3 tests
+test utils::tests::ok_one ... ok
+test utils::tests::test_sum ... FAILED
+test utils::tests::test_parse ... FAILED
+
+failures:
+
+---- utils::tests::test_sum stdout ----
+
+thread 'utils::tests::test_sum' panicked at src/utils.rs:42:9:
+assertion `left == right` failed
+  left: 1
+ right: 2
+note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace
+
+---- utils::tests::test_parse stdout ----
+thread 'utils::tests::test_parse' panicked at src/parse.rs:7:5:
+called `Result::unwrap()` on an `Err` value: Invalid
+
+failures:
+    utils::tests::test_sum
+    utils::tests::test_parse
+
+test result: FAILED. 1 passed; 2 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.01s
+"#;
+
+    const PYTEST_OUTPUT: &str = r#"============================= test session starts ==============================
+collected 3 items
+
+tests/test_math.py .F.                                                   [100%]
+
+=================================== FAILURES ===================================
+___________________________________ test_add ___________________________________
+
+    def test_add():
+>       assert add(1, 2) == 4
+E       assert 3 == 4
+E        +  where 3 = add(1, 2)
+
+tests/test_math.py:5: AssertionError
+=========================== short test summary info ============================
+FAILED tests/test_math.py::test_add - assert 3 == 4
+========================= 1 failed, 2 passed, 1 skipped in 0.12s =========================
+"#;
+
+    const JEST_OUTPUT: &str = r#"FAIL src/calc.test.js
+  ● Calculator › adds numbers
+
+    expect(received).toBe(expected) // Object.is equality
+
+    Expected: 4
+    Received: 3
+
+    > 4 |   expect(add(1, 2)).toBe(4);
+
+      at Object.<anonymous> (src/calc.test.js:4:21)
+
+Test Suites: 1 failed, 1 total
+Tests:       1 failed, 5 passed, 6 total
+"#;
+
+    const GO_OUTPUT: &str = "=== RUN   TestAdd\n--- FAIL: TestAdd (0.00s)\n    calc_test.go:10: expected 4, got 3\n=== RUN   TestSub\n--- PASS: TestSub (0.00s)\nFAIL\nFAIL\texample.com/calc\t0.002s\n";
+
+    #[test]
+    fn test_detect_framework() {
+        assert_eq!(detect_framework("cargo test", ""), Framework::Cargo);
+        assert_eq!(
+            detect_framework("python -m pytest -x", ""),
+            Framework::Pytest
+        );
+        assert_eq!(detect_framework("npx vitest run", ""), Framework::Jest);
+        assert_eq!(detect_framework("make test", GO_OUTPUT), Framework::Go);
+        assert_eq!(detect_framework("make test", "ok"), Framework::Unknown);
+    }
+
+    #[test]
+    fn test_cargo_report() {
+        let report = parse_cargo(CARGO_OUTPUT);
+        assert_eq!((report.passed, report.failed), (1, 2));
+        let out = format_report(Framework::Cargo, &report, CARGO_OUTPUT, "cargo test", 101);
+        assert!(out.starts_with("❌ 2 failed, 1 passed (cargo)\n"));
+        assert!(out.contains(
+            "  utils::tests::test_sum\n    assertion `left == right` failed\n    thread 'utils::tests::test_sum' panicked at src/utils.rs:42:9:\n"
+        ));
+        assert!(out.contains("    called `Result::unwrap()` on an `Err` value: Invalid\n"));
+        assert!(out.ends_with("Full output: rtk test --full 'utils::tests::test_sum' cargo test"));
+        assert!(!out.contains("RUST_BACKTRACE"));
+    }
+
+    #[test]
+    fn test_pytest_report() {
+        let report = parse_pytest(PYTEST_OUTPUT);
+        assert_eq!((report.passed, report.failed, report.skipped), (2, 1, 1));
+        assert_eq!(report.failures[0].name, "test_add");
+        assert_eq!(
+            key_lines(&report.failures[0].detail),
+            vec!["assert 3 == 4", "tests/test_math.py:5: AssertionError"]
+        );
+    }
+
+    #[test]
+    fn test_jest_report() {
+        let report = parse_jest(JEST_OUTPUT);
+        assert_eq!((report.passed, report.failed), (5, 1));
+        assert_eq!(report.failures[0].name, "Calculator › adds numbers");
+        assert_eq!(
+            key_lines(&report.failures[0].detail),
+            vec![
+                "expect(received).toBe(expected) // Object.is equality",
+                "at Object.<anonymous> (src/calc.test.js:4:21)"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_go_report() {
+        let report = parse_go(GO_OUTPUT);
+        assert_eq!((report.passed, report.failed), (1, 1));
+        assert_eq!(report.failures[0].name, "TestAdd");
+        assert_eq!(
+            key_lines(&report.failures[0].detail),
+            vec!["calc_test.go:10: expected 4, got 3"]
+        );
+    }
+
+    #[test]
+    fn test_all_passed() {
+        let output = "test result: ok. 15 passed; 0 failed; 2 ignored; 0 measured";
+        let report = parse_cargo(output);
+        assert_eq!(
+            format_report(Framework::Cargo, &report, output, "cargo test", 0),
+            "✓ 15 passed, 2 skipped (cargo)"
+        );
+    }
+
+    #[test]
+    fn test_format_full() {
+        let report = parse_cargo(CARGO_OUTPUT);
+        let out = format_full(&report, "test_parse");
+        assert!(out.starts_with("❌ utils::tests::test_parse\nthread"));
+        assert!(!out.contains("test_sum"));
+        let out = format_full(&report, "nope");
+        assert_eq!(
+            out,
+            "No failure matching 'nope'. Failed: utils::tests::test_sum, utils::tests::test_parse"
+        );
+    }
+
+    #[test]
+    fn test_unknown_runner_tail() {
+        let out = format_report(
+            Framework::Unknown,
+            &TestReport::default(),
+            "a\nb\n\nc",
+            "make test",
+            2,
+        );
+        assert_eq!(
+            out,
+            "❌ Tests failed (exit code 2)\n📊 OUTPUT (last 5 lines):\n  a\n  b\n  c"
+        );
+    }
+}
@@ -0,0 +1,634 @@
+//! `rtk todo`: the TODO/FIXME/HACK/XXX comments of a project with who wrote
+//! them and how long ago (from `git blame`), grouped by file or by owner,
+//! instead of every line of a raw `grep -rn TODO`.
+//!
+//! The owner is the one named in the marker (`TODO(alice):`), else the
+//! author of the line.
+
+use crate::blame_cmd::{self, BlameLine};
+use crate::layout;
+use crate::paths;
+use crate::tracking;
+use crate::utils::truncate;
+use crate::walk;
+use anyhow::Result;
+use ignore::WalkBuilder;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::process::Command;
+
+/// Files larger than this are skipped (bundles, generated code)
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+
+/// Longer marker texts are cut
+const MAX_TEXT_CHARS: usize = 80;
+
+/// Owner of markers on lines git doesn't track, not named in the marker
+const UNKNOWN: &str = "(unknown)";
+
+/// Markers, most urgent first
+const MARKERS: [&str; 4] = ["FIXME", "HACK", "XXX", "TODO"];
+
+lazy_static! {
+    /// A marker opening a comment: `// TODO(alice): text`, `# FIXME text`,
+    /// `/* HACK */`, `-- XXX`. Prose mentioning "the TODO list" is not one.
+    static ref MARKER_RE: Regex = Regex::new(
+        r"(?:^|\s)(?://+!?|#+|/\*+!?|\*|--|<!--|;+)\s*(FIXME|HACK|XXX|TODO)(?:\(([^)]*)\))?(?:[:\s-]+|$)(.*)"
+    )
+    .unwrap();
+}
+
+/// Comment and string syntax of a file, for telling a marker comment from a
+/// marker inside a string literal
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Syntax {
+    /// `//`, `/* */`, `"..."` and `'c'`
+    CLike,
+    /// C-like with raw strings (`r#"..."#`) and lifetimes (`'a`)
+    Rust,
+    /// C-like with `'...'` and template strings
+    Js,
+    /// C-like with raw strings in backticks
+    Go,
+    /// `#`, `'...'`, `"..."` and triple-quoted strings
+    Python,
+    /// `#`, `'...'` and `"..."`
+    Shell,
+    /// No string syntax known: every line is scanned as is
+    Plain,
+}
+
+impl Syntax {
+    fn from_path(path: &str) -> Self {
+        let ext = path.rsplit_once('.').map_or("", |(_, ext)| ext);
+        match ext {
+            "rs" => Syntax::Rust,
+            "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" => Syntax::Js,
+            "go" => Syntax::Go,
+            "c" | "h" | "cc" | "cpp" | "cxx" | "hpp" | "java" | "kt" | "kts" | "swift" | "cs"
+            | "scala" | "dart" => Syntax::CLike,
+            "py" | "pyi" => Syntax::Python,
+            "sh" | "bash" | "zsh" | "rb" => Syntax::Shell,
+            _ => Syntax::Plain,
+        }
+    }
+}
+
+/// How the report is grouped
+#[derive(Debug, Clone, Copy, Default, PartialEq, clap::ValueEnum)]
+pub enum GroupBy {
+    /// Markers of each file, in line order
+    #[default]
+    File,
+    /// Markers of each owner, oldest first
+    Owner,
+}
+
+/// One marker comment
+#[derive(Debug, Clone, PartialEq)]
+struct Todo {
+    path: String,
+    line: usize,
+    marker: &'static str,
+    text: String,
+    /// Named in the marker: `TODO(alice)`
+    tag: Option<String>,
+    /// Author and time of the line, from `git blame`
+    author: Option<String>,
+    time: Option<i64>,
+}
+
+impl Todo {
+    fn owner(&self) -> &str {
+        self.tag
+            .as_deref()
+            .or(self.author.as_deref())
+            .unwrap_or(UNKNOWN)
+    }
+}
+
+pub fn run(
+    path: &str,
+    group_by: GroupBy,
+    blame: bool,
+    max_results: usize,
+    verbose: u8,
+) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    let mut walker = WalkBuilder::new(path);
+    walker
+        .hidden(true)
+        .git_ignore(true)
+        .git_global(true)
+        .git_exclude(true);
+
+    // Files are read and scanned on the walker threads
+    let walked = walk::walk(&walker, |entry| {
+        let small = entry
+            .metadata()
+            .is_ok_and(|m| m.is_file() && m.len() <= MAX_FILE_BYTES);
+        if !small {
+            return None;
+        }
+        let content = std::fs::read_to_string(entry.path()).ok()?;
+        let path = entry.path().to_string_lossy();
+        let display = path.strip_prefix("./").unwrap_or(&path);
+        let todos = scan(display, &content);
+        // What `grep -rn` would have printed, for the savings estimate
+        let raw: String = todos
+            .iter()
+            .map(|t| {
+                let line = content.lines().nth(t.line - 1).unwrap_or_default();
+                format!("{}:{}:{}\n", t.path, t.line, line)
+            })
+            .collect();
+        (!todos.is_empty()).then_some((todos, raw))
+    });
+
+    let mut by_file: Vec<Vec<Todo>> = Vec::new();
+    let mut raw = String::new();
+    for (todos, lines) in walked.items {
+        by_file.push(todos);
+        raw.push_str(&lines);
+    }
+    if blame {
+        // One `git blame` per file, files sharded over the cores
+        by_file = walk::shard(&by_file, |todos| with_blame(todos));
+    }
+    if verbose > 0 {
+        eprintln!(
+            "todo: {} markers in {} files",
+            by_file.iter().map(Vec::len).sum::<usize>(),
+            by_file.len()
+        );
+    }
+
+    let todos: Vec<Todo> = by_file.into_iter().flatten().collect();
+    let now = chrono::Utc::now().timestamp();
+    let output = match group_by {
+        GroupBy::File => format_by_file(&todos, now, max_results),
+        GroupBy::Owner => format_by_owner(&todos, now, max_results),
+    };
+    println!("{}", output);
+
+    timer.track(
+        &format!("grep -rnE 'TODO|FIXME|HACK|XXX' {}", path),
+        "rtk todo",
+        &raw,
+        &output,
+    );
+    Ok(())
+}
+
+/// Marker comments of `content`; markers inside string literals are data
+fn scan(path: &str, content: &str) -> Vec<Todo> {
+    let strings = string_spans(content, Syntax::from_path(path));
+    let in_string = |pos: usize| {
+        let i = strings.partition_point(|span| span.end <= pos);
+        strings.get(i).is_some_and(|span| span.start <= pos)
+    };
+
+    let mut offset = 0;
+    content
+        .split_inclusive('\n')
+        .map(|line| {
+            let start = offset;
+            offset += line.len();
+            let line = line.strip_suffix('\n').unwrap_or(line);
+            (start, line.strip_suffix('\r').unwrap_or(line))
+        })
+        .enumerate()
+        .filter_map(|(i, (start, line))| {
+            let caps = MARKER_RE.captures(line)?;
+            if in_string(start + caps.get(1)?.start()) {
+                return None;
+            }
+            let marker = MARKERS.into_iter().find(|m| *m == &caps[1])?;
+            let text = caps[3]
+                .trim()
+                .trim_end_matches("*/")
+                .trim_end_matches("-->")
+                .trim_end();
+            Some(Todo {
+                path: path.to_string(),
+                line: i + 1,
+                marker,
+                text: text.to_string(),
+                tag: caps
+                    .get(2)
+                    .map(|m| m.as_str().trim().trim_start_matches('@').to_string())
+                    .filter(|tag| !tag.is_empty()),
+                author: None,
+                time: None,
+            })
+        })
+        .collect()
+}
+
+/// Byte ranges of `content` inside string literals, in order. Comments are
+/// skipped so an apostrophe in `// don't` doesn't open a string.
+fn string_spans(content: &str, syntax: Syntax) -> Vec<Range<usize>> {
+    let bytes = content.as_bytes();
+    let (line_comment, block_comment): (&[u8], bool) = match syntax {
+        Syntax::Plain => return Vec::new(),
+        Syntax::Python | Syntax::Shell => (b"#", false),
+        _ => (b"//", true),
+    };
+
+    let mut spans = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let rest = &bytes[i..];
+        if rest.starts_with(line_comment) {
+            i += find(rest, b"\n").unwrap_or(rest.len());
+            continue;
+        }
+        if block_comment && rest.starts_with(b"/*") {
+            i += find(rest, b"*/").map_or(rest.len(), |end| end + 2);
+            continue;
+        }
+
+        let end = match (syntax, bytes[i]) {
+            (Syntax::Rust, b'r' | b'b') => rust_raw_string(content, i),
+            (Syntax::Python, b'"' | b'\'')
+                if rest.starts_with(b"\"\"\"") || rest.starts_with(b"'''") =>
+            {
+                Some(find(&rest[3..], &rest[..3]).map_or(bytes.len(), |end| i + 3 + end + 3))
+            }
+            // `'a'` is a char, `'a` a lifetime
+            (Syntax::Rust, b'\'') => {
+                let mut chars = content[i + 1..].chars();
+                match (chars.next(), chars.next()) {
+                    (Some('\\'), _) => Some(quoted(bytes, i, b'\'', false)),
+                    (Some(c), Some('\'')) => Some(i + 1 + c.len_utf8() + 1),
+                    _ => None,
+                }
+            }
+            (Syntax::Rust | Syntax::Shell, b'"') => Some(quoted(bytes, i, b'"', true)),
+            (Syntax::Shell, b'\'') => Some(quoted(bytes, i, b'\'', true)),
+            (Syntax::Go, b'`') => {
+                Some(find(&rest[1..], b"`").map_or(bytes.len(), |end| i + end + 2))
+            }
+            (Syntax::Js, b'`') => Some(quoted(bytes, i, b'`', true)),
+            (_, quote @ (b'"' | b'\'')) => Some(quoted(bytes, i, quote, false)),
+            _ => None,
+        };
+        match end {
+            Some(end) => {
+                spans.push(i..end);
+                i = end;
+            }
+            None => i += 1,
+        }
+    }
+    spans
+}
+
+/// End of the string opened by the `quote` at `start`, past its closing
+/// quote; backslash escapes. Unless `multiline`, a newline ends it too, so
+/// an unbalanced quote can't hide the rest of the file.
+fn quoted(bytes: &[u8], start: usize, quote: u8, multiline: bool) -> usize {
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 1,
+            b'\n' if !multiline => return i,
+            b if b == quote => return i + 1,
+            _ => {}
+        }
+        i += 1;
+    }
+    bytes.len()
+}
+
+/// End of the Rust raw string (`r"..."`, `br#"..."#`) at `start`, if any
+fn rust_raw_string(content: &str, start: usize) -> Option<usize> {
+    let bytes = content.as_bytes();
+    let after_ident =
+        start > 0 && (bytes[start - 1].is_ascii_alphanumeric() || bytes[start - 1] == b'_');
+    if after_ident {
+        return None;
+    }
+    let rest = &content[start..];
+    let rest = rest.strip_prefix('b').unwrap_or(rest).strip_prefix('r')?;
+    let hashes = rest.len() - rest.trim_start_matches('#').len();
+    let body = rest[hashes..].strip_prefix('"')?;
+    let close = format!("\"{}", "#".repeat(hashes));
+    let body_start = content.len() - body.len();
+    Some(
+        find(body.as_bytes(), close.as_bytes())
+            .map_or(content.len(), |end| body_start + end + close.len()),
+    )
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+/// The markers of one file with the author and time of their lines; files
+/// git doesn't track keep none
+fn with_blame(todos: &[Todo]) -> Vec<Todo> {
+    let mut todos = todos.to_vec();
+    let Some(path) = todos.first().map(|t| t.path.clone()) else {
+        return todos;
+    };
+    let mut cmd = Command::new("git");
+    cmd.args(["blame", "--line-porcelain"]);
+    for todo in &todos {
+        cmd.arg("-L").arg(format!("{},{}", todo.line, todo.line));
+    }
+    let output = match cmd.arg("--").arg(&path).output() {
+        Ok(output) if output.status.success() => output,
+        _ => return todos,
+    };
+    let blamed = blame_cmd::parse_line_porcelain(&String::from_utf8_lossy(&output.stdout));
+    for todo in &mut todos {
+        if let Some(line) = blamed.iter().find(|b| b.line == todo.line) {
+            todo.author = Some(author(line));
+            todo.time = (line.sha != blame_cmd::UNCOMMITTED).then_some(line.time);
+        }
+    }
+    todos
+}
+
+fn author(line: &BlameLine) -> String {
+    if line.sha == blame_cmd::UNCOMMITTED {
+        "(uncommitted)".to_string()
+    } else {
+        line.author.clone()
+    }
+}
+
+/// `3d`, `5w`, `4mo`, `2y`
+pub(crate) fn age(now: i64, time: i64) -> String {
+    let days = (now - time).max(0) / 86_400;
+    match days {
+        0..=13 => format!("{}d", days),
+        14..=59 => format!("{}w", days / 7),
+        60..=364 => format!("{}mo", days / 30),
+        _ => format!("{}y", days / 365),
+    }
+}
+
+/// `📝 12 markers in 4F: 2 FIXME, 10 TODO; oldest 2y`
+fn header(todos: &[Todo], files: usize, now: i64) -> String {
+    let counts: Vec<String> = MARKERS
+        .iter()
+        .filter_map(|marker| {
+            let n = todos.iter().filter(|t| t.marker == *marker).count();
+            (n > 0).then(|| format!("{} {}", n, marker))
+        })
+        .collect();
+    let mut out = format!(
+        "📝 {} markers in {}F: {}",
+        todos.len(),
+        files,
+        counts.join(", ")
+    );
+    if let Some(oldest) = todos.iter().filter_map(|t| t.time).min() {
+        out.push_str(&format!("; oldest {}", age(now, oldest)));
+    }
+    out
+}
+
+/// `  42 FIXME alice 3mo  text`; without the owner under an owner header
+fn todo_line(todo: &Todo, location: &str, with_owner: bool, now: i64) -> String {
+    let mut out = format!("  {} {}", location, todo.marker);
+    if with_owner && (todo.author.is_some() || todo.tag.is_some()) {
+        out.push_str(&format!(" {}", todo.owner()));
+    }
+    if let Some(time) = todo.time {
+        out.push_str(&format!(" {}", age(now, time)));
+    }
+    if !todo.text.is_empty() {
+        out.push_str(&format!("  {}", truncate(&todo.text, MAX_TEXT_CHARS)));
+    }
+    out.push('\n');
+    out
+}
+
+/// Owners by number of markers, most first, unknown last
+fn owner_counts(todos: &[Todo]) -> Vec<(&str, usize)> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for todo in todos {
+        *counts.entry(todo.owner()).or_default() += 1;
+    }
+    let mut counts: Vec<(&str, usize)> = counts.into_iter().collect();
+    counts.sort_by(|a, b| {
+        (a.0 == UNKNOWN)
+            .cmp(&(b.0 == UNKNOWN))
+            .then_with(|| b.1.cmp(&a.1))
+            .then_with(|| a.0.cmp(b.0))
+    });
+    counts
+}
+
+fn format_by_file(todos: &[Todo], now: i64, max_results: usize) -> String {
+    if todos.is_empty() {
+        return "📝 No TODO/FIXME/HACK/XXX markers".to_string();
+    }
+    let mut files: Vec<(&str, Vec<&Todo>)> = Vec::new();
+    for todo in todos {
+        match files.last_mut() {
+            Some((path, group)) if *path == todo.path => group.push(todo),
+            _ => files.push((&todo.path, vec![todo])),
+        }
+    }
+
+    let mut out = format!("{}\n", header(todos, files.len(), now));
+    if todos.iter().any(|t| t.author.is_some() || t.tag.is_some()) {
+        let owners: Vec<String> = owner_counts(todos)
+            .iter()
+            .map(|(owner, n)| format!("{} {}", owner, n))
+            .collect();
+        out.push_str(&format!("Owners: {}\n", owners.join(", ")));
+    }
+    out.push('\n');
+
+    let mut shown = 0;
+    for (path, group) in &files {
+        if shown >= max_results {
+            break;
+        }
+        out.push_str(&format!(
+            "📄 {} ({}):\n",
+            paths::compact(path, layout::path_width()),
+            group.len()
+        ));
+        for todo in group.iter().take(max_results - shown) {
+            out.push_str(&todo_line(todo, &format!("{:>4}", todo.line), true, now));
+            shown += 1;
+        }
+    }
+    if todos.len() > shown {
+        out.push_str(&format!("... +{} (--max)\n", todos.len() - shown));
+    }
+    out.trim_end().to_string()
+}
+
+fn format_by_owner(todos: &[Todo], now: i64, max_results: usize) -> String {
+    if todos.is_empty() {
+        return "📝 No TODO/FIXME/HACK/XXX markers".to_string();
+    }
+    let files = {
+        let mut paths: Vec<&str> = todos.iter().map(|t| t.path.as_str()).collect();
+        paths.dedup();
+        paths.len()
+    };
+    let mut out = format!("{}\n\n", header(todos, files, now));
+
+    let mut shown = 0;
+    for (owner, count) in owner_counts(todos) {
+        if shown >= max_results {
+            break;
+        }
+        // Oldest first: the ones most likely forgotten
+        let mut owned: Vec<&Todo> = todos.iter().filter(|t| t.owner() == owner).collect();
+        owned.sort_by_key(|t| t.time.unwrap_or(i64::MAX));
+        out.push_str(&format!("👤 {} ({}):\n", owner, count));
+        for todo in owned.iter().take(max_results - shown) {
+            let location = format!(
+                "{}:{}",
+                paths::compact(&todo.path, layout::path_width()),
+                todo.line
+            );
+            out.push_str(&todo_line(todo, &location, false, now));
+            shown += 1;
+        }
+    }
+    if todos.len() > shown {
+        out.push_str(&format!("... +{} (--max)\n", todos.len() - shown));
+    }
+    out.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 86_400;
+
+    fn todo(path: &str, line: usize, marker: &'static str, owner: &str, days: i64) -> Todo {
+        Todo {
+            path: path.into(),
+            line,
+            marker,
+            text: format!("fix {}", line),
+            tag: None,
+            author: Some(owner.into()),
+            time: Some(100 * DAY - days * DAY),
+        }
+    }
+
+    #[test]
+    fn test_scan() {
+        let content = "// TODO(@alice): handle env overrides\n\
+                       let todo = \"// TODO\";\n\
+                       # FIXME - flaky on CI\n\
+                       /* HACK */\n\
+                       let TODO_LIST = 1; // XXX\n\
+                       /// Lists the TODO markers\n\
+                       // TODO/FIXME comments\n";
+        let todos = scan("a.rs", content);
+        let found: Vec<(usize, &str, Option<&str>, &str)> = todos
+            .iter()
+            .map(|t| (t.line, t.marker, t.tag.as_deref(), t.text.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (1, "TODO", Some("alice"), "handle env overrides"),
+                (3, "FIXME", None, "flaky on CI"),
+                (4, "HACK", None, ""),
+                (5, "XXX", None, ""),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scan_skips_string_literals() {
+        let rust = r####"let a = "x // TODO: not a task";
+let b = r#"
+# FIXME: raw string data
+"#;
+fn f<'a>(s: &'a str) -> char { '"' } // TODO: real one
+call("don't"); // XXX after a string
+/* "unbalanced */ // HACK: after a block comment
+"####;
+        let lines: Vec<(usize, &str)> = scan("a.rs", rust)
+            .iter()
+            .map(|t| (t.line, t.marker))
+            .collect();
+        assert_eq!(lines, vec![(5, "TODO"), (6, "XXX"), (7, "HACK")]);
+
+        let python = "def f():\n    \"\"\"\n    # TODO: docstring example\n    \"\"\"\n    s = '# FIXME: data'  # don't\n    # TODO: real\n";
+        let lines: Vec<usize> = scan("a.py", python).iter().map(|t| t.line).collect();
+        assert_eq!(lines, vec![6]);
+
+        let js = "const t = `\n// TODO: template\n`;\nconst q = '// FIXME'; // HACK: real\n";
+        let lines: Vec<usize> = scan("a.ts", js).iter().map(|t| t.line).collect();
+        assert_eq!(lines, vec![4]);
+
+        // Unknown syntax: quotes don't hide anything
+        let lines: Vec<usize> = scan("notes.md", "It's \"odd\" <!-- TODO: keep -->\n")
+            .iter()
+            .map(|t| t.line)
+            .collect();
+        assert_eq!(lines, vec![1]);
+    }
+
+    #[test]
+    fn test_age() {
+        assert_eq!(age(10 * DAY, 7 * DAY), "3d");
+        assert_eq!(age(30 * DAY, 0), "4w");
+        assert_eq!(age(150 * DAY, 0), "5mo");
+        assert_eq!(age(800 * DAY, 0), "2y");
+        // Clock skew: never negative
+        assert_eq!(age(0, DAY), "0d");
+    }
+
+    #[test]
+    fn test_format_by_file() {
+        let mut tagged = todo("src/a.rs", 9, "FIXME", "bob", 3);
+        tagged.tag = Some("carol".into());
+        let todos = vec![
+            todo("src/a.rs", 2, "TODO", "alice", 90),
+            tagged,
+            todo("src/b.rs", 5, "TODO", "alice", 1),
+        ];
+        assert_eq!(
+            format_by_file(&todos, 100 * DAY, 2),
+            "📝 3 markers in 2F: 1 FIXME, 2 TODO; oldest 3mo\n\
+             Owners: alice 2, carol 1\n\n\
+             📄 src/a.rs (2):\n     \
+             2 TODO alice 3mo  fix 2\n     \
+             9 FIXME carol 3d  fix 9\n\
+             ... +1 (--max)"
+        );
+        assert_eq!(
+            format_by_file(&[], 0, 10),
+            "📝 No TODO/FIXME/HACK/XXX markers"
+        );
+    }
+
+    #[test]
+    fn test_format_by_owner() {
+        let todos = vec![
+            todo("src/a.rs", 2, "TODO", "alice", 1),
+            todo("src/a.rs", 9, "FIXME", "bob", 3),
+            todo("src/b.rs", 5, "TODO", "alice", 400),
+        ];
+        assert_eq!(
+            format_by_owner(&todos, 100 * DAY, 50),
+            "📝 3 markers in 2F: 1 FIXME, 2 TODO; oldest 1y\n\n\
+             👤 alice (2):\n  \
+             src/b.rs:5 TODO 1y  fix 5\n  \
+             src/a.rs:2 TODO 1d  fix 2\n\
+             👤 bob (1):\n  \
+             src/a.rs:9 FIXME 3d  fix 9"
+        );
+    }
+}
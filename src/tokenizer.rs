@@ -0,0 +1,247 @@
+//! Model-aware token counting for tracking.
+//!
+//! Byte savings don't map 1:1 to context-window savings: code, JSON and
+//! numbers tokenize very differently from prose. The OpenAI encodings
+//! (`cl100k_base`, `o200k_base`) are counted exactly with tiktoken. Claude's
+//! tokenizer isn't public, so [`TokenModel::Claude`] falls back to an
+//! estimate: text is split the way a BPE pre-tokenizer does (words with their
+//! leading space, digit groups, punctuation runs, newlines) and each piece is
+//! costed with merge rates.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Tokenizer used to count tokens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenModel {
+    /// Legacy ~4 chars = 1 token heuristic
+    #[default]
+    Chars,
+    /// GPT-4 / GPT-3.5 (`cl100k_base`)
+    Cl100k,
+    /// GPT-4o and later (`o200k_base`)
+    O200k,
+    /// Claude models (estimate: the tokenizer isn't public)
+    Claude,
+}
+
+/// Costs applied to each pre-tokenized piece by the Claude estimate
+struct Rates {
+    /// Average ASCII letters per token inside a word
+    letters_per_token: f64,
+    /// Max digits grouped in a single token
+    digits_per_token: usize,
+    /// Average punctuation chars per token
+    punct_per_token: f64,
+    /// Tokens per non-ASCII char (CJK, accents, emoji)
+    non_ascii_cost: f64,
+}
+
+impl TokenModel {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Chars => "chars",
+            Self::Cl100k => "cl100k",
+            Self::O200k => "o200k",
+            Self::Claude => "claude",
+        }
+    }
+
+    /// Number of tokens `text` uses for this model.
+    pub fn count(&self, text: &str) -> usize {
+        match self {
+            Self::Chars => crate::tracking::estimate_tokens(text),
+            Self::Cl100k => tiktoken_rs::cl100k_base_singleton()
+                .encode_ordinary(text)
+                .len(),
+            Self::O200k => tiktoken_rs::o200k_base_singleton()
+                .encode_ordinary(text)
+                .len(),
+            Self::Claude => estimate(text),
+        }
+    }
+}
+
+impl fmt::Display for TokenModel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for TokenModel {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "chars" | "heuristic" => Ok(Self::Chars),
+            "cl100k" | "cl100k_base" | "gpt-4" | "gpt-3.5" => Ok(Self::Cl100k),
+            "o200k" | "o200k_base" | "gpt-4o" => Ok(Self::O200k),
+            "claude" => Ok(Self::Claude),
+            other => anyhow::bail!(
+                "Unknown tokenizer '{}' (expected one of: chars, cl100k, o200k, claude)",
+                other
+            ),
+        }
+    }
+}
+
+const CLAUDE_RATES: Rates = Rates {
+    letters_per_token: 4.2,
+    digits_per_token: 1,
+    punct_per_token: 1.5,
+    non_ascii_cost: 1.2,
+};
+
+/// Heuristic Claude token count, summed over pre-tokenized pieces.
+fn estimate(text: &str) -> usize {
+    let mut total = 0.0;
+    for piece in pieces(text) {
+        total += piece_cost(piece, &CLAUDE_RATES);
+    }
+    total.ceil() as usize
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Class {
+    Letter,
+    Digit,
+    Space,
+    Newline,
+    Punct,
+}
+
+fn classify(c: char) -> Class {
+    if c == '\n' || c == '\r' {
+        Class::Newline
+    } else if c.is_whitespace() {
+        Class::Space
+    } else if c.is_alphabetic() || c == '\'' {
+        Class::Letter
+    } else if c.is_numeric() {
+        Class::Digit
+    } else {
+        Class::Punct
+    }
+}
+
+/// Split text into BPE pre-tokenizer pieces. A single space before a word or
+/// punctuation run is merged into that piece, as in tiktoken's split pattern.
+fn pieces(text: &str) -> Vec<&str> {
+    let mut out = Vec::new();
+    let mut start = 0;
+    let mut prev: Option<Class> = None;
+
+    for (i, c) in text.char_indices() {
+        let class = classify(c);
+        match prev {
+            None => prev = Some(class),
+            Some(p) if p == class => {}
+            // " word" / " {" stay together when the space is a single char
+            Some(Class::Space)
+                if i - start == 1 && matches!(class, Class::Letter | Class::Punct) =>
+            {
+                prev = Some(class)
+            }
+            _ => {
+                out.push(&text[start..i]);
+                start = i;
+                prev = Some(class);
+            }
+        }
+    }
+    if start < text.len() {
+        out.push(&text[start..]);
+    }
+    out
+}
+
+fn piece_cost(piece: &str, rates: &Rates) -> f64 {
+    let body = piece.strip_prefix(' ').unwrap_or(piece);
+    let Some(first) = body.chars().next() else {
+        // Lone space
+        return 1.0;
+    };
+
+    match classify(first) {
+        // Runs of newlines/indentation merge into one token
+        Class::Newline | Class::Space => 1.0,
+        Class::Digit => {
+            let digits = body.chars().count();
+            digits.div_ceil(rates.digits_per_token) as f64
+        }
+        Class::Punct => {
+            let len = body.chars().count() as f64;
+            (len / rates.punct_per_token).ceil().max(1.0)
+        }
+        Class::Letter => {
+            let ascii = body.chars().filter(|c| c.is_ascii()).count() as f64;
+            let other = body.chars().filter(|c| !c.is_ascii()).count() as f64;
+            let ascii_tokens = if ascii > 0.0 {
+                (ascii / rates.letters_per_token).ceil()
+            } else {
+                0.0
+            };
+            (ascii_tokens + other * rates.non_ascii_cost).max(1.0)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pieces_keep_leading_space() {
+        assert_eq!(
+            pieces("let x = 42;\n"),
+            vec!["let", " x", " =", " ", "42", ";", "\n"]
+        );
+    }
+
+    #[test]
+    fn test_chars_matches_legacy_estimate() {
+        assert_eq!(TokenModel::Chars.count("hello world"), 3);
+        assert_eq!(TokenModel::Chars.count(""), 0);
+    }
+
+    #[test]
+    fn test_prose_counts_words() {
+        assert_eq!(TokenModel::Cl100k.count("the quick brown fox"), 4);
+        assert_eq!(TokenModel::O200k.count("the quick brown fox"), 4);
+    }
+
+    #[test]
+    fn test_openai_counts_are_exact() {
+        // "hello world" is two tokens in both encodings
+        assert_eq!(TokenModel::Cl100k.count("hello world"), 2);
+        assert_eq!(TokenModel::O200k.count("hello world"), 2);
+    }
+
+    #[test]
+    fn test_numbers_split_in_groups() {
+        assert_eq!(TokenModel::Cl100k.count("1234567"), 3);
+        assert_eq!(TokenModel::Claude.count("1234567"), 7);
+    }
+
+    #[test]
+    fn test_models_differ_on_code() {
+        let code = "fn main() {\n    println!(\"{}\", 12345);\n}\n";
+        let cl100k = TokenModel::Cl100k.count(code);
+        let o200k = TokenModel::O200k.count(code);
+        let claude = TokenModel::Claude.count(code);
+        assert!(o200k <= cl100k);
+        assert!(claude > cl100k);
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!(
+            "cl100k_base".parse::<TokenModel>().unwrap(),
+            TokenModel::Cl100k
+        );
+        assert_eq!("GPT-4o".parse::<TokenModel>().unwrap(), TokenModel::O200k);
+        assert!("bert".parse::<TokenModel>().is_err());
+    }
+}
@@ -0,0 +1,308 @@
+use crate::tracking;
+use anyhow::{Context, Result};
+use serde_json::{json, Map};
+use std::fs;
+use std::path::Path;
+use toml::Value;
+
+/// Show TOML structure without values, collapsing dependency tables to counts
+pub fn run(
+    file: &Path,
+    max_depth: usize,
+    list_deps: bool,
+    format: &str,
+    verbose: u8,
+) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    if verbose > 0 {
+        eprintln!("Analyzing TOML: {}", file.display());
+    }
+
+    let content = fs::read_to_string(file)
+        .with_context(|| format!("Failed to read file: {}", file.display()))?;
+    let value: Value = toml::from_str(&content)
+        .with_context(|| format!("Failed to parse TOML: {}", file.display()))?;
+
+    let schema = match format {
+        "json" => serde_json::to_string_pretty(&schema_value(&value, 0, max_depth, list_deps))?,
+        _ => filter_toml(&value, max_depth, list_deps),
+    };
+    println!("{}", schema);
+
+    timer.track(
+        &format!("cat {}", file.display()),
+        "rtk toml",
+        &content,
+        &schema,
+    );
+    Ok(())
+}
+
+/// `[dependencies]`, `[dev-dependencies]`, `[workspace.dependencies]`,
+/// `[tool.poetry.dependencies]`, ...
+fn is_dependency_table(key: &str) -> bool {
+    key == "dependencies" || key.ends_with("-dependencies")
+}
+
+fn filter_toml(value: &Value, max_depth: usize, list_deps: bool) -> String {
+    let mut lines = Vec::new();
+    if let Value::Table(table) = value {
+        // Top-level scalars first, as in the file layout
+        for (key, val) in table {
+            if !is_section(val) {
+                lines.push(format!("{}: {}", key, type_name(val)));
+            }
+        }
+        for (key, val) in table {
+            if is_section(val) {
+                render_section(key, val, 0, max_depth, list_deps, &mut lines);
+            }
+        }
+    }
+    lines.join("\n")
+}
+
+/// Tables and arrays of tables get their own `[header]`
+fn is_section(value: &Value) -> bool {
+    match value {
+        Value::Table(_) => true,
+        Value::Array(arr) => !arr.is_empty() && arr.iter().all(|v| v.is_table()),
+        _ => false,
+    }
+}
+
+fn render_section(
+    path: &str,
+    value: &Value,
+    depth: usize,
+    max_depth: usize,
+    list_deps: bool,
+    lines: &mut Vec<String>,
+) {
+    let name = path.rsplit('.').next().unwrap_or(path);
+
+    match value {
+        Value::Table(table) if is_dependency_table(name) => {
+            lines.push(format!("[{}] {} entries", path, table.len()));
+            if list_deps {
+                for (dep, spec) in table {
+                    lines.push(format!("  {} {}", dep, dependency_spec(spec)));
+                }
+            }
+        }
+        Value::Table(table) if depth >= max_depth => {
+            lines.push(format!("[{}] {{{} keys}}", path, table.len()));
+        }
+        Value::Table(table) => {
+            // Skip header for pure containers like [target] or [tool]
+            if table.values().any(|v| !is_section(v)) || table.is_empty() {
+                lines.push(format!("[{}]", path));
+            }
+            for (key, val) in table {
+                if !is_section(val) {
+                    lines.push(format!("  {}: {}", key, type_name(val)));
+                }
+            }
+            for (key, val) in table {
+                if is_section(val) {
+                    let child = format!("{}.{}", path, key);
+                    render_section(&child, val, depth + 1, max_depth, list_deps, lines);
+                }
+            }
+        }
+        Value::Array(arr) => {
+            lines.push(format!("[[{}]] ({})", path, arr.len()));
+            if let Some(Value::Table(first)) = arr.first() {
+                for (key, val) in first {
+                    lines.push(format!("  {}: {}", key, type_name(val)));
+                }
+            }
+        }
+        other => lines.push(format!("{}: {}", path, type_name(other))),
+    }
+}
+
+/// Version requirement or source of a dependency entry
+fn dependency_spec(spec: &Value) -> String {
+    match spec {
+        Value::String(req) => req.clone(),
+        Value::Table(table) => {
+            let source = if let Some(Value::String(v)) = table.get("version") {
+                v.clone()
+            } else if let Some(Value::String(p)) = table.get("path") {
+                format!("path:{}", p)
+            } else if let Some(Value::String(g)) = table.get("git") {
+                format!("git:{}", g)
+            } else if table.get("workspace").and_then(Value::as_bool) == Some(true) {
+                "workspace".to_string()
+            } else {
+                "*".to_string()
+            };
+
+            let mut flags = Vec::new();
+            if table.get("optional").and_then(Value::as_bool) == Some(true) {
+                flags.push("optional".to_string());
+            }
+            if let Some(Value::Array(features)) = table.get("features") {
+                let names: Vec<&str> = features.iter().filter_map(Value::as_str).collect();
+                if !names.is_empty() {
+                    flags.push(names.join(","));
+                }
+            }
+
+            if flags.is_empty() {
+                source
+            } else {
+                format!("{} ({})", source, flags.join("; "))
+            }
+        }
+        other => type_name(other),
+    }
+}
+
+fn type_name(value: &Value) -> String {
+    match value {
+        Value::String(s) if s.len() > 50 => format!("string[{}]", s.len()),
+        Value::String(_) => "string".to_string(),
+        Value::Integer(_) => "int".to_string(),
+        Value::Float(_) => "float".to_string(),
+        Value::Boolean(_) => "bool".to_string(),
+        Value::Datetime(_) => "datetime".to_string(),
+        Value::Array(arr) => match arr.first() {
+            Some(first) => format!("[{}] ({})", type_name(first), arr.len()),
+            None => "[]".to_string(),
+        },
+        Value::Table(table) => format!("{{{} keys}}", table.len()),
+    }
+}
+
+/// Schema node as a JSON value, for `--format json` (same shape as `rtk json`)
+fn schema_value(
+    value: &Value,
+    depth: usize,
+    max_depth: usize,
+    list_deps: bool,
+) -> serde_json::Value {
+    if depth > max_depth {
+        return json!({ "type": "truncated" });
+    }
+
+    match value {
+        Value::Table(table) => {
+            let mut keys = Map::new();
+            for (key, val) in table {
+                let node = match val {
+                    Value::Table(deps) if is_dependency_table(key) => {
+                        let mut node = json!({ "type": "dependencies", "count": deps.len() });
+                        if list_deps {
+                            let entries: Map<String, serde_json::Value> = deps
+                                .iter()
+                                .map(|(name, spec)| (name.clone(), json!(dependency_spec(spec))))
+                                .collect();
+                            node["entries"] = serde_json::Value::Object(entries);
+                        }
+                        node
+                    }
+                    _ => schema_value(val, depth + 1, max_depth, list_deps),
+                };
+                keys.insert(key.clone(), node);
+            }
+            json!({ "type": "object", "keys": keys })
+        }
+        Value::Array(arr) => match arr.first() {
+            Some(first) => json!({
+                "type": "array",
+                "len": arr.len(),
+                "items": schema_value(first, depth + 1, max_depth, list_deps),
+            }),
+            None => json!({ "type": "array", "len": 0 }),
+        },
+        other => json!({ "type": type_name(other) }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CARGO_TOML: &str = r#"
+[package]
+name = "rtk"
+version = "0.22.2"
+keywords = ["cli", "llm"]
+
+[dependencies]
+clap = { version = "4", features = ["derive"] }
+anyhow = "1.0"
+local = { path = "../local" }
+
+[dev-dependencies]
+
+[target.'cfg(unix)'.dependencies]
+libc = "0.2"
+
+[profile.release]
+lto = true
+opt-level = 3
+
+[[bin]]
+name = "rtk"
+path = "src/main.rs"
+"#;
+
+    fn parse(s: &str) -> Value {
+        toml::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_dependencies_collapsed() {
+        let out = filter_toml(&parse(CARGO_TOML), 5, false);
+        assert!(out.contains("[dependencies] 3 entries"));
+        assert!(out.contains("[dev-dependencies] 0 entries"));
+        assert!(out.contains("[target.cfg(unix).dependencies] 1 entries"));
+        assert!(!out.contains("clap"));
+    }
+
+    #[test]
+    fn test_dependencies_listed() {
+        let out = filter_toml(&parse(CARGO_TOML), 5, true);
+        assert!(out.contains("  clap 4 (derive)"));
+        assert!(out.contains("  anyhow 1.0"));
+        assert!(out.contains("  local path:../local"));
+    }
+
+    #[test]
+    fn test_schema_without_values() {
+        let out = filter_toml(&parse(CARGO_TOML), 5, false);
+        assert!(out.contains("[package]"));
+        assert!(out.contains("  name: string"));
+        assert!(out.contains("  keywords: [string] (2)"));
+        assert!(out.contains("  lto: bool"));
+        assert!(out.contains("[[bin]] (1)"));
+        assert!(!out.contains("0.22.2"));
+        // [target] only holds sub-tables: no empty header
+        assert!(!out.lines().any(|l| l == "[target]"));
+    }
+
+    #[test]
+    fn test_keeps_file_order() {
+        let out = filter_toml(&parse(CARGO_TOML), 5, false);
+        let position = |s: &str| out.find(s).unwrap();
+        assert!(position("  name: string") < position("  version: string"));
+        assert!(position("[package]") < position("[dependencies]"));
+        assert!(position("[profile.release]") < position("[[bin]]"));
+    }
+
+    #[test]
+    fn test_json_schema() {
+        let value = schema_value(&parse(CARGO_TOML), 0, 5, true);
+        assert_eq!(value["keys"]["dependencies"]["type"], "dependencies");
+        assert_eq!(value["keys"]["dependencies"]["count"], 3);
+        assert_eq!(value["keys"]["dependencies"]["entries"]["anyhow"], "1.0");
+        assert_eq!(
+            value["keys"]["package"]["keys"]["version"]["type"],
+            "string"
+        );
+    }
+}
@@ -0,0 +1,538 @@
+//! Stack trace compaction: Java/Kotlin, Python, Rust and Node traces are
+//! reduced to the exception (type and message) and the in-project frames
+//! nearest to it; runs of framework and runtime frames are replaced by a
+//! count. Lines around the traces are kept as they are.
+//!
+//! Used by `rtk trace` and automatically by `rtk pipe`.
+
+use crate::tracking;
+use anyhow::{Context, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+/// In-project frames kept per exception
+const MAX_PROJECT_FRAMES: usize = 5;
+
+/// Traces with fewer frames are left alone
+const MIN_FRAMES: usize = 3;
+
+lazy_static! {
+    /// `java.lang.IllegalStateException: msg`, `TypeError: x`, optionally
+    /// behind `Exception in thread "main"` or `Caused by:`
+    static ref EXCEPTION_RE: Regex = Regex::new(
+        r#"^\s*(?:Exception in thread "[^"]*" |Uncaught |(?:Caused by|Suppressed): )?([A-Za-z_$][\w$.]*(?:Exception|Error|Throwable|Failure)|Error)(?::\s*(.*))?$"#
+    )
+    .unwrap();
+    static ref AT_FRAME_RE: Regex = Regex::new(r"^\s+at\s+(.+)$").unwrap();
+    static ref JAVA_MORE_RE: Regex = Regex::new(r"^\s+\.\.\. (\d+) (?:more|common frames omitted)$").unwrap();
+    static ref CAUSE_RE: Regex = Regex::new(r"^\s*(Caused by|Suppressed): (.+)$").unwrap();
+    static ref PY_FRAME_RE: Regex = Regex::new(r#"^\s+File "(.+)", line (\d+), in (.+)$"#).unwrap();
+    static ref RUST_PANIC_RE: Regex = Regex::new(r"^thread '([^']*)' panicked at (.+?):?$").unwrap();
+    static ref RUST_FRAME_RE: Regex = Regex::new(r"^\s*\d+:\s+(.+)$").unwrap();
+    static ref RUST_AT_RE: Regex = Regex::new(r"^\s+at (.+)$").unwrap();
+}
+
+/// JVM packages that are not the project's own code
+const JVM_FRAMEWORKS: &[&str] = &[
+    "java.",
+    "javax.",
+    "jdk.",
+    "sun.",
+    "com.sun.",
+    "kotlin.",
+    "kotlinx.",
+    "scala.",
+    "org.springframework.",
+    "org.apache.",
+    "org.hibernate.",
+    "org.junit.",
+    "org.gradle.",
+    "org.eclipse.",
+    "io.netty.",
+    "reactor.",
+    "io.reactivex.",
+    "com.google.",
+    "com.fasterxml.",
+    "ch.qos.",
+];
+
+/// Rust crates of the toolchain and common runtimes
+const RUST_FRAMEWORKS: &[&str] = &[
+    "std::",
+    "core::",
+    "alloc::",
+    "tokio::",
+    "futures::",
+    "futures_util::",
+    "rust_begin_unwind",
+    "__rust",
+    "__libc",
+    "_start",
+    "<unknown>",
+    "start_thread",
+    "clone",
+];
+
+#[derive(Debug, PartialEq)]
+struct Frame {
+    text: String,
+    project: bool,
+    /// Frames this one stands for (`... 12 more` counts 12)
+    weight: usize,
+}
+
+/// One exception of a trace: its title and frames
+#[derive(Debug, PartialEq)]
+struct Section {
+    title: String,
+    frames: Vec<Frame>,
+}
+
+#[derive(Debug, PartialEq)]
+struct Trace {
+    sections: Vec<Section>,
+    /// Frames are listed oldest first (Python) rather than newest first
+    recent_last: bool,
+}
+
+impl Trace {
+    fn frame_count(&self) -> usize {
+        self.sections
+            .iter()
+            .flat_map(|s| &s.frames)
+            .map(|f| f.weight)
+            .sum()
+    }
+}
+
+fn jvm_project(frame: &str) -> bool {
+    !JVM_FRAMEWORKS.iter().any(|p| frame.starts_with(p))
+}
+
+fn node_project(frame: &str) -> bool {
+    !(frame.contains("node_modules")
+        || frame.contains("node:")
+        || frame.contains("(internal/")
+        || frame.starts_with("internal/")
+        || frame.contains("<anonymous>"))
+}
+
+fn python_project(path: &str) -> bool {
+    !(path.contains("site-packages")
+        || path.contains("dist-packages")
+        || path.contains("/lib/python")
+        || path.starts_with('<'))
+}
+
+fn rust_project(function: &str, location: Option<&str>) -> bool {
+    let framework_fn = RUST_FRAMEWORKS.iter().any(|p| function.starts_with(p));
+    let framework_loc = location.is_some_and(|l| {
+        l.starts_with("/rustc/") || l.contains("/.cargo/") || l.contains("/library/std/")
+    });
+    !framework_fn && !framework_loc
+}
+
+/// A JVM or Node trace: exception line, then `at ...` frames, with JVM
+/// `Caused by:` sections
+fn parse_at_frames(lines: &[&str]) -> Option<(Trace, usize)> {
+    let caps = EXCEPTION_RE.captures(lines[0])?;
+    let mut sections = vec![Section {
+        title: title(&caps),
+        frames: Vec::new(),
+    }];
+    let mut used = 1;
+
+    for line in &lines[1..] {
+        if let Some(frame) = AT_FRAME_RE.captures(line) {
+            let text = frame[1].trim().to_string();
+            // JVM frames read `pkg.Class.method(File.java:12)`
+            let jvm = text.ends_with(')') && !text.contains(' ');
+            let project = if jvm {
+                jvm_project(&text)
+            } else {
+                node_project(&text)
+            };
+            sections.last_mut()?.frames.push(Frame {
+                text: format!("at {}", text),
+                project,
+                weight: 1,
+            });
+        } else if let Some(more) = JAVA_MORE_RE.captures(line) {
+            sections.last_mut()?.frames.push(Frame {
+                text: String::new(),
+                project: false,
+                weight: more[1].parse().unwrap_or(1),
+            });
+        } else if let Some(cause) = CAUSE_RE.captures(line) {
+            let title = match EXCEPTION_RE.captures(line) {
+                Some(caps) => format!("{}: {}", &cause[1], self::title(&caps)),
+                None => format!("{}: {}", &cause[1], &cause[2]),
+            };
+            sections.push(Section {
+                title,
+                frames: Vec::new(),
+            });
+        } else {
+            break;
+        }
+        used += 1;
+    }
+
+    sections[0].frames.first()?;
+    Some((
+        Trace {
+            sections,
+            recent_last: false,
+        },
+        used,
+    ))
+}
+
+fn title(caps: &regex::Captures) -> String {
+    match caps.get(2).map(|m| m.as_str().trim()) {
+        Some(message) if !message.is_empty() => format!("{}: {}", &caps[1], message),
+        _ => caps[1].to_string(),
+    }
+}
+
+/// `Traceback (most recent call last):`, frames, then the exception line
+fn parse_python(lines: &[&str]) -> Option<(Trace, usize)> {
+    if lines[0].trim() != "Traceback (most recent call last):" {
+        return None;
+    }
+    let mut frames = Vec::new();
+    let mut used = 1;
+    let mut title = None;
+
+    for line in &lines[1..] {
+        used += 1;
+        if let Some(caps) = PY_FRAME_RE.captures(line) {
+            let path = &caps[1];
+            frames.push(Frame {
+                text: format!("{}:{} in {}", path, &caps[2], &caps[3]),
+                project: python_project(path),
+                weight: 1,
+            });
+        } else if line.starts_with(char::is_whitespace) {
+            // Source line of the frame above, or its ^^^^ markers
+        } else {
+            title = Some(line.trim().to_string());
+            break;
+        }
+    }
+
+    frames.first()?;
+    Some((
+        Trace {
+            sections: vec![Section {
+                title: title?,
+                frames,
+            }],
+            recent_last: true,
+        },
+        used,
+    ))
+}
+
+/// `thread 'main' panicked at ...`, its message, then `stack backtrace:`
+fn parse_rust(lines: &[&str]) -> Option<(Trace, usize)> {
+    let caps = RUST_PANIC_RE.captures(lines[0])?;
+    let mut title = format!("panicked at {}", &caps[2]);
+    let mut used = 1;
+
+    // The message: up to the backtrace
+    while used < lines.len() && lines[used].trim() != "stack backtrace:" {
+        let line = lines[used].trim();
+        if line.is_empty() || line.starts_with("note:") {
+            return None;
+        }
+        title.push_str(if used == 1 { ": " } else { " " });
+        title.push_str(line);
+        used += 1;
+    }
+    used += 1;
+
+    let mut frames: Vec<Frame> = Vec::new();
+    let mut functions: Vec<String> = Vec::new();
+    while used < lines.len() {
+        let line = lines[used];
+        if let Some(frame) = RUST_FRAME_RE.captures(line) {
+            let function = frame[1].trim().to_string();
+            frames.push(Frame {
+                text: function.clone(),
+                project: rust_project(&function, None),
+                weight: 1,
+            });
+            functions.push(function);
+        } else if let (Some(at), Some(frame)) = (RUST_AT_RE.captures(line), frames.last_mut()) {
+            let location = at[1].trim();
+            let function = functions.last().map_or("", String::as_str);
+            frame.project = rust_project(function, Some(location));
+            frame.text = format!("{} at {}", function, location);
+        } else if line.starts_with("note:") {
+            used += 1;
+            break;
+        } else {
+            break;
+        }
+        used += 1;
+    }
+
+    frames.first()?;
+    Some((
+        Trace {
+            sections: vec![Section { title, frames }],
+            recent_last: false,
+        },
+        used,
+    ))
+}
+
+fn parse_trace(lines: &[&str]) -> Option<(Trace, usize)> {
+    parse_python(lines)
+        .or_else(|| parse_rust(lines))
+        .or_else(|| parse_at_frames(lines))
+}
+
+/// The frames of a section worth showing: up to [`MAX_PROJECT_FRAMES`]
+/// in-project frames nearest to the error (the nearest frame at all when
+/// none is the project's), other runs counted
+fn render_frames(frames: &[Frame], recent_last: bool) -> Vec<String> {
+    let mut project: Vec<usize> = (0..frames.len())
+        .filter(|&i| frames[i].project && frames[i].weight == 1)
+        .collect();
+    if recent_last {
+        project = project.split_off(project.len().saturating_sub(MAX_PROJECT_FRAMES));
+    } else {
+        project.truncate(MAX_PROJECT_FRAMES);
+    }
+    if project.is_empty() {
+        let nearest = if recent_last {
+            frames.iter().rposition(|f| f.weight == 1)
+        } else {
+            frames.iter().position(|f| f.weight == 1)
+        };
+        project.extend(nearest);
+    }
+
+    let mut out = Vec::new();
+    let mut skipped = 0;
+    let mut skipped_project = false;
+    let flush = |out: &mut Vec<String>, skipped: &mut usize, skipped_project: &mut bool| {
+        if *skipped > 0 {
+            let kind = if *skipped_project {
+                "more"
+            } else {
+                "framework"
+            };
+            out.push(format!(
+                "... {} {} frame{}",
+                skipped,
+                kind,
+                if *skipped == 1 { "" } else { "s" }
+            ));
+        }
+        *skipped = 0;
+        *skipped_project = false;
+    };
+    for (i, frame) in frames.iter().enumerate() {
+        if project.contains(&i) {
+            flush(&mut out, &mut skipped, &mut skipped_project);
+            out.push(frame.text.clone());
+        } else {
+            skipped += frame.weight;
+            skipped_project |= frame.project;
+        }
+    }
+    flush(&mut out, &mut skipped, &mut skipped_project);
+    out
+}
+
+fn render(trace: &Trace) -> Vec<String> {
+    let mut out = Vec::new();
+    for (i, section) in trace.sections.iter().enumerate() {
+        if i == 0 {
+            out.push(format!("💥 {}", section.title));
+        } else {
+            out.push(format!("  ↳ {}", section.title));
+        }
+        for frame in render_frames(&section.frames, trace.recent_last) {
+            out.push(format!("    {}", frame));
+        }
+    }
+    out
+}
+
+/// Whether `text` may hold a trace, to skip the line-by-line scan
+pub fn has_trace(text: &str) -> bool {
+    text.contains("\tat ")
+        || text.contains("    at ")
+        || text.contains("Traceback (most recent call last)")
+        || text.contains("stack backtrace:")
+}
+
+/// `text` with every stack trace compacted
+pub fn compact_traces(text: &str) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut out: Vec<String> = Vec::with_capacity(lines.len());
+    let mut i = 0;
+    while i < lines.len() {
+        match parse_trace(&lines[i..]) {
+            Some((trace, used)) if trace.frame_count() >= MIN_FRAMES => {
+                out.extend(render(&trace));
+                i += used;
+            }
+            _ => {
+                out.push(lines[i].to_string());
+                i += 1;
+            }
+        }
+    }
+    out.join("\n")
+}
+
+/// Compact the traces of a file, or of stdin when `file` is `None`
+pub fn run(file: Option<&Path>, verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    let (content, source) = match file {
+        Some(file) => {
+            if verbose > 0 {
+                eprintln!("Compacting traces: {}", file.display());
+            }
+            let content = fs::read_to_string(file)
+                .with_context(|| format!("Failed to read file: {}", file.display()))?;
+            (content, file.display().to_string())
+        }
+        None => {
+            let mut content = String::new();
+            io::stdin()
+                .lock()
+                .read_to_string(&mut content)
+                .context("Failed to read from stdin")?;
+            (content, "-".to_string())
+        }
+    };
+
+    let output = compact_traces(&content);
+    println!("{}", output);
+
+    timer.track(&format!("cat {}", source), "rtk trace", &content, &output);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_java_trace_with_cause() {
+        let trace = "\
+Starting app
+Exception in thread \"main\" java.lang.IllegalStateException: cannot start
+\tat com.acme.App.start(App.java:42)
+\tat org.springframework.boot.SpringApplication.run(SpringApplication.java:300)
+\tat org.springframework.boot.SpringApplication.run(SpringApplication.java:310)
+\tat com.acme.Main.main(Main.java:10)
+Caused by: java.sql.SQLException: connection refused
+\tat org.apache.commons.dbcp2.PoolingDataSource.getConnection(PoolingDataSource.java:12)
+\tat com.acme.db.Pool.open(Pool.java:88)
+\t... 4 more
+Shutting down";
+        assert_eq!(
+            compact_traces(trace),
+            "\
+Starting app
+💥 java.lang.IllegalStateException: cannot start
+    at com.acme.App.start(App.java:42)
+    ... 2 framework frames
+    at com.acme.Main.main(Main.java:10)
+  ↳ Caused by: java.sql.SQLException: connection refused
+    ... 1 framework frame
+    at com.acme.db.Pool.open(Pool.java:88)
+    ... 4 framework frames
+Shutting down"
+        );
+    }
+
+    #[test]
+    fn test_python_trace_keeps_frames_nearest_the_error() {
+        let trace = "\
+Traceback (most recent call last):
+  File \"/app/main.py\", line 10, in <module>
+    main()
+  File \"/usr/lib/python3.11/site-packages/click/core.py\", line 1157, in __call__
+    return self.main(*args, **kwargs)
+  File \"/app/handlers.py\", line 22, in handle
+    value = int(raw)
+            ^^^^^^^^
+ValueError: invalid literal for int() with base 10: 'x'";
+        assert_eq!(
+            compact_traces(trace),
+            "\
+💥 ValueError: invalid literal for int() with base 10: 'x'
+    /app/main.py:10 in <module>
+    ... 1 framework frame
+    /app/handlers.py:22 in handle"
+        );
+    }
+
+    #[test]
+    fn test_rust_backtrace() {
+        let trace = "\
+thread 'main' panicked at src/main.rs:5:5:
+called `Option::unwrap()` on a `None` value
+stack backtrace:
+   0: rust_begin_unwind
+             at /rustc/abc/library/std/src/panicking.rs:645:5
+   1: core::panicking::panic_fmt
+             at /rustc/abc/library/core/src/panicking.rs:72:14
+   2: myapp::config::load
+             at ./src/config.rs:12:9
+   3: myapp::main
+             at ./src/main.rs:5:5
+   4: core::ops::function::FnOnce::call_once
+note: Some details are omitted, run with `RUST_BACKTRACE=full` for a verbose backtrace.
+done";
+        assert_eq!(
+            compact_traces(trace),
+            "\
+💥 panicked at src/main.rs:5:5: called `Option::unwrap()` on a `None` value
+    ... 2 framework frames
+    myapp::config::load at ./src/config.rs:12:9
+    myapp::main at ./src/main.rs:5:5
+    ... 1 framework frame
+done"
+        );
+    }
+
+    #[test]
+    fn test_node_trace() {
+        let trace = "\
+TypeError: Cannot read properties of undefined (reading 'id')
+    at getUser (/app/src/users.js:14:20)
+    at Layer.handle [as handle_request] (/app/node_modules/express/lib/router/layer.js:95:5)
+    at next (/app/node_modules/express/lib/router/route.js:149:13)
+    at process.processTicksAndRejections (node:internal/process/task_queues:95:5)";
+        assert_eq!(
+            compact_traces(trace),
+            "\
+💥 TypeError: Cannot read properties of undefined (reading 'id')
+    at getUser (/app/src/users.js:14:20)
+    ... 3 framework frames"
+        );
+    }
+
+    #[test]
+    fn test_short_or_absent_traces_are_kept() {
+        let text = "Error: config missing\n    at load (/app/x.js:1:1)\nok";
+        assert_eq!(compact_traces(text), text);
+        let panic =
+            "thread 'main' panicked at src/main.rs:5:5:\nboom\nnote: run with `RUST_BACKTRACE=1`";
+        assert_eq!(compact_traces(panic), panic);
+        assert!(!has_trace("plain output"));
+    }
+}
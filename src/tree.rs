@@ -7,11 +7,12 @@
 //! unless -a flag is present (respecting user intent).
 
 use crate::tracking;
+use crate::utils;
 use anyhow::{Context, Result};
 use std::process::Command;
 
 /// Noise directories commonly excluded from LLM context
-const NOISE_DIRS: &[&str] = &[
+pub(crate) const NOISE_DIRS: &[&str] = &[
     "node_modules",
     ".git",
     "target",
@@ -44,8 +45,7 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
     // Check if tree is installed
-    let tree_check = Command::new("which").arg("tree").output();
-    if tree_check.is_err() || !tree_check.unwrap().status.success() {
+    if !utils::tool_exists("tree") {
         anyhow::bail!(
             "tree command not found. Install it first:\n\
              - macOS: brew install tree\n\
@@ -0,0 +1,473 @@
+//! Native directory tree: walks with the `ignore` crate (respects .gitignore)
+//! and prints a depth-limited, size-annotated tree. Noise, gitignored and
+//! very large directories are collapsed to `name/ (N files, SIZE)` instead
+//! of listed, with or without `-a`.
+//!
+//! Flags the walker doesn't know are handed to the native `tree` proxy.
+
+use crate::layout;
+use crate::tracking;
+use crate::tree;
+use crate::utils::{format_bytes, truncate};
+use crate::walk;
+use anyhow::Result;
+use ignore::WalkBuilder;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+const DEFAULT_DEPTH: usize = 3;
+const DEFAULT_MAX_ENTRIES: usize = 20;
+
+/// Directories holding more files than this are summarized, not expanded
+const LARGE_DIR_FILES: usize = 1000;
+
+/// Names are never cut shorter than this
+const MIN_NAME_CHARS: usize = 12;
+
+#[derive(Debug, PartialEq)]
+struct TreeOptions {
+    max_depth: usize,
+    dirs_only: bool,
+    max_entries: usize,
+    show_all: bool,
+    /// Output columns; longer names are cut to fit
+    width: Option<usize>,
+}
+
+impl Default for TreeOptions {
+    fn default() -> Self {
+        Self {
+            max_depth: DEFAULT_DEPTH,
+            dirs_only: false,
+            max_entries: DEFAULT_MAX_ENTRIES,
+            show_all: false,
+            width: None,
+        }
+    }
+}
+
+#[derive(Default)]
+struct DirNode {
+    dirs: BTreeMap<String, DirNode>,
+    files: Vec<(String, u64)>,
+    total_files: usize,
+    total_bytes: u64,
+    /// Skipped by .gitignore: only the totals are known
+    ignored: bool,
+}
+
+impl DirNode {
+    /// Add a file at `components` (relative path), updating ancestor totals
+    fn insert_file(&mut self, components: &[&str], size: u64) {
+        self.total_files += 1;
+        self.total_bytes += size;
+        match components {
+            [] => {}
+            [name] => self.files.push((name.to_string(), size)),
+            [dir, rest @ ..] => self
+                .dirs
+                .entry(dir.to_string())
+                .or_default()
+                .insert_file(rest, size),
+        }
+    }
+
+    fn insert_dir(&mut self, components: &[&str]) {
+        if let [dir, rest @ ..] = components {
+            self.dirs
+                .entry(dir.to_string())
+                .or_default()
+                .insert_dir(rest);
+        }
+    }
+
+    /// A gitignored directory at `components`, with its totals
+    fn insert_ignored(&mut self, components: &[&str], files: usize, bytes: u64) {
+        self.total_files += files;
+        self.total_bytes += bytes;
+        match components {
+            [] => self.ignored = true,
+            [dir, rest @ ..] => self
+                .dirs
+                .entry(dir.to_string())
+                .or_default()
+                .insert_ignored(rest, files, bytes),
+        }
+    }
+
+    fn summary(&self) -> String {
+        format!(
+            "{} files, {}{}",
+            self.total_files,
+            format_bytes(self.total_bytes),
+            if self.ignored { ", ignored" } else { "" }
+        )
+    }
+}
+
+pub fn run(args: &[String], verbose: u8) -> Result<()> {
+    let Some((path, opts)) = parse_args(args) else {
+        // Native tree flags (-I, -P, --du, ...): keep the proxy behavior
+        return tree::run(args, verbose);
+    };
+
+    let timer = tracking::TimedExecution::start();
+    let opts = TreeOptions {
+        width: layout::width(),
+        ..opts
+    };
+
+    if verbose > 0 {
+        eprintln!("tree: {} (depth {})", path, opts.max_depth);
+    }
+
+    let (root, listing) = walk(&path, opts.show_all);
+    let filtered = render(&path, &root, &opts);
+
+    print!("{}", filtered);
+    timer.track(&format!("tree {}", path), "rtk tree", &listing, &filtered);
+
+    Ok(())
+}
+
+/// Parse the flags the walker supports. Returns `None` when a flag needs
+/// the native `tree` binary.
+fn parse_args(args: &[String]) -> Option<(String, TreeOptions)> {
+    let mut opts = TreeOptions::default();
+    let mut path: Option<String> = None;
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-L" | "--depth" => opts.max_depth = iter.next()?.parse().ok()?,
+            "-d" | "--dirs-only" => opts.dirs_only = true,
+            "-a" | "--all" => opts.show_all = true,
+            "--max-entries" => opts.max_entries = iter.next()?.parse().ok()?,
+            a if a.starts_with("--depth=") => {
+                opts.max_depth = a["--depth=".len()..].parse().ok()?
+            }
+            a if a.starts_with("--max-entries=") => {
+                opts.max_entries = a["--max-entries=".len()..].parse().ok()?
+            }
+            a if a.starts_with('-') => return None,
+            a => {
+                if path.is_some() {
+                    return None;
+                }
+                path = Some(a.to_string());
+            }
+        }
+    }
+
+    Some((path.unwrap_or_else(|| ".".to_string()), opts))
+}
+
+/// Walk `path` into a tree, also returning the flat listing of every entry
+/// (what a plain recursive listing would have printed).
+fn walk(path: &str, show_all: bool) -> (DirNode, String) {
+    let mut walker = WalkBuilder::new(path);
+    walker
+        .hidden(!show_all)
+        .git_ignore(!show_all)
+        .git_global(!show_all)
+        .git_exclude(!show_all);
+
+    // Sizes are read on the walker threads; the tree is built in path order
+    let walked = walk::walk(&walker, |entry| {
+        let rel = entry.path().strip_prefix(path).ok()?;
+        if rel.as_os_str().is_empty() {
+            return None;
+        }
+        let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+        let size = if is_dir {
+            None
+        } else {
+            Some(entry.metadata().map(|m| m.len()).unwrap_or(0))
+        };
+        Some((rel.to_string_lossy().to_string(), size))
+    });
+
+    let mut root = DirNode::default();
+    let mut listing = String::new();
+    let mut dirs = vec![PathBuf::new()];
+
+    for (rel_str, size) in walked.items {
+        let components: Vec<&str> = rel_str.split(std::path::MAIN_SEPARATOR).collect();
+        match size {
+            Some(size) => root.insert_file(&components, size),
+            None => {
+                root.insert_dir(&components);
+                dirs.push(PathBuf::from(&rel_str));
+            }
+        }
+
+        listing.push_str(&rel_str);
+        listing.push('\n');
+    }
+
+    // With -a nothing is skipped; otherwise .gitignore dropped whole
+    // directories the reader should still know about
+    if !show_all {
+        for rel in ignored_dirs(Path::new(path), &dirs) {
+            let (files, bytes) = dir_totals(&Path::new(path).join(&rel));
+            let components: Vec<String> = rel
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().to_string())
+                .collect();
+            let components: Vec<&str> = components.iter().map(String::as_str).collect();
+            root.insert_ignored(&components, files, bytes);
+        }
+    }
+
+    (root, listing)
+}
+
+/// Subdirectories of the walked `dirs` (relative to `root`) that the walk
+/// skipped: gitignored ones. Hidden directories stay out, as without -a.
+fn ignored_dirs(root: &Path, dirs: &[PathBuf]) -> Vec<PathBuf> {
+    let walked: HashSet<&PathBuf> = dirs.iter().collect();
+    let mut ignored = Vec::new();
+    for dir in dirs {
+        let Ok(entries) = std::fs::read_dir(root.join(dir)) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let is_dir = entry.file_type().is_ok_and(|t| t.is_dir());
+            let rel = dir.join(entry.file_name());
+            if is_dir
+                && !entry.file_name().to_string_lossy().starts_with('.')
+                && !walked.contains(&rel)
+            {
+                ignored.push(rel);
+            }
+        }
+    }
+    ignored.sort();
+    ignored
+}
+
+/// Files and bytes under `dir`, ignore files and all
+fn dir_totals(dir: &Path) -> (usize, u64) {
+    let mut walker = WalkBuilder::new(dir);
+    walker.standard_filters(false);
+    let sizes = walk::walk(&walker, |entry| {
+        let is_file = entry.file_type().is_some_and(|t| t.is_file());
+        is_file.then(|| entry.metadata().map(|m| m.len()).unwrap_or(0))
+    });
+    (sizes.items.len(), sizes.items.iter().sum())
+}
+
+fn is_noise_dir(name: &str) -> bool {
+    tree::NOISE_DIRS
+        .iter()
+        .any(|noise| match noise.strip_prefix('*') {
+            Some(suffix) => name.ends_with(suffix),
+            None => name == *noise,
+        })
+}
+
+fn render(path: &str, root: &DirNode, opts: &TreeOptions) -> String {
+    if root.total_files == 0 && root.dirs.is_empty() {
+        return "(empty)\n".to_string();
+    }
+
+    let mut out = format!("{}/ ({})\n", path.trim_end_matches('/'), root.summary());
+    render_children(root, 1, opts, &mut out);
+    out
+}
+
+fn render_children(node: &DirNode, depth: usize, opts: &TreeOptions, out: &mut String) {
+    let indent = "  ".repeat(depth);
+
+    let mut files: Vec<&(String, u64)> = if opts.dirs_only {
+        Vec::new()
+    } else {
+        node.files.iter().collect()
+    };
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let total = node.dirs.len() + files.len();
+    let mut shown = 0;
+
+    for (name, child) in &node.dirs {
+        if shown == opts.max_entries {
+            break;
+        }
+        shown += 1;
+
+        // Noise stays collapsed under -a too: .git and node_modules are
+        // never what a tree is asked for
+        let collapsed = depth >= opts.max_depth
+            || child.ignored
+            || child.total_files > LARGE_DIR_FILES
+            || is_noise_dir(name);
+        let summary = child.summary();
+        let used = indent.len() + summary.len() + 4;
+        out.push_str(&format!(
+            "{}{}/ ({})\n",
+            indent,
+            fit_name(name, used, opts.width),
+            summary
+        ));
+        if !collapsed {
+            render_children(child, depth + 1, opts, out);
+        }
+    }
+
+    for (name, size) in files.iter().take(opts.max_entries - shown) {
+        let size = format_bytes(*size);
+        let used = indent.len() + size.len() + 1;
+        out.push_str(&format!(
+            "{}{} {}\n",
+            indent,
+            fit_name(name, used, opts.width),
+            size
+        ));
+        shown += 1;
+    }
+
+    if total > shown {
+        out.push_str(&format!("{}... +{} more\n", indent, total - shown));
+    }
+}
+
+/// `name` cut so that the line, with `used` columns of indent and details,
+/// fits in `width`. Names keep at least a few chars on narrow terminals.
+fn fit_name(name: &str, used: usize, width: Option<usize>) -> String {
+    match width {
+        Some(width) => truncate(name, width.saturating_sub(used).max(MIN_NAME_CHARS)),
+        None => name.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(String::from).collect()
+    }
+
+    fn sample() -> DirNode {
+        let mut root = DirNode::default();
+        root.insert_file(&["Cargo.toml"], 1024);
+        root.insert_file(&["src", "main.rs"], 2048);
+        root.insert_file(&["src", "cli", "args.rs"], 512);
+        for i in 0..5 {
+            root.insert_file(&["node_modules", "pkg", &format!("f{}.js", i)], 100);
+        }
+        root
+    }
+
+    #[test]
+    fn test_parse_args_walker_flags() {
+        let (path, opts) = parse_args(&args("-L 2 -d --max-entries=5 src")).unwrap();
+        assert_eq!(path, "src");
+        assert_eq!(opts.max_depth, 2);
+        assert!(opts.dirs_only);
+        assert_eq!(opts.max_entries, 5);
+    }
+
+    #[test]
+    fn test_parse_args_native_flag_falls_back() {
+        assert!(parse_args(&args("-I target")).is_none());
+        assert!(parse_args(&args("--du")).is_none());
+        assert_eq!(parse_args(&[]).unwrap().0, ".");
+    }
+
+    #[test]
+    fn test_render_sizes_and_noise_collapsed() {
+        let out = render(".", &sample(), &TreeOptions::default());
+        assert!(out.starts_with("./ (8 files, 4.0KB)\n"));
+        assert!(out.contains("  src/ (2 files, 2.5KB)\n"));
+        assert!(out.contains("    main.rs 2.0KB\n"));
+        assert!(out.contains("  Cargo.toml 1.0KB\n"));
+        assert!(out.contains("  node_modules/ (5 files, 500B)\n"));
+        assert!(!out.contains("f0.js"));
+    }
+
+    #[test]
+    fn test_render_depth_and_dirs_only() {
+        let opts = TreeOptions {
+            max_depth: 1,
+            dirs_only: true,
+            ..Default::default()
+        };
+        let out = render(".", &sample(), &opts);
+        assert!(out.contains("  src/ (2 files, 2.5KB)\n"));
+        assert!(!out.contains("cli/"));
+        assert!(!out.contains("Cargo.toml"));
+    }
+
+    #[test]
+    fn test_render_cuts_long_names_to_width() {
+        let mut root = DirNode::default();
+        let long = format!("{}.rs", "very_long_module_name".repeat(3));
+        root.insert_file(&[&long], 2048);
+        let opts = TreeOptions {
+            width: Some(40),
+            ..Default::default()
+        };
+        let out = render(".", &root, &opts);
+        let line = out.lines().nth(1).unwrap();
+        assert_eq!(line.chars().count(), 40);
+        assert!(line.ends_with("... 2.0KB"));
+
+        let out = render(".", &root, &TreeOptions::default());
+        assert!(out.contains(&long));
+    }
+
+    #[test]
+    fn test_render_max_entries() {
+        let mut root = DirNode::default();
+        for i in 0..5 {
+            root.insert_file(&[&format!("file{}.txt", i)], 10);
+        }
+        let opts = TreeOptions {
+            max_entries: 2,
+            ..Default::default()
+        };
+        let out = render(".", &root, &opts);
+        assert!(out.contains("file1.txt"));
+        assert!(!out.contains("file2.txt"));
+        assert!(out.contains("  ... +3 more\n"));
+    }
+
+    #[test]
+    fn test_walk_respects_gitignore() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join(".git")).unwrap();
+        std::fs::write(dir.path().join(".gitignore"), "ignored/\n").unwrap();
+        std::fs::create_dir(dir.path().join("ignored")).unwrap();
+        std::fs::write(dir.path().join("ignored/a.txt"), "x").unwrap();
+        std::fs::write(dir.path().join("kept.txt"), "hello").unwrap();
+
+        let path = dir.path().to_string_lossy().to_string();
+        let (root, listing) = walk(&path, false);
+        assert_eq!(root.files, vec![("kept.txt".to_string(), 5)]);
+        assert!(listing.contains("kept.txt"));
+
+        // Ignored directories are summarized, not dropped
+        let ignored = &root.dirs["ignored"];
+        assert!(ignored.ignored);
+        assert_eq!((ignored.total_files, ignored.total_bytes), (1, 1));
+        assert!(ignored.files.is_empty());
+        assert!(!listing.contains("a.txt"));
+        assert!(!root.dirs.contains_key(".git"));
+        let out = render(".", &root, &TreeOptions::default());
+        assert!(out.contains("  ignored/ (1 files, 1B, ignored)\n"));
+        assert!(!out.contains("a.txt"));
+    }
+
+    #[test]
+    fn test_show_all_keeps_noise_collapsed() {
+        let opts = TreeOptions {
+            show_all: true,
+            ..Default::default()
+        };
+        let out = render(".", &sample(), &opts);
+        assert!(out.contains("  node_modules/ (5 files, 500B)\n"));
+        assert!(!out.contains("f0.js"));
+        assert!(!out.contains("pkg/"));
+    }
+}
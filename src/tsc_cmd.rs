@@ -1,24 +1,19 @@
 use crate::tracking;
-use crate::utils::truncate;
+use crate::utils::{self, truncate};
 use anyhow::{Context, Result};
 use regex::Regex;
 use std::collections::HashMap;
-use std::process::Command;
 
 pub fn run(args: &[String], verbose: u8) -> Result<()> {
     let timer = tracking::TimedExecution::start();
 
     // Try tsc directly first, fallback to npx if not found
-    let tsc_exists = Command::new("which")
-        .arg("tsc")
-        .output()
-        .map(|o| o.status.success())
-        .unwrap_or(false);
+    let tsc_exists = utils::tool_exists("tsc");
 
     let mut cmd = if tsc_exists {
-        Command::new("tsc")
+        utils::tool_command("tsc")
     } else {
-        let mut c = Command::new("npx");
+        let mut c = utils::tool_command("npx");
         c.arg("tsc");
         c
     };
@@ -35,8 +30,7 @@ pub fn run(args: &[String], verbose: u8) -> Result<()> {
     let output = cmd
         .output()
         .context("Failed to run tsc (try: npm install -g typescript)")?;
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    let (stdout, stderr) = crate::output::captured(&output);
     let raw = format!("{}\n{}", stdout, stderr);
 
     let filtered = filter_tsc_output(&raw);
@@ -7,6 +7,7 @@
 
 use anyhow::{Context, Result};
 use regex::Regex;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 /// Tronque une chaîne à `max_len` caractères avec "..." si nécessaire.
@@ -16,7 +17,7 @@ use std::process::Command;
 /// * `max_len` - Longueur maximale avant troncature (minimum 3 pour inclure "...")
 ///
 /// # Examples
-/// ```
+/// ```ignore
 /// use rtk::utils::truncate;
 /// assert_eq!(truncate("hello world", 8), "hello...");
 /// assert_eq!(truncate("hi", 10), "hi");
@@ -33,20 +34,89 @@ pub fn truncate(s: &str, max_len: usize) -> String {
     }
 }
 
+/// Split text into user-perceived characters: a base char together with the
+/// combining marks, variation selectors, skin tones and zero-width-joined
+/// chars following it, and flags as pairs of regional indicators.
+///
+/// An approximation of Unicode grapheme clusters, without the full tables:
+/// enough to never cut an accent, a Thai vowel or an emoji sequence apart.
+///
+/// # Examples
+/// ```ignore
+/// use rtk::utils::clusters;
+/// assert_eq!(clusters("e\u{301}t\u{e9}"), ["e\u{301}", "t", "\u{e9}"]);
+/// assert_eq!(clusters("👍🏽🇫🇷").len(), 2);
+/// ```
+pub fn clusters(s: &str) -> Vec<&str> {
+    let mut clusters = Vec::new();
+    let mut start = 0;
+    let mut after_joiner = false;
+    // Regional indicators seen in the current cluster
+    let mut indicators = 0;
+
+    for (i, c) in s.char_indices() {
+        let indicator = is_regional_indicator(c);
+        let joins = after_joiner || extends_cluster(c) || (indicator && indicators == 1);
+        if i > 0 && !joins {
+            clusters.push(&s[start..i]);
+            start = i;
+            indicators = 0;
+        }
+        if indicator {
+            indicators += 1;
+        }
+        after_joiner = c == '\u{200D}';
+    }
+    if start < s.len() {
+        clusters.push(&s[start..]);
+    }
+    clusters
+}
+
+fn is_regional_indicator(c: char) -> bool {
+    matches!(c as u32, 0x1F1E6..=0x1F1FF)
+}
+
+/// Chars that belong to the cluster of the char before them
+fn extends_cluster(c: char) -> bool {
+    matches!(
+        c as u32,
+        // Combining diacritical marks
+        0x0300..=0x036F | 0x1AB0..=0x1AFF | 0x1DC0..=0x1DFF | 0x20D0..=0x20FF | 0xFE20..=0xFE2F
+        // Zero-width non-joiner and joiner
+        | 0x200C | 0x200D
+        // Variation selectors
+        | 0xFE00..=0xFE0F | 0xE0100..=0xE01EF
+        // Emoji skin tones and tag sequences
+        | 0x1F3FB..=0x1F3FF | 0xE0020..=0xE007F
+        // Thai vowels and tone marks
+        | 0x0E31 | 0x0E34..=0x0E3A | 0x0E47..=0x0E4E
+        // Devanagari vowel signs and virama
+        | 0x0900..=0x0903 | 0x093A..=0x094F | 0x0951..=0x0957 | 0x0962 | 0x0963
+        // Kana voiced sound marks
+        | 0x3099 | 0x309A
+    )
+}
+
 /// Supprime les codes ANSI d'une chaîne (couleurs, styles).
 ///
 /// # Arguments
 /// * `text` - Texte contenant potentiellement des codes ANSI
 ///
 /// # Examples
-/// ```
+/// ```ignore
 /// use rtk::utils::strip_ansi;
 /// let colored = "\x1b[31mError\x1b[0m";
 /// assert_eq!(strip_ansi(colored), "Error");
 /// ```
 pub fn strip_ansi(text: &str) -> String {
     lazy_static::lazy_static! {
-        static ref ANSI_RE: Regex = Regex::new(r"\x1b\[[0-9;]*[a-zA-Z]").unwrap();
+        // CSI sequences (colors, cursor moves), OSC sequences (titles,
+        // hyperlinks) ended by BEL or ST, and short escapes (charset, cursor save)
+        static ref ANSI_RE: Regex = Regex::new(
+            r"\x1b\[[0-?]*[ -/]*[@-~]|\x1b\][^\x07\x1b]*(?:\x07|\x1b\\)|\x1b[ -/]*[0-~]"
+        )
+        .unwrap();
     }
     ANSI_RE.replace_all(text, "").to_string()
 }
@@ -61,7 +131,7 @@ pub fn strip_ansi(text: &str) -> String {
 /// `(stdout: String, stderr: String, exit_code: i32)`
 ///
 /// # Examples
-/// ```no_run
+/// ```ignore
 /// use rtk::utils::execute_command;
 /// let (stdout, stderr, code) = execute_command("echo", &["test"]).unwrap();
 /// assert_eq!(code, 0);
@@ -80,6 +150,34 @@ pub fn execute_command(cmd: &str, args: &[&str]) -> Result<(String, String, i32)
     Ok((stdout, stderr, exit_code))
 }
 
+/// Exit code of a finished child: its own, or 128 + the signal that
+/// killed it, as a shell reports it.
+pub fn exit_code(status: std::process::ExitStatus) -> i32 {
+    if let Some(code) = status.code() {
+        return code;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return 128 + signal;
+        }
+    }
+    1
+}
+
+/// End rtk with the wrapped tool's exit code, so scripts and agents can
+/// branch on it (grep: 0 matched, 1 no match, 2 error; test runners: 1
+/// on failures). With `-v` the code is printed either way.
+pub fn exit_with(tool: &str, code: i32, verbose: u8) {
+    if verbose > 0 {
+        eprintln!("{} exited with {}", tool, code);
+    }
+    if code != 0 {
+        std::process::exit(code);
+    }
+}
+
 /// Formate un nombre de tokens avec suffixes K/M pour lisibilité.
 ///
 /// # Arguments
@@ -89,7 +187,7 @@ pub fn execute_command(cmd: &str, args: &[&str]) -> Result<(String, String, i32)
 /// String formaté (ex: "1.2M", "59.2K", "694")
 ///
 /// # Examples
-/// ```
+/// ```ignore
 /// use rtk::utils::format_tokens;
 /// assert_eq!(format_tokens(1_234_567), "1.2M");
 /// assert_eq!(format_tokens(59_234), "59.2K");
@@ -105,6 +203,27 @@ pub fn format_tokens(n: usize) -> String {
     }
 }
 
+/// Format a byte count with B/KB/MB/GB suffixes.
+///
+/// # Examples
+/// ```ignore
+/// use rtk::utils::format_bytes;
+/// assert_eq!(format_bytes(512), "512B");
+/// assert_eq!(format_bytes(2048), "2.0KB");
+/// assert_eq!(format_bytes(5_242_880), "5.0MB");
+/// ```
+pub fn format_bytes(bytes: u64) -> String {
+    if bytes < 1024 {
+        format!("{}B", bytes)
+    } else if bytes < 1024 * 1024 {
+        format!("{:.1}KB", bytes as f64 / 1024.0)
+    } else if bytes < 1024 * 1024 * 1024 {
+        format!("{:.1}MB", bytes as f64 / (1024.0 * 1024.0))
+    } else {
+        format!("{:.1}GB", bytes as f64 / (1024.0 * 1024.0 * 1024.0))
+    }
+}
+
 /// Formate un montant USD avec précision adaptée.
 ///
 /// # Arguments
@@ -114,7 +233,7 @@ pub fn format_tokens(n: usize) -> String {
 /// String formaté avec $ prefix
 ///
 /// # Examples
-/// ```
+/// ```ignore
 /// use rtk::utils::format_usd;
 /// assert_eq!(format_usd(1234.567), "$1234.57");
 /// assert_eq!(format_usd(12.345), "$12.35");
@@ -141,7 +260,7 @@ pub fn format_usd(amount: f64) -> String {
 /// Formatted string like "$3.86/MTok"
 ///
 /// # Examples
-/// ```
+/// ```ignore
 /// use rtk::utils::format_cpt;
 /// assert_eq!(format_cpt(0.000003), "$3.00/MTok");
 /// assert_eq!(format_cpt(0.0000038), "$3.80/MTok");
@@ -159,7 +278,7 @@ pub fn format_cpt(cpt: f64) -> String {
 /// Used for write operations (merge, create, comment, edit, etc.)
 ///
 /// # Examples
-/// ```
+/// ```ignore
 /// use rtk::utils::ok_confirmation;
 /// assert_eq!(ok_confirmation("merged", "#42"), "ok merged #42");
 /// assert_eq!(ok_confirmation("created", "PR #5 https://..."), "ok created PR #5 https://...");
@@ -176,7 +295,7 @@ pub fn ok_confirmation(action: &str, detail: &str) -> String {
 /// Returns "pnpm", "yarn", or "npm" based on lockfile presence.
 ///
 /// # Examples
-/// ```no_run
+/// ```ignore
 /// use rtk::utils::detect_package_manager;
 /// let pm = detect_package_manager();
 /// // Returns "pnpm" if pnpm-lock.yaml exists, "yarn" if yarn.lock, else "npm"
@@ -192,32 +311,96 @@ pub fn detect_package_manager() -> &'static str {
     }
 }
 
+/// Executable extensions tried on Windows (`PATHEXT`); none elsewhere
+fn path_extensions() -> Vec<String> {
+    if !cfg!(windows) {
+        return Vec::new();
+    }
+    std::env::var("PATHEXT")
+        .unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string())
+        .split(';')
+        .filter(|e| !e.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Where `tool` is installed on `PATH`, found in process: `which` does not
+/// exist on Windows, where `tsc` is really `tsc.cmd`
+pub fn find_in_path(tool: &str) -> Option<PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    find_in_dirs(tool, std::env::split_paths(&path))
+}
+
+/// Where `tool` is in the first of `dirs` that has it, looked up as
+/// [`find_in_path`] does
+pub fn find_in_dirs(tool: &str, dirs: impl IntoIterator<Item = PathBuf>) -> Option<PathBuf> {
+    find_in(tool, dirs, &path_extensions())
+}
+
+fn find_in(
+    tool: &str,
+    dirs: impl IntoIterator<Item = PathBuf>,
+    extensions: &[String],
+) -> Option<PathBuf> {
+    dirs.into_iter().find_map(|dir| {
+        let exact = dir.join(tool);
+        if is_executable(&exact) {
+            return Some(exact);
+        }
+        extensions
+            .iter()
+            .map(|ext| dir.join(format!("{}{}", tool, ext)))
+            .find(|candidate| candidate.is_file())
+    })
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// Whether `tool` is installed on `PATH`
+pub fn tool_exists(tool: &str) -> bool {
+    find_in_path(tool).is_some()
+}
+
+/// A Command running `tool`. On Windows it runs the resolved file, since
+/// spawning a bare name only finds `.exe` files and npm tools are `.cmd`
+/// shims.
+pub fn tool_command(tool: &str) -> Command {
+    match find_in_path(tool) {
+        Some(path) if cfg!(windows) => Command::new(path),
+        _ => Command::new(tool),
+    }
+}
+
 /// Build a Command using the detected package manager's exec mechanism.
 /// Returns a Command ready to have tool-specific args appended.
 pub fn package_manager_exec(tool: &str) -> Command {
-    let tool_exists = Command::new("which")
-        .arg(tool)
-        .output()
-        .map(|o| o.status.success())
-        .unwrap_or(false);
-
-    if tool_exists {
-        Command::new(tool)
+    if tool_exists(tool) {
+        tool_command(tool)
     } else {
         let pm = detect_package_manager();
         match pm {
             "pnpm" => {
-                let mut c = Command::new("pnpm");
+                let mut c = tool_command("pnpm");
                 c.arg("exec").arg("--").arg(tool);
                 c
             }
             "yarn" => {
-                let mut c = Command::new("yarn");
+                let mut c = tool_command("yarn");
                 c.arg("exec").arg("--").arg(tool);
                 c
             }
             _ => {
-                let mut c = Command::new("npx");
+                let mut c = tool_command("npx");
                 c.arg("--no-install").arg("--").arg(tool);
                 c
             }
@@ -255,6 +438,22 @@ mod tests {
         assert_eq!(truncate("hello world", 3), "...");
     }
 
+    #[test]
+    fn test_clusters_keep_sequences_whole() {
+        assert_eq!(clusters("abc"), ["a", "b", "c"]);
+        assert_eq!(clusters(""), Vec::<&str>::new());
+        // Family emoji: three people joined by ZWJ
+        assert_eq!(
+            clusters("👨\u{200D}👩\u{200D}👧!"),
+            ["👨\u{200D}👩\u{200D}👧", "!"]
+        );
+        // Two flags, each a pair of regional indicators
+        assert_eq!(clusters("🇫🇷🇩🇪"), ["🇫🇷", "🇩🇪"]);
+        assert_eq!(clusters("❤\u{FE0F}x"), ["❤\u{FE0F}", "x"]);
+        assert_eq!(clusters("ที่"), ["ที่"]);
+        assert_eq!(clusters("中文"), ["中", "文"]);
+    }
+
     #[test]
     fn test_strip_ansi_simple() {
         let input = "\x1b[31mError\x1b[0m";
@@ -278,6 +477,12 @@ mod tests {
         assert_eq!(strip_ansi(input), "Green normal Red");
     }
 
+    #[test]
+    fn test_strip_ansi_osc_and_cursor() {
+        let input = "\x1b]8;;https://x.test\x07link\x1b]8;;\x07 \x1b[2K\x1b[1Gdone\x1b(B";
+        assert_eq!(strip_ansi(input), "link done");
+    }
+
     #[test]
     fn test_execute_command_success() {
         let result = execute_command("echo", &["test"]);
@@ -311,6 +516,15 @@ mod tests {
         assert_eq!(format_tokens(0), "0");
     }
 
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(0), "0B");
+        assert_eq!(format_bytes(1023), "1023B");
+        assert_eq!(format_bytes(1536), "1.5KB");
+        assert_eq!(format_bytes(5_242_880), "5.0MB");
+        assert_eq!(format_bytes(3 * 1024 * 1024 * 1024), "3.0GB");
+    }
+
     #[test]
     fn test_format_usd_large() {
         assert_eq!(format_usd(1234.567), "$1234.57");
@@ -395,4 +609,27 @@ mod tests {
         let result = truncate(cjk, 6);
         assert!(result.ends_with("..."));
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_exit_code_of_child() {
+        let status = |script| Command::new("sh").args(["-c", script]).status().unwrap();
+        assert_eq!(exit_code(status("exit 0")), 0);
+        assert_eq!(exit_code(status("exit 3")), 3);
+        assert_eq!(exit_code(status("kill -9 $$")), 137);
+    }
+
+    #[test]
+    fn test_find_in_path() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("tsc.cmd"), "@echo off").unwrap();
+        let path = [PathBuf::from("/nonexistent"), dir.path().to_path_buf()];
+        let windows = [".exe".to_string(), ".cmd".to_string()];
+        assert_eq!(
+            find_in("tsc", path.clone(), &windows),
+            Some(dir.path().join("tsc.cmd"))
+        );
+        assert_eq!(find_in("tsc", path.clone(), &[]), None);
+        assert_eq!(find_in("eslint", path, &windows), None);
+    }
 }
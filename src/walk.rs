@@ -0,0 +1,129 @@
+//! Parallel directory walks: entries are visited on every core by the
+//! `ignore` crate's walker, then merged back in path order so the output
+//! never depends on thread scheduling.
+
+use ignore::{DirEntry, WalkBuilder, WalkState};
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::thread;
+
+/// Results of a walk, in path order
+#[derive(Debug)]
+pub struct Walked<T> {
+    pub items: Vec<T>,
+    /// Entries that could not be read, as `ignore` words them
+    pub errors: Vec<String>,
+}
+
+/// Walk `builder`'s roots in parallel, keeping what `visit` returns for
+/// each entry. `visit` runs on the walker threads, so per-file work (reading,
+/// parsing) is sharded along with the walk.
+pub fn walk<T, F>(builder: &WalkBuilder, visit: F) -> Walked<T>
+where
+    T: Send,
+    F: Fn(&DirEntry) -> Option<T> + Sync,
+{
+    let (tx, rx) = mpsc::channel();
+    builder.build_parallel().run(|| {
+        let tx = tx.clone();
+        let visit = &visit;
+        Box::new(move |entry| {
+            let item = match entry {
+                Ok(entry) => visit(&entry).map(|item| Ok((entry.into_path(), item))),
+                Err(e) => Some(Err(e.to_string())),
+            };
+            if let Some(item) = item {
+                // The receiver outlives the walk: sending cannot fail
+                let _ = tx.send(item);
+            }
+            WalkState::Continue
+        })
+    });
+    drop(tx);
+
+    let mut items: Vec<(PathBuf, T)> = Vec::new();
+    let mut errors = Vec::new();
+    for item in rx {
+        match item {
+            Ok(item) => items.push(item),
+            Err(e) => errors.push(e),
+        }
+    }
+    items.sort_by(|a, b| a.0.cmp(&b.0));
+    errors.sort();
+
+    Walked {
+        items: items.into_iter().map(|(_, item)| item).collect(),
+        errors,
+    }
+}
+
+/// Threads available for sharded work: one per core
+pub fn threads() -> usize {
+    thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+/// Apply `work` to `jobs` on up to one thread per core; results come back
+/// in the order of `jobs`
+pub fn shard<J, T, F>(jobs: &[J], work: F) -> Vec<T>
+where
+    J: Sync,
+    T: Send,
+    F: Fn(&J) -> T + Sync,
+{
+    let cores = threads();
+    if jobs.len() <= 1 || cores == 1 {
+        return jobs.iter().map(work).collect();
+    }
+
+    let per_thread = jobs.len().div_ceil(cores);
+    thread::scope(|scope| {
+        let handles: Vec<_> = jobs
+            .chunks(per_thread)
+            .map(|chunk| scope.spawn(|| chunk.iter().map(&work).collect::<Vec<T>>()))
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("walk shard panicked"))
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_walk_is_sorted() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["b/z.txt", "b/a.txt", "a/m.txt", "c.txt", "a/n/deep.txt"] {
+            let path = dir.path().join(name);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "x").unwrap();
+        }
+
+        let walked = walk(&WalkBuilder::new(dir.path()), |entry| {
+            let is_file = entry.file_type().is_some_and(|t| t.is_file());
+            is_file.then(|| {
+                let rel = entry.path().strip_prefix(dir.path()).unwrap();
+                rel.to_string_lossy().to_string()
+            })
+        });
+        assert_eq!(
+            walked.items,
+            vec!["a/m.txt", "a/n/deep.txt", "b/a.txt", "b/z.txt", "c.txt"]
+        );
+        assert!(walked.errors.is_empty());
+    }
+
+    #[test]
+    fn test_shard_keeps_job_order() {
+        let jobs: Vec<usize> = (0..100).collect();
+        assert_eq!(
+            shard(&jobs, |n| n * 2),
+            (0..100).map(|n| n * 2).collect::<Vec<_>>()
+        );
+        assert!(shard(&Vec::<usize>::new(), |n| *n).is_empty());
+    }
+}
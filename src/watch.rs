@@ -0,0 +1,247 @@
+//! `--watch` for grep and test: rerun the same rtk command whenever a file
+//! changes, and print only what changed in its output since the previous
+//! run (new matches, newly failing tests), so a long session can poll it
+//! cheaply.
+//!
+//! Changes under gitignored paths (build output, `node_modules`) and
+//! hidden directories such as `.git` are not watched: a test run writing
+//! to `target/` must not trigger the next one.
+
+use anyhow::{Context, Result};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::collections::{BTreeSet, HashSet};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// Changes closer together than this trigger a single rerun
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// New lines shown per rerun
+const MAX_NEW_LINES: usize = 50;
+
+/// Run the current rtk command (without `--watch`) now, then again on every
+/// change below the current directory. Only returns on error.
+pub fn run(verbose: u8) -> Result<()> {
+    let args = child_args(std::env::args_os().skip(1));
+    let exe = std::env::current_exe().context("Cannot locate the rtk binary")?;
+    let root = std::env::current_dir()
+        .and_then(|dir| dir.canonicalize())
+        .context("Cannot resolve the current directory")?;
+    let ignored = ignore_rules(&root);
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx).context("Failed to start file watcher")?;
+    watcher
+        .watch(&root, RecursiveMode::Recursive)
+        .with_context(|| format!("Failed to watch {}", root.display()))?;
+
+    let mut previous = rerun(&exe, &args)?;
+    print!("{}", previous);
+    eprintln!("👀 watching {} (Ctrl-C to stop)", root.display());
+
+    loop {
+        let first = rx.recv().context("File watcher stopped")?;
+        let mut changed = relevant_paths(first, &root, &ignored);
+        if changed.is_empty() {
+            continue;
+        }
+        while let Ok(event) = rx.recv_timeout(DEBOUNCE) {
+            changed.extend(relevant_paths(event, &root, &ignored));
+        }
+        if verbose > 0 {
+            eprintln!("Changed: {:?}", changed);
+        }
+
+        let current = rerun(&exe, &args)?;
+        println!("{}", format_delta(&changed, &previous, &current));
+        previous = current;
+    }
+}
+
+/// The command line again, minus the `--watch` that brought us here
+fn child_args(args: impl Iterator<Item = OsString>) -> Vec<OsString> {
+    let mut removed = false;
+    args.filter(|arg| {
+        let watch = !removed && arg == "--watch";
+        removed |= watch;
+        !watch
+    })
+    .collect()
+}
+
+fn rerun(exe: &Path, args: &[OsString]) -> Result<String> {
+    let output = Command::new(exe)
+        .args(args)
+        .output()
+        .context("Failed to rerun rtk")?;
+    let mut text = String::from_utf8_lossy(&output.stdout).to_string();
+    text.push_str(&String::from_utf8_lossy(&output.stderr));
+    Ok(text)
+}
+
+/// The root `.gitignore`; an unreadable one ignores nothing
+fn ignore_rules(root: &Path) -> Gitignore {
+    let mut builder = GitignoreBuilder::new(root);
+    builder.add(root.join(".gitignore"));
+    builder.build().unwrap_or_else(|_| Gitignore::empty())
+}
+
+/// Paths of a content change worth a rerun, relative to `root`
+fn relevant_paths(
+    event: notify::Result<notify::Event>,
+    root: &Path,
+    ignored: &Gitignore,
+) -> Vec<PathBuf> {
+    let Ok(event) = event else {
+        return Vec::new();
+    };
+    if matches!(event.kind, EventKind::Access(_) | EventKind::Other) {
+        return Vec::new();
+    }
+    event
+        .paths
+        .into_iter()
+        .filter_map(|path| {
+            let rel = path.strip_prefix(root).ok()?.to_path_buf();
+            let hidden = rel
+                .components()
+                .any(|c| c.as_os_str().to_string_lossy().starts_with('.'));
+            let is_dir = path.is_dir();
+            let skip = hidden
+                || ignored
+                    .matched_path_or_any_parents(&rel, is_dir)
+                    .is_ignore();
+            (!skip).then_some(rel)
+        })
+        .collect()
+}
+
+/// Lines of `current` that `previous` did not have, each indented line
+/// preceded by its section header (the file of a match, the suite of a
+/// test) unless that header is new too. Also returns how many lines of
+/// `previous` are gone.
+fn delta(previous: &str, current: &str) -> (Vec<String>, usize) {
+    let before: HashSet<&str> = previous.lines().collect();
+    let after: HashSet<&str> = current.lines().collect();
+
+    let mut shown = Vec::new();
+    let mut header: Option<&str> = None;
+    let mut header_shown = false;
+    for line in current.lines() {
+        let indented = line.starts_with(char::is_whitespace);
+        if !indented {
+            header = Some(line);
+            header_shown = false;
+        }
+        if before.contains(line) || line.trim().is_empty() {
+            continue;
+        }
+        if indented && !header_shown {
+            if let Some(header) = header {
+                shown.push(header.to_string());
+            }
+        }
+        header_shown = true;
+        shown.push(line.to_string());
+    }
+
+    let gone = previous
+        .lines()
+        .filter(|line| !line.trim().is_empty() && !after.contains(line))
+        .count();
+    (shown, gone)
+}
+
+fn format_delta(changed: &[PathBuf], previous: &str, current: &str) -> String {
+    let (new, gone) = delta(previous, current);
+    let mut names: Vec<String> = changed
+        .iter()
+        .map(|p| p.display().to_string())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    let more = names.len().saturating_sub(3);
+    names.truncate(3);
+    let mut trigger = names.join(", ");
+    if more > 0 {
+        trigger.push_str(&format!(" +{}", more));
+    }
+
+    if new.is_empty() && gone == 0 {
+        return format!("🔄 {} → no change", trigger);
+    }
+    let mut out = format!("🔄 {} → +{} new, -{} gone", trigger, new.len(), gone);
+    for line in new.iter().take(MAX_NEW_LINES) {
+        out.push('\n');
+        out.push_str(line);
+    }
+    if new.len() > MAX_NEW_LINES {
+        out.push_str(&format!("\n... +{} more", new.len() - MAX_NEW_LINES));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_child_args_drop_first_watch() {
+        let args = ["grep", "--watch", "fn", "src", "--watch"].map(OsString::from);
+        assert_eq!(
+            child_args(args.into_iter()),
+            ["grep", "fn", "src", "--watch"].map(OsString::from)
+        );
+    }
+
+    #[test]
+    fn test_delta_keeps_headers() {
+        let previous = "📄 src/a.rs (1):\n  3: fn a()\n📄 src/b.rs (1):\n  7: fn b()\n";
+        let current = "📄 src/a.rs (2):\n  3: fn a()\n  9: fn c()\n📄 src/d.rs (1):\n  1: fn d()\n";
+        let (new, gone) = delta(previous, current);
+        assert_eq!(
+            new,
+            vec![
+                "📄 src/a.rs (2):",
+                "  9: fn c()",
+                "📄 src/d.rs (1):",
+                "  1: fn d()"
+            ]
+        );
+        // a.rs's old header, b.rs's header and match
+        assert_eq!(gone, 3);
+    }
+
+    #[test]
+    fn test_format_delta() {
+        let changed = vec![PathBuf::from("src/a.rs"), PathBuf::from("src/a.rs")];
+        assert_eq!(
+            format_delta(&changed, "ok\n", "ok\n"),
+            "🔄 src/a.rs → no change"
+        );
+        assert_eq!(
+            format_delta(&changed, "ok\n", "FAILED x\n"),
+            "🔄 src/a.rs → +1 new, -1 gone\nFAILED x"
+        );
+    }
+
+    #[test]
+    fn test_relevant_paths_skip_ignored_and_hidden() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        std::fs::write(root.join(".gitignore"), "target/\n").unwrap();
+        let ignored = ignore_rules(&root);
+        let event = notify::Event::new(EventKind::Modify(notify::event::ModifyKind::Any))
+            .add_path(root.join("src/lib.rs"))
+            .add_path(root.join("target/debug/rtk"))
+            .add_path(root.join(".git/index"));
+        assert_eq!(
+            relevant_paths(Ok(event), &root, &ignored),
+            vec![PathBuf::from("src/lib.rs")]
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use crate::tracking;
+use crate::utils;
 use anyhow::{Context, Result};
 use std::process::Command;
 
@@ -47,6 +48,7 @@ pub fn run(url: &str, args: &[String], verbose: u8) -> Result<()> {
         timer.track(&format!("wget {}", url), "rtk wget", &raw_output, &msg);
     }
 
+    utils::exit_with("wget", utils::exit_code(output.status), verbose);
     Ok(())
 }
 
@@ -109,6 +111,7 @@ pub fn run_stdout(url: &str, args: &[String], verbose: u8) -> Result<()> {
         timer.track(&format!("wget -O - {}", url), "rtk wget -o", &stderr, &msg);
     }
 
+    utils::exit_with("wget", utils::exit_code(output.status), verbose);
     Ok(())
 }
 
@@ -0,0 +1,452 @@
+use crate::tracking;
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+/// Distinct child tags shown per element before collapsing the rest
+const MAX_CHILDREN: usize = 20;
+
+/// HTML elements that never have a closing tag
+const VOID_ELEMENTS: [&str; 14] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// HTML elements whose content is raw text, not markup
+const RAW_TEXT_ELEMENTS: [&str; 2] = ["script", "style"];
+
+/// HTML elements whose end tag is optional: a sibling start tag closes them
+const IMPLIED_END_ELEMENTS: [&str; 8] = ["li", "p", "tr", "td", "th", "option", "dt", "dd"];
+
+/// Show the element tree of an XML/HTML document without text content
+pub fn run(file: &Path, max_depth: usize, format: &str, verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    if verbose > 0 {
+        eprintln!("Analyzing XML: {}", file.display());
+    }
+
+    let content = fs::read_to_string(file)
+        .with_context(|| format!("Failed to read file: {}", file.display()))?;
+
+    let ext = file.extension().and_then(|e| e.to_str()).unwrap_or("");
+    let html = matches!(ext, "html" | "htm") || looks_like_html(&content);
+
+    let schema = render(&content, html, max_depth, format)?;
+    println!("{}", schema);
+    timer.track(
+        &format!("cat {}", file.display()),
+        "rtk xml",
+        &content,
+        &schema,
+    );
+    Ok(())
+}
+
+/// Show the element tree of an XML/HTML document from stdin
+pub fn run_stdin(max_depth: usize, format: &str, verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    if verbose > 0 {
+        eprintln!("Analyzing XML from stdin");
+    }
+
+    let mut content = String::new();
+    io::stdin()
+        .lock()
+        .read_to_string(&mut content)
+        .context("Failed to read from stdin")?;
+
+    let html = looks_like_html(&content);
+    let schema = render(&content, html, max_depth, format)?;
+    println!("{}", schema);
+    timer.track("cat - (stdin)", "rtk xml -", &content, &schema);
+    Ok(())
+}
+
+fn looks_like_html(content: &str) -> bool {
+    let head: String = content
+        .chars()
+        .take(1024)
+        .collect::<String>()
+        .to_lowercase();
+    head.contains("<!doctype html") || head.contains("<html")
+}
+
+fn render(content: &str, html: bool, max_depth: usize, format: &str) -> Result<String> {
+    let root = parse(content, html);
+    if root.children.is_empty() {
+        anyhow::bail!("No elements found");
+    }
+    let top: Vec<&Element> = root.children.iter().collect();
+    let nodes = summarize(&top);
+
+    match format {
+        "json" => {
+            let values: Vec<Value> = nodes.iter().map(|n| n.to_value(0, max_depth)).collect();
+            let value = match values.len() {
+                1 => values.into_iter().next().unwrap_or_default(),
+                _ => Value::Array(values),
+            };
+            Ok(serde_json::to_string_pretty(&value)?)
+        }
+        _ => {
+            let mut lines = Vec::new();
+            for node in &nodes {
+                node.render(0, max_depth, &mut lines);
+            }
+            Ok(lines.join("\n"))
+        }
+    }
+}
+
+/// Parsed element: tag name, attribute names and child elements
+#[derive(Debug, Default)]
+struct Element {
+    name: String,
+    attrs: Vec<String>,
+    children: Vec<Element>,
+}
+
+/// Parse markup into an element tree under a nameless root. Lenient: text,
+/// comments, processing instructions and doctypes are skipped; unclosed
+/// elements are closed by their parent's end tag or at end of input.
+fn parse(content: &str, html: bool) -> Element {
+    let mut stack = vec![Element::default()];
+    let mut rest = content;
+
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start..];
+
+        if let Some(after) = skip_special(rest) {
+            rest = after;
+            continue;
+        }
+
+        if let Some(close) = rest.strip_prefix("</") {
+            let end = close.find('>').unwrap_or(close.len());
+            let name = normalize(close[..end].trim(), html);
+            rest = close.get(end + 1..).unwrap_or("");
+            close_element(&mut stack, &name);
+            continue;
+        }
+
+        if !rest[1..].starts_with(|c: char| c.is_alphabetic() || c == '_') {
+            // A lone '<' in text
+            rest = &rest[1..];
+            continue;
+        }
+
+        let (element, self_closing, after) = parse_open_tag(&rest[1..], html);
+        rest = after;
+
+        let void = html && VOID_ELEMENTS.contains(&element.name.as_str());
+        if self_closing || void {
+            push_child(&mut stack, element);
+        } else if html && RAW_TEXT_ELEMENTS.contains(&element.name.as_str()) {
+            // Skip script/style bodies, which may contain '<'
+            let closing = format!("</{}", element.name);
+            rest = match rest.to_ascii_lowercase().find(&closing) {
+                Some(i) => rest[i..].find('>').map_or("", |j| &rest[i + j + 1..]),
+                None => "",
+            };
+            push_child(&mut stack, element);
+        } else {
+            let implied_end = html
+                && IMPLIED_END_ELEMENTS.contains(&element.name.as_str())
+                && stack.len() > 1
+                && stack.last().is_some_and(|open| open.name == element.name);
+            if implied_end {
+                let open = stack.pop().unwrap_or_default();
+                push_child(&mut stack, open);
+            }
+            stack.push(element);
+        }
+    }
+
+    while stack.len() > 1 {
+        let element = stack.pop().unwrap_or_default();
+        push_child(&mut stack, element);
+    }
+    stack.pop().unwrap_or_default()
+}
+
+/// Skip comments, CDATA, processing instructions and doctypes
+fn skip_special(rest: &str) -> Option<&str> {
+    let (open, close) = [
+        ("<!--", "-->"),
+        ("<![CDATA[", "]]>"),
+        ("<?", "?>"),
+        ("<!", ">"),
+    ]
+    .into_iter()
+    .find(|(open, _)| rest.starts_with(open))?;
+    let body = &rest[open.len()..];
+    Some(body.find(close).map_or("", |i| &body[i + close.len()..]))
+}
+
+fn normalize(name: &str, html: bool) -> String {
+    if html {
+        name.to_lowercase()
+    } else {
+        name.to_string()
+    }
+}
+
+/// Parse `name attr="v" ...>` (after the '<'). Returns the element, whether
+/// it was self-closing, and the input after the tag.
+fn parse_open_tag(tag: &str, html: bool) -> (Element, bool, &str) {
+    let name_end = tag
+        .find(|c: char| c.is_whitespace() || c == '/' || c == '>')
+        .unwrap_or(tag.len());
+    let mut element = Element {
+        name: normalize(&tag[..name_end], html),
+        ..Default::default()
+    };
+
+    let mut chars = tag[name_end..].char_indices().peekable();
+    let offset = name_end;
+    let mut self_closing = false;
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '>' => return (element, self_closing, &tag[offset + i + 1..]),
+            '/' => self_closing = true,
+            c if c.is_whitespace() => {}
+            _ => {
+                self_closing = false;
+                let mut name = String::from(c);
+                while let Some(&(_, c)) = chars.peek() {
+                    if c.is_whitespace() || matches!(c, '=' | '>' | '/') {
+                        break;
+                    }
+                    name.push(c);
+                    chars.next();
+                }
+                while chars.peek().is_some_and(|(_, c)| c.is_whitespace()) {
+                    chars.next();
+                }
+                if chars.peek().is_some_and(|&(_, c)| c == '=') {
+                    chars.next();
+                    while chars.peek().is_some_and(|(_, c)| c.is_whitespace()) {
+                        chars.next();
+                    }
+                    skip_attr_value(&mut chars);
+                }
+                let name = normalize(&name, html);
+                if !element.attrs.contains(&name) {
+                    element.attrs.push(name);
+                }
+            }
+        }
+    }
+    (element, self_closing, "")
+}
+
+fn skip_attr_value(chars: &mut std::iter::Peekable<std::str::CharIndices>) {
+    match chars.peek().map(|&(_, c)| c) {
+        Some(quote @ ('"' | '\'')) => {
+            chars.next();
+            for (_, c) in chars.by_ref() {
+                if c == quote {
+                    break;
+                }
+            }
+        }
+        _ => {
+            while chars
+                .peek()
+                .is_some_and(|&(_, c)| !c.is_whitespace() && c != '>')
+            {
+                chars.next();
+            }
+        }
+    }
+}
+
+fn push_child(stack: &mut [Element], element: Element) {
+    if let Some(parent) = stack.last_mut() {
+        parent.children.push(element);
+    }
+}
+
+/// Close the innermost open element named `name`, implicitly closing any
+/// elements opened after it. Stray end tags are ignored.
+fn close_element(stack: &mut Vec<Element>, name: &str) {
+    let Some(pos) = stack.iter().skip(1).rposition(|e| e.name == name) else {
+        return;
+    };
+    while stack.len() > pos + 1 {
+        let element = stack.pop().unwrap_or_default();
+        push_child(stack, element);
+    }
+}
+
+/// Sibling elements sharing a tag, merged: attribute names and children
+/// are unions over all occurrences
+#[derive(Debug)]
+struct Node {
+    name: String,
+    attrs: BTreeSet<String>,
+    count: usize,
+    children: Vec<Node>,
+}
+
+/// Merge siblings by tag name, in order of first appearance. Children of
+/// merged siblings are merged in turn, as one sibling list.
+fn summarize(elements: &[&Element]) -> Vec<Node> {
+    let mut nodes: Vec<(Node, Vec<&Element>)> = Vec::new();
+    for &element in elements {
+        let idx = match nodes.iter().position(|(n, _)| n.name == element.name) {
+            Some(idx) => idx,
+            None => {
+                let node = Node {
+                    name: element.name.clone(),
+                    attrs: BTreeSet::new(),
+                    count: 0,
+                    children: Vec::new(),
+                };
+                nodes.push((node, Vec::new()));
+                nodes.len() - 1
+            }
+        };
+        let (node, members) = &mut nodes[idx];
+        node.count += 1;
+        node.attrs.extend(element.attrs.iter().cloned());
+        members.push(element);
+    }
+
+    nodes
+        .into_iter()
+        .map(|(mut node, members)| {
+            let children: Vec<&Element> = members.iter().flat_map(|m| &m.children).collect();
+            node.children = summarize(&children);
+            node
+        })
+        .collect()
+}
+
+impl Node {
+    /// Total elements below this node
+    fn descendants(&self) -> usize {
+        self.children
+            .iter()
+            .map(|c| c.count + c.descendants())
+            .sum()
+    }
+
+    fn label(&self) -> String {
+        let mut label = self.name.clone();
+        if !self.attrs.is_empty() {
+            let attrs: Vec<&str> = self.attrs.iter().map(String::as_str).collect();
+            label.push_str(&format!(" [{}]", attrs.join(", ")));
+        }
+        if self.count > 1 {
+            label.push_str(&format!(" ×{}", self.count));
+        }
+        label
+    }
+
+    fn render(&self, depth: usize, max_depth: usize, lines: &mut Vec<String>) {
+        let indent = "  ".repeat(depth);
+        if depth >= max_depth && !self.children.is_empty() {
+            lines.push(format!(
+                "{}{} ... ({} elements)",
+                indent,
+                self.label(),
+                self.descendants()
+            ));
+            return;
+        }
+
+        lines.push(format!("{}{}", indent, self.label()));
+        for child in self.children.iter().take(MAX_CHILDREN) {
+            child.render(depth + 1, max_depth, lines);
+        }
+        if self.children.len() > MAX_CHILDREN {
+            lines.push(format!(
+                "{}  ... +{} more tags",
+                indent,
+                self.children.len() - MAX_CHILDREN
+            ));
+        }
+    }
+
+    fn to_value(&self, depth: usize, max_depth: usize) -> Value {
+        let mut value = json!({ "tag": self.name, "count": self.count });
+        if !self.attrs.is_empty() {
+            value["attrs"] = json!(self.attrs);
+        }
+        if depth >= max_depth && !self.children.is_empty() {
+            value["truncated"] = json!(self.descendants());
+        } else if !self.children.is_empty() {
+            let children: Vec<Value> = self
+                .children
+                .iter()
+                .map(|c| c.to_value(depth + 1, max_depth))
+                .collect();
+            value["children"] = Value::Array(children);
+        }
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(content: &str, html: bool, max_depth: usize) -> String {
+        render(content, html, max_depth, "text").unwrap()
+    }
+
+    #[test]
+    fn test_xml_tree_with_attrs_and_counts() {
+        let xml = r#"<?xml version="1.0"?>
+<!-- catalog -->
+<catalog xmlns:x="urn:x">
+  <book id="1" lang='en'><title>A &lt; B</title><x:price>10</x:price></book>
+  <book id="2"><title><![CDATA[<not a tag>]]></title></book>
+  <empty/>
+</catalog>"#;
+        assert_eq!(
+            text(xml, false, 5),
+            "catalog [xmlns:x]\n  book [id, lang] ×2\n    title ×2\n    x:price\n  empty"
+        );
+    }
+
+    #[test]
+    fn test_html_void_raw_text_and_implicit_close() {
+        let html = r#"<!DOCTYPE html>
+<HTML><head><meta charset=utf-8><script>if (a < b) { x("</div>"); }</script></head>
+<body class="main"><ul><li>one<li>two</ul><br><IMG SRC="x.png"></body></html>"#;
+        assert!(looks_like_html(html));
+        assert_eq!(
+            text(html, true, 5),
+            "html\n  head\n    meta [charset]\n    script\n  body [class]\n    ul\n      li ×2\n    br\n    img [src]"
+        );
+    }
+
+    #[test]
+    fn test_max_depth_collapses() {
+        let xml = "<a><b><c/><c/><d><e/></d></b></a>";
+        assert_eq!(text(xml, false, 1), "a\n  b ... (4 elements)");
+    }
+
+    #[test]
+    fn test_json_format() {
+        let out = render("<a x='1'><b/><b/></a>", false, 5, "json").unwrap();
+        let value: Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(value["tag"], "a");
+        assert_eq!(value["attrs"][0], "x");
+        assert_eq!(value["children"][0]["tag"], "b");
+        assert_eq!(value["children"][0]["count"], 2);
+    }
+
+    #[test]
+    fn test_no_elements_is_error() {
+        assert!(render("just text", false, 5, "text").is_err());
+    }
+}
@@ -0,0 +1,591 @@
+use crate::json_cmd;
+use crate::tracking;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+/// Keys shown per mapping before collapsing the rest into a count
+const MAX_KEYS: usize = 15;
+
+/// Show YAML structure without values
+pub fn run(file: &Path, max_depth: usize, format: &str, verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    if verbose > 0 {
+        eprintln!("Analyzing YAML: {}", file.display());
+    }
+
+    let content = fs::read_to_string(file)
+        .with_context(|| format!("Failed to read file: {}", file.display()))?;
+
+    let schema = render(&content, max_depth, format)?;
+    println!("{}", schema);
+    timer.track(
+        &format!("cat {}", file.display()),
+        "rtk yaml",
+        &content,
+        &schema,
+    );
+    Ok(())
+}
+
+/// Show YAML structure from stdin
+pub fn run_stdin(max_depth: usize, format: &str, verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    if verbose > 0 {
+        eprintln!("Analyzing YAML from stdin");
+    }
+
+    let mut content = String::new();
+    io::stdin()
+        .lock()
+        .read_to_string(&mut content)
+        .context("Failed to read from stdin")?;
+
+    let schema = render(&content, max_depth, format)?;
+    println!("{}", schema);
+    timer.track("cat - (stdin)", "rtk yaml -", &content, &schema);
+    Ok(())
+}
+
+fn render(content: &str, max_depth: usize, format: &str) -> Result<String> {
+    let docs = parse_documents(content)?;
+    match format {
+        "json" => {
+            let mut schemas: Vec<Value> = docs
+                .iter()
+                .map(|doc| schema_value(doc, 0, max_depth))
+                .collect();
+            let value = if schemas.len() == 1 {
+                schemas.remove(0)
+            } else {
+                Value::Array(schemas)
+            };
+            Ok(serde_json::to_string_pretty(&value)?)
+        }
+        _ => Ok(filter_yaml_docs(&docs, max_depth)),
+    }
+}
+
+fn filter_yaml_docs(docs: &[YamlNode], max_depth: usize) -> String {
+    if docs.is_empty() {
+        return "(empty)".to_string();
+    }
+
+    let mut lines = Vec::new();
+    for (i, doc) in docs.iter().enumerate() {
+        if docs.len() > 1 {
+            lines.push(format!("--- [{}/{}]", i + 1, docs.len()));
+        }
+        match doc {
+            YamlNode::Map(entries) => render_map(entries, 0, max_depth, 0, &mut lines),
+            other => {
+                let mut sub = Vec::new();
+                render_child(String::new(), other, 0, max_depth, 0, &mut sub);
+                lines.extend(sub.into_iter().map(|l| l.trim_start().to_string()));
+            }
+        }
+    }
+    lines.join("\n")
+}
+
+/// Structure of a YAML node; scalars keep only their kind
+#[derive(Debug, PartialEq)]
+enum YamlNode {
+    Map(Vec<(String, YamlNode)>),
+    Seq(Vec<YamlNode>),
+    Scalar(&'static str),
+}
+
+/// Every non-empty document (`---`), with aliases resolved and `<<` merge
+/// keys applied
+fn parse_values(content: &str) -> Result<Vec<serde_yaml::Value>> {
+    let mut docs = Vec::new();
+    for doc in serde_yaml::Deserializer::from_str(content) {
+        let mut value = serde_yaml::Value::deserialize(doc).context("Failed to parse YAML")?;
+        value.apply_merge().context("Invalid YAML merge key")?;
+        if !value.is_null() {
+            docs.push(value);
+        }
+    }
+    Ok(docs)
+}
+
+fn parse_documents(content: &str) -> Result<Vec<YamlNode>> {
+    Ok(parse_values(content)?.iter().map(yaml_node).collect())
+}
+
+fn yaml_node(value: &serde_yaml::Value) -> YamlNode {
+    use serde_yaml::Value as Yaml;
+    match value {
+        Yaml::Mapping(map) => YamlNode::Map(
+            map.iter()
+                .map(|(key, value)| (key_string(key), yaml_node(value)))
+                .collect(),
+        ),
+        Yaml::Sequence(items) => YamlNode::Seq(items.iter().map(yaml_node).collect()),
+        Yaml::Tagged(tagged) => yaml_node(&tagged.value),
+        Yaml::Null => YamlNode::Scalar("null"),
+        Yaml::Bool(_) => YamlNode::Scalar("bool"),
+        Yaml::Number(n) if n.is_f64() => YamlNode::Scalar("float"),
+        Yaml::Number(_) => YamlNode::Scalar("int"),
+        Yaml::String(s) => YamlNode::Scalar(string_kind(s)),
+    }
+}
+
+fn string_kind(value: &str) -> &'static str {
+    if value.contains('\n') {
+        "text"
+    } else if value.starts_with("http") {
+        "url"
+    } else if value.contains('-') && value.len() == 10 {
+        "date?"
+    } else {
+        "string"
+    }
+}
+
+/// Mapping keys as text: JSON object keys, schema lines
+fn key_string(key: &serde_yaml::Value) -> String {
+    use serde_yaml::Value as Yaml;
+    match key {
+        Yaml::String(s) => s.clone(),
+        Yaml::Null => "null".to_string(),
+        Yaml::Bool(b) => b.to_string(),
+        Yaml::Number(n) => n.to_string(),
+        Yaml::Tagged(tagged) => key_string(&tagged.value),
+        // Complex keys (`? [a, b]`) in flow style
+        complex => serde_json::to_string(&json_value(complex)).unwrap_or_default(),
+    }
+}
+
+fn render_map(
+    entries: &[(String, YamlNode)],
+    depth: usize,
+    max_depth: usize,
+    indent: usize,
+    lines: &mut Vec<String>,
+) {
+    let pad = "  ".repeat(indent);
+    for (n, (key, node)) in entries.iter().enumerate() {
+        if n >= MAX_KEYS {
+            lines.push(format!("{}... +{} more keys", pad, entries.len() - n));
+            break;
+        }
+        render_child(
+            format!("{}{}:", pad, key),
+            node,
+            depth,
+            max_depth,
+            indent,
+            lines,
+        );
+    }
+}
+
+fn render_child(
+    head: String,
+    node: &YamlNode,
+    depth: usize,
+    max_depth: usize,
+    indent: usize,
+    lines: &mut Vec<String>,
+) {
+    match node {
+        YamlNode::Map(entries) if entries.is_empty() => lines.push(format!("{} {{}}", head)),
+        YamlNode::Map(entries) if depth >= max_depth => {
+            lines.push(format!("{} {{{} keys}}", head, entries.len()))
+        }
+        YamlNode::Map(entries) => {
+            lines.push(head);
+            render_map(entries, depth + 1, max_depth, indent + 1, lines);
+        }
+        YamlNode::Seq(items) => match items.first() {
+            None => lines.push(format!("{} []", head)),
+            Some(YamlNode::Map(entries)) if !entries.is_empty() && depth < max_depth => {
+                lines.push(format!("{} ({})", head, items.len()));
+                let mut sub = Vec::new();
+                render_map(entries, depth + 1, max_depth, indent + 2, &mut sub);
+                if let Some(first) = sub.first_mut() {
+                    *first = format!("{}- {}", "  ".repeat(indent + 1), first.trim_start());
+                }
+                lines.extend(sub);
+            }
+            Some(first) => lines.push(format!("{} [{}] ({})", head, inline(first), items.len())),
+        },
+        other => lines.push(format!("{} {}", head, inline(other))),
+    }
+}
+
+fn inline(node: &YamlNode) -> String {
+    match node {
+        YamlNode::Scalar(kind) => kind.to_string(),
+        YamlNode::Map(entries) => format!("{{{} keys}}", entries.len()),
+        YamlNode::Seq(items) => format!("[{}]", items.len()),
+    }
+}
+
+/// Schema node as a JSON value, for `--format json` (same shape as `rtk json`)
+fn schema_value(node: &YamlNode, depth: usize, max_depth: usize) -> Value {
+    if depth > max_depth {
+        return json!({ "type": "truncated" });
+    }
+
+    match node {
+        YamlNode::Scalar(kind) => json!({ "type": kind }),
+        YamlNode::Seq(items) => match items.first() {
+            Some(first) => json!({
+                "type": "array",
+                "len": items.len(),
+                "items": schema_value(first, depth + 1, max_depth),
+            }),
+            None => json!({ "type": "array", "len": 0 }),
+        },
+        YamlNode::Map(entries) => {
+            let mut keys = Map::new();
+            for (key, val) in entries {
+                keys.insert(key.clone(), schema_value(val, depth + 1, max_depth));
+            }
+            json!({ "type": "object", "keys": keys })
+        }
+    }
+}
+
+/// Convert YAML (file, `-` or URL) to JSON. Several documents become an
+/// array of documents.
+pub fn run_to_json(source: &str, minify: bool, verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    if verbose > 0 {
+        eprintln!("Converting YAML to JSON: {}", source);
+    }
+
+    let content = json_cmd::read_source(source)?;
+    let value = yaml_to_json(&content).with_context(|| format!("Invalid YAML: {}", source))?;
+    let output = if minify {
+        serde_json::to_string(&value)?
+    } else {
+        serde_json::to_string_pretty(&value)?
+    };
+    println!("{}", output);
+
+    timer.track(
+        &format!("yq -o json {}", source),
+        "rtk yaml to-json",
+        &content,
+        &output,
+    );
+    Ok(())
+}
+
+/// Convert JSON (file, `-` or URL) to YAML; `minify` prints one flow-style
+/// line instead of blocks
+pub fn run_from_json(source: &str, minify: bool, verbose: u8) -> Result<()> {
+    let timer = tracking::TimedExecution::start();
+
+    if verbose > 0 {
+        eprintln!("Converting JSON to YAML: {}", source);
+    }
+
+    let content = json_cmd::read_source(source)?;
+    let value: Value = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse JSON: {}", source))?;
+    let output = if minify {
+        // JSON is flow-style YAML
+        serde_json::to_string(&value)?
+    } else {
+        serde_yaml::to_string(&value)?
+    };
+    println!("{}", output.trim_end());
+
+    timer.track(
+        &format!("yq -P {}", source),
+        "rtk json to-yaml",
+        &content,
+        &output,
+    );
+    Ok(())
+}
+
+fn yaml_to_json(content: &str) -> Result<Value> {
+    let mut docs: Vec<Value> = parse_values(content)?.iter().map(json_value).collect();
+    Ok(match docs.len() {
+        0 => Value::Null,
+        1 => docs.remove(0),
+        _ => Value::Array(docs),
+    })
+}
+
+/// JSON for a YAML value; tags are dropped, non-finite floats become null
+fn json_value(value: &serde_yaml::Value) -> Value {
+    use serde_yaml::Value as Yaml;
+    match value {
+        Yaml::Null => Value::Null,
+        Yaml::Bool(b) => Value::Bool(*b),
+        Yaml::Number(n) => match (n.as_i64(), n.as_u64()) {
+            (Some(i), _) => Value::from(i),
+            (None, Some(u)) => Value::from(u),
+            _ => Value::from(n.as_f64().unwrap_or(f64::NAN)),
+        },
+        Yaml::String(s) => Value::String(s.clone()),
+        Yaml::Sequence(items) => Value::Array(items.iter().map(json_value).collect()),
+        Yaml::Mapping(map) => Value::Object(
+            map.iter()
+                .map(|(key, value)| (key_string(key), json_value(value)))
+                .collect(),
+        ),
+        Yaml::Tagged(tagged) => json_value(&tagged.value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scalar(kind: &'static str) -> YamlNode {
+        YamlNode::Scalar(kind)
+    }
+
+    fn filter_yaml_string(yaml: &str, max_depth: usize) -> String {
+        filter_yaml_docs(&parse_documents(yaml).unwrap(), max_depth)
+    }
+
+    const MANIFEST: &str = r#"apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: web
+  labels:
+    app: web
+spec:
+  replicas: 3
+  template:
+    spec:
+      containers:
+        - name: web
+          image: nginx:1.25
+          ports:
+            - containerPort: 80
+---
+apiVersion: v1
+kind: Service
+metadata:
+  name: web
+"#;
+
+    #[test]
+    fn test_parse_mapping_and_scalars() {
+        let docs =
+            parse_documents("name: test\ncount: 42\nratio: 0.5\nenabled: true\nempty:\n").unwrap();
+        assert_eq!(
+            docs[0],
+            YamlNode::Map(vec![
+                ("name".to_string(), scalar("string")),
+                ("count".to_string(), scalar("int")),
+                ("ratio".to_string(), scalar("float")),
+                ("enabled".to_string(), scalar("bool")),
+                ("empty".to_string(), scalar("null")),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_multi_document() {
+        let docs = parse_documents(MANIFEST).unwrap();
+        assert_eq!(docs.len(), 2);
+
+        let out = filter_yaml_string(MANIFEST, 10);
+        assert!(out.contains("--- [1/2]"));
+        assert!(out.contains("--- [2/2]"));
+        assert!(out.contains("containers: (1)"));
+        assert!(out.contains("- name: string"));
+        assert!(out.contains("ports: (1)"));
+        assert!(!out.contains("nginx"));
+    }
+
+    #[test]
+    fn test_max_depth_collapses() {
+        let out = filter_yaml_string(MANIFEST, 1);
+        assert!(out.contains("labels: {1 keys}"));
+        assert!(out.contains("template: {1 keys}"));
+    }
+
+    #[test]
+    fn test_anchors_and_aliases() {
+        // Aliases resolve and merge keys apply, so `prod` shows every key it ends up with
+        let yaml = "defaults: &base\n  retries: 3\nprod:\n  <<: *base\n  host: example.com\n";
+        let out = filter_yaml_string(yaml, 5);
+        assert_eq!(
+            out,
+            "defaults:\n  retries: int\nprod:\n  host: string\n  retries: int"
+        );
+    }
+
+    #[test]
+    fn test_sequence_at_same_indent() {
+        let yaml = "steps:\n- run: make\n- run: make test\nname: ci\n";
+        let docs = parse_documents(yaml).unwrap();
+        match &docs[0] {
+            YamlNode::Map(entries) => {
+                assert_eq!(entries.len(), 2);
+                assert!(matches!(&entries[0].1, YamlNode::Seq(items) if items.len() == 2));
+            }
+            other => panic!("expected map, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_block_scalar_and_flow() {
+        let yaml = "script: |\n  echo one\n  echo two\ntags: [a, b, c]\nenv: {A: 1, B: x}\n";
+        let out = filter_yaml_string(yaml, 5);
+        assert!(out.contains("script: text"));
+        assert!(out.contains("tags: [string] (3)"));
+        assert!(out.contains("env:"));
+        assert!(out.contains("  A: int"));
+        assert!(!out.contains("echo"));
+    }
+
+    #[test]
+    fn test_nested_and_quoted_flow_collections() {
+        let yaml = "a: {b: 1, c: [x, \"y: z\", {d: '1, 2'}]}\n";
+        let out = filter_yaml_string(yaml, 5);
+        assert_eq!(out, "a:\n  b: int\n  c: [string] (3)");
+        assert_eq!(
+            yaml_to_json(yaml).unwrap(),
+            json!({"a": {"b": 1, "c": ["x", "y: z", {"d": "1, 2"}]}})
+        );
+        assert!(render("a: [1, 2\n", 5, "text").is_err());
+    }
+
+    #[test]
+    fn test_json_format() {
+        let out = render("name: x\nitems:\n  - 1\n  - 2\n", 5, "json").unwrap();
+        let value: Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(value["keys"]["items"]["len"], 2);
+        assert_eq!(value["keys"]["items"]["items"]["type"], "int");
+    }
+
+    #[test]
+    fn test_yaml_to_json_values() {
+        let yaml = "defaults: &base\n  retries: 3\n  tls: true\nprod:\n  <<: *base\n  retries: 5\n  host: 'it''s'\nscript: |\n  echo one\n    indented\nfolded: >-\n  a\n  b\nempty:\nlist: [1, 2.5, x]\nquoted: \"a\\tb\"\nnorway: no\nlong: one\n  two\n";
+        let value = yaml_to_json(yaml).unwrap();
+        assert_eq!(
+            value["prod"],
+            json!({"retries": 5, "host": "it's", "tls": true})
+        );
+        assert_eq!(value["script"], "echo one\n  indented\n");
+        assert_eq!(value["folded"], "a b");
+        assert_eq!(value["empty"], Value::Null);
+        assert_eq!(value["list"], json!([1, 2.5, "x"]));
+        assert_eq!(value["quoted"], "a\tb");
+        // YAML 1.2: only true/false are booleans
+        assert_eq!(value["norway"], "no");
+        assert_eq!(value["long"], "one two");
+
+        let docs = yaml_to_json(MANIFEST).unwrap();
+        assert_eq!(
+            docs[0]["spec"]["template"]["spec"]["containers"][0]["ports"][0]["containerPort"],
+            80
+        );
+        assert_eq!(docs[1]["kind"], "Service");
+    }
+
+    #[test]
+    fn test_to_json_conversions() {
+        let yaml = r#"flow: {a: {b: [1, {c: d}]}, e: [[x, y], []]}
+quoted: "key: value"
+single: 'a: b, c'
+url: http://host:8080/x
+base: &base
+  retries: 3
+  tags: [a, b]
+job:
+  <<: *base
+  retries: 5
+alias: *base
+literal: |
+  line one
+    indented
+folded: >
+  folded
+  text
+
+  paragraph
+plain: first
+  second
+"#;
+        assert_eq!(
+            yaml_to_json(yaml).unwrap(),
+            json!({
+                "flow": {"a": {"b": [1, {"c": "d"}]}, "e": [["x", "y"], []]},
+                "quoted": "key: value",
+                "single": "a: b, c",
+                "url": "http://host:8080/x",
+                "base": {"retries": 3, "tags": ["a", "b"]},
+                "job": {"retries": 5, "tags": ["a", "b"]},
+                "alias": {"retries": 3, "tags": ["a", "b"]},
+                "literal": "line one\n  indented\n",
+                "folded": "folded text\nparagraph\n",
+                "plain": "first second"
+            })
+        );
+
+        // Back through to-yaml, the same document comes out
+        let value = yaml_to_json(yaml).unwrap();
+        let yaml = serde_yaml::to_string(&value).unwrap();
+        assert_eq!(yaml_to_json(&yaml).unwrap(), value);
+    }
+
+    #[test]
+    fn test_json_to_yaml() {
+        let value = json!({
+            "name": "web",
+            "port": 80,
+            "tags": ["a", "true", "1.5"],
+            "containers": [{"name": "x", "env": {"A": "b: c"}}, {"name": "y"}],
+            "matrix": [[1, 2], []],
+            "script": "echo 1\necho 2\n",
+            "note": "line\nnext",
+            "empty": {},
+            "none": null
+        });
+        let expected = r#"name: web
+port: 80
+tags:
+- a
+- 'true'
+- '1.5'
+containers:
+- name: x
+  env:
+    A: 'b: c'
+- name: y
+matrix:
+- - 1
+  - 2
+- []
+script: |
+  echo 1
+  echo 2
+note: |-
+  line
+  next
+empty: {}
+none: null
+"#;
+        assert_eq!(serde_yaml::to_string(&value).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_json_yaml_round_trip() {
+        let value = json!({
+            "a": [{"b": [1, {"c": "d"}], "e": "#tag"}, "- item", ""],
+            "f": {"g": -1.5, "h": "x\ny\n", "i": " lead"}
+        });
+        let yaml = serde_yaml::to_string(&value).unwrap();
+        assert_eq!(yaml_to_json(&yaml).unwrap(), value);
+    }
+}